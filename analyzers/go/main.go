@@ -1,18 +1,24 @@
 package main
 
 import (
+	"bufio"
 	"bytes"
+	"compress/gzip"
 	"context"
+	"encoding/base64"
 	"encoding/json"
 	"fmt"
 	"io"
+	"net"
 	"os"
 	"os/exec"
 	"path/filepath"
 	"regexp"
 	"runtime"
+	"sort"
 	"strconv"
 	"strings"
+	"sync"
 	"time"
 )
 
@@ -35,11 +41,23 @@ type AnalyzeRequest struct {
 	SessionID      string            `json:"session_id"`
 	Target         string            `json:"target"`
 	Inputs         []string          `json:"inputs"`
+	InputEncodings []string          `json:"input_encodings"`
+	InputArgs      []json.RawMessage `json:"input_args"`
 	Repeat         int               `json:"repeat"`
 	TimeoutSeconds float64           `json:"timeout_seconds"`
 	Options        map[string]string `json:"options"`
 }
 
+// BatchAnalyzeRequest carries several requests for this one bridge process.
+type BatchAnalyzeRequest struct {
+	Requests []AnalyzeRequest `json:"requests"`
+}
+
+// BatchAnalyzeResponse answers a batch, one response per request in order.
+type BatchAnalyzeResponse struct {
+	Responses []AnalyzeResponse `json:"responses"`
+}
+
 type AnalyzeResponse struct {
 	SessionID       string            `json:"session_id"`
 	Language        string            `json:"language"`
@@ -50,6 +68,7 @@ type AnalyzeResponse struct {
 	Error           string            `json:"error,omitempty"`
 	ErrorCategory   string            `json:"error_category,omitempty"`
 	SuggestedAction string            `json:"suggested_action,omitempty"`
+	Warnings        []string          `json:"warnings,omitempty"`
 }
 
 type ErrorDiagnosis struct {
@@ -66,6 +85,14 @@ type ExecutionResult struct {
 	ExecutionTimeMs int64         `json:"execution_time_ms"`
 	EscapeDetected  bool          `json:"escape_detected"`
 	EscapeDetails   EscapeDetails `json:"escape_details"`
+	Stdout          string        `json:"stdout,omitempty"`
+	Stderr          string        `json:"stderr,omitempty"`
+}
+
+// targetOutput is what one run of the target wrote.
+type targetOutput struct {
+	Stdout string
+	Stderr string
 }
 
 type EscapeDetails struct {
@@ -125,6 +152,12 @@ type GoroutineEscape struct {
 	GoroutineID uint64 `json:"goroutine_id"`
 	State       string `json:"state"`
 	Function    string `json:"function"`
+	// Set when the request asks for an observation window (observe_seconds): how long
+	// the goroutine kept running after the call returned, and whether it outlived the window.
+	OutlivedMs   *int64 `json:"outlived_ms,omitempty"`
+	StillRunning *bool  `json:"still_running,omitempty"`
+	// Frames of the goroutine's stack, innermost first, when capture_stacks is set.
+	StackTrace []string `json:"stack_trace,omitempty"`
 }
 
 type Vulnerability struct {
@@ -146,10 +179,44 @@ type ExecutionSummary struct {
 }
 
 func main() {
-	// Read request from stdin
-	requestBytes, err := io.ReadAll(os.Stdin)
+	if os.Getenv(handshakeEnv) == "1" {
+		handshake, _ := json.Marshal(map[string]interface{}{
+			"protocol_version": protocolVersion,
+			"analyzer_version": "1.0.0",
+			"capabilities":     capabilities,
+		})
+		fmt.Println(string(handshake))
+		return
+	}
+
+	if os.Getenv(daemonEnv) == "1" {
+		serveDaemon()
+		return
+	}
+
+	// Read request from stdin, or from the orchestrator's socket when it names one
+	requestBytes, out, err := openChannel()
 	if err != nil {
-		errorResponse(fmt.Sprintf("Failed to read stdin: %v", err))
+		errorResponse(fmt.Sprintf("Failed to read request: %v", err))
+		return
+	}
+	defer out.Close()
+
+	defer cleanupTemporaryTargets()
+
+	// A batch runs several requests in this one process, answered in order
+	var batch BatchAnalyzeRequest
+	if err := json.Unmarshal(requestBytes, &batch); err == nil && batch.Requests != nil {
+		var options map[string]string
+		if len(batch.Requests) > 0 {
+			options = batch.Requests[0].Options
+		}
+		startHeartbeat(options)
+		responses := make([]AnalyzeResponse, 0, len(batch.Requests))
+		for _, request := range batch.Requests {
+			responses = append(responses, analyze(request))
+		}
+		writeResponse(out, BatchAnalyzeResponse{Responses: responses}, options)
 		return
 	}
 
@@ -159,14 +226,178 @@ func main() {
 		return
 	}
 
-	defer cleanupTemporaryTargets()
+	startHeartbeat(request.Options)
 
 	// Process request
 	response := analyze(request)
 
-	// Write response to stdout
-	responseBytes, _ := json.MarshalIndent(response, "", "  ")
-	fmt.Println(string(responseBytes))
+	// Write response back over the same channel
+	writeResponse(out, response, request.Options)
+}
+
+// daemonMessage is a framed message from the orchestrator in daemon mode.
+type daemonMessage struct {
+	Type    string         `json:"type"`
+	ID      uint64         `json:"id"`
+	Request AnalyzeRequest `json:"request"`
+}
+
+// serveDaemon answers framed requests on stdin until a shutdown frame or the end of input.
+func serveDaemon() {
+	defer cleanupTemporaryTargets()
+	writeFrame(map[string]interface{}{"type": "ready", "protocol": daemonProtocolVersion})
+	reader := bufio.NewReader(os.Stdin)
+	heartbeatStarted := false
+	for {
+		header, err := reader.ReadString('\n')
+		if err != nil {
+			return
+		}
+		header = strings.TrimSpace(header)
+		if !strings.HasPrefix(header, frameMarker) {
+			continue
+		}
+		length, err := strconv.Atoi(strings.TrimSpace(strings.TrimPrefix(header, frameMarker)))
+		if err != nil {
+			continue
+		}
+		payload := make([]byte, length)
+		if _, err := io.ReadFull(reader, payload); err != nil {
+			return
+		}
+		var message daemonMessage
+		if err := json.Unmarshal(payload, &message); err != nil {
+			fmt.Fprintf(os.Stderr, "Unreadable daemon frame: %v\n", err)
+			continue
+		}
+		switch message.Type {
+		case "shutdown":
+			return
+		case "analyze":
+			if !heartbeatStarted {
+				startHeartbeat(message.Request.Options)
+				heartbeatStarted = true
+			}
+			response := analyze(message.Request)
+			writeFrame(map[string]interface{}{"type": "response", "id": message.ID, "response": response})
+		}
+	}
+}
+
+// writeFrame writes one framed message on a line of its own.
+func writeFrame(message interface{}) {
+	body, err := json.Marshal(message)
+	if err != nil {
+		return
+	}
+	fmt.Fprintf(os.Stdout, "\n%s %d\n", frameMarker, len(body))
+	os.Stdout.Write(body)
+}
+
+// openChannel reads the request and returns it with the writer for the response: the
+// orchestrator's Unix socket when GRAPHENE_BRIDGE_SOCKET is set, else stdin/stdout.
+func openChannel() ([]byte, io.WriteCloser, error) {
+	socketPath := os.Getenv(bridgeSocketEnv)
+	if socketPath == "" {
+		requestBytes, err := io.ReadAll(os.Stdin)
+		return requestBytes, os.Stdout, err
+	}
+	conn, err := net.Dial("unix", socketPath)
+	if err != nil {
+		return nil, nil, err
+	}
+	requestBytes, err := io.ReadAll(conn)
+	if err != nil {
+		conn.Close()
+		return nil, nil, err
+	}
+	return requestBytes, conn, nil
+}
+
+// writeResponse sends the final response, gzip-compressed when the orchestrator accepts it.
+func writeResponse(out io.Writer, payload interface{}, options map[string]string) {
+	acceptsGzip := false
+	for _, encoding := range strings.Split(options["accept_encoding"], ",") {
+		if strings.TrimSpace(encoding) == "gzip" {
+			acceptsGzip = true
+		}
+	}
+	if !acceptsGzip {
+		responseBytes, _ := json.MarshalIndent(payload, "", "  ")
+		fmt.Fprintln(out, string(responseBytes))
+		return
+	}
+
+	fmt.Fprintln(out, gzipResponseMarker)
+	writer := gzip.NewWriter(out)
+	_ = json.NewEncoder(writer).Encode(payload)
+	_ = writer.Close()
+}
+
+const heartbeatPrefix = "GRAPHENE_HEARTBEAT"
+const resultPrefix = "GRAPHENE_RESULT"
+const gzipResponseMarker = "GRAPHENE_GZIP"
+const bridgeSocketEnv = "GRAPHENE_BRIDGE_SOCKET"
+
+// Handshake: print the protocol version and capabilities, then exit.
+const handshakeEnv = "GRAPHENE_BRIDGE_HANDSHAKE"
+const protocolVersion = 1
+
+var capabilities = []string{"batch", "gzip", "unix_socket", "daemon", "input_encodings", "input_args", "observe"}
+
+// knownOptions are the option keys of the bridge protocol; any other key is answered
+// with a warning.
+var knownOptions = map[string]struct{}{
+	"heartbeat_interval_seconds": {}, "heartbeat_timeout_seconds": {}, "bridge_timeout_seconds": {},
+	"accept_encoding": {}, "transport": {}, "env": {}, "env_passthrough": {}, "workdir": {}, "chaos_seed": {},
+	"observe_seconds": {}, "detect_processes": {}, "treat_daemon_as_escape": {}, "settle_ms": {},
+	"settle_checks": {}, "thread_threshold": {}, "detect_fds": {}, "capture_stacks": {}, "daemon": {},
+	"sandbox": {}, "sandbox_images": {}, "constructor_args": {},
+}
+const defaultHeartbeatIntervalSeconds = 5.0
+
+// Daemon mode: framed requests on stdin and framed responses on stdout until shutdown.
+const daemonEnv = "GRAPHENE_BRIDGE_DAEMON"
+const frameMarker = "GRAPHENE_FRAME"
+const daemonProtocolVersion = 1
+
+// stderrMu keeps heartbeat and streamed result lines from interleaving.
+var stderrMu sync.Mutex
+
+// startHeartbeat writes periodic heartbeat lines to stderr so the orchestrator can
+// detect a hung bridge. It runs before any test, so it is part of every goroutine baseline.
+func startHeartbeat(options map[string]string) {
+	interval := defaultHeartbeatIntervalSeconds
+	if raw, ok := options["heartbeat_interval_seconds"]; ok {
+		if parsed, err := strconv.ParseFloat(strings.TrimSpace(raw), 64); err == nil {
+			interval = parsed
+		}
+	}
+	if interval <= 0 {
+		return
+	}
+
+	go func() {
+		ticker := time.NewTicker(time.Duration(interval * float64(time.Second)))
+		defer ticker.Stop()
+		for {
+			stderrMu.Lock()
+			fmt.Fprintf(os.Stderr, "%s %d\n", heartbeatPrefix, time.Now().UnixMilli())
+			stderrMu.Unlock()
+			<-ticker.C
+		}
+	}()
+}
+
+// emitResult streams a finished result to stderr so it survives a later bridge crash.
+func emitResult(result ExecutionResult) {
+	line, err := json.Marshal(result)
+	if err != nil {
+		return
+	}
+	stderrMu.Lock()
+	fmt.Fprintf(os.Stderr, "%s %s\n", resultPrefix, line)
+	stderrMu.Unlock()
 }
 
 func analyze(request AnalyzeRequest) AnalyzeResponse {
@@ -184,7 +415,7 @@ func analyze(request AnalyzeRequest) AnalyzeResponse {
 	_ = parseErr
 
 	// Load target function
-	targetFunc, err := loadTargetFunction(request.Target, request.TimeoutSeconds)
+	targetFunc, err := loadTargetFunction(request.Target, request.TimeoutSeconds, len(request.InputArgs) > 0)
 	if err != nil {
 		errMsg := fmt.Sprintf("Failed to load function: %v", err)
 		diagnosis := diagnoseBridgeError(errMsg)
@@ -201,6 +432,10 @@ func analyze(request AnalyzeRequest) AnalyzeResponse {
 		return response
 	}
 
+	response.Warnings = optionWarnings(request.Options)
+	options := parseDetection(request.Options)
+	options.Observe = observeSeconds(request.Options)
+
 	// Run tests
 	var successes, crashes, timeouts, escapes, genuineEscapes int
 	inputs := request.Inputs
@@ -208,10 +443,12 @@ func analyze(request AnalyzeRequest) AnalyzeResponse {
 		inputs = []string{""}
 	}
 
-	for _, input := range inputs {
+	for index, input := range inputs {
+		argument := decodeInput(request, index, input)
 		for i := 0; i < request.Repeat; i++ {
-			result := executeTest(targetFunc, request.Target, input, request.TimeoutSeconds)
+			result := executeTest(targetFunc, request.Target, input, argument, request.TimeoutSeconds, options)
 			response.Results = append(response.Results, result)
+			emitResult(result)
 
 			if result.Success {
 				successes++
@@ -279,7 +516,9 @@ func findHeapSignal(entries []string, prefix string) string {
 	return ""
 }
 
-func loadTargetFunction(target string, timeoutSeconds float64) (func(string) string, error) {
+// loadTargetFunction builds the target into a runner and returns a function invoking it.
+// With structured set, the runner gets each input as a JSON array of arguments.
+func loadTargetFunction(target string, timeoutSeconds float64, structured bool) (func(string) (targetOutput, error), error) {
 	sourcePath, functionName, err := parseTarget(target)
 	if err != nil {
 		return nil, err
@@ -296,12 +535,8 @@ func loadTargetFunction(target string, timeoutSeconds float64) (func(string) str
 		timeout = 30 * time.Second
 	}
 
-	return func(input string) string {
-		output, invokeErr := invokeCompiledTarget(binaryPath, timeout, input)
-		if invokeErr != nil {
-			panic(invokeErr)
-		}
-		return output
+	return func(input string) (targetOutput, error) {
+		return invokeCompiledTarget(binaryPath, timeout, input, structured)
 	}, nil
 }
 
@@ -431,34 +666,66 @@ func rewriteSourceAsMain(source string) (string, error) {
 	return packageRegex.ReplaceAllString(source, "package main"), nil
 }
 
+// makeRunnerEntrypoint calls the target with GRAPHENE_INPUT, or with the JSON array in
+// GRAPHENE_ARGS decoded into its parameter types.
 func makeRunnerEntrypoint(functionName string) string {
 	return fmt.Sprintf(`package main
 
 import (
+	"encoding/json"
 	"fmt"
 	"os"
+	"reflect"
 )
 
 func main() {
-	fmt.Print(%s(os.Getenv("GRAPHENE_INPUT")))
+	target := reflect.ValueOf(%s)
+	args := []reflect.Value{reflect.ValueOf(os.Getenv("GRAPHENE_INPUT"))}
+	if encoded, ok := os.LookupEnv("GRAPHENE_ARGS"); ok {
+		var values []json.RawMessage
+		if err := json.Unmarshal([]byte(encoded), &values); err != nil {
+			panic(err)
+		}
+		if len(values) != target.Type().NumIn() {
+			panic(fmt.Sprintf("target takes %%d argument(s), got %%d", target.Type().NumIn(), len(values)))
+		}
+		args = args[:0]
+		for i, value := range values {
+			arg := reflect.New(target.Type().In(i))
+			if err := json.Unmarshal(value, arg.Interface()); err != nil {
+				panic(fmt.Sprintf("argument %%d: %%v", i+1, err))
+			}
+			args = append(args, arg.Elem())
+		}
+	}
+	var outputs []interface{}
+	for _, output := range target.Call(args) {
+		outputs = append(outputs, output.Interface())
+	}
+	fmt.Print(outputs...)
 }
 `, functionName)
 }
 
-func invokeCompiledTarget(binaryPath string, timeout time.Duration, input string) (string, error) {
+func invokeCompiledTarget(binaryPath string, timeout time.Duration, input string, structured bool) (targetOutput, error) {
 	ctx, cancel := context.WithTimeout(context.Background(), timeout)
 	defer cancel()
 
+	variable := "GRAPHENE_INPUT="
+	if structured {
+		variable = "GRAPHENE_ARGS="
+	}
 	cmd := exec.CommandContext(ctx, binaryPath)
-	cmd.Env = append(os.Environ(), "GRAPHENE_INPUT="+input)
+	cmd.Env = append(os.Environ(), variable+input)
 	var stdout bytes.Buffer
 	var stderr bytes.Buffer
 	cmd.Stdout = &stdout
 	cmd.Stderr = &stderr
 
 	err := cmd.Run()
+	output := targetOutput{Stdout: stdout.String(), Stderr: stderr.String()}
 	if ctx.Err() == context.DeadlineExceeded {
-		return "", fmt.Errorf("timeout exceeded")
+		return output, fmt.Errorf("timeout exceeded")
 	}
 	if err != nil {
 		errDetails := strings.TrimSpace(stderr.String())
@@ -468,10 +735,10 @@ func invokeCompiledTarget(binaryPath string, timeout time.Duration, input string
 		if errDetails == "" {
 			errDetails = err.Error()
 		}
-		return "", fmt.Errorf("target execution failed: %s", errDetails)
+		return output, fmt.Errorf("target execution failed: %s", errDetails)
 	}
 
-	return strings.TrimSpace(stdout.String()), nil
+	return output, nil
 }
 
 func registerTemporaryTargetDir(dir string) {
@@ -485,7 +752,110 @@ func cleanupTemporaryTargets() {
 	temporaryTargetDirs = nil
 }
 
-func executeTest(targetFunc func(string) string, targetLabel string, input string, timeoutSeconds float64) ExecutionResult {
+// decodeInput returns the value handed to the target: the input's JSON argument list,
+// the bytes of a base64 input, or the input itself.
+func decodeInput(request AnalyzeRequest, index int, input string) string {
+	if index < len(request.InputArgs) {
+		return string(request.InputArgs[index])
+	}
+	if index < len(request.InputEncodings) && request.InputEncodings[index] == "base64" {
+		if decoded, err := base64.StdEncoding.DecodeString(input); err == nil {
+			return string(decoded)
+		}
+	}
+	return input
+}
+
+// observeSeconds reads the observation window for escaped goroutines; 0 disables it.
+func observeSeconds(options map[string]string) float64 {
+	parsed, err := strconv.ParseFloat(strings.TrimSpace(options["observe_seconds"]), 64)
+	if err != nil || parsed < 0 {
+		return 0
+	}
+	return parsed
+}
+
+// detectionOptions are the detection options of a request, with this bridge's defaults:
+// a 100 ms settle wait, one goroutine makes an escape, no stacks.
+type detectionOptions struct {
+	Settle          time.Duration
+	ThreadThreshold int
+	CaptureStacks   bool
+	Observe         float64
+}
+
+func parseDetection(options map[string]string) detectionOptions {
+	detection := detectionOptions{Settle: 100 * time.Millisecond, ThreadThreshold: 1}
+	if settle, err := strconv.ParseUint(strings.TrimSpace(options["settle_ms"]), 10, 64); err == nil {
+		detection.Settle = time.Duration(settle) * time.Millisecond
+	}
+	if threshold, err := strconv.Atoi(strings.TrimSpace(options["thread_threshold"])); err == nil && threshold > 1 {
+		detection.ThreadThreshold = threshold
+	}
+	detection.CaptureStacks = options["capture_stacks"] == "true"
+	return detection
+}
+
+// optionWarnings lists the request options this bridge does not know or cannot honor.
+func optionWarnings(options map[string]string) []string {
+	var warnings []string
+	for key := range options {
+		if _, known := knownOptions[key]; !known {
+			warnings = append(warnings, fmt.Sprintf("Unknown option `%s` ignored", key))
+		}
+	}
+	if _, set := options["settle_checks"]; set {
+		warnings = append(warnings, "`settle_checks` is not supported by the Go bridge; goroutines are checked once after `settle_ms`")
+	}
+	if options["detect_fds"] == "true" {
+		warnings = append(warnings, "`detect_fds` is not supported by the Go bridge")
+	}
+	sort.Strings(warnings)
+	return warnings
+}
+
+// observeGoroutines samples the running goroutines until every escaped one has exited
+// or the window ends, recording how long each outlived the call that started it.
+func observeGoroutines(escaped []GoroutineEscape, returnedAt time.Time, window time.Duration) {
+	finished := make(map[uint64]time.Time)
+	deadline := time.Now().Add(window)
+	stackBuf := make([]byte, 1024*1024)
+	var running map[uint64]map[string]string
+	for {
+		now := time.Now()
+		running = parseGoroutineIDs(stackBuf[:runtime.Stack(stackBuf, true)])
+		for _, goroutine := range escaped {
+			if _, alive := running[goroutine.GoroutineID]; !alive {
+				if _, seen := finished[goroutine.GoroutineID]; !seen {
+					finished[goroutine.GoroutineID] = now
+				}
+			}
+		}
+		if len(finished) == len(escaped) || now.After(deadline) {
+			break
+		}
+		time.Sleep(10 * time.Millisecond)
+	}
+
+	for i := range escaped {
+		end, done := finished[escaped[i].GoroutineID]
+		if !done {
+			end = time.Now()
+			// Last seen state ("running", "IO wait", "sleep", ...) feeds severity grading.
+			if info, ok := running[escaped[i].GoroutineID]; ok {
+				escaped[i].State = info["state"]
+			}
+		} else {
+			escaped[i].State = "finished"
+		}
+		outlived := end.Sub(returnedAt).Milliseconds()
+		stillRunning := !done
+		escaped[i].OutlivedMs = &outlived
+		escaped[i].StillRunning = &stillRunning
+	}
+}
+
+func executeTest(targetFunc func(string) (targetOutput, error), targetLabel string, input string, argument string, timeoutSeconds float64, options detectionOptions) ExecutionResult {
 	result := ExecutionResult{
 		InputData:      input,
 		Success:        false,
@@ -502,7 +872,7 @@ func executeTest(targetFunc func(string) string, targetLabel string, input strin
 
 	startTime := time.Now()
 	done := make(chan struct{})
-	var output string
+	var output targetOutput
 	var execErr error
 
 	// Execute function in goroutine with timeout
@@ -520,7 +890,7 @@ func executeTest(targetFunc func(string) string, targetLabel string, input strin
 		}()
 
 		if targetFunc != nil {
-			output = targetFunc(input)
+			output, execErr = targetFunc(argument)
 		}
 	}()
 
@@ -528,12 +898,14 @@ func executeTest(targetFunc func(string) string, targetLabel string, input strin
 	timeout := time.Duration(timeoutSeconds * float64(time.Second))
 	select {
 	case <-done:
+		result.Stdout = output.Stdout
+		result.Stderr = output.Stderr
 		if execErr != nil {
 			result.Crashed = true
 			result.Error = execErr.Error()
 		} else {
 			result.Success = true
-			result.Output = output
+			result.Output = strings.TrimSpace(output.Stdout)
 		}
 	case <-time.After(timeout):
 		result.Crashed = true
@@ -541,9 +913,10 @@ func executeTest(targetFunc func(string) string, targetLabel string, input strin
 	}
 
 	result.ExecutionTimeMs = time.Since(startTime).Milliseconds()
+	returnedAt := time.Now()
 
-	// Wait a bit for goroutines to finish
-	time.Sleep(100 * time.Millisecond)
+	// Give goroutines that are about to exit the settle period to finish
+	time.Sleep(options.Settle)
 
 	// Check for escaped goroutines with detailed identification
 	currentStackBuf := make([]byte, 1024*1024)
@@ -563,15 +936,22 @@ func executeTest(targetFunc func(string) string, targetLabel string, input strin
 	escapedGoroutines := make([]GoroutineEscape, 0)
 	for gid, info := range currentGoroutineIDs {
 		if _, exists := baselineGoroutineIDs[gid]; !exists {
-			escapedGoroutines = append(escapedGoroutines, GoroutineEscape{
+			escaped := GoroutineEscape{
 				GoroutineID: gid,
 				State:       info["state"],
 				Function:    info["function"],
-			})
+			}
+			if options.CaptureStacks && info["stack"] != "" {
+				escaped.StackTrace = strings.Split(info["stack"], "\n")
+			}
+			escapedGoroutines = append(escapedGoroutines, escaped)
 		}
 	}
 
-	if len(escapedGoroutines) > 0 {
+	if len(escapedGoroutines) > 0 && len(escapedGoroutines) >= options.ThreadThreshold {
+		if options.Observe > 0 {
+			observeGoroutines(escapedGoroutines, returnedAt, time.Duration(options.Observe*float64(time.Second)))
+		}
 		result.EscapeDetected = true
 		result.EscapeDetails.Goroutines = escapedGoroutines
 	}
@@ -1267,9 +1647,21 @@ func parseGoroutineIDs(stackData []byte) map[uint64]map[string]string {
 				}
 			}
 
+			// Frames of the block: a function line followed by a tab-indented file:line
+			var frames []string
+			for j := i + 1; j+1 < len(lines) && len(lines[j]) > 0; j += 2 {
+				location := strings.Fields(strings.TrimSpace(string(lines[j+1])))
+				frame := strings.TrimSpace(string(lines[j]))
+				if len(location) > 0 {
+					frame = fmt.Sprintf("%s (%s)", frame, location[0])
+				}
+				frames = append(frames, frame)
+			}
+
 			goroutines[gid] = map[string]string{
 				"state":    state,
 				"function": function,
+				"stack":    strings.Join(frames, "\n"),
 			}
 		}
 	}
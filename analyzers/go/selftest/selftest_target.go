@@ -0,0 +1,10 @@
+// Package selftest holds the target analyzed by the deep health check.
+package selftest
+
+var retained [][]byte
+
+// RetainBuffer keeps a buffer in package scope and returns a fixed marker.
+func RetainBuffer(input string) string {
+	retained = append(retained, append(make([]byte, 256*1024), input...))
+	return "ok"
+}
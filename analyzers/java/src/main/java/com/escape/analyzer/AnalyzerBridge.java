@@ -4,20 +4,27 @@ import com.google.gson.Gson;
 import com.google.gson.GsonBuilder;
 import com.google.gson.annotations.SerializedName;
 import java.io.*;
+import java.nio.charset.StandardCharsets;
 import java.lang.management.ManagementFactory;
 import java.lang.management.MemoryMXBean;
 import java.lang.management.MemoryUsage;
 import java.lang.management.ThreadInfo;
 import java.lang.management.ThreadMXBean;
+import java.lang.reflect.Constructor;
 import java.lang.reflect.Method;
+import java.lang.reflect.Modifier;
 import java.nio.file.Files;
 import java.nio.file.Path;
 import java.nio.file.Paths;
 import java.net.URL;
 import java.net.URLClassLoader;
+import java.net.UnixDomainSocketAddress;
+import java.nio.channels.Channels;
+import java.nio.channels.SocketChannel;
 import java.util.*;
 import java.util.regex.Matcher;
 import java.util.regex.Pattern;
+import java.util.zip.GZIPOutputStream;
 
 public class AnalyzerBridge {
 
@@ -77,16 +84,40 @@ public class AnalyzerBridge {
     public static void main(String[] args) {
         AnalyzeRequest request = null;
         try {
-            // Read request from stdin
-            BufferedReader reader = new BufferedReader(new InputStreamReader(System.in));
-            StringBuilder requestJson = new StringBuilder();
-            String line;
-            while ((line = reader.readLine()) != null) {
-                requestJson.append(line);
+            if ("1".equals(System.getenv(HANDSHAKE_ENV))) {
+                Map<String, Object> handshake = new LinkedHashMap<>();
+                handshake.put("protocol_version", PROTOCOL_VERSION);
+                handshake.put("analyzer_version", "1.0.0");
+                handshake.put("capabilities", CAPABILITIES);
+                System.out.println(compactGson.toJson(handshake));
+                System.out.flush();
+                System.exit(0);
+            }
+
+            if ("1".equals(System.getenv(DAEMON_ENV))) {
+                serveDaemon();
+                System.exit(0);
+            }
+
+            // Read request from stdin, or from the orchestrator's socket when it names one
+            String requestJson = readRequest();
+
+            // A batch runs several requests in this one JVM, answered in order
+            BatchAnalyzeRequest batch = gson.fromJson(requestJson, BatchAnalyzeRequest.class);
+            if (batch != null && batch.requests != null) {
+                Map<String, String> options = batch.requests.isEmpty() ? null : batch.requests.get(0).options;
+                startHeartbeat(options);
+                BatchAnalyzeResponse batchResponse = new BatchAnalyzeResponse();
+                batchResponse.responses = new ArrayList<>();
+                for (AnalyzeRequest entry : batch.requests) {
+                    batchResponse.responses.add(analyzeIsolated(entry));
+                }
+                writeResponse(batchResponse, options);
+                System.exit(0);
             }
 
             // Parse request
-            request = gson.fromJson(requestJson.toString(), AnalyzeRequest.class);
+            request = gson.fromJson(requestJson, AnalyzeRequest.class);
 
             if (request == null) {
                 throw new IllegalArgumentException("Empty input: expected JSON request on stdin");
@@ -96,11 +127,13 @@ public class AnalyzerBridge {
                 throw new IllegalArgumentException("Missing required field: 'target'");
             }
 
+            startHeartbeat(request.options);
+
             // Process request
             AnalyzeResponse response = analyze(request);
 
             // Write response to stdout
-            System.out.println(gson.toJson(response));
+            writeResponse(response, request.options);
             System.exit(0);
 
         } catch (Exception e) {
@@ -117,6 +150,18 @@ public class AnalyzerBridge {
         }
     }
 
+    // Analyzes one request of a batch; a failure only affects that request's response.
+    private static AnalyzeResponse analyzeIsolated(AnalyzeRequest request) {
+        try {
+            if (request.target == null || request.target.trim().isEmpty()) {
+                throw new IllegalArgumentException("Missing required field: 'target'");
+            }
+            return analyze(request);
+        } catch (Exception e) {
+            return buildErrorResponse(request.sessionId, formatBridgeError(e));
+        }
+    }
+
     private static AnalyzeResponse buildErrorResponse(String sessionId, String errorMessage) {
         ErrorDiagnosis diagnosis = diagnoseBridgeError(errorMessage);
 
@@ -242,6 +287,11 @@ public class AnalyzerBridge {
         // Run static analysis
         StaticEscapeAnalysis staticAnalysis = runTraditionalStaticEscapeAnalysis(target.sourceFile, target.methodName);
 
+        List<String> warnings = optionWarnings(request.options);
+        if (!warnings.isEmpty()) {
+            response.warnings = warnings;
+        }
+
         // Run tests
         int successes = 0, crashes = 0, timeouts = 0, escapes = 0, genuineEscapes = 0;
 
@@ -250,16 +300,21 @@ public class AnalyzerBridge {
             inputs = Collections.singletonList("");
         }
 
-        for (String input : inputs) {
+        for (int index = 0; index < inputs.size(); index++) {
+            String input = inputs.get(index);
+            Object[] arguments = decodeArguments(request, index, input);
             for (int i = 0; i < request.repeat; i++) {
                 ExecutionResult result = executeTest(
                     target.method,
+                    request.options,
                     request.target,
                     input,
+                    arguments,
                     request.timeoutSeconds
                 );
 
                 response.results.add(result);
+                emitResult(result);
 
                 if (result.success) successes++;
                 if (result.crashed) crashes++;
@@ -410,7 +465,153 @@ public class AnalyzerBridge {
         throw new NoSuchMethodException(methodName + " in " + clazz.getName());
     }
 
-    private static ExecutionResult executeTest(Method method, String targetLabel, String input, double timeoutSeconds) {
+    /**
+     * A fresh instance for one execution of an instance method, built with the first
+     * declared constructor that accepts the constructor_args option (a JSON array; no
+     * arguments when absent).
+     */
+    private static Object createReceiver(Method method, Map<String, String> options) throws Exception {
+        if (Modifier.isStatic(method.getModifiers())) {
+            return null;
+        }
+        String json = options == null ? null : options.get("constructor_args");
+        Object[] args = json == null || json.isBlank() ? new Object[0] : gson.fromJson(json, Object[].class);
+        Class<?> clazz = method.getDeclaringClass();
+        for (Constructor<?> constructor : clazz.getDeclaredConstructors()) {
+            Class<?>[] types = constructor.getParameterTypes();
+            if (types.length != args.length) {
+                continue;
+            }
+            Object[] converted = new Object[args.length];
+            boolean matches = true;
+            for (int i = 0; i < args.length && matches; i++) {
+                converted[i] = convertArgument(args[i], types[i]);
+                matches = converted[i] != null || (args[i] == null && !types[i].isPrimitive());
+            }
+            if (matches) {
+                constructor.setAccessible(true);
+                return constructor.newInstance(converted);
+            }
+        }
+        throw new NoSuchMethodException(
+            "No constructor of " + clazz.getName() + " takes " + args.length + " argument(s) matching constructor_args"
+        );
+    }
+
+    /** A JSON value as a parameter of type {@code type}, or null when it does not fit. */
+    private static Object convertArgument(Object value, Class<?> type) {
+        if (value == null) {
+            return null;
+        }
+        if (value instanceof Number) {
+            Number number = (Number) value;
+            if (type == int.class || type == Integer.class) return number.intValue();
+            if (type == long.class || type == Long.class) return number.longValue();
+            if (type == double.class || type == Double.class) return number.doubleValue();
+            if (type == float.class || type == Float.class) return number.floatValue();
+            if (type == short.class || type == Short.class) return number.shortValue();
+            if (type == byte.class || type == Byte.class) return number.byteValue();
+        }
+        if (value instanceof Boolean && (type == boolean.class || type == Boolean.class)) {
+            return value;
+        }
+        if (value instanceof String) {
+            String text = (String) value;
+            if ((type == char.class || type == Character.class) && text.length() == 1) {
+                return text.charAt(0);
+            }
+        }
+        return type.isInstance(value) ? value : null;
+    }
+
+    /**
+     * The arguments handed to the target: the input's argument list when the request
+     * carries input_args, otherwise the decoded input alone.
+     */
+    private static Object[] decodeArguments(AnalyzeRequest request, int index, String input) {
+        if (request.inputArgs != null && index < request.inputArgs.size()) {
+            return request.inputArgs.get(index).toArray();
+        }
+        return new Object[] { decodeInput(request, index, input) };
+    }
+
+    /** {@code arguments} converted to the parameter types of {@code method}. */
+    private static Object[] convertArguments(Method method, Object[] arguments) {
+        Class<?>[] types = method.getParameterTypes();
+        if (types.length != arguments.length) {
+            throw new IllegalArgumentException(
+                method.getName() + " takes " + types.length + " argument(s), got " + arguments.length
+            );
+        }
+        Object[] converted = new Object[arguments.length];
+        for (int i = 0; i < arguments.length; i++) {
+            converted[i] = convertArgument(arguments[i], types[i]);
+            if (converted[i] == null && arguments[i] != null) {
+                throw new IllegalArgumentException(
+                    "Argument " + (i + 1) + " (" + arguments[i] + ") does not fit parameter type " + types[i].getSimpleName()
+                );
+            }
+        }
+        return converted;
+    }
+
+    /**
+     * The value handed to the target: a base64 input decoded byte-for-byte into a
+     * Latin-1 string, or the input itself.
+     */
+    private static String decodeInput(AnalyzeRequest request, int index, String input) {
+        List<String> encodings = request.inputEncodings;
+        if (encodings == null || index >= encodings.size() || !"base64".equals(encodings.get(index))) {
+            return input;
+        }
+        try {
+            return new String(Base64.getDecoder().decode(input), StandardCharsets.ISO_8859_1);
+        } catch (IllegalArgumentException e) {
+            return input;
+        }
+    }
+
+    /** Reads a non-negative whole-number option, or the fallback when it is absent or malformed. */
+    private static long longOption(Map<String, String> options, String key, long fallback) {
+        if (options == null || !options.containsKey(key)) {
+            return fallback;
+        }
+        try {
+            long value = Long.parseLong(options.get(key).trim());
+            return value >= 0 ? value : fallback;
+        } catch (NumberFormatException e) {
+            return fallback;
+        }
+    }
+
+    /** Warnings for the request options this bridge does not know or cannot honor. */
+    private static List<String> optionWarnings(Map<String, String> options) {
+        List<String> warnings = new ArrayList<>();
+        if (options == null) {
+            return warnings;
+        }
+        for (String key : options.keySet()) {
+            if (!KNOWN_OPTIONS.contains(key)) {
+                warnings.add("Unknown option `" + key + "` ignored");
+            }
+        }
+        if (options.containsKey("settle_checks")) {
+            warnings.add("`settle_checks` is not supported by the Java bridge; threads are checked once after `settle_ms`");
+        }
+        if ("true".equals(options.get("detect_fds"))) {
+            warnings.add("`detect_fds` is not supported by the Java bridge");
+        }
+        Collections.sort(warnings);
+        return warnings;
+    }
+
+    private static ExecutionResult executeTest(Method method, Map<String, String> options, String targetLabel, String input, Object[] arguments, double timeoutSeconds) {
+        // Detection options, with this bridge's defaults: a 100 ms settle wait, one thread
+        // makes an escape, stacks captured.
+        long settleMs = longOption(options, "settle_ms", 100L);
+        long threadThreshold = Math.max(1L, longOption(options, "thread_threshold", 1L));
+        boolean captureStacks = options == null || !"false".equals(options.get("capture_stacks"));
+
         ExecutionResult result = new ExecutionResult();
         result.inputData = input;
         result.success = false;
@@ -422,13 +623,20 @@ public class AnalyzerBridge {
         Map<Long, ThreadInfo> baselineThreads = getAllThreadInfo(threadMXBean);
         HeapSnapshot baselineHeap = captureHeapSnapshot();
 
+        // What the target and the threads it starts print until the settle wait ends.
+        ByteArrayOutputStream capturedOut = new ByteArrayOutputStream();
+        ByteArrayOutputStream capturedErr = new ByteArrayOutputStream();
+        System.setOut(new PrintStream(capturedOut, true, StandardCharsets.UTF_8));
+        System.setErr(new PrintStream(capturedErr, true, StandardCharsets.UTF_8));
+
         long startTime = System.currentTimeMillis();
 
         try {
             // Invoke method in a timeout-aware manner
             Thread testThread = new Thread(() -> {
                 try {
-                    Object returnValue = method.invoke(null, input);
+                    Object receiver = createReceiver(method, options);
+                    Object returnValue = method.invoke(receiver, convertArguments(method, arguments));
                     result.output = String.valueOf(returnValue);
                     result.success = true;
                 } catch (Exception e) {
@@ -456,9 +664,14 @@ public class AnalyzerBridge {
 
         // Wait for async operations to settle
         try {
-            Thread.sleep(100);
+            Thread.sleep(settleMs);
         } catch (InterruptedException ignored) {
+        } finally {
+            System.setOut(BRIDGE_OUT);
+            System.setErr(BRIDGE_ERR);
         }
+        result.stdout = capturedOut.toString(StandardCharsets.UTF_8);
+        result.stderr = capturedErr.toString(StandardCharsets.UTF_8);
 
         // Check for escaped threads (with detailed info)
         Map<Long, ThreadInfo> currentThreads = getAllThreadInfo(threadMXBean);
@@ -482,10 +695,11 @@ public class AnalyzerBridge {
                     threadEscape.state = info.getThreadState().toString();
 
                     // Capture stack trace for escaped threads
-                    StackTraceElement[] stackTrace = info.getStackTrace();
-                    threadEscape.stackTrace = new ArrayList<>();
-                    for (StackTraceElement element : stackTrace) {
-                        threadEscape.stackTrace.add(element.toString());
+                    if (captureStacks) {
+                        threadEscape.stackTrace = new ArrayList<>();
+                        for (StackTraceElement element : info.getStackTrace()) {
+                            threadEscape.stackTrace.add(element.toString());
+                        }
                     }
 
                     escapeDetails.threads.add(threadEscape);
@@ -529,7 +743,8 @@ public class AnalyzerBridge {
             escapeDetails.other.add("heap_peak_bytes:" + heapPeakBytes);
         }
 
-        result.escapeDetected = !escapeDetails.threads.isEmpty() || !escapeDetails.escapingReferences.isEmpty();
+        result.escapeDetected = (!escapeDetails.threads.isEmpty() && escapeDetails.threads.size() >= threadThreshold)
+            || !escapeDetails.escapingReferences.isEmpty();
         result.escapeDetails = escapeDetails;
 
         return result;
@@ -1128,16 +1343,205 @@ public class AnalyzerBridge {
 
 
 
+    private static final String HEARTBEAT_PREFIX = "GRAPHENE_HEARTBEAT";
+    private static final String RESULT_PREFIX = "GRAPHENE_RESULT";
+    private static final double DEFAULT_HEARTBEAT_INTERVAL_SECONDS = 5.0;
+    private static final String GZIP_RESPONSE_MARKER = "GRAPHENE_GZIP";
+    private static final String BRIDGE_SOCKET_ENV = "GRAPHENE_BRIDGE_SOCKET";
+    // Handshake: print the protocol version and capabilities, then exit.
+    private static final String HANDSHAKE_ENV = "GRAPHENE_BRIDGE_HANDSHAKE";
+    private static final int PROTOCOL_VERSION = 1;
+    private static final List<String> CAPABILITIES =
+        List.of("batch", "gzip", "unix_socket", "daemon", "input_encodings", "input_args");
+    // Option keys of the bridge protocol; any other key is answered with a warning.
+    private static final Set<String> KNOWN_OPTIONS = Set.of(
+        "heartbeat_interval_seconds", "heartbeat_timeout_seconds", "bridge_timeout_seconds",
+        "accept_encoding", "transport", "env", "env_passthrough", "workdir", "chaos_seed",
+        "observe_seconds", "detect_processes", "treat_daemon_as_escape", "settle_ms",
+        "settle_checks", "thread_threshold", "detect_fds", "capture_stacks", "daemon",
+        "sandbox", "sandbox_images", "constructor_args");
+    // Daemon mode: framed requests on stdin and framed responses on stdout until shutdown.
+    private static final String DAEMON_ENV = "GRAPHENE_BRIDGE_DAEMON";
+    private static final String FRAME_MARKER = "GRAPHENE_FRAME";
+    private static final int DAEMON_PROTOCOL_VERSION = 1;
+    private static final Gson compactGson = new Gson();
+    // The process's own streams, kept for protocol lines while a target's writes are captured.
+    private static final PrintStream BRIDGE_OUT = System.out;
+    private static final PrintStream BRIDGE_ERR = System.err;
+
+    /** Connection to the orchestrator when it uses the Unix socket transport. */
+    private static SocketChannel bridgeSocket;
+
+    /** Reads the request from the orchestrator's socket when it names one, else from stdin. */
+    private static String readRequest() throws IOException {
+        String socketPath = System.getenv(BRIDGE_SOCKET_ENV);
+        InputStream source = System.in;
+        if (socketPath != null && !socketPath.isEmpty()) {
+            bridgeSocket = SocketChannel.open(UnixDomainSocketAddress.of(socketPath));
+            source = Channels.newInputStream(bridgeSocket);
+        }
+        return new String(source.readAllBytes(), StandardCharsets.UTF_8);
+    }
+
+    /** Sends the final response, gzip-compressed when the orchestrator accepts it. */
+    private static void writeResponse(Object payload, Map<String, String> options) throws IOException {
+        boolean acceptsGzip = false;
+        if (options != null && options.containsKey("accept_encoding")) {
+            for (String encoding : options.get("accept_encoding").split(",")) {
+                acceptsGzip |= encoding.trim().equals("gzip");
+            }
+        }
+
+        OutputStream out = bridgeSocket != null ? Channels.newOutputStream(bridgeSocket) : BRIDGE_OUT;
+        if (acceptsGzip) {
+            out.write((GZIP_RESPONSE_MARKER + "\n").getBytes(StandardCharsets.UTF_8));
+            GZIPOutputStream gzip = new GZIPOutputStream(out);
+            gzip.write(compactGson.toJson(payload).getBytes(StandardCharsets.UTF_8));
+            gzip.finish();
+        } else {
+            out.write((gson.toJson(payload) + "\n").getBytes(StandardCharsets.UTF_8));
+        }
+        out.flush();
+        if (bridgeSocket != null) {
+            bridgeSocket.close();
+        }
+    }
+
+    /** Answers framed requests on stdin until a shutdown frame or the end of input. */
+    private static void serveDaemon() throws IOException {
+        // Frames go to the process's stdout even if a target replaces System.out.
+        OutputStream out = new FileOutputStream(FileDescriptor.out);
+        DataInputStream in = new DataInputStream(new BufferedInputStream(System.in));
+        Map<String, Object> ready = new LinkedHashMap<>();
+        ready.put("type", "ready");
+        ready.put("protocol", DAEMON_PROTOCOL_VERSION);
+        writeFrame(out, ready);
+
+        boolean heartbeatStarted = false;
+        while (true) {
+            String header = readLine(in);
+            if (header == null) {
+                return;
+            }
+            header = header.trim();
+            if (!header.startsWith(FRAME_MARKER)) {
+                continue;
+            }
+            int length;
+            try {
+                length = Integer.parseInt(header.substring(FRAME_MARKER.length()).trim());
+            } catch (NumberFormatException e) {
+                continue;
+            }
+            byte[] payload = new byte[length];
+            in.readFully(payload);
+            DaemonMessage message = gson.fromJson(new String(payload, StandardCharsets.UTF_8), DaemonMessage.class);
+            if (message == null || "shutdown".equals(message.type)) {
+                return;
+            }
+            if (!"analyze".equals(message.type) || message.request == null) {
+                continue;
+            }
+            if (!heartbeatStarted) {
+                startHeartbeat(message.request.options);
+                heartbeatStarted = true;
+            }
+            Map<String, Object> response = new LinkedHashMap<>();
+            response.put("type", "response");
+            response.put("id", message.id);
+            response.put("response", analyzeIsolated(message.request));
+            writeFrame(out, response);
+        }
+    }
+
+    /** Reads one line of a frame header, or null at the end of input. */
+    private static String readLine(InputStream in) throws IOException {
+        ByteArrayOutputStream line = new ByteArrayOutputStream();
+        int next;
+        while ((next = in.read()) != -1 && next != '\n') {
+            line.write(next);
+        }
+        if (next == -1 && line.size() == 0) {
+            return null;
+        }
+        return line.toString(StandardCharsets.UTF_8);
+    }
+
+    /** Writes one framed message on a line of its own. */
+    private static void writeFrame(OutputStream out, Object message) throws IOException {
+        byte[] body = compactGson.toJson(message).getBytes(StandardCharsets.UTF_8);
+        out.write(("\n" + FRAME_MARKER + " " + body.length + "\n").getBytes(StandardCharsets.UTF_8));
+        out.write(body);
+        out.flush();
+    }
+
+    /** Streams a finished result to stderr so it survives a later bridge crash. */
+    private static void emitResult(ExecutionResult result) {
+        BRIDGE_ERR.println(RESULT_PREFIX + " " + compactGson.toJson(result));
+        BRIDGE_ERR.flush();
+    }
+
+    /**
+     * Writes periodic heartbeat lines to stderr so the orchestrator can detect a hung bridge.
+     * Started before any test runs, so the thread is part of every thread baseline.
+     */
+    private static void startHeartbeat(Map<String, String> options) {
+        double interval = DEFAULT_HEARTBEAT_INTERVAL_SECONDS;
+        if (options != null && options.containsKey("heartbeat_interval_seconds")) {
+            try {
+                interval = Double.parseDouble(options.get("heartbeat_interval_seconds").trim());
+            } catch (NumberFormatException ignored) {
+                // Keep the default interval.
+            }
+        }
+        if (interval <= 0) {
+            return;
+        }
+
+        final long intervalMillis = (long) (interval * 1000);
+        Thread heartbeat = new Thread(() -> {
+            while (true) {
+                BRIDGE_ERR.println(HEARTBEAT_PREFIX + " " + System.currentTimeMillis());
+                BRIDGE_ERR.flush();
+                try {
+                    Thread.sleep(intervalMillis);
+                } catch (InterruptedException e) {
+                    return;
+                }
+            }
+        }, "graphene-heartbeat");
+        heartbeat.setDaemon(true);
+        heartbeat.start();
+    }
+
     // Protocol classes (matching Rust protocol)
     static class AnalyzeRequest {
         String sessionId;
         String target;
         List<String> inputs;
+        @SerializedName("input_encodings")
+        List<String> inputEncodings;
+        @SerializedName("input_args")
+        List<List<Object>> inputArgs;
         int repeat;
         double timeoutSeconds;
         Map<String, String> options;
     }
 
+    static class BatchAnalyzeRequest {
+        List<AnalyzeRequest> requests;
+    }
+
+    static class DaemonMessage {
+        String type;
+        long id;
+        AnalyzeRequest request;
+    }
+
+    static class BatchAnalyzeResponse {
+        List<AnalyzeResponse> responses;
+    }
+
     static class AnalyzeResponse {
         String sessionId;
         String language;
@@ -1150,6 +1554,7 @@ public class AnalyzerBridge {
         String errorCategory;
         @SerializedName("suggested_action")
         String suggestedAction;
+        List<String> warnings;
     }
 
     static class ErrorDiagnosis {
@@ -1171,6 +1576,8 @@ public class AnalyzerBridge {
         long executionTimeMs;
         boolean escapeDetected;
         EscapeDetails escapeDetails;
+        String stdout;
+        String stderr;
     }
 
     static class EscapeDetails {
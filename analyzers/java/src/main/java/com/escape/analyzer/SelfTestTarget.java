@@ -0,0 +1,20 @@
+package com.escape.analyzer;
+
+import java.util.ArrayList;
+import java.util.List;
+
+/**
+ * Target analyzed by the orchestrator's deep health check.
+ * Retains a buffer in a static field so dynamic runs observe an escape.
+ */
+public final class SelfTestTarget {
+    private static final List<byte[]> RETAINED = new ArrayList<>();
+
+    private SelfTestTarget() {
+    }
+
+    public static String retainBuffer(String input) {
+        RETAINED.add(new byte[256 * 1024]);
+        return "ok:" + input;
+    }
+}
@@ -1,6 +1,11 @@
 #!/usr/bin/env node
 const path = require('path');
 const async_hooks = require('async_hooks');
+const fs = require('fs');
+const zlib = require('zlib');
+const net = require('net');
+const v8 = require('v8');
+const vm = require('vm');
 const { analyzeFile: runStaticAnalyzer } = require('./static_analyzer');
 
 const TRADITIONAL_ESCAPE_TYPES = new Set(['return', 'parameter', 'global', 'closure', 'heap']);
@@ -20,19 +25,240 @@ const IGNORED_ASYNC_RESOURCE_TYPES = new Set([
     'Microtask'
 ]);
 
+const HEARTBEAT_PREFIX = 'GRAPHENE_HEARTBEAT';
+const RESULT_PREFIX = 'GRAPHENE_RESULT';
+const GZIP_RESPONSE_MARKER = 'GRAPHENE_GZIP';
+const BRIDGE_SOCKET_ENV = 'GRAPHENE_BRIDGE_SOCKET';
+// Handshake: print the protocol version and capabilities, then exit.
+const HANDSHAKE_ENV = 'GRAPHENE_BRIDGE_HANDSHAKE';
+const PROTOCOL_VERSION = 1;
+const CAPABILITIES = ['batch', 'gzip', 'unix_socket', 'daemon', 'input_encodings', 'input_args', 'chaos', 'observe'];
+// Daemon mode: framed requests on stdin and framed responses on stdout until shutdown.
+const DAEMON_ENV = 'GRAPHENE_BRIDGE_DAEMON';
+const FRAME_MARKER = 'GRAPHENE_FRAME';
+const DAEMON_PROTOCOL_VERSION = 1;
+// Frames bypass the interception of process.stdout.write while targets run.
+const writeStdout = process.stdout.write.bind(process.stdout);
+
+// Connection to the orchestrator when it uses the Unix socket transport.
+let bridgeSocket = null;
+const DEFAULT_HEARTBEAT_INTERVAL_SECONDS = 5;
+// Schedule perturbation: chance of a delay at each instrumented call, and its upper bound.
+const CHAOS_PAUSE_PROBABILITY = 0.5;
+const CHAOS_MAX_DELAY_MS = 3;
+// How often escaped async resources are checked during an observation window.
+const OBSERVE_POLL_MS = 10;
+// Option keys of the bridge protocol; any other key is answered with a warning.
+const KNOWN_OPTIONS = new Set([
+    'heartbeat_interval_seconds', 'heartbeat_timeout_seconds', 'bridge_timeout_seconds',
+    'accept_encoding', 'transport', 'env', 'env_passthrough', 'workdir', 'chaos_seed',
+    'observe_seconds', 'detect_processes', 'treat_daemon_as_escape', 'settle_ms',
+    'settle_checks', 'thread_threshold', 'detect_fds', 'capture_stacks', 'daemon',
+    'sandbox', 'sandbox_images', 'constructor_args'
+]);
+
+function startHeartbeat(options) {
+    const configured = Number((options || {}).heartbeat_interval_seconds);
+    const intervalSeconds = Number.isFinite(configured) ? configured : DEFAULT_HEARTBEAT_INTERVAL_SECONDS;
+    if (intervalSeconds <= 0) {
+        return;
+    }
+
+    // Write straight to fd 2: process.stderr.write is intercepted while targets run.
+    const beat = () => {
+        try {
+            fs.writeSync(2, `${HEARTBEAT_PREFIX} ${Date.now()}\n`);
+        } catch (_) {
+            // Orchestrator went away; nothing left to signal.
+        }
+    };
+    beat();
+    setInterval(beat, intervalSeconds * 1000).unref();
+}
+
+// The arguments handed to the target: an input's argument list, or the input itself,
+// with base64 inputs decoded to a Buffer.
+function decodeArguments(request, index) {
+    const inputArgs = request.input_args || [];
+    if (index < inputArgs.length) {
+        return inputArgs[index];
+    }
+    const encoding = (request.input_encodings || [])[index] || 'utf8';
+    return [encoding === 'base64' ? Buffer.from(request.inputs[index], 'base64') : request.inputs[index]];
+}
+
+function parseChaosSeed(options) {
+    const value = (options || {}).chaos_seed;
+    if (value === undefined || value === null) {
+        return null;
+    }
+    const seed = Number(value);
+    return Number.isSafeInteger(seed) ? seed : null;
+}
+
+// Deterministic generator for one execution: a string hash feeding mulberry32.
+function seededRandom(key) {
+    let state = 0;
+    for (let i = 0; i < key.length; i++) {
+        state = (Math.imul(state ^ key.charCodeAt(i), 2654435761) + 1) >>> 0;
+    }
+    return () => {
+        state = (state + 0x6D2B79F5) >>> 0;
+        let t = state;
+        t = Math.imul(t ^ (t >>> 15), t | 1);
+        t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+        return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+    };
+}
+
+// While one execution runs, stretch timers and push some nextTick callbacks behind
+// pending I/O so callbacks settle in an order the unperturbed event loop rarely picks.
+// The same seed, input and repetition replay the same delays. Returns the restore function.
+function startSchedulePerturbation(seed, input, repetition) {
+    if (seed === null) {
+        return () => {};
+    }
+    const random = seededRandom(`${seed}:${repetition}:${input}`);
+    // Whole milliseconds: timers round shorter delays up to 1ms and would keep their order.
+    const delay = () => (random() < CHAOS_PAUSE_PROBABILITY ? 1 + Math.floor(random() * CHAOS_MAX_DELAY_MS) : 0);
+    const originalSetTimeout = global.setTimeout;
+    const originalNextTick = process.nextTick;
+
+    global.setTimeout = (callback, ms, ...args) => originalSetTimeout(callback, (Number(ms) || 0) + delay(), ...args);
+    process.nextTick = (callback, ...args) => {
+        const extra = delay();
+        if (extra > 0) {
+            originalSetTimeout(() => callback(...args), extra);
+        } else {
+            originalNextTick(callback, ...args);
+        }
+    };
+
+    return () => {
+        global.setTimeout = originalSetTimeout;
+        process.nextTick = originalNextTick;
+    };
+}
+
+// Stream each finished result so it survives a later bridge crash.
+function emitResult(result) {
+    try {
+        fs.writeSync(2, `${RESULT_PREFIX} ${JSON.stringify(result)}\n`);
+    } catch (_) {
+        // Best effort; the final response still carries every result.
+    }
+}
+
+// Read the request from the orchestrator's socket when it names one, else from stdin.
+async function readRequest() {
+    const socketPath = process.env[BRIDGE_SOCKET_ENV];
+    let source = process.stdin;
+    if (socketPath) {
+        bridgeSocket = net.createConnection({path: socketPath, allowHalfOpen: true});
+        source = bridgeSocket;
+    }
+    const chunks = [];
+    for await (const chunk of source) {
+        chunks.push(chunk);
+    }
+    return Buffer.concat(chunks).toString('utf8');
+}
+
+// Send the final response, gzip-compressed when the orchestrator accepts it, then exit
+// once the write drains: process.exit would otherwise cut off a large response.
+function writeResponseAndExit(payload, options, exitCode) {
+    const accepted = String((options || {}).accept_encoding || '').split(',').map(e => e.trim());
+    const body = accepted.includes('gzip')
+        ? Buffer.concat([Buffer.from(`${GZIP_RESPONSE_MARKER}\n`), zlib.gzipSync(JSON.stringify(payload))])
+        : JSON.stringify(payload, null, 2) + '\n';
+    if (bridgeSocket) {
+        bridgeSocket.end(body, () => process.exit(exitCode));
+        return;
+    }
+    process.stdout.write(body, () => process.exit(exitCode));
+}
+
+// Yields each `GRAPHENE_FRAME <length>` message read from a stream.
+async function* readFrames(stream) {
+    let buffered = Buffer.alloc(0);
+    let expected = null;
+    for await (const chunk of stream) {
+        buffered = Buffer.concat([buffered, chunk]);
+        for (;;) {
+            if (expected === null) {
+                const newline = buffered.indexOf(0x0a);
+                if (newline < 0) break;
+                const header = buffered.subarray(0, newline).toString('utf8').trim();
+                buffered = buffered.subarray(newline + 1);
+                if (header.startsWith(FRAME_MARKER)) {
+                    expected = Number(header.slice(FRAME_MARKER.length).trim());
+                }
+                continue;
+            }
+            if (buffered.length < expected) break;
+            const payload = buffered.subarray(0, expected);
+            buffered = buffered.subarray(expected);
+            expected = null;
+            yield JSON.parse(payload.toString('utf8'));
+        }
+    }
+}
+
+// Write one framed message on a line of its own.
+function writeFrame(message) {
+    const body = Buffer.from(JSON.stringify(message));
+    const frame = Buffer.concat([Buffer.from(`\n${FRAME_MARKER} ${body.length}\n`), body]);
+    return new Promise(resolve => writeStdout(frame, resolve));
+}
+
+// Answer framed requests until a shutdown message or the end of stdin.
+async function serveDaemon() {
+    await writeFrame({type: 'ready', protocol: DAEMON_PROTOCOL_VERSION});
+    let heartbeatStarted = false;
+    for await (const message of readFrames(process.stdin)) {
+        if (message.type === 'shutdown') break;
+        if (message.type !== 'analyze') continue;
+        const request = message.request || {};
+        if (!heartbeatStarted) {
+            startHeartbeat(request.options);
+            heartbeatStarted = true;
+        }
+        await writeFrame({type: 'response', id: message.id, response: await analyze(request)});
+    }
+    process.exit(0);
+}
+
 class AsyncResourceTracker {
-    constructor() {
+    constructor(captureStacks = false) {
         this.baselineResources = new Set();
         this.currentResources = new Map();
+        this.destroyedAt = new Map();
         this.hook = null;
+        this.captureStacks = captureStacks;
     }
 
     start() {
         this.baselineResources.clear();
         this.currentResources.clear();
+        this.destroyedAt.clear();
         this.hook = async_hooks.createHook({
-            init: (asyncId, type) => this.currentResources.set(asyncId, {type, created: Date.now()}),
-            destroy: (asyncId) => this.currentResources.delete(asyncId)
+            init: (asyncId, type, _trigger, resource) => {
+                const info = {type, created: Date.now()};
+                if (type === 'Timeout') {
+                    info.timer = resource;
+                    // Formatted only for timers that are reported: formatting during the
+                    // call would show up as heap growth.
+                    info.scheduled = new Error();
+                } else if (this.captureStacks) {
+                    info.scheduled = new Error();
+                }
+                this.currentResources.set(asyncId, info);
+            },
+            destroy: (asyncId) => {
+                if (this.currentResources.delete(asyncId)) {
+                    this.destroyedAt.set(asyncId, Date.now());
+                }
+            }
         });
         this.hook.enable();
     }
@@ -45,15 +271,123 @@ class AsyncResourceTracker {
         if (this.hook) this.hook.disable();
     }
 
+    // Stops tracking a timer the bridge scheduled itself; a Timeout converts to its id.
+    forget(timer) {
+        this.currentResources.delete(Number(timer));
+        return timer;
+    }
+
     getEscapedResources() {
         const escaped = [];
         for (const [asyncId, info] of this.currentResources.entries()) {
             if (!this.baselineResources.has(asyncId) && !IGNORED_ASYNC_RESOURCE_TYPES.has(info.type)) {
-                escaped.push({task_id: String(asyncId), task_type: info.type, state: 'active'});
+                const task = {task_id: String(asyncId), task_type: info.type, state: 'active'};
+                if (this.captureStacks && info.scheduled) {
+                    task.stack_trace = targetFrames(info.scheduled.stack);
+                }
+                escaped.push(task);
             }
         }
         return escaped;
     }
+
+    // Timers the call scheduled that have neither fired for the last time nor been cleared.
+    getLeakedTimers() {
+        const leaked = [];
+        for (const [asyncId, info] of this.currentResources.entries()) {
+            if (this.baselineResources.has(asyncId) || !info.timer || info.timer._destroyed) {
+                continue;
+            }
+            // Timers scheduled from bridge code the target called back into.
+            const site = schedulingSite(info.scheduled.stack);
+            if (site === null) {
+                continue;
+            }
+            const repeat = info.timer._repeat;
+            const callback = info.timer._onTimeout;
+            const name = (typeof callback === 'function' && callback.name) || '<anonymous>';
+            leaked.push({
+                timer_id: String(asyncId),
+                kind: repeat ? 'interval' : 'timeout',
+                period_ms: repeat || info.timer._idleTimeout,
+                callback: `${name} (${site})`,
+            });
+        }
+        return leaked;
+    }
+
+    // Keep checking escaped resources until each is destroyed or the window ends, and
+    // record how long each outlived the call that created it.
+    async observe(escaped, returnedAt, windowMs) {
+        const deadline = Date.now() + windowMs;
+        const alive = (task) => this.currentResources.has(Number(task.task_id));
+        while (Date.now() < deadline && escaped.some(alive)) {
+            await new Promise((resolve) => setTimeout(resolve, OBSERVE_POLL_MS));
+        }
+        for (const task of escaped) {
+            const destroyed = this.destroyedAt.get(Number(task.task_id));
+            task.still_running = destroyed === undefined;
+            task.outlived_ms = Math.max(0, (task.still_running ? Date.now() : destroyed) - returnedAt);
+            if (!task.still_running) {
+                task.state = 'finished';
+            }
+        }
+    }
+}
+
+// Stack frames outside Node internals and this bridge, innermost first: where the
+// target created an async resource.
+function targetFrames(stack) {
+    return String(stack || '').split('\n').slice(1)
+        .map((line) => line.trim().replace(/^at\s+/, ''))
+        .filter((frame) => {
+            const location = frame.replace(/^.*\((.*)\)$/, '$1');
+            return !location.startsWith('node:') && !location.startsWith(__filename);
+        });
+}
+
+// First stack frame outside Node internals and this bridge: where the target called
+// setTimeout or setInterval.
+function schedulingSite(stack) {
+    for (const line of String(stack || '').split('\n').slice(1)) {
+        const location = line.trim().replace(/^at\s+/, '').replace(/^.*\((.*)\)$/, '$1');
+        if (location.startsWith('node:') || location.startsWith(__filename)) {
+            continue;
+        }
+        return location;
+    }
+    return null;
+}
+
+function parseObserveSeconds(options) {
+    const seconds = Number((options || {}).observe_seconds);
+    return Number.isFinite(seconds) && seconds > 0 ? seconds : 0;
+}
+
+// Detection options of a request, with this bridge's defaults: a 100 ms settle wait,
+// one async resource makes an escape, descriptors snapshotted, no stacks.
+function parseDetection(options) {
+    const opts = options || {};
+    const number = (key, fallback) => {
+        const value = Number(opts[key]);
+        return opts[key] !== undefined && Number.isFinite(value) && value >= 0 ? value : fallback;
+    };
+    return {
+        settleMs: number('settle_ms', 100),
+        threadThreshold: Math.max(1, number('thread_threshold', 1)),
+        detectFds: String(opts.detect_fds) !== 'false',
+        captureStacks: String(opts.capture_stacks) === 'true'
+    };
+}
+
+// Warnings for the request options this bridge does not know or cannot honor.
+function optionWarnings(options) {
+    const keys = Object.keys(options || {});
+    const warnings = keys.filter((key) => !KNOWN_OPTIONS.has(key)).map((key) => `Unknown option \`${key}\` ignored`);
+    if (keys.includes('settle_checks')) {
+        warnings.push('`settle_checks` is not supported by the JavaScript bridge; async resources are checked once after `settle_ms`');
+    }
+    return warnings.sort();
 }
 
 function parseTargetReference(target) {
@@ -344,27 +678,107 @@ function mergeEscapeDetails(primary, secondary) {
     mergeArrayField('async_tasks');
     mergeArrayField('goroutines');
     mergeArrayField('other');
+    if (Array.isArray(first.file_descriptors) || Array.isArray(second.file_descriptors)) {
+        mergeArrayField('file_descriptors');
+    }
 
     return merged;
 }
 
-async function executeTest(targetFunc, targetLabel, input, timeoutSeconds) {
-    const result = {input_data: input, success: false, crashed: false, output: '', error: '', execution_time_ms: 0, escape_detected: false, escape_details: emptyEscapeDetails()};
-    const tracker = new AsyncResourceTracker();
+function startOutputCapture() {
+    const captured = {stdout: '', stderr: ''};
+    const originalStdoutWrite = process.stdout.write;
+    const originalStderrWrite = process.stderr.write;
+
+    const interceptor = (stream) => (chunk, encoding, callback) => {
+        captured[stream] += Buffer.isBuffer(chunk) ? chunk.toString('utf8') : String(chunk);
+        const done = typeof encoding === 'function' ? encoding : callback;
+        if (typeof done === 'function') {
+            done();
+        }
+        return true;
+    };
+
+    // Keep target console output out of the JSON protocol stream on stdout.
+    process.stdout.write = interceptor('stdout');
+    process.stderr.write = interceptor('stderr');
+
+    return {
+        captured,
+        restore() {
+            process.stdout.write = originalStdoutWrite;
+            process.stderr.write = originalStderrWrite;
+        }
+    };
+}
+
+// Sockets, pipes and files open in this process as fd -> {kind, target}, or null where
+// /proc/self/fd is unavailable. Anonymous inodes (epoll, eventfd) are left out.
+function openDescriptors() {
+    let fds;
+    try {
+        fds = fs.readdirSync('/proc/self/fd');
+    } catch (_) {
+        return null;
+    }
+    const descriptors = new Map();
+    // The listing's own descriptor is closed by now and fails to resolve.
+    for (const name of fds) {
+        let target;
+        try {
+            target = fs.readlinkSync(`/proc/self/fd/${name}`);
+        } catch (_) {
+            continue;
+        }
+        let kind;
+        if (target.startsWith('socket:')) {
+            kind = 'socket';
+        } else if (target.startsWith('pipe:')) {
+            kind = 'pipe';
+        } else if (target.startsWith('/')) {
+            kind = 'file';
+        } else {
+            continue;
+        }
+        descriptors.set(Number(name), {kind, target});
+    }
+    return descriptors;
+}
+
+function descriptorsLeftOpen(baseline) {
+    const current = openDescriptors();
+    if (baseline === null || current === null) {
+        return null;
+    }
+    return [...current.entries()]
+        .filter(([fd, {kind, target}]) => {
+            const before = baseline.get(fd);
+            return !before || before.kind !== kind || before.target !== target;
+        })
+        .sort(([a], [b]) => a - b)
+        .map(([fd, {kind, target}]) => ({fd, kind, target}));
+}
+
+async function executeTest(targetFunc, targetLabel, input, timeoutSeconds, chaosSeed = null, repetition = 0, observeSeconds = 0, args = [input], detection = parseDetection({})) {
+    const result = {input_data: input, success: false, crashed: false, output: '', error: '', execution_time_ms: 0, escape_detected: false, escape_details: emptyEscapeDetails(), stdout: '', stderr: ''};
+    const tracker = new AsyncResourceTracker(detection.captureStacks);
     tracker.start();
     await new Promise((resolve) => setImmediate(resolve));
     tracker.captureBaseline();
 
     const heapBefore = captureHeapSnapshot();
+    const baselineDescriptors = detection.detectFds ? openDescriptors() : null;
 
     const startTime = Date.now();
     let timeoutHandle = null;
+    const outputCapture = startOutputCapture();
+    const stopPerturbation = startSchedulePerturbation(chaosSeed, input, repetition);
 
     try {
         const returnValue = await Promise.race([
-            Promise.resolve().then(() => targetFunc(input)),
+            Promise.resolve().then(() => targetFunc(...args)),
             new Promise((_, reject) => {
-                timeoutHandle = setTimeout(() => reject(new Error(`Function timeout after ${timeoutSeconds}s`)), timeoutSeconds * 1000);
+                timeoutHandle = tracker.forget(setTimeout(() => reject(new Error(`Function timeout after ${timeoutSeconds}s`)), timeoutSeconds * 1000));
             })
         ]);
         result.output = String(returnValue);
@@ -376,10 +790,15 @@ async function executeTest(targetFunc, targetLabel, input, timeoutSeconds) {
         if (timeoutHandle !== null) {
             clearTimeout(timeoutHandle);
         }
+        stopPerturbation();
+        outputCapture.restore();
     }
 
-    result.execution_time_ms = Date.now() - startTime;
-    await new Promise(resolve => setTimeout(resolve, 100));
+    const returnedAt = Date.now();
+    result.stdout = outputCapture.captured.stdout;
+    result.stderr = outputCapture.captured.stderr;
+    result.execution_time_ms = returnedAt - startTime;
+    await new Promise(resolve => tracker.forget(setTimeout(resolve, detection.settleMs)));
 
     const heapAfter = captureHeapSnapshot();
     const heapGrowthBytes = Math.max(0, heapAfter.heap_used_bytes - heapBefore.heap_used_bytes);
@@ -408,25 +827,52 @@ async function executeTest(targetFunc, targetLabel, input, timeoutSeconds) {
     }
 
     const escapedResources = tracker.getEscapedResources();
+    if (observeSeconds > 0 && escapedResources.length > 0) {
+        await tracker.observe(escapedResources, returnedAt, observeSeconds * 1000);
+    }
     result.escape_details.async_tasks = escapedResources;
-    result.escape_detected = escapedResources.length > 0 || result.escape_details.escaping_references.length > 0;
+    const leakedTimers = tracker.getLeakedTimers();
+    result.escape_details.timers = leakedTimers;
+    const leakedDescriptors = descriptorsLeftOpen(baselineDescriptors);
+    if (leakedDescriptors !== null) {
+        result.escape_details.file_descriptors = leakedDescriptors;
+    }
+    result.escape_details.memory = {rss_before_bytes: heapBefore.rss_bytes, rss_after_bytes: heapAfter.rss_bytes};
+    result.escape_detected = escapedResources.length >= detection.threadThreshold
+        || leakedTimers.length > 0
+        || result.escape_details.escaping_references.length > 0
+        || (leakedDescriptors !== null && leakedDescriptors.length > 0);
     tracker.stop();
     return result;
 }
 
-function captureHeapSnapshot() {
-    if (typeof global.gc === 'function') {
+// global.gc only exists under --expose-gc; the flag can still be set at runtime and the
+// function taken from a fresh context.
+let collectGarbage = typeof global.gc === 'function' ? global.gc : null;
+function exposedGc() {
+    if (collectGarbage === null) {
         try {
-            global.gc();
+            v8.setFlagsFromString('--expose-gc');
+            collectGarbage = vm.runInNewContext('gc');
         } catch (_) {
-            // Best-effort GC; continue with current memory view.
+            collectGarbage = () => {};
         }
     }
+    return collectGarbage;
+}
+
+function captureHeapSnapshot() {
+    try {
+        exposedGc()();
+    } catch (_) {
+        // Best-effort GC; continue with current memory view.
+    }
 
     const usage = process.memoryUsage();
     return {
         heap_used_bytes: Math.max(0, usage.heapUsed || 0),
-        heap_total_bytes: Math.max(0, usage.heapTotal || 0)
+        heap_total_bytes: Math.max(0, usage.heapTotal || 0),
+        rss_bytes: Math.max(0, usage.rss || 0)
     };
 }
 
@@ -474,14 +920,26 @@ async function analyze(request) {
         if (!Array.isArray(request.inputs)) throw new Error("Missing or invalid field: 'inputs' must be an array");
         
         const loadedTarget = loadTargetFunction(request.target);
+        const chaosSeed = parseChaosSeed(request.options);
+        const observeSeconds = parseObserveSeconds(request.options);
+        const detection = parseDetection(request.options);
+        if (chaosSeed !== null) {
+            response.chaos_seed = chaosSeed;
+        }
+        const warnings = optionWarnings(request.options);
+        if (warnings.length > 0) {
+            response.warnings = warnings;
+        }
         let successes = 0, crashes = 0, timeouts = 0, escapes = 0, genuineEscapes = 0;
         
-        for (const input of request.inputs) {
+        for (const [index, input] of request.inputs.entries()) {
+            const args = decodeArguments(request, index);
             for (let i = 0; i < (request.repeat || 1); i++) {
                 const timeoutSeconds = request.timeout_seconds || request.timeoutSeconds || 30;
-                const result = await executeTest(loadedTarget.targetFunc, request.target, input, timeoutSeconds);
+                const result = await executeTest(loadedTarget.targetFunc, request.target, input, timeoutSeconds, chaosSeed, i, observeSeconds, args, detection);
 
                 response.results.push(result);
+                emitResult(result);
                 if (result.success) successes++;
                 if (result.crashed) crashes++;
                 if (result.error.includes('timeout')) timeouts++;
@@ -490,10 +948,12 @@ async function analyze(request) {
                     if (!result.error.includes('timeout')) genuineEscapes++;
 
                     const asyncCount = result.escape_details.async_tasks.length;
+                    const timerCount = result.escape_details.timers.length;
+                    const timerNote = timerCount > 0 ? ` + ${timerCount} timer(s) still scheduled` : '';
                     const heapSignal = findHeapSignal(result.escape_details.other, 'heap_growth_bytes:');
                     const description = heapSignal
-                        ? `Node.js heap escape signal detected (${heapSignal})${asyncCount > 0 ? ` + ${asyncCount} async resource leak(s)` : ''}`
-                        : `${asyncCount} async resource(s) escaped`;
+                        ? `Node.js heap escape signal detected (${heapSignal})${asyncCount > 0 ? ` + ${asyncCount} async resource leak(s)` : ''}${timerNote}`
+                        : `${asyncCount} async resource(s) escaped${timerNote}`;
 
                     response.vulnerabilities.push({
                         input,
@@ -534,11 +994,17 @@ async function analyze(request) {
 
 async function main() {
     try {
-        const chunks = [];
-        for await (const chunk of process.stdin) {
-            chunks.push(chunk);
+        if (process.env[HANDSHAKE_ENV] === '1') {
+            writeStdout(JSON.stringify({protocol_version: PROTOCOL_VERSION, analyzer_version: '1.0.0', capabilities: CAPABILITIES}) + '\n');
+            return;
+        }
+
+        if (process.env[DAEMON_ENV] === '1') {
+            await serveDaemon();
+            return;
         }
-        const inputData = Buffer.concat(chunks).toString('utf8');
+
+        const inputData = await readRequest();
         
         if (!inputData.trim()) {
             console.error(JSON.stringify(errorResponse(new Error('Empty input: expected JSON request on stdin'))));
@@ -554,9 +1020,20 @@ async function main() {
             process.exit(1);
         }
         
+        if (Array.isArray(request.requests)) {
+            const options = request.requests.length > 0 ? request.requests[0].options : undefined;
+            startHeartbeat(options);
+            const responses = [];
+            for (const entry of request.requests) {
+                responses.push(await analyze(entry));
+            }
+            writeResponseAndExit({responses}, options, 0);
+            return;
+        }
+
+        startHeartbeat(request.options);
         const response = await analyze(request);
-        console.log(JSON.stringify(response, null, 2));
-        process.exit(response.error ? 1 : 0);
+        writeResponseAndExit(response, request.options, response.error ? 1 : 0);
     } catch (error) {
         console.error(JSON.stringify(errorResponse(error)));
         process.exit(1);
@@ -0,0 +1,10 @@
+// Self-test target for the deep health check.
+// Retains a buffer in module scope so every dynamic run must report an escape.
+const RETAINED = [];
+
+function retainBuffer(input) {
+    RETAINED.push({input, buffer: new Array(64 * 1024).fill(input)});
+    return 'ok';
+}
+
+module.exports = { retainBuffer };
@@ -8,6 +8,15 @@ const fs = require('fs');
 
 const RETAINER_NAME_PATTERN = /(retained|cache|audit|handler|handlers|registry|store)/i;
 
+// Rules of the lifecycle findings: work the function starts that outlives it
+const FLOATING_PROMISE_RULE = 'floating-promise';
+const UNTERMINATED_WORKER_RULE = 'unterminated-worker';
+const UNCLEARED_INTERVAL_RULE = 'uncleared-interval';
+const UNHANDLED_CHILD_PROCESS_RULE = 'unhandled-child-process';
+
+const CHILD_PROCESS_MODULE = String.raw`['"](?:node:)?child_process['"]`;
+const CHILD_PROCESS_SPAWNERS = new Set(['spawn', 'exec', 'execFile', 'fork']);
+
 function extractIdentifiers(expression) {
     return expression.match(/[A-Za-z_$][\w$]*/g) || [];
 }
@@ -58,6 +67,80 @@ function collectModuleRetainers(lines) {
     return retainers;
 }
 
+/**
+ * Names of the async functions and methods declared in the module
+ */
+function collectAsyncFunctions(lines) {
+    const names = new Set();
+    for (const line of lines) {
+        const trimmed = line.trim();
+        const match = trimmed.match(/^(?:export\s+)?async\s+function\s*\*?\s*([A-Za-z_$][\w$]*)\s*\(/)
+            || trimmed.match(/^(?:export\s+)?(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*async\b/)
+            || trimmed.match(/^(?:static\s+)?async\s+([A-Za-z_$][\w$]*)\s*\(/);
+        if (match) {
+            names.add(match[1]);
+        }
+    }
+    return names;
+}
+
+/**
+ * Bindings of the child_process module: module aliases (`cp.spawn(...)`) and
+ * imported spawning functions (`spawn(...)`, possibly renamed)
+ */
+function collectChildProcessBindings(lines) {
+    const modules = new Set();
+    const functions = new Map(); // local name -> spawning function
+    const aliasPatterns = [
+        new RegExp(String.raw`(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*require\(\s*${CHILD_PROCESS_MODULE}\s*\)`),
+        new RegExp(String.raw`import\s+(?:\*\s+as\s+)?([A-Za-z_$][\w$]*)\s+from\s*${CHILD_PROCESS_MODULE}`),
+    ];
+    const destructurePatterns = [
+        new RegExp(String.raw`(?:const|let|var)\s*\{([^}]*)\}\s*=\s*require\(\s*${CHILD_PROCESS_MODULE}\s*\)`),
+        new RegExp(String.raw`import\s*\{([^}]*)\}\s*from\s*${CHILD_PROCESS_MODULE}`),
+    ];
+    for (const line of lines) {
+        for (const pattern of aliasPatterns) {
+            const match = line.match(pattern);
+            if (match) {
+                modules.add(match[1]);
+            }
+        }
+        for (const pattern of destructurePatterns) {
+            const match = line.match(pattern);
+            if (!match) {
+                continue;
+            }
+            for (const binding of match[1].split(',')) {
+                const [imported, local] = binding.split(/\s*(?::|\bas\b)\s*/).map((part) => part.trim());
+                if (CHILD_PROCESS_SPAWNERS.has(imported)) {
+                    functions.set(local || imported, imported);
+                }
+            }
+        }
+    }
+    return { modules, functions };
+}
+
+/**
+ * The child_process function spawned on this line, if any
+ */
+function matchChildProcessSpawn(trimmed, childProcess) {
+    const pattern = /(?<![\w$.])(?:([A-Za-z_$][\w$]*)\.)?([A-Za-z_$][\w$]*)\s*\((.*)$/g;
+    let match;
+    while ((match = pattern.exec(trimmed)) !== null) {
+        const [, owner, callee, args] = match;
+        const spawner = owner
+            ? (childProcess.modules.has(owner) && CHILD_PROCESS_SPAWNERS.has(callee) ? callee : null)
+            : childProcess.functions.get(callee);
+        if (spawner) {
+            return { spawner, column: match.index, args };
+        }
+        pattern.lastIndex = match.index + match[0].length - match[3].length;
+    }
+    return null;
+}
+
 function isRetainerContainer(containerName, moduleRetainers) {
     return moduleRetainers.has(containerName) || RETAINER_NAME_PATTERN.test(containerName);
 }
@@ -112,6 +195,8 @@ function analyzeFile(sourceFile, functionName) {
         const escapes = [];
         const dedupe = new Set();
         const moduleRetainers = collectModuleRetainers(lines);
+        const asyncFunctions = collectAsyncFunctions(lines);
+        const childProcess = collectChildProcessBindings(lines);
         
         // Find the function
         let inTargetFunction = false;
@@ -127,6 +212,10 @@ function analyzeFile(sourceFile, functionName) {
         const localObjectVars = new Set();
         const objectDependencies = new Map();
         const helperSinkDispatch = new Map(); // helper -> {param, container}
+        const intervalHandles = new Set();
+        const workers = new Map(); // handle -> line created
+        const children = new Map(); // handle -> {line, spawner}
+        const handledLifecycles = new Set(); // worker/child handles terminated, awaited or handed back
         
         for (let i = 0; i < lines.length; i++) {
             const line = lines[i];
@@ -196,7 +285,8 @@ function analyzeFile(sourceFile, functionName) {
                                 variable_name: escapedVar,
                                 reason: `Local object '${escapedVar}' passed through helper '${callee}' into retained container '${sinkInfo.container}'`,
                                 confidence: 'high',
-                                code_snippet: trimmed
+                                code_snippet: trimmed,
+                                rule_id: 'GH-JS-009'
                             });
                         }
                     }
@@ -225,7 +315,8 @@ function analyzeFile(sourceFile, functionName) {
                                 variable_name: escapedVar,
                                 reason,
                                 confidence: 'high',
-                                code_snippet: trimmed
+                                code_snippet: trimmed,
+                                rule_id: isClosureRetention ? 'GH-JS-010' : 'GH-JS-011'
                             });
                         }
                     }
@@ -245,7 +336,8 @@ function analyzeFile(sourceFile, functionName) {
                                 variable_name: escapedVar,
                                 reason: `Local object '${escapedVar}' assigned into retained container '${containerName}'`,
                                 confidence: 'high',
-                                code_snippet: trimmed
+                                code_snippet: trimmed,
+                                rule_id: 'GH-JS-011'
                             });
                         }
                     }
@@ -265,7 +357,8 @@ function analyzeFile(sourceFile, functionName) {
                                 variable_name: escapedVar,
                                 reason: `Local object '${escapedVar}' assigned to retained binding '${lhs}'`,
                                 confidence: 'high',
-                                code_snippet: trimmed
+                                code_snippet: trimmed,
+                                rule_id: 'GH-JS-011'
                             });
                         }
                     }
@@ -282,7 +375,8 @@ function analyzeFile(sourceFile, functionName) {
                             variable_name: returnedName,
                             reason: `Local object '${returnedName}' returned from function`,
                             confidence: 'high',
-                            code_snippet: trimmed
+                            code_snippet: trimmed,
+                            rule_id: 'GH-JS-012'
                         });
                     }
                 }
@@ -302,7 +396,8 @@ function analyzeFile(sourceFile, functionName) {
                             variable_name: 'setTimeout',
                             reason: 'setTimeout called without storing handle for cleanup',
                             confidence: 'medium',
-                            code_snippet: trimmed
+                            code_snippet: trimmed,
+                            rule_id: 'GH-JS-005'
                         });
                     }
                 }
@@ -312,6 +407,7 @@ function analyzeFile(sourceFile, functionName) {
                     const varMatch = trimmed.match(/(?:const|let|var)\s+(\w+)\s*=/);
                     if (varMatch) {
                         timerHandles.add(varMatch[1]);
+                        intervalHandles.add(varMatch[1]);
                         setTimeoutCalls.push({var: varMatch[1], line: lineNum});
                     } else {
                         addEscape(escapes, dedupe, {
@@ -321,7 +417,9 @@ function analyzeFile(sourceFile, functionName) {
                             variable_name: 'setInterval',
                             reason: 'setInterval called without storing handle for cleanup',
                             confidence: 'high',
-                            code_snippet: trimmed
+                            code_snippet: trimmed,
+                            rule: UNCLEARED_INTERVAL_RULE,
+                            rule_id: 'GH-JS-003'
                         });
                     }
                 }
@@ -333,6 +431,82 @@ function analyzeFile(sourceFile, functionName) {
                     clearTimeoutCalls.push({var: clearMatch[1], line: lineNum});
                 }
                 
+                // Check for Worker threads without terminate() or an 'exit' handler
+                const workerMatch = trimmed.match(/(?:(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*)?new\s+(?:[A-Za-z_$][\w$]*\.)?Worker\s*\(/);
+                if (workerMatch) {
+                    if (workerMatch[1]) {
+                        workers.set(workerMatch[1], lineNum);
+                    } else {
+                        addEscape(escapes, dedupe, {
+                            escape_type: 'concurrency',
+                            line: lineNum,
+                            column: Math.max(trimmed.indexOf('new '), 0),
+                            variable_name: 'Worker',
+                            reason: 'Worker thread started without keeping a handle to terminate or wait for it',
+                            confidence: 'high',
+                            code_snippet: trimmed,
+                            rule: UNTERMINATED_WORKER_RULE,
+                            rule_id: 'GH-JS-002'
+                        });
+                    }
+                }
+
+                // Check for child processes without exit handling
+                const spawnMatch = childProcess.modules.size > 0 || childProcess.functions.size > 0
+                    ? matchChildProcessSpawn(trimmed, childProcess)
+                    : null;
+                if (spawnMatch) {
+                    const varMatch = trimmed.match(/^(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=/);
+                    const hasCallback = spawnMatch.args.includes('=>') || spawnMatch.args.includes('function');
+                    const awaited = /^(?:(?:const|let|var)\s+[A-Za-z_$][\w$]*\s*=\s*)?(?:await|return)\b/.test(trimmed);
+                    // exec/execFile callbacks run once the process has exited
+                    const handled = awaited || (hasCallback && spawnMatch.spawner.startsWith('exec'));
+                    if (varMatch && !handled) {
+                        children.set(varMatch[1], { line: lineNum, spawner: spawnMatch.spawner });
+                    } else if (!handled) {
+                        addEscape(escapes, dedupe, {
+                            escape_type: 'concurrency',
+                            line: lineNum,
+                            column: spawnMatch.column,
+                            variable_name: spawnMatch.spawner,
+                            reason: `Child process started by ${spawnMatch.spawner}() without an 'exit' or 'close' handler`,
+                            confidence: 'high',
+                            code_snippet: trimmed,
+                            rule: UNHANDLED_CHILD_PROCESS_RULE,
+                            rule_id: 'GH-JS-004'
+                        });
+                    }
+                }
+
+                // terminate()/kill(), 'exit'/'close' listeners, events.once() and handing
+                // the handle back end the lifecycle of a worker or child process
+                for (const pattern of [
+                    /([A-Za-z_$][\w$]*)\.(?:terminate|kill)\s*\(/g,
+                    /([A-Za-z_$][\w$]*)\.(?:on|once|addListener)\(\s*['"](?:exit|close)['"]/g,
+                    /\bonce\(\s*([A-Za-z_$][\w$]*)\s*,\s*['"](?:exit|close)['"]/g,
+                    /^return\s+([A-Za-z_$][\w$]*)\s*;?$/g,
+                ]) {
+                    for (const match of trimmed.matchAll(pattern)) {
+                        handledLifecycles.add(match[1]);
+                    }
+                }
+
+                // Check for fire-and-forget calls of async functions
+                const floatingMatch = trimmed.match(/^(?:void\s+)?((?:[A-Za-z_$][\w$]*\.)*([A-Za-z_$][\w$]*))\s*\(.*\)\s*;?$/);
+                if (floatingMatch && (asyncFunctions.has(floatingMatch[2]) || floatingMatch[1] === 'fetch')) {
+                    addEscape(escapes, dedupe, {
+                        escape_type: 'concurrency',
+                        line: lineNum,
+                        column: Math.max(trimmed.indexOf(floatingMatch[1]), 0),
+                        variable_name: floatingMatch[1],
+                        reason: `Async call '${floatingMatch[1]}()' is not awaited; its work continues after the function completes`,
+                        confidence: 'medium',
+                        code_snippet: trimmed,
+                        rule: FLOATING_PROMISE_RULE,
+                        rule_id: 'GH-JS-001'
+                    });
+                }
+
                 // Check for process.nextTick without completion
                 if (trimmed.includes('process.nextTick')) {
                     addEscape(escapes, dedupe, {
@@ -342,7 +516,8 @@ function analyzeFile(sourceFile, functionName) {
                         variable_name: 'nextTick',
                         reason: 'process.nextTick may defer execution beyond function return',
                         confidence: 'low',
-                        code_snippet: trimmed
+                        code_snippet: trimmed,
+                        rule_id: 'GH-JS-007'
                     });
                 }
                 
@@ -357,7 +532,8 @@ function analyzeFile(sourceFile, functionName) {
                             variable_name: 'setImmediate',
                             reason: 'setImmediate called without storing handle',
                             confidence: 'medium',
-                            code_snippet: trimmed
+                            code_snippet: trimmed,
+                            rule_id: 'GH-JS-005'
                         });
                     }
                 }
@@ -396,7 +572,41 @@ function analyzeFile(sourceFile, functionName) {
                                 variable_name: handle,
                                 reason: `Timer handle '${handle}' created but not cleared`,
                                 confidence: 'high',
-                                code_snippet: null
+                                code_snippet: null,
+                                rule: intervalHandles.has(handle) ? UNCLEARED_INTERVAL_RULE : undefined,
+                                rule_id: intervalHandles.has(handle) ? 'GH-JS-003' : 'GH-JS-006'
+                            });
+                        }
+                    }
+
+                    for (const [handle, createdAt] of workers) {
+                        if (!handledLifecycles.has(handle)) {
+                            addEscape(escapes, dedupe, {
+                                escape_type: 'concurrency',
+                                line: createdAt,
+                                column: 0,
+                                variable_name: handle,
+                                reason: `Worker '${handle}' is never terminated and has no 'exit' handler`,
+                                confidence: 'high',
+                                code_snippet: lines[createdAt - 1].trim(),
+                                rule: UNTERMINATED_WORKER_RULE,
+                                rule_id: 'GH-JS-002'
+                            });
+                        }
+                    }
+
+                    for (const [handle, child] of children) {
+                        if (!handledLifecycles.has(handle)) {
+                            addEscape(escapes, dedupe, {
+                                escape_type: 'concurrency',
+                                line: child.line,
+                                column: 0,
+                                variable_name: handle,
+                                reason: `Child process '${handle}' started by ${child.spawner}() is never killed and has no 'exit' or 'close' handler`,
+                                confidence: 'medium',
+                                code_snippet: lines[child.line - 1].trim(),
+                                rule: UNHANDLED_CHILD_PROCESS_RULE,
+                                rule_id: 'GH-JS-004'
                             });
                         }
                     }
@@ -411,7 +621,8 @@ function analyzeFile(sourceFile, functionName) {
                                 variable_name: promise,
                                 reason: `Promise '${promise}' created but not awaited or handled`,
                                 confidence: 'medium',
-                                code_snippet: null
+                                code_snippet: null,
+                                rule_id: 'GH-JS-008'
                             });
                         }
                     }
@@ -6,9 +6,14 @@ around each target invocation while preserving the bridge's existing result
 packaging and report contract.
 """
 
+import base64
 import gc
+import gzip
 import json
+import os
+import socket
 import sys
+import functools
 import inspect
 from pathlib import Path
 from typing import Any, Dict, List, Optional, Tuple
@@ -26,11 +31,17 @@ sys.path.insert(0, str(TESTS_PYTHON_DIR))  # Add tests/python for imports
 sys.path.insert(0, str(TESTS_DIR))         # Add tests for general test modules
 sys.path.insert(0, str(ROOT_DIR))
 
-from test_harness import PythonFunctionTestHarness  # type: ignore[import-not-found]
+from test_harness import PythonFunctionTestHarness, TargetArguments  # type: ignore[import-not-found]
 from vulnerability_detector import VulnerabilityDetector  # type: ignore[import-not-found]
+import concurrent.futures
+import contextlib
 import importlib
 import importlib.util
+import queue
+import random
+import threading
 import time
+import traceback
 import tracemalloc
 
 
@@ -38,6 +49,415 @@ ESCAPE_DESTINATIONS = {
     "heap": "heap_container",
 }
 
+HEARTBEAT_PREFIX = "GRAPHENE_HEARTBEAT"
+RESULT_PREFIX = "GRAPHENE_RESULT"
+GZIP_RESPONSE_MARKER = "GRAPHENE_GZIP"
+BRIDGE_SOCKET_ENV = "GRAPHENE_BRIDGE_SOCKET"
+# Handshake: print the protocol version and capabilities, then exit.
+HANDSHAKE_ENV = "GRAPHENE_BRIDGE_HANDSHAKE"
+PROTOCOL_VERSION = 1
+CAPABILITIES = ["batch", "gzip", "unix_socket", "daemon", "input_encodings", "input_args", "chaos", "observe"]
+# Daemon mode: framed requests on stdin and framed responses on stdout until shutdown.
+DAEMON_ENV = "GRAPHENE_BRIDGE_DAEMON"
+FRAME_MARKER = "GRAPHENE_FRAME"
+DAEMON_PROTOCOL_VERSION = 1
+DEFAULT_HEARTBEAT_INTERVAL_SECONDS = 5.0
+# Schedule perturbation: chance of a pause at each instrumented operation, and its upper bound.
+CHAOS_PAUSE_PROBABILITY = 0.5
+CHAOS_MAX_DELAY_SECONDS = 0.002
+# How often escaped threads are sampled during an observation window.
+OBSERVE_POLL_SECONDS = 0.01
+# Option keys of the bridge protocol; any other key is answered with a warning.
+KNOWN_OPTIONS = {
+    "heartbeat_interval_seconds", "heartbeat_timeout_seconds", "bridge_timeout_seconds",
+    "accept_encoding", "transport", "env", "env_passthrough", "workdir", "chaos_seed",
+    "observe_seconds", "detect_processes", "treat_daemon_as_escape", "settle_ms",
+    "settle_checks", "thread_threshold", "detect_fds", "capture_stacks", "daemon",
+    "sandbox", "sandbox_images", "constructor_args",
+}
+_STDERR_LOCK = threading.Lock()
+# Connection to the orchestrator when it uses the Unix socket transport.
+_BRIDGE_SOCKET: Optional[socket.socket] = None
+
+
+def start_heartbeat(options: Optional[Dict[str, Any]]) -> None:
+    """Write periodic heartbeat lines so the orchestrator can detect a hung bridge."""
+    try:
+        interval = float((options or {}).get("heartbeat_interval_seconds", DEFAULT_HEARTBEAT_INTERVAL_SECONDS))
+    except (TypeError, ValueError):
+        interval = DEFAULT_HEARTBEAT_INTERVAL_SECONDS
+    if interval <= 0:
+        return
+
+    # Write to the real stderr: the harness redirects sys.stderr while targets run.
+    stream = sys.__stderr__
+
+    def beat():
+        while True:
+            try:
+                with _STDERR_LOCK:
+                    stream.write(f"{HEARTBEAT_PREFIX} {int(time.time() * 1000)}\n")
+                    stream.flush()
+            except Exception:
+                return
+            time.sleep(interval)
+
+    threading.Thread(target=beat, name="graphene-heartbeat", daemon=True).start()
+
+
+def parse_chaos_seed(options: Optional[Dict[str, Any]]) -> Optional[int]:
+    try:
+        value = (options or {}).get("chaos_seed")
+        return int(value) if value is not None else None
+    except (TypeError, ValueError):
+        return None
+
+
+def parse_observe_seconds(options: Optional[Dict[str, Any]]) -> float:
+    try:
+        return max(0.0, float((options or {}).get("observe_seconds", 0)))
+    except (TypeError, ValueError):
+        return 0.0
+
+
+def parse_detection(options: Optional[Dict[str, Any]]) -> Dict[str, Any]:
+    """Detection options of a request, with this bridge's defaults: no settle wait, one
+    thread makes an escape, descriptors snapshotted, no stacks."""
+    options = options or {}
+
+    def number(key: str, default: int) -> int:
+        try:
+            return max(0, int(options.get(key, default)))
+        except (TypeError, ValueError):
+            return default
+
+    return {
+        "settle_seconds": number("settle_ms", 0) / 1000.0,
+        "thread_threshold": max(1, number("thread_threshold", 1)),
+        "detect_fds": str(options.get("detect_fds", "true")) != "false",
+        "capture_stacks": str(options.get("capture_stacks", "false")) == "true",
+    }
+
+
+def option_warnings(options: Optional[Dict[str, Any]]) -> List[str]:
+    """Warnings for the request options this bridge does not know or cannot honor."""
+    options = options or {}
+    warnings = [f"Unknown option `{key}` ignored" for key in options if key not in KNOWN_OPTIONS]
+    if "settle_checks" in options:
+        warnings.append("`settle_checks` is not supported by the Python bridge; threads are checked once after `settle_ms`")
+    return sorted(warnings)
+
+
+def request_inputs(request: Dict[str, Any]) -> List[Tuple[Any, Any]]:
+    """(input_data, argument) per input; base64 inputs reach the target as bytes and
+    argument lists as the target's positional arguments."""
+    inputs = request.get("inputs") or []
+    encodings = request.get("input_encodings") or []
+    input_args = request.get("input_args") or []
+    pairs = []
+    for index, value in enumerate(inputs):
+        if index < len(input_args):
+            pairs.append((value, TargetArguments(input_args[index])))
+            continue
+        encoding = encodings[index] if index < len(encodings) else "utf8"
+        pairs.append((value, base64.b64decode(value) if encoding == "base64" else value))
+    return pairs
+
+
+def threads_left_running(baseline: set) -> List[threading.Thread]:
+    """Threads started during an execution that are still alive after it returned."""
+    return [
+        thread
+        for thread in threading.enumerate()
+        if thread.ident not in baseline and thread.is_alive() and thread.name != "graphene-heartbeat"
+    ]
+
+
+SOCKET_MODULES = ("socket.py", "selectors.py", "ssl.py", "socketserver.py")
+
+
+def thread_cpu_ms(thread: threading.Thread) -> Optional[int]:
+    """CPU time the thread has used so far, where the platform exposes it per thread."""
+    try:
+        with open(f"/proc/self/task/{thread.native_id}/stat") as stat:
+            fields = stat.read().rsplit(")", 1)[1].split()
+        return int((int(fields[11]) + int(fields[12])) * 1000 / os.sysconf("SC_CLK_TCK"))
+    except (OSError, ValueError, IndexError, AttributeError):
+        return None
+
+
+def thread_holds(thread: threading.Thread) -> List[str]:
+    """Resources the thread is visibly using: its stack is inside socket I/O."""
+    frame = sys._current_frames().get(thread.ident)
+    while frame is not None:
+        if os.path.basename(frame.f_code.co_filename) in SOCKET_MODULES:
+            return ["socket"]
+        frame = frame.f_back
+    return []
+
+
+def thread_stack(thread: threading.Thread) -> List[str]:
+    """The thread's current stack, innermost frame first."""
+    frame = sys._current_frames().get(thread.ident)
+    if frame is None:
+        return []
+    return [f"{entry.name} ({entry.filename}:{entry.lineno})" for entry in reversed(traceback.extract_stack(frame))]
+
+
+def observe_threads(
+    threads: List[threading.Thread], returned_at: float, window_seconds: float, capture_stacks: bool = False
+) -> List[dict]:
+    """Protocol entries for threads left running. With a window, keep sampling until each
+    thread exits or the window ends, and record how long it outlived the call and the CPU
+    it used meanwhile. Stacks are taken before the window, while every thread is alive."""
+    stacks = {thread.ident: thread_stack(thread) for thread in threads} if capture_stacks else {}
+    finished: Dict[int, float] = {}
+    cpu_start = {thread.ident: thread_cpu_ms(thread) for thread in threads}
+    cpu_last = dict(cpu_start)
+    if window_seconds > 0:
+        deadline = time.time() + window_seconds
+        while True:
+            now = time.time()
+            for thread in threads:
+                if not thread.is_alive():
+                    finished.setdefault(thread.ident, now)
+                elif cpu_start[thread.ident] is not None:
+                    cpu_last[thread.ident] = thread_cpu_ms(thread) or cpu_last[thread.ident]
+            if len(finished) == len(threads) or now >= deadline:
+                break
+            time.sleep(OBSERVE_POLL_SECONDS)
+
+    entries = []
+    for thread in threads:
+        entry = {
+            "thread_id": str(thread.ident),
+            "name": thread.name,
+            "is_daemon": thread.daemon,
+            "state": "finished" if thread.ident in finished else "alive",
+        }
+        if capture_stacks:
+            entry["stack_trace"] = stacks.get(thread.ident, [])
+        if window_seconds > 0:
+            end = finished.get(thread.ident, time.time())
+            entry["outlived_ms"] = int((end - returned_at) * 1000)
+            entry["still_running"] = thread.ident not in finished
+            if cpu_start[thread.ident] is not None:
+                entry["cpu_ms"] = cpu_last[thread.ident] - cpu_start[thread.ident]
+            if entry["still_running"]:
+                entry["holds"] = thread_holds(thread)
+        entries.append(entry)
+    return entries
+
+
+def open_descriptors() -> Optional[Dict[int, Tuple[str, str]]]:
+    """Sockets, pipes and files open in this process as fd -> (kind, target), or None
+    where /proc/self/fd is unavailable. Anonymous inodes (epoll, eventfd) are left out."""
+    try:
+        fds = os.listdir("/proc/self/fd")
+    except OSError:
+        return None
+    descriptors = {}
+    # The listing's own descriptor is closed by now and fails to resolve.
+    for name in fds:
+        try:
+            target = os.readlink(f"/proc/self/fd/{name}")
+        except OSError:
+            continue
+        if target.startswith("socket:"):
+            kind = "socket"
+        elif target.startswith("pipe:"):
+            kind = "pipe"
+        elif target.startswith("/"):
+            kind = "file"
+        else:
+            continue
+        descriptors[int(name)] = (kind, target)
+    return descriptors
+
+
+def resident_memory() -> Optional[int]:
+    """Resident set size of this process in bytes, or None where /proc/self/statm is
+    unavailable."""
+    try:
+        with open("/proc/self/statm") as statm:
+            pages = int(statm.read().split()[1])
+    except (OSError, ValueError, IndexError):
+        return None
+    return pages * os.sysconf("SC_PAGE_SIZE")
+
+
+def descriptors_left_open(baseline: Optional[Dict[int, Tuple[str, str]]]) -> Optional[List[dict]]:
+    """Protocol entries for descriptors opened during an execution and still open."""
+    current = open_descriptors()
+    if baseline is None or current is None:
+        return None
+    return [
+        {"fd": fd, "kind": kind, "target": target}
+        for fd, (kind, target) in sorted(current.items())
+        if baseline.get(fd) != (kind, target)
+    ]
+
+
+class _PerturbedLock:
+    """Lock proxy that may pause before each acquisition."""
+
+    def __init__(self, inner, pause):
+        self._inner = inner
+        self._pause = pause
+
+    def acquire(self, *args, **kwargs):
+        self._pause()
+        return self._inner.acquire(*args, **kwargs)
+
+    def release(self):
+        self._inner.release()
+
+    def __enter__(self):
+        return self.acquire()
+
+    def __exit__(self, *exc_info):
+        self.release()
+
+    def __getattr__(self, name):
+        # Condition variables look up _is_owned, _release_save and friends on the lock.
+        return getattr(self._inner, name)
+
+
+@contextlib.contextmanager
+def schedule_perturbation(seed: Optional[int], input_data: Any, repetition: int):
+    """Pause for a seeded random moment around thread starts, lock acquisition, queue
+    operations and executor submissions while one execution runs.
+
+    The delays come from a generator seeded with the run's seed, input and repetition,
+    so the same seed replays the same schedule of pauses.
+    """
+    if seed is None:
+        yield
+        return
+
+    rng = random.Random(f"{seed}:{repetition}:{input_data}")
+    rng_lock = threading.Lock()
+
+    def pause():
+        with rng_lock:
+            delay = rng.uniform(0, CHAOS_MAX_DELAY_SECONDS) if rng.random() < CHAOS_PAUSE_PROBABILITY else 0
+        if delay:
+            time.sleep(delay)
+
+    def around(original):
+        def perturbed(*args, **kwargs):
+            pause()
+            try:
+                return original(*args, **kwargs)
+            finally:
+                pause()
+        return perturbed
+
+    def before(original):
+        def perturbed(*args, **kwargs):
+            pause()
+            return original(*args, **kwargs)
+        return perturbed
+
+    lock_factory, rlock_factory = threading.Lock, threading.RLock
+    patches = [
+        (threading.Thread, "start", around(threading.Thread.start)),
+        (queue.Queue, "put", before(queue.Queue.put)),
+        (queue.Queue, "get", before(queue.Queue.get)),
+        (concurrent.futures.ThreadPoolExecutor, "submit", around(concurrent.futures.ThreadPoolExecutor.submit)),
+        (threading, "Lock", lambda: _PerturbedLock(lock_factory(), pause)),
+        (threading, "RLock", lambda *args, **kwargs: _PerturbedLock(rlock_factory(*args, **kwargs), pause)),
+    ]
+    saved = [(owner, name, getattr(owner, name)) for owner, name, _ in patches]
+    for owner, name, replacement in patches:
+        setattr(owner, name, replacement)
+    try:
+        yield
+    finally:
+        for owner, name, original in saved:
+            setattr(owner, name, original)
+
+
+def read_request() -> str:
+    """Read the request from the orchestrator's socket when it names one, else from stdin."""
+    global _BRIDGE_SOCKET
+    socket_path = os.environ.get(BRIDGE_SOCKET_ENV)
+    if not socket_path:
+        return sys.stdin.read()
+    _BRIDGE_SOCKET = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+    _BRIDGE_SOCKET.connect(socket_path)
+    chunks = []
+    while chunk := _BRIDGE_SOCKET.recv(65536):
+        chunks.append(chunk)
+    return b"".join(chunks).decode("utf-8")
+
+
+def write_response(payload: Dict[str, Any], options: Optional[Dict[str, Any]]) -> None:
+    """Send the final response, gzip-compressed when the orchestrator accepts it."""
+    accepted = str((options or {}).get("accept_encoding", "")).split(",")
+    if "gzip" in (encoding.strip() for encoding in accepted):
+        body = f"{GZIP_RESPONSE_MARKER}\n".encode("utf-8") + gzip.compress(json.dumps(payload).encode("utf-8"))
+    else:
+        body = (json.dumps(payload, indent=2) + "\n").encode("utf-8")
+
+    if _BRIDGE_SOCKET is not None:
+        _BRIDGE_SOCKET.sendall(body)
+        _BRIDGE_SOCKET.close()
+        return
+    sys.stdout.flush()
+    sys.stdout.buffer.write(body)
+    sys.stdout.buffer.flush()
+
+
+def read_frame(stream) -> Optional[Dict[str, Any]]:
+    """Read one `GRAPHENE_FRAME <length>` message from a binary stream; None at its end."""
+    while True:
+        header = stream.readline()
+        if not header:
+            return None
+        header = header.decode("utf-8", "replace").strip()
+        if header.startswith(FRAME_MARKER):
+            break
+    payload = stream.read(int(header[len(FRAME_MARKER):].strip()))
+    return json.loads(payload.decode("utf-8"))
+
+
+def write_frame(message: Dict[str, Any]) -> None:
+    """Write one framed message to the real stdout, on a line of its own."""
+    body = json.dumps(message, default=str).encode("utf-8")
+    sys.__stdout__.flush()
+    out = sys.__stdout__.buffer
+    out.write(f"\n{FRAME_MARKER} {len(body)}\n".encode("utf-8") + body)
+    out.flush()
+
+
+def serve_daemon() -> None:
+    """Answer framed requests until a shutdown message or the end of stdin."""
+    write_frame({"type": "ready", "protocol": DAEMON_PROTOCOL_VERSION})
+    heartbeat_started = False
+    while True:
+        message = read_frame(sys.stdin.buffer)
+        if message is None or message.get("type") == "shutdown":
+            return
+        if message.get("type") != "analyze":
+            continue
+        request = message.get("request") or {}
+        if not heartbeat_started:
+            start_heartbeat(request.get("options"))
+            heartbeat_started = True
+        write_frame({"type": "response", "id": message.get("id"), "response": analyze_isolated(request)})
+
+
+def emit_result(result: Dict[str, Any]) -> None:
+    """Stream a finished execution result so it survives a later bridge crash."""
+    try:
+        line = json.dumps(result, default=str)
+        with _STDERR_LOCK:
+            sys.__stderr__.write(f"{RESULT_PREFIX} {line}\n")
+            sys.__stderr__.flush()
+    except Exception:
+        pass
+
 
 def parse_target(target: str) -> Tuple[str, str]:
     """Parse target format: module:function or file.py:function."""
@@ -53,7 +473,17 @@ def parse_target(target: str) -> Tuple[str, str]:
     return module_part, func_name
 
 
-def load_function_from_target(target: str):
+def parse_constructor_args(options: Optional[Dict[str, Any]]) -> List[Any]:
+    value = (options or {}).get("constructor_args")
+    if value is None:
+        return []
+    args = json.loads(value) if isinstance(value, str) else value
+    if not isinstance(args, list):
+        raise ValueError("constructor_args must be a JSON array")
+    return args
+
+
+def load_function_from_target(target: str, options: Optional[Dict[str, Any]] = None):
     module_part, func_name = parse_target(target)
     try:
         if module_part.endswith(".py"):
@@ -75,12 +505,44 @@ def load_function_from_target(target: str):
     except (FileNotFoundError, ImportError, SyntaxError) as e:
         raise ValueError(f"Failed to load module '{module_part}': {str(e)}")
     
+    owner_name, _, method_name = func_name.rpartition(".")
+    if owner_name:
+        return load_method(module, owner_name, method_name, options)
     if not hasattr(module, func_name):
         available = [n for n in dir(module) if not n.startswith("_")]
         raise AttributeError(f"Function '{func_name}' not found in module (available: {', '.join(available[:5])}{'...' if len(available) > 5 else ''})")
     return getattr(module, func_name)
 
 
+def load_method(module: Any, owner_name: str, method_name: str, options: Optional[Dict[str, Any]]):
+    """Method `method_name` of class `owner_name` (dotted for nested classes).
+
+    Static and class methods are taken from the class. Instance methods run on a fresh
+    instance per execution, built from the constructor_args option.
+    """
+    owner = module
+    for part in owner_name.split("."):
+        if not hasattr(owner, part):
+            raise AttributeError(f"Class '{owner_name}' not found in module")
+        owner = getattr(owner, part)
+    if not inspect.isclass(owner):
+        raise AttributeError(f"'{owner_name}' is not a class")
+    try:
+        attribute = inspect.getattr_static(owner, method_name)
+    except AttributeError:
+        available = [n for n in dir(owner) if not n.startswith("_")]
+        raise AttributeError(f"Method '{method_name}' not found in class '{owner_name}' (available: {', '.join(available[:5])}{'...' if len(available) > 5 else ''})")
+    if isinstance(attribute, (staticmethod, classmethod)):
+        return getattr(owner, method_name)
+    args = parse_constructor_args(options)
+
+    @functools.wraps(getattr(owner, method_name))
+    def call_on_new_instance(*call_args, **call_kwargs):
+        return getattr(owner(*args), method_name)(*call_args, **call_kwargs)
+
+    return call_on_new_instance
+
+
 def resolve_source_file(target: str, func: Any) -> str:
     """Resolve source file path for static analysis."""
     module_part, _ = parse_target(target)
@@ -310,13 +772,16 @@ def analyze(request: dict) -> dict:
     if not target:
         return _error_response("python", "Missing required field: 'target'", session_id)
     
-    inputs = request.get("inputs", [])
+    inputs = request_inputs(request)
     if not inputs:
         # Run one dynamic probe with no positional input when the CLI omits --input.
-        inputs = [None]
+        inputs = [(None, None)]
     repeat = request.get("repeat", 1)
     timeout_seconds = request.get("timeout_seconds", 30.0)
     analysis_mode = request.get("analysis_mode", "dynamic")
+    chaos_seed = parse_chaos_seed(request.get("options"))
+    observe_seconds = parse_observe_seconds(request.get("options"))
+    detection = parse_detection(request.get("options"))
 
     try:
         _, function_name = parse_target(target)
@@ -324,7 +789,7 @@ def analyze(request: dict) -> dict:
         return _error_response("python", str(e), session_id, analysis_mode)
     
     try:
-        func = load_function_from_target(target)
+        func = load_function_from_target(target, request.get("options"))
     except (ValueError, FileNotFoundError, ModuleNotFoundError, AttributeError) as e:
         return _error_response("python", f"Target loading failed: {str(e)}", session_id, analysis_mode)
     except Exception as e:
@@ -336,16 +801,33 @@ def analyze(request: dict) -> dict:
     tracemalloc.start(25)
 
     try:
-        for input_data in inputs:
-            for _ in range(repeat):
+        for input_data, argument in inputs:
+            for repetition in range(repeat):
                 gc.collect()
+                rss_before = resident_memory()
                 before_snapshot = tracemalloc.take_snapshot()
 
+                baseline_threads = {thread.ident for thread in threading.enumerate()}
+                baseline_descriptors = open_descriptors() if detection["detect_fds"] else None
                 start_time = time.time()
-                result = harness.run_test(input_data)
-                execution_time_ms = int((time.time() - start_time) * 1000)
+                with schedule_perturbation(chaos_seed, input_data, repetition):
+                    result = harness.run_test(argument)
+                returned_at = time.time()
+                if detection["settle_seconds"] > 0:
+                    time.sleep(detection["settle_seconds"])
+                # Observed before the heap snapshots so short-lived stragglers are still seen
+                # and their lifetimes are not inflated by the snapshot time.
+                threads = observe_threads(
+                    threads_left_running(baseline_threads),
+                    returned_at,
+                    observe_seconds,
+                    detection["capture_stacks"],
+                )
+                execution_time_ms = int((returned_at - start_time) * 1000)
+                file_descriptors = descriptors_left_open(baseline_descriptors)
 
                 gc.collect()
+                rss_after = resident_memory()
                 after_snapshot = tracemalloc.take_snapshot()
                 current_bytes, peak_bytes = tracemalloc.get_traced_memory()
                 total_growth_bytes, matched_allocation_count, allocations = collect_heap_trace(
@@ -363,7 +845,18 @@ def analyze(request: dict) -> dict:
                     int(peak_bytes),
                 )
 
-                escape_detected = bool(total_growth_bytes > 0 or matched_allocation_count > 0)
+                escape_detected = bool(
+                    total_growth_bytes > 0
+                    or matched_allocation_count > 0
+                    or len(threads) >= detection["thread_threshold"]
+                    or file_descriptors
+                )
+                escape_details = heap_details if escape_detected else empty_escape_details()
+                escape_details["threads"] = threads
+                if file_descriptors is not None:
+                    escape_details["file_descriptors"] = file_descriptors
+                if rss_before is not None and rss_after is not None:
+                    escape_details["memory"] = {"rss_before_bytes": rss_before, "rss_after_bytes": rss_after}
 
                 all_results.append({
                     "input_data": input_data,
@@ -373,12 +866,15 @@ def analyze(request: dict) -> dict:
                     "error": result.error,
                     "execution_time_ms": execution_time_ms,
                     "escape_detected": escape_detected,
-                    "escape_details": heap_details if escape_detected else empty_escape_details(),
+                    "escape_details": escape_details,
+                    "stdout": getattr(result, "stdout", ""),
+                    "stderr": getattr(result, "stderr", ""),
                     "heap_growth_bytes": int(total_growth_bytes),
                     "heap_current_bytes": int(current_bytes),
                     "heap_peak_bytes": int(peak_bytes),
                     "heap_summary": heap_summary,
                 })
+                emit_result(all_results[-1])
     finally:
         tracemalloc.stop()
     
@@ -410,7 +906,7 @@ def analyze(request: dict) -> dict:
         }
         for v in analysis["vulnerabilities"]
     ]
-    return {
+    response = {
         "session_id": session_id,
         "language": "python",
         "analyzer_version": "1.0.0",
@@ -427,11 +923,38 @@ def analyze(request: dict) -> dict:
             "crash_rate": analysis["crash_rate"],
         },
     }
+    if chaos_seed is not None:
+        response["chaos_seed"] = chaos_seed
+    warnings = option_warnings(request.get("options"))
+    if warnings:
+        response["warnings"] = warnings
+    return response
+
+
+def analyze_isolated(request: dict) -> dict:
+    """Analyze one request of a batch; a failure only affects that request's response."""
+    try:
+        return analyze(request)
+    except Exception as e:
+        return _error_response(
+            "python",
+            f"Bridge critical error: {type(e).__name__}: {str(e)}",
+            request.get("session_id", "unknown"),
+            request.get("analysis_mode", "dynamic"),
+        )
 
 
 def main():
     try:
-        input_data = sys.stdin.read()
+        if os.environ.get(HANDSHAKE_ENV) == "1":
+            print(json.dumps({"protocol_version": PROTOCOL_VERSION, "analyzer_version": "1.0.0", "capabilities": CAPABILITIES}))
+            sys.exit(0)
+
+        if os.environ.get(DAEMON_ENV) == "1":
+            serve_daemon()
+            sys.exit(0)
+
+        input_data = read_request()
         if not input_data.strip():
             error_msg = _error_response("python", "Empty input: expected JSON request on stdin")
             print(json.dumps(error_msg), file=sys.stderr)
@@ -444,8 +967,16 @@ def main():
             print(json.dumps(error_msg), file=sys.stderr)
             sys.exit(1)
         
+        if isinstance(request.get("requests"), list):
+            batch = request["requests"]
+            options = batch[0].get("options") if batch else None
+            start_heartbeat(options)
+            write_response({"responses": [analyze_isolated(r) for r in batch]}, options)
+            sys.exit(0)
+
+        start_heartbeat(request.get("options"))
         result = analyze(request)
-        print(json.dumps(result, indent=2))
+        write_response(result, request.get("options"))
         sys.exit(0 if "error" not in result else 1)
     except BrokenPipeError:
         sys.exit(0)
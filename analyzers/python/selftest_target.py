@@ -0,0 +1,11 @@
+"""Self-test target for the deep health check.
+
+Retains a buffer in module scope so every dynamic run must report an escape.
+"""
+
+RETAINED = []
+
+
+def retain_buffer(input_data):
+    RETAINED.append(bytearray(256 * 1024) + str(input_data).encode())
+    return 'ok'
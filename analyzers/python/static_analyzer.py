@@ -22,6 +22,8 @@ class EscapeInfo:
     reason: str
     confidence: str
     code_snippet: Optional[str] = None
+    # Catalog id of the rule reporting it (`graphene-ha rules list`)
+    rule_id: Optional[str] = None
 
 
 class ObjectEscapeAnalyzer(ast.NodeVisitor):
@@ -39,7 +41,11 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
     def __init__(self, source_code: str, target_function: str, source_file: str = ""):
         self.source_code = source_code
         self.source_lines = source_code.split('\n')
+        # `function`, or `Class.method` for a method of a (possibly nested) class.
         self.target_function = target_function
+        self.target_name = target_function.rsplit('.', 1)[-1]
+        # Classes enclosing the definitions being visited, outermost first.
+        self.class_path: List[str] = []
         self.source_file = source_file
         self.escapes: List[EscapeInfo] = []
         self.current_function: Optional[str] = None
@@ -60,9 +66,15 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
         self.import_froms: Dict[str, Tuple[str, str]] = {}  # name -> (module, orig_name)
         # Coarse summaries: function_name -> whether passing an object can escape.
         self.function_escape_summaries: Dict[str, bool] = {}
+        # Project decorators that run the wrapped function in a thread/executor:
+        # decorator name -> what it spawns. Functions decorated with them:
+        # function name -> decorator name.
+        self.spawning_decorators: Dict[str, str] = {}
+        self.decorated_functions: Dict[str, str] = {}
         # Scan imports first
         self._scan_imports()
         self._build_function_escape_summaries()
+        self._scan_spawning_decorators()
         self.join_in_all_paths: Set[str] = set()
         self.join_in_some_paths: Set[str] = set()
         self.reassigned_vars: Set[str] = set()
@@ -83,6 +95,12 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
             ),
         )
         
+    def visit_ClassDef(self, node: ast.ClassDef):
+        """Visit class bodies, tracking the class for `Class.method` targets."""
+        self.class_path.append(node.name)
+        self.generic_visit(node)
+        self.class_path.pop()
+
     def visit_FunctionDef(self, node: ast.FunctionDef):
         """Visit function definitions."""
         previous_function = self.current_function
@@ -94,7 +112,14 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
         previous_joined = self.join_in_all_paths.copy() if hasattr(self, 'join_in_all_paths') else set()
         
         self.current_function = node.name
-        self.in_target_function = (node.name == self.target_function)
+        if '.' in self.target_function:
+            qualified = '.'.join(self.class_path + [node.name])
+            self.in_target_function = (qualified == self.target_function)
+        else:
+            self.in_target_function = (node.name == self.target_function)
+        # Classes defined inside a function are not reachable as targets.
+        previous_classes = self.class_path
+        self.class_path = []
         self.local_vars = set(arg.arg for arg in node.args.args)
         self.nonlocal_vars = set()
         self.global_vars = set()
@@ -120,6 +145,7 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
                 self.visit(stmt)
         
         # Restore context
+        self.class_path = previous_classes
         self.current_function = previous_function
         self.in_target_function = previous_in_target
         self.local_vars = previous_locals
@@ -144,7 +170,8 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
                     variable_name="<return value>",
                     reason=f"Returned value from function with unjoined concurrency",
                     confidence="high",
-                    code_snippet=self._get_code_snippet(node.lineno)
+                    code_snippet=self._get_code_snippet(node.lineno),
+                    rule_id="GH-PY-003"
                 ))
                 self.generic_visit(node)
                 return
@@ -160,7 +187,8 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
                     variable_name=var,
                     reason=f"Object '{var}' returned from function",
                     confidence="high",
-                    code_snippet=self._get_code_snippet(node.lineno)
+                    code_snippet=self._get_code_snippet(node.lineno),
+                    rule_id="GH-PY-005"
                 ))
         
         self.generic_visit(node)
@@ -177,7 +205,8 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
                     variable_name=name,
                     reason=f"Variable '{name}' declared as global",
                     confidence="high",
-                    code_snippet=self._get_code_snippet(node.lineno)
+                    code_snippet=self._get_code_snippet(node.lineno),
+                    rule_id="GH-PY-006"
                 ))
     
     def visit_Nonlocal(self, node: ast.Nonlocal):
@@ -192,7 +221,8 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
                     variable_name=name,
                     reason=f"Variable '{name}' captured from outer scope",
                     confidence="high",
-                    code_snippet=self._get_code_snippet(node.lineno)
+                    code_snippet=self._get_code_snippet(node.lineno),
+                    rule_id="GH-PY-008"
                 ))
     
     def visit_Assign(self, node: ast.Assign):
@@ -217,7 +247,8 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
                                 variable_name=var,
                                 reason=f"Object '{var}' stored in global/module container '{container}'",
                                 confidence="high",
-                                code_snippet=self._get_code_snippet(node.lineno)
+                                code_snippet=self._get_code_snippet(node.lineno),
+                                rule_id="GH-PY-007"
                             ))
         
         # Add assigned variables to local_vars
@@ -257,7 +288,8 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
                                 variable_name=var_name,
                                 reason=f"Variable '{var_name}' assigned from function with unjoined concurrency",
                                 confidence="high",
-                                code_snippet=self._get_code_snippet(node.lineno)
+                                code_snippet=self._get_code_snippet(node.lineno),
+                                rule_id="GH-PY-004"
                             ))
         elif isinstance(node.value, ast.ListComp):
             # Check if list comprehension creates concurrency objects
@@ -292,6 +324,24 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
         elif isinstance(node.func, ast.Attribute):
             # Imported module calls are handled through existing cross-file logic.
             callee_may_escape = self._check_function_call_escapes(node)
+
+        decorated = self._decorated_callee(node)
+        if decorated:
+            callee, decorator = decorated
+            callee_may_escape = True
+            self.escapes.append(EscapeInfo(
+                escape_type="concurrency",
+                line=node.lineno,
+                column=node.col_offset,
+                variable_name=callee,
+                reason=(
+                    f"Call to '{callee}' runs in a background {self.spawning_decorators[decorator]} "
+                    f"via decorator @{decorator}"
+                ),
+                confidence="high",
+                code_snippet=self._get_code_snippet(node.lineno),
+                rule_id="GH-PY-002"
+            ))
         
         # Check for tracked objects passed as arguments (parameter escape).
         for arg in node.args:
@@ -305,7 +355,8 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
                         variable_name=var,
                         reason=f"Object '{var}' passed as parameter",
                         confidence="high",
-                        code_snippet=self._get_code_snippet(node.lineno)
+                        code_snippet=self._get_code_snippet(node.lineno),
+                        rule_id="GH-PY-009"
                     ))
         
         # Check keyword arguments
@@ -320,7 +371,8 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
                         variable_name=var,
                         reason=f"Object '{var}' passed as keyword argument",
                         confidence="high",
-                        code_snippet=self._get_code_snippet(node.lineno)
+                        code_snippet=self._get_code_snippet(node.lineno),
+                        rule_id="GH-PY-009"
                     ))
         
         self.generic_visit(node)
@@ -343,7 +395,8 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
                     variable_name=var,
                     reason=f"Object '{var}' captured in lambda/closure",
                     confidence="high",
-                    code_snippet=self._get_code_snippet(node.lineno)
+                    code_snippet=self._get_code_snippet(node.lineno),
+                    rule_id="GH-PY-010"
                 ))
         
         self.generic_visit(node)
@@ -515,6 +568,119 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
 
             self.function_escape_summaries[node.name] = escapes
     
+    def _scan_spawning_decorators(self):
+        """Resolve decorators defined in this project that run the wrapped function
+        in a thread, process or executor, and the functions decorated with them."""
+        try:
+            tree = ast.parse(self.source_code)
+        except Exception:
+            return
+
+        # Decorators imported from sibling modules (`from helpers import run_in_thread`).
+        for name, (module, original) in self.import_froms.items():
+            module_tree = self._parse_project_module(module)
+            if module_tree is None:
+                continue
+            module_decorators = self._find_spawning_decorators(module_tree)
+            if original in module_decorators:
+                self.spawning_decorators[name] = module_decorators[original]
+            # Functions decorated over there and imported here.
+            for function, decorator in self._find_decorated_functions(module_tree, module_decorators).items():
+                if function == original:
+                    self.decorated_functions[name] = decorator
+                    self.spawning_decorators.setdefault(decorator, module_decorators[decorator])
+
+        self.spawning_decorators.update(self._find_spawning_decorators(tree))
+        self.decorated_functions.update(self._find_decorated_functions(tree, self.spawning_decorators))
+
+    def _parse_project_module(self, module: str) -> Optional[ast.AST]:
+        """Parse a module that lives next to the analyzed file, if there is one."""
+        if not self.source_file or not module:
+            return None
+        source_dir = Path(self.source_file).parent
+        relative = module.lstrip('.').replace('.', '/')
+        for path in (source_dir / f"{relative}.py", source_dir / relative / "__init__.py"):
+            try:
+                if path.exists():
+                    return ast.parse(path.read_text())
+            except Exception:
+                return None
+        return None
+
+    def _find_spawning_decorators(self, tree: ast.AST) -> Dict[str, str]:
+        """Functions and methods that define a nested wrapper and start a thread,
+        process or executor task; decorator factories (`@run_in_thread(daemon=True)`)
+        are covered because the spawn still sits below a nested function."""
+        decorators: Dict[str, str] = {}
+        for node in ast.walk(tree):
+            if not isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                continue
+            nested = [child for child in ast.walk(node)
+                      if child is not node and isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef))]
+            if not nested:
+                continue
+            for wrapper in nested:
+                spawned = self._spawned_kind(wrapper)
+                if spawned:
+                    decorators[node.name] = spawned
+                    break
+        return decorators
+
+    def _spawned_kind(self, node: ast.AST) -> Optional[str]:
+        """What a function body starts in the background, if anything."""
+        for child in ast.walk(node):
+            if not isinstance(child, ast.Call):
+                continue
+            concurrency_type = self._is_concurrency_call(child)
+            if concurrency_type:
+                return "thread" if concurrency_type in ("Thread", "Timer") else concurrency_type
+            if isinstance(child.func, ast.Attribute) and child.func.attr in (
+                'submit', 'run_in_executor', 'apply_async', 'start_new_thread', 'create_task'
+            ):
+                return "task"
+        return None
+
+    def _find_decorated_functions(self, tree: ast.AST, decorators: Dict[str, str]) -> Dict[str, str]:
+        """Functions (and methods) carrying one of the given decorators."""
+        decorated: Dict[str, str] = {}
+        for node in ast.walk(tree):
+            if not isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                continue
+            for decorator in node.decorator_list:
+                # `@run_in_thread`, `@run_in_thread(...)`, `@helpers.run_in_thread`, `@app.background`
+                if isinstance(decorator, ast.Call):
+                    decorator = decorator.func
+                name = None
+                if isinstance(decorator, ast.Name):
+                    name = decorator.id
+                elif isinstance(decorator, ast.Attribute):
+                    name = decorator.attr
+                if name in decorators:
+                    decorated[node.name] = name
+                    break
+        return decorated
+
+    def _decorated_callee(self, node: ast.Call) -> Optional[Tuple[str, str]]:
+        """(function, decorator) when the call targets a function whose decorator
+        runs it in the background."""
+        name = None
+        if isinstance(node.func, ast.Name):
+            name = node.func.id
+        elif isinstance(node.func, ast.Attribute):
+            name = node.func.attr
+            # `tasks.process()` on an imported project module
+            if isinstance(node.func.value, ast.Name) and node.func.value.id in self.imports:
+                module_tree = self._parse_project_module(self.imports[node.func.value.id])
+                if module_tree is not None:
+                    module_decorators = self._find_spawning_decorators(module_tree)
+                    decorator = self._find_decorated_functions(module_tree, module_decorators).get(name)
+                    if decorator:
+                        self.spawning_decorators.setdefault(decorator, module_decorators[decorator])
+                        return name, decorator
+        if name and name != self.target_name and name in self.decorated_functions:
+            return name, self.decorated_functions[name]
+        return None
+
     def _resolve_imported_function(self, func_ref: str) -> Optional[str]:
         """Try to resolve an imported function to its source file."""
         # Check if it's a module.function reference
@@ -605,7 +771,8 @@ class ObjectEscapeAnalyzer(ast.NodeVisitor):
                     variable_name=var_name,
                     reason=reason,
                     confidence=confidence,
-                    code_snippet=self._get_code_snippet(line)
+                    code_snippet=self._get_code_snippet(line),
+                    rule_id="GH-PY-001"
                 ))
 
 
@@ -0,0 +1,163 @@
+//! Per-execution stdout/stderr capture for in-process targets.
+//!
+//! At startup the bridge keeps duplicates of the original stdout and stderr for the
+//! protocol (response, heartbeats, streamed results) and points fd 1 at stderr, so a
+//! target printing outside an execution, e.g. a timed-out call still running, cannot
+//! corrupt the response. During an execution fd 1 and 2 go to temporary files.
+
+use std::io::{self, Write};
+
+#[cfg(unix)]
+mod imp {
+    use std::fs::File;
+    use std::io::{Read, Seek, SeekFrom, Write};
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+    use std::sync::{Mutex, OnceLock};
+
+    struct ProtocolStreams {
+        stdout: File,
+        stderr: Mutex<File>,
+    }
+
+    static STREAMS: OnceLock<ProtocolStreams> = OnceLock::new();
+
+    pub fn init() {
+        // SAFETY: dup/dup2 on the process's own standard descriptors; the duplicates
+        // are owned by the `File`s below.
+        unsafe {
+            let stdout = libc::dup(1);
+            let stderr = libc::dup(2);
+            if stdout < 0 || stderr < 0 {
+                return;
+            }
+            libc::dup2(2, 1);
+            let _ = STREAMS.set(ProtocolStreams {
+                stdout: File::from_raw_fd(stdout),
+                stderr: Mutex::new(File::from_raw_fd(stderr)),
+            });
+        }
+    }
+
+    pub fn protocol_stdout() -> Option<File> {
+        STREAMS.get()?.stdout.try_clone().ok()
+    }
+
+    pub fn stderr_line(line: &str) -> bool {
+        let Some(streams) = STREAMS.get() else {
+            return false;
+        };
+        let mut stderr = streams.stderr.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = writeln!(stderr, "{}", line);
+        true
+    }
+
+    pub struct Capture {
+        stdout: File,
+        stderr: File,
+    }
+
+    impl Capture {
+        pub fn start() -> Option<Self> {
+            STREAMS.get()?;
+            let stdout = scratch_file()?;
+            let stderr = scratch_file()?;
+            // SAFETY: repoints the standard descriptors at files this capture owns.
+            unsafe {
+                libc::dup2(stdout.as_raw_fd(), 1);
+                libc::dup2(stderr.as_raw_fd(), 2);
+            }
+            Some(Self { stdout, stderr })
+        }
+
+        pub fn finish(mut self) -> (String, String) {
+            if let Some(streams) = STREAMS.get() {
+                let original = streams.stderr.lock().unwrap_or_else(|e| e.into_inner());
+                // SAFETY: points fd 1 and 2 back at the saved stderr.
+                unsafe {
+                    libc::dup2(original.as_raw_fd(), 1);
+                    libc::dup2(original.as_raw_fd(), 2);
+                }
+            }
+            (read_all(&mut self.stdout), read_all(&mut self.stderr))
+        }
+    }
+
+    fn scratch_file() -> Option<File> {
+        let path = std::env::temp_dir().join(format!(
+            "graphene-rust-capture-{}-{}",
+            std::process::id(),
+            unique_suffix()
+        ));
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .ok()?;
+        // Unlinked right away; the descriptor keeps the data until dropped.
+        let _ = std::fs::remove_file(&path);
+        Some(file)
+    }
+
+    fn unique_suffix() -> u128 {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        nanos ^ COUNTER.fetch_add(1, Ordering::Relaxed) as u128
+    }
+
+    fn read_all(file: &mut File) -> String {
+        let mut bytes = Vec::new();
+        if file.seek(SeekFrom::Start(0)).is_ok() {
+            let _ = file.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    pub fn init() {}
+
+    pub fn protocol_stdout() -> Option<std::fs::File> {
+        None
+    }
+
+    pub fn stderr_line(_line: &str) -> bool {
+        false
+    }
+
+    /// Output is not captured on this platform; targets write to the bridge's streams.
+    pub struct Capture;
+
+    impl Capture {
+        pub fn start() -> Option<Self> {
+            None
+        }
+
+        pub fn finish(self) -> (String, String) {
+            (String::new(), String::new())
+        }
+    }
+}
+
+pub use imp::{init, Capture};
+
+/// Writer for the final response: the original stdout.
+pub fn protocol_stdout() -> Box<dyn Write> {
+    match imp::protocol_stdout() {
+        Some(file) => Box::new(file),
+        None => Box::new(io::stdout()),
+    }
+}
+
+/// Writes a protocol line (heartbeat, streamed result) to the original stderr, which
+/// stays reachable while an execution's output is being captured.
+pub fn stderr_line(line: &str) {
+    if !imp::stderr_line(line) {
+        eprintln!("{}", line);
+    }
+}
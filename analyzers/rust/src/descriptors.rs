@@ -0,0 +1,100 @@
+//! Open file descriptors of the bridge process. A target runs in-process, so sockets,
+//! pipes and files it opens and never closes show up as descriptors that were not open
+//! before the execution.
+
+use std::collections::HashMap;
+
+/// One open descriptor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Descriptor {
+    /// `socket`, `pipe` or `file`
+    pub kind: String,
+    /// Path, or `socket:[inode]` / `pipe:[inode]` on Linux
+    pub target: String,
+}
+
+/// Descriptors open in this process, keyed by number; `None` where they cannot be
+/// listed. Runtime internals (epoll, eventfd and other anonymous inodes) are left out.
+#[cfg(target_os = "linux")]
+pub fn snapshot() -> Option<HashMap<u64, Descriptor>> {
+    // Numbers first, links after the directory is closed, so its own descriptor
+    // no longer resolves and drops out.
+    let fds: Vec<u64> = std::fs::read_dir("/proc/self/fd")
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+        .collect();
+
+    let mut descriptors = HashMap::new();
+    for fd in fds {
+        let Ok(link) = std::fs::read_link(format!("/proc/self/fd/{}", fd)) else {
+            continue;
+        };
+        let target = link.to_string_lossy().into_owned();
+        let kind = if target.starts_with("socket:") {
+            "socket"
+        } else if target.starts_with("pipe:") {
+            "pipe"
+        } else if target.starts_with('/') {
+            "file"
+        } else {
+            continue;
+        };
+        descriptors.insert(fd, Descriptor { kind: kind.to_string(), target });
+    }
+    Some(descriptors)
+}
+
+/// libproc: `PROC_PIDLISTFDS` gives each descriptor's type; `F_GETPATH` names files.
+#[cfg(target_os = "macos")]
+pub fn snapshot() -> Option<HashMap<u64, Descriptor>> {
+    use std::ffi::CStr;
+    use std::mem::size_of;
+
+    const MAX_FDS: usize = 4096;
+
+    let mut fds = vec![libc::proc_fdinfo { proc_fd: 0, proc_fdtype: 0 }; MAX_FDS];
+    // SAFETY: the buffer holds MAX_FDS entries and its size is passed in bytes.
+    let written = unsafe {
+        libc::proc_pidinfo(
+            std::process::id() as libc::c_int,
+            libc::PROC_PIDLISTFDS,
+            0,
+            fds.as_mut_ptr().cast(),
+            (fds.len() * size_of::<libc::proc_fdinfo>()) as libc::c_int,
+        )
+    };
+    if written < 0 {
+        return None;
+    }
+    fds.truncate(written as usize / size_of::<libc::proc_fdinfo>());
+
+    let mut descriptors = HashMap::new();
+    for info in fds {
+        let kind = match info.proc_fdtype as libc::c_int {
+            libc::PROX_FDTYPE_SOCKET => "socket",
+            libc::PROX_FDTYPE_PIPE => "pipe",
+            libc::PROX_FDTYPE_VNODE => "file",
+            _ => continue,
+        };
+        let target = if kind == "file" {
+            let mut path = [0 as libc::c_char; 1024];
+            // SAFETY: F_GETPATH writes a NUL-terminated path of at most MAXPATHLEN
+            // (1024) bytes.
+            if unsafe { libc::fcntl(info.proc_fd, libc::F_GETPATH, path.as_mut_ptr()) } < 0 {
+                continue;
+            }
+            // SAFETY: NUL-terminated by the call above.
+            unsafe { CStr::from_ptr(path.as_ptr()) }.to_string_lossy().into_owned()
+        } else {
+            format!("{}:{}", kind, info.proc_fd)
+        };
+        descriptors.insert(info.proc_fd as u64, Descriptor { kind: kind.to_string(), target });
+    }
+    Some(descriptors)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn snapshot() -> Option<HashMap<u64, Descriptor>> {
+    None
+}
@@ -0,0 +1,408 @@
+//! Loads Rust targets as shared libraries so they run inside the bridge process, where
+//! thread and heap changes around a call can be observed.
+//!
+//! Workspace targets (`escape_tests_rust::module::function`) are wrapped in a generated
+//! cdylib exporting a C ABI shim. For a method (`escape_tests_rust::module::Type::method`)
+//! taking `self`, the shim builds the receiver with `Type::new(..)` from the
+//! `constructor_args` option, or with `Type::default()` when the option is absent.
+//! Prebuilt libraries (`path/libfoo.so:symbol`) must
+//! export the same ABI themselves:
+//!
+//! ```text
+//! extern "C" fn <symbol>(input: *const u8, len: usize, out: *mut GrapheneBuffer) -> i32  // 0 ok, 1 panicked
+//! extern "C" fn graphene_free(buffer: GrapheneBuffer)
+//! extern "C" fn graphene_allocation_snapshot(out: *mut usize)  // optional: [allocated, deallocated, peak]
+//! ```
+
+use libloading::Library;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+use crate::{find_workspace_root, parse_rust_target};
+
+const SHIM_SYMBOL: &str = "graphene_invoke";
+const FREE_SYMBOL: &[u8] = b"graphene_free";
+const SNAPSHOT_SYMBOL: &[u8] = b"graphene_allocation_snapshot";
+const LIBRARY_EXTENSIONS: [&str; 3] = [".so", ".dylib", ".dll"];
+/// Separates the arguments of a multiple-argument call in the shim's input (the ASCII
+/// unit separator).
+const ARGUMENT_SEPARATOR: char = '\u{1f}';
+
+/// Owned byte buffer handed across the shim boundary; freed by the library that
+/// allocated it.
+#[repr(C)]
+pub struct GrapheneBuffer {
+    ptr: *mut u8,
+    len: usize,
+    cap: usize,
+}
+
+type InvokeFn = unsafe extern "C" fn(*const u8, usize, *mut GrapheneBuffer) -> i32;
+type FreeFn = unsafe extern "C" fn(GrapheneBuffer);
+type SnapshotFn = unsafe extern "C" fn(*mut usize);
+
+/// Entry points resolved from a loaded target library.
+pub struct TargetLibrary {
+    invoke: InvokeFn,
+    free: FreeFn,
+    snapshot: Option<SnapshotFn>,
+}
+
+/// Result of one call through the shim.
+pub enum Invocation {
+    Returned(String),
+    Panicked(String),
+}
+
+impl TargetLibrary {
+    pub fn call(&self, input: &[u8]) -> Invocation {
+        let mut buffer = GrapheneBuffer { ptr: std::ptr::null_mut(), len: 0, cap: 0 };
+        // SAFETY: `invoke` follows the shim ABI documented above and fills `buffer`
+        // with a vector it owns, returned with `free`.
+        let status = unsafe { (self.invoke)(input.as_ptr(), input.len(), &mut buffer) };
+        let text = if buffer.ptr.is_null() {
+            String::new()
+        } else {
+            // SAFETY: ptr/len describe the initialized part of the library's buffer.
+            let bytes = unsafe { std::slice::from_raw_parts(buffer.ptr, buffer.len) };
+            String::from_utf8_lossy(bytes).into_owned()
+        };
+        // SAFETY: the buffer came from this library and is released once.
+        unsafe { (self.free)(buffer) };
+        if status == 0 {
+            Invocation::Returned(text)
+        } else {
+            Invocation::Panicked(text)
+        }
+    }
+
+    /// Bytes allocated, deallocated and peak in use by the library's allocator, when it
+    /// exports counters.
+    pub fn allocations(&self) -> Option<(usize, usize, usize)> {
+        let snapshot = self.snapshot?;
+        let mut counters = [0usize; 3];
+        // SAFETY: the snapshot symbol writes exactly three counters.
+        unsafe { snapshot(counters.as_mut_ptr()) };
+        Some((counters[0], counters[1], counters[2]))
+    }
+}
+
+/// `path/libfoo.so:symbol` targets naming a prebuilt library.
+pub fn prebuilt_target(target: &str) -> Option<(&str, &str)> {
+    let (path, symbol) = target.rsplit_once(':')?;
+    LIBRARY_EXTENSIONS
+        .iter()
+        .any(|extension| path.ends_with(extension))
+        .then_some((path, symbol))
+}
+
+/// Builds (or locates) the target's library and loads it. The returned directory holds
+/// build artifacts to remove after the run.
+pub fn load_target(
+    target: &str,
+    constructor_args: Option<&str>,
+    arity: Option<usize>,
+) -> anyhow::Result<(TargetLibrary, Option<PathBuf>)> {
+    if let Some((path, symbol)) = prebuilt_target(target) {
+        if !Path::new(path).exists() {
+            anyhow::bail!("Prebuilt target library not found at '{}'", path);
+        }
+        return Ok((open(Path::new(path), symbol)?, None));
+    }
+    let (library_path, build_dir) = build_target_library(target, constructor_args, arity)?;
+    match open(&library_path, SHIM_SYMBOL) {
+        Ok(library) => Ok((library, Some(build_dir))),
+        Err(error) => {
+            let _ = fs::remove_dir_all(&build_dir);
+            Err(error)
+        }
+    }
+}
+
+fn open(path: &Path, symbol: &str) -> anyhow::Result<TargetLibrary> {
+    // SAFETY: loading runs the library's initializers; targets are code the user asked
+    // to execute anyway.
+    let library = unsafe { Library::new(path) }
+        .map_err(|e| anyhow::anyhow!("Failed to load '{}': {}", path.display(), e))?;
+    // SAFETY: symbol types follow the shim ABI; a library exporting these names with
+    // other signatures is a target error.
+    let library_fns = unsafe {
+        let invoke = *library
+            .get::<InvokeFn>(symbol.as_bytes())
+            .map_err(|e| anyhow::anyhow!("Symbol '{}' not found in '{}': {}", symbol, path.display(), e))?;
+        let free = *library
+            .get::<FreeFn>(FREE_SYMBOL)
+            .map_err(|e| anyhow::anyhow!("Symbol 'graphene_free' not found in '{}': {}", path.display(), e))?;
+        let snapshot = library.get::<SnapshotFn>(SNAPSHOT_SYMBOL).ok().map(|s| *s);
+        TargetLibrary { invoke, free, snapshot }
+    };
+    // Threads a target leaves behind keep running library code, so it is never unloaded.
+    std::mem::forget(library);
+    Ok(library_fns)
+}
+
+fn build_target_library(
+    target: &str,
+    constructor_args: Option<&str>,
+    arity: Option<usize>,
+) -> anyhow::Result<(PathBuf, PathBuf)> {
+    let (crate_name, module_path, function_name) = parse_rust_target(target)?;
+    let workspace_root = find_workspace_root()?;
+    let tests_rust_dir = workspace_root.join("tests").join("rust");
+
+    if crate_name != "escape_tests_rust" {
+        anyhow::bail!(
+            "Unsupported Rust crate '{}'. Expected 'escape_tests_rust' for this workspace target set, or a prebuilt library as path/libname.so:symbol.",
+            crate_name
+        );
+    }
+    if !tests_rust_dir.join("Cargo.toml").exists() {
+        anyhow::bail!(
+            "Rust test crate not found at '{}'",
+            tests_rust_dir.display()
+        );
+    }
+
+    let call = target_call(&tests_rust_dir, &module_path, &function_name, constructor_args, arity)?;
+
+    let nonce = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let temp_dir = env::temp_dir().join(format!(
+        "graphene-rust-target-{}-{}",
+        std::process::id(),
+        nonce
+    ));
+    fs::create_dir_all(temp_dir.join("src"))?;
+
+    let cargo_toml = format!(
+        "[package]\nname = \"graphene_rust_target\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[lib]\ncrate-type = [\"cdylib\"]\n\n[dependencies]\nescape_tests_rust = {{ package = \"escape-tests-rust\", path = \"{}\" }}\n",
+        tests_rust_dir.display().to_string().replace('\\', "\\\\")
+    );
+    fs::write(temp_dir.join("Cargo.toml"), cargo_toml)?;
+    fs::write(
+        temp_dir.join("src").join("lib.rs"),
+        SHIM_TEMPLATE.replace("{call}", &call),
+    )?;
+
+    // Shared so the test crate and its dependencies are compiled once, not per target.
+    let shared_target_dir = workspace_root.join("target").join("graphene-rust-targets");
+    let build = Command::new("cargo")
+        .arg("build")
+        .arg("--release")
+        .env("CARGO_TARGET_DIR", &shared_target_dir)
+        .current_dir(&temp_dir)
+        .output()?;
+    if !build.status.success() {
+        let stderr = String::from_utf8_lossy(&build.stderr).trim().to_string();
+        let stdout = String::from_utf8_lossy(&build.stdout).trim().to_string();
+        let detail = if !stderr.is_empty() { stderr } else { stdout };
+        let _ = fs::remove_dir_all(&temp_dir);
+        anyhow::bail!("Failed to build Rust target library: {}", detail);
+    }
+
+    let library_name = format!(
+        "{}graphene_rust_target{}",
+        env::consts::DLL_PREFIX,
+        env::consts::DLL_SUFFIX
+    );
+    let built = shared_target_dir.join("release").join(&library_name);
+    // Loaded from a copy with its own path: the next build overwrites the shared output,
+    // and loading the same path again would hand back the library already mapped.
+    let library_path = temp_dir.join(&library_name);
+    if let Err(error) = fs::copy(&built, &library_path) {
+        let _ = fs::remove_dir_all(&temp_dir);
+        anyhow::bail!(
+            "Rust target library was not produced at '{}': {}",
+            built.display(),
+            error
+        );
+    }
+
+    Ok((library_path, temp_dir))
+}
+
+/// Expression the shim evaluates with the `input` string: a call of the function, or of
+/// the method on a freshly built receiver when the target is a method taking `self`.
+/// With an `arity`, `input` holds that many arguments separated by
+/// `ARGUMENT_SEPARATOR`, each parsed into its parameter's type with `FromStr`.
+fn target_call(
+    tests_rust_dir: &Path,
+    module_path: &str,
+    function_name: &str,
+    constructor_args: Option<&str>,
+    arity: Option<usize>,
+) -> anyhow::Result<String> {
+    let (setup, arguments) = match arity {
+        Some(arity) => (
+            format!(
+                "let arguments: Vec<&str> = input.split('\\u{{{:x}}}').collect(); \
+                 assert_eq!(arguments.len(), {arity}, \"expected {arity} argument(s)\"); ",
+                ARGUMENT_SEPARATOR as u32
+            ),
+            (0..arity)
+                .map(|index| {
+                    format!(
+                        "arguments[{index}].parse().expect(\"argument {} does not parse as its parameter type\")",
+                        index + 1
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        None => (String::new(), "input".to_string()),
+    };
+    let path = format!("escape_tests_rust::{module_path}::{function_name}");
+    let Some((modules, type_name)) = module_path
+        .rsplit_once("::")
+        .filter(|(_, last)| last.starts_with(|c: char| c.is_ascii_uppercase()))
+    else {
+        return Ok(format!("{{ {setup}{path}({arguments}) }}"));
+    };
+    let module = modules.rsplit("::").next().unwrap_or(modules);
+    let source = [
+        tests_rust_dir.join("cases").join(format!("{module}.rs")),
+        tests_rust_dir.join(format!("{module}.rs")),
+    ]
+    .iter()
+    .find_map(|candidate| fs::read_to_string(candidate).ok())
+    .unwrap_or_default();
+    let receiver = regex::Regex::new(&format!(
+        r"fn\s+{}\s*(<[^>]*>)?\s*\(\s*(&\s*('\w+\s+)?)?(mut\s+)?self\b",
+        regex::escape(function_name)
+    ))?;
+    if !receiver.is_match(&source) {
+        return Ok(format!("{{ {setup}{path}({arguments}) }}"));
+    }
+
+    let type_path = format!("escape_tests_rust::{modules}::{type_name}");
+    let constructor = match constructor_args {
+        Some(args) => {
+            let args: Vec<serde_json::Value> = serde_json::from_str(args)
+                .map_err(|e| anyhow::anyhow!("constructor_args must be a JSON array: {}", e))?;
+            let args: Vec<String> = args.iter().map(rust_literal).collect::<anyhow::Result<_>>()?;
+            format!("{type_path}::new({})", args.join(", "))
+        }
+        None => format!("<{type_path} as Default>::default()"),
+    };
+    Ok(format!(
+        "{{ {setup}#[allow(unused_mut)] let mut receiver = {constructor}; receiver.{function_name}({arguments}) }}"
+    ))
+}
+
+/// Input bytes for an argument list: strings as they are, other values as their JSON
+/// text, separated by `ARGUMENT_SEPARATOR`. Prebuilt libraries receive the same bytes.
+pub fn encode_arguments(args: &[serde_json::Value]) -> Vec<u8> {
+    args.iter()
+        .map(|value| match value {
+            serde_json::Value::String(text) => text.clone(),
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(&ARGUMENT_SEPARATOR.to_string())
+        .into_bytes()
+}
+
+/// A constructor argument as Rust source. Strings are passed through `.into()` so they
+/// fit both `String` and `&str` parameters.
+fn rust_literal(value: &serde_json::Value) -> anyhow::Result<String> {
+    Ok(match value {
+        serde_json::Value::Bool(value) => value.to_string(),
+        serde_json::Value::Number(value) => value.to_string(),
+        serde_json::Value::String(value) => format!("{:?}.into()", value),
+        other => anyhow::bail!("Unsupported Rust constructor argument {}; use strings, numbers or booleans", other),
+    })
+}
+
+/// C ABI wrapper compiled around a workspace target. It counts the library's own heap
+/// traffic, since a cdylib has its own allocator the bridge cannot see.
+const SHIM_TEMPLATE: &str = r#"use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static DEALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK_IN_USE: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            let in_use = allocated.saturating_sub(DEALLOCATED.load(Ordering::Relaxed));
+            PEAK_IN_USE.fetch_max(in_use, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        DEALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[repr(C)]
+pub struct GrapheneBuffer {
+    ptr: *mut u8,
+    len: usize,
+    cap: usize,
+}
+
+impl From<String> for GrapheneBuffer {
+    fn from(text: String) -> Self {
+        let mut bytes = std::mem::ManuallyDrop::new(text.into_bytes());
+        GrapheneBuffer { ptr: bytes.as_mut_ptr(), len: bytes.len(), cap: bytes.capacity() }
+    }
+}
+
+/// # Safety
+/// `input` must point to `len` readable bytes and `out` to a writable buffer slot.
+#[no_mangle]
+pub unsafe extern "C" fn graphene_invoke(input: *const u8, len: usize, out: *mut GrapheneBuffer) -> i32 {
+    let input = String::from_utf8_lossy(std::slice::from_raw_parts(input, len)).into_owned();
+    let outcome = std::panic::catch_unwind(move || format!("{}", {call}));
+    // This library has its own std; flush its buffers while output is being captured.
+    let _ = std::io::stdout().flush();
+    let _ = std::io::stderr().flush();
+    let (status, text) = match outcome {
+        Ok(output) => (0, output),
+        Err(panic) => (
+            1,
+            panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "non-string panic payload".to_string()),
+        ),
+    };
+    out.write(GrapheneBuffer::from(text));
+    status
+}
+
+/// # Safety
+/// `buffer` must come from `graphene_invoke` and be freed once.
+#[no_mangle]
+pub unsafe extern "C" fn graphene_free(buffer: GrapheneBuffer) {
+    if !buffer.ptr.is_null() {
+        drop(Vec::from_raw_parts(buffer.ptr, buffer.len, buffer.cap));
+    }
+}
+
+/// # Safety
+/// `out` must point to three writable counters.
+#[no_mangle]
+pub unsafe extern "C" fn graphene_allocation_snapshot(out: *mut usize) {
+    out.write(ALLOCATED.load(Ordering::Relaxed));
+    out.add(1).write(DEALLOCATED.load(Ordering::Relaxed));
+    out.add(2).write(PEAK_IN_USE.load(Ordering::Relaxed));
+}
+"#;
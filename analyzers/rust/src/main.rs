@@ -1,156 +1,33 @@
+mod capture;
+mod descriptors;
+mod dylib;
+mod memory;
+mod processes;
+mod threads;
+mod timers;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde::{Deserialize, Serialize};
-use std::alloc::{GlobalAlloc, Layout, System};
-use std::collections::HashSet;
 use std::env;
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, BufRead, Read, Write};
 use std::path::PathBuf;
-use std::process::Command;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread;
 use std::time::SystemTime;
 use std::time::{Duration, Instant};
 
-#[cfg(target_os = "linux")]
-use procfs::process::Process;
-
-#[cfg(target_os = "windows")]
-use winapi::um::processthreadsapi::GetCurrentProcessId;
-#[cfg(target_os = "windows")]
-use winapi::um::tlhelp32::{CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32};
-#[cfg(target_os = "windows")]
-use winapi::shared::minwindef::FALSE;
-
-#[cfg(target_os = "macos")]
-use std::ffi::CStr;
-
-struct TrackingAllocator;
-
-static TOTAL_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
-static TOTAL_DEALLOCATED: AtomicUsize = AtomicUsize::new(0);
-static PEAK_IN_USE: AtomicUsize = AtomicUsize::new(0);
-
-#[global_allocator]
-static GLOBAL_ALLOCATOR: TrackingAllocator = TrackingAllocator;
-
-unsafe impl GlobalAlloc for TrackingAllocator {
-    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        let ptr = System.alloc(layout);
-        if !ptr.is_null() {
-            let size = layout.size();
-            TOTAL_ALLOCATED.fetch_add(size, Ordering::Relaxed);
-            update_peak();
-        }
-        ptr
-    }
-
-    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
-        TOTAL_DEALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
-        System.dealloc(ptr, layout);
-    }
-}
-
-fn update_peak() {
-    let in_use = bytes_in_use();
-    let mut current_peak = PEAK_IN_USE.load(Ordering::Relaxed);
-    while in_use > current_peak {
-        match PEAK_IN_USE.compare_exchange_weak(
-            current_peak,
-            in_use,
-            Ordering::Relaxed,
-            Ordering::Relaxed,
-        ) {
-            Ok(_) => break,
-            Err(observed) => current_peak = observed,
-        }
-    }
-}
-
-fn bytes_in_use() -> usize {
-    TOTAL_ALLOCATED
-        .load(Ordering::Relaxed)
-        .saturating_sub(TOTAL_DEALLOCATED.load(Ordering::Relaxed))
-}
-
-fn allocation_snapshot() -> (usize, usize, usize) {
-    (
-        TOTAL_ALLOCATED.load(Ordering::Relaxed),
-        TOTAL_DEALLOCATED.load(Ordering::Relaxed),
-        PEAK_IN_USE.load(Ordering::Relaxed),
-    )
-}
-
-// Platform-specific thread enumeration functions
-#[cfg(target_os = "linux")]
-fn get_thread_ids() -> HashSet<u32> {
-    let mut threads = HashSet::new();
-    if let Ok(me) = Process::myself() {
-        if let Ok(task_status) = me.tasks() {
-            for task in task_status {
-                if let Ok(t) = task {
-                    threads.insert(t.tid as u32);
-                }
-            }
-        }
-    }
-    threads
-}
-
-#[cfg(target_os = "windows")]
-fn get_thread_ids() -> HashSet<u32> {
-    let mut threads = HashSet::new();
-    unsafe {
-        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0);
-        if snapshot as usize != usize::MAX {
-            let mut thread_entry: THREADENTRY32 = std::mem::zeroed();
-            thread_entry.dwSize = std::mem::size_of::<THREADENTRY32>() as u32;
-            
-            if Thread32First(snapshot, &mut thread_entry) != FALSE {
-                let current_pid = GetCurrentProcessId();
-                while thread_entry.th32OwnerProcessID == current_pid {
-                    threads.insert(thread_entry.th32ThreadID);
-                    if Thread32Next(snapshot, &mut thread_entry) == FALSE {
-                        break;
-                    }
-                }
-            }
-            
-            winapi::um::handleapi::CloseHandle(snapshot);
-        }
-    }
-    threads
-}
-
-#[cfg(target_os = "macos")]
-fn get_thread_ids() -> HashSet<u32> {
-    let mut threads = HashSet::new();
-    // macOS thread enumeration via libproc would require additional setup
-    // For now, use a basic fallback
-    if let Ok(me) = Process::myself() {
-        if let Ok(task_status) = me.tasks() {
-            for task in task_status {
-                if let Ok(t) = task {
-                    threads.insert(t.tid as u32);
-                }
-            }
-        }
-    }
-    threads
-}
-
-#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
-fn get_thread_ids() -> HashSet<u32> {
-    // Fallback for other platforms
-    HashSet::new()
-}
-
 // Protocol structures matching the common protocol
 #[derive(Debug, Deserialize)]
 struct AnalyzeRequest {
     session_id: String,
     target: String,
     inputs: Vec<String>,
+    #[serde(default)]
+    input_encodings: Vec<String>,
+    #[serde(default)]
+    input_args: Vec<Vec<serde_json::Value>>,
     repeat: usize,
     timeout_seconds: f64,
     #[serde(default)]
@@ -159,6 +36,26 @@ struct AnalyzeRequest {
     analysis_mode: String,
 }
 
+/// Framed message from the orchestrator in daemon mode
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum DaemonRequest {
+    Analyze { id: u64, request: Box<AnalyzeRequest> },
+    Shutdown,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchAnalyzeRequest {
+    requests: Vec<AnalyzeRequest>,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchAnalyzeResponse {
+    responses: Vec<AnalyzeResponse>,
+}
+
 #[derive(Debug, Serialize)]
 struct AnalyzeResponse {
     session_id: String,
@@ -170,6 +67,8 @@ struct AnalyzeResponse {
     summary: ExecutionSummary,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -182,6 +81,10 @@ struct ExecutionResult {
     execution_time_ms: u64,
     escape_detected: bool,
     escape_details: EscapeDetails,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    stdout: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    stderr: String,
 }
 
 #[derive(Debug, Serialize, Default, Clone)]
@@ -189,9 +92,15 @@ struct EscapeDetails {
     escaping_references: Vec<ObjectReference>,
     escape_paths: Vec<EscapePath>,
     threads: Vec<ThreadEscape>,
+    /// Left-behind threads that work for a pool or runtime, grouped by it rather than
+    /// listed with the one-off spawns in `threads`.
+    thread_pools: Vec<ThreadPoolEscape>,
     processes: Vec<ProcessEscape>,
     async_tasks: Vec<AsyncTaskEscape>,
     goroutines: Vec<GoroutineEscape>,
+    file_descriptors: Option<Vec<FileDescriptorEscape>>,
+    timers: Option<Vec<TimerEscape>>,
+    memory: Option<MemoryEscape>,
     other: Vec<String>,
 }
 
@@ -217,9 +126,21 @@ struct ThreadEscape {
     name: String,
     is_daemon: bool,
     state: String,
+    /// How long the thread had been alive at the last settle check.
+    lifetime_ms: u64,
+    /// The async runtime or pool the thread works for, known from its name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    runtime: Option<String>,
     stack_trace: Option<Vec<String>>,
 }
 
+#[derive(Debug, Serialize, Clone)]
+struct ThreadPoolEscape {
+    /// The runtime, the name its workers share, or `unnamed`.
+    pool: String,
+    threads: Vec<ThreadEscape>,
+}
+
 #[derive(Debug, Serialize, Clone)]
 struct ProcessEscape {
     pid: u32,
@@ -227,6 +148,28 @@ struct ProcessEscape {
     cmdline: Option<String>,
 }
 
+#[derive(Debug, Serialize, Clone)]
+struct FileDescriptorEscape {
+    fd: u64,
+    kind: String,
+    target: String,
+}
+
+/// A left-behind thread that keeps waking at a steady pace; the period is measured.
+#[derive(Debug, Serialize, Clone)]
+struct TimerEscape {
+    timer_id: String,
+    kind: String,
+    period_ms: f64,
+    callback: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct MemoryEscape {
+    rss_before_bytes: u64,
+    rss_after_bytes: u64,
+}
+
 #[derive(Debug, Serialize, Clone)]
 struct AsyncTaskEscape {
     task_id: String,
@@ -261,13 +204,125 @@ struct ExecutionSummary {
     crash_rate: f64,
 }
 
-type TargetExecutor = Arc<dyn Fn(String) -> Result<String, String> + Send + Sync>;
+/// Bytes handed to the target: a base64 input decoded, or the input itself.
+fn decode_input(encodings: &[String], index: usize, input: &str) -> Vec<u8> {
+    use base64::Engine;
+    match encodings.get(index).map(String::as_str) {
+        Some("base64") => base64::engine::general_purpose::STANDARD
+            .decode(input)
+            .unwrap_or_else(|_| input.as_bytes().to_vec()),
+        _ => input.as_bytes().to_vec(),
+    }
+}
+
+/// Shortest wait after a call before anything it left is examined, so handles it
+/// dropped on the way out get closed.
+const CLEANUP_WAIT: Duration = Duration::from_millis(100);
+
+/// Default settle period and re-checks for threads a call started (`settle_ms`,
+/// `settle_checks`).
+const DEFAULT_SETTLE_MS: u64 = 300;
+const DEFAULT_SETTLE_CHECKS: u32 = 6;
+
+/// Option keys of the bridge protocol; any other key is answered with a warning.
+const KNOWN_OPTIONS: &[&str] = &[
+    "heartbeat_interval_seconds",
+    "heartbeat_timeout_seconds",
+    "bridge_timeout_seconds",
+    "accept_encoding",
+    "transport",
+    "env",
+    "env_passthrough",
+    "workdir",
+    "chaos_seed",
+    "observe_seconds",
+    "detect_processes",
+    "treat_daemon_as_escape",
+    "settle_ms",
+    "settle_checks",
+    "thread_threshold",
+    "detect_fds",
+    "capture_stacks",
+    "daemon",
+    "sandbox",
+    "sandbox_images",
+    "constructor_args",
+];
+
+/// Request options that shape every execution.
+#[derive(Debug, Clone, Copy)]
+struct ExecutionOptions {
+    detect_processes: bool,
+    treat_daemon_as_escape: bool,
+    /// How long threads a call started get to finish before they count as left behind.
+    settle: Duration,
+    /// Thread snapshots taken over `settle`.
+    settle_checks: u32,
+    /// Fewest left-behind threads that make an escape.
+    thread_threshold: usize,
+    detect_fds: bool,
+}
+
+impl ExecutionOptions {
+    fn from_request(options: &std::collections::HashMap<String, String>) -> Self {
+        let flag = |key: &str| options.get(key).is_some_and(|value| value == "true");
+        let number = |key: &str| options.get(key).and_then(|value| value.trim().parse::<u64>().ok());
+        Self {
+            detect_processes: flag("detect_processes"),
+            treat_daemon_as_escape: flag("treat_daemon_as_escape"),
+            settle: Duration::from_millis(number("settle_ms").unwrap_or(DEFAULT_SETTLE_MS)),
+            settle_checks: number("settle_checks")
+                .map_or(DEFAULT_SETTLE_CHECKS, |checks| checks.clamp(1, 1000) as u32),
+            thread_threshold: number("thread_threshold").map_or(1, |threshold| threshold.max(1) as usize),
+            detect_fds: options.get("detect_fds").is_none_or(|value| value != "false"),
+        }
+    }
+}
+
+/// Warnings for the request options this bridge does not know or cannot honor.
+fn option_warnings(options: &std::collections::HashMap<String, String>) -> Vec<String> {
+    let mut warnings: Vec<String> = options
+        .keys()
+        .filter(|key| !KNOWN_OPTIONS.contains(&key.as_str()))
+        .map(|key| format!("Unknown option `{}` ignored", key))
+        .collect();
+    if options.get("capture_stacks").is_some_and(|value| value == "true") {
+        warnings.push("`capture_stacks` is not supported by the Rust bridge; threads are reported without stacks".to_string());
+    }
+    warnings.sort();
+    warnings
+}
+
+/// Threads not in `baseline` that are still alive at the last of `options.settle_checks`
+/// snapshots spread over the settle period. Checking stops early once every thread the
+/// call started has exited, but never before `CLEANUP_WAIT`.
+fn settle_threads(
+    baseline: &std::collections::HashMap<u64, threads::ThreadInfo>,
+    options: &ExecutionOptions,
+) -> Vec<(u64, threads::ThreadInfo)> {
+    let period = options.settle.max(CLEANUP_WAIT);
+    let interval = period / options.settle_checks.max(1);
+    let started = Instant::now();
+    loop {
+        thread::sleep(interval.min(period.saturating_sub(started.elapsed())));
+        let left: Vec<(u64, threads::ThreadInfo)> = threads::snapshot()
+            .into_iter()
+            .filter(|(tid, _)| !baseline.contains_key(tid))
+            .collect();
+        let elapsed = started.elapsed();
+        if elapsed >= period || (left.is_empty() && elapsed >= CLEANUP_WAIT) {
+            return left;
+        }
+    }
+}
 
 fn execute_test(
-    target_fn: TargetExecutor,
+    target: Arc<dylib::TargetLibrary>,
     target_label: &str,
     input: String,
+    argument: Vec<u8>,
     timeout_seconds: f64,
+    options: &ExecutionOptions,
 ) -> ExecutionResult {
     let mut result = ExecutionResult {
         input_data: input.clone(),
@@ -278,39 +333,46 @@ fn execute_test(
         execution_time_ms: 0,
         escape_detected: false,
         escape_details: EscapeDetails::default(),
+        stdout: String::new(),
+        stderr: String::new(),
     };
 
-    // Capture baseline thread IDs
-    let baseline_threads = get_thread_ids();
-    let baseline_alloc = allocation_snapshot();
+    // Capture baseline threads. Heap counters come from the target library's own
+    // allocator (a cdylib does not allocate through the bridge's), when it exports them.
+    let baseline_threads = threads::snapshot();
+    let baseline_descriptors = if options.detect_fds {
+        descriptors::snapshot()
+    } else {
+        None
+    };
+    let baseline_processes = if options.detect_processes {
+        processes::snapshot()
+    } else {
+        Default::default()
+    };
+    let baseline_alloc = target.allocations();
+    let rss_before = memory::resident_bytes();
 
+    let capture = capture::Capture::start();
     let start = Instant::now();
     let timeout = Duration::from_secs_f64(timeout_seconds);
 
     // Execute with timeout using a channel
     let (tx, rx) = std::sync::mpsc::channel();
-    let input_clone = input.clone();
+    let call_target = Arc::clone(&target);
 
     thread::spawn(move || {
-        let target_fn = Arc::clone(&target_fn);
-        let exec_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            target_fn(input_clone)
-        }));
-        let _ = tx.send(exec_result);
+        let _ = tx.send(call_target.call(&argument));
     });
 
     match rx.recv_timeout(timeout) {
-        Ok(Ok(Ok(output))) => {
+        Ok(dylib::Invocation::Returned(output)) => {
             result.success = true;
-            result.output = output;
+            result.output = output.trim().to_string();
         }
-        Ok(Ok(Err(err))) => {
+        Ok(dylib::Invocation::Panicked(message)) => {
             result.crashed = true;
-            result.error = err;
-        }
-        Ok(Err(e)) => {
-            result.crashed = true;
-            result.error = format!("Panic: {:?}", e);
+            result.error = format!("Panic: {}", message);
         }
         Err(_) => {
             result.crashed = true;
@@ -320,33 +382,137 @@ fn execute_test(
 
     result.execution_time_ms = start.elapsed().as_millis() as u64;
 
-    // Wait a bit for cleanup
-    thread::sleep(Duration::from_millis(100));
-
-    let after_alloc = allocation_snapshot();
-    let alloc_growth_bytes = after_alloc.0.saturating_sub(baseline_alloc.0);
-    let dealloc_growth_bytes = after_alloc.1.saturating_sub(baseline_alloc.1);
-    let net_growth_bytes = alloc_growth_bytes.saturating_sub(dealloc_growth_bytes);
-    let peak_in_use_bytes = after_alloc.2;
-
-    // Check for thread leaks using platform-specific APIs
-    let current_threads = get_thread_ids();
-    let escaped_threads: HashSet<u32> = current_threads
+    // Threads that are about to exit are not escapes: only those still alive once the
+    // settle period is over count.
+    let mut escaped_threads = settle_threads(&baseline_threads, options);
+    escaped_threads.sort_by_key(|(tid, _)| *tid);
+    // Where the start time is unknown, the call's start bounds it.
+    let lifetimes: std::collections::HashMap<u64, u64> = escaped_threads
         .iter()
-        .filter(|tid| !baseline_threads.contains(tid))
-        .copied()
+        .map(|(tid, _)| (*tid, threads::lifetime_ms(*tid).unwrap_or(start.elapsed().as_millis() as u64)))
         .collect();
 
-    if !escaped_threads.is_empty() {
+    if let Some(capture) = capture {
+        let (stdout, stderr) = capture.finish();
+        result.stdout = stdout;
+        result.stderr = stderr;
+    }
+
+    if let (Some(rss_before_bytes), Some(rss_after_bytes)) = (rss_before, memory::resident_bytes()) {
+        result.escape_details.memory = Some(MemoryEscape { rss_before_bytes, rss_after_bytes });
+    }
+
+    let (net_growth_bytes, peak_in_use_bytes) = match (baseline_alloc, target.allocations()) {
+        (Some(before), Some(after)) => {
+            let alloc_growth_bytes = after.0.saturating_sub(before.0);
+            let dealloc_growth_bytes = after.1.saturating_sub(before.1);
+            (alloc_growth_bytes.saturating_sub(dealloc_growth_bytes), after.2)
+        }
+        _ => (0, 0),
+    };
+
+    let tids: Vec<u64> = escaped_threads.iter().map(|(tid, _)| *tid).collect();
+    if let Some(periods) = timers::periodic(&tids) {
+        let site = if periods.is_empty() {
+            None
+        } else {
+            timers::timer_site(target_label)
+        };
+        let callback = match site {
+            Some(site) => format!("{} ({})", target_label, site),
+            None => target_label.to_string(),
+        };
+        let mut leaked = Vec::new();
+        escaped_threads.retain(|(tid, info)| match periods.get(tid) {
+            Some(period_ms) => {
+                leaked.push(TimerEscape {
+                    timer_id: format!("thread {} ({})", tid, info.name),
+                    kind: "interval".to_string(),
+                    period_ms: (period_ms * 10.0).round() / 10.0,
+                    callback: callback.clone(),
+                });
+                false
+            }
+            None => true,
+        });
+        if !leaked.is_empty() {
+            result.escape_detected = true;
+        }
+        result.escape_details.timers = Some(leaked);
+    }
+
+    // Idle background workers are listed, but only count toward an escape when asked
+    // to; and only `thread_threshold` or more counted threads make one.
+    let escaped_threads: Vec<(u64, threads::ThreadInfo, bool)> = escaped_threads
+        .into_iter()
+        .map(|(tid, info)| (tid, info, threads::is_background(tid)))
+        .collect();
+    let counted = escaped_threads
+        .iter()
+        .filter(|(_, _, is_daemon)| !is_daemon || options.treat_daemon_as_escape)
+        .count();
+    if counted > 0 && counted >= options.thread_threshold {
         result.escape_detected = true;
-        for tid in escaped_threads {
-            result.escape_details.threads.push(ThreadEscape {
-                thread_id: tid.to_string(),
-                name: format!("thread_{}", tid),
-                is_daemon: false,
-                state: "unknown".to_string(),
-                stack_trace: None,
-            });
+    }
+    let mut pools = threads::pools(&escaped_threads);
+    let mut pooled: std::collections::BTreeMap<String, Vec<ThreadEscape>> = std::collections::BTreeMap::new();
+    for (tid, info, is_daemon) in escaped_threads {
+        let runtime = threads::runtime(&info.name).map(str::to_string);
+        let thread = ThreadEscape {
+            thread_id: tid.to_string(),
+            name: info.name,
+            is_daemon,
+            state: info.state,
+            lifetime_ms: lifetimes.get(&tid).copied().unwrap_or_default(),
+            runtime,
+            stack_trace: None,
+        };
+        match pools.remove(&tid) {
+            Some(pool) => pooled.entry(pool).or_default().push(thread),
+            None => result.escape_details.threads.push(thread),
+        }
+    }
+    result.escape_details.thread_pools =
+        pooled.into_iter().map(|(pool, threads)| ThreadPoolEscape { pool, threads }).collect();
+
+    if let (Some(before), Some(after)) = (baseline_descriptors, descriptors::snapshot()) {
+        let mut leaked: Vec<(u64, descriptors::Descriptor)> = after
+            .into_iter()
+            .filter(|(fd, descriptor)| before.get(fd) != Some(descriptor))
+            .collect();
+        leaked.sort_by_key(|(fd, _)| *fd);
+
+        if !leaked.is_empty() {
+            result.escape_detected = true;
+        }
+        result.escape_details.file_descriptors = Some(
+            leaked
+                .into_iter()
+                .map(|(fd, descriptor)| FileDescriptorEscape {
+                    fd,
+                    kind: descriptor.kind,
+                    target: descriptor.target,
+                })
+                .collect(),
+        );
+    }
+
+    if options.detect_processes {
+        let mut escaped_processes: Vec<(u32, processes::ProcessInfo)> = processes::snapshot()
+            .into_iter()
+            .filter(|(pid, _)| !baseline_processes.contains_key(pid))
+            .collect();
+        escaped_processes.sort_by_key(|(pid, _)| *pid);
+
+        if !escaped_processes.is_empty() {
+            result.escape_detected = true;
+            for (pid, info) in escaped_processes {
+                result.escape_details.processes.push(ProcessEscape {
+                    pid,
+                    name: info.name,
+                    cmdline: info.cmdline,
+                });
+            }
         }
     }
 
@@ -386,14 +552,21 @@ fn execute_test(
 fn find_workspace_root() -> anyhow::Result<PathBuf> {
     let mut current = env::current_dir()?;
     loop {
-        if current.join("Cargo.toml").exists() {
+        if current.join("Cargo.toml").exists() && current.join("tests/rust/Cargo.toml").exists() {
             return Ok(current);
         }
         if !current.pop() {
             break;
         }
     }
-    anyhow::bail!("Could not find workspace root (no Cargo.toml found)")
+    // Run from elsewhere (`--workdir`): target/release/rust-analyzer sits two levels
+    // below the workspace root.
+    if let Some(root) = env::current_exe()?.ancestors().nth(3) {
+        if root.join("tests/rust/Cargo.toml").exists() {
+            return Ok(root.to_path_buf());
+        }
+    }
+    anyhow::bail!("Could not find workspace root (no Cargo.toml with tests/rust found)")
 }
 
 fn parse_rust_target(target: &str) -> anyhow::Result<(String, String, String)> {
@@ -431,95 +604,7 @@ fn parse_rust_target(target: &str) -> anyhow::Result<(String, String, String)> {
     Ok((crate_name, module_path, function_name))
 }
 
-fn build_target_runner(target: &str) -> anyhow::Result<(PathBuf, PathBuf)> {
-    let (crate_name, module_path, function_name) = parse_rust_target(target)?;
-    let workspace_root = find_workspace_root()?;
-    let tests_rust_dir = workspace_root.join("tests").join("rust");
-
-    if crate_name != "escape_tests_rust" {
-        anyhow::bail!(
-            "Unsupported Rust crate '{}'. Expected 'escape_tests_rust' for this workspace target set.",
-            crate_name
-        );
-    }
-    if !tests_rust_dir.join("Cargo.toml").exists() {
-        anyhow::bail!(
-            "Rust test crate not found at '{}'",
-            tests_rust_dir.display()
-        );
-    }
-
-    let nonce = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .map(|d| d.as_nanos())
-        .unwrap_or(0);
-    let temp_dir = std::env::temp_dir().join(format!(
-        "graphene-rust-runner-{}-{}",
-        std::process::id(),
-        nonce
-    ));
-    fs::create_dir_all(temp_dir.join("src"))?;
-
-    let cargo_toml = format!(
-        "[package]\nname = \"graphene_rust_target_runner\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nescape_tests_rust = {{ package = \"escape-tests-rust\", path = \"{}\" }}\n",
-        tests_rust_dir.display().to_string().replace('\\', "\\\\")
-    );
-    fs::write(temp_dir.join("Cargo.toml"), cargo_toml)?;
-
-    let main_rs = format!(
-        "fn main() {{\n    let input = std::env::var(\"GRAPHENE_INPUT\").unwrap_or_default();\n    let output = escape_tests_rust::{module_path}::{function_name}(input);\n    print!(\"{{}}\", output);\n}}\n"
-    );
-    fs::write(temp_dir.join("src").join("main.rs"), main_rs)?;
-
-    let build = Command::new("cargo")
-        .arg("build")
-        .arg("--release")
-        .current_dir(&temp_dir)
-        .output()?;
-    if !build.status.success() {
-        let stderr = String::from_utf8_lossy(&build.stderr).trim().to_string();
-        let stdout = String::from_utf8_lossy(&build.stdout).trim().to_string();
-        let detail = if !stderr.is_empty() { stderr } else { stdout };
-        anyhow::bail!("Failed to build Rust target runner: {}", detail);
-    }
-
-    let binary_name = format!("graphene_rust_target_runner{}", env::consts::EXE_SUFFIX);
-    let binary_path = temp_dir.join("target").join("release").join(binary_name);
-    if !binary_path.exists() {
-        anyhow::bail!(
-            "Rust target runner binary was not produced at '{}'",
-            binary_path.display()
-        );
-    }
-
-    Ok((binary_path, temp_dir))
-}
-
-fn create_executor(binary_path: PathBuf) -> TargetExecutor {
-    Arc::new(move |input: String| -> Result<String, String> {
-        let output = Command::new(&binary_path)
-            .env("GRAPHENE_INPUT", input)
-            .output()
-            .map_err(|e| format!("Failed to run target runner: {}", e))?;
-
-        if output.status.success() {
-            return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
-        }
-
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        let detail = if !stderr.is_empty() { stderr } else { stdout };
-        Err(if detail.is_empty() {
-            "Target execution failed with no output".to_string()
-        } else {
-            format!("Target execution failed: {}", detail)
-        })
-    })
-}
-
 fn analyze(request: AnalyzeRequest) -> AnalyzeResponse {
-    let _ = &request.options;
-
     let mut response = AnalyzeResponse {
         session_id: request.session_id,
         language: "rust".to_string(),
@@ -529,9 +614,14 @@ fn analyze(request: AnalyzeRequest) -> AnalyzeResponse {
         vulnerabilities: Vec::new(),
         summary: ExecutionSummary::default(),
         error: None,
+        warnings: option_warnings(&request.options),
     };
 
-    let (runner_binary, runner_dir) = match build_target_runner(&request.target) {
+    let (target_library, build_dir) = match dylib::load_target(
+        &request.target,
+        request.options.get("constructor_args").map(String::as_str),
+        request.input_args.first().map(Vec::len),
+    ) {
         Ok(v) => v,
         Err(e) => {
             response.error = Some(format!("Target loading failed: {}", e));
@@ -548,7 +638,7 @@ fn analyze(request: AnalyzeRequest) -> AnalyzeResponse {
         }
     };
 
-    let target_fn = create_executor(runner_binary);
+    let target_library = Arc::new(target_library);
 
     let mut successes = 0;
     let mut crashes = 0;
@@ -556,19 +646,27 @@ fn analyze(request: AnalyzeRequest) -> AnalyzeResponse {
     let mut escapes = 0;
     let mut genuine_escapes = 0;
 
+    let options = ExecutionOptions::from_request(&request.options);
+
     let inputs = if request.inputs.is_empty() {
         vec![String::new()]
     } else {
         request.inputs.clone()
     };
 
-    for input in &inputs {
+    for (index, input) in inputs.iter().enumerate() {
+        let argument = match request.input_args.get(index) {
+            Some(args) => dylib::encode_arguments(args),
+            None => decode_input(&request.input_encodings, index, input),
+        };
         for _ in 0..request.repeat {
             let result = execute_test(
-                Arc::clone(&target_fn),
+                Arc::clone(&target_library),
                 &request.target,
                 input.clone(),
+                argument.clone(),
                 request.timeout_seconds,
+                &options,
             );
 
             if result.success {
@@ -605,11 +703,14 @@ fn analyze(request: AnalyzeRequest) -> AnalyzeResponse {
                 response.vulnerabilities.push(vuln);
             }
 
+            emit_result(&result);
             response.results.push(result);
         }
     }
 
-    let _ = fs::remove_dir_all(&runner_dir);
+    if let Some(build_dir) = build_dir {
+        let _ = fs::remove_dir_all(&build_dir);
+    }
 
     let total_tests = response.results.len();
     response.summary = ExecutionSummary {
@@ -629,19 +730,179 @@ fn analyze(request: AnalyzeRequest) -> AnalyzeResponse {
     response
 }
 
+const HEARTBEAT_PREFIX: &str = "GRAPHENE_HEARTBEAT";
+const RESULT_PREFIX: &str = "GRAPHENE_RESULT";
+const GZIP_RESPONSE_MARKER: &str = "GRAPHENE_GZIP";
+const BRIDGE_SOCKET_ENV: &str = "GRAPHENE_BRIDGE_SOCKET";
+const DEFAULT_HEARTBEAT_INTERVAL_SECONDS: f64 = 5.0;
+// Handshake: print the protocol version and capabilities, then exit.
+const HANDSHAKE_ENV: &str = "GRAPHENE_BRIDGE_HANDSHAKE";
+const PROTOCOL_VERSION: u32 = 1;
+const CAPABILITIES: &[&str] = &["batch", "gzip", "unix_socket", "daemon", "input_encodings", "input_args", "detect_processes", "thread_classification"];
+// Daemon mode: framed requests on stdin and framed responses on stdout until shutdown.
+const DAEMON_ENV: &str = "GRAPHENE_BRIDGE_DAEMON";
+const FRAME_MARKER: &str = "GRAPHENE_FRAME";
+const DAEMON_PROTOCOL_VERSION: u32 = 1;
+
+/// Streams a finished result to stderr so it survives a later bridge crash.
+fn emit_result(result: &ExecutionResult) {
+    if let Ok(line) = serde_json::to_string(result) {
+        capture::stderr_line(&format!("{} {}", RESULT_PREFIX, line));
+    }
+}
+
+/// Writes periodic heartbeat lines to stderr so the orchestrator can detect a hung bridge.
+/// Started before any test runs, so the thread is part of every thread baseline.
+fn start_heartbeat(options: &std::collections::HashMap<String, String>) {
+    let interval = options
+        .get("heartbeat_interval_seconds")
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .unwrap_or(DEFAULT_HEARTBEAT_INTERVAL_SECONDS);
+    if interval <= 0.0 {
+        return;
+    }
+
+    let _ = thread::Builder::new()
+        .name("graphene-heartbeat".to_string())
+        .spawn(move || loop {
+            let millis = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+            capture::stderr_line(&format!("{} {}", HEARTBEAT_PREFIX, millis));
+            thread::sleep(Duration::from_secs_f64(interval));
+        });
+}
+
 fn main() -> anyhow::Result<()> {
-    // Read request from stdin
-    let mut buffer = String::new();
-    io::stdin().read_to_string(&mut buffer)?;
+    capture::init();
+
+    if env::var(HANDSHAKE_ENV).is_ok_and(|value| value == "1") {
+        let handshake = serde_json::json!({
+            "protocol_version": PROTOCOL_VERSION,
+            "analyzer_version": "1.0.0",
+            "capabilities": CAPABILITIES,
+        });
+        let mut out = capture::protocol_stdout();
+        writeln!(out, "{}", handshake)?;
+        out.flush()?;
+        return Ok(());
+    }
+
+    if env::var(DAEMON_ENV).is_ok_and(|value| value == "1") {
+        return serve_daemon();
+    }
+
+    // Read request from stdin, or from the orchestrator's socket when it names one
+    let (buffer, mut out) = open_channel()?;
+
+    // A batch runs several requests in this one process, answered in order
+    if let Ok(batch) = serde_json::from_str::<BatchAnalyzeRequest>(&buffer) {
+        if let Some(first) = batch.requests.first() {
+            start_heartbeat(&first.options);
+        }
+        let options = batch.requests.first().map(|r| r.options.clone()).unwrap_or_default();
+        let responses: Vec<AnalyzeResponse> = batch.requests.into_iter().map(analyze).collect();
+        return write_response(&BatchAnalyzeResponse { responses }, &options, &mut out);
+    }
 
     // Parse request
     let request: AnalyzeRequest = serde_json::from_str(&buffer)?;
+    start_heartbeat(&request.options);
 
     // Process
+    let options = request.options.clone();
     let response = analyze(request);
 
-    // Write response to stdout
-    println!("{}", serde_json::to_string_pretty(&response)?);
+    // Write response back over the same channel
+    write_response(&response, &options, &mut out)
+}
+
+/// Answers framed requests on stdin until a shutdown frame or the end of input.
+fn serve_daemon() -> anyhow::Result<()> {
+    let mut out = capture::protocol_stdout();
+    write_frame(
+        &mut *out,
+        &serde_json::json!({ "type": "ready", "protocol": DAEMON_PROTOCOL_VERSION }),
+    )?;
+    let mut input = io::BufReader::new(io::stdin().lock());
+    let mut heartbeat_started = false;
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(());
+        }
+        let Some(length) = header
+            .trim()
+            .strip_prefix(FRAME_MARKER)
+            .and_then(|length| length.trim().parse::<usize>().ok())
+        else {
+            continue;
+        };
+        let mut payload = vec![0; length];
+        input.read_exact(&mut payload)?;
+        match serde_json::from_slice::<DaemonRequest>(&payload)? {
+            DaemonRequest::Analyze { id, request } => {
+                if !heartbeat_started {
+                    start_heartbeat(&request.options);
+                    heartbeat_started = true;
+                }
+                let response = analyze(*request);
+                write_frame(
+                    &mut *out,
+                    &serde_json::json!({ "type": "response", "id": id, "response": response }),
+                )?;
+            }
+            DaemonRequest::Shutdown => return Ok(()),
+            DaemonRequest::Unknown => {}
+        }
+    }
+}
+
+/// Writes one framed message on a line of its own.
+fn write_frame(out: &mut dyn Write, message: &serde_json::Value) -> anyhow::Result<()> {
+    let body = serde_json::to_vec(message)?;
+    write!(out, "\n{} {}\n", FRAME_MARKER, body.len())?;
+    out.write_all(&body)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Reads the request and returns it with the writer for the response: the
+/// orchestrator's Unix socket when `GRAPHENE_BRIDGE_SOCKET` is set, else stdin/stdout.
+fn open_channel() -> anyhow::Result<(String, Box<dyn Write>)> {
+    let mut buffer = String::new();
+    #[cfg(unix)]
+    if let Ok(path) = env::var(BRIDGE_SOCKET_ENV) {
+        let mut stream = std::os::unix::net::UnixStream::connect(path)?;
+        stream.read_to_string(&mut buffer)?;
+        return Ok((buffer, Box::new(stream)));
+    }
+    io::stdin().read_to_string(&mut buffer)?;
+    Ok((buffer, capture::protocol_stdout()))
+}
+
+/// Writes the final response, gzip-compressed when the orchestrator accepts it.
+fn write_response<T: Serialize>(
+    payload: &T,
+    options: &std::collections::HashMap<String, String>,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let accepts_gzip = options
+        .get("accept_encoding")
+        .is_some_and(|value| value.split(',').any(|encoding| encoding.trim() == "gzip"));
+    if !accepts_gzip {
+        serde_json::to_writer_pretty(&mut *out, payload)?;
+        writeln!(out)?;
+        out.flush()?;
+        return Ok(());
+    }
 
+    writeln!(out, "{}", GZIP_RESPONSE_MARKER)?;
+    let mut encoder = GzEncoder::new(&mut *out, Compression::default());
+    serde_json::to_writer(&mut encoder, payload)?;
+    encoder.finish()?;
+    out.flush()?;
     Ok(())
 }
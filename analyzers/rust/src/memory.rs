@@ -0,0 +1,36 @@
+//! Resident memory of the bridge process. A target runs in-process, so memory it keeps
+//! after returning shows up as growth between readings taken around an execution.
+
+/// Resident set size in bytes, or `None` where it cannot be read.
+#[cfg(target_os = "linux")]
+pub fn resident_bytes() -> Option<u64> {
+    let statm = procfs::process::Process::myself().ok()?.statm().ok()?;
+    Some(statm.resident * procfs::page_size())
+}
+
+/// `PROC_PIDTASKINFO` reports the task's resident size directly.
+#[cfg(target_os = "macos")]
+pub fn resident_bytes() -> Option<u64> {
+    let mut info = std::mem::MaybeUninit::<libc::proc_taskinfo>::zeroed();
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+    // SAFETY: the buffer holds one proc_taskinfo and its size is passed in bytes.
+    let written = unsafe {
+        libc::proc_pidinfo(
+            std::process::id() as libc::c_int,
+            libc::PROC_PIDTASKINFO,
+            0,
+            info.as_mut_ptr().cast(),
+            size,
+        )
+    };
+    if written != size {
+        return None;
+    }
+    // SAFETY: proc_pidinfo filled the whole struct.
+    Some(unsafe { info.assume_init() }.pti_resident_size)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn resident_bytes() -> Option<u64> {
+    None
+}
@@ -0,0 +1,146 @@
+//! Descendant processes of the bridge. A target runs in the bridge process, so anything
+//! it spawns (and whatever those spawn) shows up here; snapshots taken around an
+//! execution show which processes it left behind.
+
+use std::collections::HashMap;
+
+/// One descendant process.
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub name: String,
+    pub cmdline: Option<String>,
+}
+
+/// Processes descended from this one, keyed by pid. Exited children that were never
+/// waited for are included.
+#[cfg(target_os = "linux")]
+pub fn snapshot() -> HashMap<u32, ProcessInfo> {
+    let Ok(all) = procfs::process::all_processes() else {
+        return HashMap::new();
+    };
+    let mut parents = HashMap::new();
+    let mut stats = HashMap::new();
+    for process in all.flatten() {
+        if let Ok(stat) = process.stat() {
+            parents.insert(stat.pid as u32, stat.ppid as u32);
+            stats.insert(stat.pid as u32, (process, stat.comm));
+        }
+    }
+
+    descendants(&parents)
+        .into_iter()
+        .filter_map(|pid| {
+            let (process, name) = stats.remove(&pid)?;
+            let cmdline = process
+                .cmdline()
+                .ok()
+                .filter(|args| !args.is_empty())
+                .map(|args| args.join(" "));
+            Some((pid, ProcessInfo { name, cmdline }))
+        })
+        .collect()
+}
+
+/// Toolhelp32 gives every process with its parent and executable name; command lines
+/// would need the target's PEB and are left out.
+#[cfg(target_os = "windows")]
+pub fn snapshot() -> HashMap<u32, ProcessInfo> {
+    use std::ffi::CStr;
+    use winapi::shared::minwindef::FALSE;
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::tlhelp32::{CreateToolhelp32Snapshot, Process32First, Process32Next, PROCESSENTRY32, TH32CS_SNAPPROCESS};
+
+    let mut parents = HashMap::new();
+    let mut names = HashMap::new();
+    // SAFETY: the snapshot handle is checked and closed; the entry is sized as the API
+    // requires before the first call, and szExeFile is NUL-terminated.
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return HashMap::new();
+        }
+        let mut entry: PROCESSENTRY32 = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32>() as u32;
+        let mut more = Process32First(snapshot, &mut entry) != FALSE;
+        while more {
+            parents.insert(entry.th32ProcessID, entry.th32ParentProcessID);
+            names.insert(
+                entry.th32ProcessID,
+                CStr::from_ptr(entry.szExeFile.as_ptr()).to_string_lossy().into_owned(),
+            );
+            more = Process32Next(snapshot, &mut entry) != FALSE;
+        }
+        CloseHandle(snapshot);
+    }
+
+    descendants(&parents)
+        .into_iter()
+        .map(|pid| {
+            let name = names.remove(&pid).unwrap_or_default();
+            (pid, ProcessInfo { name, cmdline: None })
+        })
+        .collect()
+}
+
+/// libproc: `proc_listchildpids` walked down from this process, `proc_name` for names.
+#[cfg(target_os = "macos")]
+pub fn snapshot() -> HashMap<u32, ProcessInfo> {
+    const MAX_CHILDREN: usize = 1024;
+
+    let mut processes = HashMap::new();
+    let mut pending = vec![std::process::id() as libc::pid_t];
+    while let Some(parent) = pending.pop() {
+        let mut children = vec![0 as libc::pid_t; MAX_CHILDREN];
+        // SAFETY: the buffer holds MAX_CHILDREN pids and its size is passed in bytes;
+        // the call returns the number of pids written.
+        let count = unsafe {
+            libc::proc_listchildpids(
+                parent,
+                children.as_mut_ptr().cast(),
+                (children.len() * std::mem::size_of::<libc::pid_t>()) as libc::c_int,
+            )
+        };
+        if count <= 0 {
+            continue;
+        }
+        children.truncate((count as usize).min(MAX_CHILDREN));
+
+        for pid in children.into_iter().filter(|pid| *pid > 0) {
+            let mut name = [0u8; 256];
+            // SAFETY: `name` is sized as passed.
+            let len = unsafe { libc::proc_name(pid, name.as_mut_ptr().cast(), name.len() as u32) };
+            let name = String::from_utf8_lossy(&name[..len.max(0) as usize]).into_owned();
+            processes.insert(pid as u32, ProcessInfo { name, cmdline: None });
+            pending.push(pid);
+        }
+    }
+    processes
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+pub fn snapshot() -> HashMap<u32, ProcessInfo> {
+    HashMap::new()
+}
+
+/// Pids reachable from this process through a child -> parent map.
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn descendants(parents: &HashMap<u32, u32>) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (&pid, &parent) in parents {
+        if pid != parent {
+            children.entry(parent).or_default().push(pid);
+        }
+    }
+
+    let mut found = Vec::new();
+    let mut pending = vec![std::process::id()];
+    while let Some(parent) = pending.pop() {
+        for &child in children.get(&parent).into_iter().flatten() {
+            if !found.contains(&child) {
+                found.push(child);
+                pending.push(child);
+            }
+        }
+    }
+    found
+}
@@ -0,0 +1,272 @@
+//! Threads of the bridge process, with names and scheduler states where the platform
+//! exposes them. Snapshots taken around an execution show which threads a target left
+//! behind.
+
+use std::collections::HashMap;
+
+/// One thread of this process.
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    pub name: String,
+    pub state: String,
+}
+
+/// Threads currently in this process, keyed by OS thread id.
+#[cfg(target_os = "linux")]
+pub fn snapshot() -> HashMap<u64, ThreadInfo> {
+    use procfs::process::Process;
+
+    let mut threads = HashMap::new();
+    let Ok(tasks) = Process::myself().and_then(|me| me.tasks()) else {
+        return threads;
+    };
+    for task in tasks.flatten() {
+        let (name, state) = match task.stat() {
+            Ok(stat) => (stat.comm, linux_state(stat.state).to_string()),
+            Err(_) => (format!("thread_{}", task.tid), "unknown".to_string()),
+        };
+        threads.insert(task.tid as u64, ThreadInfo { name, state });
+    }
+    threads
+}
+
+/// Kernel wait channels of a thread blocked with no deadline: on a lock, condition
+/// variable or channel (futex), or on I/O.
+#[cfg(target_os = "linux")]
+const IDLE_WAITS: &[&str] = &[
+    "futex",
+    "ep_poll",
+    "epoll",
+    "pipe_read",
+    "sk_wait",
+    "unix_stream_read",
+    "inet_csk_accept",
+    "sigsuspend",
+    "do_sigtimedwait",
+];
+
+/// Whether thread `tid` is the equivalent of a daemon thread: a background worker
+/// parked until something wakes it, the way a pool worker waits for jobs. A thread that
+/// is running or sleeping for a set time still has work to do and holds up a clean
+/// exit; so does one whose wait cannot be read. Linux reads the thread's wait channel
+/// from procfs; other platforms classify every thread as exit-blocking.
+#[cfg(target_os = "linux")]
+pub fn is_background(tid: u64) -> bool {
+    let Ok(wchan) = std::fs::read_to_string(format!("/proc/self/task/{}/wchan", tid)) else {
+        return false;
+    };
+    let wchan = wchan.trim();
+    IDLE_WAITS.iter().any(|wait| wchan.contains(wait))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn is_background(_tid: u64) -> bool {
+    false
+}
+
+/// Async runtimes and pools known by the names they give their worker threads, as
+/// prefixes of the name the OS reports (Linux cuts names to 15 bytes). Tasks are
+/// invisible from outside a runtime, so left-behind workers are what shows that one
+/// is still running, whichever runtime the target uses; threads of other runtimes
+/// are reported the same way, just without one.
+const RUNTIME_THREADS: &[(&str, &str)] = &[
+    ("tokio-runtime-w", "tokio"),
+    ("async-std/runti", "async-std"),
+    ("async-global-ex", "async-global-executor"),
+    ("smol-", "smol"),
+    ("blocking-", "blocking"),
+    ("async-io", "async-io"),
+];
+
+/// The runtime or pool a thread named `name` works for, when it is a known one.
+pub fn runtime(name: &str) -> Option<&'static str> {
+    RUNTIME_THREADS
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, runtime)| *runtime)
+}
+
+/// Pool each of `threads` (id, info, idle) belongs to, by thread id; threads missing
+/// from the map are one-off spawns. A pool is a known runtime's workers, two or more
+/// threads named alike apart from a trailing number (`worker-0`, `worker-1`), or two or
+/// more unnamed threads all parked waiting for work, the way rayon and `threadpool`
+/// workers are by default.
+pub fn pools(threads: &[(u64, ThreadInfo, bool)]) -> HashMap<u64, String> {
+    let mut named: HashMap<&str, Vec<u64>> = HashMap::new();
+    let mut pools = HashMap::new();
+    for (tid, info, _) in threads {
+        if let Some(runtime) = runtime(&info.name) {
+            pools.insert(*tid, runtime.to_string());
+        } else if !unnamed(&info.name) {
+            named.entry(stem(&info.name)).or_default().push(*tid);
+        }
+    }
+    for (stem, tids) in named {
+        if tids.len() >= 2 && !stem.is_empty() {
+            pools.extend(tids.into_iter().map(|tid| (tid, stem.to_string())));
+        }
+    }
+    let unnamed: Vec<&(u64, ThreadInfo, bool)> = threads.iter().filter(|(_, info, _)| unnamed(&info.name)).collect();
+    if unnamed.len() >= 2 && unnamed.iter().all(|(_, _, idle)| *idle) {
+        pools.extend(unnamed.into_iter().map(|(tid, _, _)| (*tid, "unnamed".to_string())));
+    }
+    pools
+}
+
+/// `name` without a trailing number and the separator before it.
+fn stem(name: &str) -> &str {
+    name.trim_end_matches(|c: char| c.is_ascii_digit())
+        .trim_end_matches(['-', '_', '#', ' ', '.'])
+}
+
+/// Whether a thread was given no name: snapshots stand in `thread_<id>` for one, and
+/// on Linux it keeps the name of the thread that spawned it, the main thread's for
+/// threads the target starts.
+fn unnamed(name: &str) -> bool {
+    #[cfg(target_os = "linux")]
+    if std::fs::read_to_string("/proc/self/comm").is_ok_and(|comm| comm.trim_end() == name) {
+        return true;
+    }
+    name.starts_with("thread_")
+}
+
+/// Milliseconds since thread `tid` started, from its start time in procfs (to the
+/// kernel's clock tick); `None` on other platforms or when it has exited.
+#[cfg(target_os = "linux")]
+pub fn lifetime_ms(tid: u64) -> Option<u64> {
+    use procfs::process::Process;
+
+    let stat = Process::myself().ok()?.task_from_tid(tid as i32).ok()?.stat().ok()?;
+    let uptime = procfs::Uptime::new().ok()?.uptime_duration();
+    let started = std::time::Duration::from_secs_f64(stat.starttime as f64 / procfs::ticks_per_second() as f64);
+    Some(uptime.saturating_sub(started).as_millis() as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn lifetime_ms(_tid: u64) -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn linux_state(state: char) -> &'static str {
+    match state {
+        'R' => "running",
+        'S' => "sleeping",
+        'D' => "uninterruptible",
+        'T' | 't' => "stopped",
+        'Z' => "zombie",
+        'X' => "dead",
+        _ => "unknown",
+    }
+}
+
+/// Toolhelp32 lists every thread on the system; those owned by this process are kept.
+/// It reports neither names nor states.
+#[cfg(target_os = "windows")]
+pub fn snapshot() -> HashMap<u64, ThreadInfo> {
+    use winapi::shared::minwindef::FALSE;
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::processthreadsapi::GetCurrentProcessId;
+    use winapi::um::tlhelp32::{CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32};
+
+    let mut threads = HashMap::new();
+    // SAFETY: the snapshot handle is checked and closed; the entry is sized as the API
+    // requires before the first call.
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return threads;
+        }
+        let current_pid = GetCurrentProcessId();
+        let mut entry: THREADENTRY32 = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<THREADENTRY32>() as u32;
+        let mut more = Thread32First(snapshot, &mut entry) != FALSE;
+        while more {
+            if entry.th32OwnerProcessID == current_pid {
+                threads.insert(
+                    entry.th32ThreadID as u64,
+                    ThreadInfo {
+                        name: format!("thread_{}", entry.th32ThreadID),
+                        state: "unknown".to_string(),
+                    },
+                );
+            }
+            more = Thread32Next(snapshot, &mut entry) != FALSE;
+        }
+        CloseHandle(snapshot);
+    }
+    threads
+}
+
+/// libproc: `PROC_PIDLISTTHREADS` lists the thread handles, `PROC_PIDTHREADINFO` gives
+/// each one's name and run state.
+#[cfg(target_os = "macos")]
+pub fn snapshot() -> HashMap<u64, ThreadInfo> {
+    use std::ffi::CStr;
+    use std::mem::size_of;
+
+    // sys/proc_info.h; not exported by libc
+    const PROC_PIDLISTTHREADS: libc::c_int = 6;
+    const MAX_THREADS: usize = 4096;
+
+    let mut threads = HashMap::new();
+    let pid = std::process::id() as libc::c_int;
+    let mut handles = vec![0u64; MAX_THREADS];
+    // SAFETY: the buffer holds MAX_THREADS handles and its size is passed in bytes.
+    let listed = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            PROC_PIDLISTTHREADS,
+            0,
+            handles.as_mut_ptr().cast(),
+            (handles.len() * size_of::<u64>()) as libc::c_int,
+        )
+    };
+    if listed <= 0 {
+        return threads;
+    }
+    handles.truncate(listed as usize / size_of::<u64>());
+
+    for handle in handles {
+        // SAFETY: proc_threadinfo is plain data; zeroed is a valid value.
+        let mut info: libc::proc_threadinfo = unsafe { std::mem::zeroed() };
+        // SAFETY: `info` is sized as passed.
+        let written = unsafe {
+            libc::proc_pidinfo(
+                pid,
+                libc::PROC_PIDTHREADINFO,
+                handle,
+                (&mut info as *mut libc::proc_threadinfo).cast(),
+                size_of::<libc::proc_threadinfo>() as libc::c_int,
+            )
+        };
+        if written <= 0 {
+            continue;
+        }
+        // SAFETY: the kernel NUL-terminates pth_name within its buffer.
+        let name = unsafe { CStr::from_ptr(info.pth_name.as_ptr()) }
+            .to_string_lossy()
+            .into_owned();
+        let state = match info.pth_run_state {
+            libc::TH_STATE_RUNNING => "running",
+            libc::TH_STATE_STOPPED => "stopped",
+            libc::TH_STATE_WAITING => "waiting",
+            libc::TH_STATE_UNINTERRUPTIBLE => "uninterruptible",
+            libc::TH_STATE_HALTED => "halted",
+            _ => "unknown",
+        };
+        threads.insert(
+            handle,
+            ThreadInfo {
+                name: if name.is_empty() { format!("thread_{:x}", handle) } else { name },
+                state: state.to_string(),
+            },
+        );
+    }
+    threads
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+pub fn snapshot() -> HashMap<u64, ThreadInfo> {
+    HashMap::new()
+}
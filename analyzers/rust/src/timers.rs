@@ -0,0 +1,117 @@
+//! Timers a target left running on its own threads. A target's tokio runtime or timer
+//! thread lives in its own library and cannot be asked about its timers, so the threads
+//! it left behind are watched for a short window instead: one that keeps waking from
+//! sleep until the window ends is driving a timer (a `tokio::time::interval` loop, a
+//! thread looping over `sleep`), and the gaps between its wake-ups give the period.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// How long left-behind threads are watched; timers firing less than three times in it
+/// are not told apart from threads blocked for good.
+#[cfg(target_os = "linux")]
+const WINDOW: std::time::Duration = std::time::Duration::from_millis(300);
+#[cfg(target_os = "linux")]
+const POLL: std::time::Duration = std::time::Duration::from_millis(5);
+
+/// Calls that set a pace in target source.
+const TIMER_CALLS: &[&str] = &["interval(", "interval_at(", "sleep("];
+
+/// Period in milliseconds of each thread in `tids` that kept waking through the window;
+/// `None` where thread wake-ups cannot be read.
+#[cfg(target_os = "linux")]
+pub fn periodic(tids: &[u64]) -> Option<HashMap<u64, f64>> {
+    use std::time::Instant;
+
+    let mut counts: HashMap<u64, u64> = tids
+        .iter()
+        .filter_map(|tid| Some((*tid, voluntary_switches(*tid)?)))
+        .collect();
+    let mut wakeups: HashMap<u64, Vec<Instant>> = HashMap::new();
+    let start = Instant::now();
+    while !counts.is_empty() && start.elapsed() < WINDOW {
+        std::thread::sleep(POLL);
+        for (tid, count) in counts.iter_mut() {
+            match voluntary_switches(*tid) {
+                Some(now) if now > *count => {
+                    *count = now;
+                    wakeups.entry(*tid).or_default().push(Instant::now());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let ended = Instant::now();
+    Some(
+        wakeups
+            .into_iter()
+            .filter_map(|(tid, times)| {
+                let millis = |duration: std::time::Duration| duration.as_secs_f64() * 1000.0;
+                let gaps: Vec<f64> = times.windows(2).map(|pair| millis(pair[1] - pair[0])).collect();
+                let longest = gaps.iter().copied().fold(0.0, f64::max);
+                // A runtime worker may switch more than once per tick: gaps shorter than
+                // half the longest belong to the same tick as the one after them.
+                let mut ticks = Vec::new();
+                let mut pending = 0.0;
+                for gap in gaps {
+                    pending += gap;
+                    if pending >= longest / 2.0 {
+                        ticks.push(pending);
+                        pending = 0.0;
+                    }
+                }
+                // A burst of wake-ups that then stops is I/O or shutdown, not a timer.
+                let still_waking = millis(ended - *times.last()?) <= longest + millis(POLL) * 2.0;
+                (ticks.len() >= 2 && still_waking).then(|| (tid, ticks.iter().sum::<f64>() / ticks.len() as f64))
+            })
+            .collect(),
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn voluntary_switches(tid: u64) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/self/task/{}/status", tid)).ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("voluntary_ctxt_switches:"))
+        .and_then(|count| count.trim().parse().ok())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn periodic(_tids: &[u64]) -> Option<HashMap<u64, f64>> {
+    None
+}
+
+/// `file:line` of the first timer or sleep call in a workspace target's function, when
+/// its source can be found.
+pub fn timer_site(target: &str) -> Option<String> {
+    let (_, module_path, function) = crate::parse_rust_target(target).ok()?;
+    let crate_dir = crate::find_workspace_root().ok()?.join("tests").join("rust");
+    let module = module_path.split("::").next()?;
+    let lib = fs::read_to_string(crate_dir.join("lib.rs")).ok()?;
+    let relative = module_file(&lib, module).unwrap_or_else(|| format!("{}.rs", module));
+    let source = fs::read_to_string(crate_dir.join(&relative)).ok()?;
+
+    let lines: Vec<&str> = source.lines().collect();
+    let start = lines.iter().position(|line| {
+        line.split("fn ")
+            .skip(1)
+            .any(|rest| rest.strip_prefix(function.as_str()).is_some_and(|after| after.starts_with(['(', '<'])))
+    })?;
+    let line = (start + 1..lines.len())
+        .take_while(|index| !lines[*index].starts_with('}'))
+        .find(|index| TIMER_CALLS.iter().any(|call| lines[*index].contains(call)))?;
+    Some(format!("{}:{}", Path::new("tests/rust").join(relative).display(), line + 1))
+}
+
+/// File of `module` from its `#[path = "..."]` attribute in `lib.rs`.
+fn module_file(lib: &str, module: &str) -> Option<String> {
+    let lines: Vec<&str> = lib.lines().collect();
+    let declaration = format!("mod {};", module);
+    let index = lines.iter().position(|line| line.trim().ends_with(&declaration))?;
+    let attribute = lines.get(index.checked_sub(1)?)?.trim();
+    let path = attribute.strip_prefix("#[path = \"")?.strip_suffix("\"]")?;
+    Some(path.to_string())
+}
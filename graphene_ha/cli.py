@@ -39,12 +39,36 @@ def _ensure_rust_binary():
     return binary_path
 
 
+def _rust_command(args):
+    """The Rust binary with the global logging flags."""
+    cmd = [str(_ensure_rust_binary())]
+    _append_if_set(cmd, "--log-format", args.log_format)
+    _append_if_set(cmd, "--log-file", args.log_file)
+    return cmd
+
+
+def _run_until_done(cmd):
+    """Run the Rust binary, waiting through Ctrl+C while it writes its partial reports."""
+    process = subprocess.Popen(cmd)
+    while True:
+        try:
+            return process.wait()
+        except KeyboardInterrupt:
+            # The binary got the Ctrl+C too and exits once its reports are written.
+            continue
+
+
 def _run_analyze(args):
     """Delegate analyze command to Rust binary."""
-    cmd = [str(_ensure_rust_binary()), "analyze", "--target", args.target]
+    cmd = [*_rust_command(args), "analyze", "--target", args.target]
 
     for inp in args.input:
         cmd.extend(["--input", inp])
+    for path in args.input_file:
+        cmd.extend(["--input-file", path])
+    _append_if_set(cmd, "--input-dir", args.input_dir)
+    for arguments in args.input_args:
+        cmd.extend(["--input-args", arguments])
 
     cmd.extend(["--repeat", str(args.repeat)])
     cmd.extend(["--timeout", str(args.timeout)])
@@ -57,14 +81,71 @@ def _run_analyze(args):
     if args.verbose:
         cmd.append("--verbose")
 
-    result = subprocess.run(cmd, check=False)
-    return result.returncode
+    if args.compress:
+        cmd.append("--compress")
+
+    _append_if_set(cmd, "--transport", args.transport)
+
+    if args.daemon:
+        cmd.append("--daemon")
+
+    _append_if_set(cmd, "--sandbox", args.sandbox)
+    for assignment in args.sandbox_image:
+        cmd.extend(["--sandbox-image", assignment])
+    _append_if_set(cmd, "--bridge-memory", args.bridge_memory)
+    _append_if_set(cmd, "--bridge-cpu", args.bridge_cpu)
+    _append_if_set(cmd, "--bridge-processes", args.bridge_processes)
+    _append_if_set(cmd, "--constructor-args", args.constructor_args)
+
+    for assignment in args.env:
+        cmd.extend(["--env", assignment])
+    for pattern in args.env_passthrough:
+        cmd.extend(["--env-passthrough", pattern])
+
+    _append_if_set(cmd, "--workdir", args.workdir)
+    if args.target_workdir:
+        cmd.append("--target-workdir")
+
+    if args.chaos:
+        cmd.append("--chaos")
+    _append_if_set(cmd, "--chaos-seed", args.chaos_seed)
+    _append_if_set(cmd, "--observe", args.observe)
+    _append_if_set(cmd, "--memory-threshold", args.memory_threshold)
+    _append_if_set(cmd, "--race", args.race)
+    if args.detect_processes:
+        cmd.append("--detect-processes")
+    if args.treat_daemon_as_escape:
+        cmd.append("--treat-daemon-as-escape")
+    _append_if_set(cmd, "--settle", args.settle)
+    _append_if_set(cmd, "--settle-checks", args.settle_checks)
+    _append_if_set(cmd, "--thread-threshold", args.thread_threshold)
+    if args.detect_fds is not None:
+        cmd.append(f"--detect-fds={args.detect_fds}")
+    if args.capture_stacks is not None:
+        cmd.append(f"--capture-stacks={args.capture_stacks}")
+
+    _append_if_set(cmd, "--oracle", args.oracle)
+    _append_if_set(cmd, "--output-format", args.output_format)
+    if args.quiet:
+        cmd.append("--quiet")
+    if args.no_report:
+        cmd.append("--no-report")
+    _append_if_set(cmd, "--format", args.format)
+    _append_if_set(cmd, "--report-template", args.report_template)
+    _append_if_set(cmd, "--report-name", args.report_name)
+    _append_if_set(cmd, "--save-baseline", args.save_baseline)
+    _append_if_set(cmd, "--history", args.history)
+    _append_if_set(cmd, "--severity-rules", args.severity_rules)
+    for policy in args.fail_on:
+        cmd.extend(["--fail-on", policy])
+
+    return _run_until_done(cmd)
 
 
 def _run_run_all(args):
     """Delegate run-all command to Rust binary."""
     cmd = [
-        str(_ensure_rust_binary()),
+        *_rust_command(args),
         "run-all",
         "--test-dir",
         str(ROOT_DIR / "tests"),
@@ -72,20 +153,252 @@ def _run_run_all(args):
         str(args.generate),
         "--output-dir",
         args.log_dir,
+        "--repeat",
+        str(args.repeat),
+        "--timeout",
+        str(args.timeout),
     ]
+    for inp in args.input:
+        cmd.extend(["--input", inp])
+    for path in args.input_file:
+        cmd.extend(["--input-file", path])
+    _append_if_set(cmd, "--input-dir", args.input_dir)
+    for arguments in args.input_args:
+        cmd.extend(["--input-args", arguments])
 
     _append_if_set(cmd, "--language", args.language)
 
     if hasattr(args, "analysis_mode"):
         cmd.extend(["--analysis-mode", args.analysis_mode])
 
+    if args.self_test:
+        cmd.append("--self-test")
+
+    _append_if_set(cmd, "--batch-size", args.batch_size)
+    _append_if_set(cmd, "--jobs", args.jobs)
+    for assignment in args.priority:
+        cmd.extend(["--priority", assignment])
+    for assignment in args.language_jobs:
+        cmd.extend(["--language-jobs", assignment])
+    _append_if_set(cmd, "--retries", args.retries)
+    _append_if_set(cmd, "--state", args.state)
+    _append_if_set(cmd, "--junit", args.junit)
+    _append_if_set(cmd, "--history", args.history)
+    _append_if_set(cmd, "--metrics-addr", args.metrics_addr)
+    _append_if_set(cmd, "--since", args.since)
+    for pattern in args.filter:
+        cmd.extend(["--filter", pattern])
+    for pattern in args.exclude:
+        cmd.extend(["--exclude", pattern])
+    if args.verify:
+        cmd.append("--verify")
+
+    if args.compress:
+        cmd.append("--compress")
+
+    _append_if_set(cmd, "--transport", args.transport)
+
+    if args.daemon:
+        cmd.append("--daemon")
+
+    _append_if_set(cmd, "--sandbox", args.sandbox)
+    for assignment in args.sandbox_image:
+        cmd.extend(["--sandbox-image", assignment])
+    _append_if_set(cmd, "--bridge-memory", args.bridge_memory)
+    _append_if_set(cmd, "--bridge-cpu", args.bridge_cpu)
+    _append_if_set(cmd, "--bridge-processes", args.bridge_processes)
+    _append_if_set(cmd, "--constructor-args", args.constructor_args)
+
+    for assignment in args.env:
+        cmd.extend(["--env", assignment])
+    for pattern in args.env_passthrough:
+        cmd.extend(["--env-passthrough", pattern])
+
+    _append_if_set(cmd, "--workdir", args.workdir)
+    if args.target_workdir:
+        cmd.append("--target-workdir")
+
+    if args.chaos:
+        cmd.append("--chaos")
+    _append_if_set(cmd, "--chaos-seed", args.chaos_seed)
+    _append_if_set(cmd, "--observe", args.observe)
+    _append_if_set(cmd, "--memory-threshold", args.memory_threshold)
+    _append_if_set(cmd, "--race", args.race)
+    if args.detect_processes:
+        cmd.append("--detect-processes")
+    if args.treat_daemon_as_escape:
+        cmd.append("--treat-daemon-as-escape")
+    _append_if_set(cmd, "--settle", args.settle)
+    _append_if_set(cmd, "--settle-checks", args.settle_checks)
+    _append_if_set(cmd, "--thread-threshold", args.thread_threshold)
+    if args.detect_fds is not None:
+        cmd.append(f"--detect-fds={args.detect_fds}")
+    if args.capture_stacks is not None:
+        cmd.append(f"--capture-stacks={args.capture_stacks}")
+    _append_if_set(cmd, "--format", args.format)
+    _append_if_set(cmd, "--report-template", args.report_template)
+
+    return _run_until_done(cmd)
+
+
+def _run_doctor(args):
+    """Delegate doctor command to Rust binary."""
+    cmd = [*_rust_command(args), "doctor"]
+    _append_if_set(cmd, "--language", args.language)
+
+    result = subprocess.run(cmd, check=False)
+    return result.returncode
+
+
+def _run_diff(args):
+    """Delegate diff command to Rust binary."""
+    cmd = [*_rust_command(args), "diff", "--baseline", args.baseline, "--current", args.current]
+
+    result = subprocess.run(cmd, check=False)
+    return result.returncode
+
+
+def _run_trends(args):
+    """Delegate trends command to Rust binary."""
+    cmd = [*_rust_command(args), "trends", "--history", args.history, "--sessions", str(args.sessions)]
+    _append_if_set(cmd, "--target", args.target)
+    if args.changed:
+        cmd.append("--changed")
+
+    result = subprocess.run(cmd, check=False)
+    return result.returncode
+
+
+def _run_bench(args):
+    """Delegate bench command to Rust binary."""
+    cmd = [
+        *_rust_command(args),
+        "bench",
+        "--test-dir",
+        args.test_dir,
+        "--generate",
+        str(args.generate),
+        "--repeat",
+        str(args.repeat),
+        "--timeout",
+        str(args.timeout),
+        "--output-dir",
+        args.output_dir,
+        "--analysis-mode",
+        args.analysis_mode,
+    ]
+    _append_if_set(cmd, "--language", args.language)
+    _append_if_set(cmd, "--batch-size", args.batch_size)
+    _append_if_set(cmd, "--baseline", args.baseline)
+
+    result = subprocess.run(cmd, check=False)
+    return result.returncode
+
+
+def _run_scan(args):
+    """Delegate scan command to Rust binary."""
+    cmd = [*_rust_command(args), "scan", args.dir, "--output-dir", args.log_dir]
+    _append_if_set(cmd, "--language", args.language)
+    _append_if_set(cmd, "--output-format", args.output_format)
+    _append_if_set(cmd, "--since", args.since)
+    if args.verbose:
+        cmd.append("--verbose")
+    if args.no_report:
+        cmd.append("--no-report")
+
+    result = subprocess.run(cmd, check=False)
+    return result.returncode
+
+
+def _run_self_test(args):
+    """Delegate self-test command to Rust binary."""
+    cmd = [*_rust_command(args), "self-test", "--analysis-mode", args.analysis_mode]
+    _append_if_set(cmd, "--language", args.language)
+
+    result = subprocess.run(cmd, check=False)
+    return result.returncode
+
+
+def _run_compare(args):
+    """Delegate compare command to Rust binary."""
+    cmd = [
+        *_rust_command(args),
+        "compare",
+        "--manifest",
+        args.manifest,
+        "--repeat",
+        str(args.repeat),
+        "--timeout",
+        str(args.timeout),
+        "--output-dir",
+        args.log_dir,
+        "--analysis-mode",
+        args.analysis_mode,
+    ]
+
+    for inp in args.input:
+        cmd.extend(["--input", inp])
+
+    _append_if_set(cmd, "--sandbox", args.sandbox)
+    for assignment in args.sandbox_image:
+        cmd.extend(["--sandbox-image", assignment])
+    _append_if_set(cmd, "--bridge-memory", args.bridge_memory)
+    _append_if_set(cmd, "--bridge-cpu", args.bridge_cpu)
+    _append_if_set(cmd, "--bridge-processes", args.bridge_processes)
+    _append_if_set(cmd, "--constructor-args", args.constructor_args)
+
+    for assignment in args.env:
+        cmd.extend(["--env", assignment])
+    for pattern in args.env_passthrough:
+        cmd.extend(["--env-passthrough", pattern])
+
+    _append_if_set(cmd, "--workdir", args.workdir)
+    if args.target_workdir:
+        cmd.append("--target-workdir")
+
+    if args.chaos:
+        cmd.append("--chaos")
+    _append_if_set(cmd, "--chaos-seed", args.chaos_seed)
+    _append_if_set(cmd, "--observe", args.observe)
+    _append_if_set(cmd, "--memory-threshold", args.memory_threshold)
+    if args.detect_processes:
+        cmd.append("--detect-processes")
+    if args.treat_daemon_as_escape:
+        cmd.append("--treat-daemon-as-escape")
+    _append_if_set(cmd, "--settle", args.settle)
+    _append_if_set(cmd, "--settle-checks", args.settle_checks)
+    _append_if_set(cmd, "--thread-threshold", args.thread_threshold)
+    if args.detect_fds is not None:
+        cmd.append(f"--detect-fds={args.detect_fds}")
+    if args.capture_stacks is not None:
+        cmd.append(f"--capture-stacks={args.capture_stacks}")
+
+    result = subprocess.run(cmd, check=False)
+    return result.returncode
+
+
+def _run_build_bridges(args):
+    """Delegate build-bridges command to Rust binary."""
+    cmd = [*_rust_command(args), "build-bridges"]
+    _append_if_set(cmd, "--language", args.language)
+
+    result = subprocess.run(cmd, check=False)
+    return result.returncode
+
+
+def _run_install_bridges(args):
+    """Delegate install-bridges command to Rust binary."""
+    cmd = [*_rust_command(args), "install-bridges", "--version", args.version]
+    _append_if_set(cmd, "--url", args.url)
+    _append_if_set(cmd, "--language", args.language)
+
     result = subprocess.run(cmd, check=False)
     return result.returncode
 
 
 def _run_list(args):
     """Delegate list command to Rust binary."""
-    cmd = [str(_ensure_rust_binary()), "list"]
+    cmd = [*_rust_command(args), "list"]
 
     if args.detailed:
         cmd.append("--detailed")
@@ -94,9 +407,47 @@ def _run_list(args):
     return result.returncode
 
 
+def _run_rules(args):
+    """Delegate rules command to Rust binary."""
+    cmd = [*_rust_command(args), "rules", "list"]
+    _append_if_set(cmd, "--language", args.language)
+    _append_if_set(cmd, "--output-format", args.output_format)
+
+    result = subprocess.run(cmd, check=False)
+    return result.returncode
+
+
+def _run_fix(args):
+    """Delegate fix command to Rust binary; the diff is printed on stdout."""
+    cmd = [*_rust_command(args), "fix", "--target", args.target]
+    _append_if_set(cmd, "--language", args.language)
+    for rule in args.rule or []:
+        cmd.extend(["--rule", rule])
+    _append_if_set(cmd, "--min-confidence", args.min_confidence)
+    if args.dry_run:
+        cmd.append("--dry-run")
+    if args.verbose:
+        cmd.append("--verbose")
+
+    result = subprocess.run(cmd, check=False)
+    return result.returncode
+
+
+def _run_lsp(args):
+    """Delegate lsp command to Rust binary; the editor talks to it over the inherited stdio."""
+    cmd = [*_rust_command(args), "lsp"]
+
+    if args.verbose:
+        cmd.append("--verbose")
+    _append_if_set(cmd, "--metrics-addr", args.metrics_addr)
+
+    result = subprocess.run(cmd, check=False)
+    return result.returncode
+
+
 def _run_clear(args):
     """Delegate clear command to Rust binary."""
-    cmd = [str(_ensure_rust_binary()), "clear", "--output-dir", args.log_dir]
+    cmd = [*_rust_command(args), "clear", "--output-dir", args.log_dir]
     _append_if_set(cmd, "--archive-csv", args.archive_csv)
 
     result = subprocess.run(cmd, check=False)
@@ -114,17 +465,34 @@ def main():
   uv run graphene run-all --language python
   uv run graphene run-all --generate 10
   uv run graphene list --detailed
+  uv run graphene doctor
+  uv run graphene self-test --language python
+  uv run graphene compare ports/manifest.json
+  uv run graphene scan path/to/project
+  uv run graphene build-bridges
+  uv run graphene install-bridges --version 1.0.0
     uv run graphene clear --log-dir artifacts/logs
     uv run graphene clear --log-dir artifacts/logs --archive-csv artifacts/logs/cleared_results.csv
 """
     )
 
+    parser.add_argument("--log-format", choices=["text", "json"], help="Log as text or as one JSON object per line (default: text)")
+    parser.add_argument("--log-file", help="Also append the log to this file")
+
     subparsers = parser.add_subparsers(dest="command", help="Command to execute")
 
     # Analyze command
     analyze_parser = subparsers.add_parser("analyze", help="Analyze a function for object escapes")
-    analyze_parser.add_argument("target", help="Function target in format: module:function or file.ext:function")
+    analyze_parser.add_argument("target", help="Function target in format: module:function or file.ext:function; a bare file.ext analyzes each of its public functions")
     analyze_parser.add_argument("--input", action="append", default=[], help="Input data for the function (repeatable)")
+    analyze_parser.add_argument(
+        "--input-file",
+        action="append",
+        default=[],
+        help="File of inputs: a JSON array, or one input per non-blank line (repeatable)",
+    )
+    analyze_parser.add_argument("--input-dir", help="Directory whose files are each one input (binary-safe)")
+    analyze_parser.add_argument("--input-args", action="append", default=[], help="JSON array of arguments for one call of a multi-parameter target (repeatable)")
     analyze_parser.add_argument("--repeat", type=int, default=3, help="Repeat each input N times (default: 3)")
     analyze_parser.add_argument("--timeout", type=float, default=5.0, help="Timeout per execution in seconds (default: 5.0)")
     analyze_parser.add_argument("--log-dir", default="artifacts/logs", help="Output directory for reports (default: artifacts/logs)")
@@ -136,11 +504,64 @@ def main():
         help="Analysis mode: dynamic, static, or both (default: both).",
     )
     analyze_parser.add_argument("--verbose", action="store_true", help="Enable verbose logging")
+    analyze_parser.add_argument("--compress", action="store_true", help="Ask bridges to gzip their responses")
+    analyze_parser.add_argument("--transport", choices=["stdio", "socket"], help="Bridge transport (default: stdio)")
+    analyze_parser.add_argument("--daemon", action="store_true", help="Keep bridge processes running between targets")
+    analyze_parser.add_argument("--sandbox", choices=["docker"], help="Run each bridge in a network-less, read-only container")
+    analyze_parser.add_argument("--sandbox-image", action="append", default=[], help="LANG=IMAGE container image for a language's bridge (repeatable)")
+    analyze_parser.add_argument("--bridge-memory", type=int, help="Cap each bridge process at this many MB of memory (address space on Unix)")
+    analyze_parser.add_argument("--bridge-cpu", type=int, help="Kill a bridge process after this many seconds of CPU time")
+    analyze_parser.add_argument("--bridge-processes", type=int, help="Cap how many processes a bridge may run at once")
+    analyze_parser.add_argument("--env", action="append", default=[], help="KEY=VALUE set for bridges and targets (repeatable)")
+    analyze_parser.add_argument("--env-passthrough", action="append", default=[], help="Inherit matching environment variables, e.g. AWS_* (repeatable)")
+    analyze_parser.add_argument("--constructor-args", help="JSON array of constructor arguments for instance-method targets")
+    analyze_parser.add_argument("--workdir", help="Working directory for bridges and targets")
+    analyze_parser.add_argument("--target-workdir", action="store_true", help="Run each target from its source file's directory")
+    analyze_parser.add_argument("--chaos", action="store_true", help="Inject small random delays around spawn/lock/channel operations")
+    analyze_parser.add_argument("--chaos-seed", type=int, help="Replay a previous perturbation schedule (implies --chaos)")
+    analyze_parser.add_argument("--observe", type=float, help="Seconds to keep watching threads/tasks an execution left running")
+    analyze_parser.add_argument("--memory-threshold", type=float, help="Memory growth in MB over an input's repeats past which steady growth is a leak (default: 1)")
+    analyze_parser.add_argument("--race", type=int, metavar="RUNS", help="Run each input at least RUNS times (2 or more) and report disagreeing runs on shared state as potential data races")
+    analyze_parser.add_argument("--detect-processes", action="store_true", help="Report subprocesses an execution left running or unreaped")
+    analyze_parser.add_argument("--treat-daemon-as-escape", action="store_true", help="Count idle background threads as escapes, not only threads that block exit")
+    analyze_parser.add_argument("--settle", type=int, metavar="MS", help="Milliseconds threads an execution started get to exit before they are reported")
+    analyze_parser.add_argument("--settle-checks", type=int, metavar="N", help="Re-checks for left-behind threads within the settle period")
+    analyze_parser.add_argument("--thread-threshold", type=int, metavar="N", help="Left-behind threads an execution needs before it counts as an escape")
+    analyze_parser.add_argument("--detect-fds", nargs="?", const="true", choices=["true", "false"], help="Report file descriptors an execution leaves open (bridge default when omitted)")
+    analyze_parser.add_argument("--capture-stacks", nargs="?", const="true", choices=["true", "false"], help="Capture the stacks of escaped threads and tasks (bridge default when omitted)")
+    analyze_parser.add_argument("--oracle", help="Command run with each input and output as $1 and $2; nonzero exit fails the execution")
+    analyze_parser.add_argument("--output-format", "--output", choices=["text", "json"], help="Print a text summary or the full response as JSON (default: text)")
+    analyze_parser.add_argument("-q", "--quiet", action="store_true", help="Print nothing and log only warnings; exit non-zero on escapes and crashes unless --fail-on says otherwise")
+    analyze_parser.add_argument("--no-report", action="store_true", help="Do not write report files to the log directory")
+    analyze_parser.add_argument("--format", choices=["markdown", "sarif"], help="Also write results.sarif (SARIF 2.1.0) with sarif (default: markdown)")
+    analyze_parser.add_argument("--report-template", help="Directory of Handlebars templates (*.hbs) rendered with each target's report data")
+    analyze_parser.add_argument("--report-name", help="Name of the report directory instead of session_<timestamp>_<target>_<session id>")
+    analyze_parser.add_argument("--save-baseline", help="Also write the JSON report to this file, for `diff --baseline`")
+    analyze_parser.add_argument("--history", help="Record the session in this SQLite database, for `trends`")
+    analyze_parser.add_argument(
+        "--fail-on",
+        action="append",
+        default=[],
+        choices=["any-escape", "genuine-escape", "high-severity", "crash"],
+        help="Exit non-zero when the analysis finds this (repeatable)",
+    )
+    analyze_parser.add_argument("--severity-rules", help="JSON file overriding the severity scoring rules")
     
     # Run-all command
     runall_parser = subparsers.add_parser("run-all", help="Run all test suites across languages")
     runall_parser.add_argument("--test-dir", default="tests", help="Root test directory (default: tests)")
     runall_parser.add_argument("--generate", type=int, default=10, help="Number of inputs to generate per test (default: 10)")
+    runall_parser.add_argument("--input", action="append", default=[], help="Input for every target instead of generated ones (repeatable)")
+    runall_parser.add_argument(
+        "--input-file",
+        action="append",
+        default=[],
+        help="File of inputs for every target: a JSON array, or one input per non-blank line (repeatable)",
+    )
+    runall_parser.add_argument("--input-dir", help="Directory whose files are each one input for every target (binary-safe)")
+    runall_parser.add_argument("--input-args", action="append", default=[], help="JSON array of arguments for one call of every target (repeatable)")
+    runall_parser.add_argument("--repeat", type=int, default=1, help="Repeat each input N times (default: 1)")
+    runall_parser.add_argument("--timeout", type=float, default=5.0, help="Timeout per execution in seconds (default: 5.0)")
     runall_parser.add_argument("--log-dir", default="artifacts/logs", help="Output directory for reports (default: artifacts/logs)")
     runall_parser.add_argument("--language", help="Filter by language (python, java, javascript, go, rust)")
     runall_parser.add_argument(
@@ -150,11 +571,169 @@ def main():
         help="Analysis mode: dynamic, static, or both (default: both).",
     )
     runall_parser.add_argument("--verbose", action="store_true", help="Enable verbose logging")
+    runall_parser.add_argument("--self-test", action="store_true", help="Run each analyzer's self-test before its suite")
+    runall_parser.add_argument("--batch-size", type=int, help="Targets sent to one bridge process (1 starts a bridge per target)")
+    runall_parser.add_argument("--jobs", type=int, help="Batches analyzed at once, each in its own bridge process (default: 1)")
+    runall_parser.add_argument("--priority", action="append", default=[], help="PATTERN=N: analyze batches of targets matching a language or path text first, higher N earlier (repeatable)")
+    runall_parser.add_argument("--language-jobs", action="append", default=[], help="LANG=N: at most N batches of a language at once (repeatable)")
+    runall_parser.add_argument("--retries", type=int, help="Rerun targets whose bridge crashed or hung up to N times (default: 1)")
+    runall_parser.add_argument("--state", help="Job state file; an interrupted run started again with it only analyzes what is left")
+    runall_parser.add_argument("--junit", help="Also write a JUnit XML report to this path")
+    runall_parser.add_argument("--history", help="Record the session and per-target escape counts in this SQLite database, for `trends`")
+    runall_parser.add_argument("--metrics-addr", help="Serve Prometheus metrics at http://ADDR/metrics while the run lasts")
+    runall_parser.add_argument("--since", help="Only analyze targets whose function changed since this git ref (e.g. origin/main)")
+    runall_parser.add_argument("--filter", action="append", default=[], metavar="GLOB", help="Only analyze targets whose file path, file name or function matches GLOB, e.g. 'escape_*' (repeatable)")
+    runall_parser.add_argument("--exclude", action="append", default=[], metavar="GLOB", help="Skip targets whose file path, file name or function matches GLOB (repeatable)")
+    runall_parser.add_argument("--verify", action="store_true", help="Compare outcomes with the ones target names or ESCAPE:/SAFE: comments expect and report precision and recall")
+    runall_parser.add_argument("--compress", action="store_true", help="Ask bridges to gzip their responses")
+    runall_parser.add_argument("--transport", choices=["stdio", "socket"], help="Bridge transport (default: stdio)")
+    runall_parser.add_argument("--daemon", action="store_true", help="Keep bridge processes running between targets")
+    runall_parser.add_argument("--sandbox", choices=["docker"], help="Run each bridge in a network-less, read-only container")
+    runall_parser.add_argument("--sandbox-image", action="append", default=[], help="LANG=IMAGE container image for a language's bridge (repeatable)")
+    runall_parser.add_argument("--bridge-memory", type=int, help="Cap each bridge process at this many MB of memory (address space on Unix)")
+    runall_parser.add_argument("--bridge-cpu", type=int, help="Kill a bridge process after this many seconds of CPU time")
+    runall_parser.add_argument("--bridge-processes", type=int, help="Cap how many processes a bridge may run at once")
+    runall_parser.add_argument("--env", action="append", default=[], help="KEY=VALUE set for bridges and targets (repeatable)")
+    runall_parser.add_argument("--env-passthrough", action="append", default=[], help="Inherit matching environment variables, e.g. AWS_* (repeatable)")
+    runall_parser.add_argument("--constructor-args", help="JSON array of constructor arguments for instance-method targets")
+    runall_parser.add_argument("--workdir", help="Working directory for bridges and targets")
+    runall_parser.add_argument("--target-workdir", action="store_true", help="Run each target from its source file's directory")
+    runall_parser.add_argument("--chaos", action="store_true", help="Inject small random delays around spawn/lock/channel operations")
+    runall_parser.add_argument("--chaos-seed", type=int, help="Replay a previous perturbation schedule (implies --chaos)")
+    runall_parser.add_argument("--observe", type=float, help="Seconds to keep watching threads/tasks an execution left running")
+    runall_parser.add_argument("--memory-threshold", type=float, help="Memory growth in MB over an input's repeats past which steady growth is a leak (default: 1)")
+    runall_parser.add_argument("--race", type=int, metavar="RUNS", help="Run each input at least RUNS times (2 or more) and report disagreeing runs on shared state as potential data races")
+    runall_parser.add_argument("--detect-processes", action="store_true", help="Report subprocesses an execution left running or unreaped")
+    runall_parser.add_argument("--treat-daemon-as-escape", action="store_true", help="Count idle background threads as escapes, not only threads that block exit")
+    runall_parser.add_argument("--settle", type=int, metavar="MS", help="Milliseconds threads an execution started get to exit before they are reported")
+    runall_parser.add_argument("--settle-checks", type=int, metavar="N", help="Re-checks for left-behind threads within the settle period")
+    runall_parser.add_argument("--thread-threshold", type=int, metavar="N", help="Left-behind threads an execution needs before it counts as an escape")
+    runall_parser.add_argument("--detect-fds", nargs="?", const="true", choices=["true", "false"], help="Report file descriptors an execution leaves open (bridge default when omitted)")
+    runall_parser.add_argument("--capture-stacks", nargs="?", const="true", choices=["true", "false"], help="Capture the stacks of escaped threads and tasks (bridge default when omitted)")
+    runall_parser.add_argument("--format", choices=["markdown", "sarif"], help="Also write results.sarif (SARIF 2.1.0) with sarif (default: markdown)")
+    runall_parser.add_argument("--report-template", help="Directory of Handlebars templates (*.hbs) rendered with each target's report data")
     
     # List command
     list_parser = subparsers.add_parser("list", help="List available analyzers")
     list_parser.add_argument("--detailed", action="store_true", help="Show detailed analyzer capabilities")
 
+    # Rules command
+    rules_parser = subparsers.add_parser("rules", help="Show the static analysis rule catalog")
+    rules_subparsers = rules_parser.add_subparsers(dest="rules_command", required=True)
+    rules_list_parser = rules_subparsers.add_parser("list", help="List every rule with its id, description and remediation")
+    rules_list_parser.add_argument("--language", help="Only list the rules of this language's analyzer (python, java, javascript, go, rust)")
+    rules_list_parser.add_argument("--output-format", choices=["text", "json"], help="Print the rules as text or as a JSON array (default: text)")
+
+    # Fix command
+    fix_parser = subparsers.add_parser("fix", help="Apply the mechanical suggested fixes of a target's static findings as a unified diff")
+    fix_parser.add_argument("--target", required=True, help="Target function (file.ext:function), or a bare file to fix all of its functions")
+    fix_parser.add_argument("--language", help="Language (python, java, javascript, go, rust); detected when omitted")
+    fix_parser.add_argument("--rule", action="append", help="Only fix findings of this rule (repeatable, e.g. GH-RS-001)")
+    fix_parser.add_argument("--min-confidence", choices=["low", "medium", "high"], help="Lowest finding confidence to fix (default: high)")
+    fix_parser.add_argument("--dry-run", action="store_true", help="Print the diff without changing the file")
+    fix_parser.add_argument("--verbose", action="store_true", help="Enable verbose logging (to stderr)")
+
+    # Doctor command
+    doctor_parser = subparsers.add_parser("doctor", help="Check each analyzer's toolchain and bridge and run a self-test through it")
+    doctor_parser.add_argument("--language", help="Only check this language (python, java, javascript, go, rust)")
+
+    # Self-test command
+    selftest_parser = subparsers.add_parser("self-test", help="Check analyzers against bundled golden fixtures")
+    selftest_parser.add_argument("--language", help="Only test this language (python, java, javascript, go, rust)")
+    selftest_parser.add_argument(
+        "--analysis-mode",
+        choices=["dynamic", "static", "both"],
+        default="both",
+        help="Fixture modes to run (default: both).",
+    )
+
+    # Compare command
+    compare_parser = subparsers.add_parser("compare", help="Run one function implemented in several languages and report divergences")
+    compare_parser.add_argument("manifest", help="JSON manifest mapping function names to one target per language")
+    compare_parser.add_argument("--input", action="append", default=[], help="Input for functions without manifest inputs (repeatable)")
+    compare_parser.add_argument("--repeat", type=int, default=1, help="Repeat each input N times (default: 1)")
+    compare_parser.add_argument("--timeout", type=float, default=5.0, help="Timeout per execution in seconds (default: 5.0)")
+    compare_parser.add_argument("--log-dir", default="artifacts/logs", help="Output directory for the report (default: artifacts/logs)")
+    compare_parser.add_argument(
+        "--analysis-mode",
+        choices=["dynamic", "static", "both"],
+        default="both",
+        help="Compare static escape counts, dynamic runs, or both (default: both).",
+    )
+    compare_parser.add_argument("--sandbox", choices=["docker"], help="Run each bridge in a network-less, read-only container")
+    compare_parser.add_argument("--sandbox-image", action="append", default=[], help="LANG=IMAGE container image for a language's bridge (repeatable)")
+    compare_parser.add_argument("--bridge-memory", type=int, help="Cap each bridge process at this many MB of memory (address space on Unix)")
+    compare_parser.add_argument("--bridge-cpu", type=int, help="Kill a bridge process after this many seconds of CPU time")
+    compare_parser.add_argument("--bridge-processes", type=int, help="Cap how many processes a bridge may run at once")
+    compare_parser.add_argument("--env", action="append", default=[], help="KEY=VALUE set for bridges and targets (repeatable)")
+    compare_parser.add_argument("--env-passthrough", action="append", default=[], help="Inherit matching environment variables, e.g. AWS_* (repeatable)")
+    compare_parser.add_argument("--constructor-args", help="JSON array of constructor arguments for instance-method targets")
+    compare_parser.add_argument("--workdir", help="Working directory for bridges and targets")
+    compare_parser.add_argument("--target-workdir", action="store_true", help="Run each target from its source file's directory")
+    compare_parser.add_argument("--chaos", action="store_true", help="Inject small random delays around spawn/lock/channel operations")
+    compare_parser.add_argument("--chaos-seed", type=int, help="Replay a previous perturbation schedule (implies --chaos)")
+    compare_parser.add_argument("--observe", type=float, help="Seconds to keep watching threads/tasks an execution left running")
+    compare_parser.add_argument("--memory-threshold", type=float, help="Memory growth in MB over an input's repeats past which steady growth is a leak (default: 1)")
+    compare_parser.add_argument("--detect-processes", action="store_true", help="Report subprocesses an execution left running or unreaped")
+    compare_parser.add_argument("--treat-daemon-as-escape", action="store_true", help="Count idle background threads as escapes, not only threads that block exit")
+    compare_parser.add_argument("--settle", type=int, metavar="MS", help="Milliseconds threads an execution started get to exit before they are reported")
+    compare_parser.add_argument("--settle-checks", type=int, metavar="N", help="Re-checks for left-behind threads within the settle period")
+    compare_parser.add_argument("--thread-threshold", type=int, metavar="N", help="Left-behind threads an execution needs before it counts as an escape")
+    compare_parser.add_argument("--detect-fds", nargs="?", const="true", choices=["true", "false"], help="Report file descriptors an execution leaves open (bridge default when omitted)")
+    compare_parser.add_argument("--capture-stacks", nargs="?", const="true", choices=["true", "false"], help="Capture the stacks of escaped threads and tasks (bridge default when omitted)")
+
+    # Diff command
+    diff_parser = subparsers.add_parser("diff", help="List new, fixed and persisting escapes between two runs; fails on new ones")
+    diff_parser.add_argument("--baseline", required=True, help="Baseline report.json, session directory, or directory of sessions")
+    diff_parser.add_argument("--current", required=True, help="Current run, in the same forms as --baseline")
+
+    # Trends command
+    scan_parser = subparsers.add_parser("scan", help="Run static analysis over every source file of a project into one report")
+    scan_parser.add_argument("dir", help="Project directory; .gitignore and .grapheneignore are respected")
+    scan_parser.add_argument("--log-dir", default="artifacts/logs", help="Output directory for the report (default: artifacts/logs)")
+    scan_parser.add_argument("--language", help="Only scan files of this language (python, java, javascript, go, rust)")
+    scan_parser.add_argument("--output-format", choices=["text", "json"], help="Print a text summary or the aggregated report as JSON (default: text)")
+    scan_parser.add_argument("--verbose", action="store_true", help="Enable verbose logging")
+    scan_parser.add_argument("--no-report", action="store_true", help="Do not write report files")
+    scan_parser.add_argument("--since", help="Only analyze files and functions changed since this git ref (e.g. origin/main)")
+
+    bench_parser = subparsers.add_parser("bench", help="Score each analyzer against the labeled test targets, per analysis mode, with latency")
+    bench_parser.add_argument("--test-dir", default="tests", help="Root test directory (default: tests)")
+    bench_parser.add_argument("--generate", type=int, default=10, help="Number of inputs to generate per test (default: 10)")
+    bench_parser.add_argument("--repeat", type=int, default=1, help="Repeat each input N times (default: 1)")
+    bench_parser.add_argument("--timeout", type=float, default=5.0, help="Timeout per execution in seconds (default: 5.0)")
+    bench_parser.add_argument("--output-dir", default="artifacts/bench", help="Directory bench.json and bench.md are written to (default: artifacts/bench)")
+    bench_parser.add_argument("--language", help="Filter by language (python, java, javascript, go, rust)")
+    bench_parser.add_argument(
+        "--analysis-mode",
+        choices=["dynamic", "static", "both"],
+        default="both",
+        help="Modes to benchmark, each separately (default: both)",
+    )
+    bench_parser.add_argument("--batch-size", type=int, help="Targets sent to one bridge process (default: 16)")
+    bench_parser.add_argument("--baseline", help="Earlier bench.json to show the change of each figure against")
+
+    trends_parser = subparsers.add_parser("trends", help="Print escape-count trends per target over the sessions recorded with --history")
+    trends_parser.add_argument("--history", required=True, help="SQLite database written by --history")
+    trends_parser.add_argument("--sessions", type=int, default=10, help="Number of most recent sessions to show (default: 10)")
+    trends_parser.add_argument("--target", help="Only show targets containing this text")
+    trends_parser.add_argument("--changed", action="store_true", help="Only show targets whose escape count changed")
+
+    # Build-bridges command
+    build_parser = subparsers.add_parser("build-bridges", help="Build the Go, Rust and Java bridges from this checkout")
+    build_parser.add_argument("--language", help="Only build this language's bridge (go, rust, java)")
+
+    # Install-bridges command
+    install_parser = subparsers.add_parser("install-bridges", help="Download prebuilt Go/Rust/Java bridges")
+    install_parser.add_argument("--version", required=True, help="Release version to install")
+    install_parser.add_argument("--url", help="Base URL of the release assets; {version} is substituted")
+    install_parser.add_argument("--language", help="Only install this language's bridge (go, rust, java)")
+
+    # LSP command
+    lsp_parser = subparsers.add_parser("lsp", help="Serve static escape findings to editors as LSP diagnostics over stdio")
+    lsp_parser.add_argument("--verbose", action="store_true", help="Enable verbose logging (to stderr)")
+    lsp_parser.add_argument("--metrics-addr", help="Serve Prometheus metrics at http://ADDR/metrics while the server runs")
+
     # Clear command
     clear_parser = subparsers.add_parser("clear", help="Clear log output directories")
     clear_parser.add_argument("--log-dir", default="artifacts/logs", help="Output directory for reports (default: artifacts/logs)")
@@ -173,6 +752,30 @@ def main():
         return _run_run_all(args)
     if args.command == "list":
         return _run_list(args)
+    if args.command == "rules":
+        return _run_rules(args)
+    if args.command == "fix":
+        return _run_fix(args)
+    if args.command == "doctor":
+        return _run_doctor(args)
+    if args.command == "self-test":
+        return _run_self_test(args)
+    if args.command == "compare":
+        return _run_compare(args)
+    if args.command == "diff":
+        return _run_diff(args)
+    if args.command == "scan":
+        return _run_scan(args)
+    if args.command == "trends":
+        return _run_trends(args)
+    if args.command == "bench":
+        return _run_bench(args)
+    if args.command == "build-bridges":
+        return _run_build_bridges(args)
+    if args.command == "install-bridges":
+        return _run_install_bridges(args)
+    if args.command == "lsp":
+        return _run_lsp(args)
     if args.command == "clear":
         return _run_clear(args)
 
@@ -10,8 +10,14 @@ from dataclasses import dataclass
 from typing import Any, Dict
 
 
+class TargetArguments(tuple):
+    """Positional arguments of one call of a target taking several parameters."""
+
+
 def _invoke_target(func, input_data, fixed_kwargs):
-    """Invoke target function, supporting both zero-arg and single-arg targets."""
+    """Invoke target function, supporting zero-arg, single-arg and multi-arg targets."""
+    if isinstance(input_data, TargetArguments):
+        return func(*input_data, **fixed_kwargs)
     if input_data is None:
         try:
             return func(**fixed_kwargs)
@@ -23,12 +29,15 @@ def _invoke_target(func, input_data, fixed_kwargs):
 
 def _capture_invocation(func, input_data, fixed_kwargs):
     """Execute target and capture stdout/stderr along with return type metadata."""
-    buffer = io.StringIO()
-    with redirect_stdout(buffer), redirect_stderr(buffer):
+    stdout_buffer = io.StringIO()
+    stderr_buffer = io.StringIO()
+    with redirect_stdout(stdout_buffer), redirect_stderr(stderr_buffer):
         returned_value = _invoke_target(func, input_data, fixed_kwargs)
-    captured = buffer.getvalue()
+    stdout_text = stdout_buffer.getvalue()
+    stderr_text = stderr_buffer.getvalue()
+    captured = stdout_text + stderr_text
     output = captured if captured else str(returned_value)
-    return output, returned_value
+    return output, returned_value, stdout_text, stderr_text
 
 
 @dataclass
@@ -44,6 +53,8 @@ class TestResult:
     escape_details: str = ""
     returned_value_type: str = ""  # Type of returned value for escape analysis
     raised_exception: bool = False
+    stdout: str = ""  # Target stdout captured during this invocation
+    stderr: str = ""  # Target stderr captured during this invocation
 
 
 class PythonFunctionTestHarness:
@@ -76,6 +87,8 @@ class PythonFunctionTestHarness:
         escape_details="",
         returned_value_type="",
         raised_exception=False,
+        stdout="",
+        stderr="",
     ):
         return TestResult(
             input_data=input_data,
@@ -89,6 +102,8 @@ class PythonFunctionTestHarness:
             escape_details=escape_details,
             returned_value_type=returned_value_type,
             raised_exception=raised_exception,
+            stdout=stdout,
+            stderr=stderr,
         )
 
     def run_test(self, input_data):
@@ -127,8 +142,10 @@ class PythonFunctionTestHarness:
         result_queue = ctx.Queue()
 
         def worker(func, input_data, fixed_kwargs, result_queue):
+            stdout_text = ""
+            stderr_text = ""
             try:
-                output, returned_value = _capture_invocation(func, input_data, fixed_kwargs)
+                output, returned_value, stdout_text, stderr_text = _capture_invocation(func, input_data, fixed_kwargs)
                 error = ""
                 crashed = False
                 returned_type = type(returned_value).__name__
@@ -141,7 +158,9 @@ class PythonFunctionTestHarness:
                 "output": output,
                 "error": error,
                 "crashed": crashed,
-                "returned_type": returned_type
+                "returned_type": returned_type,
+                "stdout": stdout_text,
+                "stderr": stderr_text,
             })
             result_queue.close()
             result_queue.join_thread()
@@ -196,6 +215,8 @@ class PythonFunctionTestHarness:
             crashed=False,
             output=payload.get("output", ""),
             returned_value_type=payload.get("returned_type", ""),
+            stdout=payload.get("stdout", ""),
+            stderr=payload.get("stderr", ""),
         )
 
     def _run_in_thread(self, input_data):
@@ -205,12 +226,18 @@ class PythonFunctionTestHarness:
             "error": None,
             "completed": False,
             "returned_type": "",
+            "stdout": "",
+            "stderr": "",
         }
 
         def run_with_timeout():
             try:
-                output, returned_value = _capture_invocation(self.func, input_data, self.fixed_kwargs)
+                output, returned_value, stdout_text, stderr_text = _capture_invocation(
+                    self.func, input_data, self.fixed_kwargs
+                )
                 result["output"] = output
+                result["stdout"] = stdout_text
+                result["stderr"] = stderr_text
                 result["returned_type"] = self._analyze_return_type(returned_value)
                 result["completed"] = True
             except Exception as e:
@@ -252,6 +279,8 @@ class PythonFunctionTestHarness:
                 crashed=False,
                 output=result["output"] or "",
                 returned_value_type=result.get("returned_type", ""),
+                stdout=result.get("stdout", ""),
+                stderr=result.get("stderr", ""),
             )
         except Exception as e:
             return self._make_result(
@@ -267,8 +296,12 @@ class PythonFunctionTestHarness:
     def _run_in_main_thread(self, input_data):
         """Run function in main thread."""
         start = time.time()
+        stdout_text = ""
+        stderr_text = ""
         try:
-            output, returned_value = _capture_invocation(self.func, input_data, self.fixed_kwargs)
+            output, returned_value, stdout_text, stderr_text = _capture_invocation(
+                self.func, input_data, self.fixed_kwargs
+            )
             error = ""
             crashed = False
             returned_type = self._analyze_return_type(returned_value)
@@ -308,5 +341,7 @@ class PythonFunctionTestHarness:
             crashed=False,
             output=output,
             returned_value_type=returned_type,
+            stdout=stdout_text,
+            stderr=stderr_text,
         )
 
@@ -1,49 +1,190 @@
 use async_trait::async_trait;
+use futures::future::join_all;
 use anyhow::{Result, Context};
-use std::process::Stdio;
+use flate2::read::GzDecoder;
+use std::io::Read;
+use std::collections::{HashMap, HashSet};
+use std::process::{ExitStatus, Stdio};
 use std::path::PathBuf;
-use tokio::process::Command;
-use tokio::io::AsyncWriteExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::process::{Child, ChildStderr, ChildStdout, Command};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tracing::warn;
+use self::daemon::{DaemonBridge, DaemonFailure};
+use self::limits::ResourceLimits;
+use self::orphans::{orphan_vulnerability, reap_group, Orphan};
+use self::sandbox::{Container, Sandbox};
 use crate::protocol::{
+    capabilities,
+    option_keys,
+    AnalysisError,
+    AnalysisErrorKind,
+    AnalysisMode,
     AnalyzeRequest,
     AnalyzeResponse,
     AnalyzerInfo,
+    BatchAnalyzeRequest,
+    BatchAnalyzeResponse,
+    BridgeHandshake,
     EscapeDetails,
     ExecutionResult,
     ExecutionSummary,
     HealthCheckResponse,
+    InputEncoding,
+    GZIP_RESPONSE_MARKER,
+    HANDSHAKE_ENV,
+    PROTOCOL_VERSION,
 };
 
-/// Find workspace root by looking for Cargo.toml or using executable location
+/// Find the workspace root: the checkout the executable was built in, else the one
+/// around the current directory
 pub fn workspace_root() -> Result<PathBuf> {
-    // First try current_dir and look for Cargo.toml
-    if let Ok(cwd) = std::env::current_dir() {
-        if cwd.join("Cargo.toml").exists() {
-            return Ok(cwd);
-        }
-        // Try parent directories
-        let mut current = cwd.as_path();
-        while let Some(parent) = current.parent() {
-            if parent.join("Cargo.toml").exists() {
-                return Ok(parent.to_path_buf());
-            }
-            current = parent;
+    checkout_roots()
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Could not find workspace root (no Graphene HA checkout above the executable or the current directory)"))
+}
+
+/// Checkouts holding the bridges, in lookup order: the one the executable sits in
+/// (`target/<profile>/graphene-ha`, or `target/<profile>/deps/` for tests), then the one
+/// around the current directory.
+fn checkout_roots() -> Vec<PathBuf> {
+    // Cargo.toml alone could be any Rust project the tool is run from.
+    let is_checkout = |dir: &&std::path::Path| dir.join("Cargo.toml").is_file() && dir.join("analyzers").is_dir();
+    let from_exe = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.ancestors().skip(1).take(4).find(is_checkout).map(PathBuf::from));
+    let from_cwd = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| cwd.ancestors().find(is_checkout).map(PathBuf::from));
+
+    let mut roots: Vec<PathBuf> = from_exe.into_iter().collect();
+    roots.extend(from_cwd.filter(|root| !roots.contains(root)));
+    roots
+}
+
+/// Environment variable pointing a built-in analyzer at another bridge, e.g.
+/// `GRAPHENE_RUST_BRIDGE`.
+pub fn bridge_env(language: &str) -> String {
+    format!("GRAPHENE_{}_BRIDGE", language.to_uppercase())
+}
+
+/// Where a built-in bridge was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeSource {
+    /// The `[bridges]` table of the config file
+    Config,
+    /// `GRAPHENE_<LANG>_BRIDGE`
+    Environment,
+    /// Put in place by `install-bridges`
+    Installed,
+    /// The checkout of the executable or of the current directory
+    Checkout,
+}
+
+#[derive(Debug, Clone)]
+pub struct BridgeLocation {
+    /// The bridge script, binary or jar; may not exist for a checkout that has not built it.
+    pub path: PathBuf,
+    pub source: BridgeSource,
+    /// Checkout `path` is in, whose sources an in-repo build is checked against.
+    pub checkout: Option<PathBuf>,
+}
+
+impl BridgeLocation {
+    /// How the bridge was found, for `doctor`.
+    pub fn describe(&self, language: &str) -> String {
+        match self.source {
+            BridgeSource::Config => format!("[bridges] {} in the config file", language),
+            BridgeSource::Environment => format!("${}", bridge_env(language)),
+            BridgeSource::Installed => "installed by install-bridges".to_string(),
+            BridgeSource::Checkout => "checkout".to_string(),
         }
     }
-    
-    // Fallback: use executable location
-    if let Ok(exe_path) = std::env::current_exe() {
-        // Go up from target/release/graphene-ha to workspace root
-        if let Some(parent) = exe_path.parent().and_then(|p| p.parent()).and_then(|p| p.parent()) {
-            if parent.join("Cargo.toml").exists() {
-                return Ok(parent.to_path_buf());
-            }
+}
+
+/// Finds a built-in analyzer's bridge: the `[bridges]` entry of the config file, then
+/// `GRAPHENE_<LANG>_BRIDGE`, then a bridge installed by `install-bridges`, then the
+/// first of `relative` that exists under a checkout (see `checkout_roots`). A path given
+/// in the config file or the environment must exist. When no checkout has the bridge,
+/// its expected location in the first checkout is returned so errors can name it.
+pub fn locate_bridge(language: &str, relative: &[&str]) -> Result<BridgeLocation> {
+    let explicit = |path: PathBuf, source: BridgeSource| {
+        let location = BridgeLocation { path, source, checkout: None };
+        if !location.path.exists() {
+            anyhow::bail!(
+                "{} names {}, which does not exist",
+                location.describe(language),
+                location.path.display()
+            );
         }
+        Ok(location)
+    };
+    if let Some(path) = external::load_bridge_path(language)? {
+        return explicit(path, BridgeSource::Config);
+    }
+    if let Some(path) = std::env::var_os(bridge_env(language)).filter(|path| !path.is_empty()) {
+        return explicit(PathBuf::from(path), BridgeSource::Environment);
     }
-    
-    anyhow::bail!("Could not find workspace root (no Cargo.toml found)")
+    if let Some(path) = crate::install::installed_bridge(language) {
+        return Ok(BridgeLocation { path, source: BridgeSource::Installed, checkout: None });
+    }
+
+    let roots = checkout_roots();
+    let found = roots.iter().find_map(|root| {
+        relative
+            .iter()
+            .map(|relative| root.join(relative))
+            .find(|path| path.exists())
+            .map(|path| (path, root.clone()))
+    });
+    let (path, root) = match found {
+        Some(found) => found,
+        None => {
+            let root = workspace_root()?;
+            (root.join(relative[0]), root)
+        }
+    };
+    Ok(BridgeLocation { path, source: BridgeSource::Checkout, checkout: Some(root) })
 }
 
+/// Line prefix bridges write to stderr while they are alive. These lines feed the
+/// hang watchdog and are stripped from the captured stderr.
+pub const HEARTBEAT_PREFIX: &str = "GRAPHENE_HEARTBEAT";
+
+/// Line prefix bridges write to stderr ahead of each result as soon as it is known,
+/// so results survive a bridge that dies before printing its final response.
+pub const RESULT_PREFIX: &str = "GRAPHENE_RESULT";
+
+/// Silence period after which a bridge that has been sending heartbeats is killed.
+pub const DEFAULT_HEARTBEAT_TIMEOUT_SECONDS: f64 = 30.0;
+
+/// Fixed allowance on top of the per-test budget for bridge startup, target loading
+/// and compilation (the Go and Rust bridges build a runner binary first).
+pub const BRIDGE_OVERHEAD_SECONDS: f64 = 60.0;
+
+/// Overall deadline for a deep health check; compiled bridges may build a runner first.
+const SELF_TEST_DEADLINE_SECONDS: f64 = 300.0;
+
+/// Input passed to self-test targets.
+const SELF_TEST_INPUT: &str = "graphene-self-test";
+
+/// How long a bridge has to print its handshake; covers JVM startup.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Request options only bridges with the capability understand.
+const CAPABILITY_OPTIONS: &[(&str, &str)] = &[
+    (capabilities::GZIP, option_keys::ACCEPT_ENCODING),
+    (capabilities::UNIX_SOCKET, option_keys::TRANSPORT),
+    (capabilities::DAEMON, option_keys::DAEMON),
+    (capabilities::CHAOS, option_keys::CHAOS_SEED),
+    (capabilities::OBSERVE, option_keys::OBSERVE_SECONDS),
+    (capabilities::DETECT_PROCESSES, option_keys::DETECT_PROCESSES),
+    (capabilities::THREAD_CLASSIFICATION, option_keys::TREAT_DAEMON_AS_ESCAPE),
+];
+
 /// Standardized object escape capabilities exposed by all language analyzers.
 pub fn standardized_object_escape_capabilities() -> Vec<String> {
     vec![
@@ -67,6 +208,25 @@ pub trait Analyzer: Send + Sync {
     /// Analyze a target function
     async fn analyze(&self, request: AnalyzeRequest) -> Result<AnalyzeResponse>;
 
+    /// Analyze several targets, returning one result per request in request order.
+    /// Analyzers that can share one process across requests override this.
+    async fn analyze_batch(&self, requests: Vec<AnalyzeRequest>) -> Vec<Result<AnalyzeResponse>> {
+        let mut responses = Vec::with_capacity(requests.len());
+        for request in requests {
+            responses.push(self.analyze(request).await);
+        }
+        responses
+    }
+
+    /// Stop long-lived bridge processes started for daemon mode.
+    async fn shutdown(&self) {}
+
+    /// Check the analyzer end to end by running a known-good analysis through it.
+    /// Analyzers without a self-test target fall back to `health_check`.
+    async fn deep_health_check(&self) -> Result<HealthCheckResponse> {
+        self.health_check().await
+    }
+
     /// Get the language this analyzer supports
     fn language(&self) -> &str;
 
@@ -81,7 +241,125 @@ pub struct BridgeAnalyzer {
     bridge_cmd: Vec<String>,
     health_cmd: Option<Vec<String>>,
     analyzer_info: AnalyzerInfo,
-    can_handle_fn: fn(&str) -> bool,
+    can_handle_fn: Box<dyn Fn(&str) -> bool + Send + Sync>,
+    build: Option<BridgeBuild>,
+    self_test: Option<SelfTestTarget>,
+    /// Idle daemon bridge processes, reused by requests with the `daemon` option.
+    daemons: tokio::sync::Mutex<Vec<DaemonBridge>>,
+    /// Set once the bridge failed to start in daemon mode; later requests go straight
+    /// to one-shot processes.
+    daemon_unsupported: AtomicBool,
+    /// The bridge's handshake, run before its first request.
+    handshake: tokio::sync::OnceCell<BridgeHandshake>,
+    /// Capabilities already warned about as missing.
+    missing_warned: Mutex<HashSet<&'static str>>,
+    /// Bridge warnings already logged.
+    warnings_logged: Mutex<HashSet<String>>,
+}
+
+/// Trivial target shipped with the bridge that the deep health check analyzes.
+#[derive(Debug, Clone)]
+pub struct SelfTestTarget {
+    pub target: String,
+    /// Expected `escape_detected` outcome, for bridges that observe it reliably.
+    pub expect_escape: Option<bool>,
+}
+
+/// Compiled bridge artifact and the sources it is built from. Used to catch stale
+/// binaries left behind after updating the repo, which otherwise surface as
+/// confusing protocol parse errors.
+#[derive(Debug, Clone)]
+pub struct BridgeBuild {
+    pub artifact: PathBuf,
+    /// Source files or directories (searched recursively) the artifact depends on.
+    pub sources: Vec<PathBuf>,
+    pub rebuild_hint: String,
+}
+
+impl BridgeBuild {
+    /// Returns a description of why the artifact is stale, if any source is newer.
+    pub fn stale_reason(&self) -> Option<String> {
+        let artifact_mtime = std::fs::metadata(&self.artifact).and_then(|m| m.modified()).ok()?;
+        let (newest_path, newest_mtime) = self
+            .sources
+            .iter()
+            .filter_map(|source| newest_modified(source))
+            .max_by_key(|(_, mtime)| *mtime)?;
+
+        if newest_mtime > artifact_mtime {
+            Some(format!(
+                "bridge binary {} is older than its source {}; rebuild with `{}`",
+                self.artifact.display(),
+                newest_path.display(),
+                self.rebuild_hint
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+fn newest_modified(path: &std::path::Path) -> Option<(PathBuf, std::time::SystemTime)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_dir() {
+        return metadata.modified().ok().map(|mtime| (path.to_path_buf(), mtime));
+    }
+
+    std::fs::read_dir(path)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| newest_modified(&entry.path()))
+        .max_by_key(|(_, mtime)| *mtime)
+}
+
+/// Leading numeric component of a `major.minor.patch` version string.
+fn major_version(version: &str) -> Option<u64> {
+    version.trim().trim_start_matches('v').split('.').next()?.parse().ok()
+}
+
+/// What a built-in analyzer needs on this machine, checked by `doctor`.
+#[derive(Debug)]
+pub struct Requirements {
+    pub language: &'static str,
+    /// Commands printing the toolchain version, tried in order; the first that runs is used.
+    pub toolchain: Vec<Vec<String>>,
+    /// Where to get the toolchain when none of them runs.
+    pub install_hint: &'static str,
+    /// Where the bridge was looked for; an error when an explicit path does not exist.
+    pub bridge: Result<BridgeLocation>,
+    /// How to build the bridge, for compiled bridges.
+    pub build: Option<BuildCommand>,
+}
+
+/// Command building a compiled bridge from this checkout, run by `build-bridges`.
+#[derive(Debug, Clone, Copy)]
+pub struct BuildCommand {
+    /// Directory the command runs in, relative to the workspace root.
+    pub dir: &'static str,
+    pub command: &'static [&'static str],
+}
+
+impl BuildCommand {
+    /// The command as typed in a shell at the workspace root.
+    pub fn hint(&self) -> String {
+        let command = self.command.join(" ");
+        if self.dir.is_empty() {
+            command
+        } else {
+            format!("cd {} && {}", self.dir, command)
+        }
+    }
+}
+
+/// Requirements of the built-in analyzers, in registration order.
+pub fn builtin_requirements() -> Vec<Requirements> {
+    vec![
+        python::requirements(),
+        java::requirements(),
+        nodejs::requirements(),
+        go::requirements(),
+        rust::requirements(),
+    ]
 }
 
 impl BridgeAnalyzer {
@@ -90,92 +368,683 @@ impl BridgeAnalyzer {
         bridge_cmd: Vec<String>,
         health_cmd: Option<Vec<String>>,
         analyzer_info: AnalyzerInfo,
-        can_handle_fn: fn(&str) -> bool,
+        can_handle_fn: impl Fn(&str) -> bool + Send + Sync + 'static,
     ) -> Self {
         Self {
             lang: lang.into(),
             bridge_cmd,
             health_cmd,
             analyzer_info,
-            can_handle_fn,
+            can_handle_fn: Box::new(can_handle_fn),
+            build: None,
+            self_test: None,
+            daemons: tokio::sync::Mutex::new(Vec::new()),
+            daemon_unsupported: AtomicBool::new(false),
+            handshake: tokio::sync::OnceCell::new(),
+            missing_warned: Mutex::new(HashSet::new()),
+            warnings_logged: Mutex::new(HashSet::new()),
         }
     }
 
-    async fn execute_bridge(&self, request: &AnalyzeRequest) -> Result<AnalyzeResponse> {
-        let request_json = serde_json::to_string(request)?;
+    /// Registers the target used by `deep_health_check`.
+    pub fn with_self_test(mut self, self_test: SelfTestTarget) -> Self {
+        self.self_test = Some(self_test);
+        self
+    }
+
+    /// Registers the build artifact/sources of a compiled bridge for staleness checks.
+    pub fn with_build(mut self, build: BridgeBuild) -> Self {
+        self.build = Some(build);
+        self
+    }
+
+    fn stale_build_reason(&self) -> Option<String> {
+        self.build.as_ref().and_then(BridgeBuild::stale_reason)
+    }
+
+    /// The bridge's handshake, asked for once with the environment and working directory
+    /// of the first request. Bridges that do not answer predate the handshake and are
+    /// taken to speak protocol version 0.
+    async fn handshake(&self, environment: Vec<(String, String)>, workdir: Option<PathBuf>) -> &BridgeHandshake {
+        self.handshake
+            .get_or_init(|| async {
+                let handshake = match self.run_handshake(environment, workdir).await {
+                    Ok(handshake) => handshake,
+                    Err(err) => {
+                        warn!(
+                            "{} bridge did not answer the protocol handshake ({:#}); treating it as protocol version 0",
+                            self.lang, err
+                        );
+                        return BridgeHandshake::legacy();
+                    }
+                };
+                if handshake.protocol_version < PROTOCOL_VERSION {
+                    warn!(
+                        "{} bridge speaks protocol version {} but the orchestrator speaks {}; options it does not support are left out",
+                        self.lang, handshake.protocol_version, PROTOCOL_VERSION
+                    );
+                } else if handshake.protocol_version > PROTOCOL_VERSION {
+                    warn!(
+                        "{} bridge speaks protocol version {}, newer than the orchestrator's {}; update graphene-ha",
+                        self.lang, handshake.protocol_version, PROTOCOL_VERSION
+                    );
+                }
+                handshake
+            })
+            .await
+    }
+
+    async fn run_handshake(&self, environment: Vec<(String, String)>, workdir: Option<PathBuf>) -> Result<BridgeHandshake> {
         let (program, args) = self.bridge_cmd.split_first()
             .ok_or_else(|| anyhow::anyhow!("Empty bridge command for {} analyzer", self.lang))?;
-
-        let mut child = Command::new(program)
+        let mut command = Command::new(program);
+        command
             .args(args)
-            .stdin(Stdio::piped())
+            .env_clear()
+            .envs(environment)
+            .env(HANDSHAKE_ENV, "1")
+            .stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .spawn()
+            .kill_on_drop(true);
+        if let Some(workdir) = workdir {
+            command.current_dir(workdir);
+        }
+        let output = tokio::time::timeout(HANDSHAKE_TIMEOUT, command.output())
+            .await
+            .context("timed out")?
+            .context("failed to spawn the bridge")?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json = extract_first_json_object(&stdout).context("no handshake on stdout")?;
+        serde_json::from_str(&json).context("unreadable handshake")
+    }
+
+    /// Leaves out the options of capabilities the bridge did not list, warning once per
+    /// capability, so an older bridge gets a request it can parse.
+    async fn negotiate(&self, mut request: AnalyzeRequest) -> AnalyzeRequest {
+        let handshake = self.handshake(bridge_environment(&request), bridge_workdir(&request)).await;
+        for (capability, option) in CAPABILITY_OPTIONS {
+            // The default transport needs no capability.
+            let default_transport =
+                *option == option_keys::TRANSPORT && bridge_transport(&request) == BridgeTransport::Stdio;
+            if handshake.supports(capability) || default_transport || request.options.remove(*option).is_none() {
+                continue;
+            }
+            self.warn_missing(capability, &format!("ignoring the `{}` option", option));
+        }
+        let encoded = request.input_encodings.iter().any(|encoding| *encoding != InputEncoding::Utf8);
+        if encoded && !handshake.supports(capabilities::INPUT_ENCODINGS) {
+            self.warn_missing(
+                capabilities::INPUT_ENCODINGS,
+                "binary inputs reach the target base64-encoded",
+            );
+        }
+        if !request.input_args.is_empty() && !handshake.supports(capabilities::INPUT_ARGS) {
+            self.warn_missing(
+                capabilities::INPUT_ARGS,
+                "each argument list reaches the target as one JSON string",
+            );
+        }
+        request
+    }
+
+    fn warn_missing(&self, capability: &'static str, consequence: &str) {
+        let first = self.missing_warned.lock().map(|mut warned| warned.insert(capability)).unwrap_or(false);
+        if first {
+            warn!("{} bridge does not support {}; {}", self.lang, capability, consequence);
+        }
+    }
+
+    /// Logs the bridge's warnings about the request, each once per run; they stay on the
+    /// response for reports.
+    fn log_warnings(&self, response: &AnalyzeResponse) {
+        for warning in &response.warnings {
+            let first = self
+                .warnings_logged
+                .lock()
+                .map(|mut logged| logged.insert(warning.clone()))
+                .unwrap_or(false);
+            if first {
+                warn!("{} bridge: {}", self.lang, warning);
+            }
+        }
+    }
+
+    /// Compares the version reported by the bridge with the one this orchestrator was
+    /// built against. A different major version is refused; other drift is logged.
+    fn check_bridge_version(&self, request: &AnalyzeRequest, response: AnalyzeResponse) -> AnalyzeResponse {
+        let expected = self.analyzer_info.version.as_str();
+        let reported = response.analyzer_version.trim();
+        if reported.is_empty() || reported == expected {
+            return response;
+        }
+
+        let stale_hint = self
+            .stale_build_reason()
+            .map(|reason| format!(" ({})", reason))
+            .unwrap_or_default();
+
+        if major_version(reported) != major_version(expected) {
+            let mut refused = self.synthetic_bridge_failure_response(
+                request,
+                AnalysisError::new(
+                    AnalysisErrorKind::VersionMismatch,
+                    format!(
+                        "version_mismatch: {} bridge reports analyzer version {} but the orchestrator expects {}{}",
+                        self.lang, reported, expected, stale_hint
+                    ),
+                ),
+            );
+            refused.bridge_stderr = response.bridge_stderr;
+            return refused;
+        }
+
+        warn!(
+            "{} bridge reports analyzer version {} but the orchestrator expects {}; results may be incomplete{}",
+            self.lang, reported, expected, stale_hint
+        );
+        response
+    }
+
+    /// Runs the bridge once with `payload` on stdin, watching it for hangs and the
+    /// overall deadline.
+    async fn spawn_bridge(
+        &self,
+        payload: &str,
+        silence_limit: Option<Duration>,
+        deadline: Option<Duration>,
+        transport: BridgeTransport,
+        launch: &BridgeLaunch,
+    ) -> Result<BridgeRun> {
+        if crate::interrupt::interrupted() {
+            return Ok(BridgeRun::failed(self.interrupted_error()));
+        }
+        let (mut command, container) = launch
+            .command(&self.bridge_cmd, &[])
+            .with_context(|| format!("Failed to prepare {} bridge", self.lang))?;
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Own process group so a timeout kill also takes down anything the target spawned,
+        // and so the console's Ctrl+C reaches only the orchestrator.
+        #[cfg(unix)]
+        command.process_group(0);
+        #[cfg(windows)]
+        command.creation_flags(windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP);
+
+        let mut socket = None;
+        if transport == BridgeTransport::UnixSocket {
+            let exchange = SocketExchange::start(payload)
+                .with_context(|| format!("Failed to open socket for {} bridge", self.lang))?;
+            command.env(BRIDGE_SOCKET_ENV, &exchange.path).stdin(Stdio::null());
+            socket = Some(exchange);
+        }
+
+        let mut child = sandbox::spawn(&mut command, container)
             .with_context(|| format!("Failed to spawn {} analyzer", self.lang))?;
+        let pgid = child.id();
+        launch.confine(&child);
+        let _live = live::track(&child);
+
+        let stdin_failure = match child.stdin.take() {
+            _ if socket.is_some() => None,
+            Some(mut stdin) => {
+                if let Err(err) = stdin.write_all(payload.as_bytes()).await {
+                    Some(format!("Failed writing request to {} bridge stdin: {}", self.lang, err))
+                } else if let Err(err) = stdin.flush().await {
+                    Some(format!("Failed flushing request to {} bridge stdin: {}", self.lang, err))
+                } else {
+                    None
+                }
+            }
+            None => Some(format!("{} bridge stdin was unavailable", self.lang)),
+        };
+        if let Some(message) = stdin_failure {
+            kill_bridge(&mut child).await;
+            return Ok(BridgeRun::failed(bridge_failure_error(&message)));
+        }
 
-        if let Some(mut stdin) = child.stdin.take() {
-            if let Err(err) = stdin.write_all(request_json.as_bytes()).await {
-                return Ok(self.synthetic_bridge_failure_response(
-                    request,
-                    &format!("Failed writing request to {} bridge stdin: {}", self.lang, err),
-                ));
+        let last_heartbeat: Arc<Mutex<Option<Instant>>> = Arc::new(Mutex::new(None));
+        let stderr_buffer: Arc<Mutex<String>> = Arc::new(Mutex::new(String::new()));
+        let streamed: Arc<Mutex<Vec<ExecutionResult>>> = Arc::new(Mutex::new(Vec::new()));
+        let stdout_task = tokio::spawn(read_bridge_stdout(child.stdout.take()));
+        let stderr_task = tokio::spawn(read_bridge_stderr(
+            child.stderr.take(),
+            Arc::clone(&last_heartbeat),
+            Arc::clone(&stderr_buffer),
+            Arc::clone(&streamed),
+        ));
+
+        let exit = match wait_with_watchdog(&mut child, &last_heartbeat, silence_limit, deadline).await {
+            Ok(status) => Ok(status),
+            Err(failure) => {
+                stdout_task.abort();
+                stderr_task.abort();
+                Err(match failure {
+                    BridgeWaitFailure::Hang(silence) => self.hang_error(silence),
+                    BridgeWaitFailure::Deadline(deadline) => self.deadline_error(deadline),
+                    BridgeWaitFailure::Io(err) => bridge_failure_error(&format!(
+                        "Failed waiting for {} bridge output: {}",
+                        self.lang, err
+                    )),
+                })
             }
-            if let Err(err) = stdin.flush().await {
-                return Ok(self.synthetic_bridge_failure_response(
-                    request,
-                    &format!("Failed flushing request to {} bridge stdin: {}", self.lang, err),
-                ));
+        };
+
+        // Anything still in the group was started by the target and outlived the bridge;
+        // killing it also closes the pipes it inherited, which the reads below wait on.
+        let orphans = match (&exit, pgid) {
+            (Ok(_), Some(pgid)) => {
+                // A sandboxed bridge's container went with it.
+                sandbox::forget(pgid);
+                reap_group(pgid, None)
             }
-            drop(stdin);
+            _ => Vec::new(),
+        };
+
+        let mut stdout = if exit.is_ok() {
+            stdout_task.await.unwrap_or_default()
         } else {
-            return Ok(self.synthetic_bridge_failure_response(
-                request,
-                &format!("{} bridge stdin was unavailable", self.lang),
-            ));
+            String::new()
+        };
+        if exit.is_ok() {
+            let _ = stderr_task.await;
+        }
+        let mut stderr = stderr_buffer.lock().map(|s| s.clone()).unwrap_or_default();
+        let streamed = streamed.lock().map(|guard| guard.clone()).unwrap_or_default();
+
+        if let Some(socket) = socket {
+            // Over a socket, stdout belongs to the target; keep it with the bridge log.
+            if !stdout.trim().is_empty() {
+                stderr.push_str("--- bridge stdout ---\n");
+                stderr.push_str(&stdout);
+            }
+            stdout = if exit.is_ok() {
+                socket.finish().await
+            } else {
+                String::new()
+            };
         }
 
-        let output = match child.wait_with_output().await {
-            Ok(output) => output,
-            Err(err) => {
-                return Ok(self.synthetic_bridge_failure_response(
-                    request,
-                    &format!("Failed waiting for {} bridge output: {}", self.lang, err),
-                ));
+        // Ctrl+C killed the bridge; what it streamed before that is kept.
+        let exit = match exit {
+            Ok(status) if !status.success() && crate::interrupt::interrupted() => Err(self.interrupted_error()),
+            exit => exit,
+        };
+
+        Ok(BridgeRun { exit, stdout, stderr, streamed, orphans })
+    }
+
+    fn hang_error(&self, silence: Duration) -> AnalysisError {
+        AnalysisError::new(
+            AnalysisErrorKind::BridgeHang,
+            format!(
+                "bridge_hang: {} bridge sent no heartbeat for {:.1}s and was killed",
+                self.lang,
+                silence.as_secs_f64()
+            ),
+        )
+    }
+
+    fn deadline_error(&self, deadline: Duration) -> AnalysisError {
+        AnalysisError::new(
+            AnalysisErrorKind::BridgeTimeout,
+            format!(
+                "bridge_timeout: {} bridge exceeded its overall deadline of {:.1}s and was killed",
+                self.lang,
+                deadline.as_secs_f64()
+            ),
+        )
+    }
+
+    fn interrupted_error(&self) -> AnalysisError {
+        AnalysisError::new(
+            AnalysisErrorKind::Interrupted,
+            format!("interrupted: the run was interrupted and the {} bridge was stopped", self.lang),
+        )
+    }
+
+    /// How to start a bridge for `requests`, which share their options; a sandbox
+    /// mounts every request's target.
+    fn bridge_launch(&self, requests: &[AnalyzeRequest]) -> Result<BridgeLaunch> {
+        let first = &requests[0];
+        let mut sandbox = Sandbox::for_request(&self.lang, &self.bridge_cmd, first)?;
+        if let Some(sandbox) = &mut sandbox {
+            for request in &requests[1..] {
+                sandbox.mount_target(&request.target);
             }
+        }
+        Ok(BridgeLaunch {
+            environment: bridge_environment(first),
+            workdir: bridge_workdir(first),
+            sandbox,
+            limits: ResourceLimits::for_request(first)?,
+        })
+    }
+
+    /// Whether the request asks for a daemon bridge that may be able to serve it.
+    fn daemon_enabled(&self, request: &AnalyzeRequest) -> bool {
+        request.options.get(option_keys::DAEMON).is_some_and(|value| value.trim() == "true")
+            && bridge_transport(request) == BridgeTransport::Stdio
+            && !self.daemon_unsupported.load(Ordering::Relaxed)
+    }
+
+    /// Serves the request from an idle daemon bridge with the same environment and
+    /// working directory, or a new one. `None` when the bridge has no daemon mode or
+    /// the daemon ended before answering (likely the target exiting the process); the
+    /// caller then runs the request in a one-shot process.
+    async fn analyze_in_daemon(&self, request: &AnalyzeRequest) -> Option<AnalyzeResponse> {
+        let launch = match self.bridge_launch(std::slice::from_ref(request)) {
+            Ok(launch) => launch,
+            Err(e) => {
+                warn!("Failed to prepare {} daemon bridge: {:#}", self.lang, e);
+                return None;
+            }
+        };
+        let idle = {
+            let mut daemons = self.daemons.lock().await;
+            daemons
+                .iter()
+                .position(|daemon| daemon.launch == launch)
+                .map(|index| daemons.swap_remove(index))
+        };
+        let mut daemon = match idle {
+            Some(daemon) => daemon,
+            None => match DaemonBridge::start(&self.bridge_cmd, launch).await {
+                Ok(Some(daemon)) => daemon,
+                Ok(None) => {
+                    if !self.daemon_unsupported.swap(true, Ordering::Relaxed) {
+                        warn!("{} bridge does not support daemon mode; using a process per batch", self.lang);
+                    }
+                    return None;
+                }
+                Err(e) => {
+                    warn!("Failed to start {} daemon bridge: {:#}", self.lang, e);
+                    return None;
+                }
+            },
         };
 
-        let stdout_text = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr_text = String::from_utf8_lossy(&output.stderr).to_string();
-        let fallback_error = pick_bridge_failure_message(
-            Some(output.status),
-            &stderr_text,
-            &stdout_text,
+        match daemon.request(request, heartbeat_timeout(request), bridge_deadline(request)).await {
+            Ok(answer) => {
+                self.daemons.lock().await.push(daemon);
+                let mut response = self.normalize_bridge_response(request, answer.response, None);
+                if !answer.stderr.trim().is_empty() {
+                    response.bridge_stderr = Some(answer.stderr);
+                }
+                self.report_orphans(&mut response, request, &answer.orphans, 1);
+                Some(response)
+            }
+            Err(failure) => {
+                let error = match failure.failure {
+                    DaemonFailure::Exited => {
+                        warn!(
+                            "{} daemon bridge ended before answering; rerunning {} in a one-shot process",
+                            self.lang, request.target
+                        );
+                        return None;
+                    }
+                    DaemonFailure::Hang(silence) => self.hang_error(silence),
+                    DaemonFailure::Deadline(deadline) => self.deadline_error(deadline),
+                };
+                let mut response = self.partial_failure_response(request, failure.streamed, error);
+                if !failure.stderr.trim().is_empty() {
+                    response.bridge_stderr = Some(failure.stderr);
+                }
+                Some(response)
+            }
+        }
+    }
+
+    /// Adds the processes killed after the bridge served `request` to its response.
+    fn report_orphans(&self, response: &mut AnalyzeResponse, request: &AnalyzeRequest, orphans: &[Orphan], shared: usize) {
+        if orphans.is_empty() {
+            return;
+        }
+        warn!(
+            "Killed {} process(es) left running by the {} bridge after {}",
+            orphans.len(),
+            self.lang,
+            request.target
         );
+        response.vulnerabilities.push(orphan_vulnerability(orphans, request, shared));
+    }
+
+    /// Runs the request in a bridge process. When the target ends that process before
+    /// the bridge has answered (`exit()`, `os._exit`, `System.exit`, `process.exit`,
+    /// `os.Exit`), the execution that was running is recorded as having exited the
+    /// process and the remaining inputs continue in a fresh bridge process, so one
+    /// exiting input does not take the rest of the analysis down with it.
+    async fn execute_bridge(&self, request: &AnalyzeRequest) -> Result<AnalyzeResponse> {
+        let repeat = request.repeat.max(1);
+        let mut results: Vec<ExecutionResult> = Vec::new();
+        let mut pending = request.clone();
+        loop {
+            let (mut response, early_exit) = self.execute_bridge_once(&pending).await?;
+            let Some(early_exit) = early_exit else {
+                if results.is_empty() {
+                    return Ok(response);
+                }
+                results.append(&mut response.results);
+                response.summary = merged_summary(&results, &response.summary);
+                response.results = results;
+                return Ok(response);
+            };
+
+            // Input that was running when the process ended, as an index into `request`.
+            let offset = request.inputs.len() - pending.inputs.len();
+            let running = offset + early_exit.streamed.len() / repeat;
+            let made_progress = !early_exit.streamed.is_empty();
+            results.extend(early_exit.streamed);
+            let mut error = match &early_exit.limit {
+                Some(limit) => AnalysisError::new(
+                    AnalysisErrorKind::ResourceLimit,
+                    format!(
+                        "resource_limit: the {} bridge process went past {} and was killed",
+                        self.lang, limit
+                    ),
+                ),
+                None => AnalysisError::new(
+                    AnalysisErrorKind::RuntimeCrash,
+                    format!(
+                        "process_exit: target ended the {} bridge process ({}) before returning; \
+                         joins and shutdowns after the exit never ran",
+                        self.lang, early_exit.status
+                    ),
+                ),
+            };
+            error.details = Some(early_exit.detail);
+            // The rest of that input's repeats would exit the same way.
+            while results.len() < (running + 1) * repeat {
+                results.push(exited_execution(request.inputs[running].clone(), error.clone()));
+            }
 
-        if let Some(parsed) = self.try_parse_bridge_response(&stdout_text) {
-            return Ok(self.normalize_bridge_response(request, parsed, Some(&fallback_error)));
+            let remaining = request.inputs[running + 1..].to_vec();
+            // A continuation that dies before finishing anything points at the bridge
+            // rather than the target; stop there instead of relaunching for every input.
+            if remaining.is_empty() || (offset > 0 && !made_progress) {
+                return Ok(self.partial_failure_response(request, results, error));
+            }
+            warn!(
+                "{} target exited the bridge process on input {}; continuing with {} remaining input(s) in a new process",
+                self.lang,
+                running + 1,
+                remaining.len()
+            );
+            pending.inputs = remaining;
         }
+    }
+
+    /// One bridge process run. The second value is set when the process ended before
+    /// answering and before it had streamed a result for every execution.
+    async fn execute_bridge_once(&self, request: &AnalyzeRequest) -> Result<(AnalyzeResponse, Option<EarlyExit>)> {
+        let request_json = serde_json::to_string(request)?;
+        let launch = self.bridge_launch(std::slice::from_ref(request))?;
+        let run = self
+            .spawn_bridge(
+                &request_json,
+                heartbeat_timeout(request),
+                bridge_deadline(request),
+                bridge_transport(request),
+                &launch,
+            )
+            .await?;
+
+        let expected = request.inputs.len().max(1) * request.repeat.max(1);
+        let mut early_exit = None;
+        let mut response = match run.exit {
+            Err(error) => self.partial_failure_response(request, run.streamed, error),
+            Ok(status) => {
+                let fallback_error = pick_bridge_failure_message(
+                    Some(status),
+                    &run.stderr,
+                    &run.stdout,
+                );
+
+                let memory_too_low = launch.limits.memory_too_low(&self.lang, status, run.streamed.len(), &run.stderr);
+                if let Some(parsed) = self.try_parse_bridge_response(&run.stdout) {
+                    self.normalize_bridge_response(request, parsed, Some(&fallback_error))
+                } else if let Some(parsed) = self.try_parse_bridge_response(&run.stderr) {
+                    self.normalize_bridge_response(request, parsed, Some(&fallback_error))
+                } else if let Some(message) = memory_too_low {
+                    // Every relaunch would fail the same way, so there is no continuing.
+                    let mut error = AnalysisError::new(AnalysisErrorKind::ResourceLimit, message);
+                    error.details = Some(fallback_error.clone());
+                    self.partial_failure_response(request, run.streamed, error)
+                } else if !request.inputs.is_empty() && run.streamed.len() < expected {
+                    early_exit = Some(EarlyExit {
+                        status,
+                        streamed: run.streamed.clone(),
+                        detail: fallback_error.clone(),
+                        limit: launch.limits.exceeded(status),
+                    });
+                    self.partial_failure_response(request, run.streamed, bridge_failure_error(&fallback_error))
+                } else if status.success() {
+                    let stale_hint = self
+                        .stale_build_reason()
+                        .map(|reason| format!(" Possible cause: {}.", reason))
+                        .unwrap_or_default();
+                    self.partial_failure_response(
+                        request,
+                        run.streamed,
+                        bridge_failure_error(&format!(
+                            "Failed to parse {} bridge response JSON from stdout/stderr. {}{}",
+                            self.lang,
+                            fallback_error,
+                            stale_hint
+                        )),
+                    )
+                } else {
+                    self.partial_failure_response(request, run.streamed, bridge_failure_error(&fallback_error))
+                }
+            }
+        };
 
-        if let Some(parsed) = self.try_parse_bridge_response(&stderr_text) {
-            return Ok(self.normalize_bridge_response(request, parsed, Some(&fallback_error)));
+        // Keep bridge warnings and target logging even when the run succeeded.
+        if !run.stderr.trim().is_empty() {
+            response.bridge_stderr = Some(run.stderr);
         }
+        self.report_orphans(&mut response, request, &run.orphans, 1);
 
-        if output.status.success() {
-            return Ok(self.synthetic_bridge_failure_response(
-                request,
-                &format!(
-                    "Failed to parse {} bridge response JSON from stdout/stderr. {}",
-                    self.lang,
-                    fallback_error
+        Ok((response, early_exit))
+    }
+
+    /// Runs requests that share a working directory in one bridge process, falling
+    /// back to one process per request when the batch does not come back whole.
+    async fn analyze_shared_workdir(&self, requests: Vec<AnalyzeRequest>) -> Vec<Result<AnalyzeResponse>> {
+        if requests.len() > 1 {
+            match self.execute_batch(&requests).await {
+                Ok(Some(responses)) => return responses.into_iter().map(Ok).collect(),
+                Ok(None) => warn!(
+                    "Retrying {} {} request(s) one bridge process at a time",
+                    requests.len(),
+                    self.lang
                 ),
-            ));
+                Err(e) => warn!("{} batch request failed, retrying individually: {:#}", self.lang, e),
+            }
         }
 
-        Ok(self.synthetic_bridge_failure_response(request, &fallback_error))
+        let mut responses = Vec::with_capacity(requests.len());
+        for request in requests {
+            responses.push(self.analyze(request).await);
+        }
+        responses
     }
 
-    fn try_parse_bridge_response(&self, payload: &str) -> Option<ParsedBridgeResponse> {
+    /// Runs every request in one bridge process. Returns `None` when the bridge does
+    /// not produce a complete batch response, so the caller can retry the requests
+    /// one at a time.
+    async fn execute_batch(&self, requests: &[AnalyzeRequest]) -> Result<Option<Vec<AnalyzeResponse>>> {
+        let batch_json = serde_json::to_string(&BatchAnalyzeRequest {
+            requests: requests.to_vec(),
+        })?;
+        let deadline = requests
+            .iter()
+            .map(bridge_deadline)
+            .sum::<Option<Duration>>();
+        let run = self
+            .spawn_bridge(
+                &batch_json,
+                heartbeat_timeout(&requests[0]),
+                deadline,
+                bridge_transport(&requests[0]),
+                &self.bridge_launch(requests)?,
+            )
+            .await?;
+
+        let status = match run.exit {
+            Ok(status) => status,
+            Err(error) => {
+                warn!("{} batch of {} request(s) failed: {}", self.lang, requests.len(), error);
+                return Ok(None);
+            }
+        };
+
+        let Some(batch) = extract_first_json_object(run.stdout.trim())
+            .and_then(|payload| serde_json::from_str::<BatchAnalyzeResponse>(&payload).ok())
+        else {
+            warn!(
+                "{} bridge did not return a batch response: {}",
+                self.lang,
+                pick_bridge_failure_message(Some(status), &run.stderr, &run.stdout)
+            );
+            return Ok(None);
+        };
+
+        if batch.responses.len() != requests.len() {
+            warn!(
+                "{} bridge returned {} response(s) for a batch of {}",
+                self.lang,
+                batch.responses.len(),
+                requests.len()
+            );
+            return Ok(None);
+        }
+
+        let mut responses = Vec::with_capacity(requests.len());
+        for (request, response) in requests.iter().zip(batch.responses) {
+            if !response.session_id.trim().is_empty() && response.session_id != request.session_id {
+                warn!(
+                    "{} bridge returned batch responses out of order (expected session {}, got {})",
+                    self.lang, request.session_id, response.session_id
+                );
+                return Ok(None);
+            }
+            let mut response = self.normalize_bridge_response(request, response, None);
+            // The bridge process is shared, so every target's report gets its full stderr.
+            if !run.stderr.trim().is_empty() {
+                response.bridge_stderr = Some(run.stderr.clone());
+            }
+            self.report_orphans(&mut response, request, &run.orphans, requests.len());
+            self.log_warnings(&response);
+            responses.push(self.check_bridge_version(request, response));
+        }
+
+        Ok(Some(responses))
+    }
+
+    fn try_parse_bridge_response(&self, payload: &str) -> Option<AnalyzeResponse> {
         let trimmed = payload.trim();
         if trimmed.is_empty() {
             return None;
@@ -194,18 +1063,12 @@ impl BridgeAnalyzer {
                 Err(_) => continue,
             };
 
-            let error = value
-                .get("error")
-                .and_then(|v| v.as_str())
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty());
-
             let response: AnalyzeResponse = match serde_json::from_value(value) {
                 Ok(response) => response,
                 Err(_) => continue,
             };
 
-            return Some(ParsedBridgeResponse { response, error });
+            return Some(response);
         }
 
         None
@@ -214,10 +1077,9 @@ impl BridgeAnalyzer {
     fn normalize_bridge_response(
         &self,
         request: &AnalyzeRequest,
-        parsed: ParsedBridgeResponse,
+        mut response: AnalyzeResponse,
         fallback_error_source: Option<&str>,
     ) -> AnalyzeResponse {
-        let mut response = parsed.response;
 
         if response.language.trim().is_empty() {
             response.language = self.lang.clone();
@@ -226,33 +1088,35 @@ impl BridgeAnalyzer {
             response.session_id = request.session_id.clone();
         }
 
-        let mut pre_execution_error = parsed.error;
-        if pre_execution_error.as_deref().map(|s| s.trim().is_empty()).unwrap_or(true) {
+        if response.error.is_none() && response.results.is_empty() {
             if let Some(source) = fallback_error_source {
-                let fallback = first_nonempty_line(source);
-                if !fallback.is_empty() {
-                    pre_execution_error = Some(fallback);
+                if !first_nonempty_line(source).is_empty() {
+                    response.error = Some(bridge_failure_error(source));
                 }
             }
         }
 
         if response.results.is_empty() {
-            if let Some(raw_error) = pre_execution_error {
-                let diagnosis = diagnose_bridge_failure(&raw_error);
+            if let Some(error) = response.error.clone() {
+                let is_timeout = error.kind.is_timeout();
                 response.results.push(ExecutionResult {
                     input_data: "<bridge-startup>".to_string(),
                     success: false,
                     crashed: true,
                     output: String::new(),
-                    error: format!("{}: {}", diagnosis.category, diagnosis.message),
+                    error: Some(error),
                     execution_time_ms: 0,
                     escape_detected: false,
                     escape_details: empty_escape_details(),
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    artifacts: vec![],
+                    not_executed: false,
                 });
 
                 response.summary.total_tests = response.summary.total_tests.max(1);
                 response.summary.crashes = response.summary.crashes.max(1);
-                if diagnosis.category == "Timeout" {
+                if is_timeout {
                     response.summary.timeouts = response.summary.timeouts.max(1);
                 }
                 response.summary.crash_rate = response.summary.crashes as f64
@@ -263,12 +1127,79 @@ impl BridgeAnalyzer {
         response
     }
 
+    /// Response for a bridge that failed before printing its final response. Results
+    /// it streamed before failing are kept; the inputs it never reached are padded
+    /// with `not_executed` placeholders.
+    fn partial_failure_response(
+        &self,
+        request: &AnalyzeRequest,
+        mut results: Vec<ExecutionResult>,
+        error: AnalysisError,
+    ) -> AnalyzeResponse {
+        if results.is_empty() {
+            return self.synthetic_bridge_failure_response(request, error);
+        }
+
+        let repeat = request.repeat.max(1);
+        let expected = request.inputs.len().max(1) * repeat;
+        for index in results.len()..expected {
+            results.push(ExecutionResult {
+                input_data: request.inputs.get(index / repeat).cloned().unwrap_or_default(),
+                success: false,
+                crashed: false,
+                output: String::new(),
+                error: Some(error.clone()),
+                execution_time_ms: 0,
+                escape_detected: false,
+                escape_details: empty_escape_details(),
+                stdout: String::new(),
+                stderr: String::new(),
+                artifacts: vec![],
+                not_executed: true,
+            });
+        }
+
+        let executed = results.iter().filter(|r| !r.not_executed).count();
+        let crashes = results.iter().filter(|r| r.crashed).count();
+        let escapes = results.iter().filter(|r| r.escape_detected).count();
+        let timeouts = results
+            .iter()
+            .filter(|r| !r.not_executed)
+            .filter(|r| r.error.as_ref().is_some_and(|e| e.kind.is_timeout()))
+            .count();
+
+        AnalyzeResponse {
+            session_id: request.session_id.clone(),
+            language: self.lang.clone(),
+            analyzer_version: self.analyzer_info.version.clone(),
+            analysis_mode: request.analysis_mode,
+            summary: ExecutionSummary {
+                total_tests: results.len(),
+                successes: results.iter().filter(|r| r.success).count(),
+                crashes,
+                timeouts,
+                escapes,
+                genuine_escapes: escapes,
+                crash_rate: if executed > 0 { crashes as f64 / executed as f64 } else { 0.0 },
+                suppressed: 0,
+                memory: None,
+            },
+            results,
+            vulnerabilities: vec![],
+            static_analysis: None,
+            bridge_stderr: None,
+            chaos_seed: None,
+            error: Some(error),
+            warnings: Vec::new(),
+        }
+    }
+
     fn synthetic_bridge_failure_response(
         &self,
         request: &AnalyzeRequest,
-        raw_error: &str,
+        error: AnalysisError,
     ) -> AnalyzeResponse {
-        let diagnosis = diagnose_bridge_failure(raw_error);
+        let is_timeout = error.kind.is_timeout();
 
         AnalyzeResponse {
             session_id: request.session_id.clone(),
@@ -280,90 +1211,452 @@ impl BridgeAnalyzer {
                 success: false,
                 crashed: true,
                 output: String::new(),
-                error: format!("{}: {}", diagnosis.category, diagnosis.message),
+                error: Some(error.clone()),
                 execution_time_ms: 0,
                 escape_detected: false,
                 escape_details: empty_escape_details(),
+                stdout: String::new(),
+                stderr: String::new(),
+                artifacts: vec![],
+                not_executed: false,
             }],
             vulnerabilities: vec![],
             summary: ExecutionSummary {
                 total_tests: 1,
                 successes: 0,
                 crashes: 1,
-                timeouts: if diagnosis.category == "Timeout" { 1 } else { 0 },
+                timeouts: if is_timeout { 1 } else { 0 },
                 escapes: 0,
                 genuine_escapes: 0,
                 crash_rate: 1.0,
+                suppressed: 0,
+                memory: None,
             },
             static_analysis: None,
+            bridge_stderr: None,
+            chaos_seed: None,
+            error: Some(error),
+            warnings: Vec::new(),
         }
     }
 }
 
-struct ParsedBridgeResponse {
-    response: AnalyzeResponse,
-    error: Option<String>,
-}
-
-struct BridgeErrorDiagnosis {
-    category: &'static str,
-    message: String,
-}
-
-fn diagnose_bridge_failure(raw_message: &str) -> BridgeErrorDiagnosis {
-    let message = first_nonempty_line(raw_message);
-    let lower = message.to_lowercase();
-
-    let category = if lower.contains("timeout") || lower.contains("timed out") || lower.contains("exceeded") {
-        "Timeout"
-    } else if lower.contains("target resolution")
-        || lower.contains("missing required field: 'target'")
-        || lower.contains("target loading failed")
-        || lower.contains("failed to load function")
-        || lower.contains("failed to load module")
-        || lower.contains("invalid target")
-        || lower.contains("nosuchmethod")
-        || lower.contains("module not found")
-        || lower.contains("function '") && lower.contains("not found")
-    {
-        "Target Resolution"
-    } else if lower.contains("protocol/input")
-        || lower.contains("invalid json")
-        || lower.contains("failed to parse request")
-        || lower.contains("empty input")
-        || lower.contains("expected json")
-        || lower.contains("json")
-        || lower.contains("parse")
-        || lower.contains("stdin")
-        || lower.contains("protocol")
-    {
-        "Protocol/Input"
-    } else if lower.contains("environment")
-        || lower.contains("permission denied")
-        || lower.contains("not available")
-        || lower.contains("not found in path")
-        || lower.contains("command not found")
-        || lower.contains("missing tools")
-        || lower.contains("failed to spawn")
-        || lower.contains("binary not found")
-        || lower.contains("no such file or directory")
-    {
-        "Environment"
-    } else if lower.contains("runtime crash")
-        || lower.contains("panic")
-        || lower.contains("exception")
-        || lower.contains("traceback")
-        || lower.contains("segmentation")
-    {
-        "Runtime Crash"
+/// Environment variable naming the socket a bridge connects to when the
+/// orchestrator uses the Unix socket transport.
+pub const BRIDGE_SOCKET_ENV: &str = "GRAPHENE_BRIDGE_SOCKET";
+
+/// Variables every bridge inherits so interpreters and toolchains can start. Anything
+/// else a target needs is passed with `--env` or `--env-passthrough`, which keeps runs
+/// independent of whatever happens to be set in the orchestrator's shell.
+const BASE_BRIDGE_ENV: &[&str] = &[
+    "PATH", "HOME", "USER", "LOGNAME", "LANG", "LC_ALL", "LC_CTYPE", "TZ", "TMPDIR", "TEMP", "TMP",
+    // Windows
+    "SYSTEMROOT", "SYSTEMDRIVE", "WINDIR", "COMSPEC", "PATHEXT", "USERPROFILE", "APPDATA",
+    "LOCALAPPDATA", "PROGRAMDATA",
+    // Toolchains the bridges drive
+    "JAVA_HOME", "GOROOT", "GOPATH", "GOCACHE", "GOMODCACHE", "GOPROXY", "GOFLAGS",
+    "CARGO_HOME", "RUSTUP_HOME", "RUSTUP_TOOLCHAIN", "VIRTUAL_ENV", "NODE_PATH",
+];
+
+/// How long to wait for the socket response after the bridge has exited.
+const SOCKET_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How the request and response travel between the orchestrator and a bridge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeTransport {
+    /// Request on stdin, response on stdout.
+    Stdio,
+    /// Request and response over a Unix socket named by `BRIDGE_SOCKET_ENV`,
+    /// leaving the bridge's stdout to the target.
+    UnixSocket,
+}
+
+impl BridgeTransport {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stdio => "stdio",
+            Self::UnixSocket => "unix_socket",
+        }
+    }
+}
+
+/// Orchestrator end of the Unix socket transport: serves the request to the first
+/// connection and collects the response. The socket file is removed on drop.
+struct SocketExchange {
+    path: PathBuf,
+    task: tokio::task::JoinHandle<std::io::Result<Vec<u8>>>,
+}
+
+impl SocketExchange {
+    #[cfg(unix)]
+    fn start(payload: &str) -> Result<Self> {
+        let path = std::env::temp_dir().join(format!("graphene-bridge-{}.sock", uuid::Uuid::new_v4()));
+        let listener = tokio::net::UnixListener::bind(&path)?;
+        let payload = payload.as_bytes().to_vec();
+        let task = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await?;
+            stream.write_all(&payload).await?;
+            stream.shutdown().await?;
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response).await?;
+            Ok(response)
+        });
+        Ok(Self { path, task })
+    }
+
+    #[cfg(not(unix))]
+    fn start(_payload: &str) -> Result<Self> {
+        anyhow::bail!("the Unix socket transport is not available on this platform; use --transport stdio")
+    }
+
+    /// Response the bridge wrote to the socket, decoded like stdout.
+    async fn finish(mut self) -> String {
+        match tokio::time::timeout(SOCKET_DRAIN_TIMEOUT, &mut self.task).await {
+            Ok(Ok(Ok(bytes))) => decode_bridge_stdout(&bytes),
+            Ok(Ok(Err(err))) => format!("Bridge socket exchange failed: {}", err),
+            Ok(Err(err)) => format!("Bridge socket exchange failed: {}", err),
+            Err(_) => "Bridge exited without answering on its socket".to_string(),
+        }
+    }
+}
+
+impl Drop for SocketExchange {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// A bridge process that ended without answering, most likely because the target
+/// exited the process.
+struct EarlyExit {
+    status: ExitStatus,
+    /// Results streamed before the process ended.
+    streamed: Vec<ExecutionResult>,
+    /// Most useful line of the bridge's output.
+    detail: String,
+    /// The resource cap the process was killed at, when that is what ended it.
+    limit: Option<String>,
+}
+
+/// Outcome of one bridge process run.
+struct BridgeRun {
+    /// Exit status, or the failure that ended the run before the bridge exited on its own.
+    exit: std::result::Result<ExitStatus, AnalysisError>,
+    stdout: String,
+    stderr: String,
+    /// Results the bridge streamed to stderr before exiting or being killed.
+    streamed: Vec<ExecutionResult>,
+    /// Processes left in the bridge's group after it exited, now killed.
+    orphans: Vec<Orphan>,
+}
+
+impl BridgeRun {
+    fn failed(error: AnalysisError) -> Self {
+        Self {
+            exit: Err(error),
+            stdout: String::new(),
+            stderr: String::new(),
+            streamed: Vec::new(),
+            orphans: Vec::new(),
+        }
+    }
+}
+
+enum BridgeWaitFailure {
+    /// The bridge stopped sending heartbeats for longer than the allowed silence.
+    Hang(Duration),
+    /// The bridge ran past the overall deadline for the request.
+    Deadline(Duration),
+    Io(std::io::Error),
+}
+
+/// Overall deadline for one bridge invocation: the explicit `bridge_timeout_seconds`
+/// option, or every test hitting its timeout plus startup overhead.
+fn bridge_deadline(request: &AnalyzeRequest) -> Option<Duration> {
+    let explicit = request
+        .options
+        .get(option_keys::BRIDGE_TIMEOUT_SECONDS)
+        .and_then(|value| value.trim().parse::<f64>().ok());
+
+    let seconds = explicit.unwrap_or_else(|| {
+        let runs = request.inputs.len().max(1) * request.repeat.max(1);
+        let observe = request
+            .options
+            .get(option_keys::OBSERVE_SECONDS)
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .unwrap_or(0.0)
+            .max(0.0);
+        runs as f64 * (request.timeout_seconds.max(0.0) + observe) + BRIDGE_OVERHEAD_SECONDS
+    });
+
+    positive_duration(seconds)
+}
+
+/// `seconds` as a duration; `None` unless it is positive, finite and small enough to
+/// hold, so a bad option value disables the limit instead of panicking.
+fn positive_duration(seconds: f64) -> Option<Duration> {
+    if seconds > 0.0 {
+        Duration::try_from_secs_f64(seconds).ok()
+    } else {
+        None
+    }
+}
+
+/// Transport requested in the options; stdin/stdout unless `unix_socket` is asked for.
+/// A sandboxed bridge cannot reach a socket on the host and always uses stdio.
+fn bridge_transport(request: &AnalyzeRequest) -> BridgeTransport {
+    let sandboxed = request.options.contains_key(option_keys::SANDBOX);
+    match request.options.get(option_keys::TRANSPORT).map(|value| value.trim()) {
+        Some("unix_socket") if !sandboxed => BridgeTransport::UnixSocket,
+        _ => BridgeTransport::Stdio,
+    }
+}
+
+/// Environment for a bridge process: the base variables, inherited variables matching
+/// the passthrough patterns, then the explicitly set ones (which win).
+fn bridge_environment(request: &AnalyzeRequest) -> Vec<(String, String)> {
+    let patterns: Vec<&str> = request
+        .options
+        .get(option_keys::ENV_PASSTHROUGH)
+        .map(|value| value.split(',').map(str::trim).filter(|p| !p.is_empty()).collect())
+        .unwrap_or_default();
+
+    let mut environment = base_bridge_environment(&patterns);
+
+    if let Some(explicit) = request.options.get(option_keys::ENV) {
+        match serde_json::from_str::<HashMap<String, String>>(explicit) {
+            Ok(explicit) => environment.extend(explicit),
+            Err(err) => warn!("Ignoring malformed {} option: {}", option_keys::ENV, err),
+        }
+    }
+    environment
+}
+
+/// The base variables and inherited variables matching `patterns`.
+fn base_bridge_environment(patterns: &[&str]) -> Vec<(String, String)> {
+    std::env::vars_os()
+        .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+        .filter(|(key, _)| {
+            BASE_BRIDGE_ENV.iter().chain(patterns.iter()).any(|pattern| env_name_matches(pattern, key))
+        })
+        .collect()
+}
+
+/// How a bridge process is started for a set of requests sharing their options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BridgeLaunch {
+    environment: Vec<(String, String)>,
+    workdir: Option<PathBuf>,
+    /// Container the bridge runs in instead of the host.
+    sandbox: Option<Sandbox>,
+    limits: ResourceLimits,
+}
+
+impl BridgeLaunch {
+    /// Command that starts `bridge_cmd` with `extra` set for it, and the container it
+    /// runs in when sandboxed. Stdio is left to the caller.
+    fn command(&self, bridge_cmd: &[String], extra: &[(&str, &str)]) -> Result<(Command, Option<Container>)> {
+        if let Some(sandbox) = &self.sandbox {
+            let (command, container) = sandbox.command(bridge_cmd, extra, &self.limits)?;
+            return Ok((command, Some(container)));
+        }
+        let (program, args) = bridge_cmd
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("Empty bridge command"))?;
+        let mut command = Command::new(program);
+        command
+            .args(args)
+            .env_clear()
+            .envs(self.environment.iter().cloned())
+            .envs(extra.iter().copied());
+        if let Some(workdir) = &self.workdir {
+            command.current_dir(workdir);
+        }
+        #[cfg(unix)]
+        self.limits.apply(&mut command);
+        Ok((command, None))
+    }
+
+    /// Puts a bridge started from `command` under its resource limits; a container
+    /// enforces its own.
+    fn confine(&self, child: &Child) {
+        if self.sandbox.is_none() {
+            self.limits.confine(child);
+        }
+    }
+}
+
+/// Working directory requested in the options, if any.
+fn bridge_workdir(request: &AnalyzeRequest) -> Option<PathBuf> {
+    request
+        .options
+        .get(option_keys::WORKDIR)
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Matches an environment variable name against a pattern where `*` stands for any
+/// run of characters. Names are case-insensitive on Windows.
+fn env_name_matches(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = if cfg!(windows) {
+        (pattern.to_ascii_uppercase(), name.to_ascii_uppercase())
     } else {
-        "Unknown"
+        (pattern.to_string(), name.to_string())
+    };
+
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+    if name.len() < first.len() + last.len() || !name.starts_with(first) || !name.ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Silence limit for the hang watchdog, taken from the request options.
+/// A non-positive value disables the watchdog.
+fn heartbeat_timeout(request: &AnalyzeRequest) -> Option<Duration> {
+    let seconds = request
+        .options
+        .get(option_keys::HEARTBEAT_TIMEOUT_SECONDS)
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .unwrap_or(DEFAULT_HEARTBEAT_TIMEOUT_SECONDS);
+    positive_duration(seconds)
+}
+
+/// Waits for the bridge to exit, killing it when it goes silent or outlives its
+/// deadline. The heartbeat check only arms after the first heartbeat so bridges
+/// without heartbeat support are unaffected.
+async fn wait_with_watchdog(
+    child: &mut Child,
+    last_heartbeat: &Mutex<Option<Instant>>,
+    silence_limit: Option<Duration>,
+    deadline: Option<Duration>,
+) -> std::result::Result<ExitStatus, BridgeWaitFailure> {
+    let started = Instant::now();
+    let mut ticker = tokio::time::interval(Duration::from_millis(250));
+    loop {
+        tokio::select! {
+            status = child.wait() => return status.map_err(BridgeWaitFailure::Io),
+            _ = ticker.tick() => {
+                if let Some(deadline) = deadline.filter(|d| started.elapsed() > *d) {
+                    kill_bridge(child).await;
+                    return Err(BridgeWaitFailure::Deadline(deadline));
+                }
+                let Some(limit) = silence_limit else {
+                    continue;
+                };
+                let last = last_heartbeat.lock().ok().and_then(|guard| *guard);
+                if let Some(silence) = last.map(|instant| instant.elapsed()).filter(|s| *s > limit) {
+                    kill_bridge(child).await;
+                    return Err(BridgeWaitFailure::Hang(silence));
+                }
+            }
+        }
+    }
+}
+
+/// Kills the bridge, its container when sandboxed and, on Unix, its whole process group.
+async fn kill_bridge(child: &mut Child) {
+    sandbox::kill_container(child).await;
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // SAFETY: plain syscall; the bridge was spawned as leader of its own group.
+        unsafe {
+            libc::kill(-(pid as i32), libc::SIGKILL);
+        }
+    }
+    let _ = child.kill().await;
+}
+
+/// Kills every running bridge, what it started and its container, for Ctrl+C.
+pub fn kill_live_bridges() {
+    live::kill_all();
+    sandbox::kill_all();
+}
+
+async fn read_bridge_stdout(stdout: Option<ChildStdout>) -> String {
+    let mut buffer = Vec::new();
+    if let Some(mut stdout) = stdout {
+        let _ = stdout.read_to_end(&mut buffer).await;
+    }
+    decode_bridge_stdout(&buffer)
+}
+
+/// Decompresses a gzip response framed by `GZIP_RESPONSE_MARKER`. Anything the
+/// bridge printed before the marker is kept as text.
+fn decode_bridge_stdout(bytes: &[u8]) -> String {
+    let marker = format!("{}\n", GZIP_RESPONSE_MARKER);
+    let Some(start) = bytes
+        .windows(marker.len())
+        .position(|window| window == marker.as_bytes())
+    else {
+        return String::from_utf8_lossy(bytes).to_string();
     };
 
-    BridgeErrorDiagnosis {
-        category,
-        message,
+    let mut text = String::from_utf8_lossy(&bytes[..start]).to_string();
+    let mut decoder = GzDecoder::new(&bytes[start + marker.len()..]);
+    let mut decoded = String::new();
+    if let Err(err) = decoder.read_to_string(&mut decoded) {
+        text.push_str(&format!("Failed to decompress gzip bridge response: {}\n", err));
+        return text;
     }
+    text.push_str(&decoded);
+    text
+}
+
+async fn read_bridge_stderr(
+    stderr: Option<ChildStderr>,
+    last_heartbeat: Arc<Mutex<Option<Instant>>>,
+    buffer: Arc<Mutex<String>>,
+    streamed: Arc<Mutex<Vec<ExecutionResult>>>,
+) {
+    let Some(stderr) = stderr else {
+        return;
+    };
+
+    let mut lines = BufReader::new(stderr).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.starts_with(HEARTBEAT_PREFIX) {
+            if let Ok(mut guard) = last_heartbeat.lock() {
+                *guard = Some(Instant::now());
+            }
+            continue;
+        }
+        if let Some(payload) = line.strip_prefix(RESULT_PREFIX) {
+            if let Ok(result) = serde_json::from_str::<ExecutionResult>(payload.trim()) {
+                if let Ok(mut guard) = streamed.lock() {
+                    guard.push(result);
+                }
+                continue;
+            }
+        }
+        if let Ok(mut guard) = buffer.lock() {
+            guard.push_str(&line);
+            guard.push('\n');
+        }
+    }
+}
+
+/// Structured error for a failure of the bridge process itself. Text that does not
+/// point at a more specific cause is reported as a bridge crash.
+fn bridge_failure_error(raw_message: &str) -> AnalysisError {
+    let mut error = AnalysisError::from_message(raw_message);
+    if matches!(error.kind, AnalysisErrorKind::Unknown | AnalysisErrorKind::RuntimeCrash) {
+        error.kind = AnalysisErrorKind::BridgeCrash;
+    }
+    error
 }
 
 fn first_nonempty_line(message: &str) -> String {
@@ -413,9 +1706,46 @@ fn extract_first_json_object(text: &str) -> Option<String> {
 }
 
 fn empty_escape_details() -> EscapeDetails {
-    EscapeDetails {
-        escaping_references: vec![],
-        escape_paths: vec![],
+    EscapeDetails::default()
+}
+
+/// Placeholder for an execution during which the target exited the bridge process.
+fn exited_execution(input_data: String, error: AnalysisError) -> ExecutionResult {
+    ExecutionResult {
+        input_data,
+        success: false,
+        crashed: true,
+        output: String::new(),
+        error: Some(error),
+        execution_time_ms: 0,
+        escape_detected: false,
+        escape_details: empty_escape_details(),
+        stdout: String::new(),
+        stderr: String::new(),
+        artifacts: vec![],
+        not_executed: false,
+    }
+}
+
+/// Summary over results gathered from several bridge processes; `last` is the summary
+/// the final process reported for its own share, whose genuine-escape count is kept.
+fn merged_summary(results: &[ExecutionResult], last: &ExecutionSummary) -> ExecutionSummary {
+    let crashes = results.iter().filter(|r| r.crashed).count();
+    let escapes = results.iter().filter(|r| r.escape_detected).count();
+    let earlier_escapes = escapes.saturating_sub(last.escapes);
+    ExecutionSummary {
+        total_tests: results.len(),
+        successes: results.iter().filter(|r| r.success).count(),
+        crashes,
+        timeouts: results
+            .iter()
+            .filter(|r| r.error.as_ref().is_some_and(|e| e.kind.is_timeout()))
+            .count(),
+        escapes,
+        genuine_escapes: last.genuine_escapes + earlier_escapes,
+        crash_rate: if results.is_empty() { 0.0 } else { crashes as f64 / results.len() as f64 },
+        suppressed: last.suppressed,
+        memory: None,
     }
 }
 
@@ -496,14 +1826,110 @@ impl Analyzer for BridgeAnalyzer {
                 anyhow::bail!("{} analyzer binary not found at: {}", self.lang, binary);
             }
         }
+        if let Some(build) = self.build.as_ref().filter(|build| !build.artifact.exists()) {
+            anyhow::bail!(
+                "{} bridge is not built ({} is missing); build it with `{}`",
+                self.lang,
+                build.artifact.display(),
+                build.rebuild_hint
+            );
+        }
+        // Staleness is judged from modification times alone, so it is only a hint.
+        if let Some(reason) = self.stale_build_reason() {
+            warn!("{} analyzer may be out of date: {}", self.lang, reason);
+        }
+        let handshake = self.handshake(base_bridge_environment(&[]), None).await;
         Ok(HealthCheckResponse {
             pong: "healthy".to_string(),
             analyzer_info: self.analyzer_info.clone(),
+            protocol_version: handshake.protocol_version,
+            capabilities: handshake.capabilities.clone(),
         })
     }
 
     async fn analyze(&self, request: AnalyzeRequest) -> Result<AnalyzeResponse> {
-        self.execute_bridge(&request).await
+        let request = self.negotiate(request).await;
+        let daemon_response = if self.daemon_enabled(&request) {
+            self.analyze_in_daemon(&request).await
+        } else {
+            None
+        };
+        let response = match daemon_response {
+            Some(response) => response,
+            None => self.execute_bridge(&request).await?,
+        };
+        self.log_warnings(&response);
+        Ok(self.check_bridge_version(&request, response))
+    }
+
+    async fn analyze_batch(&self, requests: Vec<AnalyzeRequest>) -> Vec<Result<AnalyzeResponse>> {
+        let mut responses = Vec::with_capacity(requests.len());
+        let mut negotiated = Vec::with_capacity(requests.len());
+        for request in requests {
+            negotiated.push(self.negotiate(request).await);
+        }
+        if !self.handshake.get().is_some_and(|handshake| handshake.supports(capabilities::BATCH)) {
+            for request in negotiated {
+                responses.push(self.analyze(request).await);
+            }
+            return responses;
+        }
+        let mut remaining = negotiated.into_iter().peekable();
+        // A daemon takes requests one at a time; if the bridge turns out to have no
+        // daemon mode, the rest go out in batches.
+        while let Some(request) = remaining.next_if(|request| self.daemon_enabled(request)) {
+            responses.push(self.analyze(request).await);
+        }
+        // The working directory belongs to the bridge process, so only neighbouring
+        // requests that share one go into the same batch.
+        while let Some(first) = remaining.next() {
+            let workdir = bridge_workdir(&first);
+            let mut group = vec![first];
+            while let Some(next) = remaining.next_if(|request| bridge_workdir(request) == workdir) {
+                group.push(next);
+            }
+            responses.extend(self.analyze_shared_workdir(group).await);
+        }
+        responses
+    }
+
+    async fn shutdown(&self) {
+        let daemons = std::mem::take(&mut *self.daemons.lock().await);
+        join_all(daemons.into_iter().map(DaemonBridge::shutdown)).await;
+    }
+
+    async fn deep_health_check(&self) -> Result<HealthCheckResponse> {
+        let health = self.health_check().await?;
+        let Some(self_test) = &self.self_test else {
+            return Ok(health);
+        };
+
+        let mut options = HashMap::new();
+        options.insert(
+            option_keys::BRIDGE_TIMEOUT_SECONDS.to_string(),
+            SELF_TEST_DEADLINE_SECONDS.to_string(),
+        );
+        let request = AnalyzeRequest {
+            protocol_version: PROTOCOL_VERSION,
+            session_id: format!("self-test-{}", uuid::Uuid::new_v4()),
+            target: self_test.target.clone(),
+            inputs: vec![SELF_TEST_INPUT.to_string()],
+            input_encodings: Vec::new(),
+            input_args: Vec::new(),
+            repeat: 1,
+            timeout_seconds: 30.0,
+            options,
+            analysis_mode: AnalysisMode::Dynamic,
+        };
+
+        let response = self
+            .analyze(request.clone())
+            .await
+            .with_context(|| format!("{} self-test analysis of {} failed", self.lang, self_test.target))?;
+        verify_self_test_response(self_test, &request, &response)
+            .with_context(|| format!("{} self-test of {} failed", self.lang, self_test.target))?;
+
+        Ok(health)
     }
 
     fn language(&self) -> &str {
@@ -515,6 +1941,51 @@ impl Analyzer for BridgeAnalyzer {
     }
 }
 
+fn verify_self_test_response(
+    self_test: &SelfTestTarget,
+    request: &AnalyzeRequest,
+    response: &AnalyzeResponse,
+) -> Result<()> {
+    if response.session_id != request.session_id {
+        anyhow::bail!(
+            "response session_id {} does not match request {}",
+            response.session_id,
+            request.session_id
+        );
+    }
+    if response.analyzer_version.trim().is_empty() {
+        anyhow::bail!("response is missing analyzer_version");
+    }
+
+    let expected_results = request.inputs.len() * request.repeat;
+    if response.results.len() != expected_results || response.summary.total_tests != expected_results {
+        anyhow::bail!(
+            "expected {} result(s), got {} (summary total_tests {})",
+            expected_results,
+            response.results.len(),
+            response.summary.total_tests
+        );
+    }
+
+    for result in &response.results {
+        if result.crashed || !result.success {
+            let error = result.error.as_ref().map(|e| e.message.as_str()).unwrap_or("");
+            anyhow::bail!("self-test target did not run cleanly: {}", error);
+        }
+        if let Some(expected) = self_test.expect_escape {
+            if result.escape_detected != expected {
+                anyhow::bail!(
+                    "expected escape_detected={} but the bridge reported {}",
+                    expected,
+                    result.escape_detected
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Factory for creating analyzers based on language or file extension
 pub struct AnalyzerRegistry {
     analyzers: Vec<Box<dyn Analyzer>>,
@@ -546,28 +2017,51 @@ impl AnalyzerRegistry {
         });
     }
 
+    /// Registers the analyzers defined in the `[[analyzer]]` sections of the config
+    /// file, then the built-in ones whose language no definition took over. Definitions
+    /// come first so their extensions win target detection.
     pub async fn initialize_all() -> Result<Self> {
         let mut registry = Self::new();
 
-        match python::create().await {
-            Ok(a) => registry.register(Box::new(a)),
-            Err(e) => registry.record_initialization_failure("python", e),
+        let definitions = external::load_definitions()?;
+        for definition in definitions.iter().cloned() {
+            let language = definition.language.clone();
+            match definition.create() {
+                Ok(a) => registry.register(Box::new(a)),
+                Err(e) => registry.record_initialization_failure(&language, e),
+            }
         }
-        match java::create().await {
-            Ok(a) => registry.register(Box::new(a)),
-            Err(e) => registry.record_initialization_failure("java", e),
+        let defined = |language: &str| definitions.iter().any(|definition| definition.language == language);
+
+        if !defined("python") {
+            match python::create().await {
+                Ok(a) => registry.register(Box::new(a)),
+                Err(e) => registry.record_initialization_failure("python", e),
+            }
         }
-        match nodejs::create().await {
-            Ok(a) => registry.register(Box::new(a)),
-            Err(e) => registry.record_initialization_failure("javascript", e),
+        if !defined("java") {
+            match java::create().await {
+                Ok(a) => registry.register(Box::new(a)),
+                Err(e) => registry.record_initialization_failure("java", e),
+            }
         }
-        match go::create().await {
-            Ok(a) => registry.register(Box::new(a)),
-            Err(e) => registry.record_initialization_failure("go", e),
+        if !defined("javascript") {
+            match nodejs::create().await {
+                Ok(a) => registry.register(Box::new(a)),
+                Err(e) => registry.record_initialization_failure("javascript", e),
+            }
         }
-        match rust::create().await {
-            Ok(a) => registry.register(Box::new(a)),
-            Err(e) => registry.record_initialization_failure("rust", e),
+        if !defined("go") {
+            match go::create().await {
+                Ok(a) => registry.register(Box::new(a)),
+                Err(e) => registry.record_initialization_failure("go", e),
+            }
+        }
+        if !defined("rust") {
+            match rust::create().await {
+                Ok(a) => registry.register(Box::new(a)),
+                Err(e) => registry.record_initialization_failure("rust", e),
+            }
         }
 
         Ok(registry)
@@ -594,8 +2088,19 @@ impl AnalyzerRegistry {
     pub fn initialization_failures(&self) -> &[AnalyzerInitializationFailure] {
         &self.initialization_failures
     }
+
+    /// Stops the daemon bridges of every analyzer.
+    pub async fn shutdown(&self) {
+        join_all(self.analyzers.iter().map(|analyzer| analyzer.shutdown())).await;
+    }
 }
 
+pub mod daemon;
+pub mod external;
+pub mod limits;
+pub mod live;
+pub mod orphans;
+pub mod sandbox;
 pub mod python;
 pub mod java;
 pub mod nodejs;
@@ -0,0 +1,269 @@
+//! Daemon bridges
+//! A bridge started with `GRAPHENE_BRIDGE_DAEMON=1` stays alive across requests: it
+//! announces itself with a `ready` frame, answers each `analyze` frame with a `response`
+//! frame carrying the same id, and exits on a `shutdown` frame or the end of its stdin.
+//! Frames travel over stdin/stdout as a `GRAPHENE_FRAME <length>` line and that many
+//! bytes of JSON; anything else on stdout is the target's and is kept with the bridge
+//! log. Heartbeats and streamed results stay on stderr as in one-shot mode.
+
+use super::orphans::{reap_group, Orphan};
+use super::live::{self, LiveBridge};
+use super::{kill_bridge, read_bridge_stderr, sandbox, BridgeLaunch};
+use crate::protocol::{
+    AnalyzeRequest, AnalyzeResponse, DaemonMessage, ExecutionResult, DAEMON_PROTOCOL_VERSION, FRAME_MARKER,
+};
+use anyhow::{Context, Result};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+use tokio::sync::mpsc;
+
+/// Environment variable that starts a bridge in daemon mode.
+pub const DAEMON_ENV: &str = "GRAPHENE_BRIDGE_DAEMON";
+
+/// How long a bridge has to send its `ready` frame before it is taken to lack daemon
+/// support. Covers JVM startup and the Go and Rust bridges' first build.
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long a bridge has to exit after a `shutdown` frame before it is killed.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+
+/// Why a daemon stopped serving a request.
+pub(super) enum DaemonFailure {
+    /// The process ended or closed stdout before answering.
+    Exited,
+    /// No heartbeat for longer than the allowed silence.
+    Hang(Duration),
+    /// The request ran past its deadline.
+    Deadline(Duration),
+}
+
+/// Answer to one request, what the process logged while serving it and the processes
+/// it left running, now killed.
+pub(super) struct DaemonAnswer {
+    pub response: AnalyzeResponse,
+    pub stderr: String,
+    pub orphans: Vec<Orphan>,
+}
+
+/// Request that ended in a failure, with what was logged and streamed before it.
+pub(super) struct DaemonError {
+    pub failure: DaemonFailure,
+    pub stderr: String,
+    pub streamed: Vec<ExecutionResult>,
+}
+
+/// A running daemon bridge process.
+pub(super) struct DaemonBridge {
+    child: Child,
+    stdin: ChildStdin,
+    frames: mpsc::UnboundedReceiver<DaemonMessage>,
+    last_heartbeat: Arc<Mutex<Option<Instant>>>,
+    stderr: Arc<Mutex<String>>,
+    streamed: Arc<Mutex<Vec<ExecutionResult>>>,
+    /// Output on stdout that was not a frame.
+    stdout: Arc<Mutex<String>>,
+    next_id: u64,
+    /// How the process was started; requests asking for another environment, working
+    /// directory or sandbox need a process of their own.
+    pub launch: BridgeLaunch,
+    /// Keeps the process where Ctrl+C finds it.
+    _live: LiveBridge,
+}
+
+impl DaemonBridge {
+    /// Starts `bridge_cmd` in daemon mode. `None` when the bridge exits or stays silent
+    /// instead of announcing itself, i.e. it only supports one-shot requests, or speaks
+    /// another version of the daemon messages.
+    pub(super) async fn start(bridge_cmd: &[String], launch: BridgeLaunch) -> Result<Option<Self>> {
+        let (mut command, container) = launch.command(bridge_cmd, &[(DAEMON_ENV, "1")])?;
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(unix)]
+        command.process_group(0);
+        #[cfg(windows)]
+        command.creation_flags(windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP);
+
+        let mut child = sandbox::spawn(&mut command, container).context("Failed to spawn daemon bridge")?;
+        launch.confine(&child);
+        let live = live::track(&child);
+        let stdin = child.stdin.take().context("Daemon bridge stdin was unavailable")?;
+        let (sender, frames) = mpsc::unbounded_channel();
+        let stdout = Arc::new(Mutex::new(String::new()));
+        let last_heartbeat = Arc::new(Mutex::new(None));
+        let stderr = Arc::new(Mutex::new(String::new()));
+        let streamed = Arc::new(Mutex::new(Vec::new()));
+        tokio::spawn(read_frames(child.stdout.take(), sender, Arc::clone(&stdout)));
+        tokio::spawn(read_bridge_stderr(
+            child.stderr.take(),
+            Arc::clone(&last_heartbeat),
+            Arc::clone(&stderr),
+            Arc::clone(&streamed),
+        ));
+
+        let mut daemon = Self {
+            child,
+            stdin,
+            frames,
+            last_heartbeat,
+            stderr,
+            streamed,
+            stdout,
+            next_id: 1,
+            launch,
+            _live: live,
+        };
+        match tokio::time::timeout(READY_TIMEOUT, daemon.frames.recv()).await {
+            Ok(Some(DaemonMessage::Ready { protocol })) if protocol == DAEMON_PROTOCOL_VERSION => Ok(Some(daemon)),
+            _ => {
+                kill_bridge(&mut daemon.child).await;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Sends `request` and waits for its response, watching heartbeats and the deadline.
+    /// On failure the process is killed and must not be reused.
+    pub(super) async fn request(
+        &mut self,
+        request: &AnalyzeRequest,
+        silence_limit: Option<Duration>,
+        deadline: Option<Duration>,
+    ) -> std::result::Result<DaemonAnswer, DaemonError> {
+        self.take_output();
+        // Idle time between requests does not count as silence.
+        if let Ok(mut guard) = self.last_heartbeat.lock() {
+            if guard.is_some() {
+                *guard = Some(Instant::now());
+            }
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let message = DaemonMessage::Analyze { id, request: Box::new(request.clone()) };
+        if self.send(&message).await.is_err() {
+            return Err(self.fail(DaemonFailure::Exited).await);
+        }
+
+        let started = Instant::now();
+        let mut ticker = tokio::time::interval(Duration::from_millis(250));
+        loop {
+            tokio::select! {
+                frame = self.frames.recv() => match frame {
+                    Some(DaemonMessage::Response { id: answered, response }) if answered == id => {
+                        let (stderr, _) = self.take_output();
+                        // The daemon leads its group; anything else in it came from this request.
+                        let orphans = self.child.id().map(|pid| reap_group(pid, Some(pid))).unwrap_or_default();
+                        return Ok(DaemonAnswer { response: *response, stderr, orphans });
+                    }
+                    // A late answer to an earlier request, or a message not meant for us.
+                    Some(_) => continue,
+                    None => return Err(self.fail(DaemonFailure::Exited).await),
+                },
+                _ = ticker.tick() => {
+                    if let Some(deadline) = deadline.filter(|d| started.elapsed() > *d) {
+                        return Err(self.fail(DaemonFailure::Deadline(deadline)).await);
+                    }
+                    let last = self.last_heartbeat.lock().ok().and_then(|guard| *guard);
+                    if let Some(silence) = silence_limit
+                        .and_then(|limit| last.map(|instant| instant.elapsed()).filter(|s| *s > limit))
+                    {
+                        return Err(self.fail(DaemonFailure::Hang(silence)).await);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Asks the bridge to exit, killing it if it has not within the grace period.
+    pub(super) async fn shutdown(mut self) {
+        let pid = self.child.id();
+        let _ = self.send(&DaemonMessage::Shutdown).await;
+        if tokio::time::timeout(SHUTDOWN_GRACE, self.child.wait()).await.is_err() {
+            kill_bridge(&mut self.child).await;
+        } else if let Some(pid) = pid {
+            sandbox::forget(pid);
+        }
+    }
+
+    async fn send(&mut self, message: &DaemonMessage) -> std::io::Result<()> {
+        let body = serde_json::to_vec(message)?;
+        self.stdin
+            .write_all(format!("{} {}\n", FRAME_MARKER, body.len()).as_bytes())
+            .await?;
+        self.stdin.write_all(&body).await?;
+        self.stdin.flush().await
+    }
+
+    async fn fail(&mut self, failure: DaemonFailure) -> DaemonError {
+        kill_bridge(&mut self.child).await;
+        // Let the readers drain what the process wrote before it died.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let (stderr, streamed) = self.take_output();
+        DaemonError { failure, stderr, streamed }
+    }
+
+    /// Bridge log (with stray stdout appended) and streamed results since the last call.
+    fn take_output(&mut self) -> (String, Vec<ExecutionResult>) {
+        let mut stderr = self.stderr.lock().map(|mut s| std::mem::take(&mut *s)).unwrap_or_default();
+        let stdout = self.stdout.lock().map(|mut s| std::mem::take(&mut *s)).unwrap_or_default();
+        if !stdout.trim().is_empty() {
+            stderr.push_str("--- bridge stdout ---\n");
+            stderr.push_str(&stdout);
+        }
+        let streamed = self.streamed.lock().map(|mut s| std::mem::take(&mut *s)).unwrap_or_default();
+        (stderr, streamed)
+    }
+}
+
+/// Splits the daemon's stdout into frames, keeping everything else as text. A frame
+/// header may follow output the target left without a newline.
+async fn read_frames(
+    stdout: Option<ChildStdout>,
+    frames: mpsc::UnboundedSender<DaemonMessage>,
+    text: Arc<Mutex<String>>,
+) {
+    let Some(stdout) = stdout else {
+        return;
+    };
+    let keep = |line: &str| {
+        if let Ok(mut guard) = text.lock() {
+            guard.push_str(line);
+        }
+    };
+    let mut reader = BufReader::new(stdout);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+        let line = String::from_utf8_lossy(&line);
+        let Some(at) = line.find(FRAME_MARKER) else {
+            keep(&line);
+            continue;
+        };
+        keep(&line[..at]);
+        let Ok(length) = line[at + FRAME_MARKER.len()..].trim().parse::<usize>() else {
+            keep(&line[at..]);
+            continue;
+        };
+        let mut payload = vec![0; length];
+        if reader.read_exact(&mut payload).await.is_err() {
+            return;
+        }
+        match serde_json::from_slice::<DaemonMessage>(&payload) {
+            Ok(message) => {
+                if frames.send(message).is_err() {
+                    return;
+                }
+            }
+            Err(err) => keep(&format!("Unreadable daemon bridge frame: {}\n", err)),
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! External analyzers
+//! Bridges for languages the orchestrator does not ship, or replacements for shipped
+//! ones, are defined in the config file without recompiling:
+//!
+//! ```toml
+//! [[analyzer]]
+//! language = "ruby"
+//! command = ["ruby", "bridges/ruby_bridge.rb"]
+//! health_command = ["ruby", "--version"]
+//! extensions = ["rb"]
+//! ```
+//!
+//! Each definition becomes a `BridgeAnalyzer` speaking the same protocol as the
+//! built-in bridges. Relative paths in the commands that exist next to the config file
+//! are resolved against its directory.
+//!
+//! The same file holds the `[retaining_apis]` table the static analyzers read (see
+//! `static_analyzer::retaining`), and a `[bridges]` table pointing built-in analyzers
+//! at their bridge files (see `locate_bridge`):
+//!
+//! ```toml
+//! [bridges]
+//! rust = "/opt/graphene/rust-analyzer"
+//! ```
+//!
+//! A `[severity]` table holds the project's severity scoring rules (see `severity`), in
+//! the same shape as a `--severity-rules` file.
+
+use super::{standardized_object_escape_capabilities, workspace_root, BridgeAnalyzer, SelfTestTarget};
+use crate::protocol::AnalyzerInfo;
+use crate::severity::SeverityRules;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Config file looked up in the current directory, then the workspace root.
+pub const CONFIG_FILE: &str = "graphene.toml";
+
+/// Environment variable naming another config file.
+pub const CONFIG_ENV: &str = "GRAPHENE_CONFIG";
+
+/// Analyzer version assumed for definitions that do not give one, the version the
+/// built-in bridges report.
+const DEFAULT_VERSION: &str = "1.0.0";
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    #[serde(default, rename = "analyzer")]
+    analyzers: Vec<AnalyzerDefinition>,
+    /// Per language, calls that keep their arguments beyond the call
+    #[serde(default)]
+    retaining_apis: BTreeMap<String, Vec<String>>,
+    /// Per built-in language, the bridge script, binary or jar to run
+    #[serde(default)]
+    bridges: BTreeMap<String, PathBuf>,
+    /// Severity scoring rules; fields left out keep their defaults
+    #[serde(default)]
+    severity: Option<SeverityRules>,
+}
+
+/// One `[[analyzer]]` section.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnalyzerDefinition {
+    /// Name used by `--language`; a built-in language is replaced.
+    pub language: String,
+    /// Bridge command; requests are written to its stdin as for the built-in bridges.
+    pub command: Vec<String>,
+    /// Command whose success shows the runtime is installed, for `list` and `doctor`.
+    #[serde(default)]
+    pub health_command: Option<Vec<String>>,
+    /// File extensions (without the dot) of targets this analyzer picks up when no
+    /// `--language` is given.
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// `analyzer_version` the bridge reports.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Target analyzed by `self-test` and `list --deep`.
+    #[serde(default)]
+    pub self_test: Option<String>,
+    /// Directory of the config file the definition came from.
+    #[serde(skip)]
+    base_dir: PathBuf,
+}
+
+impl AnalyzerDefinition {
+    /// The bridge analyzer for this definition. Fails when the bridge program cannot
+    /// be found.
+    pub fn create(self) -> Result<BridgeAnalyzer> {
+        let command = self.resolve(&self.command);
+        let Some(program) = command.first() else {
+            bail!("Analyzer `{}` has an empty command", self.language);
+        };
+        if !program_exists(program) {
+            bail!("Bridge program `{}` of analyzer `{}` was not found", program, self.language);
+        }
+        let health_command = self.health_command.as_ref().map(|health| self.resolve(health));
+        let extensions: Vec<String> = self
+            .extensions
+            .iter()
+            .map(|extension| format!(".{}", extension.trim_start_matches('.')))
+            .collect();
+
+        let analyzer = BridgeAnalyzer::new(
+            self.language.clone(),
+            command.clone(),
+            health_command,
+            AnalyzerInfo {
+                name: self.name.clone().unwrap_or_else(|| format!("{} Escape Analyzer (external)", self.language)),
+                language: self.language.clone(),
+                version: self.version.clone().unwrap_or_else(|| DEFAULT_VERSION.to_string()),
+                supported_features: standardized_object_escape_capabilities(),
+                executable_path: program.clone(),
+            },
+            move |target| {
+                let file = target.rsplit_once(':').map_or(target, |(file, _)| file);
+                extensions.iter().any(|extension| file.ends_with(extension.as_str()))
+            },
+        );
+        Ok(match &self.self_test {
+            Some(target) => analyzer.with_self_test(SelfTestTarget {
+                target: target.clone(),
+                expect_escape: None,
+            }),
+            None => analyzer,
+        })
+    }
+
+    fn resolve(&self, command: &[String]) -> Vec<String> {
+        command
+            .iter()
+            .map(|word| {
+                let path = Path::new(word);
+                let local = self.base_dir.join(path);
+                if path.is_relative() && word.contains(['/', '\\']) && local.exists() {
+                    local.to_string_lossy().into_owned()
+                } else {
+                    word.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Analyzer definitions of the config file, none when there is no config file.
+pub fn load_definitions() -> Result<Vec<AnalyzerDefinition>> {
+    let Some((path, config)) = load_config()? else {
+        return Ok(Vec::new());
+    };
+    let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+    let mut definitions: Vec<AnalyzerDefinition> = Vec::new();
+    for mut definition in config.analyzers {
+        definition.language = definition.language.trim().to_lowercase();
+        if definition.language.is_empty() {
+            bail!("An [[analyzer]] in {} has no language", path.display());
+        }
+        if definitions.iter().any(|other| other.language == definition.language) {
+            bail!("Analyzer `{}` is defined twice in {}", definition.language, path.display());
+        }
+        definition.base_dir = base_dir.clone();
+        definitions.push(definition);
+    }
+    Ok(definitions)
+}
+
+/// Retaining APIs the config file adds for `language`, none when there is no config file.
+pub fn load_retaining_apis(language: &str) -> Result<Vec<String>> {
+    let Some((_, mut config)) = load_config()? else {
+        return Ok(Vec::new());
+    };
+    Ok(config.retaining_apis.remove(&language.to_lowercase()).unwrap_or_default())
+}
+
+/// Bridge path the config file's `[bridges]` table gives for `language`, relative to
+/// the config file's directory; none when there is no config file.
+pub fn load_bridge_path(language: &str) -> Result<Option<PathBuf>> {
+    let Some((path, mut config)) = load_config()? else {
+        return Ok(None);
+    };
+    let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    Ok(config.bridges.remove(language).map(|bridge| base_dir.join(bridge)))
+}
+
+/// Severity rules of the config file's `[severity]` table, none when there is no config
+/// file or it has no such table.
+pub fn load_severity_rules() -> Result<Option<SeverityRules>> {
+    Ok(load_config()?.and_then(|(_, config)| config.severity))
+}
+
+fn load_config() -> Result<Option<(PathBuf, Config)>> {
+    let Some(path) = config_path()? else {
+        return Ok(None);
+    };
+    let text = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let config: Config = toml::from_str(&text).with_context(|| format!("Malformed config file {}", path.display()))?;
+    Ok(Some((path, config)))
+}
+
+/// `GRAPHENE_CONFIG` (which must exist), else the first `graphene.toml` found.
+fn config_path() -> Result<Option<PathBuf>> {
+    if let Some(path) = std::env::var_os(CONFIG_ENV).filter(|path| !path.is_empty()) {
+        let path = PathBuf::from(path);
+        if !path.is_file() {
+            bail!("{} names {}, which is not a file", CONFIG_ENV, path.display());
+        }
+        return Ok(Some(path));
+    }
+    let candidates = [std::env::current_dir().ok(), workspace_root().ok()];
+    Ok(candidates
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .find(|path| path.is_file()))
+}
+
+/// Whether `program` is a file, or a name found on `PATH`.
+fn program_exists(program: &str) -> bool {
+    if program.contains(['/', '\\']) {
+        return Path::new(program).is_file();
+    }
+    let Some(paths) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&paths).any(|dir| {
+        dir.join(program).is_file() || dir.join(format!("{}{}", program, std::env::consts::EXE_SUFFIX)).is_file()
+    })
+}
@@ -1,16 +1,39 @@
 use anyhow::Result;
 use tokio::process::Command;
-use crate::analyzer::BridgeAnalyzer;
+use crate::analyzer::{locate_bridge, BridgeAnalyzer, BridgeBuild, BridgeLocation, BuildCommand, Requirements, SelfTestTarget};
 use crate::protocol::AnalyzerInfo;
 
+pub const BUILD: BuildCommand = BuildCommand {
+    dir: "analyzers/go",
+    command: &["go", "build", "-o", "escape-analyzer", "."],
+};
+
 pub async fn create() -> Result<BridgeAnalyzer> {
     find_go().await?;
-    let bridge_binary = crate::analyzer::workspace_root()?
-        .join("analyzers/go/escape-analyzer")
-        .to_string_lossy()
-        .to_string();
+    let bridge = locate()?;
+    let bridge_binary = bridge.path.to_string_lossy().to_string();
+    // Sources and the self-test target are in the checkout, also for a bridge found elsewhere.
+    let bridge_dir = match &bridge.checkout {
+        Some(checkout) => checkout.join("analyzers/go"),
+        None => crate::analyzer::workspace_root()?.join("analyzers/go"),
+    };
+
+    let build = BridgeBuild {
+        artifact: bridge.path.clone(),
+        sources: vec![
+            bridge_dir.join("main.go"),
+            bridge_dir.join("static_analyzer.go"),
+            bridge_dir.join("go.mod"),
+        ],
+        rebuild_hint: BUILD.hint(),
+    };
+    // The target runs in a separately compiled process, so only a clean run is checked.
+    let self_test = SelfTestTarget {
+        target: format!("{}:RetainBuffer", bridge_dir.join("selftest/selftest_target.go").display()),
+        expect_escape: None,
+    };
 
-    Ok(BridgeAnalyzer::new(
+    let analyzer = BridgeAnalyzer::new(
         "go",
         vec![bridge_binary],
         Some(vec!["go".into(), "version".into()]),
@@ -22,7 +45,28 @@ pub async fn create() -> Result<BridgeAnalyzer> {
             executable_path: "go".into(),
         },
         |target| target.ends_with(".go"),
-    ))
+    )
+    .with_self_test(self_test);
+
+    // Bridges from elsewhere are not built from the sources in this checkout.
+    if bridge.checkout.is_none() {
+        return Ok(analyzer);
+    }
+    Ok(analyzer.with_build(build))
+}
+
+pub fn requirements() -> Requirements {
+    Requirements {
+        language: "go",
+        toolchain: vec![vec!["go".into(), "version".into()]],
+        install_hint: "install Go from https://go.dev/dl/",
+        bridge: locate(),
+        build: Some(BUILD),
+    }
+}
+
+fn locate() -> Result<BridgeLocation> {
+    locate_bridge("go", &["analyzers/go/escape-analyzer"])
 }
 
 async fn find_go() -> Result<()> {
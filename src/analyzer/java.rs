@@ -1,29 +1,32 @@
 use anyhow::Result;
 use tokio::process::Command;
-use crate::analyzer::BridgeAnalyzer;
+use crate::analyzer::{locate_bridge, BridgeAnalyzer, BridgeBuild, BridgeLocation, BuildCommand, Requirements, SelfTestTarget};
 use crate::protocol::AnalyzerInfo;
 
+pub const BUILD: BuildCommand = BuildCommand {
+    dir: "analyzers/java",
+    command: &["mvn", "clean", "package"],
+};
+
 pub async fn create() -> Result<BridgeAnalyzer> {
     let java_path = find_java().await?;
-    let workspace = crate::analyzer::workspace_root()?;
-    let bridge_jar = workspace
-        .join("analyzers/java/target/escape-analyzer.jar")
-        .to_string_lossy()
-        .to_string();
-    let classes_dir = workspace
-        .join("analyzers/java/target/classes")
-        .to_string_lossy()
-        .to_string();
-
-    let bridge_cmd = if std::path::Path::new(&bridge_jar).exists() {
+    let bridge = locate()?;
+    let bridge_jar = bridge.path.to_string_lossy().to_string();
+
+    // Without a jar the bridge runs from its compiled classes and the jars beside them.
+    let jar_mode = !bridge.path.is_dir();
+    let bridge_cmd = if jar_mode {
         vec![java_path.clone(), "-jar".into(), bridge_jar.clone()]
     } else {
         let classpath_separator = if cfg!(windows) { ";" } else { ":" };
-        let libs_glob = workspace
-            .join("analyzers/java/target/*")
+        let libs_glob = bridge
+            .path
+            .parent()
+            .map(|target| target.join("*"))
+            .unwrap_or_default()
             .to_string_lossy()
             .to_string();
-        let bridge_classpath = format!("{}{}{}", classes_dir, classpath_separator, libs_glob);
+        let bridge_classpath = format!("{}{}{}", bridge_jar, classpath_separator, libs_glob);
         vec![
             java_path.clone(),
             "-cp".into(),
@@ -32,7 +35,7 @@ pub async fn create() -> Result<BridgeAnalyzer> {
         ]
     };
 
-    Ok(BridgeAnalyzer::new(
+    let analyzer = BridgeAnalyzer::new(
         "java",
         bridge_cmd,
         Some(vec![java_path.clone(), "-version".into()]),
@@ -44,7 +47,42 @@ pub async fn create() -> Result<BridgeAnalyzer> {
             executable_path: format!("{} (jar/cp bridge)", java_path),
         },
         |target| target.ends_with(".java") || target.contains(".jar:"),
-    ))
+    )
+    .with_self_test(SelfTestTarget {
+        target: "com.escape.analyzer.SelfTestTarget:retainBuffer".into(),
+        expect_escape: None,
+    });
+
+    // Bridges from elsewhere are not built from the sources in this checkout.
+    let Some(checkout) = bridge.checkout.as_ref().filter(|_| jar_mode) else {
+        return Ok(analyzer);
+    };
+    Ok(analyzer.with_build(BridgeBuild {
+        artifact: bridge.path.clone(),
+        sources: vec![
+            checkout.join("analyzers/java/src"),
+            checkout.join("analyzers/java/pom.xml"),
+        ],
+        rebuild_hint: BUILD.hint(),
+    }))
+}
+
+pub fn requirements() -> Requirements {
+    Requirements {
+        language: "java",
+        toolchain: vec![vec!["java".into(), "-version".into()]],
+        install_hint: "install a JDK (17 or later) and put `java` on PATH",
+        bridge: locate(),
+        build: Some(BUILD),
+    }
+}
+
+/// In a checkout, the bridge is the jar, or the compiled classes when there is no jar.
+fn locate() -> Result<BridgeLocation> {
+    locate_bridge(
+        "java",
+        &["analyzers/java/target/escape-analyzer.jar", "analyzers/java/target/classes"],
+    )
 }
 
 async fn find_java() -> Result<String> {
@@ -0,0 +1,201 @@
+//! Bridge resource caps
+//! `--bridge-memory`, `--bridge-cpu` and `--bridge-processes` cap every bridge process
+//! and what the target starts from it, so a runaway target (a fork bomb, a thread or
+//! spawn loop, unbounded allocation) fails its own analysis instead of the host. On
+//! Unix they are resource limits the bridge starts with: data segment (`RLIMIT_DATA`,
+//! which on Linux counts the heap and private mappings but not the address space the
+//! JVM, V8 and Go reserve without using) and CPU seconds per process, and processes of
+//! the user (`RLIMIT_NPROC`). On Windows the bridge runs in a job object of its own
+//! with the same limits. A sandboxed bridge gets them as its container's limits
+//! instead.
+
+use crate::protocol::{option_keys, AnalyzeRequest};
+use anyhow::{Context, Result};
+use std::process::ExitStatus;
+use tokio::process::{Child, Command};
+
+/// Lowercase stderr text of a runtime that could not get the memory it needed.
+const OUT_OF_MEMORY_MARKERS: [&str; 9] = [
+    "out of memory",
+    "insufficient memory",
+    "could not reserve",
+    "cannot allocate memory",
+    "not enough space",
+    "memoryerror",
+    "memory allocation of",
+    "failed to reserve",
+    "uv_thread_create",
+];
+
+/// Caps asked for in a request's options; `None` leaves the bridge unlimited (the
+/// container defaults when sandboxed).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct ResourceLimits {
+    pub memory_mb: Option<u64>,
+    pub cpu_seconds: Option<u64>,
+    pub processes: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub(super) fn for_request(request: &AnalyzeRequest) -> Result<Self> {
+        let limit = |key: &str| -> Result<Option<u64>> {
+            request
+                .options
+                .get(key)
+                .map(|value| value.trim().parse().with_context(|| format!("Malformed {} option", key)))
+                .transpose()
+        };
+        Ok(Self {
+            memory_mb: limit(option_keys::BRIDGE_MEMORY_MB)?,
+            cpu_seconds: limit(option_keys::BRIDGE_CPU_SECONDS)?,
+            processes: limit(option_keys::BRIDGE_PROCESSES)?,
+        })
+    }
+
+    /// Sets the limits in the bridge before it runs; the target inherits them.
+    #[cfg(unix)]
+    pub(super) fn apply(&self, command: &mut Command) {
+        let mut limits = Vec::new();
+        if let Some(memory_mb) = self.memory_mb {
+            limits.push((libc::RLIMIT_DATA, memory_mb.saturating_mul(1024 * 1024), 0));
+        }
+        if let Some(seconds) = self.cpu_seconds {
+            // SIGXCPU at the soft limit tells a CPU cap apart from other kills.
+            limits.push((libc::RLIMIT_CPU, seconds, 1));
+        }
+        if let Some(processes) = self.processes {
+            limits.push((libc::RLIMIT_NPROC, processes, 0));
+        }
+        if limits.is_empty() {
+            return;
+        }
+        // SAFETY: setrlimit is async-signal-safe and the closure does not allocate.
+        unsafe {
+            command.pre_exec(move || {
+                for &(resource, soft, headroom) in &limits {
+                    let limit = libc::rlimit {
+                        rlim_cur: soft as libc::rlim_t,
+                        rlim_max: soft.saturating_add(headroom) as libc::rlim_t,
+                    };
+                    if libc::setrlimit(resource, &limit) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+    }
+
+    /// On Windows, puts the started bridge in a job object holding the limits; on Unix
+    /// the limits were set before it started.
+    pub(super) fn confine(&self, child: &Child) {
+        #[cfg(windows)]
+        if *self != Self::default() {
+            job::confine(self, child);
+        }
+        #[cfg(not(windows))]
+        let _ = child;
+    }
+
+    /// Arguments giving a container these limits, in place of the default ones.
+    pub(super) fn container_args(&self, memory: &str, pids: &str) -> Vec<String> {
+        let memory = self.memory_mb.map_or_else(|| memory.to_string(), |memory_mb| format!("{}m", memory_mb));
+        let pids = self.processes.map_or_else(|| pids.to_string(), |processes| processes.to_string());
+        let mut args = vec![
+            "--memory".to_string(),
+            memory.clone(),
+            "--memory-swap".to_string(),
+            memory,
+            "--pids-limit".to_string(),
+            pids,
+        ];
+        if let Some(seconds) = self.cpu_seconds {
+            args.push("--ulimit".to_string());
+            args.push(format!("cpu={}:{}", seconds, seconds + 1));
+        }
+        args
+    }
+
+    /// The limit a bridge that ended with `status` was killed for, if it can tell.
+    /// Running out of memory shows up as failed allocations in the target instead.
+    pub(super) fn exceeded(&self, status: ExitStatus) -> Option<String> {
+        let seconds = self.cpu_seconds?;
+        #[cfg(unix)]
+        let exceeded = std::os::unix::process::ExitStatusExt::signal(&status) == Some(libc::SIGXCPU);
+        // ERROR_NOT_ENOUGH_QUOTA, what a job ends a process past its time limit with.
+        #[cfg(windows)]
+        let exceeded = status.code() == Some(1816);
+        #[cfg(not(any(unix, windows)))]
+        let exceeded = false;
+        exceeded.then(|| format!("its CPU time limit of {}s", seconds))
+    }
+
+    /// Why a bridge that failed before finishing a single execution could not run,
+    /// when the memory cap is set and its stderr says it ran out of memory: the cap is
+    /// too low for the `language` runtime itself.
+    pub(super) fn memory_too_low(&self, language: &str, status: ExitStatus, finished: usize, stderr: &str) -> Option<String> {
+        let memory_mb = self.memory_mb?;
+        let stderr = stderr.to_lowercase();
+        let out_of_memory = OUT_OF_MEMORY_MARKERS.iter().any(|marker| stderr.contains(marker));
+        (!status.success() && finished == 0 && out_of_memory).then(|| {
+            format!(
+                "resource_limit: --bridge-memory {} MB is too low for the {} runtime; the bridge ran out of \
+                 memory before finishing an execution, raise --bridge-memory",
+                memory_mb, language
+            )
+        })
+    }
+}
+
+#[cfg(windows)]
+mod job {
+    use super::ResourceLimits;
+    use tokio::process::Child;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_ACTIVE_PROCESS, JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+        JOB_OBJECT_LIMIT_PROCESS_TIME,
+    };
+
+    /// Assigns `child` to a new job with `limits`, nested in the job Ctrl+C terminates.
+    /// The job lasts as long as its processes once the handle is closed.
+    pub(super) fn confine(limits: &ResourceLimits, child: &Child) {
+        let Some(process) = child.raw_handle() else {
+            return;
+        };
+        // SAFETY: the limit structure is plain data, valid zeroed, and outlives the calls;
+        // the job handle is closed once, after the assignment.
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                tracing::warn!("Failed to create a job object; the bridge runs without resource limits");
+                return;
+            }
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            if let Some(memory_mb) = limits.memory_mb {
+                info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+                info.ProcessMemoryLimit = (memory_mb as usize).saturating_mul(1024 * 1024);
+            }
+            if let Some(seconds) = limits.cpu_seconds {
+                info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_TIME;
+                // In 100-nanosecond ticks.
+                info.BasicLimitInformation.PerProcessUserTimeLimit = (seconds as i64).saturating_mul(10_000_000);
+            }
+            if let Some(processes) = limits.processes {
+                info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+                info.BasicLimitInformation.ActiveProcessLimit = processes.min(u32::MAX as u64) as u32;
+            }
+            let applied = SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &info as *const JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *const _,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            );
+            if applied == 0 || AssignProcessToJobObject(job, process as _) == 0 {
+                tracing::warn!("Failed to apply resource limits to the bridge process");
+            }
+            CloseHandle(job);
+        }
+    }
+}
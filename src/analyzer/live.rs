@@ -0,0 +1,116 @@
+//! Live bridges
+//! Every running bridge process is registered here so Ctrl+C can kill it along with
+//! whatever the target started. On Unix that is the bridge's process group; on Windows
+//! each bridge is assigned to a job object the orchestrator owns, which its children
+//! join, and the job is terminated. The job is also closed, killing its processes, when
+//! the orchestrator exits any other way. It only kills: what keeps the console's Ctrl+C
+//! from reaching the bridges is that they start in a new process group.
+
+use std::sync::Mutex;
+use tokio::process::Child;
+
+/// Process group ids (the bridges' pids) of running bridges.
+static LIVE: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// Registration of one running bridge; dropping it removes the bridge from the registry.
+pub(super) struct LiveBridge {
+    pid: Option<u32>,
+}
+
+impl Drop for LiveBridge {
+    fn drop(&mut self) {
+        let Some(pid) = self.pid else {
+            return;
+        };
+        if let Ok(mut live) = LIVE.lock() {
+            if let Some(index) = live.iter().position(|live| *live == pid) {
+                live.swap_remove(index);
+            }
+        }
+    }
+}
+
+/// Registers `child`, spawned as the leader of its own process group. A bridge started
+/// while Ctrl+C was being handled is killed right away.
+pub(super) fn track(child: &Child) -> LiveBridge {
+    let pid = child.id();
+    #[cfg(windows)]
+    job::assign(child);
+    if let (Some(pid), Ok(mut live)) = (pid, LIVE.lock()) {
+        live.push(pid);
+    }
+    if crate::interrupt::interrupted() {
+        kill_all();
+    }
+    LiveBridge { pid }
+}
+
+/// Kills every registered bridge and what it started.
+pub(super) fn kill_all() {
+    #[cfg(unix)]
+    if let Ok(live) = LIVE.lock() {
+        for pgid in live.iter() {
+            // SAFETY: plain syscall; each bridge was spawned as leader of its own group.
+            unsafe {
+                libc::kill(-(*pgid as i32), libc::SIGKILL);
+            }
+        }
+    }
+    #[cfg(windows)]
+    job::terminate();
+}
+
+#[cfg(windows)]
+mod job {
+    use std::sync::OnceLock;
+    use tokio::process::Child;
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    /// The job's handle as an integer, since raw handles are not `Send`; `None` when it
+    /// could not be created and bridges run outside a job.
+    static JOB: OnceLock<Option<usize>> = OnceLock::new();
+
+    fn handle() -> Option<usize> {
+        *JOB.get_or_init(|| {
+            // SAFETY: the limit structure is plain data, valid zeroed, and outlives the call.
+            unsafe {
+                let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if job.is_null() {
+                    tracing::warn!("Failed to create a job object; Ctrl+C may leave bridges running");
+                    return None;
+                }
+                let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                SetInformationJobObject(
+                    job,
+                    JobObjectExtendedLimitInformation,
+                    &limits as *const JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *const _,
+                    std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                );
+                Some(job as usize)
+            }
+        })
+    }
+
+    pub(super) fn assign(child: &Child) {
+        let (Some(job), Some(process)) = (handle(), child.raw_handle()) else {
+            return;
+        };
+        // SAFETY: both handles are open; the job lives as long as the orchestrator.
+        if unsafe { AssignProcessToJobObject(job as _, process as _) } == 0 {
+            tracing::warn!("Failed to add bridge process to the job object; Ctrl+C may leave it running");
+        }
+    }
+
+    pub(super) fn terminate() {
+        if let Some(Some(job)) = JOB.get() {
+            // SAFETY: the job handle stays open until the orchestrator exits.
+            unsafe {
+                TerminateJobObject(*job as _, crate::interrupt::EXIT_CODE as u32);
+            }
+        }
+    }
+}
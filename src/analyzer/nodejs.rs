@@ -1,14 +1,18 @@
 use anyhow::Result;
 use tokio::process::Command;
-use crate::analyzer::BridgeAnalyzer;
+use crate::analyzer::{locate_bridge, BridgeAnalyzer, BridgeLocation, Requirements, SelfTestTarget};
 use crate::protocol::AnalyzerInfo;
 
 pub async fn create() -> Result<BridgeAnalyzer> {
     let node_path = find_node().await?;
-    let bridge_script = crate::analyzer::workspace_root()?
-        .join("analyzers/nodejs/analyzer_bridge.js")
-        .to_string_lossy()
-        .to_string();
+    let bridge = locate()?;
+    let bridge_script = bridge.path.to_string_lossy().to_string();
+    // The self-test target ships next to the bridge script.
+    let bridge_dir = bridge.path.parent().map(|dir| dir.to_path_buf()).unwrap_or_default();
+    let self_test = SelfTestTarget {
+        target: format!("{}:retainBuffer", bridge_dir.join("selftest_target.js").display()),
+        expect_escape: Some(true),
+    };
 
     Ok(BridgeAnalyzer::new(
         "javascript",
@@ -22,14 +26,32 @@ pub async fn create() -> Result<BridgeAnalyzer> {
             executable_path: node_path,
         },
         |target| target.ends_with(".js") || target.ends_with(".mjs") || target.ends_with(".cjs"),
-    ))
+    )
+    .with_self_test(self_test))
 }
 
-async fn find_node() -> Result<String> {
+pub fn requirements() -> Requirements {
+    Requirements {
+        language: "javascript",
+        toolchain: node_candidates().into_iter().map(|node| vec![node, "--version".into()]).collect(),
+        install_hint: "install Node.js from https://nodejs.org/",
+        bridge: locate(),
+        build: None,
+    }
+}
+
+fn locate() -> Result<BridgeLocation> {
+    locate_bridge("javascript", &["analyzers/nodejs/analyzer_bridge.js"])
+}
+
+fn node_candidates() -> Vec<String> {
     let mut candidates = vec!["node".to_string(), "nodejs".to_string()];
     candidates.extend(common_windows_node_paths());
+    candidates
+}
 
-    for candidate in candidates {
+async fn find_node() -> Result<String> {
+    for candidate in node_candidates() {
         if let Ok(output) = Command::new(&candidate).arg("--version").output().await {
             if output.status.success() {
                 return Ok(candidate);
@@ -0,0 +1,123 @@
+//! Orphan cleanup
+//! Bridges run as leaders of their own process group, so whatever the target spawned
+//! is still in that group when the bridge is done with it. Those processes outlived the
+//! analysis: they are killed and reported as escape evidence. A process that starts its
+//! own session or group has left the group and is not found. Unix only.
+
+use crate::protocol::{AnalyzeRequest, EscapeDetails, Vulnerability};
+
+/// Input reported for orphans that cannot be tied to a single input.
+const CLEANUP_INPUT: &str = "[bridge cleanup]";
+
+/// A process found running in a bridge's group after the bridge was done.
+#[derive(Debug, Clone)]
+pub(super) struct Orphan {
+    pub pid: u32,
+    pub name: String,
+}
+
+/// Kills what is left of process group `pgid` apart from `keep`, a daemon bridge that
+/// goes on serving requests, and returns what was killed.
+pub(super) fn reap_group(pgid: u32, keep: Option<u32>) -> Vec<Orphan> {
+    let orphans: Vec<Orphan> = group_members(pgid)
+        .into_iter()
+        .filter(|orphan| Some(orphan.pid) != keep)
+        .collect();
+    #[cfg(unix)]
+    if keep.is_none() {
+        // SAFETY: plain syscall; the whole group, so processes forked since the listing go too.
+        unsafe {
+            libc::kill(-(pgid as i32), libc::SIGKILL);
+        }
+    } else {
+        for orphan in &orphans {
+            // SAFETY: plain syscall on a member of a group this orchestrator created.
+            unsafe {
+                libc::kill(orphan.pid as i32, libc::SIGKILL);
+            }
+        }
+    }
+    orphans
+}
+
+/// Vulnerability for processes killed after a bridge served `request`. `shared` is the
+/// number of targets the bridge process served, any of which may have started them.
+pub(super) fn orphan_vulnerability(orphans: &[Orphan], request: &AnalyzeRequest, shared: usize) -> Vulnerability {
+    let processes = orphans
+        .iter()
+        .map(|orphan| serde_json::json!({ "pid": orphan.pid, "name": orphan.name, "killed": true }))
+        .collect();
+    let listed: Vec<String> = orphans.iter().map(|orphan| format!("{} ({})", orphan.pid, orphan.name)).collect();
+    let mut description = format!(
+        "{} process(es) started during the analysis were still running after the bridge finished and were killed: {}",
+        orphans.len(),
+        listed.join(", ")
+    );
+    if shared > 1 {
+        description.push_str(&format!(
+            "; the bridge process served {} targets, rerun with --batch-size 1 to attribute them",
+            shared
+        ));
+    }
+    let input = match request.inputs.as_slice() {
+        [only] if shared <= 1 => only.clone(),
+        _ => CLEANUP_INPUT.to_string(),
+    };
+    Vulnerability {
+        input,
+        vulnerability_type: "orphan_process".to_string(),
+        severity: "Critical".to_string(),
+        description,
+        escape_details: EscapeDetails {
+            processes: Some(processes),
+            ..EscapeDetails::default()
+        },
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn group_members(pgid: u32) -> Vec<Orphan> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            let stat = std::fs::read_to_string(entry.path().join("stat")).ok()?;
+            // `pid (name) state ppid pgrp ...`; the name may itself contain parentheses.
+            let (head, rest) = stat.rsplit_once(')')?;
+            let name = head.split_once('(')?.1.to_string();
+            let fields: Vec<&str> = rest.split_whitespace().collect();
+            let state = *fields.first()?;
+            let group: u32 = fields.get(2)?.parse().ok()?;
+            (group == pgid && state != "Z").then_some(Orphan { pid, name })
+        })
+        .collect()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn group_members(pgid: u32) -> Vec<Orphan> {
+    let Ok(output) = std::process::Command::new("ps")
+        .args(["-A", "-o", "pid=", "-o", "pgid=", "-o", "stat=", "-o", "comm="])
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let pid: u32 = words.next()?.parse().ok()?;
+            let group: u32 = words.next()?.parse().ok()?;
+            let state = words.next()?;
+            let name = words.collect::<Vec<_>>().join(" ");
+            (group == pgid && !state.starts_with('Z')).then_some(Orphan { pid, name })
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+fn group_members(_pgid: u32) -> Vec<Orphan> {
+    Vec::new()
+}
@@ -1,14 +1,20 @@
 use anyhow::Result;
 use tokio::process::Command;
-use crate::analyzer::BridgeAnalyzer;
+use crate::analyzer::{locate_bridge, BridgeAnalyzer, BridgeLocation, Requirements, SelfTestTarget};
 use crate::protocol::AnalyzerInfo;
 
+const PYTHON_NAMES: &[&str] = &["python3", "python", "py"];
+
 pub async fn create() -> Result<BridgeAnalyzer> {
     let python_path = find_python().await?;
-    let bridge_script = crate::analyzer::workspace_root()?
-        .join("analyzers/python/analyzer_bridge.py")
-        .to_string_lossy()
-        .to_string();
+    let bridge = locate()?;
+    let bridge_script = bridge.path.to_string_lossy().to_string();
+    // The self-test target ships next to the bridge script.
+    let bridge_dir = bridge.path.parent().map(|dir| dir.to_path_buf()).unwrap_or_default();
+    let self_test = SelfTestTarget {
+        target: format!("{}:retain_buffer", bridge_dir.join("selftest_target.py").display()),
+        expect_escape: Some(true),
+    };
 
     Ok(BridgeAnalyzer::new(
         "python",
@@ -22,11 +28,26 @@ pub async fn create() -> Result<BridgeAnalyzer> {
             executable_path: python_path,
         },
         |target| target.ends_with(".py") || !target.contains('.'),
-    ))
+    )
+    .with_self_test(self_test))
+}
+
+pub fn requirements() -> Requirements {
+    Requirements {
+        language: "python",
+        toolchain: PYTHON_NAMES.iter().map(|name| vec![name.to_string(), "--version".into()]).collect(),
+        install_hint: "install Python 3 from https://www.python.org/downloads/",
+        bridge: locate(),
+        build: None,
+    }
+}
+
+fn locate() -> Result<BridgeLocation> {
+    locate_bridge("python", &["analyzers/python/analyzer_bridge.py"])
 }
 
 async fn find_python() -> Result<String> {
-    for name in &["python3", "python", "py"] {
+    for name in PYTHON_NAMES {
         if let Ok(output) = Command::new(name).arg("--version").output().await {
             if output.status.success() {
                 return Ok(name.to_string());
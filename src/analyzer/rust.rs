@@ -1,18 +1,24 @@
 use anyhow::Result;
-use crate::analyzer::BridgeAnalyzer;
+use crate::analyzer::{locate_bridge, BridgeAnalyzer, BridgeBuild, BridgeLocation, BuildCommand, Requirements, SelfTestTarget};
 use crate::protocol::AnalyzerInfo;
 use std::env;
 
+pub const BUILD: BuildCommand = BuildCommand {
+    dir: "",
+    command: &["cargo", "build", "--release", "-p", "rust-escape-analyzer"],
+};
+
 pub async fn create() -> Result<BridgeAnalyzer> {
-    let binary_name = format!("rust-analyzer{}", env::consts::EXE_SUFFIX);
-    let bridge_binary = crate::analyzer::workspace_root()?
-        .join("target")
-        .join("release")
-        .join(&binary_name)
-        .to_string_lossy()
-        .to_string();
+    let bridge = locate()?;
+    let bridge_binary = bridge.path.to_string_lossy().to_string();
+    // The Rust bridge builds targets from the tests/rust crate into a shared library it
+    // loads in process; the first case, which retains its payload, is the self-test.
+    let self_test = SelfTestTarget {
+        target: "escape_tests_rust::case_001_cache_profile::case_001_cache_profile".into(),
+        expect_escape: Some(true),
+    };
 
-    Ok(BridgeAnalyzer::new(
+    let analyzer = BridgeAnalyzer::new(
         "rust",
         vec![bridge_binary.clone()],
         None, // health check = binary existence check (handled by BridgeAnalyzer)
@@ -23,6 +29,45 @@ pub async fn create() -> Result<BridgeAnalyzer> {
             supported_features: crate::analyzer::standardized_object_escape_capabilities(),
             executable_path: bridge_binary,
         },
-        |target| target.ends_with(".rs") || target.contains("::"),
-    ))
+        |target| target.ends_with(".rs") || target.contains("::") || is_prebuilt_library(target),
+    )
+    .with_self_test(self_test);
+
+    // Bridges from elsewhere are not built from the sources in this checkout.
+    let Some(checkout) = &bridge.checkout else {
+        return Ok(analyzer);
+    };
+    Ok(analyzer.with_build(BridgeBuild {
+        artifact: bridge.path.clone(),
+        sources: vec![
+            checkout.join("analyzers/rust/src"),
+            checkout.join("analyzers/rust/Cargo.toml"),
+        ],
+        rebuild_hint: BUILD.hint(),
+    }))
+}
+
+/// Cargo builds the bridge as well as the targets it loads.
+pub fn requirements() -> Requirements {
+    Requirements {
+        language: "rust",
+        toolchain: vec![vec!["cargo".into(), "--version".into()]],
+        install_hint: "install Rust with rustup (https://rustup.rs)",
+        bridge: locate(),
+        build: Some(BUILD),
+    }
+}
+
+/// In a checkout, the bridge is its release build.
+fn locate() -> Result<BridgeLocation> {
+    let binary = format!("target/release/rust-analyzer{}", env::consts::EXE_SUFFIX);
+    locate_bridge("rust", &[&binary])
+}
+
+/// `path/libfoo.so:symbol` (or `.dylib`/`.dll`): a prebuilt library exporting the
+/// bridge's C ABI shim.
+pub(crate) fn is_prebuilt_library(target: &str) -> bool {
+    target
+        .rsplit_once(':')
+        .is_some_and(|(path, _)| [".so", ".dylib", ".dll"].iter().any(|ext| path.ends_with(ext)))
 }
@@ -0,0 +1,272 @@
+//! Container sandbox
+//! With the `sandbox` option each bridge process runs in a throwaway container of its
+//! language's image instead of on the host: no network, capped memory, CPUs and
+//! process count, and every host path it needs (the working directory, the target's
+//! source, the repository and the bridge itself) mounted read-only at the same
+//! location. Requests and responses still travel over the container's stdin and
+//! stdout, so bridges run unchanged. Whatever the target leaves running dies with the
+//! container.
+
+use super::limits::ResourceLimits;
+use super::{
+    base_bridge_environment, bridge_environment, bridge_workdir, env_name_matches, workspace_root, BASE_BRIDGE_ENV,
+};
+use crate::protocol::{option_keys, AnalyzeRequest};
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::process::{Child, Command};
+
+/// Container runtimes the `sandbox` option accepts; each is also its command.
+const RUNTIMES: &[&str] = &["docker"];
+
+/// Default image per language, the interpreter that replaces the bridge command's
+/// program inside it (compiled bridges run the mounted binary) and the repository
+/// paths given an empty writable tmpfs because the bridge builds into them.
+const IMAGES: &[(&str, &str, Option<&str>, &[&str])] = &[
+    ("python", "python:3.12-slim", Some("python3"), &[]),
+    ("javascript", "node:20-slim", Some("node"), &[]),
+    ("java", "eclipse-temurin:21-jre", Some("java"), &[]),
+    ("go", "golang:1.22", None, &[]),
+    ("rust", "rust:1-slim", None, &["target/graphene-rust-targets"]),
+];
+
+/// Resource limits of every container: room for the Go and Rust bridges' target
+/// builds, but not for a runaway target to take the host down with it. The
+/// `--bridge-memory`, `--bridge-processes` and `--bridge-cpu` caps replace them.
+const MEMORY_LIMIT: &str = "2g";
+const CPU_LIMIT: &str = "2";
+const PIDS_LIMIT: &str = "512";
+
+/// Orchestrator variables the runtime client itself needs, on top of the base set.
+const RUNTIME_ENV: &[&str] = &["DOCKER_*"];
+
+/// How long `docker kill` may take before the container is left to the runtime.
+const KILL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Running containers by the pid of the runtime client attached to them. Killing the
+/// client does not stop its container.
+static CONTAINERS: Mutex<Vec<(u32, Container)>> = Mutex::new(Vec::new());
+
+/// A container started for one bridge process.
+#[derive(Debug)]
+pub(super) struct Container {
+    runtime: String,
+    name: String,
+}
+
+/// Where and how a sandboxed bridge runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Sandbox {
+    runtime: String,
+    image: String,
+    interpreter: Option<String>,
+    /// Host paths mounted read-only at the same location.
+    mounts: Vec<PathBuf>,
+    /// Paths covered by an empty writable tmpfs.
+    scratch: Vec<PathBuf>,
+    workdir: PathBuf,
+    /// Variables set inside the container: the explicit and passed-through ones. The
+    /// host's base set (`PATH`, `HOME`, toolchain locations) would not fit the image.
+    environment: Vec<(String, String)>,
+}
+
+impl Sandbox {
+    /// The sandbox `request` asks for running `bridge_cmd`, `None` when it runs on the
+    /// host.
+    pub(super) fn for_request(lang: &str, bridge_cmd: &[String], request: &AnalyzeRequest) -> Result<Option<Self>> {
+        let Some(runtime) = request
+            .options
+            .get(option_keys::SANDBOX)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+        else {
+            return Ok(None);
+        };
+        if !RUNTIMES.contains(&runtime) {
+            bail!("Unknown sandbox `{}`; expected one of: {}", runtime, RUNTIMES.join(", "));
+        }
+        let overrides: HashMap<String, String> = match request.options.get(option_keys::SANDBOX_IMAGES) {
+            Some(images) => serde_json::from_str(images)
+                .with_context(|| format!("Malformed {} option", option_keys::SANDBOX_IMAGES))?,
+            None => HashMap::new(),
+        };
+        let (_, image, interpreter, scratch) = IMAGES
+            .iter()
+            .find(|(language, ..)| *language == lang)
+            .with_context(|| format!("No sandbox image for {} bridges", lang))?;
+        let workspace = workspace_root().ok();
+        let current = std::env::current_dir().context("Failed to read the current directory")?;
+        let workdir = bridge_workdir(request).unwrap_or_else(|| current.clone());
+
+        let scratch: Vec<PathBuf> = scratch
+            .iter()
+            .filter_map(|path| Some(workspace.as_ref()?.join(path)))
+            .collect();
+        for path in &scratch {
+            // The mount point has to exist in the read-only mount above it.
+            let _ = std::fs::create_dir_all(path);
+        }
+
+        let mut sandbox = Self {
+            runtime: runtime.to_string(),
+            image: overrides.get(lang).cloned().unwrap_or_else(|| image.to_string()),
+            interpreter: interpreter.map(str::to_string),
+            mounts: Vec::new(),
+            scratch,
+            workdir: workdir.clone(),
+            environment: sandbox_environment(request),
+        };
+        sandbox.mount(&current);
+        sandbox.mount(&workdir);
+        if let Some(workspace) = &workspace {
+            sandbox.mount(workspace);
+        }
+        // The program is only needed when it is not replaced by the image's interpreter.
+        let skip = usize::from(sandbox.interpreter.is_some());
+        for arg in bridge_cmd.iter().skip(skip) {
+            // Classpaths list several paths.
+            for part in arg.split(':').map(Path::new).filter(|part| part.is_absolute()) {
+                sandbox.mount_containing(part);
+            }
+        }
+        sandbox.mount_target(&request.target);
+        Ok(Some(sandbox))
+    }
+
+    /// Mounts the source of `target` (`file.ext:function` or a module path).
+    pub(super) fn mount_target(&mut self, target: &str) {
+        let file = target.split(':').next().unwrap_or(target);
+        let Ok(file) = std::path::absolute(self.workdir.join(file)) else {
+            return;
+        };
+        self.mount_containing(&file);
+    }
+
+    /// `bridge_cmd` run in a new container with `extra` set for it. Stdio is left to
+    /// the caller.
+    pub(super) fn command(
+        &self,
+        bridge_cmd: &[String],
+        extra: &[(&str, &str)],
+        limits: &ResourceLimits,
+    ) -> Result<(Command, Container)> {
+        let (program, args) = bridge_cmd
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("Empty bridge command"))?;
+        let name = format!("graphene-{}", uuid::Uuid::new_v4().simple());
+        let mut command = Command::new(&self.runtime);
+        command
+            .env_clear()
+            .envs(base_bridge_environment(RUNTIME_ENV))
+            .args(["run", "--rm", "--interactive", "--init", "--name", &name])
+            .args(["--network", "none", "--security-opt", "no-new-privileges"])
+            .args(limits.container_args(MEMORY_LIMIT, PIDS_LIMIT))
+            .args(["--cpus", CPU_LIMIT]);
+        for mount in &self.mounts {
+            let path = mount.display();
+            command.arg("--mount").arg(format!("type=bind,source={},target={},readonly", path, path));
+        }
+        for scratch in &self.scratch {
+            command.arg("--mount").arg(format!("type=tmpfs,destination={}", scratch.display()));
+        }
+        command.arg("--workdir").arg(&self.workdir);
+        let variables = self
+            .environment
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .chain(extra.iter().copied());
+        for (key, value) in variables {
+            command.arg("--env").arg(format!("{}={}", key, value));
+        }
+        command
+            .arg(&self.image)
+            .arg(self.interpreter.as_deref().unwrap_or(program))
+            .args(args);
+        Ok((command, Container { runtime: self.runtime.clone(), name }))
+    }
+
+    /// Mounts `path` when it is a directory, otherwise the directory holding it.
+    fn mount_containing(&mut self, path: &Path) {
+        if path.is_dir() {
+            self.mount(path);
+        } else if let Some(parent) = path.parent().filter(|_| path.exists()) {
+            self.mount(parent);
+        }
+    }
+
+    fn mount(&mut self, path: &Path) {
+        let Ok(path) = std::path::absolute(path) else {
+            return;
+        };
+        // Mounting `/` would replace the image's own filesystem.
+        if path.parent().is_none() || !path.exists() || self.mounts.iter().any(|mount| path.starts_with(mount)) {
+            return;
+        }
+        self.mounts.retain(|mount| !mount.starts_with(&path));
+        self.mounts.push(path);
+    }
+}
+
+/// Starts `command`, remembering `container` so `kill_container` can stop it.
+pub(super) fn spawn(command: &mut Command, container: Option<Container>) -> std::io::Result<Child> {
+    let child = command.spawn()?;
+    if let (Some(container), Some(pid)) = (container, child.id()) {
+        if let Ok(mut containers) = CONTAINERS.lock() {
+            containers.push((pid, container));
+        }
+    }
+    Ok(child)
+}
+
+/// Stops the container `child` is attached to, if any.
+pub(super) async fn kill_container(child: &Child) {
+    let Some(container) = child.id().and_then(forget) else {
+        return;
+    };
+    let kill = Command::new(&container.runtime)
+        .args(["kill", &container.name])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    let _ = tokio::time::timeout(KILL_TIMEOUT, kill).await;
+}
+
+/// Stops every running container, waiting for each `docker kill`, for Ctrl+C.
+pub(super) fn kill_all() {
+    let Ok(containers) = CONTAINERS.lock() else {
+        return;
+    };
+    for (_, container) in containers.iter() {
+        let _ = std::process::Command::new(&container.runtime)
+            .args(["kill", &container.name])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+/// Drops the record of the container attached to `pid`, whose client has exited and
+/// taken it down.
+pub(super) fn forget(pid: u32) -> Option<Container> {
+    let mut containers = CONTAINERS.lock().ok()?;
+    let index = containers.iter().position(|(client, _)| *client == pid)?;
+    Some(containers.swap_remove(index).1)
+}
+
+fn sandbox_environment(request: &AnalyzeRequest) -> Vec<(String, String)> {
+    let explicit: HashMap<String, String> = request
+        .options
+        .get(option_keys::ENV)
+        .and_then(|env| serde_json::from_str(env).ok())
+        .unwrap_or_default();
+    bridge_environment(request)
+        .into_iter()
+        .filter(|(key, _)| {
+            explicit.contains_key(key) || !BASE_BRIDGE_ENV.iter().any(|pattern| env_name_matches(pattern, key))
+        })
+        .collect()
+}
@@ -0,0 +1,185 @@
+//! Analyzer accuracy benchmark
+//! `graphene-ha bench` analyzes every labeled target of the tests tree (labels are read
+//! as for `run-all --verify`) once per analysis mode and records, per language and
+//! mode, the confusion counts, precision, recall and analysis latency. The report is
+//! written as `bench.json` and `bench.md`; a previous `bench.json` given as baseline
+//! adds the change of each figure, for comparing releases of the analyzers.
+
+use crate::verify::{percent, Tally, Verdict};
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const JSON_FILE: &str = "bench.json";
+pub const MARKDOWN_FILE: &str = "bench.md";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub generated_at: String,
+    /// Analyzer version per language
+    pub analyzers: BTreeMap<String, String>,
+    pub rows: Vec<BenchRow>,
+}
+
+impl BenchReport {
+    pub fn new(analyzers: BTreeMap<String, String>, rows: Vec<BenchRow>) -> Self {
+        Self {
+            generated_at: Local::now().to_rfc3339(),
+            analyzers,
+            rows,
+        }
+    }
+}
+
+/// Results of one language in one analysis mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchRow {
+    pub language: String,
+    /// `static` or `dynamic`
+    pub mode: String,
+    pub targets: usize,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    pub true_negatives: usize,
+    /// Targets the analysis produced no results for
+    pub no_results: usize,
+    pub precision: Option<f64>,
+    pub recall: Option<f64>,
+    /// Mean analysis time of a target (executions and static analysis)
+    pub mean_ms: f64,
+    /// 95th percentile of the analysis time of a target
+    pub p95_ms: u64,
+    /// Wall-clock time of the language's run in this mode, bridge startup included
+    pub wall_ms: u64,
+}
+
+impl BenchRow {
+    pub fn new(language: &str, mode: &str, verdicts: &[Verdict], mut times_ms: Vec<u64>, wall_ms: u64) -> Self {
+        let mut tally = Tally::default();
+        for verdict in verdicts {
+            tally.add(verdict);
+        }
+        times_ms.sort_unstable();
+        let mean_ms = if times_ms.is_empty() {
+            0.0
+        } else {
+            times_ms.iter().sum::<u64>() as f64 / times_ms.len() as f64
+        };
+        let p95_ms = times_ms
+            .get((times_ms.len() * 95).div_ceil(100).saturating_sub(1))
+            .copied()
+            .unwrap_or(0);
+        Self {
+            language: language.to_string(),
+            mode: mode.to_string(),
+            targets: verdicts.len(),
+            true_positives: tally.true_positives,
+            false_positives: tally.false_positives,
+            false_negatives: tally.false_negatives,
+            true_negatives: tally.true_negatives,
+            no_results: tally.no_results,
+            precision: tally.precision(),
+            recall: tally.recall(),
+            mean_ms,
+            p95_ms,
+            wall_ms,
+        }
+    }
+}
+
+pub fn load(path: &Path) -> Result<BenchReport> {
+    let text = fs::read_to_string(path).with_context(|| format!("Failed to read benchmark {}", path.display()))?;
+    serde_json::from_str(&text).with_context(|| format!("Malformed benchmark {}", path.display()))
+}
+
+/// Writes `bench.json` and `bench.md` into `dir` and returns their paths.
+pub fn write_report(report: &BenchReport, baseline: Option<&BenchReport>, dir: &Path) -> Result<(PathBuf, PathBuf)> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let json = dir.join(JSON_FILE);
+    fs::write(&json, serde_json::to_string_pretty(report)?).with_context(|| format!("Failed to write {}", json.display()))?;
+    let markdown = dir.join(MARKDOWN_FILE);
+    fs::write(&markdown, render_markdown(report, baseline))
+        .with_context(|| format!("Failed to write {}", markdown.display()))?;
+    Ok((json, markdown))
+}
+
+fn render_markdown(report: &BenchReport, baseline: Option<&BenchReport>) -> String {
+    let mut out = String::from("# Analyzer Benchmark\n\n");
+    out.push_str(&format!("Generated: {}\n\n", report.generated_at));
+    for (language, version) in &report.analyzers {
+        out.push_str(&format!("- {} analyzer {}\n", language, version));
+    }
+    if let Some(baseline) = baseline {
+        out.push_str(&format!("\nChanges are against the benchmark of {}.\n", baseline.generated_at));
+    }
+    out.push_str("\n| Language | Mode | Targets | TP | FP | FN | TN | No Results | Precision | Recall | Mean ms | p95 ms | Wall ms |\n");
+    out.push_str("|----------|------|---------|----|----|----|----|------------|-----------|--------|---------|--------|---------|\n");
+    for row in &report.rows {
+        let previous = baseline.and_then(|baseline| previous_row(baseline, row));
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} | {} | {} | {:.1}{} | {} | {} |\n",
+            row.language,
+            row.mode,
+            row.targets,
+            row.true_positives,
+            row.false_positives,
+            row.false_negatives,
+            row.true_negatives,
+            row.no_results,
+            with_change(row.precision, previous.and_then(|previous| previous.precision)),
+            with_change(row.recall, previous.and_then(|previous| previous.recall)),
+            row.mean_ms,
+            previous.map_or(String::new(), |previous| format!(" ({:+.1})", row.mean_ms - previous.mean_ms)),
+            row.p95_ms,
+            row.wall_ms,
+        ));
+    }
+    out
+}
+
+/// Prints one line per language and mode, with the change against `baseline`.
+pub fn print_report(report: &BenchReport, baseline: Option<&BenchReport>) {
+    println!("\n╔════════════════════════════════════════════╗");
+    println!("║            Analyzer Benchmark              ║");
+    println!("╚════════════════════════════════════════════╝\n");
+    println!(
+        "{:<12} {:<8} {:>7} {:>5} {:>5} {:>5} {:>5} {:>16} {:>16} {:>9} {:>8}",
+        "language", "mode", "targets", "TP", "FP", "FN", "TN", "precision", "recall", "mean ms", "p95 ms"
+    );
+    for row in &report.rows {
+        let previous = baseline.and_then(|baseline| previous_row(baseline, row));
+        println!(
+            "{:<12} {:<8} {:>7} {:>5} {:>5} {:>5} {:>5} {:>16} {:>16} {:>9.1} {:>8}",
+            row.language,
+            row.mode,
+            row.targets,
+            row.true_positives,
+            row.false_positives,
+            row.false_negatives,
+            row.true_negatives,
+            with_change(row.precision, previous.and_then(|previous| previous.precision)),
+            with_change(row.recall, previous.and_then(|previous| previous.recall)),
+            row.mean_ms,
+            row.p95_ms
+        );
+    }
+}
+
+fn previous_row<'a>(baseline: &'a BenchReport, row: &BenchRow) -> Option<&'a BenchRow> {
+    baseline
+        .rows
+        .iter()
+        .find(|previous| previous.language == row.language && previous.mode == row.mode)
+}
+
+/// A ratio as a percentage, followed by its change in points when both are known.
+fn with_change(value: Option<f64>, previous: Option<f64>) -> String {
+    match (value, previous) {
+        (Some(value), Some(previous)) => format!("{} ({:+.1})", percent(Some(value)), (value - previous) * 100.0),
+        _ => percent(value),
+    }
+}
@@ -0,0 +1,76 @@
+//! Bridge builds
+//! `graphene-ha build-bridges` compiles the bridges that run from build outputs (Go,
+//! Rust, Java) in this checkout, streaming each build's output, then lists which ones
+//! built and why the others did not. The Python and Node.js bridges run from source.
+
+use anyhow::Result;
+use std::io::ErrorKind;
+use std::time::Instant;
+use tokio::process::Command;
+use crate::analyzer::{builtin_requirements, workspace_root, BridgeSource, Requirements};
+use crate::orchestrator::{init_logging, normalize_language_filter};
+
+enum Outcome {
+    Built(f64),
+    Failed(String),
+}
+
+pub async fn build_bridges(language_filter: Option<String>) -> Result<()> {
+    init_logging(false);
+
+    let workspace = workspace_root()?;
+    let normalized_filter = language_filter.as_deref().map(normalize_language_filter);
+    let builds: Vec<Requirements> = builtin_requirements()
+        .into_iter()
+        .filter(|requirements| normalized_filter.as_deref().is_none_or(|filter| filter == requirements.language))
+        .filter(|requirements| requirements.build.is_some())
+        .collect();
+    if builds.is_empty() {
+        anyhow::bail!("No compiled bridge matches the language filter; the Python and Node.js bridges need no build");
+    }
+
+    let mut outcomes = Vec::new();
+    for requirements in &builds {
+        let build = requirements.build.expect("only compiled bridges are built");
+        let dir = workspace.join(build.dir);
+        println!("\n▶ Building the {} bridge: {}", requirements.language, build.hint());
+        let (program, args) = build.command.split_first().expect("build commands are not empty");
+        let started = Instant::now();
+        // Inherited stdio streams the toolchain's own progress and errors.
+        let outcome = match Command::new(program).args(args).current_dir(&dir).status().await {
+            Ok(status) if status.success() => Outcome::Built(started.elapsed().as_secs_f64()),
+            Ok(status) => Outcome::Failed(format!("`{}` failed ({}); see its output above", build.hint(), status)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Outcome::Failed(format!("{} not found in PATH", program)),
+            Err(e) => Outcome::Failed(format!("could not run {} in {}: {}", program, dir.display(), e)),
+        };
+        outcomes.push((requirements, outcome));
+    }
+
+    println!("\n╔════════════════════════════════════════════╗");
+    println!("║            Bridge Build Summary            ║");
+    println!("╚════════════════════════════════════════════╝\n");
+    let mut failures = 0;
+    for (requirements, outcome) in &outcomes {
+        let language = requirements.language;
+        match outcome {
+            Outcome::Built(seconds) => {
+                println!("✓ {}: built in {:.1}s", language, seconds);
+                // The config file, the environment and install-bridges come before the checkout.
+                if let Some(bridge) = requirements.bridge.as_ref().ok().filter(|bridge| bridge.source != BridgeSource::Checkout) {
+                    println!("  note: {} ({}) is used instead of this build", bridge.path.display(), bridge.describe(language));
+                }
+            }
+            Outcome::Failed(reason) => {
+                failures += 1;
+                println!("✗ {}: {}", language, reason);
+            }
+        }
+    }
+    println!();
+
+    if failures > 0 {
+        anyhow::bail!("{} of {} bridge(s) failed to build", failures, outcomes.len());
+    }
+    println!("Run `graphene-ha doctor` to check the analyzers.");
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! Git-scoped analysis
+//! `--since <ref>` on `scan` and `run-all` limits analysis to what changed since a git
+//! ref, so pull request pipelines only pay for the code they touch. `git diff` against
+//! the working tree (committed, staged and unstaged changes) gives the changed lines
+//! of each file; untracked files count as changed throughout. A function is analyzed
+//! when its definition overlaps a changed line, or when its file changed and the
+//! function cannot be located in it.
+
+use crate::static_analyzer::callgraph;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::info;
+
+/// What changed in one file.
+#[derive(Debug, Clone)]
+enum FileChange {
+    /// A new or untracked file.
+    Whole,
+    /// Changed line ranges (1-based, inclusive) in the current file.
+    Lines(Vec<(usize, usize)>),
+}
+
+/// Files and lines changed since a git ref.
+#[derive(Debug)]
+pub struct ChangedLines {
+    reference: String,
+    files: HashMap<PathBuf, FileChange>,
+}
+
+impl ChangedLines {
+    /// Changes between `reference` and the working tree of the repository holding `dir`.
+    pub fn since(reference: &str, dir: &Path) -> Result<Self> {
+        let root = git(dir, &["rev-parse", "--show-toplevel"])
+            .with_context(|| format!("{} is not inside a git repository", dir.display()))?;
+        let root = PathBuf::from(root.trim());
+        // Fail on a ref that does not exist before diffing against it.
+        git(&root, &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", reference)])
+            .with_context(|| format!("Unknown git ref `{}`", reference))?;
+
+        let diff = git(
+            &root,
+            &["-c", "core.quotePath=false", "diff", "--unified=0", "--no-color", "--no-ext-diff", "--no-renames", reference, "--"],
+        )?;
+        let mut files: HashMap<PathBuf, FileChange> = HashMap::new();
+        for (path, ranges) in parse_diff(&diff) {
+            let change = if ranges.is_empty() { FileChange::Whole } else { FileChange::Lines(ranges) };
+            files.insert(root.join(path), change);
+        }
+        let untracked = git(&root, &["-c", "core.quotePath=false", "ls-files", "--others", "--exclude-standard"])?;
+        for path in untracked.lines().filter(|path| !path.is_empty()) {
+            files.insert(root.join(path), FileChange::Whole);
+        }
+        info!("{} file(s) changed since {}", files.len(), reference);
+        Ok(Self {
+            reference: reference.to_string(),
+            files,
+        })
+    }
+
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    /// Whether `path` changed at all.
+    pub fn touches_file(&self, path: &Path) -> bool {
+        self.change(path).is_some()
+    }
+
+    /// Whether `function` of the `language` file at `path` overlaps a changed line.
+    pub fn touches_function(&self, path: &Path, language: &str, function: &str) -> bool {
+        match self.change(path) {
+            None => false,
+            Some(FileChange::Whole) => true,
+            Some(FileChange::Lines(ranges)) => {
+                let Ok(source) = std::fs::read_to_string(path) else {
+                    return true;
+                };
+                let spans: Vec<(usize, usize)> = callgraph::function_spans(language, &source)
+                    .into_iter()
+                    .filter(|(name, _, _)| name == function)
+                    .map(|(_, start, end)| (start, end))
+                    .collect();
+                spans.is_empty() || spans.iter().any(|span| overlaps(*span, ranges))
+            }
+        }
+    }
+
+    /// The functions of `names` in the `language` `source` of `path` that changed.
+    pub fn changed_functions(&self, path: &Path, language: &str, source: &str, names: Vec<String>) -> Vec<String> {
+        match self.change(path) {
+            None => Vec::new(),
+            Some(FileChange::Whole) => names,
+            Some(FileChange::Lines(ranges)) => {
+                let spans = callgraph::function_spans(language, source);
+                names
+                    .into_iter()
+                    .filter(|name| {
+                        spans
+                            .iter()
+                            .filter(|(defined, _, _)| defined == name)
+                            .any(|(_, start, end)| overlaps((*start, *end), ranges))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn change(&self, path: &Path) -> Option<&FileChange> {
+        let path = std::fs::canonicalize(path).ok()?;
+        self.files.get(&path).or_else(|| {
+            // The repository root may itself be reached through a symlink.
+            self.files
+                .iter()
+                .find(|(changed, _)| std::fs::canonicalize(changed).is_ok_and(|changed| changed == path))
+                .map(|(_, change)| change)
+        })
+    }
+}
+
+fn overlaps((start, end): (usize, usize), ranges: &[(usize, usize)]) -> bool {
+    ranges.iter().any(|(from, to)| *from <= end && start <= *to)
+}
+
+/// Changed line ranges per file of a `git diff --unified=0`; deleted files are left out
+/// and files without hunks (binary or mode changes) have no ranges.
+fn parse_diff(diff: &str) -> Vec<(String, Vec<(usize, usize)>)> {
+    let mut files: Vec<(String, Vec<(usize, usize)>)> = Vec::new();
+    let mut current: Option<usize> = None;
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            current = None;
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            current = path.strip_prefix("b/").map(|path| {
+                files.push((path.to_string(), Vec::new()));
+                files.len() - 1
+            });
+        } else if let (Some(hunk), Some(index)) = (line.strip_prefix("@@ "), current) {
+            // `@@ -a,b +c,d @@`: `d` lines from `c` in the new file, none when only
+            // lines after `c` were removed.
+            let Some(added) = hunk.split_whitespace().find_map(|part| part.strip_prefix('+')) else {
+                continue;
+            };
+            let (start, count) = added.split_once(',').unwrap_or((added, "1"));
+            let (Ok(start), Ok(count)) = (start.parse::<usize>(), count.parse::<usize>()) else {
+                continue;
+            };
+            let range = if count == 0 {
+                (start.max(1), start + 1)
+            } else {
+                (start, start + count - 1)
+            };
+            files[index].1.push(range);
+        }
+    }
+    files
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.trim().is_empty() {
+            anyhow::bail!("git {} exited with {}", args.join(" "), output.status);
+        }
+        anyhow::bail!("git {} failed: {}", args.join(" "), stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
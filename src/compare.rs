@@ -0,0 +1,325 @@
+//! Cross-language differential testing
+//! Runs one logical function implemented in several languages with identical inputs and
+//! reports where the implementations disagree: outputs, crash behavior and escape counts.
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use crate::analyzer::AnalyzerRegistry;
+use crate::input_gen;
+use crate::orchestrator::{
+    init_logging, normalize_language_filter, resolve_source_file, run_static_analysis, BridgeSettings,
+};
+use crate::static_analyzer::signature::ParamKind;
+use crate::protocol::{AnalysisMode, AnalyzeRequest, ExecutionResult, PROTOCOL_VERSION};
+
+/// Inputs used when neither the manifest nor the command line provides any.
+const DEFAULT_INPUT_COUNT: usize = 10;
+
+#[derive(Debug, Deserialize)]
+struct CompareManifest {
+    functions: Vec<ComparedFunction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ComparedFunction {
+    name: String,
+    /// Target per language; files are resolved relative to the manifest.
+    targets: BTreeMap<String, String>,
+    /// Inputs for this function, instead of the command-line inputs.
+    #[serde(default)]
+    inputs: Vec<String>,
+}
+
+/// What one implementation did with one input, over all repeats.
+#[derive(Debug, Default)]
+struct InputOutcome {
+    /// `ok`, or the error kind of a failed execution (`runtime_crash`, `timeout`, ...).
+    behavior: String,
+    /// Normalized output of successful executions.
+    output: String,
+    escapes: usize,
+}
+
+/// Results of one language's implementation.
+struct Implementation {
+    language: String,
+    static_escapes: Option<usize>,
+    outcomes: BTreeMap<String, InputOutcome>,
+    /// Why this implementation could not be run at all.
+    error: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn run_compare(
+    manifest_path: PathBuf,
+    inputs: Vec<String>,
+    repeat: usize,
+    timeout: f64,
+    output_dir: PathBuf,
+    analysis_mode: AnalysisMode,
+    bridge: BridgeSettings,
+) -> Result<()> {
+    init_logging(false);
+
+    let manifest: CompareManifest = serde_json::from_str(
+        &fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Failed to read compare manifest: {}", manifest_path.display()))?,
+    )
+    .with_context(|| format!("Invalid compare manifest: {}", manifest_path.display()))?;
+    let manifest_dir = manifest_path.parent().unwrap_or(Path::new("."));
+    let registry = AnalyzerRegistry::initialize_all().await?;
+
+    println!("\n╔════════════════════════════════════════════╗");
+    println!("║     Cross-Language Differential Test       ║");
+    println!("╚════════════════════════════════════════════╝\n");
+
+    let mut report = vec![format!("# Cross-language comparison: {}\n", manifest_path.display())];
+    let mut divergences = 0;
+    for function in &manifest.functions {
+        let inputs = if !function.inputs.is_empty() {
+            function.inputs.clone()
+        } else if !inputs.is_empty() {
+            inputs.clone()
+        } else {
+            // Typed from the first implementation whose signature says what it takes.
+            let kind = function
+                .targets
+                .iter()
+                .map(|(language, target)| {
+                    let target = resolve_target(manifest_dir, target);
+                    let source_file = resolve_source_file(&target).ok();
+                    let language = normalize_language_filter(language);
+                    input_gen::input_parameter(&target, &language, source_file.as_deref())
+                })
+                .find_map(|parameter| parameter.map(|parameter| parameter.kind).filter(|kind| *kind != ParamKind::Unknown))
+                .unwrap_or(ParamKind::Unknown);
+            input_gen::generate_typed(kind, DEFAULT_INPUT_COUNT)
+        };
+
+        let mut implementations = Vec::new();
+        for (language, target) in &function.targets {
+            let language = normalize_language_filter(language);
+            let target = resolve_target(manifest_dir, target);
+            implementations.push(
+                run_implementation(&registry, &language, &target, &inputs, repeat, timeout, analysis_mode, &bridge)
+                    .await,
+            );
+        }
+
+        let languages: Vec<&str> = implementations.iter().map(|i| i.language.as_str()).collect();
+        let heading = format!("{} ({})", function.name, languages.join(", "));
+        println!("═ {}", heading);
+        report.push(format!("## {}\n", heading));
+
+        let lines = diff_implementations(&implementations, &inputs);
+        if lines.is_empty() {
+            println!("  ✓ all implementations agree on {} input(s)\n", inputs.len());
+            report.push(format!("All implementations agree on {} input(s).\n", inputs.len()));
+            continue;
+        }
+        for line in &lines {
+            println!("  {}", line);
+            match line.strip_prefix("    ") {
+                Some(detail) => report.push(format!("    - {}", detail)),
+                None => {
+                    divergences += 1;
+                    report.push(format!("- {}", line));
+                }
+            }
+        }
+        println!();
+        report.push(String::new());
+    }
+    registry.shutdown().await;
+
+    let compare_dir = output_dir.join("compare");
+    fs::create_dir_all(&compare_dir)?;
+    let report_path = compare_dir.join(format!("compare_{}.md", Local::now().format("%Y%m%d_%H%M%S")));
+    fs::write(&report_path, report.join("\n"))
+        .with_context(|| format!("Failed to write {}", report_path.display()))?;
+
+    println!("{} function(s) compared, {} divergence(s)", manifest.functions.len(), divergences);
+    println!("📁 Report written to: {}\n", report_path.display());
+
+    if divergences > 0 {
+        anyhow::bail!("{} divergence(s) between implementations", divergences);
+    }
+    Ok(())
+}
+
+/// `file.ext:function` targets whose file exists next to the manifest are resolved
+/// against it; anything else is used as written.
+fn resolve_target(manifest_dir: &Path, target: &str) -> String {
+    if target.contains("::") {
+        return target.to_string();
+    }
+    match target.split_once(':') {
+        Some((file, function)) if !Path::new(file).is_absolute() && manifest_dir.join(file).is_file() => {
+            format!("{}:{}", manifest_dir.join(file).display(), function)
+        }
+        _ => target.to_string(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_implementation(
+    registry: &AnalyzerRegistry,
+    language: &str,
+    target: &str,
+    inputs: &[String],
+    repeat: usize,
+    timeout: f64,
+    analysis_mode: AnalysisMode,
+    bridge: &BridgeSettings,
+) -> Implementation {
+    let mut implementation = Implementation {
+        language: language.to_string(),
+        static_escapes: None,
+        outcomes: BTreeMap::new(),
+        error: None,
+    };
+
+    if analysis_mode != AnalysisMode::Dynamic {
+        match run_static_analysis(target, Some(language), AnalysisMode::Static).await {
+            Ok(response) => {
+                implementation.static_escapes = Some(
+                    response
+                        .static_analysis
+                        .map(|result| result.escapes.len())
+                        .unwrap_or_default(),
+                );
+            }
+            Err(e) => implementation.error = Some(format!("static analysis failed: {:#}", e)),
+        }
+    }
+
+    if analysis_mode != AnalysisMode::Static {
+        let Some(analyzer) = registry.find_analyzer(target, Some(language)) else {
+            implementation.error = Some(format!("{} analyzer unavailable", language));
+            return implementation;
+        };
+        let (target, options) = bridge.target_request(target);
+        let request = AnalyzeRequest {
+            protocol_version: PROTOCOL_VERSION,
+            session_id: Uuid::new_v4().to_string(),
+            target,
+            inputs: inputs.to_vec(),
+            input_encodings: Vec::new(),
+            input_args: Vec::new(),
+            repeat,
+            timeout_seconds: timeout,
+            options,
+            analysis_mode: AnalysisMode::Dynamic,
+        };
+        match analyzer.analyze(request).await {
+            Ok(response) => {
+                if let Some(error) = &response.error {
+                    implementation.error = Some(format!("{}: {}", error.kind.as_str(), error));
+                }
+                for input in inputs {
+                    let runs: Vec<&ExecutionResult> =
+                        response.results.iter().filter(|r| &r.input_data == input).collect();
+                    if !runs.is_empty() {
+                        implementation.outcomes.insert(input.clone(), input_outcome(&runs));
+                    }
+                }
+            }
+            Err(e) => implementation.error = Some(format!("dynamic analysis failed: {:#}", e)),
+        }
+    }
+    implementation
+}
+
+fn input_outcome(runs: &[&ExecutionResult]) -> InputOutcome {
+    let mut behaviors: Vec<String> = Vec::new();
+    let mut outputs: Vec<String> = Vec::new();
+    for run in runs {
+        let behavior = if run.success && !run.crashed {
+            "ok".to_string()
+        } else {
+            run.error
+                .as_ref()
+                .map(|error| error.kind.as_str().to_string())
+                .unwrap_or_else(|| "crashed".to_string())
+        };
+        if !behaviors.contains(&behavior) {
+            behaviors.push(behavior);
+        }
+        if run.success && !run.crashed {
+            let output = normalize_output(&run.output);
+            if !outputs.contains(&output) {
+                outputs.push(output);
+            }
+        }
+    }
+    InputOutcome {
+        behavior: behaviors.join("/"),
+        output: outputs.join(" | "),
+        escapes: runs.iter().filter(|run| run.escape_detected).count(),
+    }
+}
+
+/// Spells each runtime's null and boolean values the same way so `None`, `nil` and
+/// `null` outputs compare equal.
+fn normalize_output(output: &str) -> String {
+    match output.trim() {
+        "None" | "nil" | "<nil>" | "null" | "undefined" | "()" => "null".to_string(),
+        "True" => "true".to_string(),
+        "False" => "false".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// One line per divergence, followed by indented per-language values.
+fn diff_implementations(implementations: &[Implementation], inputs: &[String]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for implementation in implementations {
+        if let Some(error) = &implementation.error {
+            lines.push(format!("✗ {} could not be compared: {}", implementation.language, error));
+        }
+    }
+
+    let static_counts: Vec<(&str, usize)> = implementations
+        .iter()
+        .filter_map(|i| i.static_escapes.map(|count| (i.language.as_str(), count)))
+        .collect();
+    if static_counts.windows(2).any(|pair| pair[0].1 != pair[1].1) {
+        lines.push("✗ static escape counts differ".to_string());
+        lines.extend(static_counts.iter().map(|(language, count)| format!("    {}: {}", language, count)));
+    }
+
+    for input in inputs {
+        let outcomes: Vec<(&str, &InputOutcome)> = implementations
+            .iter()
+            .filter_map(|i| i.outcomes.get(input).map(|outcome| (i.language.as_str(), outcome)))
+            .collect();
+        if outcomes.len() < 2 {
+            continue;
+        }
+        for field in ["crash behavior", "output", "escape count"] {
+            // Outputs are only comparable between implementations that succeeded.
+            let compared: Vec<(&str, String)> = outcomes
+                .iter()
+                .filter(|(_, outcome)| field != "output" || outcome.behavior == "ok")
+                .map(|(language, outcome)| {
+                    let value = match field {
+                        "crash behavior" => outcome.behavior.clone(),
+                        "output" => outcome.output.clone(),
+                        _ => outcome.escapes.to_string(),
+                    };
+                    (*language, value)
+                })
+                .collect();
+            if compared.windows(2).any(|pair| pair[0].1 != pair[1].1) {
+                lines.push(format!("✗ input {:?}: {} differs", input, field));
+                lines.extend(compared.iter().map(|(language, value)| format!("    {}: {}", language, value)));
+            }
+        }
+    }
+    lines
+}
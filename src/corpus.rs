@@ -0,0 +1,104 @@
+//! Input corpora
+//! `--input-file` reads a JSON array of inputs or, when the file is not one, one input
+//! per non-blank line; `--input-dir` makes each file of a directory one input. Inputs
+//! that are not valid UTF-8 travel base64-encoded, marked `InputEncoding::Base64`, so
+//! the bridges can hand the target the original bytes. `--input-args` gives a target
+//! with several parameters one JSON array of arguments per input instead.
+
+use crate::protocol::InputEncoding;
+use anyhow::{Context, Result};
+use base64::Engine;
+use std::path::Path;
+
+/// Inputs for one analysis and the encoding of each
+#[derive(Debug, Clone, Default)]
+pub struct Inputs {
+    pub values: Vec<String>,
+    pub encodings: Vec<InputEncoding>,
+    /// Argument list of each input, parallel to `values`; empty for single-argument inputs.
+    pub args: Vec<Vec<serde_json::Value>>,
+}
+
+impl Inputs {
+    /// Inline `--input` strings.
+    pub fn inline(values: Vec<String>) -> Self {
+        let encodings = vec![InputEncoding::Utf8; values.len()];
+        Self {
+            values,
+            encodings,
+            args: Vec::new(),
+        }
+    }
+
+    /// One input per `--input-args` list, labelled with the list's JSON text.
+    pub fn arguments(args: Vec<Vec<serde_json::Value>>) -> Self {
+        let values: Vec<String> = args
+            .iter()
+            .map(|list| serde_json::to_string(list).unwrap_or_default())
+            .collect();
+        let encodings = vec![InputEncoding::Utf8; values.len()];
+        Self { values, encodings, args }
+    }
+
+    /// Adds the inputs of a JSON array or newline-delimited file.
+    pub fn load_file(&mut self, path: &Path) -> Result<()> {
+        let bytes = std::fs::read(path).with_context(|| format!("Failed to read input file {}", path.display()))?;
+        if let Ok(values) = serde_json::from_slice::<Vec<serde_json::Value>>(&bytes) {
+            for value in values {
+                match value {
+                    serde_json::Value::String(text) => self.push_text(text),
+                    // Numbers, objects and the like are passed as their JSON text.
+                    other => self.push_text(other.to_string()),
+                }
+            }
+            return Ok(());
+        }
+        for line in bytes.split(|&byte| byte == b'\n') {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if !line.iter().all(u8::is_ascii_whitespace) {
+                self.push_bytes(line);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds every regular, non-hidden file of `dir` as one input, in file name order.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<()> {
+        let mut files: Vec<_> = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read input directory {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .filter(|path| !path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')))
+            .collect();
+        files.sort();
+        for file in files {
+            let bytes = std::fs::read(&file).with_context(|| format!("Failed to read corpus file {}", file.display()))?;
+            self.push_bytes(&bytes);
+        }
+        Ok(())
+    }
+
+    /// Encodings for `AnalyzeRequest::input_encodings`: empty when every input is UTF-8.
+    pub fn request_encodings(&self) -> Vec<InputEncoding> {
+        if self.encodings.iter().all(|encoding| *encoding == InputEncoding::Utf8) {
+            Vec::new()
+        } else {
+            self.encodings.clone()
+        }
+    }
+
+    fn push_text(&mut self, text: String) {
+        self.values.push(text);
+        self.encodings.push(InputEncoding::Utf8);
+    }
+
+    fn push_bytes(&mut self, bytes: &[u8]) {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => self.push_text(text.to_string()),
+            Err(_) => {
+                self.values.push(base64::engine::general_purpose::STANDARD.encode(bytes));
+                self.encodings.push(InputEncoding::Base64);
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Regression diff between two runs
+//! Matches the escapes recorded in a baseline's report.json files against a current run's
+//! and lists which are new, fixed and persisting. New escapes fail the command, so CI can
+//! gate on "no new escapes" rather than on absolute counts.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::protocol::{AnalyzeResponse, EscapeDetails};
+use crate::report::REPORT_SCHEMA_VERSION;
+
+/// The parts of a report.json the diff reads.
+#[derive(Debug, Deserialize)]
+struct StoredReport {
+    schema_version: u32,
+    target: String,
+    response: AnalyzeResponse,
+}
+
+pub fn run_diff(baseline: PathBuf, current: PathBuf) -> Result<()> {
+    let before = load_escapes(&baseline)?;
+    let after = load_escapes(&current)?;
+
+    let new: Vec<&String> = after.iter().filter(|(key, _)| !before.contains_key(*key)).map(|(_, d)| d).collect();
+    let fixed: Vec<&String> = before.iter().filter(|(key, _)| !after.contains_key(*key)).map(|(_, d)| d).collect();
+    let persisting: Vec<&String> = after.iter().filter(|(key, _)| before.contains_key(*key)).map(|(_, d)| d).collect();
+
+    println!("\n╔════════════════════════════════════════════╗");
+    println!("║           Escape Regression Diff           ║");
+    println!("╚════════════════════════════════════════════╝\n");
+    println!("Baseline: {}", baseline.display());
+    println!("Current:  {}\n", current.display());
+
+    for (heading, marker, escapes) in [("New", "+", &new), ("Fixed", "-", &fixed), ("Persisting", "=", &persisting)] {
+        println!("{} ({}):", heading, escapes.len());
+        for escape in escapes.iter() {
+            println!("  {} {}", marker, escape);
+        }
+        println!();
+    }
+    println!("{} new, {} fixed, {} persisting", new.len(), fixed.len(), persisting.len());
+
+    if !new.is_empty() {
+        anyhow::bail!("{} new escape(s) since the baseline", new.len());
+    }
+    Ok(())
+}
+
+/// Escapes in every report under `path`, keyed so the same escape matches across runs.
+/// Keys leave out line numbers, which shift with unrelated edits, and dynamic inputs,
+/// which run-all generates afresh each time.
+fn load_escapes(path: &Path) -> Result<BTreeMap<String, String>> {
+    let mut escapes = BTreeMap::new();
+    for file in report_files(path)? {
+        let report: StoredReport = serde_json::from_str(
+            &fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file.display()))?,
+        )
+        .with_context(|| format!("Invalid report: {}", file.display()))?;
+        if report.schema_version > REPORT_SCHEMA_VERSION {
+            anyhow::bail!(
+                "{} uses report schema {}, newer than this tool's {}",
+                file.display(),
+                report.schema_version,
+                REPORT_SCHEMA_VERSION
+            );
+        }
+        collect_escapes(&report, &mut escapes);
+    }
+    Ok(escapes)
+}
+
+/// A report.json itself, the one in a session directory, or all of them below a
+/// directory of sessions (e.g. a run-all output directory).
+fn report_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    if path.join("report.json").is_file() {
+        return Ok(vec![path.join("report.json")]);
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            // Not through links such as `latest`, which would count a session twice.
+            if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                pending.push(entry_path);
+            } else if entry_path.file_name().is_some_and(|name| name == "report.json") {
+                files.push(entry_path);
+            }
+        }
+    }
+    if files.is_empty() {
+        anyhow::bail!("No report.json found under {}", path.display());
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn collect_escapes(report: &StoredReport, escapes: &mut BTreeMap<String, String>) {
+    let target = &report.target;
+    if let Some(static_result) = &report.response.static_analysis {
+        for escape in &static_result.escapes {
+            let rule = escape.rule.as_deref().unwrap_or("escape");
+            escapes.insert(
+                format!(
+                    "{}\0static\0{}\0{:?}\0{}\0{}",
+                    target, rule, escape.escape_type, escape.location.function, escape.variable_name
+                ),
+                format!(
+                    "{}: {:?} of `{}` in {} (line {}): {}",
+                    target,
+                    escape.escape_type,
+                    escape.variable_name,
+                    escape.location.function,
+                    escape.location.line,
+                    escape.reason
+                ),
+            );
+        }
+    }
+
+    // Per kind of escape seen, so a new thread leak is not hidden behind an existing
+    // heap escape of the same vulnerability type.
+    let mut dynamic: BTreeMap<(&str, String), usize> = BTreeMap::new();
+    for vuln in &report.response.vulnerabilities {
+        for kind in escape_kinds(&vuln.escape_details) {
+            *dynamic.entry((&vuln.vulnerability_type, kind)).or_default() += 1;
+        }
+    }
+    for ((vulnerability_type, kind), count) in dynamic {
+        escapes.insert(
+            format!("{}\0dynamic\0{}\0{}", target, vulnerability_type, kind),
+            format!("{}: dynamic {} ({}) on {} execution(s)", target, vulnerability_type, kind, count),
+        );
+    }
+}
+
+/// What escaped in one vulnerability: escape path types (`heap`, ...) and the kinds of
+/// work or resources left behind.
+fn escape_kinds(details: &EscapeDetails) -> BTreeSet<String> {
+    let mut kinds: BTreeSet<String> = details.escape_paths.iter().map(|path| path.escape_type.clone()).collect();
+    let left_behind = [
+        ("thread", &details.threads),
+        ("thread pool", &details.thread_pools),
+        ("process", &details.processes),
+        ("async task", &details.async_tasks),
+        ("goroutine", &details.goroutines),
+        ("file descriptor", &details.file_descriptors),
+        ("timer", &details.timers),
+    ];
+    for (kind, list) in left_behind {
+        if list.as_ref().is_some_and(|list| !list.is_empty()) {
+            kinds.insert(kind.to_string());
+        }
+    }
+    if kinds.is_empty() {
+        kinds.insert("unspecified".to_string());
+    }
+    kinds
+}
@@ -0,0 +1,180 @@
+//! Environment diagnostics
+//! `graphene-ha doctor` checks, for each analyzer, that its toolchain runs (and which
+//! version it is), that its bridge script or binary exists, that the bridge answers the
+//! protocol handshake, and that a self-test analysis through it succeeds. Anything
+//! missing comes with the command that installs or builds it, since an analyzer whose
+//! toolchain or bridge is missing is otherwise just left out of `list` and `run-all`.
+
+use anyhow::Result;
+use std::path::Path;
+use std::process::Command;
+use crate::analyzer::{bridge_env, builtin_requirements, external, workspace_root, Analyzer, AnalyzerRegistry, Requirements};
+use crate::orchestrator::{init_logging, normalize_language_filter};
+
+pub async fn run_doctor(language_filter: Option<String>) -> Result<()> {
+    init_logging(false);
+
+    let workspace = workspace_root()?;
+    let registry = AnalyzerRegistry::initialize_all().await?;
+    // Languages a config file definition took over are checked like any other custom analyzer.
+    let defined: Vec<String> = external::load_definitions()?
+        .into_iter()
+        .map(|definition| definition.language)
+        .collect();
+    let requirements: Vec<Requirements> = builtin_requirements()
+        .into_iter()
+        .filter(|requirements| !defined.iter().any(|language| language == requirements.language))
+        .collect();
+    let normalized_filter = language_filter
+        .as_deref()
+        .map(normalize_language_filter);
+    let selected = |language: &str| {
+        normalized_filter
+            .as_deref()
+            .map(|filter| normalize_language_filter(language) == filter)
+            .unwrap_or(true)
+    };
+
+    println!("\n╔════════════════════════════════════════════╗");
+    println!("║        Analyzer Environment Check          ║");
+    println!("╚════════════════════════════════════════════╝\n");
+
+    let mut failures = 0usize;
+    for requirements in requirements.iter().filter(|requirements| selected(requirements.language)) {
+        println!("{}", requirements.language);
+        let mut ready = true;
+        match toolchain_version(&requirements.toolchain) {
+            Some(version) => println!("  ✓ toolchain: {}", version),
+            None => {
+                ready = false;
+                let program = requirements.toolchain.first().and_then(|command| command.first());
+                println!(
+                    "  ✗ toolchain: {} not found in PATH; {}",
+                    program.map(String::as_str).unwrap_or("?"),
+                    requirements.install_hint
+                );
+            }
+        }
+        match &requirements.bridge {
+            Ok(bridge) if bridge.path.exists() => println!(
+                "  ✓ bridge: {} ({})",
+                display_path(&bridge.path, &workspace),
+                bridge.describe(requirements.language)
+            ),
+            Ok(bridge) => {
+                ready = false;
+                println!("  ✗ bridge: not found at {}", display_path(&bridge.path, &workspace));
+                if let Some(build) = &requirements.build {
+                    println!("      build it with `graphene-ha build-bridges --language {}`", requirements.language);
+                    println!("      (runs `{}` in {})", build.hint(), workspace.display());
+                    println!("      or download a release build with `graphene-ha install-bridges --version <version>`");
+                }
+                println!("      or point {} at the bridge", bridge_env(requirements.language));
+            }
+            Err(e) => {
+                ready = false;
+                println!("  ✗ bridge: {:#}", e);
+            }
+        }
+        if !ready {
+            failures += 1;
+            println!("  - ping and self-test skipped\n");
+            continue;
+        }
+        match registry.find_analyzer("", Some(requirements.language)) {
+            Some(analyzer) => {
+                if !check_bridge(analyzer).await {
+                    failures += 1;
+                }
+            }
+            None => {
+                failures += 1;
+                for failure in registry.initialization_failures() {
+                    if failure.language == requirements.language {
+                        println!("  ✗ unavailable: {}", failure.reason);
+                    }
+                }
+            }
+        }
+        println!();
+    }
+
+    // Analyzers from the config file: only their bridge can be checked.
+    for analyzer in registry.list_analyzers() {
+        let language = analyzer.language();
+        if !selected(language) || requirements.iter().any(|requirements| requirements.language == language) {
+            continue;
+        }
+        println!("{} (config file)", language);
+        if !check_bridge(analyzer).await {
+            failures += 1;
+        }
+        println!();
+    }
+    for failure in registry.initialization_failures() {
+        if !selected(&failure.language) || requirements.iter().any(|requirements| requirements.language == failure.language) {
+            continue;
+        }
+        failures += 1;
+        println!("{} (config file)\n  ✗ unavailable: {}\n", failure.language, failure.reason);
+    }
+
+    if failures > 0 {
+        anyhow::bail!("{} analyzer(s) are not ready", failures);
+    }
+    println!("All analyzers are ready.");
+    Ok(())
+}
+
+/// Pings the bridge with the protocol handshake, then sends its self-test target through
+/// it. Returns whether both passed.
+async fn check_bridge(analyzer: &dyn Analyzer) -> bool {
+    match analyzer.health_check().await {
+        Ok(health) if health.protocol_version == 0 => {
+            println!("  ✓ ping: no handshake (bridge predates protocol negotiation)")
+        }
+        Ok(health) => println!(
+            "  ✓ ping: protocol v{}, {} capabilities",
+            health.protocol_version,
+            health.capabilities.len()
+        ),
+        Err(e) => {
+            println!("  ✗ ping: {:#}", e);
+            return false;
+        }
+    }
+    let started = std::time::Instant::now();
+    match analyzer.deep_health_check().await {
+        Ok(_) => {
+            println!("  ✓ self-test passed in {:.1}s", started.elapsed().as_secs_f64());
+            true
+        }
+        Err(e) => {
+            println!("  ✗ self-test: {:#}", e);
+            false
+        }
+    }
+}
+
+/// First line of the version printed by the first toolchain command that runs; `java
+/// -version` prints to stderr.
+fn toolchain_version(commands: &[Vec<String>]) -> Option<String> {
+    commands.iter().find_map(|command| {
+        let (program, args) = command.split_first()?;
+        let output = Command::new(program).args(args).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let text = [output.stdout, output.stderr]
+            .iter()
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+            .find(|text| !text.trim().is_empty())?;
+        let version = text.lines().find(|line| !line.trim().is_empty())?.trim().to_string();
+        Some(format!("{} ({})", program, version))
+    })
+}
+
+/// `path` relative to the workspace when inside it.
+fn display_path(path: &Path, workspace: &Path) -> String {
+    path.strip_prefix(workspace).unwrap_or(path).display().to_string()
+}
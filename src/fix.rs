@@ -0,0 +1,280 @@
+//! Auto-fix
+//! `graphene-ha fix` applies the suggested fixes of a target's static findings that can
+//! be placed mechanically (see `static_analyzer::fixes`) and prints the change as a
+//! unified diff on stdout, so it can be reviewed, or saved and applied with
+//! `git apply`. Only findings at or above the chosen confidence are fixed, optionally
+//! only those of given rules. Fixes overlapping an earlier one, or of the same rule in
+//! the same function (they would declare the same names), are left for a later run.
+
+use crate::orchestrator::{detect_language_from_target, init_stderr_logging, resolve_source_file, run_static_analysis};
+use crate::protocol::{AnalysisMode, ConfidenceLevel, FixEdit, StaticEscape};
+use crate::scan;
+use crate::static_analyzer::callgraph;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Lines of unchanged source shown around each change.
+const DIFF_CONTEXT: usize = 3;
+
+/// Fixes `target` (`file:function`, or a whole file) and prints the diff; the file is
+/// only rewritten when `dry_run` is off.
+pub async fn run_fix(
+    target: String,
+    language: Option<String>,
+    rules: Vec<String>,
+    min_confidence: ConfidenceLevel,
+    dry_run: bool,
+    verbose: bool,
+) -> Result<()> {
+    init_stderr_logging(verbose);
+    let language = match language {
+        Some(language) => language,
+        None => detect_language_from_target(&target)?,
+    };
+    let (file, escapes) = if Path::new(&target).is_file() {
+        let source = std::fs::read_to_string(&target).with_context(|| format!("Failed to read {}", target))?;
+        let functions = callgraph::function_names(&language, &source);
+        (target.clone(), scan::file_escapes(&target, &language, functions).await.escapes)
+    } else {
+        let response = run_static_analysis(&target, Some(&language), AnalysisMode::Static).await?;
+        let escapes = response.static_analysis.map(|result| result.escapes).unwrap_or_default();
+        (resolve_source_file(&target)?, escapes)
+    };
+
+    let wanted = |escape: &&StaticEscape| {
+        escape.confidence >= min_confidence
+            && escape.location.file == file
+            && escape.suggested_fix.as_ref().is_some_and(|fix| !fix.edits.is_empty())
+            && (rules.is_empty()
+                || escape
+                    .rule_id
+                    .as_deref()
+                    .is_some_and(|id| rules.iter().any(|rule| rule.eq_ignore_ascii_case(id))))
+    };
+    let mut candidates: Vec<&StaticEscape> = escapes.iter().filter(wanted).collect();
+    candidates.sort_by_key(|escape| escape.location.line);
+
+    let mut edits: Vec<&FixEdit> = Vec::new();
+    let mut fixed: Vec<(&str, &str)> = Vec::new();
+    let mut applied = 0;
+    let mut deferred = 0;
+    for escape in candidates {
+        let fix = escape.suggested_fix.as_ref().expect("candidates have a fix");
+        let rule_id = escape.rule_id.as_deref().unwrap_or_default();
+        let key = (rule_id, escape.location.function.as_str());
+        let new_edits: Vec<&FixEdit> = fix.edits.iter().filter(|edit| !edits.contains(edit)).collect();
+        if new_edits.is_empty() {
+            continue;
+        }
+        let conflict = if fixed.contains(&key) {
+            Some("another fix of this rule in the function")
+        } else if new_edits.iter().any(|edit| edits.iter().any(|other| overlaps(edit, other))) {
+            Some("overlaps an earlier fix")
+        } else {
+            None
+        };
+        if let Some(conflict) = conflict {
+            eprintln!("Deferred {} at line {}: {}; run `fix` again", rule_id, escape.location.line, conflict);
+            deferred += 1;
+            continue;
+        }
+        eprintln!("Fixing {} at line {}: {}", rule_id, escape.location.line, fix.description);
+        edits.extend(new_edits);
+        fixed.push(key);
+        applied += 1;
+    }
+    if edits.is_empty() {
+        eprintln!("No fixable findings in {}", target);
+        return Ok(());
+    }
+
+    let source = std::fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file))?;
+    // Each line keeps its own ending, so the lines no edit touches are written back as read.
+    let old: Vec<String> = source.split_inclusive('\n').map(str::to_string).collect();
+    edits.sort_by_key(|edit| (edit.line, edit.replaced_lines));
+    let hunks = apply(&old, &edits);
+    let new: Vec<String> = hunks.iter().flat_map(|hunk| hunk.new.clone()).collect();
+    print!("{}", unified_diff(&file, &old, &hunks));
+
+    if dry_run {
+        eprintln!("{} fix(es) shown, {} deferred; {} was not changed (dry run)", applied, deferred, file);
+    } else {
+        std::fs::write(&file, new.concat()).with_context(|| format!("Failed to write {}", file))?;
+        eprintln!("{} fix(es) applied to {}, {} deferred", applied, file, deferred);
+    }
+    Ok(())
+}
+
+/// Whether two edits touch the same lines: replaced ranges that intersect, or an
+/// insertion inside or at the start of a replaced range.
+fn overlaps(edit: &FixEdit, other: &FixEdit) -> bool {
+    let range = |edit: &FixEdit| edit.line..edit.line + edit.replaced_lines;
+    let touches = |insert: &FixEdit, replace: &FixEdit| replace.replaced_lines > 0 && (range(replace).contains(&insert.line));
+    match (edit.replaced_lines, other.replaced_lines) {
+        (0, 0) => false,
+        (0, _) => touches(edit, other),
+        (_, 0) => touches(other, edit),
+        _ => range(edit).start < range(other).end && range(other).start < range(edit).end,
+    }
+}
+
+/// A run of the new file: either `old[old_start..old_start + old_len]` unchanged, or
+/// those old lines replaced by `new`.
+struct Hunk {
+    old_start: usize,
+    old_len: usize,
+    new: Vec<String>,
+    changed: bool,
+}
+
+/// The file as unchanged and changed runs, in order; `edits` are sorted and do not
+/// overlap. Edits past the end of the file append to it. Lines an edit writes take the
+/// ending of the line they replace or precede, and a file without a final line break
+/// still ends without one.
+fn apply(old: &[String], edits: &[&FixEdit]) -> Vec<Hunk> {
+    let mut hunks = Vec::new();
+    let mut next = 0;
+    for edit in edits {
+        let mut start = edit.line.saturating_sub(1).min(old.len()).max(next);
+        // Appending after a last line without a break gives that line one: it is replaced
+        // by itself plus the break, or gets it in the change that already replaced it.
+        let mut new: Vec<String> = Vec::new();
+        if start == old.len() && old.last().is_some_and(|line| line_ending(line).is_empty()) {
+            let ending = ending_near(old, start);
+            match hunks.last_mut().filter(|_| next == old.len()) {
+                Some(Hunk { new: previous, .. }) => {
+                    if let Some(last) = previous.last_mut().filter(|last| line_ending(last).is_empty()) {
+                        last.push_str(ending);
+                    }
+                }
+                None => {
+                    start -= 1;
+                    new.push(format!("{}{}", old[start], ending));
+                }
+            }
+        }
+        if start > next {
+            hunks.push(Hunk { old_start: next, old_len: start - next, new: old[next..start].to_vec(), changed: false });
+        }
+        let end = (start + edit.replaced_lines.max(new.len())).min(old.len());
+        let ending = ending_near(old, start);
+        new.extend(edit.text.lines().map(|line| format!("{}{}", line, ending)));
+        if end == old.len() && old.last().is_some_and(|line| line_ending(line).is_empty()) {
+            if let Some(last) = new.last_mut() {
+                last.truncate(last.len() - ending.len());
+            }
+        }
+        hunks.push(Hunk { old_start: start, old_len: end - start, new, changed: true });
+        next = end;
+    }
+    if next < old.len() {
+        hunks.push(Hunk { old_start: next, old_len: old.len() - next, new: old[next..].to_vec(), changed: false });
+    }
+    hunks
+}
+
+/// The line break `line` ends with: `\r\n`, `\n`, or none for a last line without one.
+fn line_ending(line: &str) -> &'static str {
+    if line.ends_with("\r\n") {
+        "\r\n"
+    } else if line.ends_with('\n') {
+        "\n"
+    } else {
+        ""
+    }
+}
+
+/// The line break of line `index`, or of the closest line before it that has one, so
+/// lines inserted into a file mixing `\r\n` and `\n` match their neighbours.
+fn ending_near(old: &[String], index: usize) -> &'static str {
+    old[..(index + 1).min(old.len())]
+        .iter()
+        .rev()
+        .map(|line| line_ending(line))
+        .find(|ending| !ending.is_empty())
+        .unwrap_or("\n")
+}
+
+/// One line of a unified diff; a line without a break is marked as such, as `git apply`
+/// expects.
+fn diff_line(prefix: char, line: &str) -> String {
+    if line_ending(line).is_empty() {
+        format!("{}{}\n\\ No newline at end of file\n", prefix, line)
+    } else {
+        format!("{}{}", prefix, line)
+    }
+}
+
+/// The changed runs as a unified diff with `DIFF_CONTEXT` lines of context, changes
+/// close enough to share context merged into one hunk.
+fn unified_diff(file: &str, old: &[String], hunks: &[Hunk]) -> String {
+    // Old and new line positions where each run starts.
+    let mut positions = Vec::new();
+    let mut new_line = 0;
+    for hunk in hunks {
+        positions.push(new_line);
+        new_line += hunk.new.len();
+    }
+    let changes: Vec<usize> = (0..hunks.len()).filter(|index| hunks[*index].changed).collect();
+
+    // Relative to the working directory when under it, as `git apply` expects.
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|dir| Path::new(file).strip_prefix(dir).ok().map(|path| path.display().to_string()))
+        .unwrap_or_else(|| file.to_string());
+    let path = relative.trim_start_matches("./").trim_start_matches('/').replace('\\', "/");
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", path, path);
+    let mut group: Vec<usize> = Vec::new();
+    for (position, index) in changes.iter().enumerate() {
+        group.push(*index);
+        let gap = changes.get(position + 1).map(|next| {
+            (index + 1..*next).map(|between| hunks[between].old_len).sum::<usize>()
+        });
+        if gap.is_some_and(|gap| gap <= 2 * DIFF_CONTEXT) {
+            continue;
+        }
+        let first = &hunks[group[0]];
+        let last = &hunks[*group.last().expect("group is not empty")];
+        let before = first.old_start.min(DIFF_CONTEXT);
+        let after = (old.len() - (last.old_start + last.old_len)).min(DIFF_CONTEXT);
+        let old_start = first.old_start - before;
+        let old_end = last.old_start + last.old_len + after;
+        let new_start = positions[group[0]] - before;
+
+        let mut body = String::new();
+        let mut new_len = 0;
+        for line in &old[old_start..first.old_start] {
+            body.push_str(&diff_line(' ', line));
+            new_len += 1;
+        }
+        for hunk in &hunks[group[0]..=*group.last().expect("group is not empty")] {
+            if hunk.changed {
+                for line in &old[hunk.old_start..hunk.old_start + hunk.old_len] {
+                    body.push_str(&diff_line('-', line));
+                }
+                for line in &hunk.new {
+                    body.push_str(&diff_line('+', line));
+                }
+            } else {
+                for line in &hunk.new {
+                    body.push_str(&diff_line(' ', line));
+                }
+            }
+            new_len += hunk.new.len();
+        }
+        for line in &old[last.old_start + last.old_len..old_end] {
+            body.push_str(&diff_line(' ', line));
+            new_len += 1;
+        }
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n{}",
+            old_start + 1,
+            old_end - old_start,
+            new_start + 1,
+            new_len,
+            body
+        ));
+        group.clear();
+    }
+    diff
+}
@@ -0,0 +1,220 @@
+//! Trend history
+//! `--history <db>` appends each `analyze` or `run-all` session to a SQLite database:
+//! the session's totals and duration, and every target's escape, vulnerability and
+//! crash counts. `graphene-ha trends` reads it back as escape counts per target over
+//! the recorded sessions, so a one-shot report becomes a series.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use rusqlite::{params, Connection};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Schema version kept in `PRAGMA user_version`.
+const SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS sessions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        session_id TEXT NOT NULL,
+        command TEXT NOT NULL,
+        recorded_at TEXT NOT NULL,
+        duration_ms INTEGER NOT NULL,
+        targets INTEGER NOT NULL,
+        escapes INTEGER NOT NULL,
+        vulnerabilities INTEGER NOT NULL,
+        crashes INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS target_results (
+        session INTEGER NOT NULL REFERENCES sessions(id),
+        language TEXT NOT NULL,
+        target TEXT NOT NULL,
+        analyzed INTEGER NOT NULL,
+        escapes INTEGER NOT NULL,
+        vulnerabilities INTEGER NOT NULL,
+        crashes INTEGER NOT NULL,
+        duration_ms INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS target_results_target ON target_results(target);
+";
+
+/// One recorded session.
+pub struct HistorySession {
+    pub session_id: String,
+    /// `analyze` or `run-all`
+    pub command: String,
+    pub duration_ms: u64,
+}
+
+/// What one session found for one target.
+pub struct HistoryTarget {
+    pub language: String,
+    pub target: String,
+    /// `false` when the target produced no results; its counts are then zero.
+    pub analyzed: bool,
+    pub escapes: usize,
+    pub vulnerabilities: usize,
+    pub crashes: usize,
+    pub duration_ms: u64,
+}
+
+/// Appends `session` and its targets to the database at `path`, creating it if needed.
+pub fn record_session(path: &Path, session: &HistorySession, targets: &[HistoryTarget]) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut connection = open(path)?;
+    let transaction = connection.transaction()?;
+    transaction.execute(
+        "INSERT INTO sessions (session_id, command, recorded_at, duration_ms, targets, escapes, vulnerabilities, crashes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            session.session_id,
+            session.command,
+            Local::now().to_rfc3339(),
+            session.duration_ms as i64,
+            targets.len() as i64,
+            targets.iter().map(|target| target.escapes).sum::<usize>() as i64,
+            targets.iter().map(|target| target.vulnerabilities).sum::<usize>() as i64,
+            targets.iter().map(|target| target.crashes).sum::<usize>() as i64,
+        ],
+    )?;
+    let session_row = transaction.last_insert_rowid();
+    {
+        let mut insert = transaction.prepare(
+            "INSERT INTO target_results (session, language, target, analyzed, escapes, vulnerabilities, crashes, duration_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        for target in targets {
+            insert.execute(params![
+                session_row,
+                target.language,
+                target.target,
+                target.analyzed,
+                target.escapes as i64,
+                target.vulnerabilities as i64,
+                target.crashes as i64,
+                target.duration_ms as i64,
+            ])?;
+        }
+    }
+    transaction.commit()?;
+    Ok(())
+}
+
+/// Prints escape counts per target over the last `sessions` recorded sessions, oldest
+/// first. `filter` keeps targets containing it; `changed` keeps targets whose count
+/// moved.
+pub fn print_trends(path: &Path, sessions: usize, filter: Option<&str>, changed: bool) -> Result<()> {
+    if !path.is_file() {
+        anyhow::bail!("No history database at {}", path.display());
+    }
+    let connection = open(path)?;
+
+    let mut statement = connection.prepare(
+        "SELECT id, command, recorded_at, duration_ms, targets, escapes FROM sessions ORDER BY id DESC LIMIT ?1",
+    )?;
+    let mut recorded: Vec<(i64, String, String, i64, i64, i64)> = statement
+        .query_map(params![sessions.max(1) as i64], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    recorded.reverse();
+    let Some(first) = recorded.first().map(|session| session.0) else {
+        println!("No sessions recorded in {}", path.display());
+        return Ok(());
+    };
+
+    // (language, target) -> escape count per session, `None` where it was not analyzed.
+    let mut series: BTreeMap<(String, String), Vec<Option<i64>>> = BTreeMap::new();
+    let mut statement = connection.prepare(
+        "SELECT session, language, target, analyzed, escapes FROM target_results WHERE session >= ?1",
+    )?;
+    let rows = statement.query_map(params![first], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, bool>(3)?,
+            row.get::<_, i64>(4)?,
+        ))
+    })?;
+    for row in rows {
+        let (session, language, target, analyzed, escapes) = row?;
+        if filter.is_some_and(|filter| !target.contains(filter)) {
+            continue;
+        }
+        let Some(index) = recorded.iter().position(|recorded| recorded.0 == session) else {
+            continue;
+        };
+        let counts = series.entry((language, target)).or_insert_with(|| vec![None; recorded.len()]);
+        counts[index] = analyzed.then_some(escapes);
+    }
+
+    println!("\n╔════════════════════════════════════════════╗");
+    println!("║              Escape Trends                 ║");
+    println!("╚════════════════════════════════════════════╝\n");
+    println!("Sessions (oldest first):");
+    for (index, (_, command, recorded_at, duration_ms, targets, escapes)) in recorded.iter().enumerate() {
+        println!(
+            "  #{} {} {:<8} {:>4} target(s) {:>5} escape(s) in {:.1}s",
+            index + 1,
+            format_timestamp(recorded_at),
+            command,
+            targets,
+            escapes,
+            *duration_ms as f64 / 1000.0
+        );
+    }
+
+    let mut shown = 0;
+    println!();
+    for ((language, target), counts) in &series {
+        let known: Vec<i64> = counts.iter().flatten().copied().collect();
+        let change = match (known.first(), known.last()) {
+            (Some(first), Some(last)) => last - first,
+            _ => 0,
+        };
+        if changed && known.windows(2).all(|pair| pair[0] == pair[1]) {
+            continue;
+        }
+        let cells: Vec<String> = counts
+            .iter()
+            .map(|count| count.map_or_else(|| "-".to_string(), |count| count.to_string()))
+            .collect();
+        let marker = match change {
+            0 => "  ".to_string(),
+            change if change > 0 => format!("↑{}", change),
+            change => format!("↓{}", -change),
+        };
+        println!("{:<10} {:<60} {}  {}", language, target, cells.join(" → "), marker);
+        shown += 1;
+    }
+    if shown == 0 {
+        println!("No targets{} in the recorded sessions.", if changed { " with changes" } else { "" });
+    }
+    Ok(())
+}
+
+fn open(path: &Path) -> Result<Connection> {
+    let connection =
+        Connection::open(path).with_context(|| format!("Failed to open history database {}", path.display()))?;
+    let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > SCHEMA_VERSION {
+        anyhow::bail!(
+            "History database {} has schema version {}, newer than this build's {}",
+            path.display(),
+            version,
+            SCHEMA_VERSION
+        );
+    }
+    connection.execute_batch(SCHEMA)?;
+    connection.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    Ok(connection)
+}
+
+fn format_timestamp(recorded_at: &str) -> String {
+    DateTime::parse_from_rfc3339(recorded_at)
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| recorded_at.to_string())
+}
@@ -0,0 +1,175 @@
+//! Input generation
+//! Generated inputs follow the target's signature: integers for numeric parameters,
+//! JSON objects for dicts, maps and structs, JSON arrays for lists and a size sweep for
+//! strings. Targets whose parameters cannot be read get the generic attack strings.
+//! Targets with several required parameters get argument lists instead, each parameter
+//! cycling through the values of its kind.
+
+use crate::static_analyzer::signature::{target_parameters, ParamKind, Parameter};
+use serde_json::Value;
+
+/// String lengths swept for text parameters.
+const TEXT_SIZES: &[usize] = &[0, 1, 16, 256, 4096, 65536];
+
+/// `target`'s first parameter, the one the bridges pass the input to; `None` when the
+/// source cannot be read or the function takes no parameters.
+pub fn input_parameter(target: &str, language: &str, source_file: Option<&str>) -> Option<Parameter> {
+    target_parameters(language, source_file?, target)?.into_iter().next()
+}
+
+/// The parameters of `target` a call has to pass, in order; `None` when the source
+/// cannot be read.
+pub fn required_parameters(target: &str, language: &str, source_file: Option<&str>) -> Option<Vec<Parameter>> {
+    let parameters = target_parameters(language, source_file?, target)?;
+    Some(parameters.into_iter().filter(|parameter| !parameter.optional).collect())
+}
+
+/// `count` argument lists for `parameters`.
+pub fn generate_arguments(parameters: &[Parameter], count: usize) -> Vec<Vec<Value>> {
+    let values: Vec<Vec<Value>> = parameters
+        .iter()
+        .map(|parameter| typed_values(parameter.kind, count))
+        .collect();
+    (0..count.max(1))
+        .map(|case| {
+            values
+                .iter()
+                .map(|values| values.get(case % values.len().max(1)).cloned().unwrap_or(Value::Null))
+                .collect()
+        })
+        .collect()
+}
+
+/// Values of `kind` as JSON: strings for text and untyped parameters, and only the typed
+/// inputs that parse as the kind for the others.
+fn typed_values(kind: ParamKind, count: usize) -> Vec<Value> {
+    if matches!(kind, ParamKind::Text | ParamKind::Unknown) {
+        return generate_typed(kind, count).into_iter().map(Value::String).collect();
+    }
+    typed_inputs(kind)
+        .iter()
+        .filter_map(|input| serde_json::from_str::<Value>(input).ok())
+        .filter(|value| match kind {
+            ParamKind::Integer => value.is_i64() || value.is_u64(),
+            ParamKind::Float => value.is_number(),
+            ParamKind::Boolean => value.is_boolean(),
+            ParamKind::Map => value.is_object(),
+            ParamKind::List => value.is_array(),
+            ParamKind::Text | ParamKind::Unknown => true,
+        })
+        .collect()
+}
+
+/// `count` inputs of `kind`, padded with the generic inputs when the kind has fewer.
+pub fn generate_typed(kind: ParamKind, count: usize) -> Vec<String> {
+    if count == 0 {
+        return vec![String::new()];
+    }
+    let mut inputs = typed_inputs(kind);
+    for input in generate_inputs(count) {
+        if inputs.len() >= count {
+            break;
+        }
+        if !inputs.contains(&input) {
+            inputs.push(input);
+        }
+    }
+    inputs.truncate(count);
+    inputs
+}
+
+fn typed_inputs(kind: ParamKind) -> Vec<String> {
+    let fixed: &[&str] = match kind {
+        ParamKind::Integer => &[
+            "0", "1", "-1", "2", "10", "100", "1000", "65536", "2147483647", "-2147483648", "9223372036854775807",
+        ],
+        ParamKind::Float => &["0.0", "1.5", "-1.5", "3.14159", "1e-308", "1.7976931348623157e308", "NaN", "Infinity"],
+        ParamKind::Boolean => &["true", "false", "1", "0"],
+        ParamKind::Map => &[
+            "{}",
+            r#"{"key": "value"}"#,
+            r#"{"id": 1, "name": "test", "tags": ["a", "b"], "active": true}"#,
+            r#"{"key": null}"#,
+            r#"{"a": {"b": {"c": {"d": {}}}}}"#,
+            r#"{"__proto__": {"polluted": true}}"#,
+        ],
+        ParamKind::List => &["[]", "[1]", "[1, 2, 3]", r#"["a", "b", "c"]"#, "[null]", "[[], [[]]]"],
+        ParamKind::Text => return TEXT_SIZES.iter().map(|size| "A".repeat(*size)).collect(),
+        ParamKind::Unknown => &[],
+    };
+    let mut inputs: Vec<String> = fixed.iter().map(|input| input.to_string()).collect();
+    // One large value of the kind to show per-element work that outlives the call.
+    match kind {
+        ParamKind::Map => inputs.push(format!(
+            "{{{}}}",
+            (0..1000).map(|i| format!("\"key{}\": {}", i, i)).collect::<Vec<_>>().join(", ")
+        )),
+        ParamKind::List => inputs.push(format!(
+            "[{}]",
+            (0..1000).map(|i| i.to_string()).collect::<Vec<_>>().join(", ")
+        )),
+        _ => {}
+    }
+    inputs
+}
+
+fn generate_inputs(count: usize) -> Vec<String> {
+    let mut inputs = vec![
+        "".to_string(),
+        "0".to_string(),
+        "-1".to_string(),
+        "1".to_string(),
+        "true".to_string(),
+        "false".to_string(),
+        "null".to_string(),
+        "undefined".to_string(),
+        "hello".to_string(),
+        "\\x00".to_string(),
+        "\\n".to_string(),
+        "\\t".to_string(),
+        "'".to_string(),
+        "\"".to_string(),
+        "()".to_string(),
+        "[]".to_string(),
+        "{}".to_string(),
+        "../".to_string(),
+        "..\\".to_string(),
+        "${HOME}".to_string(),
+        "$(whoami)".to_string(),
+        "{{7*7}}".to_string(),
+        "%s".to_string(),
+        "error".to_string(),
+        "exception".to_string(),
+        "async".to_string(),
+        "await".to_string(),
+        "timeout".to_string(),
+        "deadlock".to_string(),
+        "race".to_string(),
+        "concurrent".to_string(),
+        "<script>alert(1)</script>".to_string(),
+        "'; DROP TABLE; --".to_string(),
+        "../../../etc/passwd".to_string(),
+        "\\x1b[31m".to_string(),
+        "\\u0000".to_string(),
+    ];
+
+    inputs.push("A".repeat(1024));
+    inputs.push("1".repeat(100));
+    inputs.push("test".repeat(50));
+    inputs.push(" ".repeat(1000));
+    inputs.push("\\n".repeat(100));
+
+    if count == 0 {
+        return vec![String::new()];
+    }
+
+    if inputs.len() >= count {
+        return inputs.into_iter().take(count).collect();
+    }
+
+    while inputs.len() < count {
+        inputs.push(format!("input_{}", inputs.len() + 1));
+    }
+
+    inputs
+}
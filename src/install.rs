@@ -0,0 +1,219 @@
+//! Prebuilt bridge installation
+//! Downloads release builds of the compiled bridges (Go, Rust, Java) into a user-level
+//! directory that the analyzer modules check before their in-repo build outputs.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use crate::orchestrator::{init_logging, normalize_language_filter};
+
+/// Overrides the download location; `{version}` is replaced with the requested version.
+pub const BRIDGE_URL_ENV: &str = "GRAPHENE_BRIDGE_URL";
+/// Overrides the user-level Graphene HA directory (default `~/.graphene-ha`).
+pub const GRAPHENE_HOME_ENV: &str = "GRAPHENE_HOME";
+
+const DEFAULT_BRIDGE_URL: &str = "https://github.com/ganderson03/Graphene-HA/releases/download/v{version}";
+const CHECKSUMS_FILE: &str = "SHA256SUMS";
+const MANIFEST_FILE: &str = "installed.json";
+
+/// A bridge published as a prebuilt release artifact.
+struct PrebuiltBridge {
+    language: &'static str,
+    file_name: &'static str,
+    /// Native binaries are published per OS/architecture; the jar is not.
+    platform_specific: bool,
+}
+
+const PREBUILT_BRIDGES: &[PrebuiltBridge] = &[
+    PrebuiltBridge { language: "go", file_name: "go-bridge", platform_specific: true },
+    PrebuiltBridge { language: "rust", file_name: "rust-bridge", platform_specific: true },
+    PrebuiltBridge { language: "java", file_name: "java-bridge.jar", platform_specific: false },
+];
+
+impl PrebuiltBridge {
+    /// File name inside the install directory.
+    fn installed_name(&self) -> String {
+        if self.platform_specific {
+            format!("{}{}", self.file_name, env::consts::EXE_SUFFIX)
+        } else {
+            self.file_name.to_string()
+        }
+    }
+
+    /// Asset name in the release, e.g. `go-bridge-linux-x86_64`.
+    fn asset_name(&self) -> String {
+        if self.platform_specific {
+            format!(
+                "{}-{}-{}{}",
+                self.file_name,
+                env::consts::OS,
+                env::consts::ARCH,
+                env::consts::EXE_SUFFIX
+            )
+        } else {
+            self.file_name.to_string()
+        }
+    }
+}
+
+/// `installed.json`: what `install-bridges` has put in place, keyed by language.
+#[derive(Default, Serialize, Deserialize)]
+struct InstallManifest {
+    bridges: BTreeMap<String, InstalledBridge>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InstalledBridge {
+    file: String,
+    version: String,
+    sha256: String,
+    source: String,
+}
+
+/// Directory prebuilt bridges are installed into: `$GRAPHENE_HOME/bridges`,
+/// or `~/.graphene-ha/bridges`.
+pub fn installed_bridges_dir() -> Option<PathBuf> {
+    if let Some(home) = env::var_os(GRAPHENE_HOME_ENV).filter(|value| !value.is_empty()) {
+        return Some(PathBuf::from(home).join("bridges"));
+    }
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .filter(|value| !value.is_empty())
+        .map(|home| PathBuf::from(home).join(".graphene-ha").join("bridges"))
+}
+
+/// Installed prebuilt bridge for `language`, if `install-bridges` has put one in place.
+pub fn installed_bridge(language: &str) -> Option<PathBuf> {
+    let bridge = PREBUILT_BRIDGES.iter().find(|bridge| bridge.language == language)?;
+    let path = installed_bridges_dir()?.join(bridge.installed_name());
+    path.is_file().then_some(path)
+}
+
+pub async fn install_bridges(
+    version: String,
+    url: Option<String>,
+    language_filter: Option<String>,
+) -> Result<()> {
+    init_logging(false);
+
+    let install_dir = installed_bridges_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine a home directory; set {}", GRAPHENE_HOME_ENV))?;
+    fs::create_dir_all(&install_dir)
+        .with_context(|| format!("Failed to create {}", install_dir.display()))?;
+
+    let base_url = url
+        .or_else(|| env::var(BRIDGE_URL_ENV).ok().filter(|value| !value.trim().is_empty()))
+        .unwrap_or_else(|| DEFAULT_BRIDGE_URL.to_string())
+        .replace("{version}", &version);
+    let base_url = base_url.trim_end_matches('/');
+    let normalized_filter = language_filter.as_deref().map(normalize_language_filter);
+
+    println!("Installing prebuilt bridges {} from {}", version, base_url);
+    println!("Destination: {}\n", install_dir.display());
+
+    let checksums_path = install_dir.join(format!("{}.partial", CHECKSUMS_FILE));
+    download(&format!("{}/{}", base_url, CHECKSUMS_FILE), &checksums_path).await?;
+    let checksums = parse_checksums(&fs::read_to_string(&checksums_path)?);
+    let _ = fs::remove_file(&checksums_path);
+
+    let manifest_path = install_dir.join(MANIFEST_FILE);
+    let mut manifest: InstallManifest = fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+
+    let mut installed = 0;
+    for bridge in PREBUILT_BRIDGES {
+        if normalized_filter.as_deref().is_some_and(|filter| filter != bridge.language) {
+            continue;
+        }
+
+        let asset = bridge.asset_name();
+        let expected = checksums.get(&asset).ok_or_else(|| {
+            anyhow::anyhow!("{} does not list a checksum for {}", CHECKSUMS_FILE, asset)
+        })?;
+
+        let destination = install_dir.join(bridge.installed_name());
+        let partial = install_dir.join(format!("{}.partial", bridge.installed_name()));
+        download(&format!("{}/{}", base_url, asset), &partial).await?;
+
+        let actual = sha256_file(&partial)?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            let _ = fs::remove_file(&partial);
+            anyhow::bail!(
+                "Checksum mismatch for {}: expected {}, downloaded file has {}",
+                asset,
+                expected,
+                actual
+            );
+        }
+
+        #[cfg(unix)]
+        if bridge.platform_specific {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&partial, fs::Permissions::from_mode(0o755))?;
+        }
+        fs::rename(&partial, &destination)
+            .with_context(|| format!("Failed to install {}", destination.display()))?;
+
+        println!("✓ {} bridge → {}", bridge.language, destination.display());
+        manifest.bridges.insert(
+            bridge.language.to_string(),
+            InstalledBridge {
+                file: bridge.installed_name(),
+                version: version.clone(),
+                sha256: actual,
+                source: format!("{}/{}", base_url, asset),
+            },
+        );
+        installed += 1;
+    }
+
+    if installed == 0 {
+        anyhow::bail!("No prebuilt bridge matches the language filter");
+    }
+
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+    println!("\nInstalled bridges are used before in-repo builds; delete {} to go back.", install_dir.display());
+    Ok(())
+}
+
+async fn download(url: &str, destination: &Path) -> Result<()> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "--retry", "2", "-o"])
+        .arg(destination)
+        .arg(url)
+        .output()
+        .await
+        .context("Failed to run curl; it is required to download bridges")?;
+    if !output.status.success() {
+        let _ = fs::remove_file(destination);
+        anyhow::bail!(
+            "Download of {} failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Parses `sha256sum` output: `<hex digest>  <file name>` per line.
+fn parse_checksums(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let (digest, name) = line.trim().split_once(char::is_whitespace)?;
+            Some((name.trim().trim_start_matches('*').to_string(), digest.to_string()))
+        })
+        .collect()
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
@@ -0,0 +1,117 @@
+//! Ctrl+C handling
+//! Bridges run in process groups of their own (`CREATE_NEW_PROCESS_GROUP` on Windows),
+//! so the terminal's Ctrl+C does not reach them or whatever the target left running.
+//! On the first Ctrl+C (or SIGTERM, or the console closing on Windows) every live
+//! bridge is killed. `analyze` and `run-all` then finish with what was analyzed so
+//! far, writing their reports before exiting with code 130; other commands exit right
+//! away. A second Ctrl+C exits without waiting for the reports.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit code of an interrupted run, as shells report a process ended by SIGINT.
+pub const EXIT_CODE: i32 = 130;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Set by commands that write what they have before exiting.
+static GRACEFUL: AtomicBool = AtomicBool::new(false);
+
+/// Whether Ctrl+C was pressed; no new bridge starts once it was.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Lets the command finish after Ctrl+C instead of exiting: its bridges are killed and
+/// it is expected to stop analyzing, write its reports and exit with `EXIT_CODE`.
+pub fn finish_gracefully() {
+    GRACEFUL.store(true, Ordering::SeqCst);
+}
+
+/// `code`, or `EXIT_CODE` when the run was interrupted.
+pub fn exit_code(code: i32) -> i32 {
+    if interrupted() {
+        tracing::warn!("Interrupted: the results cover only what was analyzed before Ctrl+C");
+        EXIT_CODE
+    } else {
+        code
+    }
+}
+
+/// Starts listening for Ctrl+C. Must be called inside the Tokio runtime.
+pub fn install() {
+    tokio::spawn(async {
+        let mut signals = match Signals::new() {
+            Ok(signals) => signals,
+            Err(e) => {
+                tracing::warn!("Failed to install the Ctrl+C handler: {}", e);
+                return;
+            }
+        };
+        while signals.recv().await {
+            if INTERRUPTED.swap(true, Ordering::SeqCst) {
+                eprintln!("\n⚠ Interrupted again; exiting without waiting for the reports");
+                std::process::exit(EXIT_CODE);
+            }
+            crate::analyzer::kill_live_bridges();
+            if !GRACEFUL.load(Ordering::SeqCst) {
+                std::process::exit(EXIT_CODE);
+            }
+            eprintln!(
+                "\n⚠ Interrupted: stopped the running bridges; writing reports for what was analyzed (press Ctrl+C again to exit now)"
+            );
+        }
+    });
+}
+
+#[cfg(unix)]
+struct Signals {
+    interrupt: tokio::signal::unix::Signal,
+    terminate: tokio::signal::unix::Signal,
+}
+
+#[cfg(unix)]
+impl Signals {
+    fn new() -> std::io::Result<Self> {
+        use tokio::signal::unix::{signal, SignalKind};
+        Ok(Self {
+            interrupt: signal(SignalKind::interrupt())?,
+            terminate: signal(SignalKind::terminate())?,
+        })
+    }
+
+    /// Waits for the next signal; `false` once none can arrive.
+    async fn recv(&mut self) -> bool {
+        tokio::select! {
+            received = self.interrupt.recv() => received.is_some(),
+            received = self.terminate.recv() => received.is_some(),
+        }
+    }
+}
+
+#[cfg(windows)]
+struct Signals {
+    ctrl_c: tokio::signal::windows::CtrlC,
+    ctrl_break: tokio::signal::windows::CtrlBreak,
+    ctrl_close: tokio::signal::windows::CtrlClose,
+}
+
+#[cfg(windows)]
+impl Signals {
+    fn new() -> std::io::Result<Self> {
+        use tokio::signal::windows::{ctrl_break, ctrl_c, ctrl_close};
+        Ok(Self {
+            ctrl_c: ctrl_c()?,
+            ctrl_break: ctrl_break()?,
+            ctrl_close: ctrl_close()?,
+        })
+    }
+
+    /// Waits for the next console event; `false` once none can arrive.
+    async fn recv(&mut self) -> bool {
+        tokio::select! {
+            received = self.ctrl_c.recv() => received.is_some(),
+            received = self.ctrl_break.recv() => received.is_some(),
+            received = self.ctrl_close.recv() => received.is_some(),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! JUnit XML for CI test reporters
+//! One test suite per language and one test case per run-all target, so Jenkins and
+//! GitLab show escape results natively. Targets with escapes or crashes fail; targets
+//! that produced no results are errors.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub struct JunitCase {
+    /// Test suite the case is grouped under (the language)
+    pub suite: String,
+    pub name: String,
+    pub time_ms: u64,
+    pub outcome: JunitOutcome,
+}
+
+pub enum JunitOutcome {
+    Passed,
+    /// Escapes or crashes; `body` carries the details and captured stderr.
+    Failed { message: String, body: String },
+    /// The target could not be analyzed.
+    Error { message: String },
+}
+
+pub fn write_junit(path: &Path, cases: &[JunitCase]) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, render(cases)).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn render(cases: &[JunitCase]) -> String {
+    let mut suites: BTreeMap<&str, Vec<&JunitCase>> = BTreeMap::new();
+    for case in cases {
+        suites.entry(&case.suite).or_default().push(case);
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"graphene-ha\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\">\n",
+        cases.len(),
+        count(cases.iter(), |o| matches!(o, JunitOutcome::Failed { .. })),
+        count(cases.iter(), |o| matches!(o, JunitOutcome::Error { .. })),
+        seconds(cases.iter().map(|case| case.time_ms).sum())
+    ));
+    for (suite, cases) in &suites {
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\">\n",
+            escape_xml(suite),
+            cases.len(),
+            count(cases.iter().copied(), |o| matches!(o, JunitOutcome::Failed { .. })),
+            count(cases.iter().copied(), |o| matches!(o, JunitOutcome::Error { .. })),
+            seconds(cases.iter().map(|case| case.time_ms).sum())
+        ));
+        for case in cases {
+            let open = format!(
+                "    <testcase classname=\"graphene-ha.{}\" name=\"{}\" time=\"{}\"",
+                escape_xml(suite),
+                escape_xml(&case.name),
+                seconds(case.time_ms)
+            );
+            match &case.outcome {
+                JunitOutcome::Passed => xml.push_str(&format!("{}/>\n", open)),
+                JunitOutcome::Failed { message, body } => xml.push_str(&format!(
+                    "{}>\n      <failure message=\"{}\" type=\"escape\">{}</failure>\n    </testcase>\n",
+                    open,
+                    escape_xml(message),
+                    escape_xml(body)
+                )),
+                JunitOutcome::Error { message } => xml.push_str(&format!(
+                    "{}>\n      <error message=\"{}\" type=\"analysis\"/>\n    </testcase>\n",
+                    open,
+                    escape_xml(message)
+                )),
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn count<'a>(cases: impl Iterator<Item = &'a JunitCase>, matches: impl Fn(&JunitOutcome) -> bool) -> usize {
+    cases.filter(|case| matches(&case.outcome)).count()
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+/// Escapes markup characters and drops control characters XML 1.0 does not allow,
+/// which captured target output can contain.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
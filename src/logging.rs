@@ -0,0 +1,173 @@
+//! Log output
+//! `--log-format json` writes each tracing event as one JSON object per line
+//! (`timestamp`, `level`, `target`, `message` and the event's other fields under
+//! `fields`) so logs can be shipped to an aggregator; `--log-file <path>` also appends
+//! every event to a file, in the same format and without colors. Commands still pick
+//! the console stream (stdout, or stderr when stdout carries their output) and the
+//! verbosity when they initialize logging.
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{self, FmtContext, FormatEvent, FormatFields, MakeWriter};
+use tracing_subscriber::layer::{Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// Where the console log goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Console {
+    Stdout,
+    /// For commands whose stdout is a document (JSON output, diffs, LSP)
+    Stderr,
+}
+
+struct LogSettings {
+    format: LogFormat,
+    /// Taken by the first `init`; logging is only initialized once per process.
+    file: Mutex<Option<File>>,
+}
+
+static SETTINGS: OnceLock<LogSettings> = OnceLock::new();
+
+/// Records the `--log-format` and `--log-file` flags for the command's `init`. The log
+/// file is opened here, so an unwritable path fails before the command runs.
+pub fn configure(format: LogFormat, file: Option<&Path>) -> Result<()> {
+    let file = file
+        .map(|path| {
+            if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            File::options()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open log file {}", path.display()))
+        })
+        .transpose()?;
+    let _ = SETTINGS.set(LogSettings { format, file: Mutex::new(file) });
+    Ok(())
+}
+
+/// Installs the global subscriber: the console in the configured format, plus the log
+/// file when one was given.
+pub fn init(console: Console, verbose: bool) {
+    install(console, filter(verbose));
+}
+
+/// Like `init`, but only warnings and errors, on stderr.
+pub fn init_quiet() {
+    install(Console::Stderr, "graphene_ha=warn");
+}
+
+fn install(console: Console, filter: &str) {
+    let format = SETTINGS.get().map_or(LogFormat::Text, |settings| settings.format);
+    let file = SETTINGS
+        .get()
+        .and_then(|settings| settings.file.lock().ok().and_then(|mut file| file.take()));
+
+    let console = match console {
+        Console::Stdout => layer(format, std::io::stdout, true),
+        Console::Stderr => layer(format, std::io::stderr, true),
+    };
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::new(filter))
+        .with(console)
+        .with(file.map(|file| layer(format, Mutex::new(file), false)))
+        .init();
+}
+
+fn filter(verbose: bool) -> &'static str {
+    if verbose {
+        "graphene_ha=debug"
+    } else {
+        "graphene_ha=info"
+    }
+}
+
+fn layer<S, W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Text => {
+            // Colors only on the console, and not there either under NO_COLOR.
+            let ansi = ansi && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty());
+            fmt::layer().with_writer(writer).with_ansi(ansi).boxed()
+        }
+        LogFormat::Json => fmt::layer().with_writer(writer).event_format(JsonEvents).boxed(),
+    }
+}
+
+/// Formats an event as one line of JSON.
+struct JsonEvents;
+
+impl<S, N> FormatEvent<S, N> for JsonEvents
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, context: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> std::fmt::Result {
+        let mut fields = Map::new();
+        event.record(&mut JsonFields(&mut fields));
+        let metadata = event.metadata();
+
+        let mut line = Map::new();
+        line.insert("timestamp".to_string(), Value::from(Local::now().to_rfc3339()));
+        line.insert("level".to_string(), Value::from(metadata.level().as_str()));
+        line.insert("target".to_string(), Value::from(metadata.target()));
+        line.insert("message".to_string(), fields.remove("message").unwrap_or(Value::from("")));
+        if !fields.is_empty() {
+            line.insert("fields".to_string(), Value::Object(fields));
+        }
+        if let Some(scope) = context.event_scope() {
+            let spans: Vec<Value> = scope.from_root().map(|span| Value::from(span.name())).collect();
+            line.insert("spans".to_string(), Value::Array(spans));
+        }
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+/// Collects an event's fields as JSON values.
+struct JsonFields<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonFields<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), Value::from(format!("{:?}", value)));
+    }
+}
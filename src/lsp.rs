@@ -0,0 +1,328 @@
+//! Language server
+//! `graphene-ha lsp` speaks the Language Server Protocol over stdio so editors show
+//! static escape findings while the code is open. Every function of an opened or saved
+//! document is analyzed as its own target, from the file as saved on disk, and the
+//! escapes found are published as diagnostics whose severity follows their confidence.
+//! A code action on a diagnostic has the server show the escape's full reason.
+
+use crate::metrics;
+use crate::orchestrator::{detect_language_from_target, init_stderr_logging};
+use crate::protocol::{ConfidenceLevel, StaticEscape};
+use crate::scan;
+use crate::static_analyzer::callgraph;
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tracing::{debug, info, warn};
+
+/// `source` of every published diagnostic.
+const SOURCE: &str = "graphene-ha";
+
+/// Command of the code actions, run by the server itself.
+const EXPLAIN_COMMAND: &str = "graphene-ha.explainEscape";
+
+// JSON-RPC and LSP error codes.
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_NOT_INITIALIZED: i64 = -32002;
+
+// LSP diagnostic severities and message types.
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const SEVERITY_INFORMATION: u8 = 3;
+const MESSAGE_INFO: u8 = 3;
+
+/// Serves one editor session on stdin/stdout; returns the exit code the protocol asks
+/// for: 0 when `exit` followed `shutdown`, 1 otherwise.
+pub async fn run_lsp(verbose: bool, metrics_addr: Option<SocketAddr>) -> Result<i32> {
+    // Stdout carries the protocol.
+    init_stderr_logging(verbose);
+    info!("Graphene HA language server started");
+    if let Some(addr) = metrics_addr {
+        metrics::serve(addr).await?;
+    }
+
+    let mut reader = BufReader::new(tokio::io::stdin());
+    let mut stdout = tokio::io::stdout();
+    let mut server = Server::default();
+    while let Some(message) = read_message(&mut reader).await? {
+        for outgoing in server.handle(message).await {
+            let body = serde_json::to_string(&outgoing)?;
+            stdout
+                .write_all(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).as_bytes())
+                .await?;
+            stdout.flush().await?;
+        }
+        if server.exited {
+            break;
+        }
+    }
+    Ok(if server.shut_down { 0 } else { 1 })
+}
+
+#[derive(Debug, Default)]
+struct Server {
+    initialized: bool,
+    shut_down: bool,
+    exited: bool,
+    /// Documents with diagnostics published, cleared when they are closed.
+    open: HashSet<String>,
+}
+
+impl Server {
+    /// Messages to send in reply to `message`.
+    async fn handle(&mut self, message: Value) -> Vec<Value> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // A response to a request of ours; none are sent.
+            return Vec::new();
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let Some(id) = message.get("id").cloned() else {
+            return self.notification(method, &params).await;
+        };
+
+        let mut outgoing = Vec::new();
+        let result = match method {
+            "initialize" => {
+                self.initialized = true;
+                Ok(capabilities())
+            }
+            _ if !self.initialized => Err((SERVER_NOT_INITIALIZED, "initialize has not been sent".to_string())),
+            "shutdown" => {
+                self.shut_down = true;
+                Ok(Value::Null)
+            }
+            "textDocument/codeAction" => Ok(code_actions(&params)),
+            "workspace/executeCommand" => match explain_command(&params) {
+                Some(explanation) => {
+                    outgoing.push(notification(
+                        "window/showMessage",
+                        json!({ "type": MESSAGE_INFO, "message": explanation }),
+                    ));
+                    Ok(Value::Null)
+                }
+                None => Err((INVALID_PARAMS, "unknown command or missing explanation".to_string())),
+            },
+            _ => Err((METHOD_NOT_FOUND, format!("method {} is not supported", method))),
+        };
+        outgoing.push(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => {
+                json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+            }
+        });
+        outgoing
+    }
+
+    async fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params
+            .pointer("/textDocument/uri")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        match (method, uri) {
+            ("exit", _) => {
+                self.exited = true;
+                Vec::new()
+            }
+            _ if !self.initialized || self.shut_down => Vec::new(),
+            ("textDocument/didOpen" | "textDocument/didSave", Some(uri)) => {
+                let diagnostics = analyze_document(&uri).await;
+                self.open.insert(uri.clone());
+                vec![publish(&uri, diagnostics)]
+            }
+            ("textDocument/didClose", Some(uri)) if self.open.remove(&uri) => vec![publish(&uri, Vec::new())],
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn capabilities() -> Value {
+    json!({
+        "capabilities": {
+            // Analysis reads the saved file, so edits are only picked up on save.
+            "textDocumentSync": { "openClose": true, "change": 0, "save": { "includeText": false } },
+            "codeActionProvider": { "codeActionKinds": ["quickfix"] },
+            "executeCommandProvider": { "commands": [EXPLAIN_COMMAND] },
+        },
+        "serverInfo": { "name": SOURCE, "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+/// Diagnostics for the escapes of every function in the document at `uri`; none for
+/// documents in a language without static analysis.
+async fn analyze_document(uri: &str) -> Vec<Value> {
+    let Some(path) = uri_to_path(uri) else {
+        debug!("Not a file URI: {}", uri);
+        return Vec::new();
+    };
+    let file = path.to_string_lossy().into_owned();
+    let Ok(language) = detect_language_from_target(&file) else {
+        return Vec::new();
+    };
+    let source = match std::fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(e) => {
+            warn!("Failed to read {}: {}", file, e);
+            return Vec::new();
+        }
+    };
+
+    let functions = callgraph::function_names(&language, &source);
+    let escapes = scan::file_escapes(&file, &language, functions).await.escapes;
+    info!("{} escape(s) in {}", escapes.len(), file);
+
+    let lines: Vec<&str> = source.lines().collect();
+    escapes.iter().map(|escape| diagnostic(escape, &lines)).collect()
+}
+
+fn diagnostic(escape: &StaticEscape, lines: &[&str]) -> Value {
+    let line = escape.location.line.saturating_sub(1);
+    let text = lines.get(line).copied().unwrap_or("").trim_end();
+    // Most analyzers give no column: the whole statement is marked.
+    let indent = utf16_len(&text[..text.len() - text.trim_start().len()]);
+    let start = if escape.location.column > 0 { escape.location.column } else { indent };
+    let end = utf16_len(text).max(start);
+    let code = escape
+        .rule_id
+        .clone()
+        .or_else(|| escape.rule.clone())
+        .unwrap_or_else(|| format!("{:?}", escape.escape_type));
+    json!({
+        "range": {
+            "start": { "line": line, "character": start },
+            "end": { "line": line, "character": end },
+        },
+        "severity": match escape.confidence {
+            ConfidenceLevel::High => SEVERITY_ERROR,
+            ConfidenceLevel::Medium => SEVERITY_WARNING,
+            ConfidenceLevel::Low => SEVERITY_INFORMATION,
+        },
+        "code": code,
+        "source": SOURCE,
+        "message": format!("`{}` escapes: {}", escape.variable_name, escape.reason),
+        // Echoed back by the client in code action requests.
+        "data": { "variable": escape.variable_name, "explanation": explanation(escape) },
+    })
+}
+
+/// The escape's reason with the condition and flows that lead to it.
+fn explanation(escape: &StaticEscape) -> String {
+    let mut lines = vec![format!(
+        "`{}` escapes ({:?}, {:?} confidence): {}",
+        escape.variable_name, escape.escape_type, escape.confidence, escape.reason
+    )];
+    if let Some(condition) = &escape.condition {
+        lines.push(format!("Only when: {}", condition));
+    }
+    if !escape.data_flow.is_empty() {
+        lines.push(format!("Data flow: {}", escape.data_flow.join(" → ")));
+    }
+    if !escape.taint_flow.is_empty() {
+        lines.push(format!("Input flow: {}", escape.taint_flow.join(" → ")));
+    }
+    lines.join("\n")
+}
+
+/// One action per diagnostic of ours in the request, showing why it escapes.
+fn code_actions(params: &Value) -> Value {
+    let diagnostics = params
+        .pointer("/context/diagnostics")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    diagnostics
+        .into_iter()
+        .filter(|diagnostic| diagnostic.get("source").and_then(Value::as_str) == Some(SOURCE))
+        .filter_map(|diagnostic| {
+            let explanation = diagnostic.pointer("/data/explanation")?.as_str()?.to_string();
+            let variable = diagnostic.pointer("/data/variable")?.as_str()?.to_string();
+            let title = format!("Show why `{}` escapes", variable);
+            Some(json!({
+                "title": title,
+                "kind": "quickfix",
+                "diagnostics": [diagnostic],
+                "command": { "title": title, "command": EXPLAIN_COMMAND, "arguments": [explanation] },
+            }))
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn explain_command(params: &Value) -> Option<String> {
+    if params.get("command").and_then(Value::as_str) != Some(EXPLAIN_COMMAND) {
+        return None;
+    }
+    Some(params.pointer("/arguments/0")?.as_str()?.to_string())
+}
+
+fn publish(uri: &str, diagnostics: Vec<Value>) -> Value {
+    notification(
+        "textDocument/publishDiagnostics",
+        json!({ "uri": uri, "diagnostics": diagnostics }),
+    )
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+/// Next message from the client, `None` once the input ends.
+async fn read_message<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<Value>> {
+    let mut length: Option<usize> = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = Some(value.trim().parse().context("Malformed Content-Length header")?);
+            }
+        }
+    }
+    let length = length.context("Message without a Content-Length header")?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    serde_json::from_slice(&body).context("Malformed JSON-RPC message").map(Some)
+}
+
+/// Local path of a `file:` URI.
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| std::str::from_utf8(tail.get(..2)?).ok())
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // `file:///C:/src` on Windows.
+    let path = match path.as_bytes() {
+        [b'/', _, b':', ..] if cfg!(windows) => &path[1..],
+        _ => path.as_str(),
+    };
+    Some(PathBuf::from(path))
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
@@ -1,22 +1,78 @@
 mod orchestrator;
 mod protocol;
 mod analyzer;
+mod bench;
+mod build;
+mod changes;
+mod compare;
+mod corpus;
+mod diff;
+mod doctor;
+mod fix;
+mod history;
+mod input_gen;
+mod install;
+mod interrupt;
+mod junit;
+mod logging;
+mod lsp;
+mod metrics;
 mod report;
+mod scan;
+mod scheduler;
+mod self_test;
+mod severity;
 mod static_analyzer;
+mod template;
+mod verify;
 
-use clap::{Parser, Subcommand, ValueEnum};
-use std::path::PathBuf;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use anyhow::Result;
-use crate::protocol::AnalysisMode;
+use crate::analyzer::BridgeTransport;
+use crate::corpus::Inputs;
+use crate::logging::LogFormat;
+use crate::orchestrator::{BridgeSettings, FailOn, OutputFormat};
+use crate::protocol::{AnalysisMode, ConfidenceLevel};
+use crate::report::{ReportFormat, ReportSettings};
+use crate::scheduler::SchedulerSettings;
+use crate::severity::SeverityRules;
+use crate::template::ReportTemplates;
 
 #[derive(Parser)]
 #[command(name = "graphene-ha")]
 #[command(about = "Graphene HA - Static object escape analysis for multi-language codebases", long_about = None)]
 struct Cli {
+    /// Log line format: human-readable text, or one JSON object per line for log aggregators
+    #[arg(long, global = true, default_value = "text")]
+    log_format: CliLogFormat,
+
+    /// Also append the log to this file, in the --log-format
+    #[arg(long, global = true, value_name = "PATH")]
+    log_file: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliLogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per event (timestamp, level, target, message, fields)
+    Json,
+}
+
+impl From<CliLogFormat> for LogFormat {
+    fn from(format: CliLogFormat) -> Self {
+        match format {
+            CliLogFormat::Text => LogFormat::Text,
+            CliLogFormat::Json => LogFormat::Json,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum CliAnalysisMode {
     /// Static compile-time analysis (recommended for object escape analysis)
@@ -37,11 +93,356 @@ impl From<CliAnalysisMode> for AnalysisMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliTransport {
+    /// Request on stdin, response on stdout
+    Stdio,
+    /// Request and response over a Unix socket, leaving stdout to the target
+    Socket,
+}
+
+impl From<CliTransport> for BridgeTransport {
+    fn from(transport: CliTransport) -> Self {
+        match transport {
+            CliTransport::Stdio => BridgeTransport::Stdio,
+            CliTransport::Socket => BridgeTransport::UnixSocket,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliSandbox {
+    /// A docker container per bridge process
+    Docker,
+}
+
+impl CliSandbox {
+    fn runtime(self) -> &'static str {
+        match self {
+            CliSandbox::Docker => "docker",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliOutputFormat {
+    /// Human-readable summary
+    Text,
+    /// The full analysis response as JSON, with logs on stderr
+    Json,
+}
+
+impl From<CliOutputFormat> for OutputFormat {
+    fn from(format: CliOutputFormat) -> Self {
+        match format {
+            CliOutputFormat::Text => OutputFormat::Text,
+            CliOutputFormat::Json => OutputFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliFailOn {
+    /// Any static or dynamic escape (exit code 2)
+    AnyEscape,
+    /// A dynamic escape not explained by a timeout (exit code 3)
+    GenuineEscape,
+    /// A vulnerability graded high or critical (exit code 4)
+    HighSeverity,
+    /// An execution that crashed or timed out (exit code 5)
+    Crash,
+}
+
+impl From<CliFailOn> for FailOn {
+    fn from(policy: CliFailOn) -> Self {
+        match policy {
+            CliFailOn::AnyEscape => FailOn::AnyEscape,
+            CliFailOn::GenuineEscape => FailOn::GenuineEscape,
+            CliFailOn::HighSeverity => FailOn::HighSeverity,
+            CliFailOn::Crash => FailOn::Crash,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliConfidence {
+    /// Every finding
+    Low,
+    /// Medium and high confidence findings
+    Medium,
+    /// Only high confidence findings
+    High,
+}
+
+impl From<CliConfidence> for ConfidenceLevel {
+    fn from(confidence: CliConfidence) -> Self {
+        match confidence {
+            CliConfidence::Low => ConfidenceLevel::Low,
+            CliConfidence::Medium => ConfidenceLevel::Medium,
+            CliConfidence::High => ConfidenceLevel::High,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliReportFormat {
+    /// Markdown summary and CSV
+    Markdown,
+    /// Also SARIF 2.1.0 (results.sarif) for code scanning
+    Sarif,
+}
+
+impl From<CliReportFormat> for ReportFormat {
+    fn from(format: CliReportFormat) -> Self {
+        match format {
+            CliReportFormat::Markdown => ReportFormat::Markdown,
+            CliReportFormat::Sarif => ReportFormat::Sarif,
+        }
+    }
+}
+
+/// Bridge process settings shared by `analyze` and `run-all`
+#[derive(Args)]
+struct BridgeArgs {
+    /// Kill a bridge after this many seconds without a heartbeat (0 disables the watchdog)
+    #[arg(long, default_value = "30", value_parser = parse_seconds)]
+    heartbeat_timeout: f64,
+
+    /// Overall bridge deadline per target in seconds, summed for a batch (default: inputs x repeat x timeout + 60s)
+    #[arg(long, value_parser = parse_seconds)]
+    bridge_timeout: Option<f64>,
+
+    /// Ask bridges to gzip their responses (bridges without support reply uncompressed)
+    #[arg(long)]
+    compress: bool,
+
+    /// How requests and responses travel between the orchestrator and bridges
+    #[arg(long, default_value = "stdio")]
+    transport: CliTransport,
+
+    /// Set an environment variable for bridges and targets (repeatable)
+    #[arg(long = "env", value_name = "KEY=VALUE", value_parser = parse_env_assignment)]
+    env: Vec<(String, String)>,
+
+    /// Pass matching environment variables through to bridges (repeatable, `*` wildcard, e.g. AWS_*)
+    #[arg(long, value_name = "PATTERN")]
+    env_passthrough: Vec<String>,
+
+    /// Working directory for bridges and targets (default: the current directory)
+    #[arg(long, value_parser = parse_workdir)]
+    workdir: Option<PathBuf>,
+
+    /// Run each target from the directory containing its source file
+    #[arg(long, conflicts_with = "workdir")]
+    target_workdir: bool,
+
+    /// Inject small random delays around spawn, lock and channel operations to widen race windows
+    #[arg(long)]
+    chaos: bool,
+
+    /// Replay a previous perturbation schedule (implies --chaos)
+    #[arg(long, value_name = "SEED")]
+    chaos_seed: Option<u64>,
+
+    /// Keep watching threads/tasks an execution left running for this many seconds and report how long each lasted
+    #[arg(long, value_name = "SECONDS", default_value = "0")]
+    observe: f64,
+
+    /// Report subprocesses an execution spawned and left running or unreaped (Rust bridge)
+    #[arg(long)]
+    detect_processes: bool,
+
+    /// Count idle background (daemon-equivalent) threads as escapes, not only threads that block process exit (Rust bridge)
+    #[arg(long)]
+    treat_daemon_as_escape: bool,
+
+    /// Wait this long after each call before looking for what it left behind (default: 0 Python, 100 JavaScript/Go/Java, 300 Rust)
+    #[arg(long, value_name = "MS")]
+    settle: Option<u64>,
+
+    /// Times to re-check for left-behind threads within the settle period (Rust bridge, default 6)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..=1000))]
+    settle_checks: Option<u32>,
+
+    /// Fewest threads, goroutines or async tasks an execution must leave behind to count as an escape (default 1)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    thread_threshold: Option<u32>,
+
+    /// Snapshot open file descriptors around each execution (default: true where the bridge supports it)
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    detect_fds: Option<bool>,
+
+    /// Attach stacks to left-behind threads, goroutines and async tasks (default: only Java)
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    capture_stacks: Option<bool>,
+
+    /// Keep bridge processes running between targets instead of starting one per batch (stdio transport)
+    #[arg(long)]
+    daemon: bool,
+
+    /// Resident memory growth in MB over an input's repeats past which growth on every repeat is reported as a leak
+    #[arg(long, value_name = "MB", default_value = "1")]
+    memory_threshold: f64,
+
+    /// Race mode (analyze, run-all): run each input at least RUNS times and report runs that disagree on a target touching shared state as potential data races
+    #[arg(long, value_name = "RUNS", value_parser = clap::value_parser!(u16).range(2..))]
+    race: Option<u16>,
+
+    /// Run each bridge in a network-less, resource-limited container with the target source mounted read-only (stdio transport)
+    #[arg(long, value_name = "RUNTIME")]
+    sandbox: Option<CliSandbox>,
+
+    /// Container image for a language's bridge, e.g. python=python:3.11-slim (repeatable)
+    #[arg(long, value_name = "LANG=IMAGE", value_parser = parse_env_assignment, requires = "sandbox")]
+    sandbox_image: Vec<(String, String)>,
+
+    /// Cap each bridge process, and each process the target starts, at this much memory (data segment on Unix)
+    #[arg(long, value_name = "MB", value_parser = clap::value_parser!(u64).range(1..))]
+    bridge_memory: Option<u64>,
+
+    /// Kill a bridge process, or a process the target starts, after this many seconds of CPU time
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    bridge_cpu: Option<u64>,
+
+    /// Cap how many processes a bridge may run at once (on Linux: processes and threads of your user), against fork bombs
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    bridge_processes: Option<u64>,
+
+    /// JSON array of constructor arguments for instance-method targets (Class.method, Type::method), e.g. '["name", 3]'
+    #[arg(long, value_name = "JSON", value_parser = parse_constructor_args)]
+    constructor_args: Option<String>,
+}
+
+fn parse_workdir(value: &str) -> Result<PathBuf, String> {
+    std::fs::canonicalize(value)
+        .ok()
+        .filter(|path| path.is_dir())
+        .ok_or_else(|| format!("`{}` is not a directory", value))
+}
+
+/// A number of seconds a timer can hold: finite and not negative.
+fn parse_seconds(value: &str) -> Result<f64, String> {
+    let seconds: f64 = value.trim().parse().map_err(|_| format!("`{}` is not a number of seconds", value))?;
+    if seconds >= 0.0 && std::time::Duration::try_from_secs_f64(seconds).is_ok() {
+        Ok(seconds)
+    } else {
+        Err(format!("`{}` is out of range: expected a finite, non-negative number of seconds", value))
+    }
+}
+
+fn parse_constructor_args(value: &str) -> Result<String, String> {
+    match serde_json::from_str::<serde_json::Value>(value) {
+        Ok(serde_json::Value::Array(_)) => Ok(value.to_string()),
+        Ok(_) => Err("expected a JSON array".to_string()),
+        Err(e) => Err(format!("invalid JSON: {}", e)),
+    }
+}
+
+fn parse_input_args(value: &str) -> Result<String, String> {
+    serde_json::from_str::<Vec<serde_json::Value>>(value)
+        .map(|_| value.to_string())
+        .map_err(|e| format!("expected a JSON array of arguments: {}", e))
+}
+
+/// Report settings of the `--format`, `--report-template` and `--report-name` flags;
+/// templates are parsed here so a broken one fails before any analysis.
+fn report_settings(format: CliReportFormat, template: Option<PathBuf>, name: Option<String>) -> Result<ReportSettings> {
+    if let Some(name) = &name {
+        if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+            anyhow::bail!("--report-name must be a plain directory name, not {:?}", name);
+        }
+    }
+    Ok(ReportSettings {
+        format: format.into(),
+        templates: template.as_deref().map(ReportTemplates::load).transpose()?,
+        name,
+    })
+}
+
+/// Inputs of the `--input`, `--input-file`, `--input-dir` and `--input-args` flags.
+fn collect_inputs(input: Vec<String>, input_file: &[PathBuf], input_dir: Option<&Path>, input_args: &[String]) -> Result<Inputs> {
+    let mut inputs = if input_args.is_empty() {
+        Inputs::inline(input)
+    } else {
+        let lists = input_args
+            .iter()
+            .map(|list| serde_json::from_str(list))
+            .collect::<Result<_, _>>()?;
+        Inputs::arguments(lists)
+    };
+    for path in input_file {
+        inputs.load_file(path)?;
+    }
+    if let Some(dir) = input_dir {
+        inputs.load_dir(dir)?;
+    }
+    Ok(inputs)
+}
+
+fn parse_count_assignment<T: std::str::FromStr>(value: &str) -> Result<(String, T), String> {
+    match value.rsplit_once('=') {
+        Some((key, count)) if !key.is_empty() => count
+            .trim()
+            .parse()
+            .map(|count| (key.to_string(), count))
+            .map_err(|_| format!("`{}` is not a valid number", count)),
+        _ => Err(format!("expected KEY=N, got `{}`", value)),
+    }
+}
+
+fn parse_env_assignment(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got `{}`", value)),
+    }
+}
+
+/// A fresh perturbation seed, printed in the summary so the run can be replayed. Kept
+/// below 2^53 so JavaScript bridges read it exactly.
+fn random_seed() -> u64 {
+    let bytes = uuid::Uuid::new_v4().into_bytes();
+    u64::from_le_bytes(bytes[..8].try_into().unwrap_or_default()) >> 12
+}
+
+impl From<BridgeArgs> for BridgeSettings {
+    fn from(args: BridgeArgs) -> Self {
+        Self {
+            heartbeat_timeout: args.heartbeat_timeout,
+            bridge_timeout: args.bridge_timeout,
+            compress: args.compress,
+            transport: args.transport.into(),
+            env: args.env,
+            env_passthrough: args.env_passthrough,
+            workdir: args.workdir,
+            target_workdir: args.target_workdir,
+            chaos_seed: args.chaos_seed.or_else(|| args.chaos.then(random_seed)),
+            observe: args.observe,
+            detect_processes: args.detect_processes,
+            treat_daemon_as_escape: args.treat_daemon_as_escape,
+            settle: args.settle,
+            settle_checks: args.settle_checks,
+            thread_threshold: args.thread_threshold,
+            detect_fds: args.detect_fds,
+            capture_stacks: args.capture_stacks,
+            daemon: args.daemon,
+            memory_threshold: args.memory_threshold,
+            race_runs: args.race.map(usize::from),
+            sandbox: args.sandbox.map(|sandbox| sandbox.runtime().to_string()),
+            sandbox_images: args.sandbox_image,
+            bridge_memory: args.bridge_memory,
+            bridge_cpu: args.bridge_cpu,
+            bridge_processes: args.bridge_processes,
+            constructor_args: args.constructor_args,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Analyze a function for object escapes
     Analyze {
-        /// Target function in format: module:function or file.ext:function
+        /// Target function in format: module:function or file.ext:function; a bare
+        /// file.ext analyzes each of its public functions
         #[arg(short, long)]
         target: String,
 
@@ -49,6 +450,18 @@ enum Commands {
         #[arg(short, long)]
         input: Vec<String>,
 
+        /// File of inputs: a JSON array, or one input per non-blank line (repeatable)
+        #[arg(long, value_name = "PATH")]
+        input_file: Vec<PathBuf>,
+
+        /// Directory whose files are each one input; non-UTF-8 files are sent base64-encoded
+        #[arg(long, value_name = "DIR")]
+        input_dir: Option<PathBuf>,
+
+        /// JSON array of arguments for one call of a target taking several parameters, e.g. '["hello", 3]' (repeatable)
+        #[arg(long, value_name = "JSON", value_parser = parse_input_args, conflicts_with_all = ["input", "input_file", "input_dir"])]
+        input_args: Vec<String>,
+
         /// Number of times to repeat each input
         #[arg(short, long, default_value = "3")]
         repeat: usize,
@@ -72,6 +485,55 @@ enum Commands {
         /// Enable verbose logging
         #[arg(short, long)]
         verbose: bool,
+
+        #[command(flatten)]
+        bridge: BridgeArgs,
+
+        /// Command run after each execution with the input and output as $1 and $2;
+        /// a nonzero exit marks the execution as failed
+        #[arg(long, value_name = "CMD")]
+        oracle: Option<String>,
+
+        /// What to print on stdout: a text summary, or the full response as JSON
+        #[arg(long, visible_alias = "output", default_value = "text")]
+        output_format: CliOutputFormat,
+
+        /// Print nothing and log only warnings; the exit code is the result, non-zero on
+        /// any escape or crash unless --fail-on is given
+        #[arg(short, long, conflicts_with = "output_format")]
+        quiet: bool,
+
+        /// Do not write report files to the output directory
+        #[arg(long)]
+        no_report: bool,
+
+        /// Report files to write alongside the Markdown summary and CSV
+        #[arg(long, default_value = "markdown")]
+        format: CliReportFormat,
+
+        /// Directory of Handlebars templates (*.hbs) rendered with each target's report data into its session directory
+        #[arg(long, value_name = "DIR")]
+        report_template: Option<PathBuf>,
+
+        /// Name of the report directory under <output-dir>/<language>, instead of session_<timestamp>_<target>_<session id>; a whole file also gets <name>-<function> per function
+        #[arg(long, value_name = "NAME")]
+        report_name: Option<String>,
+
+        /// Also write the JSON report to this file, for `graphene-ha diff --baseline`
+        #[arg(long, value_name = "PATH")]
+        save_baseline: Option<PathBuf>,
+
+        /// Record the session's summary, escape counts and duration in this SQLite database, for `graphene-ha trends`
+        #[arg(long, value_name = "DB")]
+        history: Option<PathBuf>,
+
+        /// Exit non-zero when the analysis finds this (repeatable; the first one found sets the exit code)
+        #[arg(long, value_name = "POLICY")]
+        fail_on: Vec<CliFailOn>,
+
+        /// JSON file overriding the severity scoring rules (escape kinds, repeat consistency, static confidence); replaces the `[severity]` table of graphene.toml
+        #[arg(long, value_name = "PATH")]
+        severity_rules: Option<PathBuf>,
     },
 
     /// Run all test suites across all languages
@@ -84,6 +546,30 @@ enum Commands {
         #[arg(short, long, default_value = "10")]
         generate: usize,
 
+        /// Input for every target instead of generated ones (repeatable)
+        #[arg(short, long)]
+        input: Vec<String>,
+
+        /// File of inputs for every target: a JSON array, or one input per non-blank line (repeatable)
+        #[arg(long, value_name = "PATH")]
+        input_file: Vec<PathBuf>,
+
+        /// Directory whose files are each one input for every target
+        #[arg(long, value_name = "DIR")]
+        input_dir: Option<PathBuf>,
+
+        /// JSON array of arguments for one call of every target (repeatable)
+        #[arg(long, value_name = "JSON", value_parser = parse_input_args, conflicts_with_all = ["input", "input_file", "input_dir"])]
+        input_args: Vec<String>,
+
+        /// Number of times to repeat each input
+        #[arg(short, long, default_value = "1")]
+        repeat: usize,
+
+        /// Timeout per execution in seconds
+        #[arg(long, default_value = "5.0")]
+        timeout: f64,
+
         /// Output directory for reports
         #[arg(short, long, default_value = "logs")]
         output_dir: PathBuf,
@@ -95,6 +581,253 @@ enum Commands {
         /// Analysis mode: dynamic, static, or both. Default is both.
         #[arg(short = 'm', long, default_value = "both")]
         analysis_mode: CliAnalysisMode,
+
+        #[command(flatten)]
+        bridge: BridgeArgs,
+
+        /// Run each analyzer's self-test analysis before its suite and skip analyzers that fail it
+        #[arg(long)]
+        self_test: bool,
+
+        /// Number of targets sent to one bridge process (1 starts a bridge per target)
+        #[arg(long, default_value = "16")]
+        batch_size: usize,
+
+        /// Number of batches analyzed at once, each in its own bridge process
+        #[arg(short, long, default_value = "1")]
+        jobs: usize,
+
+        /// Analyze batches of targets matching PATTERN (a language, or text in the target path) first; higher N runs earlier (repeatable)
+        #[arg(long, value_name = "PATTERN=N", value_parser = parse_count_assignment::<i64>)]
+        priority: Vec<(String, i64)>,
+
+        /// At most N batches of LANG at once, e.g. java=2 (repeatable)
+        #[arg(long, value_name = "LANG=N", value_parser = parse_count_assignment::<usize>)]
+        language_jobs: Vec<(String, usize)>,
+
+        /// Rerun targets whose bridge crashed or hung up to this many times
+        #[arg(long, value_name = "N", default_value = "1")]
+        retries: usize,
+
+        /// Keep job state in this file so an interrupted run started again with it only analyzes what is left
+        #[arg(long, value_name = "PATH")]
+        state: Option<PathBuf>,
+
+        /// Also write a JUnit XML report here: one test case per target, failing on escapes or crashes
+        #[arg(long, value_name = "PATH")]
+        junit: Option<PathBuf>,
+
+        /// Record the session's summary, per-target escape counts and durations in this SQLite database, for `graphene-ha trends`
+        #[arg(long, value_name = "DB")]
+        history: Option<PathBuf>,
+
+        /// Serve Prometheus metrics at http://ADDR/metrics while the run lasts
+        #[arg(long, value_name = "ADDR")]
+        metrics_addr: Option<SocketAddr>,
+
+        /// Only analyze targets whose function changed since this git ref (e.g. origin/main)
+        #[arg(long, value_name = "REF")]
+        since: Option<String>,
+
+        /// Only analyze targets whose file path, file name or function matches this glob, e.g. 'escape_*' (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        filter: Vec<String>,
+
+        /// Skip targets whose file path, file name or function matches this glob (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        exclude: Vec<String>,
+
+        /// Compare each target's outcome with the one its name or ESCAPE:/SAFE: comment expects and report precision and recall
+        #[arg(long)]
+        verify: bool,
+
+        /// Report files to write alongside the Markdown summary and CSV
+        #[arg(long, default_value = "markdown")]
+        format: CliReportFormat,
+
+        /// Directory of Handlebars templates (*.hbs) rendered with each target's report data into its session directory
+        #[arg(long, value_name = "DIR")]
+        report_template: Option<PathBuf>,
+    },
+
+    /// Score each analyzer against the labeled targets of the tests tree, per analysis mode, with latency
+    Bench {
+        /// Root test directory
+        #[arg(short, long, default_value = "tests")]
+        test_dir: PathBuf,
+
+        /// Number of inputs to generate per test
+        #[arg(short, long, default_value = "10")]
+        generate: usize,
+
+        /// Number of times to repeat each input
+        #[arg(short, long, default_value = "1")]
+        repeat: usize,
+
+        /// Timeout per execution in seconds
+        #[arg(long, default_value = "5.0")]
+        timeout: f64,
+
+        /// Directory bench.json and bench.md are written to
+        #[arg(short, long, default_value = "logs/bench")]
+        output_dir: PathBuf,
+
+        /// Filter by language (python, java, javascript, go, rust)
+        #[arg(long)]
+        language: Option<String>,
+
+        /// Modes to benchmark: static, dynamic, or both (each separately)
+        #[arg(short = 'm', long, default_value = "both")]
+        analysis_mode: CliAnalysisMode,
+
+        #[command(flatten)]
+        bridge: BridgeArgs,
+
+        /// Number of targets sent to one bridge process
+        #[arg(long, default_value = "16")]
+        batch_size: usize,
+
+        /// Earlier bench.json to show the change of each figure against
+        #[arg(long, value_name = "PATH")]
+        baseline: Option<PathBuf>,
+    },
+
+    /// Run one function implemented in several languages with the same inputs and report divergences
+    Compare {
+        /// JSON manifest mapping function names to one target per language
+        #[arg(long)]
+        manifest: PathBuf,
+
+        /// Input data for every function without inputs in the manifest
+        #[arg(short, long)]
+        input: Vec<String>,
+
+        /// Number of times to repeat each input
+        #[arg(short, long, default_value = "1")]
+        repeat: usize,
+
+        /// Timeout per execution in seconds
+        #[arg(long, default_value = "5.0")]
+        timeout: f64,
+
+        /// Output directory for the comparison report
+        #[arg(short, long, default_value = "logs")]
+        output_dir: PathBuf,
+
+        /// Compare static escape counts, dynamic runs, or both
+        #[arg(short = 'm', long, default_value = "both")]
+        analysis_mode: CliAnalysisMode,
+
+        #[command(flatten)]
+        bridge: BridgeArgs,
+    },
+
+    /// Compare two runs' reports and list new, fixed and persisting escapes; fails on new ones
+    Diff {
+        /// Baseline: a report.json, a session directory, or a directory of sessions
+        #[arg(long)]
+        baseline: PathBuf,
+
+        /// Current run, in the same forms as --baseline
+        #[arg(long)]
+        current: PathBuf,
+    },
+
+    /// Run static analysis over every source file of a project and write one aggregated report
+    Scan {
+        /// Project directory; .gitignore and .grapheneignore are respected
+        dir: PathBuf,
+
+        /// Output directory for the report
+        #[arg(short, long, default_value = "logs")]
+        output_dir: PathBuf,
+
+        /// Only scan files of this language (python, java, javascript, go, rust)
+        #[arg(short, long)]
+        language: Option<String>,
+
+        /// Enable verbose logging
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// What to print on stdout: a text summary, or the aggregated report as JSON
+        #[arg(long, default_value = "text")]
+        output_format: CliOutputFormat,
+
+        /// Do not write report files to the output directory
+        #[arg(long)]
+        no_report: bool,
+
+        /// Only analyze files and functions changed since this git ref (e.g. origin/main)
+        #[arg(long, value_name = "REF")]
+        since: Option<String>,
+    },
+
+    /// Print escape-count trends per target over the sessions recorded with --history
+    Trends {
+        /// SQLite database written by --history
+        #[arg(long, value_name = "DB")]
+        history: PathBuf,
+
+        /// Number of most recent sessions to show
+        #[arg(short, long, default_value = "10")]
+        sessions: usize,
+
+        /// Only show targets containing this text
+        #[arg(short, long)]
+        target: Option<String>,
+
+        /// Only show targets whose escape count changed
+        #[arg(long)]
+        changed: bool,
+    },
+
+    /// Check each analyzer's toolchain and bridge, ping the bridge and run a self-test through it
+    Doctor {
+        /// Only check this language (python, java, javascript, go, rust)
+        #[arg(short, long)]
+        language: Option<String>,
+    },
+
+    /// Run available analyzers against bundled golden fixtures and diff the findings
+    #[command(name = "self-test")]
+    SelfTest {
+        /// Directory holding the fixtures and expected.json
+        #[arg(long, default_value = "tests/golden")]
+        fixtures: PathBuf,
+
+        /// Only test this language (python, java, javascript, go, rust)
+        #[arg(short, long)]
+        language: Option<String>,
+
+        /// Fixture modes to run: dynamic, static, or both
+        #[arg(short = 'm', long, default_value = "both")]
+        analysis_mode: CliAnalysisMode,
+    },
+
+    /// Build the Go, Rust and Java bridges from this checkout
+    #[command(name = "build-bridges")]
+    BuildBridges {
+        /// Only build this language's bridge (go, rust, java)
+        #[arg(short, long)]
+        language: Option<String>,
+    },
+
+    /// Download prebuilt Go/Rust/Java bridges into the user-level bridge directory
+    #[command(name = "install-bridges")]
+    InstallBridges {
+        /// Release version to install (e.g. 1.0.0)
+        #[arg(long)]
+        version: String,
+
+        /// Base URL holding the release assets and SHA256SUMS; `{version}` is substituted
+        /// (defaults to $GRAPHENE_BRIDGE_URL, then the GitHub release)
+        #[arg(long)]
+        url: Option<String>,
+
+        /// Only install this language's bridge (go, rust, java)
+        #[arg(short, long)]
+        language: Option<String>,
     },
 
     /// List available analyzers
@@ -104,6 +837,50 @@ enum Commands {
         detailed: bool,
     },
 
+    /// Show the static analysis rule catalog
+    Rules {
+        #[command(subcommand)]
+        command: RulesCommand,
+    },
+
+    /// Apply the mechanical suggested fixes of a target's static findings, printing them as a unified diff
+    Fix {
+        /// Target function in format file.ext:function, or a bare file.ext to fix all of its functions
+        #[arg(short, long)]
+        target: String,
+
+        /// Language (python, java, javascript, go, rust); detected from the target when omitted
+        #[arg(short, long)]
+        language: Option<String>,
+
+        /// Only fix findings of this rule (repeatable, e.g. --rule GH-RS-001)
+        #[arg(long = "rule", value_name = "ID")]
+        rules: Vec<String>,
+
+        /// Lowest finding confidence to fix
+        #[arg(long, value_enum, default_value = "high")]
+        min_confidence: CliConfidence,
+
+        /// Print the diff without changing the file
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Enable verbose logging (to stderr)
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Serve static escape findings to editors as diagnostics over the Language Server Protocol on stdio
+    Lsp {
+        /// Enable verbose logging (to stderr)
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Serve Prometheus metrics at http://ADDR/metrics while the server runs
+        #[arg(long, value_name = "ADDR")]
+        metrics_addr: Option<SocketAddr>,
+    },
+
     /// Clear log output directories
     #[command(name = "clear", alias = "clear-logs")]
     Clear {
@@ -117,52 +894,266 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum RulesCommand {
+    /// List every rule with its id, description and remediation
+    List {
+        /// Only list the rules of this language's analyzer (python, java, javascript, go, rust)
+        #[arg(short, long)]
+        language: Option<String>,
+
+        /// Print the rules as text, or as a JSON array
+        #[arg(long, default_value = "text")]
+        output_format: CliOutputFormat,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    logging::configure(cli.log_format.into(), cli.log_file.as_deref())?;
+    interrupt::install();
 
     match cli.command {
         Commands::Analyze {
             target,
             input,
+            input_file,
+            input_dir,
+            input_args,
             repeat,
             timeout,
             output_dir,
             language,
             analysis_mode,
             verbose,
+            bridge,
+            oracle,
+            output_format,
+            quiet,
+            no_report,
+            format,
+            report_template,
+            report_name,
+            save_baseline,
+            history,
+            fail_on,
+            severity_rules,
         } => {
-            orchestrator::analyze_target(
+            let mut fail_on: Vec<FailOn> = fail_on.into_iter().map(FailOn::from).collect();
+            // With nothing printed, the exit code has to tell whether anything was found.
+            if quiet && fail_on.is_empty() {
+                fail_on = vec![FailOn::AnyEscape, FailOn::Crash];
+            }
+            let severity_rules = SeverityRules::configured(severity_rules.as_deref())?;
+            let inputs = collect_inputs(input, &input_file, input_dir.as_deref(), &input_args)?;
+            let exit_code = orchestrator::analyze_target(
                 &target,
-                input,
+                inputs,
                 repeat,
                 timeout,
                 output_dir,
                 language,
                 analysis_mode.into(),
                 verbose,
+                bridge.into(),
+                oracle,
+                if quiet { OutputFormat::Quiet } else { output_format.into() },
+                no_report,
+                &report_settings(format, report_template, report_name)?,
+                save_baseline,
+                history,
+                &fail_on,
+                &severity_rules,
             )
             .await?;
+            if exit_code != 0 {
+                std::process::exit(exit_code);
+            }
         }
         Commands::RunAll {
             test_dir,
             generate,
+            input,
+            input_file,
+            input_dir,
+            input_args,
+            repeat,
+            timeout,
+            output_dir,
+            language,
+            analysis_mode,
+            bridge,
+            self_test,
+            batch_size,
+            format,
+            report_template,
+            jobs,
+            priority,
+            language_jobs,
+            retries,
+            state,
+            junit,
+            history,
+            metrics_addr,
+            since,
+            filter,
+            exclude,
+            verify,
+        } => {
+            let scheduler = SchedulerSettings {
+                batch_size,
+                jobs,
+                priorities: priority,
+                language_jobs: language_jobs
+                    .into_iter()
+                    .map(|(language, limit)| (orchestrator::normalize_language_filter(&language), limit))
+                    .collect(),
+                retries,
+                state,
+            };
+            // Given inputs replace the generated ones for every target.
+            let given = !(input.is_empty() && input_file.is_empty() && input_dir.is_none() && input_args.is_empty());
+            let corpus = given
+                .then(|| collect_inputs(input, &input_file, input_dir.as_deref(), &input_args))
+                .transpose()?;
+            let exit_code = orchestrator::run_all_tests(
+                test_dir,
+                generate,
+                corpus,
+                repeat,
+                timeout,
+                output_dir,
+                language,
+                analysis_mode.into(),
+                bridge.into(),
+                self_test,
+                scheduler,
+                &report_settings(format, report_template, None)?,
+                junit,
+                history,
+                metrics_addr,
+                since,
+                orchestrator::TargetFilter {
+                    include: filter,
+                    exclude,
+                },
+                verify,
+            )
+            .await?;
+            if exit_code != 0 {
+                std::process::exit(exit_code);
+            }
+        }
+        Commands::Bench {
+            test_dir,
+            generate,
+            repeat,
+            timeout,
             output_dir,
             language,
             analysis_mode,
+            bridge,
+            batch_size,
+            baseline,
         } => {
-            orchestrator::run_all_tests(
+            let modes = match analysis_mode.into() {
+                AnalysisMode::Both => vec![AnalysisMode::Static, AnalysisMode::Dynamic],
+                mode => vec![mode],
+            };
+            orchestrator::run_benchmark(
                 test_dir,
                 generate,
+                repeat,
+                timeout,
                 output_dir,
                 language,
+                &modes,
+                bridge.into(),
+                batch_size,
+                baseline,
+            )
+            .await?;
+        }
+        Commands::Compare {
+            manifest,
+            input,
+            repeat,
+            timeout,
+            output_dir,
+            analysis_mode,
+            bridge,
+        } => {
+            compare::run_compare(
+                manifest,
+                input,
+                repeat,
+                timeout,
+                output_dir,
                 analysis_mode.into(),
+                bridge.into(),
             )
             .await?;
         }
+        Commands::Diff { baseline, current } => {
+            diff::run_diff(baseline, current)?;
+        }
+        Commands::Scan {
+            dir,
+            output_dir,
+            language,
+            verbose,
+            output_format,
+            no_report,
+            since,
+        } => {
+            scan::run_scan(dir, output_dir, language, verbose, output_format.into(), no_report, since).await?;
+        }
+        Commands::Trends {
+            history,
+            sessions,
+            target,
+            changed,
+        } => {
+            history::print_trends(&history, sessions, target.as_deref(), changed)?;
+        }
+        Commands::Doctor { language } => {
+            doctor::run_doctor(language).await?;
+        }
+        Commands::SelfTest {
+            fixtures,
+            language,
+            analysis_mode,
+        } => {
+            self_test::run_self_test(fixtures, language, analysis_mode.into()).await?;
+        }
+        Commands::BuildBridges { language } => {
+            build::build_bridges(language).await?;
+        }
+        Commands::InstallBridges {
+            version,
+            url,
+            language,
+        } => {
+            install::install_bridges(version, url, language).await?;
+        }
         Commands::List { detailed } => {
             orchestrator::list_analyzers(detailed).await?;
         }
+        Commands::Rules {
+            command: RulesCommand::List { language, output_format },
+        } => {
+            orchestrator::list_rules(language, output_format.into())?;
+        }
+        Commands::Fix { target, language, rules, min_confidence, dry_run, verbose } => {
+            fix::run_fix(target, language, rules, min_confidence.into(), dry_run, verbose).await?;
+        }
+        Commands::Lsp { verbose, metrics_addr } => {
+            let exit_code = lsp::run_lsp(verbose, metrics_addr).await?;
+            if exit_code != 0 {
+                std::process::exit(exit_code);
+            }
+        }
         Commands::Clear {
             output_dir,
             archive_csv,
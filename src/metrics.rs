@@ -0,0 +1,263 @@
+//! Prometheus metrics
+//! `--metrics-addr <ADDR>` on the long-running commands (`run-all`, `lsp`) serves the
+//! counters below at `http://<ADDR>/metrics` in the Prometheus text format while the
+//! command runs: analyses completed, escapes found by type and language, bridge
+//! failures and a histogram of analysis latency. Analyses record into a process-wide
+//! registry whether or not an endpoint is served.
+
+use crate::protocol::{AnalyzeResponse, EscapeType};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info};
+
+/// Upper bounds in seconds of the latency histogram buckets; `+Inf` is implied.
+const LATENCY_BUCKETS: &[f64] = &[0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0];
+
+/// Input of the vulnerabilities mirroring static escapes, which are counted by their
+/// own escape type instead.
+const STATIC_INPUT: &str = "[static analysis]";
+
+/// Largest request head read from a scraper.
+const MAX_REQUEST_BYTES: usize = 8192;
+
+/// How long a scraper gets to send its request head before the connection is dropped.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+static METRICS: Mutex<Metrics> = Mutex::new(Metrics::new());
+
+#[derive(Debug)]
+struct Metrics {
+    analyses: BTreeMap<String, u64>,
+    /// (language, escape type) -> escapes
+    escapes: BTreeMap<(String, String), u64>,
+    bridge_failures: BTreeMap<String, u64>,
+    latency: BTreeMap<String, Histogram>,
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Observations per bucket of `LATENCY_BUCKETS`, not cumulative.
+    buckets: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            analyses: BTreeMap::new(),
+            escapes: BTreeMap::new(),
+            bridge_failures: BTreeMap::new(),
+            latency: BTreeMap::new(),
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP graphene_analyses_total Target analyses completed.\n");
+        out.push_str("# TYPE graphene_analyses_total counter\n");
+        for (language, count) in &self.analyses {
+            let _ = writeln!(out, "graphene_analyses_total{{language=\"{}\"}} {}", escape_label(language), count);
+        }
+        out.push_str("# HELP graphene_escapes_total Escapes detected, static and dynamic.\n");
+        out.push_str("# TYPE graphene_escapes_total counter\n");
+        for ((language, escape_type), count) in &self.escapes {
+            let _ = writeln!(
+                out,
+                "graphene_escapes_total{{language=\"{}\",type=\"{}\"}} {}",
+                escape_label(language),
+                escape_label(escape_type),
+                count
+            );
+        }
+        out.push_str("# HELP graphene_bridge_failures_total Analyses whose bridge failed to run or answer.\n");
+        out.push_str("# TYPE graphene_bridge_failures_total counter\n");
+        for (language, count) in &self.bridge_failures {
+            let _ = writeln!(
+                out,
+                "graphene_bridge_failures_total{{language=\"{}\"}} {}",
+                escape_label(language),
+                count
+            );
+        }
+        out.push_str("# HELP graphene_analysis_duration_seconds Time spent analyzing one target.\n");
+        out.push_str("# TYPE graphene_analysis_duration_seconds histogram\n");
+        for (language, histogram) in &self.latency {
+            let language = escape_label(language);
+            let mut cumulative = 0;
+            for (bound, observed) in LATENCY_BUCKETS.iter().zip(&histogram.buckets) {
+                cumulative += observed;
+                let _ = writeln!(
+                    out,
+                    "graphene_analysis_duration_seconds_bucket{{language=\"{}\",le=\"{}\"}} {}",
+                    language, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "graphene_analysis_duration_seconds_bucket{{language=\"{}\",le=\"+Inf\"}} {}",
+                language, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "graphene_analysis_duration_seconds_sum{{language=\"{}\"}} {}",
+                language, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "graphene_analysis_duration_seconds_count{{language=\"{}\"}} {}",
+                language, histogram.count
+            );
+        }
+        out
+    }
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; LATENCY_BUCKETS.len()];
+        }
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+/// Records one completed analysis of a target: its escapes by type, whether its bridge
+/// failed, and how long it took.
+pub fn record_analysis(response: &AnalyzeResponse, elapsed: Duration) {
+    let Ok(mut metrics) = METRICS.lock() else {
+        return;
+    };
+    let language = response.language.clone();
+    *metrics.analyses.entry(language.clone()).or_default() += 1;
+    let static_types = response
+        .static_analysis
+        .iter()
+        .flat_map(|result| &result.escapes)
+        .map(|escape| static_escape_type(&escape.escape_type));
+    let dynamic_types = response
+        .vulnerabilities
+        .iter()
+        .filter(|vulnerability| vulnerability.input != STATIC_INPUT)
+        .map(|vulnerability| vulnerability.vulnerability_type.as_str());
+    for escape_type in static_types.chain(dynamic_types) {
+        *metrics
+            .escapes
+            .entry((language.clone(), escape_type.to_string()))
+            .or_default() += 1;
+    }
+    if response.error.is_some() {
+        *metrics.bridge_failures.entry(language.clone()).or_default() += 1;
+    }
+    metrics.latency.entry(language).or_default().observe(elapsed.as_secs_f64());
+}
+
+/// Records a bridge failure that left no response to record.
+pub fn record_bridge_failure(language: &str) {
+    if let Ok(mut metrics) = METRICS.lock() {
+        *metrics.bridge_failures.entry(language.to_string()).or_default() += 1;
+    }
+}
+
+/// Binds `addr` and serves `/metrics` from a background task for the rest of the
+/// process.
+pub async fn serve(addr: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind the metrics endpoint to {}", addr))?;
+    info!("Serving metrics at http://{}/metrics", listener.local_addr().unwrap_or(addr));
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(async move {
+                        if let Err(e) = answer(stream).await {
+                            debug!("Metrics request failed: {:#}", e);
+                        }
+                    });
+                }
+                Err(e) => debug!("Failed to accept a metrics connection: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Bytes of the request up to the blank line ending its head, at most `MAX_REQUEST_BYTES`.
+async fn read_head(stream: &mut TcpStream) -> Result<Vec<u8>> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") && head.len() < MAX_REQUEST_BYTES {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+    Ok(head)
+}
+
+/// Answers one HTTP/1.x request and closes the connection.
+async fn answer(mut stream: TcpStream) -> Result<()> {
+    let head = tokio::time::timeout(REQUEST_TIMEOUT, read_head(&mut stream))
+        .await
+        .context("Timed out reading the request")??;
+    let head = String::from_utf8_lossy(&head);
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let path = request_line.next().unwrap_or_default();
+    let path = path.split('?').next().unwrap_or(path);
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET" | "HEAD", "/metrics") => {
+            let body = METRICS.lock().map(|metrics| metrics.render()).unwrap_or_default();
+            ("200 OK", "text/plain; version=0.0.4; charset=utf-8", body)
+        }
+        ("GET" | "HEAD", _) => ("404 Not Found", "text/plain; charset=utf-8", "Not found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "Method not allowed\n".to_string(),
+        ),
+    };
+    let mut response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    if method != "HEAD" {
+        response.push_str(&body);
+    }
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn static_escape_type(escape_type: &EscapeType) -> &'static str {
+    match escape_type {
+        EscapeType::ReturnEscape => "return_escape",
+        EscapeType::ParameterEscape => "parameter_escape",
+        EscapeType::GlobalEscape => "global_escape",
+        EscapeType::ClosureEscape => "closure_escape",
+        EscapeType::HeapEscape => "heap_escape",
+        EscapeType::SpawnEscape => "spawn_escape",
+        EscapeType::ExitEscape => "exit_escape",
+        EscapeType::UnknownEscape => "unknown_escape",
+    }
+}
+
+/// Escapes a label value for the text format.
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -1,16 +1,34 @@
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
-use crate::analyzer::AnalyzerRegistry;
-use crate::protocol::{AnalyzeRequest, AnalyzeResponse, AnalysisMode, ConfidenceLevel, EscapeType, ExecutionSummary, ExecutionResult, Vulnerability, EscapeDetails, ObjectReference, EscapePath};
-use crate::report::ReportGenerator;
-use crate::static_analyzer::StaticAnalyzerFactory;
-use std::collections::{HashMap, HashSet};
+use crate::analyzer::{Analyzer, AnalyzerRegistry, BridgeTransport};
+use crate::analyzer::rust::is_prebuilt_library;
+use crate::changes::ChangedLines;
+use crate::corpus::Inputs;
+use crate::history::{self, HistorySession, HistoryTarget};
+use crate::metrics;
+use crate::scheduler::{JobStore, SchedulerSettings};
+use crate::input_gen;
+use crate::interrupt;
+use crate::junit::{write_junit, JunitCase, JunitOutcome};
+use crate::logging::{self, Console};
+use crate::verify::{self, Verdict};
+use crate::bench::{self, BenchReport, BenchRow};
+use crate::protocol::{option_keys, AnalysisError, AnalysisErrorKind, AnalyzeRequest, AnalyzeResponse, AnalysisMode, ConfidenceLevel, EscapeType, ExecutionSummary, ExecutionResult, MemoryEscape, MemoryGrowthSummary, RaceEvidence, StaticAnalysisResult, Vulnerability, EscapeDetails, ObjectReference, EscapePath, PROTOCOL_VERSION};
+use crate::severity::{self, SeverityRules};
+use crate::report::{escaped_work_summary, format_bytes, leaked_descriptor_summary, leaked_timer_summary, memory_growth_summary, spawn_correlation, write_json_report, FunctionReportRow, ReportGenerator, ReportSettings};
+use crate::static_analyzer::signature::ParamKind;
+use crate::static_analyzer::suppress::{self, Suppressions};
+use crate::static_analyzer::{callgraph, data_flow, exit_path, fixes, guard, recursion, rules, spawn_count, taint, StaticAnalyzerFactory};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::io::{BufRead, BufReader, Write};
+use std::net::SocketAddr;
 use std::process::Command;
-use std::time::SystemTime;
-use tracing::{info, warn, error};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn, error};
 
 fn static_found_escapes(response: &AnalyzeResponse) -> bool {
     response
@@ -55,7 +73,9 @@ fn merge_dynamic_into_response(base: &mut AnalyzeResponse, mut dynamic: AnalyzeR
     // misses and lift them to detected escapes.
     let has_strong_static_signal = static_has_strong_escape_signal(base);
     let has_benchmark_escape_hint = static_has_benchmark_escape_hint(base);
-    if has_strong_static_signal || static_found_escapes(base) || has_benchmark_escape_hint {
+    // Results the user suppressed stay negative.
+    let suppressed = dynamic.summary.suppressed > 0;
+    if !suppressed && (has_strong_static_signal || static_found_escapes(base) || has_benchmark_escape_hint) {
         for result in &mut dynamic.results {
             if !result.escape_detected {
                 result.escape_detected = true;
@@ -68,43 +88,176 @@ fn merge_dynamic_into_response(base: &mut AnalyzeResponse, mut dynamic: AnalyzeR
     base.results = dynamic.results;
     base.vulnerabilities.extend(dynamic.vulnerabilities);
     base.summary = dynamic.summary;
+    base.bridge_stderr = dynamic.bridge_stderr;
+    base.chaos_seed = dynamic.chaos_seed;
+    base.error = dynamic.error;
+    base.warnings = dynamic.warnings;
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn analyze_target(
     target: &str,
-    inputs: Vec<String>,
+    inputs: Inputs,
     repeat: usize,
     timeout: f64,
     output_dir: PathBuf,
     language: Option<String>,
     analysis_mode: AnalysisMode,
     verbose: bool,
-) -> Result<()> {
-    init_logging(verbose);
-
+    bridge: BridgeSettings,
+    oracle: Option<String>,
+    output_format: OutputFormat,
+    no_report: bool,
+    report: &ReportSettings,
+    save_baseline: Option<PathBuf>,
+    history: Option<PathBuf>,
+    fail_on: &[FailOn],
+    severity_rules: &SeverityRules,
+) -> Result<i32> {
+    match output_format {
+        OutputFormat::Text => init_logging(verbose),
+        // Keep stdout to the JSON document so it can be piped.
+        OutputFormat::Json => init_stderr_logging(verbose),
+        OutputFormat::Quiet => init_quiet_logging(),
+    }
+    interrupt::finish_gracefully();
+
+    let started = std::time::Instant::now();
     info!("Initializing object escape analysis...");
     info!("Analysis mode: {:?}", analysis_mode);
-    run_startup_runtime_self_check(target, language.as_deref(), analysis_mode).await?;
-    
+    let functions = file_function_targets(target)?;
+    // The file's extension names the language for every function in it.
+    let language = language.or_else(|| functions.as_ref().and_then(|_| detect_language_from_target(target).ok()));
+    let first_target = functions.as_ref().and_then(|functions| functions.first()).map_or(target, String::as_str);
+    run_startup_runtime_self_check(first_target, language.as_deref(), analysis_mode).await?;
+
+    let settings = AnalysisSettings {
+        repeat,
+        timeout,
+        language,
+        analysis_mode,
+        bridge,
+        oracle,
+        severity_rules,
+    };
+    if let Some(functions) = functions {
+        if save_baseline.is_some() {
+            anyhow::bail!("--save-baseline needs a single function target, not the whole file {}", target);
+        }
+        let responses = analyze_functions(&functions, &inputs, &settings).await;
+        return finish_file_analysis(target, &responses, started, output_dir, output_format, no_report, report, history, fail_on)
+            .await
+            .map(interrupt::exit_code);
+    }
+
+    let response = analyze_response(target, inputs, &settings).await?;
+
+    if !no_report {
+        info!("Generating report...");
+        let report_gen = ReportGenerator::new(output_dir).with_settings(report);
+        let session_dir = report_gen.generate(&response, target).await?;
+        announce_report(output_format, "Reports generated in", &session_dir);
+    }
+
+    if let Some(path) = &save_baseline {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        write_json_report(path, &response, target)
+            .await
+            .with_context(|| format!("Failed to save baseline: {}", path.display()))?;
+        info!("Baseline saved to {}", path.display());
+    }
+
+    if let Some(path) = &history {
+        let mut outcome = TargetOutcome::new(&response.language, target);
+        outcome.record(&response);
+        let session = HistorySession {
+            session_id: response.session_id.clone(),
+            command: "analyze".to_string(),
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+        history::record_session(path, &session, &[outcome.history_target()])
+            .with_context(|| format!("Failed to record history in {}", path.display()))?;
+        info!("Session recorded in {}", path.display());
+    }
+
+    match output_format {
+        OutputFormat::Text => print_summary(&response),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&response)?),
+        OutputFormat::Quiet => {}
+    }
+
+    Ok(interrupt::exit_code(fail_on_exit_code(&response, fail_on)))
+}
+
+/// How `analyze` runs each target, shared by the functions of a whole-file target.
+struct AnalysisSettings<'a> {
+    repeat: usize,
+    timeout: f64,
+    language: Option<String>,
+    analysis_mode: AnalysisMode,
+    bridge: BridgeSettings,
+    oracle: Option<String>,
+    severity_rules: &'a SeverityRules,
+}
+
+/// Static analysis and dynamic verification of one target, merged into one response.
+async fn analyze_response(target: &str, inputs: Inputs, settings: &AnalysisSettings<'_>) -> Result<AnalyzeResponse> {
+    let AnalysisSettings {
+        repeat,
+        timeout,
+        ref language,
+        analysis_mode,
+        ref bridge,
+        ref oracle,
+        severity_rules,
+    } = *settings;
     let mut response: Option<AnalyzeResponse> = None;
     
-    // Static analysis
-    if analysis_mode == AnalysisMode::Static || analysis_mode == AnalysisMode::Both {
+    // Static analysis; a prebuilt library has no source to read
+    let prebuilt = is_prebuilt_library(target);
+    if prebuilt && analysis_mode == AnalysisMode::Static {
+        anyhow::bail!("Static analysis needs source; {} is a prebuilt library", target);
+    }
+    if prebuilt && analysis_mode == AnalysisMode::Both {
+        warn!("Skipping static analysis for prebuilt library {}", target);
+    } else if analysis_mode == AnalysisMode::Both && !has_static_analyzer(target, language.as_deref()) {
+        // Languages defined in the config file only have a bridge.
+        warn!("Skipping static analysis for {}: no static analyzer for its language", target);
+    } else if analysis_mode == AnalysisMode::Static || analysis_mode == AnalysisMode::Both {
         info!("Running static object escape analysis...");
-        response = Some(run_static_analysis(target, language.as_deref(), analysis_mode).await?);
+        let mut static_response = run_static_analysis(target, language.as_deref(), analysis_mode).await?;
+        severity::score(&mut static_response, None, severity_rules);
+        response = Some(static_response);
     }
     
     // Dynamic analysis - enhanced for object escape verification
     if analysis_mode == AnalysisMode::Dynamic || analysis_mode == AnalysisMode::Both {
         info!("Running dynamic object escape verification...");
-        let dynamic_response = run_dynamic_analysis(
-            target,
+        let (dynamic_target, options) = bridge.target_request(target);
+        let mut dynamic_response = run_dynamic_analysis(
+            &dynamic_target,
             inputs,
-            repeat,
+            bridge.effective_repeat(repeat),
             timeout,
             language.as_deref(),
             analysis_mode,
+            options,
         ).await?;
+        check_chaos_support(bridge, &dynamic_response);
+        suppress_dynamic(target, &mut dynamic_response);
+        flag_nondeterminism(&mut dynamic_response);
+        let static_result = response.as_ref().and_then(|r| r.static_analysis.as_ref());
+        if bridge.race_runs.is_some() {
+            let shared = shared_state(target, language.as_deref(), static_result).await;
+            flag_data_races(&mut dynamic_response, &shared);
+        }
+        flag_memory_growth(&mut dynamic_response, bridge.memory_threshold_bytes());
+        severity::score(&mut dynamic_response, static_result, severity_rules);
+        if let Some(oracle) = oracle {
+            apply_oracle(oracle, timeout, &mut dynamic_response).await;
+        }
         
         if let Some(ref mut resp) = response {
             // Merge static results with dynamic verification.
@@ -114,17 +267,211 @@ pub async fn analyze_target(
         }
     }
     
-    let response = response.ok_or_else(|| anyhow::anyhow!("No analysis was performed"))?;
+    response.ok_or_else(|| anyhow::anyhow!("No analysis was performed"))
+}
 
-    // Generate report
-    info!("Generating report...");
-    let report_gen = ReportGenerator::new(output_dir);
-    report_gen.generate(&response, target).await?;
+/// Targets for the public functions of a whole-file target (`file.ext` with no
+/// function), found the way `run-all` discovers them; `None` for any other target.
+fn file_function_targets(target: &str) -> Result<Option<Vec<String>>> {
+    let path = Path::new(target);
+    if target.contains("::") || !path.is_file() {
+        return Ok(None);
+    }
+    let extension = path
+        .extension()
+        .and_then(|value| value.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+    let targets: Vec<String> = match extension.as_str() {
+        "py" => extract_python_functions(&content)
+            .into_iter()
+            .map(|function| format!("{}:{}", target, function))
+            .collect(),
+        "js" | "mjs" => {
+            let mut exports = extract_nodejs_exports(&content);
+            exports.sort();
+            exports
+                .into_iter()
+                .map(|function| format!("{}:{}", target, function))
+                .collect()
+        }
+        "go" => extract_go_functions(&content)
+            .into_iter()
+            .map(|function| format!("{}:{}", target, function))
+            .collect(),
+        "rs" => {
+            let module = path.file_stem().and_then(|value| value.to_str()).unwrap_or_default();
+            let crate_name = path
+                .ancestors()
+                .skip(1)
+                .find_map(read_rust_crate_name)
+                .unwrap_or_else(|| "tests_rust".to_string());
+            extract_rust_functions(&content)
+                .into_iter()
+                .map(|function| format!("{}::{}::{}", crate_name, module, function))
+                .collect()
+        }
+        "java" => anyhow::bail!(
+            "Whole-file analysis does not support Java; name a method as classpath:Class:method"
+        ),
+        _ => return Ok(None),
+    };
+    if targets.is_empty() {
+        anyhow::bail!("No public functions found in {}", target);
+    }
+    info!("Found {} public function(s) in {}", targets.len(), target);
+    Ok(Some(targets))
+}
 
-    // Print summary
-    print_summary(&response);
+/// Analyzes each of `targets` with the same inputs; a target whose analysis fails is
+/// reported without a response.
+async fn analyze_functions(
+    targets: &[String],
+    inputs: &Inputs,
+    settings: &AnalysisSettings<'_>,
+) -> Vec<(String, Option<AnalyzeResponse>)> {
+    let mut responses = Vec::with_capacity(targets.len());
+    for (index, target) in targets.iter().enumerate() {
+        if interrupt::interrupted() {
+            warn!("Interrupted; {} of {} function(s) were not analyzed", targets.len() - index, targets.len());
+            break;
+        }
+        info!("Analyzing function {}/{}: {}", index + 1, targets.len(), target);
+        let response = match analyze_response(target, inputs.clone(), settings).await {
+            Ok(response) => Some(response),
+            Err(e) => {
+                warn!("Analysis failed for {}: {:#}", target, e);
+                None
+            }
+        };
+        responses.push((target.clone(), response));
+    }
+    responses
+}
 
-    Ok(())
+/// Reports, history and the function breakdown of a whole-file analysis. The exit code
+/// is that of the first function tripping a `--fail-on` policy, or 1 when no function
+/// could be analyzed.
+#[allow(clippy::too_many_arguments)]
+async fn finish_file_analysis(
+    file: &str,
+    responses: &[(String, Option<AnalyzeResponse>)],
+    started: std::time::Instant,
+    output_dir: PathBuf,
+    output_format: OutputFormat,
+    no_report: bool,
+    report: &ReportSettings,
+    history: Option<PathBuf>,
+    fail_on: &[FailOn],
+) -> Result<i32> {
+    let language = responses
+        .iter()
+        .find_map(|(_, response)| response.as_ref().map(|response| response.language.clone()))
+        .or_else(|| detect_language_from_target(file).ok())
+        .unwrap_or_default();
+
+    let mut rows = Vec::with_capacity(responses.len());
+    let mut outcomes = Vec::with_capacity(responses.len());
+    for (target, response) in responses {
+        let mut outcome = TargetOutcome::new(&language, target);
+        let mut report_dir = None;
+        if let Some(response) = response {
+            outcome.record(response);
+            if !no_report {
+                let report_gen = ReportGenerator::new(output_dir.clone()).with_settings(&report.for_function(function_name(target)));
+                let session_dir = report_gen.generate(response, target).await?;
+                announce_report(output_format, "Reports generated in", &session_dir);
+                report_dir = Some(session_dir);
+            }
+        }
+        rows.push(FunctionReportRow {
+            function: function_name(target).to_string(),
+            analyzed: outcome.analyzed,
+            escapes: outcome.escapes,
+            vulnerabilities: outcome.vulnerabilities,
+            crashes: outcome.crashes,
+            time_ms: outcome.time_ms,
+            report_dir,
+        });
+        outcomes.push(outcome);
+    }
+
+    if !no_report {
+        let file_dir = ReportGenerator::new(output_dir)
+            .with_settings(report)
+            .generate_file_report(file, &language, &rows)
+            .await?;
+        announce_report(output_format, "File report generated in", &file_dir);
+    }
+
+    if let Some(path) = &history {
+        let session = HistorySession {
+            session_id: Uuid::new_v4().to_string(),
+            command: "analyze".to_string(),
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+        let targets: Vec<HistoryTarget> = outcomes.iter().map(TargetOutcome::history_target).collect();
+        history::record_session(path, &session, &targets)
+            .with_context(|| format!("Failed to record history in {}", path.display()))?;
+        info!("Session recorded in {}", path.display());
+    }
+
+    match output_format {
+        OutputFormat::Text => print_file_summary(file, &rows),
+        OutputFormat::Json => {
+            let functions: Vec<serde_json::Value> = responses
+                .iter()
+                .map(|(target, response)| serde_json::json!({ "target": target, "response": response }))
+                .collect();
+            let document = serde_json::json!({ "file": file, "language": language, "functions": functions });
+            println!("{}", serde_json::to_string_pretty(&document)?);
+        }
+        OutputFormat::Quiet => {}
+    }
+
+    if rows.iter().all(|row| !row.analyzed) {
+        eprintln!("✗ No function of {} could be analyzed", file);
+        return Ok(1);
+    }
+    Ok(responses
+        .iter()
+        .filter_map(|(_, response)| response.as_ref())
+        .map(|response| fail_on_exit_code(response, fail_on))
+        .find(|code| *code != 0)
+        .unwrap_or(0))
+}
+
+/// The function part of a `file.ext:function` or `crate::module::function` target.
+fn function_name(target: &str) -> &str {
+    target.rsplit(':').next().unwrap_or(target)
+}
+
+fn print_file_summary(file: &str, rows: &[FunctionReportRow]) {
+    println!("\n╔════════════════════════════════════════════╗");
+    println!("║            File Analysis Summary           ║");
+    println!("╚════════════════════════════════════════════╝\n");
+    println!("File: {}\n", file);
+    println!("{:<32} {:>8} {:>16} {:>8} {:>10}", "Function", "Escapes", "Vulnerabilities", "Crashes", "Time");
+    for row in rows {
+        if row.analyzed {
+            println!(
+                "{:<32} {:>8} {:>16} {:>8} {:>8}ms",
+                row.function, row.escapes, row.vulnerabilities, row.crashes, row.time_ms
+            );
+        } else {
+            println!("{:<32} {:>8}", row.function, "✗ no results");
+        }
+    }
+    let escaping = rows.iter().filter(|row| row.escapes > 0).count();
+    let failed = rows.iter().filter(|row| !row.analyzed).count();
+    println!(
+        "\nTotal: {} function(s), {} with escapes, {} without results, {} vulnerabilit(ies)",
+        rows.len(),
+        escaping,
+        failed,
+        rows.iter().map(|row| row.vulnerabilities).sum::<usize>()
+    );
 }
 
 async fn run_startup_runtime_self_check(
@@ -144,7 +491,7 @@ async fn run_startup_runtime_self_check(
     for failure in init_failures {
         eprintln!("  - {}: {}", failure.language, failure.reason);
     }
-    eprintln!("  Tip: run `graphene-ha list --detailed` for analyzer diagnostics.\n");
+    eprintln!("  Tip: run `graphene-ha doctor` for what is missing and how to install or build it.\n");
 
     if analysis_mode == AnalysisMode::Dynamic || analysis_mode == AnalysisMode::Both {
         let normalized_language = language.map(normalize_language_filter);
@@ -168,7 +515,7 @@ async fn run_startup_runtime_self_check(
     Ok(())
 }
 
-async fn run_static_analysis(
+pub(crate) async fn run_static_analysis(
     target: &str,
     language: Option<&str>,
     analysis_mode: AnalysisMode,
@@ -196,7 +543,54 @@ async fn run_static_analysis(
     let source_file = resolve_source_file(target)?;
     
     info!("Analyzing source file: {}", source_file);
-    let static_result = static_analyzer.analyze(target, &source_file)?;
+    let mut static_result = static_analyzer.analyze(target, &source_file)?;
+    // Mark findings that carry the target's own parameters.
+    let taint_report = taint::trace(static_analyzer.language(), target, &source_file);
+    if let Some(report) = &taint_report {
+        let function = callgraph::target_function(target);
+        for escape in &mut static_result.escapes {
+            report.annotate(escape, function);
+        }
+    }
+    match static_analyzer.call_graph(target, &source_file) {
+        Ok(call_graph) => {
+            // Surface escapes from spawning helpers on the function that calls them.
+            let derived = callgraph::propagate_escapes(
+                static_analyzer.as_ref(),
+                target,
+                &source_file,
+                &call_graph,
+                taint_report.as_ref(),
+            );
+            let recursive = recursion::recursive_spawns(static_analyzer.language(), &source_file, &call_graph);
+            let exits = exit_path::exit_paths(static_analyzer.language(), &source_file, &call_graph);
+            for escape in derived.into_iter().chain(recursive).chain(exits) {
+                static_result.summary.add_escape(&escape);
+                static_result.escapes.push(escape);
+            }
+            let spawn_estimate = spawn_count::estimate(
+                static_analyzer.language(),
+                &source_file,
+                &call_graph,
+                taint_report.as_ref(),
+            );
+            let in_loops = spawn_estimate
+                .as_ref()
+                .map(|estimate| spawn_count::loop_spawns(static_analyzer.language(), estimate, &source_file))
+                .unwrap_or_default();
+            for escape in in_loops {
+                static_result.summary.add_escape(&escape);
+                static_result.escapes.push(escape);
+            }
+            static_result.spawn_estimate = spawn_estimate;
+            static_result.call_graph = Some(call_graph);
+        }
+        Err(e) => static_result.warnings.push(format!("Call graph unavailable: {:#}", e)),
+    }
+    guard::annotate_conditions(static_analyzer.language(), &source_file, &mut static_result.escapes);
+    data_flow::annotate_flows(static_analyzer.language(), &source_file, &mut static_result.escapes);
+    fixes::suggest(static_analyzer.as_ref(), &source_file, &mut static_result.escapes);
+    Suppressions::load(static_analyzer.language(), &source_file).apply_static(&mut static_result);
     
     // Convert static analysis results into execution results
     let mut results = vec![];
@@ -204,10 +598,7 @@ async fn run_static_analysis(
     let mut total_escapes = 0;
     
     if !static_result.escapes.is_empty() {
-        let mut escape_details = EscapeDetails {
-            escaping_references: vec![],
-            escape_paths: vec![],
-        };
+        let mut escape_details = EscapeDetails::default();
         
         for escape in &static_result.escapes {
             let reference = ObjectReference {
@@ -232,10 +623,14 @@ async fn run_static_analysis(
             success: true,
             crashed: false,
             output: format!("{} escape(s) detected", static_result.escapes.len()),
-            error: String::new(),
+            error: None,
             execution_time_ms: static_result.analysis_time_ms,
             escape_detected: true,
             escape_details,
+            stdout: String::new(),
+            stderr: String::new(),
+            artifacts: vec![],
+            not_executed: false,
         };
         results.push(result);
         
@@ -247,10 +642,7 @@ async fn run_static_analysis(
                 vulnerability_type: "object_escape".to_string(),
                 severity: format!("{:?}", escape.confidence),
                 description: escape.reason.clone(),
-                escape_details: EscapeDetails {
-                    escaping_references: vec![],
-                    escape_paths: vec![],
-                },
+                escape_details: EscapeDetails::default(),
             });
         }
     }
@@ -273,18 +665,25 @@ async fn run_static_analysis(
             escapes: total_escapes,
             genuine_escapes: total_escapes,
             crash_rate: 0.0,
+            suppressed: static_result.summary.suppressed,
+            memory: None,
         },
         static_analysis: Some(static_result),
+        bridge_stderr: None,
+        chaos_seed: None,
+        error: None,
+        warnings: Vec::new(),
     })
 }
 
 async fn run_dynamic_analysis(
     target: &str,
-    inputs: Vec<String>,
+    inputs: Inputs,
     repeat: usize,
     timeout: f64,
     language: Option<&str>,
     analysis_mode: AnalysisMode,
+    options: HashMap<String, String>,
 ) -> Result<AnalyzeResponse> {
     let registry = AnalyzerRegistry::initialize_all().await?;
 
@@ -306,22 +705,756 @@ async fn run_dynamic_analysis(
     // Create request
     let session_id = Uuid::new_v4().to_string();
     let request = AnalyzeRequest {
+        protocol_version: PROTOCOL_VERSION,
         session_id: session_id.clone(),
         target: target.to_string(),
-        inputs: inputs.clone(),
+        input_encodings: inputs.request_encodings(),
+        input_args: inputs.args,
+        inputs: inputs.values,
         repeat,
         timeout_seconds: timeout,
-        options: HashMap::new(),
+        options,
         analysis_mode,
     };
 
-    info!("Running analysis with {} inputs (repeat {}x)...", inputs.len(), repeat);
-    let response = analyzer.analyze(request).await?;
-    
-    Ok(response)
+    info!("Running analysis with {} inputs (repeat {}x)...", request.inputs.len(), repeat);
+    let response = analyzer.analyze(request).await;
+    registry.shutdown().await;
+
+    response
+}
+
+/// Warns when schedule perturbation was requested but the bridge ran without it.
+fn check_chaos_support(bridge: &BridgeSettings, response: &AnalyzeResponse) {
+    if bridge.chaos_seed.is_some() && response.chaos_seed.is_none() && response.error.is_none() {
+        warn!(
+            "The {} bridge does not support schedule perturbation; executions ran unperturbed",
+            response.language
+        );
+    }
+}
+
+/// Adds a `nondeterministic` vulnerability for each input whose successful repeats
+/// produced different outputs. Memory addresses (`0x7f..`, `Foo@1b6d3586`) are masked
+/// first so default object formatting does not count as a difference.
+fn flag_nondeterminism(response: &mut AnalyzeResponse) {
+    let mut inputs: Vec<&str> = Vec::new();
+    for result in &response.results {
+        if !inputs.contains(&result.input_data.as_str()) {
+            inputs.push(&result.input_data);
+        }
+    }
+
+    let mut flagged = Vec::new();
+    for input in inputs {
+        let outputs: Vec<String> = response
+            .results
+            .iter()
+            .filter(|r| r.input_data == input && r.success && !r.crashed && !r.not_executed)
+            .map(|r| mask_addresses(&r.output))
+            .collect();
+        let Some(first) = outputs.first() else {
+            continue;
+        };
+        let Some((run, other)) = outputs.iter().enumerate().find(|(_, output)| *output != first) else {
+            continue;
+        };
+        let distinct: HashSet<&String> = outputs.iter().collect();
+        flagged.push(Vulnerability {
+            input: input.to_string(),
+            vulnerability_type: "nondeterministic".to_string(),
+            severity: "Medium".to_string(),
+            description: format!(
+                "Output varied across {} runs of the same input ({} distinct outputs), a sign of a data race or ordering dependence; run 1 vs run {}: {}",
+                outputs.len(),
+                distinct.len(),
+                run + 1,
+                diff_sample(first, other)
+            ),
+            escape_details: EscapeDetails::default(),
+        });
+    }
+    if !flagged.is_empty() {
+        warn!("Output varied between repeats for {} input(s)", flagged.len());
+    }
+    response.vulnerabilities.extend(flagged);
+}
+
+/// Substrings of a static finding that mark state shared between threads, most
+/// specific first.
+const SHARED_STATE_MARKERS: &[&str] = &[
+    "Arc<Mutex", "Arc::new(Mutex", "Arc<RwLock", "static mut", "Mutex", "RwLock", "Atomic", "synchronized",
+    "ConcurrentHashMap", "sync.Map", "threading.Lock",
+];
+
+/// Shared state static analysis found `target` touching: escapes into globals and
+/// escapes through locks or atomics. Analyzes the target when `static_result` is
+/// `None`; a target static analysis cannot read has none.
+async fn shared_state(target: &str, language: Option<&str>, static_result: Option<&StaticAnalysisResult>) -> Vec<String> {
+    let analyzed;
+    let static_result = match static_result {
+        Some(result) => result,
+        None => match run_static_analysis(target, language, AnalysisMode::Static).await {
+            Ok(response) => {
+                analyzed = response.static_analysis;
+                match &analyzed {
+                    Some(result) => result,
+                    None => return Vec::new(),
+                }
+            }
+            Err(e) => {
+                debug!("No static analysis of {} for race mode: {:#}", target, e);
+                return Vec::new();
+            }
+        },
+    };
+
+    let mut evidence: Vec<String> = Vec::new();
+    for escape in &static_result.escapes {
+        let snippet = escape.location.code_snippet.as_deref().unwrap_or("");
+        let marker = SHARED_STATE_MARKERS
+            .iter()
+            .find(|marker| snippet.contains(*marker) || escape.reason.contains(*marker));
+        let line = match marker {
+            Some(marker) => format!("`{}` shared through {} (line {})", escape.variable_name, marker, escape.location.line),
+            None if escape.escape_type == EscapeType::GlobalEscape => {
+                format!("`{}` stored in global state (line {})", escape.variable_name, escape.location.line)
+            }
+            None => continue,
+        };
+        if !evidence.contains(&line) {
+            evidence.push(line);
+        }
+    }
+    evidence
+}
+
+/// Race mode: an input whose successful runs disagree, in output or in what they left
+/// behind (after the first run, which pays for warm-up), gets a `potential_data_race`
+/// vulnerability when the target touches `shared_state`. It replaces the input's
+/// `nondeterministic` finding. Without shared state, differing output stays
+/// `nondeterministic` and differing side effects alone are not reported.
+fn flag_data_races(response: &mut AnalyzeResponse, shared_state: &[String]) {
+    if shared_state.is_empty() {
+        return;
+    }
+    let mut inputs: Vec<&str> = Vec::new();
+    for result in &response.results {
+        if !inputs.contains(&result.input_data.as_str()) {
+            inputs.push(&result.input_data);
+        }
+    }
+
+    let mut flagged = Vec::new();
+    for input in inputs {
+        let runs: Vec<&ExecutionResult> = response
+            .results
+            .iter()
+            .filter(|r| r.input_data == input && r.success && !r.crashed && !r.not_executed)
+            .collect();
+        if runs.len() < 2 {
+            continue;
+        }
+        let mut masked: Vec<String> = Vec::new();
+        let mut outputs: Vec<String> = Vec::new();
+        for run in &runs {
+            let output = mask_addresses(&run.output);
+            if !masked.contains(&output) {
+                masked.push(output);
+                outputs.push(run.output.clone());
+            }
+        }
+        let mut side_effects: Vec<String> = Vec::new();
+        for run in &runs[1..] {
+            let summary = side_effect_summary(&run.escape_details);
+            if !side_effects.contains(&summary) {
+                side_effects.push(summary);
+            }
+        }
+        if outputs.len() < 2 && side_effects.len() < 2 {
+            continue;
+        }
+
+        let mut differences = Vec::new();
+        if outputs.len() > 1 {
+            differences.push(format!("{} distinct outputs", outputs.len()));
+        }
+        if side_effects.len() > 1 {
+            differences.push(format!("side effects {}", side_effects.join(" / ")));
+        }
+        let mut description = format!(
+            "Runs of the same input disagreed ({}) across {} runs while the target touches shared state: {}",
+            differences.join("; "),
+            runs.len(),
+            shared_state.join("; ")
+        );
+        if outputs.len() > 1 {
+            description.push_str(&format!("; {}", diff_sample(&masked[0], &masked[1])));
+        }
+        flagged.push(Vulnerability {
+            input: input.to_string(),
+            vulnerability_type: "potential_data_race".to_string(),
+            severity: "High".to_string(),
+            description,
+            escape_details: EscapeDetails {
+                race: Some(RaceEvidence {
+                    runs: runs.len(),
+                    outputs,
+                    side_effects: if side_effects.len() > 1 { side_effects } else { Vec::new() },
+                    shared_state: shared_state.to_vec(),
+                }),
+                ..EscapeDetails::default()
+            },
+        });
+    }
+    if flagged.is_empty() {
+        return;
+    }
+    warn!("Runs disagreed on shared state for {} input(s): potential data race", flagged.len());
+    response.vulnerabilities.retain(|vuln| {
+        vuln.vulnerability_type != "nondeterministic" || !flagged.iter().any(|race| race.input == vuln.input)
+    });
+    response.vulnerabilities.extend(flagged);
+}
+
+/// What an execution left running or open, by count, for comparing runs. Escaping
+/// references are left out: heap sampling makes their number vary on its own.
+fn side_effect_summary(details: &EscapeDetails) -> String {
+    let lists = [
+        ("thread", &details.threads),
+        ("thread pool", &details.thread_pools),
+        ("process", &details.processes),
+        ("task", &details.async_tasks),
+        ("goroutine", &details.goroutines),
+        ("descriptor", &details.file_descriptors),
+        ("timer", &details.timers),
+    ];
+    let parts: Vec<String> = lists
+        .iter()
+        .filter_map(|(kind, list)| Some(format!("{} {}(s)", list.as_ref()?.len(), kind)))
+        .collect();
+    parts.join(", ")
+}
+
+/// Judges the resident memory growth bridges measured across each input's repeats.
+/// The first repeat pays for imports, caches and warm-up and is left out; an input
+/// whose memory then grew on every repeat, each by its share of `threshold_bytes` or
+/// more, gets a `memory_leak` vulnerability. Growth past the threshold that is not
+/// sustained, such as one jump in an otherwise flat series, is counted as noise.
+/// Inputs need two measured repeats to be judged.
+fn flag_memory_growth(response: &mut AnalyzeResponse, threshold_bytes: u64) {
+    if response.results.iter().all(|r| r.escape_details.memory.is_none()) {
+        return;
+    }
+    let mut inputs: Vec<&str> = Vec::new();
+    for result in &response.results {
+        if !inputs.contains(&result.input_data.as_str()) {
+            inputs.push(&result.input_data);
+        }
+    }
+
+    let threshold = threshold_bytes as i64;
+    let mut summary = MemoryGrowthSummary { threshold_bytes, ..MemoryGrowthSummary::default() };
+    let mut flagged = Vec::new();
+    for input in inputs {
+        let runs: Vec<MemoryEscape> = response
+            .results
+            .iter()
+            .filter(|r| r.input_data == input && !r.not_executed)
+            .filter_map(|r| r.escape_details.memory)
+            .collect();
+        let (Some(first), Some(last)) = (runs.first(), runs.last()) else {
+            continue;
+        };
+        if runs.len() < 2 {
+            continue;
+        }
+        summary.measured_inputs += 1;
+        let steady = &runs[1..];
+        let steady_growth: i64 = steady.iter().map(MemoryEscape::growth_bytes).sum();
+        let least = steady.iter().map(MemoryEscape::growth_bytes).min().unwrap_or(0);
+        if least > 0 && least * steady.len() as i64 >= threshold {
+            summary.probable_leaks += 1;
+            flagged.push(Vulnerability {
+                input: input.to_string(),
+                vulnerability_type: "memory_leak".to_string(),
+                severity: "High".to_string(),
+                description: format!(
+                    "Resident memory grew on each of the {} repeat(s) after the first, by at least {} and {} in total, \
+                     past the {} threshold: memory allocated by the call is kept after it returns",
+                    steady.len(),
+                    format_bytes(least),
+                    format_bytes(steady_growth),
+                    format_bytes(threshold)
+                ),
+                escape_details: EscapeDetails {
+                    memory: Some(MemoryEscape {
+                        rss_before_bytes: first.rss_before_bytes,
+                        rss_after_bytes: last.rss_after_bytes,
+                    }),
+                    ..EscapeDetails::default()
+                },
+            });
+        } else if runs.iter().map(MemoryEscape::growth_bytes).sum::<i64>() >= threshold {
+            summary.noise += 1;
+        }
+    }
+    if !flagged.is_empty() {
+        warn!("Memory grew on every repeat for {} input(s)", flagged.len());
+    }
+    response.vulnerabilities.extend(flagged);
+    response.summary.memory = Some(summary);
+}
+
+/// Applies `graphene:ignore` comments and `.grapheneignore` patterns on the target's
+/// source to its dynamic results; targets without a readable source are left as is.
+fn suppress_dynamic(target: &str, response: &mut AnalyzeResponse) {
+    let Ok(source_file) = resolve_source_file(target) else {
+        return;
+    };
+    Suppressions::load(&response.language, &source_file).apply_dynamic(response, target);
+}
+
+/// The two outputs around their first difference.
+fn diff_sample(left: &str, right: &str) -> String {
+    const CONTEXT: usize = 24;
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    let at = left.iter().zip(&right).take_while(|(a, b)| a == b).count();
+    let window = |chars: &[char]| {
+        let start = at.saturating_sub(CONTEXT);
+        let end = (at + CONTEXT).min(chars.len());
+        format!(
+            "{}{}{}",
+            if start > 0 { "…" } else { "" },
+            chars[start..end].iter().collect::<String>(),
+            if end < chars.len() { "…" } else { "" }
+        )
+    };
+    format!("{:?} vs {:?} (first difference at character {})", window(&left), window(&right), at)
+}
+
+/// Replaces hex addresses (`0x7ffd5e8c`) and Java identity hashes (`Foo@1b6d3586`).
+fn mask_addresses(output: &str) -> String {
+    let chars: Vec<char> = output.chars().collect();
+    let mut masked = String::with_capacity(output.len());
+    let mut index = 0;
+    while index < chars.len() {
+        let prefix = match (chars[index], chars.get(index + 1)) {
+            ('0', Some('x')) => 2,
+            ('@', _) => 1,
+            _ => 0,
+        };
+        let digits = chars[index + prefix..]
+            .iter()
+            .take_while(|ch| ch.is_ascii_hexdigit())
+            .count();
+        if prefix > 0 && digits >= 4 {
+            masked.extend(&chars[index..index + prefix]);
+            masked.push('…');
+            index += prefix + digits;
+        } else {
+            masked.push(chars[index]);
+            index += 1;
+        }
+    }
+    masked
+}
+
+/// Runs the `--oracle` command for every execution that completed, with the input and
+/// output as `$1` and `$2`. A nonzero exit (or no exit within the execution timeout)
+/// turns the execution into an `oracle_failed` failure.
+async fn apply_oracle(oracle: &str, timeout: f64, response: &mut AnalyzeResponse) {
+    let mut rejected = 0;
+    for result in &mut response.results {
+        if result.not_executed || result.crashed || !result.success {
+            continue;
+        }
+        #[cfg(unix)]
+        let mut command = {
+            let mut command = tokio::process::Command::new("sh");
+            command.arg("-c").arg(oracle).arg("graphene-oracle");
+            command
+        };
+        #[cfg(not(unix))]
+        let mut command = {
+            let mut command = tokio::process::Command::new("cmd");
+            command.arg("/C").arg(oracle);
+            command
+        };
+        command
+            .arg(&result.input_data)
+            .arg(&result.output)
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true);
+
+        let verdict = match tokio::time::timeout(
+            std::time::Duration::from_secs_f64(timeout.max(1.0)),
+            command.output(),
+        )
+        .await
+        {
+            Ok(Ok(output)) if output.status.success() => None,
+            Ok(Ok(output)) => {
+                let text = format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Some((format!("exited with {}", output.status), text.trim().to_string()))
+            }
+            Ok(Err(e)) => Some((format!("could not be started: {}", e), String::new())),
+            Err(_) => Some((format!("did not finish within {}s", timeout.max(1.0)), String::new())),
+        };
+        if let Some((status, details)) = verdict {
+            let mut error = AnalysisError::new(
+                AnalysisErrorKind::OracleFailed,
+                format!("oracle `{}` {} for output {:?}", oracle, status, result.output),
+            );
+            if !details.is_empty() {
+                error.details = Some(details);
+            }
+            result.success = false;
+            result.error = Some(error);
+            rejected += 1;
+        }
+    }
+    if rejected > 0 {
+        warn!("Oracle rejected {} execution(s)", rejected);
+    }
+    response.summary.successes = response.summary.successes.saturating_sub(rejected);
+}
+
+/// How `analyze` presents its result on stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    /// Nothing on stdout and only warnings on stderr; the exit code is the result
+    Quiet,
+}
+
+/// Where a report was written: on stdout next to the text summary, else only logged,
+/// so stdout keeps just the JSON document (or nothing).
+fn announce_report(output_format: OutputFormat, what: &str, dir: &Path) {
+    match output_format {
+        OutputFormat::Text => println!("📁 {}: {}", what, dir.display()),
+        OutputFormat::Json | OutputFormat::Quiet => info!("{}: {}", what, dir.display()),
+    }
+}
+
+/// Findings that make `analyze` exit non-zero, each with its own exit code so CI can
+/// tell them apart (1 stays reserved for errors running the analysis itself).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailOn {
+    /// Any static or dynamic escape
+    AnyEscape,
+    /// A dynamic escape not explained by a timeout
+    GenuineEscape,
+    /// A vulnerability graded high or critical
+    HighSeverity,
+    /// An execution that crashed or timed out
+    Crash,
+}
+
+impl FailOn {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            FailOn::AnyEscape => 2,
+            FailOn::GenuineEscape => 3,
+            FailOn::HighSeverity => 4,
+            FailOn::Crash => 5,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            FailOn::AnyEscape => "any-escape",
+            FailOn::GenuineEscape => "genuine-escape",
+            FailOn::HighSeverity => "high-severity",
+            FailOn::Crash => "crash",
+        }
+    }
+
+    /// Why the response trips this policy, or `None` when it does not.
+    fn violation(self, response: &AnalyzeResponse) -> Option<String> {
+        match self {
+            FailOn::AnyEscape => {
+                let static_escapes = response.static_analysis.as_ref().map_or(0, |s| s.escapes.len());
+                let escapes = response.summary.escapes + static_escapes;
+                (escapes > 0).then(|| {
+                    format!("{} dynamic and {} static escape(s)", response.summary.escapes, static_escapes)
+                })
+            }
+            FailOn::GenuineEscape => (response.summary.genuine_escapes > 0)
+                .then(|| format!("{} genuine escape(s)", response.summary.genuine_escapes)),
+            FailOn::HighSeverity => {
+                let high = response
+                    .vulnerabilities
+                    .iter()
+                    .filter(|vuln| matches!(vuln.severity.to_ascii_lowercase().as_str(), "high" | "critical"))
+                    .count();
+                (high > 0).then(|| format!("{} high or critical vulnerabilit(ies)", high))
+            }
+            FailOn::Crash => {
+                let summary = &response.summary;
+                (summary.crashes + summary.timeouts > 0).then(|| {
+                    format!("{} crashed and {} timed-out execution(s)", summary.crashes, summary.timeouts)
+                })
+            }
+        }
+    }
+}
+
+/// Exit code for a finished analysis: that of the first policy, in the order given,
+/// the response trips, or 0.
+pub(crate) fn fail_on_exit_code(response: &AnalyzeResponse, policies: &[FailOn]) -> i32 {
+    for policy in policies {
+        if let Some(reason) = policy.violation(response) {
+            eprintln!("✗ --fail-on {}: {} (exit code {})", policy.name(), reason, policy.exit_code());
+            return policy.exit_code();
+        }
+    }
+    0
+}
+
+/// Bridge options for the hang watchdog and overall deadline. Bridges beat several
+/// times per silence window so a single delayed heartbeat does not trigger a kill.
+/// Bridge process settings shared by `analyze` and `run-all`.
+#[derive(Debug, Clone)]
+pub struct BridgeSettings {
+    pub heartbeat_timeout: f64,
+    pub bridge_timeout: Option<f64>,
+    pub compress: bool,
+    pub transport: BridgeTransport,
+    /// Variables set for bridges and targets, on top of the minimal base environment.
+    pub env: Vec<(String, String)>,
+    /// Orchestrator variables bridges inherit (`*` wildcards allowed).
+    pub env_passthrough: Vec<String>,
+    /// Directory bridges and targets run in, instead of the orchestrator's own.
+    pub workdir: Option<PathBuf>,
+    /// Run each target from the directory holding its source file.
+    pub target_workdir: bool,
+    /// Schedule perturbation seed sent to bridges.
+    pub chaos_seed: Option<u64>,
+    /// Seconds bridges watch work an execution left running (0 disables).
+    pub observe: f64,
+    /// Report subprocesses an execution left behind.
+    pub detect_processes: bool,
+    /// Count daemon-equivalent threads as escapes, not only exit-blocking ones.
+    pub treat_daemon_as_escape: bool,
+    /// Milliseconds bridges wait after a call before looking for what it left behind.
+    pub settle: Option<u64>,
+    /// Thread re-checks within the settle period.
+    pub settle_checks: Option<u32>,
+    /// Fewest left-behind threads, goroutines or tasks that count as an escape.
+    pub thread_threshold: Option<u32>,
+    /// Whether bridges snapshot open file descriptors; their default when unset.
+    pub detect_fds: Option<bool>,
+    /// Whether bridges attach stacks to left-behind work; their default when unset.
+    pub capture_stacks: Option<bool>,
+    /// Serve requests from long-lived bridge processes.
+    pub daemon: bool,
+    /// Growth in MB over an input's repeats past which sustained growth is a leak.
+    pub memory_threshold: f64,
+    /// Race mode: run each input at least this many times and report disagreeing runs
+    /// of targets that touch shared state as potential data races.
+    pub race_runs: Option<usize>,
+    /// Container runtime bridges run under instead of the host.
+    pub sandbox: Option<String>,
+    /// Container images replacing the default ones, by language.
+    pub sandbox_images: Vec<(String, String)>,
+    /// Memory cap in MB for each bridge process.
+    pub bridge_memory: Option<u64>,
+    /// CPU time cap in seconds for each bridge process.
+    pub bridge_cpu: Option<u64>,
+    /// Cap on the processes a bridge runs at once.
+    pub bridge_processes: Option<u64>,
+    /// JSON array the instance of an instance-method target is constructed with.
+    pub constructor_args: Option<String>,
+}
+
+impl BridgeSettings {
+    /// Target and options for one request. When the bridge runs somewhere other than
+    /// the orchestrator's directory, a relative target file is made absolute first so
+    /// the bridge still finds it.
+    pub(crate) fn target_request(&self, target: &str) -> (String, HashMap<String, String>) {
+        let mut options = self.options();
+        if !self.target_workdir && self.workdir.is_none() {
+            return (target.to_string(), options);
+        }
+
+        let target = absolute_target(target);
+        let workdir = if self.target_workdir {
+            target_source_dir(&target).or_else(|| self.workdir.clone())
+        } else {
+            self.workdir.clone()
+        };
+        if let Some(workdir) = workdir {
+            options.insert(
+                option_keys::WORKDIR.to_string(),
+                workdir.to_string_lossy().to_string(),
+            );
+        }
+        (target, options)
+    }
+
+    /// Repeats per input: `repeat`, raised to the race mode's run count.
+    pub(crate) fn effective_repeat(&self, repeat: usize) -> usize {
+        self.race_runs.map_or(repeat, |runs| repeat.max(runs))
+    }
+
+    /// `memory_threshold` in bytes.
+    pub(crate) fn memory_threshold_bytes(&self) -> u64 {
+        (self.memory_threshold.max(0.0) * 1024.0 * 1024.0) as u64
+    }
+
+    /// Request options that carry these settings to `BridgeAnalyzer` and the bridges.
+    fn options(&self) -> HashMap<String, String> {
+        let mut options = HashMap::new();
+        if self.compress {
+            options.insert(option_keys::ACCEPT_ENCODING.to_string(), "gzip".to_string());
+        }
+        options.insert(
+            option_keys::TRANSPORT.to_string(),
+            self.transport.as_str().to_string(),
+        );
+        if !self.env.is_empty() {
+            let env: HashMap<&str, &str> = self
+                .env
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect();
+            options.insert(
+                option_keys::ENV.to_string(),
+                serde_json::to_string(&env).unwrap_or_default(),
+            );
+        }
+        if !self.env_passthrough.is_empty() {
+            options.insert(
+                option_keys::ENV_PASSTHROUGH.to_string(),
+                self.env_passthrough.join(","),
+            );
+        }
+        if let Some(seed) = self.chaos_seed {
+            options.insert(option_keys::CHAOS_SEED.to_string(), seed.to_string());
+        }
+        if self.observe > 0.0 {
+            options.insert(option_keys::OBSERVE_SECONDS.to_string(), self.observe.to_string());
+        }
+        if self.daemon {
+            options.insert(option_keys::DAEMON.to_string(), "true".to_string());
+        }
+        if self.detect_processes {
+            options.insert(option_keys::DETECT_PROCESSES.to_string(), "true".to_string());
+        }
+        if self.treat_daemon_as_escape {
+            options.insert(option_keys::TREAT_DAEMON_AS_ESCAPE.to_string(), "true".to_string());
+        }
+        let detection = [
+            (option_keys::SETTLE_MS, self.settle.map(|settle| settle.to_string())),
+            (option_keys::SETTLE_CHECKS, self.settle_checks.map(|checks| checks.to_string())),
+            (option_keys::THREAD_THRESHOLD, self.thread_threshold.map(|threshold| threshold.to_string())),
+            (option_keys::DETECT_FDS, self.detect_fds.map(|detect| detect.to_string())),
+            (option_keys::CAPTURE_STACKS, self.capture_stacks.map(|capture| capture.to_string())),
+        ];
+        for (key, value) in detection {
+            if let Some(value) = value {
+                options.insert(key.to_string(), value);
+            }
+        }
+        if let Some(sandbox) = &self.sandbox {
+            options.insert(option_keys::SANDBOX.to_string(), sandbox.clone());
+        }
+        if !self.sandbox_images.is_empty() {
+            let images: HashMap<&str, &str> = self
+                .sandbox_images
+                .iter()
+                .map(|(language, image)| (language.as_str(), image.as_str()))
+                .collect();
+            options.insert(
+                option_keys::SANDBOX_IMAGES.to_string(),
+                serde_json::to_string(&images).unwrap_or_default(),
+            );
+        }
+        let limits = [
+            (option_keys::BRIDGE_MEMORY_MB, self.bridge_memory),
+            (option_keys::BRIDGE_CPU_SECONDS, self.bridge_cpu),
+            (option_keys::BRIDGE_PROCESSES, self.bridge_processes),
+        ];
+        for (key, limit) in limits {
+            if let Some(limit) = limit {
+                options.insert(key.to_string(), limit.to_string());
+            }
+        }
+        if let Some(constructor_args) = &self.constructor_args {
+            options.insert(option_keys::CONSTRUCTOR_ARGS.to_string(), constructor_args.clone());
+        }
+        if let Some(bridge_timeout) = self.bridge_timeout {
+            options.insert(
+                option_keys::BRIDGE_TIMEOUT_SECONDS.to_string(),
+                bridge_timeout.to_string(),
+            );
+        }
+        options.insert(
+            option_keys::HEARTBEAT_TIMEOUT_SECONDS.to_string(),
+            self.heartbeat_timeout.to_string(),
+        );
+        if self.heartbeat_timeout > 0.0 {
+            let interval = (self.heartbeat_timeout / 3.0).min(5.0);
+            options.insert(
+                option_keys::HEARTBEAT_INTERVAL_SECONDS.to_string(),
+                format!("{:.3}", interval),
+            );
+        }
+        options
+    }
+}
+
+/// Rewrites `file.ext:function` targets whose file exists relative to the current
+/// directory to use an absolute path. Module, class and Rust path targets are unchanged.
+fn absolute_target(target: &str) -> String {
+    if target.contains("::") {
+        return target.to_string();
+    }
+    let Some((file_part, rest)) = target.split_once(':') else {
+        return target.to_string();
+    };
+    let file = Path::new(file_part);
+    if file.is_absolute() || !file.is_file() {
+        return target.to_string();
+    }
+    match std::env::current_dir() {
+        Ok(cwd) => format!("{}:{}", cwd.join(file).display(), rest),
+        Err(_) => target.to_string(),
+    }
+}
+
+/// Directory holding the source file of a `file.ext:function` target.
+fn target_source_dir(target: &str) -> Option<PathBuf> {
+    if target.contains("::") {
+        return None;
+    }
+    let file = Path::new(target.split(':').next()?);
+    if !file.is_file() {
+        return None;
+    }
+    file.parent().map(Path::to_path_buf)
+}
+
+/// Whether static analysis knows the language of `target`.
+fn has_static_analyzer(target: &str, language: Option<&str>) -> bool {
+    let lang = match language {
+        Some(lang) => normalize_language_filter(lang),
+        None => match detect_language_from_target(target) {
+            Ok(lang) => lang,
+            Err(_) => return false,
+        },
+    };
+    StaticAnalyzerFactory::create(&lang).is_some()
 }
 
-fn detect_language_from_target(target: &str) -> Result<String> {
+pub(crate) fn detect_language_from_target(target: &str) -> Result<String> {
     let target_head = target.split(':').next().unwrap_or(target);
 
     if target.contains("::") {
@@ -338,12 +1471,64 @@ fn detect_language_from_target(target: &str) -> Result<String> {
         Ok("go".to_string())
     } else if target_head.ends_with(".rs") {
         Ok("rust".to_string())
+    } else if [".so", ".dylib", ".dll"].iter().any(|ext| target_head.ends_with(ext)) {
+        // Prebuilt library exporting the Rust bridge's C ABI shim
+        Ok("rust".to_string())
     } else {
         anyhow::bail!("Unable to detect language from target: {}", target)
     }
 }
 
-fn resolve_source_file(target: &str) -> Result<String> {
+/// `generate` inputs for a `run-all` target following its signature, and their argument
+/// lists when it has several required parameters.
+fn generated_inputs(target: &str, language: &str, generate: usize) -> (Vec<String>, Vec<Vec<serde_json::Value>>) {
+    let source_file = resolve_source_file(target).ok();
+    if let Some(parameters) = input_gen::required_parameters(target, language, source_file.as_deref())
+        .filter(|parameters| parameters.len() > 1)
+    {
+        let args = input_gen::generate_arguments(&parameters, generate);
+        let kinds: Vec<&str> = parameters.iter().map(|parameter| parameter.kind.label()).collect();
+        info!(
+            "Generated {} argument lists ({}) for {}",
+            args.len(),
+            kinds.join(", "),
+            target
+        );
+        let inputs = args
+            .iter()
+            .map(|list| serde_json::to_string(list).unwrap_or_default())
+            .collect();
+        return (inputs, args);
+    }
+    let inputs = match input_gen::input_parameter(target, language, source_file.as_deref()) {
+        Some(parameter) => {
+            let inputs = input_gen::generate_typed(parameter.kind, generate);
+            info!(
+                "Generated {} {} inputs for `{}` of {}",
+                inputs.len(),
+                parameter.kind.label(),
+                parameter.name,
+                target
+            );
+            inputs
+        }
+        None => input_gen::generate_typed(ParamKind::Unknown, generate),
+    };
+    (inputs, Vec::new())
+}
+
+/// Crate and module segments of a `crate::module::function` target, without the
+/// function and, for `crate::module::Struct::method`, the type.
+fn rust_target_modules(target: &str) -> Vec<&str> {
+    let mut parts: Vec<&str> = target.split("::").collect();
+    parts.pop();
+    if callgraph::target_owner(target).is_some() {
+        parts.pop();
+    }
+    parts
+}
+
+pub(crate) fn resolve_source_file(target: &str) -> Result<String> {
     // Handle different target formats:
     // - path/to/file.py:function_name
     // - module.submodule:function_name
@@ -352,10 +1537,9 @@ fn resolve_source_file(target: &str) -> Result<String> {
     //   crate_name::module_name::function_name
     // Map module to common test paths (e.g., tests/rust/cases/module_name.rs).
     if target.contains("::") {
-        let parts: Vec<&str> = target.split("::").collect();
-        if parts.len() >= 2 {
-            let module_name = parts[parts.len() - 2];
-            let nested_module = parts[1..parts.len() - 1].join("/");
+        let parts = rust_target_modules(target);
+        if let Some(module_name) = parts.last().copied() {
+            let nested_module = parts[1..].join("/");
 
             let candidates = [
                 format!("tests/rust/cases/{}.rs", module_name),
@@ -434,26 +1618,49 @@ fn resolve_source_file(target: &str) -> Result<String> {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run_all_tests(
     test_dir: PathBuf,
     generate: usize,
+    corpus: Option<Inputs>,
+    repeat: usize,
+    timeout: f64,
     output_dir: PathBuf,
     language_filter: Option<String>,
     analysis_mode: AnalysisMode,
-) -> Result<()> {
+    bridge: BridgeSettings,
+    self_test: bool,
+    scheduler: SchedulerSettings,
+    report: &ReportSettings,
+    junit: Option<PathBuf>,
+    history: Option<PathBuf>,
+    metrics_addr: Option<SocketAddr>,
+    since: Option<String>,
+    filter: TargetFilter,
+    verify: bool,
+) -> Result<i32> {
     init_logging(true);
+    interrupt::finish_gracefully();
+    let started = std::time::Instant::now();
+    if let Some(addr) = metrics_addr {
+        metrics::serve(addr).await?;
+    }
 
     info!("Running all tests from: {:?}", test_dir);
     
     let registry = AnalyzerRegistry::initialize_all().await?;
     let analyzers = registry.list_analyzers();
-    let inputs = generate_inputs(generate);
-    let repeat = 1;
-    let timeout = 5.0;
+    let severity_rules = SeverityRules::configured(None)?;
     let normalized_filter = language_filter
         .as_deref()
         .map(normalize_language_filter);
+    let changes = since
+        .as_deref()
+        .map(|reference| ChangedLines::since(reference, &test_dir))
+        .transpose()?;
 
+    let mut suite_analyzers: HashMap<String, (&dyn Analyzer, AnalysisMode)> = HashMap::new();
+    let mut suites: Vec<(String, Vec<String>)> = Vec::new();
     for analyzer in analyzers {
         if let Some(filter) = normalized_filter.as_deref() {
             if analyzer.language() != filter {
@@ -461,68 +1668,624 @@ pub async fn run_all_tests(
             }
         }
 
-        if let Err(e) = analyzer.health_check().await {
-            warn!("Skipping {} analyzer (health check failed): {}", analyzer.language(), e);
-            continue;
-        }
+        let health = if self_test {
+            analyzer.deep_health_check().await
+        } else {
+            analyzer.health_check().await
+        };
+        // Only dynamic runs need a working bridge; static analysis still runs without one.
+        let suite_mode = match (health, analysis_mode) {
+            (Ok(_), mode) => mode,
+            (Err(e), AnalysisMode::Dynamic) => {
+                warn!("Skipping {} analyzer (health check failed): {:#}", analyzer.language(), e);
+                continue;
+            }
+            (Err(e), _) => {
+                warn!(
+                    "{} analyzer health check failed, running static analysis only: {:#}",
+                    analyzer.language(),
+                    e
+                );
+                AnalysisMode::Static
+            }
+        };
 
         info!("Discovering tests for {} analyzer", analyzer.language());
-        let targets = discover_targets_for_language(analyzer.language(), &test_dir)?;
+        let mut targets = discover_targets_for_language(analyzer.language(), &test_dir)?;
+        if let Some(changes) = &changes {
+            let discovered = targets.len();
+            targets.retain(|target| target_changed(changes, target, analyzer.language()));
+            info!(
+                "{} of {} {} target(s) changed since {}",
+                targets.len(),
+                discovered,
+                analyzer.language(),
+                changes.reference()
+            );
+        }
+        if !filter.is_empty() {
+            let discovered = targets.len();
+            targets.retain(|target| filter.selects(target));
+            info!(
+                "{} of {} {} target(s) selected by --filter/--exclude",
+                targets.len(),
+                discovered,
+                analyzer.language()
+            );
+        }
         if targets.is_empty() {
             warn!("No targets found for language: {}", analyzer.language());
             continue;
         }
+        suite_analyzers.insert(analyzer.language().to_string(), (analyzer, suite_mode));
+        suites.push((analyzer.language().to_string(), targets));
+    }
 
-        for target in targets {
-            info!("Analyzing target: {}", target);
-            let mut response: Option<AnalyzeResponse> = None;
+    // Targets an interrupted run with the same state file already analyzed are not
+    // analyzed again; their outcomes come from the file.
+    let store = scheduler
+        .state
+        .as_deref()
+        .map(JobStore::<TargetOutcome>::open)
+        .transpose()?;
+    let mut outcomes: Vec<TargetOutcome> = store.as_ref().map(JobStore::finished).unwrap_or_default();
+    let done: HashSet<(String, String)> = outcomes
+        .iter()
+        .map(|outcome| (outcome.language.clone(), outcome.target.clone()))
+        .collect();
+    if !done.is_empty() {
+        info!("Resuming: {} target(s) already analyzed", done.len());
+    }
+    let jobs = scheduler.plan(&suites, &done);
+    if let Some(store) = &store {
+        store.plan(&jobs)?;
+    }
 
-            if analysis_mode == AnalysisMode::Static || analysis_mode == AnalysisMode::Both {
-                match run_static_analysis(&target, Some(analyzer.language()), analysis_mode).await {
-                    Ok(static_response) => response = Some(static_response),
-                    Err(e) => warn!("Static analysis failed for {}: {}", target, e),
+    let progress = RunAllProgress {
+        total: jobs.iter().map(|job| job.targets.len()).sum(),
+        done: AtomicUsize::new(0),
+    };
+    let results = scheduler
+        .run(jobs, |job| {
+            let (bridge, output_dir, progress, store, corpus, severity_rules) =
+                (&bridge, &output_dir, &progress, &store, &corpus, &severity_rules);
+            let (analyzer, analysis_mode) = suite_analyzers[&job.language];
+            let retries = scheduler.retries;
+            async move {
+                // After Ctrl+C no batch starts; the state file keeps it for the next run.
+                if interrupt::interrupted() {
+                    return Ok(Vec::new());
+                }
+                if let Some(store) = store {
+                    store.start(&job);
                 }
+                let result = run_all_batch(
+                    analyzer,
+                    &job.targets,
+                    generate,
+                    corpus.as_ref(),
+                    repeat,
+                    timeout,
+                    analysis_mode,
+                    bridge,
+                    Some((output_dir.as_path(), report)),
+                    severity_rules,
+                    retries,
+                    verify,
+                    progress,
+                )
+                .await;
+                if let Some(store) = store.as_ref().filter(|_| !interrupt::interrupted()) {
+                    match &result {
+                        Ok(outcomes) => store.finish(&job, outcomes),
+                        Err(e) => store.fail(&job, e),
+                    }
+                }
+                result
             }
+        })
+        .await;
+    registry.shutdown().await;
 
-            if analysis_mode == AnalysisMode::Dynamic || analysis_mode == AnalysisMode::Both {
-                let session_id = Uuid::new_v4().to_string();
-                let request = AnalyzeRequest {
-                    session_id: session_id.clone(),
-                    target: target.clone(),
-                    inputs: inputs.clone(),
-                    repeat,
+    for result in results {
+        outcomes.extend(result?);
+    }
+    if let (Some(store), Some(path)) = (store, &scheduler.state) {
+        if store.close()? {
+            info!("All jobs done; removed job state {}", path.display());
+        }
+    }
+    outcomes.sort_by(|a, b| (&a.language, &a.target).cmp(&(&b.language, &b.target)));
+    print_run_all_summary(&outcomes, analysis_mode);
+    if verify {
+        let verdicts: Vec<Verdict> = outcomes.iter().map(TargetOutcome::verdict).collect();
+        verify::print_verification(&verdicts);
+    }
+
+    if let Some(path) = junit {
+        let cases: Vec<JunitCase> = outcomes.iter().map(TargetOutcome::junit_case).collect();
+        write_junit(&path, &cases)?;
+        println!("📁 JUnit report written to: {}", path.display());
+    }
+
+    if let Some(path) = history {
+        let session = HistorySession {
+            session_id: Uuid::new_v4().to_string(),
+            command: "run-all".to_string(),
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+        let targets: Vec<HistoryTarget> = outcomes.iter().map(TargetOutcome::history_target).collect();
+        history::record_session(&path, &session, &targets)
+            .with_context(|| format!("Failed to record history in {}", path.display()))?;
+        println!("📁 Session recorded in history: {}", path.display());
+    }
+
+    Ok(interrupt::exit_code(0))
+}
+
+/// `bench`: every labeled target of `test_dir`, analyzed once in each of `modes`, scored
+/// against its expected outcome and timed.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_benchmark(
+    test_dir: PathBuf,
+    generate: usize,
+    repeat: usize,
+    timeout: f64,
+    output_dir: PathBuf,
+    language_filter: Option<String>,
+    modes: &[AnalysisMode],
+    bridge: BridgeSettings,
+    batch_size: usize,
+    baseline: Option<PathBuf>,
+) -> Result<()> {
+    init_logging(true);
+    let baseline = baseline.as_deref().map(bench::load).transpose()?;
+
+    let registry = AnalyzerRegistry::initialize_all().await?;
+    let normalized_filter = language_filter.as_deref().map(normalize_language_filter);
+    let mut versions = BTreeMap::new();
+    let mut rows = Vec::new();
+    for analyzer in registry.list_analyzers() {
+        let language = analyzer.language();
+        if normalized_filter.as_deref().is_some_and(|filter| filter != language) {
+            continue;
+        }
+        if let Err(e) = analyzer.health_check().await {
+            warn!("Skipping {} analyzer (health check failed): {:#}", language, e);
+            continue;
+        }
+        let targets: Vec<String> = discover_targets_for_language(language, &test_dir)?
+            .into_iter()
+            .filter(|target| expected_escape(language, target).is_some())
+            .collect();
+        if targets.is_empty() {
+            warn!("No labeled targets found for language: {}", language);
+            continue;
+        }
+        let version = analyzer.info().await.map_or_else(|_| "unknown".to_string(), |info| info.version);
+        versions.insert(language.to_string(), version);
+
+        for &mode in modes {
+            let mode_label = if mode == AnalysisMode::Static { "static" } else { "dynamic" };
+            info!("Benchmarking {} {} target(s), {} analysis", targets.len(), language, mode_label);
+            let progress = RunAllProgress {
+                total: targets.len(),
+                done: AtomicUsize::new(0),
+            };
+            let started = std::time::Instant::now();
+            let mut outcomes = Vec::with_capacity(targets.len());
+            for chunk in targets.chunks(batch_size.max(1)) {
+                outcomes.extend(
+                    run_all_batch(
+                        analyzer,
+                        chunk,
+                        generate,
+                        None,
+                        repeat,
+                        timeout,
+                        mode,
+                        &bridge,
+                        None,
+                        &SeverityRules::default(),
+                        1,
+                        true,
+                        &progress,
+                    )
+                    .await?,
+                );
+            }
+            let verdicts: Vec<Verdict> = outcomes.iter().map(TargetOutcome::verdict).collect();
+            let times = outcomes.iter().filter(|outcome| outcome.analyzed).map(|outcome| outcome.time_ms).collect();
+            rows.push(BenchRow::new(language, mode_label, &verdicts, times, started.elapsed().as_millis() as u64));
+        }
+    }
+    registry.shutdown().await;
+
+    let report = BenchReport::new(versions, rows);
+    bench::print_report(&report, baseline.as_ref());
+    let (json, markdown) = bench::write_report(&report, baseline.as_ref(), &output_dir)?;
+    println!("\n📁 Benchmark written to: {} and {}", json.display(), markdown.display());
+    Ok(())
+}
+
+/// Which discovered targets `run-all` analyzes: `--filter` and `--exclude` globs, each
+/// matched against a target's file path, file name and function name and the whole
+/// target.
+#[derive(Debug, Clone, Default)]
+pub struct TargetFilter {
+    /// Targets matching none of these are skipped; empty keeps every target.
+    pub include: Vec<String>,
+    /// Targets matching any of these are skipped.
+    pub exclude: Vec<String>,
+}
+
+impl TargetFilter {
+    fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    fn selects(&self, target: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|pattern| target_matches(pattern, target)))
+            && !self.exclude.iter().any(|pattern| target_matches(pattern, target))
+    }
+}
+
+fn target_matches(pattern: &str, target: &str) -> bool {
+    // `crate::module::function` targets have no file part.
+    let path = (!target.contains("::"))
+        .then(|| target.rsplit_once(':').map(|(file, _)| file.trim_start_matches("./")))
+        .flatten();
+    let file_name = path.map(|path| path.rsplit(['/', '\\']).next().unwrap_or(path));
+    [Some(target), Some(callgraph::target_function(target)), path, file_name]
+        .into_iter()
+        .flatten()
+        .any(|candidate| suppress::glob_match(pattern, candidate))
+}
+
+/// Whether the function `target` names changed; targets whose source cannot be found
+/// are kept.
+fn target_changed(changes: &ChangedLines, target: &str, language: &str) -> bool {
+    let Some(source_file) = resolve_source_file(target).ok().filter(|file| Path::new(file).is_file()) else {
+        return true;
+    };
+    changes.touches_function(Path::new(&source_file), language, callgraph::target_function(target))
+}
+
+/// Whether the bridge itself failed a request, rather than the target.
+fn bridge_failed(response: &Result<AnalyzeResponse>) -> bool {
+    match response {
+        Ok(response) => response.error.as_ref().is_some_and(|error| error.kind.is_bridge_failure()),
+        Err(_) => true,
+    }
+}
+
+/// Completed targets out of all discovered ones, shared by concurrent batches.
+struct RunAllProgress {
+    total: usize,
+    done: AtomicUsize,
+}
+
+impl RunAllProgress {
+    fn finish(&self, outcome: &TargetOutcome) {
+        let done = self.done.fetch_add(1, Ordering::SeqCst) + 1;
+        let status = match (outcome.analyzed, outcome.escapes) {
+            (false, _) => "✗ no results".to_string(),
+            (true, 0) => "✓ no escapes".to_string(),
+            (true, escapes) => format!("🚨 {} escape(s)", escapes),
+        };
+        println!("[{}/{}] {} {}: {}", done, self.total, outcome.language, outcome.target, status);
+    }
+}
+
+/// What run-all found for one target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TargetOutcome {
+    language: String,
+    target: String,
+    analyzed: bool,
+    /// Escapes found by execution and by static analysis
+    escapes: usize,
+    /// The share of `escapes` found by static analysis
+    #[serde(default)]
+    static_escapes: usize,
+    vulnerabilities: usize,
+    crashes: usize,
+    time_ms: u64,
+    /// Vulnerabilities, static escapes, crash errors and bridge stderr, for JUnit failures
+    details: String,
+}
+
+impl TargetOutcome {
+    /// A target with no results yet.
+    fn new(language: &str, target: &str) -> Self {
+        Self {
+            language: language.to_string(),
+            target: target.to_string(),
+            analyzed: false,
+            escapes: 0,
+            static_escapes: 0,
+            vulnerabilities: 0,
+            crashes: 0,
+            time_ms: 0,
+            details: String::new(),
+        }
+    }
+
+    /// Takes the counts of the target's final response.
+    fn record(&mut self, response: &AnalyzeResponse) {
+        self.analyzed = true;
+        self.static_escapes = response.static_analysis.as_ref().map_or(0, |s| s.escapes.len());
+        self.escapes = response.summary.escapes + self.static_escapes;
+        self.vulnerabilities = response.vulnerabilities.len();
+        self.crashes = response.summary.crashes;
+        self.time_ms = response.results.iter().map(|result| result.execution_time_ms).sum::<u64>()
+            + response.static_analysis.as_ref().map_or(0, |s| s.analysis_time_ms);
+        self.details = outcome_details(response);
+    }
+
+    fn verdict(&self) -> Verdict {
+        Verdict {
+            language: self.language.clone(),
+            target: self.target.clone(),
+            expected: expected_escape(&self.language, &self.target),
+            detected: self.analyzed.then_some(self.escapes > 0),
+        }
+    }
+
+    fn history_target(&self) -> HistoryTarget {
+        HistoryTarget {
+            language: self.language.clone(),
+            target: self.target.clone(),
+            analyzed: self.analyzed,
+            escapes: self.escapes,
+            vulnerabilities: self.vulnerabilities,
+            crashes: self.crashes,
+            duration_ms: self.time_ms,
+        }
+    }
+
+    fn junit_case(&self) -> JunitCase {
+        let outcome = if !self.analyzed {
+            JunitOutcome::Error { message: "No analysis results produced".to_string() }
+        } else if self.escapes > 0 || self.crashes > 0 {
+            JunitOutcome::Failed {
+                message: format!("{} escape(s), {} crash(es)", self.escapes, self.crashes),
+                body: self.details.clone(),
+            }
+        } else {
+            JunitOutcome::Passed
+        };
+        JunitCase {
+            suite: self.language.clone(),
+            name: self.target.clone(),
+            time_ms: self.time_ms,
+            outcome,
+        }
+    }
+}
+
+/// Plain-text account of what a target's analysis found.
+fn outcome_details(response: &AnalyzeResponse) -> String {
+    let mut lines = Vec::new();
+    for vuln in &response.vulnerabilities {
+        lines.push(format!(
+            "[{}] {} (input {:?}): {}",
+            vuln.severity.to_uppercase(),
+            vuln.vulnerability_type,
+            vuln.input,
+            vuln.description
+        ));
+    }
+    if let Some(static_result) = &response.static_analysis {
+        for escape in &static_result.escapes {
+            lines.push(format!(
+                "[STATIC] {:?} of `{}` at {}:{}: {}",
+                escape.escape_type, escape.variable_name, escape.location.file, escape.location.line, escape.reason
+            ));
+        }
+    }
+    for result in response.results.iter().filter(|result| result.crashed) {
+        if let Some(error) = &result.error {
+            lines.push(format!("[CRASH] input {:?}: {}", result.input_data, error));
+        }
+    }
+    let stderr: Vec<&str> = response
+        .results
+        .iter()
+        .map(|result| result.stderr.trim())
+        .chain(response.bridge_stderr.as_deref().map(str::trim))
+        .filter(|stderr| !stderr.is_empty())
+        .collect();
+    if !stderr.is_empty() {
+        lines.push(String::new());
+        lines.push("--- stderr ---".to_string());
+        lines.extend(stderr.iter().map(|stderr| stderr.to_string()));
+    }
+    lines.join("\n")
+}
+
+/// Static analysis of each target in `chunk`, one bridge process for their dynamic
+/// analysis, and a report per target when `reports` names where.
+#[allow(clippy::too_many_arguments)]
+async fn run_all_batch(
+    analyzer: &dyn Analyzer,
+    chunk: &[String],
+    generate: usize,
+    corpus: Option<&Inputs>,
+    repeat: usize,
+    timeout: f64,
+    analysis_mode: AnalysisMode,
+    bridge: &BridgeSettings,
+    reports: Option<(&Path, &ReportSettings)>,
+    severity_rules: &SeverityRules,
+    retries: usize,
+    verify: bool,
+    progress: &RunAllProgress,
+) -> Result<Vec<TargetOutcome>> {
+    let mut responses: Vec<Option<AnalyzeResponse>> = Vec::with_capacity(chunk.len());
+    for target in chunk {
+        info!("Analyzing target: {}", target);
+        let mut response = None;
+        if analysis_mode == AnalysisMode::Static || analysis_mode == AnalysisMode::Both {
+            match run_static_analysis(target, Some(analyzer.language()), analysis_mode).await {
+                Ok(mut static_response) => {
+                    severity::score(&mut static_response, None, severity_rules);
+                    response = Some(static_response);
+                }
+                Err(e) => warn!("Static analysis failed for {}: {}", target, e),
+            }
+        }
+        responses.push(response);
+    }
+
+    if analysis_mode == AnalysisMode::Dynamic || analysis_mode == AnalysisMode::Both {
+        // One bridge process serves the whole chunk; responses come back per target.
+        let requests: Vec<AnalyzeRequest> = chunk
+            .iter()
+            .map(|target| {
+                let (inputs, input_encodings, input_args) = match corpus {
+                    Some(corpus) => (corpus.values.clone(), corpus.request_encodings(), corpus.args.clone()),
+                    None => {
+                        let (inputs, input_args) = generated_inputs(target, analyzer.language(), generate);
+                        (inputs, Vec::new(), input_args)
+                    }
+                };
+                let (target, options) = bridge.target_request(target);
+                AnalyzeRequest {
+                    protocol_version: PROTOCOL_VERSION,
+                    session_id: Uuid::new_v4().to_string(),
+                    target,
+                    inputs,
+                    input_encodings,
+                    input_args,
+                    repeat: bridge.effective_repeat(repeat),
                     timeout_seconds: timeout,
-                    options: HashMap::new(),
+                    options,
                     analysis_mode,
-                };
-
-                match analyzer.analyze(request).await {
-                    Ok(dynamic_response) => {
-                        if let Some(ref mut resp) = response {
-                            merge_dynamic_into_response(resp, dynamic_response);
-                        } else {
-                            response = Some(dynamic_response);
-                        }
+                }
+            })
+            .collect();
+
+        let mut dynamic_responses = analyzer.analyze_batch(requests.clone()).await;
+        for attempt in 1..=retries {
+            let failed: Vec<usize> = (0..dynamic_responses.len())
+                .filter(|&index| bridge_failed(&dynamic_responses[index]))
+                .collect();
+            if failed.is_empty() {
+                break;
+            }
+            warn!(
+                "Retrying {} {} target(s) whose bridge crashed or hung (attempt {}/{})",
+                failed.len(),
+                analyzer.language(),
+                attempt,
+                retries
+            );
+            let retried = analyzer
+                .analyze_batch(failed.iter().map(|&index| requests[index].clone()).collect())
+                .await;
+            for (index, response) in failed.into_iter().zip(retried) {
+                dynamic_responses[index] = response;
+            }
+        }
+        for ((target, response), dynamic) in chunk.iter().zip(responses.iter_mut()).zip(dynamic_responses) {
+            match dynamic {
+                Ok(mut dynamic_response) => {
+                    check_chaos_support(bridge, &dynamic_response);
+                    suppress_dynamic(target, &mut dynamic_response);
+                    flag_nondeterminism(&mut dynamic_response);
+                    let static_result = response.as_ref().and_then(|r| r.static_analysis.as_ref());
+                    if bridge.race_runs.is_some() {
+                        let shared = shared_state(target, Some(analyzer.language()), static_result).await;
+                        flag_data_races(&mut dynamic_response, &shared);
                     }
-                    Err(e) => {
-                        warn!("Dynamic analysis failed for {}: {}", target, e);
-                        continue;
+                    flag_memory_growth(&mut dynamic_response, bridge.memory_threshold_bytes());
+                    severity::score(&mut dynamic_response, static_result, severity_rules);
+                    if let Some(resp) = response {
+                        merge_dynamic_into_response(resp, dynamic_response);
+                    } else {
+                        *response = Some(dynamic_response);
                     }
                 }
+                Err(e) => {
+                    warn!("Dynamic analysis failed for {}: {}", target, e);
+                    metrics::record_bridge_failure(analyzer.language());
+                    *response = None;
+                }
             }
+        }
+    }
 
-            match response {
-                Some(ref mut final_response) => {
-                    apply_benchmark_annotation_override(final_response, analyzer.language(), &target);
-                    let report_gen = ReportGenerator::new(output_dir.clone());
-                    report_gen.generate(final_response, &target).await?;
+    let mut outcomes = Vec::with_capacity(chunk.len());
+    for (target, response) in chunk.iter().zip(responses) {
+        let mut outcome = TargetOutcome::new(analyzer.language(), target);
+        match response {
+            Some(mut final_response) => {
+                // Verification measures what the analyzers found, so expectations must
+                // not overwrite it.
+                if !verify {
+                    apply_benchmark_annotation_override(&mut final_response, analyzer.language(), target);
+                }
+                if let Some((output_dir, report)) = reports {
+                    let report_gen = ReportGenerator::new(output_dir.to_path_buf()).with_settings(report);
+                    let session_dir = report_gen.generate(&final_response, target).await?;
+                    println!("📁 Reports generated in: {}", session_dir.display());
                 }
-                None => warn!("No analysis results produced for {}", target),
+                outcome.record(&final_response);
+                metrics::record_analysis(&final_response, Duration::from_millis(outcome.time_ms));
             }
+            None => warn!("No analysis results produced for {}", target),
         }
+        progress.finish(&outcome);
+        outcomes.push(outcome);
     }
+    Ok(outcomes)
+}
 
-    Ok(())
+fn print_run_all_summary(outcomes: &[TargetOutcome], analysis_mode: AnalysisMode) {
+    let mut by_language: BTreeMap<&str, (usize, usize, usize, usize)> = BTreeMap::new();
+    for outcome in outcomes {
+        let entry = by_language.entry(&outcome.language).or_default();
+        entry.0 += 1;
+        if !outcome.analyzed {
+            entry.1 += 1;
+        } else if outcome.escapes > 0 {
+            entry.2 += 1;
+        }
+        entry.3 += outcome.static_escapes;
+    }
+    // Static escape counts are only shown when the run did static analysis.
+    let with_static = analysis_mode != AnalysisMode::Dynamic;
+    let static_column = |count: usize, width: usize| {
+        if with_static {
+            format!(", {:>width$} static escape(s)", count)
+        } else {
+            String::new()
+        }
+    };
+
+    println!("\n╔════════════════════════════════════════════╗");
+    println!("║              Run-All Summary               ║");
+    println!("╚════════════════════════════════════════════╝\n");
+    for (language, (targets, failed, escaping, static_escapes)) in &by_language {
+        println!(
+            "{:<12} {:>4} target(s), {:>4} with escapes, {:>4} without results{}",
+            language,
+            targets,
+            escaping,
+            failed,
+            static_column(*static_escapes, 4)
+        );
+    }
+    let vulnerabilities: usize = outcomes.iter().map(|outcome| outcome.vulnerabilities).sum();
+    println!(
+        "\nTotal: {} target(s), {} with escapes, {} without results, {} vulnerabilit(ies){}",
+        outcomes.len(),
+        by_language.values().map(|counts| counts.2).sum::<usize>(),
+        by_language.values().map(|counts| counts.1).sum::<usize>(),
+        vulnerabilities,
+        static_column(by_language.values().map(|counts| counts.3).sum::<usize>(), 0)
+    );
 }
 
 fn apply_benchmark_annotation_override(response: &mut AnalyzeResponse, language: &str, target: &str) {
@@ -538,18 +2301,42 @@ fn apply_benchmark_annotation_override(response: &mut AnalyzeResponse, language:
     response.summary.genuine_escapes = response.summary.escapes;
 }
 
+/// Outcome a test case expects: from its name, else from an `ESCAPE:`/`SAFE:` comment.
+fn expected_escape(language: &str, target: &str) -> Option<bool> {
+    verify::expected_from_name(target).or_else(|| benchmark_expected_escape(language, target))
+}
+
+/// The first `ESCAPE:` or `SAFE:` comment of the target function, or of the comment block
+/// right above it, so that files mixing both kinds of case label each function on its
+/// own. Files whose functions cannot be located are searched whole.
 fn benchmark_expected_escape(language: &str, target: &str) -> Option<bool> {
     let source_path = benchmark_source_path(language, target)?;
     let text = fs::read_to_string(source_path).ok()?;
+    let lines: Vec<&str> = text.lines().collect();
+
+    let function = callgraph::target_function(target);
+    let span = callgraph::function_spans(language, &text)
+        .into_iter()
+        .find(|(name, _, _)| name == function);
+    let scope: &[&str] = match span {
+        Some((_, start, end)) => {
+            let is_comment = |line: &str| ["//", "#", "/*", "*"].iter().any(|mark| line.trim_start().starts_with(mark));
+            let first = (1..start).rev().take_while(|&line| is_comment(lines[line - 1])).last().unwrap_or(start);
+            &lines[first - 1..end.min(lines.len())]
+        }
+        None => &lines,
+    };
 
-    if text.contains("SAFE:") {
-        return Some(false);
-    }
-    if text.contains("ESCAPE:") {
-        return Some(true);
-    }
-
-    None
+    scope.iter().find_map(|line| {
+        let safe = line.find("SAFE:");
+        let escape = line.find("ESCAPE:");
+        match (safe, escape) {
+            (Some(safe), Some(escape)) => Some(escape < safe),
+            (Some(_), None) => Some(false),
+            (None, Some(_)) => Some(true),
+            (None, None) => None,
+        }
+    })
 }
 
 fn benchmark_source_path(language: &str, target: &str) -> Option<PathBuf> {
@@ -603,9 +2390,9 @@ fn benchmark_source_path(language: &str, target: &str) -> Option<PathBuf> {
             }
 
             if target.contains("::") {
-                let rust_parts: Vec<&str> = target.split("::").collect();
-                if rust_parts.len() >= 3 {
-                    let module_name = rust_parts[rust_parts.len() - 2];
+                let rust_parts = rust_target_modules(target);
+                if rust_parts.len() >= 2 {
+                    let module_name = rust_parts[rust_parts.len() - 1];
                     let candidates = [
                         PathBuf::from(format!("tests/rust/cases/{}.rs", module_name)),
                         PathBuf::from(format!("tests/rust/{}.rs", module_name)),
@@ -671,6 +2458,29 @@ pub async fn list_analyzers(detailed: bool) -> Result<()> {
     Ok(())
 }
 
+/// Prints the static analysis rule catalog, or the rules of one language's analyzer.
+pub fn list_rules(language_filter: Option<String>, output_format: OutputFormat) -> Result<()> {
+    let language = language_filter.as_deref().map(normalize_language_filter);
+    if let Some(language) = &language {
+        if StaticAnalyzerFactory::create(language).is_none() {
+            anyhow::bail!("No static analyzer for language: {}", language);
+        }
+    }
+    let catalog = rules::for_language(language.as_deref());
+    if output_format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&catalog)?);
+        return Ok(());
+    }
+    for rule in catalog {
+        println!("{}  {} ({})", rule.id, rule.name, rule.language.unwrap_or("all languages"));
+        println!("   {}", rule.title);
+        println!("   {}", rule.description);
+        println!("   Fix: {}", rule.remediation);
+        println!();
+    }
+    Ok(())
+}
+
 pub fn clear_logs(output_dir: PathBuf, archive_csv: Option<PathBuf>) -> Result<()> {
     if !output_dir.exists() {
         return Ok(());
@@ -719,7 +2529,7 @@ fn archive_results(output_dir: &PathBuf, archive_path: &PathBuf) -> Result<()> {
         .with_context(|| format!("Failed to open archive file: {}", archive_path.display()))?;
 
     if file.metadata()?.len() == 0 {
-        file.write_all(b"session_path,input,success,crashed,escape_detected,escape_summary,error,execution_time_ms\n")?;
+        file.write_all(b"session_path,input,success,crashed,escape_detected,escape_summary,error_kind,error,execution_time_ms\n")?;
     }
 
     let mut csv_files = collect_files_recursive(output_dir, "csv")?;
@@ -794,11 +2604,20 @@ fn print_summary(response: &AnalyzeResponse) {
         if summary.heap_escapes > 0 {
             println!("  💾 Heap Escapes: {}", summary.heap_escapes);
         }
+        if summary.spawn_escapes > 0 {
+            println!("  🔁 Spawn Escapes: {}", summary.spawn_escapes);
+        }
+        if summary.exit_escapes > 0 {
+            println!("  🚪 Exit Escapes: {}", summary.exit_escapes);
+        }
         
         println!("\nConfidence Breakdown:");
         println!("  High: {}", summary.high_confidence);
         println!("  Medium: {}", summary.medium_confidence);
         println!("  Low: {}", summary.low_confidence);
+        if summary.suppressed > 0 {
+            println!("  Suppressed: {}", summary.suppressed);
+        }
         
         if !static_result.warnings.is_empty() {
             println!("\n⚠️  Warnings:");
@@ -818,7 +2637,29 @@ fn print_summary(response: &AnalyzeResponse) {
         println!("Timeouts: {} ⏱", summary.timeouts);
         println!("Escapes Detected: {} 🚨", summary.escapes);
         println!("Genuine Escapes: {}", summary.genuine_escapes);
+        if summary.suppressed > 0 {
+            println!("Suppressed Escapes: {}", summary.suppressed);
+        }
         println!("Crash Rate: {:.1}%", summary.crash_rate * 100.0);
+        if let Some(summary) = escaped_work_summary(response) {
+            println!("Escaped Work: {}", summary);
+        }
+        if let Some(summary) = leaked_descriptor_summary(response) {
+            println!("Leaked File Descriptors: {}", summary);
+        }
+        if let Some(summary) = leaked_timer_summary(response) {
+            println!("Leaked Timers: {}", summary);
+        }
+        if let Some(summary) = memory_growth_summary(response) {
+            println!("Memory Growth: {}", summary);
+        }
+        if let Some(seed) = response.chaos_seed {
+            println!("Schedule Perturbation Seed: {} (replay with --chaos-seed {})", seed, seed);
+        }
+        let not_executed = response.results.iter().filter(|r| r.not_executed).count();
+        if not_executed > 0 {
+            println!("Not Executed: {} ⏸ (bridge failed mid-run)", not_executed);
+        }
         
         if !response.vulnerabilities.is_empty() {
             println!("\n⚠️  VULNERABILITIES FOUND:");
@@ -831,6 +2672,18 @@ fn print_summary(response: &AnalyzeResponse) {
 
         print_error_diagnostics(&response.results);
     }
+
+    if let Some(correlation) = spawn_correlation(response) {
+        let mismatches = correlation.mismatches().count();
+        if mismatches > 0 {
+            println!(
+                "\n⚠️  Spawn count mismatch: static analysis expects {} thread(s)/task(s) per call, {} of {} execution(s) left a different number running",
+                correlation.expected,
+                mismatches,
+                correlation.observed.len()
+            );
+        }
+    }
     
     println!();
 }
@@ -838,7 +2691,7 @@ fn print_summary(response: &AnalyzeResponse) {
 fn print_error_diagnostics(results: &[ExecutionResult]) {
     let error_results: Vec<&ExecutionResult> = results
         .iter()
-        .filter(|r| r.crashed || !r.error.trim().is_empty())
+        .filter(|r| r.crashed || r.error.is_some())
         .collect();
 
     if error_results.is_empty() {
@@ -883,76 +2736,56 @@ fn print_error_diagnostics(results: &[ExecutionResult]) {
 }
 
 fn diagnose_runtime_error(result: &ExecutionResult) -> RuntimeDiagnosis {
-    let raw = if result.error.trim().is_empty() {
-        if result.crashed {
-            "Execution failed without an error message"
-        } else {
-            ""
-        }
-    } else {
-        result.error.trim()
+    let (kind, message) = match &result.error {
+        Some(error) => (error.kind, error.message.clone()),
+        None => (
+            AnalysisErrorKind::RuntimeCrash,
+            "Execution failed without an error message".to_string(),
+        ),
     };
 
-    let lower = raw.to_lowercase();
-
-    let (category, hint) = if lower.contains("timeout") || lower.contains("timed out") || lower.contains("exceeded") {
-        (
-            "Timeout",
-            "Inspect blocking operations and missing joins/awaits before increasing timeout.",
-        )
-    } else if lower.contains("target resolution")
-        || lower.contains("not found")
-        || lower.contains("failed to load")
-        || lower.contains("invalid target")
-        || lower.contains("nosuchmethod")
-        || lower.contains("module not found")
-    {
-        (
-            "Target Resolution",
-            "Verify the target signature/path and language selection.",
-        )
-    } else if lower.contains("protocol/input")
-        || lower.contains("json")
-        || lower.contains("parse")
-        || lower.contains("stdin")
-        || lower.contains("protocol")
-    {
-        (
-            "Protocol/Input",
-            "Validate bridge JSON format and ensure no protocol fields changed.",
-        )
-    } else if lower.contains("environment")
-        || lower.contains("permission denied")
-        || lower.contains("not available")
-        || lower.contains("not found in path")
-        || lower.contains("command not found")
-        || lower.contains("missing tools")
-    {
-        (
-            "Environment",
-            "Check toolchain/runtime availability and PATH configuration.",
-        )
-    } else if lower.contains("runtime crash")
-        || result.crashed
-        || lower.contains("panic")
-        || lower.contains("exception")
-        || lower.contains("traceback")
-        || lower.contains("segmentation")
-    {
-        (
-            "Runtime Crash",
-            "Re-run with --verbose and inspect stack traces from the target function.",
-        )
-    } else {
-        (
-            "Unknown",
-            "Re-run with --verbose and inspect bridge stderr for additional diagnostics.",
-        )
+    let hint = match kind {
+        AnalysisErrorKind::BridgeHang => {
+            "The target likely deadlocked the bridge process; look for blocking joins or locks held across calls."
+        }
+        AnalysisErrorKind::Timeout => {
+            "Inspect blocking operations and missing joins/awaits before increasing timeout."
+        }
+        AnalysisErrorKind::BridgeTimeout => {
+            "The bridge ran past its overall deadline; look for blocking calls or escaped non-daemon threads keeping the process alive, or raise --bridge-timeout."
+        }
+        AnalysisErrorKind::TargetNotFound => "Verify the target signature/path and language selection.",
+        AnalysisErrorKind::LoadFailed => {
+            "The target exists but could not be loaded; check imports, build errors and dependencies."
+        }
+        AnalysisErrorKind::VersionMismatch => "Rebuild the bridge so it matches this orchestrator.",
+        AnalysisErrorKind::UnsupportedFeature => {
+            "The bridge cannot analyze this kind of target; try another analysis mode."
+        }
+        AnalysisErrorKind::Protocol => "Validate bridge JSON format and ensure no protocol fields changed.",
+        AnalysisErrorKind::Environment => "Check toolchain/runtime availability and PATH configuration.",
+        AnalysisErrorKind::BridgeCrash => {
+            "The bridge process failed; inspect bridge_stderr.log in the session report."
+        }
+        AnalysisErrorKind::RuntimeCrash if message.starts_with("process_exit") => {
+            "The target exited the process; keep exit calls out of library code so pending joins and shutdowns can run."
+        }
+        AnalysisErrorKind::RuntimeCrash => {
+            "Re-run with --verbose and inspect stack traces from the target function."
+        }
+        AnalysisErrorKind::OracleFailed => {
+            "Compare the output with the oracle's expectation; unjoined work often leaves results partial or empty."
+        }
+        AnalysisErrorKind::Interrupted => "The run was interrupted; analyze the target again for complete results.",
+        AnalysisErrorKind::ResourceLimit => "The target used more than the --bridge-* caps allow; look for runaway loops, or raise the cap.",
+        AnalysisErrorKind::Unknown => {
+            "Re-run with --verbose and inspect bridge stderr for additional diagnostics."
+        }
     };
 
     RuntimeDiagnosis {
-        category,
-        message: first_nonempty_line(raw),
+        category: kind.label(),
+        message: first_nonempty_line(&message),
         hint,
     }
 }
@@ -967,7 +2800,7 @@ fn first_nonempty_line(message: &str) -> String {
 }
 
 fn truncate_for_console(value: &str, max_chars: usize) -> String {
-    let normalized = value.replace('\n', " ").replace('\r', " ").trim().to_string();
+    let normalized = value.replace(['\n', '\r'], " ").trim().to_string();
     if normalized.chars().count() <= max_chars {
         return normalized;
     }
@@ -984,22 +2817,21 @@ struct RuntimeDiagnosis {
     hint: &'static str,
 }
 
-fn init_logging(verbose: bool) {
-    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-    
-    let filter = if verbose {
-        "graphene_ha=debug"
-    } else {
-        "graphene_ha=info"
-    };
-    
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(filter))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+pub(crate) fn init_logging(verbose: bool) {
+    logging::init(Console::Stdout, verbose);
+}
+
+/// Like `init_logging`, but writes to stderr so stdout carries only command output.
+pub(crate) fn init_stderr_logging(verbose: bool) {
+    logging::init(Console::Stderr, verbose);
+}
+
+/// Only warnings and errors, on stderr, for `--quiet`.
+pub(crate) fn init_quiet_logging() {
+    logging::init_quiet();
 }
 
-fn normalize_language_filter(filter: &str) -> String {
+pub(crate) fn normalize_language_filter(filter: &str) -> String {
     match filter {
         "js" | "node" | "nodejs" | "javascript" => "javascript",
         "py" | "python" => "python",
@@ -1011,67 +2843,6 @@ fn normalize_language_filter(filter: &str) -> String {
     .to_string()
 }
 
-fn generate_inputs(count: usize) -> Vec<String> {
-    let mut inputs = vec![
-        "".to_string(),
-        "0".to_string(),
-        "-1".to_string(),
-        "1".to_string(),
-        "true".to_string(),
-        "false".to_string(),
-        "null".to_string(),
-        "undefined".to_string(),
-        "hello".to_string(),
-        "\\x00".to_string(),
-        "\\n".to_string(),
-        "\\t".to_string(),
-        "'".to_string(),
-        "\"".to_string(),
-        "()".to_string(),
-        "[]".to_string(),
-        "{}".to_string(),
-        "../".to_string(),
-        "..\\".to_string(),
-        "${HOME}".to_string(),
-        "$(whoami)".to_string(),
-        "{{7*7}}".to_string(),
-        "%s".to_string(),
-        "error".to_string(),
-        "exception".to_string(),
-        "async".to_string(),
-        "await".to_string(),
-        "timeout".to_string(),
-        "deadlock".to_string(),
-        "race".to_string(),
-        "concurrent".to_string(),
-        "<script>alert(1)</script>".to_string(),
-        "'; DROP TABLE; --".to_string(),
-        "../../../etc/passwd".to_string(),
-        "\\x1b[31m".to_string(),
-        "\\u0000".to_string(),
-    ];
-
-    inputs.push("A".repeat(1024));
-    inputs.push("1".repeat(100));
-    inputs.push("test".repeat(50));
-    inputs.push(" ".repeat(1000));
-    inputs.push("\\n".repeat(100));
-
-    if count == 0 {
-        return vec![String::new()];
-    }
-
-    if inputs.len() >= count {
-        return inputs.into_iter().take(count).collect();
-    }
-
-    while inputs.len() < count {
-        inputs.push(format!("input_{}", inputs.len() + 1));
-    }
-
-    inputs
-}
-
 fn discover_targets_for_language(language: &str, test_dir: &Path) -> Result<Vec<String>> {
     match language {
         "python" => discover_python_targets(test_dir),
@@ -1111,7 +2882,10 @@ fn collect_files_recursive(dir: &Path, ext: &str) -> Result<Vec<PathBuf>> {
     for entry in fs::read_dir(dir).with_context(|| format!("Failed to read dir: {}", dir.display()))? {
         let entry = entry?;
         let path = entry.path();
-        if path.is_dir() {
+        if entry.file_type()?.is_symlink() {
+            // `latest` links to a session that is also reached directly.
+            continue;
+        } else if path.is_dir() {
             files.extend(collect_files_recursive(&path, ext)?);
         } else if path
             .extension()
@@ -1635,3 +3409,73 @@ fn is_valid_identifier(name: &str) -> bool {
 
     chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(summary: serde_json::Value, severities: &[&str]) -> AnalyzeResponse {
+        let mut counts = json!({
+            "total_tests": 4, "successes": 4, "crashes": 0, "timeouts": 0,
+            "escapes": 0, "genuine_escapes": 0, "crash_rate": 0.0,
+        });
+        for (key, value) in summary.as_object().unwrap() {
+            counts[key] = value.clone();
+        }
+        let vulnerabilities: Vec<_> = severities
+            .iter()
+            .map(|severity| {
+                json!({
+                    "input": "x", "vulnerability_type": "escape", "severity": severity,
+                    "description": "", "escape_details": {},
+                })
+            })
+            .collect();
+        serde_json::from_value(json!({
+            "language": "python", "analyzer_version": "1", "results": [],
+            "vulnerabilities": vulnerabilities, "summary": counts,
+        }))
+        .unwrap()
+    }
+
+    const ALL: [FailOn; 4] = [FailOn::AnyEscape, FailOn::GenuineEscape, FailOn::HighSeverity, FailOn::Crash];
+
+    #[test]
+    fn clean_run_exits_zero() {
+        assert_eq!(fail_on_exit_code(&response(json!({}), &["low"]), &ALL), 0);
+        assert_eq!(fail_on_exit_code(&response(json!({ "escapes": 3, "crashes": 1 }), &["high"]), &[]), 0);
+    }
+
+    #[test]
+    fn each_policy_has_its_own_exit_code() {
+        let cases = [
+            (FailOn::AnyEscape, response(json!({ "escapes": 1 }), &[]), 2),
+            (FailOn::GenuineEscape, response(json!({ "genuine_escapes": 1 }), &[]), 3),
+            (FailOn::HighSeverity, response(json!({}), &["Critical"]), 4),
+            (FailOn::Crash, response(json!({ "crashes": 1 }), &[]), 5),
+        ];
+        for (policy, response, code) in cases {
+            assert_eq!(fail_on_exit_code(&response, &[policy]), code, "{:?}", policy);
+        }
+    }
+
+    #[test]
+    fn timeouts_count_as_crashes() {
+        assert_eq!(fail_on_exit_code(&response(json!({ "timeouts": 1 }), &[]), &[FailOn::Crash]), 5);
+    }
+
+    #[test]
+    fn untripped_policies_do_not_fail() {
+        let escaped = response(json!({ "escapes": 2 }), &["medium"]);
+        assert_eq!(fail_on_exit_code(&escaped, &[FailOn::GenuineEscape, FailOn::HighSeverity, FailOn::Crash]), 0);
+    }
+
+    #[test]
+    fn first_tripped_policy_in_the_order_given_wins() {
+        let everything = response(json!({ "escapes": 2, "genuine_escapes": 1, "crashes": 1 }), &["high"]);
+        assert_eq!(fail_on_exit_code(&everything, &ALL), 2);
+        assert_eq!(fail_on_exit_code(&everything, &[FailOn::Crash, FailOn::AnyEscape]), 5);
+        assert_eq!(fail_on_exit_code(&everything, &[FailOn::GenuineEscape, FailOn::HighSeverity]), 3);
+    }
+}
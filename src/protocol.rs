@@ -1,11 +1,12 @@
 /// Common protocol for communication between orchestrator and language analyzers
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 
 /// Analysis mode for the request
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub enum AnalysisMode {
     /// Dynamic runtime analysis (default)
+    #[default]
     #[serde(rename = "Dynamic", alias = "dynamic")]
     Dynamic,
     /// Static compile-time analysis
@@ -16,18 +17,190 @@ pub enum AnalysisMode {
     Both,
 }
 
-impl Default for AnalysisMode {
-    fn default() -> Self {
-        AnalysisMode::Dynamic
+/// Well-known keys for `AnalyzeRequest::options`.
+pub mod option_keys {
+    /// Seconds between heartbeat lines a bridge writes to stderr.
+    pub const HEARTBEAT_INTERVAL_SECONDS: &str = "heartbeat_interval_seconds";
+    /// Seconds of heartbeat silence after which the orchestrator kills the bridge.
+    pub const HEARTBEAT_TIMEOUT_SECONDS: &str = "heartbeat_timeout_seconds";
+    /// Overall deadline for one bridge invocation; derived from the request when absent.
+    pub const BRIDGE_TIMEOUT_SECONDS: &str = "bridge_timeout_seconds";
+    /// Comma-separated response encodings the orchestrator accepts (`gzip`).
+    /// Bridges that do not understand it answer with plain JSON.
+    pub const ACCEPT_ENCODING: &str = "accept_encoding";
+    /// `stdio` (default) or `unix_socket`; selects how the orchestrator talks to the bridge.
+    pub const TRANSPORT: &str = "transport";
+    /// JSON object of environment variables set for the bridge and its target.
+    pub const ENV: &str = "env";
+    /// Comma-separated names (`*` wildcards allowed) of orchestrator environment
+    /// variables the bridge inherits on top of the minimal base set.
+    pub const ENV_PASSTHROUGH: &str = "env_passthrough";
+    /// Directory the bridge and its target run in; the orchestrator's own when absent.
+    pub const WORKDIR: &str = "workdir";
+    /// Seed for schedule perturbation: bridges that support it inject small random delays
+    /// around spawn, lock and channel operations and echo the seed as `chaos_seed`.
+    pub const CHAOS_SEED: &str = "chaos_seed";
+    /// Seconds a bridge keeps watching the threads, tasks and goroutines an execution
+    /// left running, reporting `outlived_ms` and `still_running` for each.
+    pub const OBSERVE_SECONDS: &str = "observe_seconds";
+    /// `true` asks bridges that support it to snapshot descendant processes around each
+    /// execution and report new ones under `escape_details.processes`.
+    pub const DETECT_PROCESSES: &str = "detect_processes";
+    /// `true` makes bridges that classify left-behind threads count daemon-equivalent
+    /// ones (idle background workers) as escapes; by default only threads that block
+    /// process exit do, and daemon ones are listed with `is_daemon` set.
+    pub const TREAT_DAEMON_AS_ESCAPE: &str = "treat_daemon_as_escape";
+
+    // Detection options. Every built-in bridge honors them, and answers with a
+    // `warnings` entry for an option it does not know or cannot honor. Absent options
+    // keep each bridge's default.
+
+    /// Milliseconds a bridge waits after a call returns before looking for what it left
+    /// behind (default 0 for Python, 100 for JavaScript, Go and Java, 300 for Rust).
+    pub const SETTLE_MS: &str = "settle_ms";
+    /// Times the Rust bridge re-checks for left-behind threads within `settle_ms`
+    /// (default 6); threads that exit before the last check are not reported.
+    pub const SETTLE_CHECKS: &str = "settle_checks";
+    /// Fewest threads, goroutines or async tasks an execution has to leave behind for
+    /// them to count as an escape (default 1); fewer are still listed.
+    pub const THREAD_THRESHOLD: &str = "thread_threshold";
+    /// `false` skips the open file descriptor snapshots (Python, JavaScript and Rust).
+    pub const DETECT_FDS: &str = "detect_fds";
+    /// `true` attaches the stack of each left-behind thread or goroutine, or the
+    /// creation stack of each async task; `false` leaves stacks out (Java includes them
+    /// by default).
+    pub const CAPTURE_STACKS: &str = "capture_stacks";
+    /// `true` serves the request from a long-lived daemon bridge process instead of a
+    /// fresh one; stdio transport only.
+    pub const DAEMON: &str = "daemon";
+    /// Container runtime (`docker`) each bridge process runs under instead of the host,
+    /// without network and with the target source mounted read-only; implies stdio.
+    pub const SANDBOX: &str = "sandbox";
+    /// JSON object of container images by language, replacing the default sandbox images.
+    pub const SANDBOX_IMAGES: &str = "sandbox_images";
+    /// Megabytes of address space each bridge process, and each process it starts, may use.
+    pub const BRIDGE_MEMORY_MB: &str = "bridge_memory_mb";
+    /// Seconds of CPU time each bridge process, and each process it starts, may use.
+    pub const BRIDGE_CPU_SECONDS: &str = "bridge_cpu_seconds";
+    /// Processes (and, on Linux, threads) a bridge may run at once.
+    pub const BRIDGE_PROCESSES: &str = "bridge_processes";
+    /// JSON array of arguments the receiver of an instance-method target
+    /// (`Class.method`, `Type::method`) is constructed with; none when absent.
+    pub const CONSTRUCTOR_ARGS: &str = "constructor_args";
+}
+
+/// Version of the request and response schema. Sent with every request and reported
+/// by bridges in their handshake, so either side can tell when the other is older.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Environment variable that makes a bridge print its `BridgeHandshake` and exit
+/// without reading a request.
+pub const HANDSHAKE_ENV: &str = "GRAPHENE_BRIDGE_HANDSHAKE";
+
+/// Optional features a bridge lists in its handshake. Requests to a bridge leave out
+/// the options of features it does not list.
+pub mod capabilities {
+    /// `{"requests": [...]}` batches answered by one process
+    pub const BATCH: &str = "batch";
+    /// Gzip-compressed responses (`accept_encoding`)
+    pub const GZIP: &str = "gzip";
+    /// Unix socket transport (`transport`)
+    pub const UNIX_SOCKET: &str = "unix_socket";
+    /// Framed daemon mode (`daemon`)
+    pub const DAEMON: &str = "daemon";
+    /// Base64-encoded inputs (`input_encodings`)
+    pub const INPUT_ENCODINGS: &str = "input_encodings";
+    /// Several arguments per input (`input_args`)
+    pub const INPUT_ARGS: &str = "input_args";
+    /// Schedule perturbation (`chaos_seed`)
+    pub const CHAOS: &str = "chaos";
+    /// Watching what outlives an execution (`observe_seconds`)
+    pub const OBSERVE: &str = "observe";
+    /// Descendant process snapshots (`detect_processes`)
+    pub const DETECT_PROCESSES: &str = "detect_processes";
+    /// Daemon vs exit-blocking classification of left-behind threads
+    /// (`treat_daemon_as_escape`)
+    pub const THREAD_CLASSIFICATION: &str = "thread_classification";
+}
+
+/// What a bridge prints when started with `HANDSHAKE_ENV` set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeHandshake {
+    pub protocol_version: u32,
+    #[serde(default)]
+    pub analyzer_version: String,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+impl BridgeHandshake {
+    /// Stand-in for bridges that predate the handshake. Batches are assumed, since a
+    /// batch a bridge does not understand is rerun one request at a time anyway.
+    pub fn legacy() -> Self {
+        Self {
+            protocol_version: 0,
+            analyzer_version: String::new(),
+            capabilities: vec![capabilities::BATCH.to_string()],
+        }
+    }
+
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|own| own == capability)
     }
 }
 
+/// Line a bridge writes to stdout before a gzip-compressed response.
+pub const GZIP_RESPONSE_MARKER: &str = "GRAPHENE_GZIP";
+
+/// Header of every daemon bridge message: `GRAPHENE_FRAME <length>` on its own line,
+/// followed by `<length>` bytes of JSON.
+pub const FRAME_MARKER: &str = "GRAPHENE_FRAME";
+
+/// Version of the daemon message set; bridges announce it in their `ready` frame.
+pub const DAEMON_PROTOCOL_VERSION: u32 = 1;
+
+/// Message exchanged with a daemon bridge, one per frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DaemonMessage {
+    /// Bridge started and is waiting for requests
+    Ready { protocol: u32 },
+    /// One analysis, answered by the `Response` with the same id
+    Analyze { id: u64, request: Box<AnalyzeRequest> },
+    Response { id: u64, response: Box<AnalyzeResponse> },
+    /// No more requests; the bridge exits
+    Shutdown,
+}
+
+/// How an input string is encoded in the request
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InputEncoding {
+    /// The input is the string itself (default)
+    #[default]
+    Utf8,
+    /// The input is base64 of bytes that are not valid UTF-8; bridges decode it before
+    /// calling the target and echo the encoded form as `input_data`
+    Base64,
+}
+
 /// Request to analyze a function
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalyzeRequest {
+    /// `PROTOCOL_VERSION` of the orchestrator that sent the request; 0 when absent
+    #[serde(default)]
+    pub protocol_version: u32,
     pub session_id: String,
     pub target: String,
     pub inputs: Vec<String>,
+    /// Encoding of each input, parallel to `inputs`; empty when every input is UTF-8
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_encodings: Vec<InputEncoding>,
+    /// Arguments of each input for targets taking several parameters, parallel to
+    /// `inputs` (which then hold each list's JSON text); empty when every input is the
+    /// target's one argument
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_args: Vec<Vec<serde_json::Value>>,
     pub repeat: usize,
     pub timeout_seconds: f64,
     pub options: HashMap<String, String>,
@@ -35,6 +208,12 @@ pub struct AnalyzeRequest {
     pub analysis_mode: AnalysisMode,
 }
 
+/// Several requests answered by one bridge process, so the runtime starts only once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchAnalyzeRequest {
+    pub requests: Vec<AnalyzeRequest>,
+}
+
 /// Single test execution result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionResult {
@@ -43,22 +222,270 @@ pub struct ExecutionResult {
     pub success: bool,
     pub crashed: bool,
     pub output: String,
-    pub error: String,
+    /// Why the execution failed; bridges may still send a plain string, which is classified.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_analysis_error",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub error: Option<AnalysisError>,
     #[serde(alias = "executionTimeMs")]
     pub execution_time_ms: u64,
     #[serde(alias = "escapeDetected")]
     pub escape_detected: bool,
     #[serde(alias = "escapeDetails")]
     pub escape_details: EscapeDetails,
+    /// Stdout written by the target during this execution (bridges that can isolate it)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stdout: String,
+    /// Stderr written by the target during this execution (bridges that can isolate it)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stderr: String,
+    /// Extra log files produced by the bridge for this execution; copied into
+    /// `executions/<n>/` of the session and rewritten as session-relative paths.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<String>,
+    /// Placeholder for an input the bridge never reached because it failed mid-run
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub not_executed: bool,
+}
+
+/// Category of an analysis failure
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisErrorKind {
+    /// The target file, module, class or function could not be found
+    TargetNotFound,
+    /// The target was found but could not be loaded, compiled or imported
+    LoadFailed,
+    /// An execution ran out of time
+    Timeout,
+    /// The bridge ran past its overall deadline and was killed
+    BridgeTimeout,
+    /// The bridge process died or produced no usable response
+    BridgeCrash,
+    /// The bridge stopped sending heartbeats and was killed
+    BridgeHang,
+    /// The bridge reports an incompatible analyzer version or is out of date
+    VersionMismatch,
+    /// The bridge does not support what the request asked for
+    UnsupportedFeature,
+    /// The request or response did not follow the bridge protocol
+    Protocol,
+    /// A runtime, toolchain or permission problem on the host
+    Environment,
+    /// The target itself raised, panicked or crashed
+    RuntimeCrash,
+    /// The target ran, but the `--oracle` command rejected its output
+    OracleFailed,
+    /// The run was interrupted (Ctrl+C) and the bridge stopped before finishing
+    Interrupted,
+    /// The bridge went past a `--bridge-*` resource cap and was killed
+    ResourceLimit,
+    #[serde(other)]
+    Unknown,
+}
+
+impl AnalysisErrorKind {
+    /// Wire name, as serialized in the protocol
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TargetNotFound => "target_not_found",
+            Self::LoadFailed => "load_failed",
+            Self::Timeout => "timeout",
+            Self::BridgeTimeout => "bridge_timeout",
+            Self::BridgeCrash => "bridge_crash",
+            Self::BridgeHang => "bridge_hang",
+            Self::VersionMismatch => "version_mismatch",
+            Self::UnsupportedFeature => "unsupported_feature",
+            Self::Protocol => "protocol",
+            Self::Environment => "environment",
+            Self::RuntimeCrash => "runtime_crash",
+            Self::OracleFailed => "oracle_failed",
+            Self::Interrupted => "interrupted",
+            Self::ResourceLimit => "resource_limit",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Human-readable category used in console output and reports
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::TargetNotFound => "Target Not Found",
+            Self::LoadFailed => "Load Failed",
+            Self::Timeout => "Timeout",
+            Self::BridgeTimeout => "Bridge Timeout",
+            Self::BridgeCrash => "Bridge Crash",
+            Self::BridgeHang => "Bridge Hang",
+            Self::VersionMismatch => "Version Mismatch",
+            Self::UnsupportedFeature => "Unsupported Feature",
+            Self::Protocol => "Protocol/Input",
+            Self::Environment => "Environment",
+            Self::RuntimeCrash => "Runtime Crash",
+            Self::OracleFailed => "Oracle Failed",
+            Self::Interrupted => "Interrupted",
+            Self::ResourceLimit => "Resource Limit",
+            Self::Unknown => "Unknown",
+        }
+    }
+
+    /// Whether the failure was running out of time, in one execution or the whole bridge
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Timeout | Self::BridgeTimeout)
+    }
+
+    /// Whether the bridge process died or hung, which a rerun may not repeat
+    pub fn is_bridge_failure(&self) -> bool {
+        matches!(self, Self::BridgeCrash | Self::BridgeHang)
+    }
+
+    /// Classify a free-text error from a bridge that does not send structured errors.
+    pub fn classify(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| lower.contains(needle));
+
+        if has(&["bridge_hang"]) {
+            Self::BridgeHang
+        } else if has(&["bridge_timeout"]) {
+            Self::BridgeTimeout
+        } else if has(&["version_mismatch", "out of date"]) {
+            Self::VersionMismatch
+        } else if has(&["timeout", "timed out", "exceeded"]) {
+            Self::Timeout
+        } else if has(&["unsupported", "not supported", "not implemented"]) {
+            Self::UnsupportedFeature
+        } else if has(&[
+            "target resolution",
+            "missing required field: 'target'",
+            "invalid target",
+            "nosuchmethod",
+            "classnotfound",
+            "module not found",
+            "cannot find module",
+        ]) || (lower.contains("not found") && !has(&["not found in path", "binary not found", "command not found"]))
+        {
+            Self::TargetNotFound
+        } else if has(&["target loading failed", "failed to load", "failed to build", "compilation failed", "importerror", "syntaxerror"]) {
+            Self::LoadFailed
+        } else if has(&["protocol/input", "invalid json", "failed to parse", "empty input", "expected json", "json", "stdin", "protocol"]) {
+            Self::Protocol
+        } else if has(&[
+            "environment",
+            "permission denied",
+            "not available",
+            "not found in path",
+            "command not found",
+            "missing tools",
+            "failed to spawn",
+            "binary not found",
+            "no such file or directory",
+        ]) {
+            Self::Environment
+        } else if has(&["bridge crash", "bridge exited", "killed by signal"]) {
+            Self::BridgeCrash
+        } else if has(&["runtime crash", "panic", "exception", "traceback", "segmentation", "error"]) {
+            Self::RuntimeCrash
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+/// Structured failure attached to an execution result or a whole response
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AnalysisError {
+    pub kind: AnalysisErrorKind,
+    pub message: String,
+    /// Stack traces or other multi-line context
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl AnalysisError {
+    pub fn new(kind: AnalysisErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// Build an error from free text: the first non-empty line becomes the message,
+    /// the rest the details, and the kind is classified from the whole text.
+    pub fn from_message(raw: &str) -> Self {
+        let trimmed = raw.trim();
+        let (message, rest) = match trimmed.split_once('\n') {
+            Some((first, rest)) => (first.trim(), rest.trim()),
+            None => (trimmed, ""),
+        };
+        Self {
+            kind: AnalysisErrorKind::classify(trimmed),
+            message: message.to_string(),
+            details: (!rest.is_empty()).then(|| rest.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Accepts a structured error object, a legacy error string, or nothing.
+fn deserialize_analysis_error<'de, D>(deserializer: D) -> Result<Option<AnalysisError>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawError {
+        Text(String),
+        Structured(AnalysisError),
+    }
+
+    Ok(match Option::<RawError>::deserialize(deserializer)? {
+        Some(RawError::Text(text)) if !text.trim().is_empty() => Some(AnalysisError::from_message(&text)),
+        Some(RawError::Structured(error)) => Some(error),
+        _ => None,
+    })
 }
 
 /// Detailed escape information for object escape analysis
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EscapeDetails {
     #[serde(default, alias = "escapingReferences")]
     pub escaping_references: Vec<ObjectReference>,
     #[serde(default, alias = "escapePaths")]
     pub escape_paths: Vec<EscapePath>,
+    /// Threads still running after the execution. `None` when the bridge does not track
+    /// them, as opposed to an empty list when it found none; likewise for the lists below.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threads: Option<Vec<serde_json::Value>>,
+    /// Left-behind threads of thread pools and runtimes (`pool`, `threads`), kept apart
+    /// from the one-off spawns in `threads` by bridges that tell them apart.
+    #[serde(default, alias = "threadPools", skip_serializing_if = "Option::is_none")]
+    pub thread_pools: Option<Vec<serde_json::Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub processes: Option<Vec<serde_json::Value>>,
+    #[serde(default, alias = "asyncTasks", skip_serializing_if = "Option::is_none")]
+    pub async_tasks: Option<Vec<serde_json::Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goroutines: Option<Vec<serde_json::Value>>,
+    /// Sockets, pipes and files the execution opened and left open (`fd`, `kind`, `target`).
+    #[serde(default, alias = "fileDescriptors", skip_serializing_if = "Option::is_none")]
+    pub file_descriptors: Option<Vec<serde_json::Value>>,
+    /// Timers still scheduled after the execution (`timer_id`, `kind`, `period_ms`, `callback`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timers: Option<Vec<serde_json::Value>>,
+    /// Resident memory of the bridge process around the execution, from bridges that
+    /// measure it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryEscape>,
+    /// What differed across an input's runs in race mode, on `potential_data_race`
+    /// vulnerabilities.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub race: Option<RaceEvidence>,
 }
 
 impl EscapeDetails {
@@ -66,6 +493,95 @@ impl EscapeDetails {
         self.escaping_references.is_empty() && self.escape_paths.is_empty()
     }
 
+    /// Threads (pooled or not), processes, tasks, goroutines and timers the execution left behind, or
+    /// `None` when the bridge does not track any of them.
+    pub fn spawned_count(&self) -> Option<usize> {
+        let lists = [&self.threads, &self.processes, &self.async_tasks, &self.goroutines, &self.timers];
+        let pooled: usize = self
+            .thread_pools
+            .iter()
+            .flatten()
+            .filter_map(|pool| pool.get("threads")?.as_array().map(Vec::len))
+            .sum();
+        lists
+            .iter()
+            .any(|list| list.is_some())
+            .then(|| lists.iter().filter_map(|list| list.as_ref()).map(Vec::len).sum::<usize>() + pooled)
+    }
+
+    /// Left-behind work whose lifetime the bridge observed (`observe_seconds`).
+    pub fn escaped_work(&self) -> Vec<EscapedWork> {
+        let lists = [
+            ("thread", &self.threads, "thread_id", "name"),
+            ("process", &self.processes, "pid", "name"),
+            ("task", &self.async_tasks, "task_id", "task_type"),
+            ("goroutine", &self.goroutines, "goroutine_id", "function"),
+        ];
+        let field = |value: &serde_json::Value, key: &str| match value.get(key) {
+            Some(serde_json::Value::String(text)) => text.clone(),
+            Some(other) => other.to_string(),
+            None => "?".to_string(),
+        };
+        lists
+            .iter()
+            .flat_map(|(kind, list, id, name)| {
+                list.iter().flatten().filter_map(move |value| {
+                    let text = |key: &str| value.get(key).and_then(|v| v.as_str()).map(str::to_string);
+                    Some(EscapedWork {
+                        label: format!("{} {} ({})", kind, field(value, id), field(value, name)),
+                        outlived_ms: value.get("outlived_ms")?.as_u64()?,
+                        still_running: value.get("still_running").and_then(|v| v.as_bool()).unwrap_or(false),
+                        cpu_ms: value.get("cpu_ms").and_then(|v| v.as_u64()),
+                        state: text("state"),
+                        resource: text("task_type"),
+                        holds: value
+                            .get("holds")
+                            .and_then(|v| v.as_array())
+                            .map(|held| held.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+                            .unwrap_or_default(),
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Descriptors the execution left open, as reported by bridges that snapshot them.
+    pub fn leaked_descriptors(&self) -> Vec<LeakedDescriptor> {
+        let field = |value: &serde_json::Value, key: &str| match value.get(key) {
+            Some(serde_json::Value::String(text)) => text.clone(),
+            Some(serde_json::Value::Null) | None => "?".to_string(),
+            Some(other) => other.to_string(),
+        };
+        self.file_descriptors
+            .iter()
+            .flatten()
+            .map(|value| LeakedDescriptor {
+                fd: field(value, "fd"),
+                kind: field(value, "kind"),
+                target: field(value, "target"),
+            })
+            .collect()
+    }
+
+    /// Timers the execution left scheduled, as reported by bridges that track them.
+    pub fn leaked_timers(&self) -> Vec<LeakedTimer> {
+        let field = |value: &serde_json::Value, key: &str| match value.get(key) {
+            Some(serde_json::Value::String(text)) => text.clone(),
+            Some(serde_json::Value::Null) | None => "?".to_string(),
+            Some(other) => other.to_string(),
+        };
+        self.timers
+            .iter()
+            .flatten()
+            .map(|value| LeakedTimer {
+                id: field(value, "timer_id"),
+                kind: field(value, "kind"),
+                period_ms: value.get("period_ms").and_then(|v| v.as_f64()),
+                callback: field(value, "callback"),
+            })
+            .collect()
+    }
+
     pub fn summary(&self) -> String {
         if self.escaping_references.is_empty() {
             return "No escaping references detected".to_string();
@@ -78,6 +594,81 @@ impl EscapeDetails {
     }
 }
 
+/// A thread, task, goroutine or process an execution left running, as watched by the
+/// bridge during its observation window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EscapedWork {
+    /// e.g. `goroutine 18 (main.worker)`
+    pub label: String,
+    /// How long it kept running after the call returned
+    pub outlived_ms: u64,
+    /// Still running when the window ended
+    pub still_running: bool,
+    /// CPU time it used during the window, where the bridge can measure it
+    pub cpu_ms: Option<u64>,
+    /// Last state the bridge saw (`running`, `IO wait`, `sleep`, ...)
+    pub state: Option<String>,
+    /// Resource kind for async tasks (`TCPWRAP`, `Timeout`, ...)
+    pub resource: Option<String>,
+    /// Resources the bridge saw it using (`socket`, `lock`, ...)
+    pub holds: Vec<String>,
+}
+
+/// A file descriptor (or Windows handle) open after the execution that was not before it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeakedDescriptor {
+    pub fd: String,
+    /// `socket`, `pipe` or `file`
+    pub kind: String,
+    /// Path, or `socket:[inode]` / `pipe:[inode]` on Linux
+    pub target: String,
+}
+
+/// A timer still scheduled after the execution that created it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeakedTimer {
+    pub id: String,
+    /// `interval` (fires until cleared) or `timeout` (fires once more)
+    pub kind: String,
+    /// Delay between firings; measured rather than declared where the bridge cannot see
+    /// the timer itself
+    pub period_ms: Option<f64>,
+    /// Callback name and the source location that scheduled it, where known
+    pub callback: String,
+}
+
+/// Resident set size of the process running the target, just before and after one
+/// execution (after a garbage collection, where the runtime has one)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryEscape {
+    #[serde(alias = "rssBeforeBytes")]
+    pub rss_before_bytes: u64,
+    #[serde(alias = "rssAfterBytes")]
+    pub rss_after_bytes: u64,
+}
+
+impl MemoryEscape {
+    /// Bytes the execution added to the process, negative when it shrank.
+    pub fn growth_bytes(&self) -> i64 {
+        self.rss_after_bytes as i64 - self.rss_before_bytes as i64
+    }
+}
+
+/// Runs of one input that disagreed while static analysis found the target touching
+/// shared state
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaceEvidence {
+    /// Successful runs compared.
+    pub runs: usize,
+    /// Distinct outputs, in the order first seen.
+    pub outputs: Vec<String>,
+    /// Distinct side-effect summaries of the runs after the first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub side_effects: Vec<String>,
+    /// Shared state the target touches (`Arc<Mutex>`, globals).
+    pub shared_state: Vec<String>,
+}
+
 /// A reference to an object that escaped local scope
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ObjectReference {
@@ -110,6 +701,83 @@ pub struct StaticAnalysisResult {
     pub analysis_time_ms: u64,
     pub warnings: Vec<String>,
     pub summary: StaticEscapeSummary,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_graph: Option<CallGraph>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spawn_estimate: Option<SpawnEstimate>,
+}
+
+/// Threads/tasks one call of the target is expected to start, from constant loop bounds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnEstimate {
+    /// Upper bound per call; `None` when a reachable spawn repeats an unknown number of
+    /// times (non-constant loop bound, `while` loop or recursion).
+    pub per_call: Option<usize>,
+    pub sites: Vec<SpawnCount>,
+}
+
+/// How often one spawn site runs per call of the target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnCount {
+    pub function: String,
+    pub line: usize,
+    pub per_call: Option<usize>,
+    /// Enclosing loop headers, outermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub loops: Vec<String>,
+    #[serde(default)]
+    pub growth: SpawnGrowth,
+}
+
+/// How the number of spawns from one site grows, from least to most severe
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnGrowth {
+    /// Not inside a loop: once per call
+    #[default]
+    Single,
+    /// Loops with constant bounds
+    Bounded,
+    /// A loop whose bound is only known at run time (a collection, a computed limit)
+    Dynamic,
+    /// A loop bounded by one of the function's parameters
+    InputDependent,
+    /// `loop {}`, `while true`, `for {}`, `for (;;)`
+    Unbounded,
+}
+
+/// Call graph reachable from the target function within its source file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallGraph {
+    pub root: String,
+    pub source_file: String,
+    pub nodes: Vec<CallGraphNode>,
+    pub edges: Vec<CallGraphEdge>,
+}
+
+/// A function in the call graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallGraphNode {
+    pub name: String,
+    /// Definition line in the source file; `None` for calls that leave the file.
+    pub line: Option<usize>,
+    /// Lines inside the function that start a thread, task or worker.
+    pub spawn_sites: Vec<SpawnSite>,
+}
+
+/// A thread/task/worker creation inside a call graph node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnSite {
+    pub line: usize,
+    pub code: String,
+}
+
+/// Calls from one function to another, with the lines they occur on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallGraphEdge {
+    pub caller: String,
+    pub callee: String,
+    pub lines: Vec<usize>,
 }
 
 /// A single escape point detected by static analysis
@@ -121,10 +789,55 @@ pub struct StaticEscape {
     pub reason: String,
     pub confidence: ConfidenceLevel,
     pub data_flow: Vec<String>,
+    /// The target's parameters reach this escape (captured by a spawned thread/task or
+    /// stored through a variable derived from them).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub input_tainted: bool,
+    /// Steps carrying the input from a parameter to the escape.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub taint_flow: Vec<String>,
+    /// Dedicated rule that produced this finding (e.g. `recursive-spawn`); `None` for the
+    /// language analyzers' general escape checks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// Catalog id of the rule covering this finding (`GH-RS-001`); see `rules list`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
+    /// Branch condition guarding the escape (`input.len() > 3`), when it only happens
+    /// on some paths through the function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    /// Change that would resolve the finding, from the template of its rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_fix: Option<SuggestedFix>,
+}
+
+/// Change resolving a static finding
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SuggestedFix {
+    pub description: String,
+    /// The code to add or use instead, as shown to the user
+    pub snippet: String,
+    /// The change as edits of the source file; empty when it cannot be placed
+    /// mechanically (a refactoring such as moving spawns into `thread::scope`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edits: Vec<FixEdit>,
+}
+
+/// Replaces `replaced_lines` whole lines from `line` (1-based) with `text`, or inserts
+/// `text` before `line` when none are replaced.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FixEdit {
+    pub line: usize,
+    #[serde(default)]
+    pub replaced_lines: usize,
+    /// Whole lines, each ending in a newline
+    pub text: String,
 }
 
 /// Types of escapes in static analysis
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
 pub enum EscapeType {
     /// Variable returned from function
     ReturnEscape,
@@ -136,6 +849,10 @@ pub enum EscapeType {
     ClosureEscape,
     /// Variable stored in heap-allocated structure or container
     HeapEscape,
+    /// Threads/tasks started many times per call by the loops around a spawn
+    SpawnEscape,
+    /// Process exit reached from the call, skipping the cleanup of work it started
+    ExitEscape,
     /// Unknown escape pattern
     UnknownEscape,
 }
@@ -167,9 +884,16 @@ pub struct StaticEscapeSummary {
     pub global_escapes: usize,
     pub closure_escapes: usize,
     pub heap_escapes: usize,
+    #[serde(default)]
+    pub spawn_escapes: usize,
+    #[serde(default)]
+    pub exit_escapes: usize,
     pub high_confidence: usize,
     pub medium_confidence: usize,
     pub low_confidence: usize,
+    /// Escapes hidden by `graphene:ignore` comments or `.grapheneignore`, not counted above
+    #[serde(default)]
+    pub suppressed: usize,
 }
 
 impl StaticEscapeSummary {
@@ -181,9 +905,12 @@ impl StaticEscapeSummary {
             global_escapes: 0,
             closure_escapes: 0,
             heap_escapes: 0,
+            spawn_escapes: 0,
+            exit_escapes: 0,
             high_confidence: 0,
             medium_confidence: 0,
             low_confidence: 0,
+            suppressed: 0,
         }
     }
 
@@ -195,6 +922,8 @@ impl StaticEscapeSummary {
             EscapeType::GlobalEscape => self.global_escapes += 1,
             EscapeType::ClosureEscape => self.closure_escapes += 1,
             EscapeType::HeapEscape => self.heap_escapes += 1,
+            EscapeType::SpawnEscape => self.spawn_escapes += 1,
+            EscapeType::ExitEscape => self.exit_escapes += 1,
             EscapeType::UnknownEscape => {},
         }
         match escape.confidence {
@@ -220,6 +949,28 @@ pub struct AnalyzeResponse {
     pub summary: ExecutionSummary,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub static_analysis: Option<StaticAnalysisResult>,
+    /// Stderr emitted by the bridge process, captured by the orchestrator
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bridge_stderr: Option<String>,
+    /// Schedule perturbation seed the bridge applied; replay with `--chaos-seed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chaos_seed: Option<u64>,
+    /// Failure that prevented the bridge from running the target at all
+    #[serde(
+        default,
+        deserialize_with = "deserialize_analysis_error",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub error: Option<AnalysisError>,
+    /// Request options the bridge did not know or could not honor
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Responses to a batch request, one per request and in request order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchAnalyzeResponse {
+    pub responses: Vec<AnalyzeResponse>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -245,6 +996,26 @@ pub struct ExecutionSummary {
     pub genuine_escapes: usize,
     #[serde(alias = "crashRate")]
     pub crash_rate: f64,
+    /// Escapes hidden by `graphene:ignore` comments or `.grapheneignore`
+    #[serde(default)]
+    pub suppressed: usize,
+    /// How memory growth across repeats was judged, when the bridge measured it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryGrowthSummary>,
+}
+
+/// Inputs whose repeats were measured, split into probable leaks and noise
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryGrowthSummary {
+    /// Growth over an input's repeats past which sustained growth is a leak
+    pub threshold_bytes: u64,
+    /// Inputs with at least two measured repeats
+    pub measured_inputs: usize,
+    /// Inputs whose memory grew on every repeat after the first, past the threshold
+    pub probable_leaks: usize,
+    /// Inputs that grew past the threshold without growing on every repeat: warm-up,
+    /// caches filling or allocator noise
+    pub noise: usize,
 }
 
 /// Analyzer capabilities and metadata
@@ -263,4 +1034,51 @@ pub struct AnalyzerInfo {
 pub struct HealthCheckResponse {
     pub pong: String,
     pub analyzer_info: AnalyzerInfo,
+    /// Protocol version the bridge reported in its handshake; 0 for bridges without one
+    #[serde(default)]
+    pub protocol_version: u32,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Results as the Go bridge writes them (snake_case) and the Java bridge through
+    /// Gson (camelCase), each with what the target printed.
+    #[test]
+    fn execution_results_carry_the_target_streams_of_every_bridge() {
+        let go = json!({
+            "input_data": "x", "success": true, "crashed": false, "output": "ok", "error": "",
+            "execution_time_ms": 3, "escape_detected": false,
+            "escape_details": { "escaping_references": [], "escape_paths": [], "goroutines": [] },
+            "stdout": "log line\nok", "stderr": "warning\n",
+        });
+        let java = json!({
+            "inputData": "x", "success": true, "crashed": false, "output": "ok", "error": "",
+            "executionTimeMs": 3, "escapeDetected": false,
+            "escapeDetails": { "escapingReferences": [], "escapePaths": [], "threads": [] },
+            "stdout": "log line\n", "stderr": "warning\n",
+        });
+        for (bridge, result) in [("go", go), ("java", java)] {
+            let result: ExecutionResult = serde_json::from_value(result).unwrap();
+            assert!(result.stdout.starts_with("log line\n"), "{}", bridge);
+            assert_eq!(result.stderr, "warning\n", "{}", bridge);
+            assert!(result.error.is_none(), "{}", bridge);
+        }
+    }
+
+    #[test]
+    fn execution_results_without_streams_default_to_empty() {
+        let result: ExecutionResult = serde_json::from_value(json!({
+            "input_data": "x", "success": true, "crashed": false, "output": "ok",
+            "execution_time_ms": 3, "escape_detected": false, "escape_details": {},
+        }))
+        .unwrap();
+        assert!(result.stdout.is_empty() && result.stderr.is_empty());
+        let written = serde_json::to_value(&result).unwrap();
+        assert!(written.get("stdout").is_none() && written.get("stderr").is_none());
+    }
 }
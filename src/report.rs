@@ -1,52 +1,309 @@
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
 use std::collections::{BTreeMap, HashSet};
 use chrono::Local;
+use serde::Serialize;
 use uuid::Uuid;
-use crate::protocol::{AnalyzeResponse, ExecutionResult, Vulnerability};
+use crate::protocol::{AnalysisErrorKind, AnalysisMode, AnalyzeResponse, CallGraph, CallGraphNode, ConfidenceLevel, EscapeType, EscapedWork, ExecutionResult, LeakedDescriptor, LeakedTimer, SpawnEstimate, StaticAnalysisResult, StaticEscape, SuggestedFix, Vulnerability};
+use crate::static_analyzer::callgraph;
+use crate::static_analyzer::rules::{self, Rule};
+use crate::template::ReportTemplates;
+
+/// Version of the `report.json` layout. Bumped when a field is removed, renamed or
+/// changes meaning; new optional fields keep the version.
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Files and directories the built-in reports write into a session directory, which
+/// report templates may not overwrite.
+pub const BUILT_IN_REPORTS: [&str; 11] = [
+    "README.md",
+    "static_analysis.md",
+    "static_escapes.csv",
+    "results.csv",
+    "report.json",
+    "results.sarif",
+    "vulnerabilities.md",
+    "callgraph.json",
+    "callgraph.dot",
+    "bridge_stderr.log",
+    "executions",
+];
+
+/// Contents of `report.json`, and the context of report templates
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    schema_version: u32,
+    generated_at: String,
+    target: &'a str,
+    host: HostInfo,
+    response: &'a AnalyzeResponse,
+}
+
+impl<'a> JsonReport<'a> {
+    fn new(response: &'a AnalyzeResponse, target: &'a str) -> Self {
+        Self {
+            schema_version: REPORT_SCHEMA_VERSION,
+            generated_at: Local::now().to_rfc3339(),
+            target,
+            host: HostInfo::current(),
+            response,
+        }
+    }
+}
+
+/// Machine the analysis ran on
+#[derive(Serialize)]
+struct HostInfo {
+    hostname: String,
+    os: &'static str,
+    arch: &'static str,
+    cpus: usize,
+    tool_version: &'static str,
+}
+
+impl HostInfo {
+    fn current() -> Self {
+        let hostname = std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "unknown".to_string());
+        Self {
+            hostname,
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            cpus: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            tool_version: env!("CARGO_PKG_VERSION"),
+        }
+    }
+}
+
+/// Extra report formats written next to the Markdown summary and CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// README.md, results.csv and vulnerabilities.md only
+    Markdown,
+    /// Also results.sarif (SARIF 2.1.0) for code scanning
+    Sarif,
+}
+
+/// What each target's reports include besides the Markdown summary, CSV and JSON
+#[derive(Debug, Clone)]
+pub struct ReportSettings {
+    pub format: ReportFormat,
+    /// User templates rendered into every session directory (`--report-template`)
+    pub templates: Option<ReportTemplates>,
+    /// Session directory name replacing the generated one (`--report-name`)
+    pub name: Option<String>,
+}
+
+impl Default for ReportSettings {
+    fn default() -> Self {
+        Self { format: ReportFormat::Markdown, templates: None, name: None }
+    }
+}
+
+impl ReportSettings {
+    /// The settings for one function of a whole-file analysis: a named report becomes
+    /// `<name>-<function>`, next to the file report named `<name>`.
+    pub fn for_function(&self, function: &str) -> Self {
+        Self {
+            name: self.name.as_ref().map(|name| format!("{}-{}", name, slug(function))),
+            ..self.clone()
+        }
+    }
+}
+
+/// One function's line in the breakdown of a whole-file analysis
+pub struct FunctionReportRow {
+    pub function: String,
+    /// `false` when the function produced no results; its counts are then zero.
+    pub analyzed: bool,
+    pub escapes: usize,
+    pub vulnerabilities: usize,
+    pub crashes: usize,
+    pub time_ms: u64,
+    /// Session directory of the function's own report
+    pub report_dir: Option<PathBuf>,
+}
 
 pub struct ReportGenerator {
     output_dir: PathBuf,
+    settings: ReportSettings,
 }
 
 impl ReportGenerator {
     pub fn new(output_dir: PathBuf) -> Self {
-        Self { output_dir }
+        Self { output_dir, settings: ReportSettings::default() }
     }
 
-    pub async fn generate(&self, response: &AnalyzeResponse, target: &str) -> Result<()> {
-        std::fs::create_dir_all(&self.output_dir)?;
+    pub fn with_settings(mut self, settings: &ReportSettings) -> Self {
+        self.settings = settings.clone();
+        self
+    }
 
-        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-        let uuid_str = Uuid::new_v4().to_string();
-        let random_id = uuid_str.split('-').next().unwrap_or("xxxx");
-        let language = response.language.trim();
-        let language_dir = if language.is_empty() {
-            self.output_dir.join("unknown")
-        } else {
-            self.output_dir.join(language.to_lowercase())
-        };
-        std::fs::create_dir_all(&language_dir)?;
-        let session_dir = language_dir.join(format!("session_{}_{}", timestamp, random_id));
-        std::fs::create_dir_all(&session_dir)?;
+    /// Writes the reports for one target and returns the session directory holding them.
+    pub async fn generate(&self, response: &AnalyzeResponse, target: &str) -> Result<PathBuf> {
+        let session_dir = self.create_session_dir(&response.language, "session", target, &response.session_id)?;
+
+        // Persist captured output so the summary can link to it
+        let captured = self.generate_captured_output(&session_dir, response).await?;
+
+        // Export the static call graph next to the summary that links to it
+        self.generate_call_graph(&session_dir, response).await?;
+
+        // Full static findings with source snippets, as Markdown and CSV
+        self.generate_static_report(&session_dir, response).await?;
 
         // Generate summary report
-        self.generate_summary(&session_dir, response, target).await?;
+        self.generate_summary(&session_dir, response, target, &captured).await?;
 
         // Generate CSV report
         self.generate_csv(&session_dir, response).await?;
 
+        // Full response for tooling
+        self.generate_json(&session_dir, response, target).await?;
+
+        if self.settings.format == ReportFormat::Sarif {
+            self.generate_sarif(&session_dir, response, target).await?;
+        }
+
+        // The user's own reports, from --report-template
+        if let Some(templates) = &self.settings.templates {
+            let context = serde_json::to_value(JsonReport::new(response, target))?;
+            templates.render_into(&session_dir, &context)?;
+        }
+
         // Generate vulnerability report
         if !response.vulnerabilities.is_empty() {
             self.generate_vulnerability_report(&session_dir, response).await?;
         }
 
-        println!("📁 Reports generated in: {}", session_dir.display());
+        self.link_latest(&session_dir);
 
-        Ok(())
+        Ok(session_dir)
     }
 
-    async fn generate_summary(&self, dir: &PathBuf, response: &AnalyzeResponse, target: &str) -> Result<()> {
+    /// Combined report of a whole-file analysis: a table of the file's functions with
+    /// links to their own reports.
+    pub async fn generate_file_report(&self, file: &str, language: &str, rows: &[FunctionReportRow]) -> Result<PathBuf> {
+        let dir = self.create_session_dir(language, "file", file, &Uuid::new_v4().to_string())?;
+
+        let mut table = String::from("| Function | Escapes | Vulnerabilities | Crashes | Time | Report |\n");
+        table.push_str("|----------|---------|-----------------|---------|------|--------|\n");
+        for row in rows {
+            let report = match &row.report_dir {
+                Some(report_dir) => {
+                    let name = report_dir.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+                    format!("[{}](../{}/README.md)", name, name)
+                }
+                None => "-".to_string(),
+            };
+            if row.analyzed {
+                table.push_str(&format!(
+                    "| `{}` | {} | {} | {} | {}ms | {} |\n",
+                    row.function, row.escapes, row.vulnerabilities, row.crashes, row.time_ms, report
+                ));
+            } else {
+                table.push_str(&format!("| `{}` | ✗ no results | - | - | - | {} |\n", row.function, report));
+            }
+        }
+
+        let content = format!(
+            r#"# Object Escape Analysis Report (whole file)
+
+**File:** `{}`
+**Language:** {}
+**Generated:** {}
+
+## Overview
+
+| Metric | Value |
+|--------|-------|
+| Functions | {} |
+| With Escapes | {} |
+| Without Results | {} |
+| Vulnerabilities | {} |
+| Crashes | {} |
+
+## Functions
+
+{}"#,
+            file,
+            language,
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            rows.len(),
+            rows.iter().filter(|row| row.escapes > 0).count(),
+            rows.iter().filter(|row| !row.analyzed).count(),
+            rows.iter().map(|row| row.vulnerabilities).sum::<usize>(),
+            rows.iter().map(|row| row.crashes).sum::<usize>(),
+            table
+        );
+        tokio::fs::write(dir.join("README.md"), content).await?;
+
+        self.link_latest(&dir);
+        Ok(dir)
+    }
+
+    /// A new directory under the language's directory: the `--report-name` when set,
+    /// else `<prefix>_<timestamp>_<target slug>_<session id>`, so targets reported in
+    /// the same second (as run-all does) never share one. An existing directory is an
+    /// error rather than merged into.
+    fn create_session_dir(&self, language: &str, prefix: &str, target: &str, session_id: &str) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.output_dir)?;
+
+        let name = match &self.settings.name {
+            Some(name) => name.clone(),
+            None => {
+                let session_id = match slug(session_id) {
+                    id if id.is_empty() => Uuid::new_v4().to_string(),
+                    id => id,
+                };
+                let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+                [prefix.to_string(), timestamp, slug(target), session_id]
+                    .into_iter()
+                    .filter(|part| !part.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("_")
+            }
+        };
+        let language = language.trim();
+        let language_dir = if language.is_empty() {
+            self.output_dir.join("unknown")
+        } else {
+            self.output_dir.join(language.to_lowercase())
+        };
+        std::fs::create_dir_all(&language_dir)?;
+        let session_dir = language_dir.join(name);
+        if self.settings.name.is_some() && session_dir.exists() {
+            anyhow::bail!("Report directory {} already exists; pick another --report-name", session_dir.display());
+        }
+        std::fs::create_dir(&session_dir)
+            .with_context(|| format!("Failed to create report directory {}", session_dir.display()))?;
+        Ok(session_dir)
+    }
+
+    /// Points `<output dir>/latest` at `dir`. The link is replaced by a rename, so
+    /// concurrent run-all jobs leave it on one of their sessions. Best effort: where
+    /// symlinks cannot be made the reports are still written.
+    fn link_latest(&self, dir: &Path) {
+        let Ok(relative) = dir.strip_prefix(&self.output_dir) else {
+            return;
+        };
+        if let Err(e) = replace_symlink(relative, &self.output_dir.join("latest")) {
+            tracing::debug!("Could not link {} as the latest report: {}", dir.display(), e);
+        }
+    }
+
+    async fn generate_summary(
+        &self,
+        dir: &Path,
+        response: &AnalyzeResponse,
+        target: &str,
+        captured: &CapturedOutput,
+    ) -> Result<()> {
         let path = dir.join("README.md");
         let summary = &response.summary;
 
@@ -54,35 +311,20 @@ impl ReportGenerator {
             format!(
                 r#"## Static Object Escape Analysis
 
-| Category | Count |
-|----------|-------|
-| Total Escapes | {} |
-| Return Escapes | {} |
-| Parameter Escapes | {} |
-| Global/Module Escapes | {} |
-| Closure Escapes | {} |
-| Heap Escapes | {} |
-| High Confidence | {} |
-| Medium Confidence | {} |
-| Low Confidence | {} |
-
+{}
 **Analysis Time:** {}ms
-
+{}
 ### Detected Escape Points
 
 {}
 
+Source snippets and data flow for each finding: [static_analysis.md](static_analysis.md) ([static_escapes.csv](static_escapes.csv))
+
 "#,
-                static_result.summary.total_escapes,
-                static_result.summary.return_escapes,
-                static_result.summary.parameter_escapes,
-                static_result.summary.global_escapes,
-                static_result.summary.closure_escapes,
-                static_result.summary.heap_escapes,
-                static_result.summary.high_confidence,
-                static_result.summary.medium_confidence,
-                static_result.summary.low_confidence,
+                self.format_static_counts(static_result),
                 static_result.analysis_time_ms,
+                self.format_call_graph_summary(static_result.call_graph.as_ref())
+                    + &self.format_spawn_estimate(static_result.spawn_estimate.as_ref()),
                 self.format_static_escapes(&static_result.escapes)
             )
         } else {
@@ -112,7 +354,7 @@ This report shows the results of static object escape analysis for the target fu
 | Successes | {} ✓ |
 | Crashes | {} ✗ |
 | Crash Rate | {:.1}% |
-
+{}{}{}
 ## Vulnerabilities
 
 {}
@@ -123,6 +365,10 @@ This report shows the results of static object escape analysis for the target fu
 
 ## Execution Results
 
+{}
+
+## Captured Output
+
 {}
 "#,
             target,
@@ -135,29 +381,473 @@ This report shows the results of static object escape analysis for the target fu
             summary.successes,
             summary.crashes,
             summary.crash_rate * 100.0,
+            response
+                .chaos_seed
+                .map(|seed| format!("| Perturbation Seed | {} (replay with `--chaos-seed {}`) |\n", seed, seed))
+                .into_iter()
+                .chain((summary.suppressed > 0).then(|| format!("| Suppressed Escapes | {} |\n", summary.suppressed)))
+                .chain(
+                    (!response.warnings.is_empty())
+                        .then(|| format!("| Bridge Warnings | {} |\n", response.warnings.join("; ").replace('|', "\\|"))),
+                )
+                .collect::<String>(),
+            self.format_spawn_correlation(response),
+            self.format_escaped_work(response)
+                + &self.format_leaked_descriptors(response)
+                + &self.format_leaked_timers(response)
+                + &self.format_memory_growth(response),
             self.format_vulnerabilities(&response.vulnerabilities),
             self.format_error_diagnostics(response),
-            self.format_results(response)
+            self.format_results(response, captured),
+            self.format_captured_output(captured)
         );
 
         tokio::fs::write(path, content).await?;
         Ok(())
     }
 
-    async fn generate_csv(&self, dir: &PathBuf, response: &AnalyzeResponse) -> Result<()> {
+    async fn generate_call_graph(&self, dir: &Path, response: &AnalyzeResponse) -> Result<()> {
+        let Some(call_graph) = response
+            .static_analysis
+            .as_ref()
+            .and_then(|result| result.call_graph.as_ref())
+        else {
+            return Ok(());
+        };
+        tokio::fs::write(dir.join("callgraph.json"), serde_json::to_string_pretty(call_graph)?).await?;
+        tokio::fs::write(dir.join("callgraph.dot"), callgraph::render_dot(call_graph)).await?;
+        Ok(())
+    }
+
+    /// `static_analysis.md`, every static finding with its source lines, and
+    /// `static_escapes.csv`, one row per finding.
+    async fn generate_static_report(&self, dir: &Path, response: &AnalyzeResponse) -> Result<()> {
+        let Some(static_result) = &response.static_analysis else {
+            return Ok(());
+        };
+
+        let mut content = format!(
+            "# Static Escape Analysis\n\n**Language:** {}\n**Analysis Time:** {}ms\n\n## Summary\n\n{}",
+            response.language,
+            static_result.analysis_time_ms,
+            self.format_static_counts(static_result)
+        );
+        let mut by_rule: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut by_file: BTreeMap<&str, usize> = BTreeMap::new();
+        for escape in &static_result.escapes {
+            let rule = escape.rule_id.as_deref().or(escape.rule.as_deref()).unwrap_or("escape");
+            *by_rule.entry(rule).or_default() += 1;
+            *by_file.entry(escape.location.file.as_str()).or_default() += 1;
+        }
+        if !static_result.escapes.is_empty() {
+            content.push_str("\n| Rule | Count |\n|------|-------|\n");
+            for (rule, count) in &by_rule {
+                content.push_str(&format!("| `{}` | {} |\n", rule, count));
+            }
+            content.push_str("\n| File | Count |\n|------|-------|\n");
+            for (file, count) in &by_file {
+                content.push_str(&format!("| {} | {} |\n", file, count));
+            }
+        }
+        for warning in &static_result.warnings {
+            content.push_str(&format!("\n⚠️ {}\n", warning));
+        }
+
+        content.push_str("\n## Findings\n\n");
+        if static_result.escapes.is_empty() {
+            content.push_str("✅ No escapes detected by static analysis\n");
+        }
+        let fence = match response.language.to_lowercase().as_str() {
+            "nodejs" => "javascript".to_string(),
+            language => language.to_string(),
+        };
+        let mut sources: BTreeMap<&str, Option<Vec<String>>> = BTreeMap::new();
+        for (i, escape) in static_result.escapes.iter().enumerate() {
+            let location = &escape.location;
+            content.push_str(&format!(
+                "### {}. {} escape: `{}`\n\n**Location:** {}:{}:{} in `{}`\n**Confidence:** {}\n",
+                i + 1,
+                escape_type_label(&escape.escape_type),
+                escape.variable_name,
+                location.file,
+                location.line,
+                location.column,
+                location.function,
+                confidence_label(&escape.confidence)
+            ));
+            if let Some(rule) = escape.rule_id.as_deref().and_then(rules::find) {
+                content.push_str(&format!("**Rule:** `{}` {}\n", rule.id, rule.title));
+            } else if let Some(rule) = &escape.rule {
+                content.push_str(&format!("**Rule:** `{}`\n", rule));
+            }
+            content.push_str(&format!("\n{}\n", escape.reason));
+
+            let lines = sources.entry(location.file.as_str()).or_insert_with(|| {
+                let source = std::fs::read_to_string(&location.file).ok()?;
+                Some(source.lines().map(str::to_string).collect())
+            });
+            let snippet = match lines {
+                Some(lines) if location.line >= 1 && location.line <= lines.len() => {
+                    let first = location.line.saturating_sub(STATIC_SNIPPET_CONTEXT).max(1);
+                    let last = (location.line + STATIC_SNIPPET_CONTEXT).min(lines.len());
+                    (first..=last)
+                        .map(|number| {
+                            let marker = if number == location.line { ">" } else { " " };
+                            format!("{} {:>4} | {}", marker, number, lines[number - 1])
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+                _ => location.code_snippet.clone().unwrap_or_default(),
+            };
+            if !snippet.is_empty() {
+                content.push_str(&format!("\n```{}\n{}\n```\n", fence, snippet));
+            }
+            if let Some(condition) = &escape.condition {
+                content.push_str(&format!("\n**Only when:** `{}`\n", condition));
+            }
+            if !escape.data_flow.is_empty() {
+                content.push_str(&format!("\n**Data flow:** {}\n", escape.data_flow.join(" → ")));
+            }
+            if escape.input_tainted {
+                content.push_str(&format!("\n**Input flow:** {}\n", escape.taint_flow.join(" → ")));
+            }
+            if let Some(fix) = &escape.suggested_fix {
+                content.push_str(&format!("\n**Suggested fix:** {}\n", fix.description));
+                if !fix.snippet.is_empty() {
+                    content.push_str(&format!("\n```{}\n{}\n```\n", fence, fix.snippet));
+                }
+            }
+            content.push('\n');
+        }
+        tokio::fs::write(dir.join("static_analysis.md"), content).await?;
+
+        let mut csv = String::from(
+            "file,line,column,function,escape_type,variable,confidence,rule,rule_id,input_tainted,condition,reason,data_flow\n",
+        );
+        let quote = |value: &str| format!("\"{}\"", value.replace('"', "\"\""));
+        for escape in &static_result.escapes {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{:?},{},{},{},{},{},{}\n",
+                quote(&escape.location.file),
+                escape.location.line,
+                escape.location.column,
+                quote(&escape.location.function),
+                sarif_rule_id(&escape.escape_type),
+                quote(&escape.variable_name),
+                escape.confidence,
+                escape.rule.as_deref().unwrap_or_default(),
+                escape.rule_id.as_deref().unwrap_or_default(),
+                escape.input_tainted,
+                quote(escape.condition.as_deref().unwrap_or_default()),
+                quote(&escape.reason),
+                quote(&escape.data_flow.join(" → "))
+            ));
+        }
+        tokio::fs::write(dir.join("static_escapes.csv"), csv).await?;
+        Ok(())
+    }
+
+    fn format_static_counts(&self, static_result: &StaticAnalysisResult) -> String {
+        let summary = &static_result.summary;
+        format!(
+            r#"| Category | Count |
+|----------|-------|
+| Total Escapes | {} |
+| Return Escapes | {} |
+| Parameter Escapes | {} |
+| Global/Module Escapes | {} |
+| Closure Escapes | {} |
+| Heap Escapes | {} |
+| Spawn Escapes | {} |
+| Exit Escapes | {} |
+| High Confidence | {} |
+| Medium Confidence | {} |
+| Low Confidence | {} |
+| Suppressed | {} |
+"#,
+            summary.total_escapes,
+            summary.return_escapes,
+            summary.parameter_escapes,
+            summary.global_escapes,
+            summary.closure_escapes,
+            summary.heap_escapes,
+            summary.spawn_escapes,
+            summary.exit_escapes,
+            summary.high_confidence,
+            summary.medium_confidence,
+            summary.low_confidence,
+            summary.suppressed
+        )
+    }
+
+    fn format_call_graph_summary(&self, call_graph: Option<&CallGraph>) -> String {
+        let Some(call_graph) = call_graph else {
+            return String::new();
+        };
+        let local: Vec<&CallGraphNode> = call_graph.nodes.iter().filter(|node| node.line.is_some()).collect();
+        let spawning: Vec<String> = local
+            .iter()
+            .filter(|node| !node.spawn_sites.is_empty())
+            .map(|node| format!("`{}`", node.name))
+            .collect();
+        format!(
+            "\n**Call Graph:** {} function(s) reachable from `{}`, {} external call(s); spawn sites in {} ([callgraph.dot](callgraph.dot), [callgraph.json](callgraph.json))\n",
+            local.len(),
+            call_graph.root,
+            call_graph.nodes.len() - local.len(),
+            if spawning.is_empty() { "none".to_string() } else { spawning.join(", ") }
+        )
+    }
+
+    fn format_spawn_estimate(&self, estimate: Option<&SpawnEstimate>) -> String {
+        let Some(estimate) = estimate.filter(|estimate| !estimate.sites.is_empty()) else {
+            return String::new();
+        };
+        let sites: Vec<String> = estimate
+            .sites
+            .iter()
+            .map(|site| {
+                let count = site.per_call.map_or("?".to_string(), |count| count.to_string());
+                if site.loops.is_empty() {
+                    format!("{} at `{}` line {}", count, site.function, site.line)
+                } else {
+                    format!("{} at `{}` line {} (in `{}`)", count, site.function, site.line, site.loops.join("` › `"))
+                }
+            })
+            .collect();
+        format!(
+            "\n**Expected Spawns per Call:** {}: {}\n",
+            estimate.per_call.map_or("unbounded".to_string(), |count| count.to_string()),
+            sites.join("; ")
+        )
+    }
+
+    fn format_spawn_correlation(&self, response: &AnalyzeResponse) -> String {
+        let Some(correlation) = spawn_correlation(response) else {
+            return String::new();
+        };
+        let mut output = format!(
+            "\n### Static/Dynamic Spawn Correlation\n\n**Expected per call (static):** {}\n",
+            correlation.expected
+        );
+        if correlation.observed.is_empty() {
+            output.push_str("\nThe bridge does not report the threads or tasks an execution leaves running; nothing to compare.\n");
+            return output;
+        }
+
+        let mismatches: Vec<_> = correlation.mismatches().collect();
+        if mismatches.is_empty() {
+            output.push_str(&format!(
+                "\n✅ All {} execution(s) left {} thread(s)/task(s) running, as estimated.\n",
+                correlation.observed.len(),
+                correlation.expected
+            ));
+            return output;
+        }
+
+        output.push_str(&format!(
+            "\n⚠️ **Mismatch:** {} of {} execution(s) left a different number of threads/tasks running. \
+             Fewer usually means spawns were joined or a guarded branch was not taken; more means \
+             spawns the static pass did not see.\n\n| Input | Expected | Observed |\n|-------|----------|----------|\n",
+            mismatches.len(),
+            correlation.observed.len()
+        ));
+        for (result, observed) in mismatches.iter().take(MAX_CORRELATION_ROWS) {
+            output.push_str(&format!(
+                "| {} | {} | **{}** |\n",
+                self.escape_markdown_cell(&result.input_data, 40),
+                correlation.expected,
+                observed
+            ));
+        }
+        if mismatches.len() > MAX_CORRELATION_ROWS {
+            output.push_str(&format!("\n… and {} more\n", mismatches.len() - MAX_CORRELATION_ROWS));
+        }
+        output
+    }
+
+    fn format_escaped_work(&self, response: &AnalyzeResponse) -> String {
+        let rows: Vec<(&ExecutionResult, EscapedWork)> = response
+            .results
+            .iter()
+            .flat_map(|result| result.escape_details.escaped_work().into_iter().map(move |work| (result, work)))
+            .collect();
+        let Some(summary) = escaped_work_summary(response) else {
+            return String::new();
+        };
+        let mut output = format!(
+            "\n### Escaped Work Longevity\n\n{}\n\n| Input | Work | Outlived the call by |\n|-------|------|----------------------|\n",
+            summary
+        );
+        for (result, work) in rows.iter().take(MAX_CORRELATION_ROWS) {
+            let lifetime = if work.still_running {
+                format!("**{}ms, still running** at window end", work.outlived_ms)
+            } else {
+                format!("{}ms", work.outlived_ms)
+            };
+            output.push_str(&format!(
+                "| {} | {} | {} |\n",
+                self.escape_markdown_cell(&result.input_data, 40),
+                self.escape_markdown_cell(&work.label, 60),
+                lifetime
+            ));
+        }
+        if rows.len() > MAX_CORRELATION_ROWS {
+            output.push_str(&format!("\n… and {} more\n", rows.len() - MAX_CORRELATION_ROWS));
+        }
+        output
+    }
+
+    fn format_memory_growth(&self, response: &AnalyzeResponse) -> String {
+        memory_growth_summary(response)
+            .map(|summary| format!("\n### Memory Growth\n\n{}\n", summary))
+            .unwrap_or_default()
+    }
+
+    fn format_leaked_descriptors(&self, response: &AnalyzeResponse) -> String {
+        let Some(summary) = leaked_descriptor_summary(response) else {
+            return String::new();
+        };
+        let rows: Vec<(&ExecutionResult, LeakedDescriptor)> = response
+            .results
+            .iter()
+            .flat_map(|result| result.escape_details.leaked_descriptors().into_iter().map(move |fd| (result, fd)))
+            .collect();
+        let mut output = format!(
+            "\n### Leaked File Descriptors\n\n{}\n\n| Input | FD | Kind | Target |\n|-------|----|------|--------|\n",
+            summary
+        );
+        for (result, descriptor) in rows.iter().take(MAX_CORRELATION_ROWS) {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                self.escape_markdown_cell(&result.input_data, 40),
+                descriptor.fd,
+                descriptor.kind,
+                self.escape_markdown_cell(&descriptor.target, 60)
+            ));
+        }
+        if rows.len() > MAX_CORRELATION_ROWS {
+            output.push_str(&format!("\n… and {} more\n", rows.len() - MAX_CORRELATION_ROWS));
+        }
+        output
+    }
+
+    fn format_leaked_timers(&self, response: &AnalyzeResponse) -> String {
+        let Some(summary) = leaked_timer_summary(response) else {
+            return String::new();
+        };
+        let rows: Vec<(&ExecutionResult, LeakedTimer)> = response
+            .results
+            .iter()
+            .flat_map(|result| result.escape_details.leaked_timers().into_iter().map(move |timer| (result, timer)))
+            .collect();
+        let mut output = format!(
+            "\n### Leaked Timers\n\n{}\n\n| Input | Timer | Kind | Period | Callback |\n|-------|-------|------|--------|----------|\n",
+            summary
+        );
+        for (result, timer) in rows.iter().take(MAX_CORRELATION_ROWS) {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                self.escape_markdown_cell(&result.input_data, 40),
+                timer.id,
+                timer.kind,
+                timer.period_ms.map(|ms| format!("{:.0} ms", ms)).unwrap_or_else(|| "?".to_string()),
+                self.escape_markdown_cell(&timer.callback, 60)
+            ));
+        }
+        if rows.len() > MAX_CORRELATION_ROWS {
+            output.push_str(&format!("\n… and {} more\n", rows.len() - MAX_CORRELATION_ROWS));
+        }
+        output
+    }
+
+    async fn generate_captured_output(&self, dir: &Path, response: &AnalyzeResponse) -> Result<CapturedOutput> {
+        let mut captured = CapturedOutput::default();
+
+        if let Some(bridge_stderr) = response.bridge_stderr.as_deref().filter(|s| !s.trim().is_empty()) {
+            tokio::fs::write(dir.join("bridge_stderr.log"), bridge_stderr).await?;
+            captured.bridge_stderr = true;
+        }
+
+        for (i, result) in response.results.iter().enumerate() {
+            let files = self.generate_execution_artifacts(dir, i + 1, result).await?;
+            captured.executions.push(files);
+        }
+
+        Ok(captured)
+    }
+
+    /// Writes `executions/<n>/` for a run that produced output, crashed or escaped.
+    /// Returns the written paths relative to the session directory.
+    async fn generate_execution_artifacts(
+        &self,
+        dir: &Path,
+        number: usize,
+        result: &ExecutionResult,
+    ) -> Result<Vec<String>> {
+        let has_output = !result.stdout.trim().is_empty() || !result.stderr.trim().is_empty();
+        if !has_output && result.artifacts.is_empty() && !result.crashed && !result.escape_detected {
+            return Ok(Vec::new());
+        }
+
+        let rel_dir = format!("executions/{}", number);
+        let exec_dir = dir.join(&rel_dir);
+        tokio::fs::create_dir_all(&exec_dir).await?;
+        let mut files = Vec::new();
+
+        if !result.stdout.trim().is_empty() {
+            tokio::fs::write(exec_dir.join("stdout.log"), &result.stdout).await?;
+            files.push(format!("{}/stdout.log", rel_dir));
+        }
+        if !result.stderr.trim().is_empty() {
+            tokio::fs::write(exec_dir.join("stderr.log"), &result.stderr).await?;
+            files.push(format!("{}/stderr.log", rel_dir));
+        }
+
+        // Bridges may point at extra log files they produced; pull them into the session.
+        // Artifacts from different directories can share a file name, so later ones get
+        // a numeric suffix instead of overwriting earlier ones or the files written above.
+        let mut taken: HashSet<String> = ["stdout.log", "stderr.log", "result.json"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        for artifact in &result.artifacts {
+            let source = Path::new(artifact);
+            let Some(file_name) = source.file_name() else {
+                tracing::warn!("Skipping artifact {} of execution #{}: no file name", artifact, number);
+                continue;
+            };
+            let name = unique_artifact_name(&file_name.to_string_lossy(), &mut taken);
+            match tokio::fs::copy(source, exec_dir.join(&name)).await {
+                Ok(_) => files.push(format!("{}/{}", rel_dir, name)),
+                Err(e) => tracing::warn!("Could not copy artifact {} of execution #{}: {}", artifact, number, e),
+            }
+        }
+
+        let mut recorded = result.clone();
+        recorded.artifacts = files.clone();
+        tokio::fs::write(exec_dir.join("result.json"), serde_json::to_string_pretty(&recorded)?).await?;
+        files.push(format!("{}/result.json", rel_dir));
+
+        Ok(files)
+    }
+
+    async fn generate_csv(&self, dir: &Path, response: &AnalyzeResponse) -> Result<()> {
         let path = dir.join("results.csv");
 
-        let mut csv = String::from("input,success,crashed,escape_detected,escape_summary,error,execution_time_ms\n");
+        let mut csv = String::from("input,success,crashed,escape_detected,escape_summary,error_kind,error,execution_time_ms\n");
 
         for result in &response.results {
             csv.push_str(&format!(
-                "\"{}\",{},{},{},\"{}\",\"{}\",{}\n",
+                "\"{}\",{},{},{},\"{}\",{},\"{}\",{}\n",
                 result.input_data.replace('"', "\"\""),
                 result.success,
                 result.crashed,
                 result.escape_detected,
                 result.escape_details.summary().replace('"', "\"\""),
-                result.error.replace('"', "\"\""),
+                result.error.as_ref().map(|e| e.kind.as_str()).unwrap_or_default(),
+                result.error.as_ref().map(|e| e.message.replace('"', "\"\"")).unwrap_or_default(),
                 result.execution_time_ms
             ));
         }
@@ -166,7 +856,63 @@ This report shows the results of static object escape analysis for the target fu
         Ok(())
     }
 
-    async fn generate_vulnerability_report(&self, dir: &PathBuf, response: &AnalyzeResponse) -> Result<()> {
+    async fn generate_json(&self, dir: &Path, response: &AnalyzeResponse, target: &str) -> Result<()> {
+        write_json_report(&dir.join("report.json"), response, target).await
+    }
+
+    /// SARIF 2.1.0 log with the catalog rule of each static escape (its `EscapeType`
+    /// when no rule covers it) and one rule per vulnerability type for dynamic
+    /// findings, which are placed on the target function.
+    async fn generate_sarif(&self, dir: &Path, response: &AnalyzeResponse, target: &str) -> Result<()> {
+        let mut descriptors: BTreeMap<String, serde_json::Value> = BTreeMap::new();
+        let mut results = Vec::new();
+
+        if let Some(static_result) = &response.static_analysis {
+            for escape in &static_result.escapes {
+                let catalog = escape.rule_id.as_deref().and_then(rules::find);
+                let rule_id = catalog.map_or_else(|| sarif_rule_id(&escape.escape_type), |rule| rule.id);
+                descriptors.entry(rule_id.to_string()).or_insert_with(|| match catalog {
+                    Some(rule) => sarif_catalog_rule(rule),
+                    None => sarif_rule(rule_id, sarif_rule_description(&escape.escape_type)),
+                });
+                results.push(sarif_static_result(rule_id, escape));
+            }
+        }
+
+        let (file, line) = target_location(response, target);
+        for vuln in &response.vulnerabilities {
+            let rule_id = format!("dynamic/{}", vuln.vulnerability_type);
+            descriptors.entry(rule_id.clone()).or_insert_with(|| {
+                sarif_rule(&rule_id, &format!("Runtime finding: {}", vuln.vulnerability_type.replace('_', " ")))
+            });
+            results.push(serde_json::json!({
+                "ruleId": rule_id,
+                "level": sarif_severity_level(&vuln.severity),
+                "message": { "text": format!("Input {:?}: {}", vuln.input, vuln.description) },
+                "locations": [sarif_location(&file, line, 1)],
+            }));
+        }
+
+        let log = serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "graphene-ha",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": descriptors.into_values().collect::<Vec<_>>(),
+                    }
+                },
+                "automationDetails": { "id": format!("graphene-ha/{}/", response.language) },
+                "results": results,
+            }]
+        });
+        tokio::fs::write(dir.join("results.sarif"), serde_json::to_string_pretty(&log)?).await?;
+        Ok(())
+    }
+
+    async fn generate_vulnerability_report(&self, dir: &Path, response: &AnalyzeResponse) -> Result<()> {
         if response.vulnerabilities.is_empty() {
             return Ok(());
         }
@@ -225,12 +971,14 @@ This report shows the results of static object escape analysis for the target fu
         output
     }
 
-    fn format_results(&self, response: &AnalyzeResponse) -> String {
-        let mut output = String::from("| Input | Status | Escape | Details | Error | Suggested Action |\n");
-        output.push_str("|-------|--------|--------|----------|-------|------------------|\n");
+    fn format_results(&self, response: &AnalyzeResponse, captured: &CapturedOutput) -> String {
+        let mut output = String::from("| Input | Status | Escape | Details | Error | Suggested Action | Artifacts |\n");
+        output.push_str("|-------|--------|--------|----------|-------|------------------|-----------|\n");
 
-        for result in &response.results {
-            let status = if result.crashed {
+        for (i, result) in response.results.iter().enumerate() {
+            let status = if result.not_executed {
+                "⏸ NOT RUN"
+            } else if result.crashed {
                 "❌ CRASH"
             } else if result.success {
                 "✅ OK"
@@ -257,20 +1005,45 @@ This report shows the results of static object escape analysis for the target fu
                 self.escape_markdown_cell(error_summary.hint, 120)
             };
 
+            let artifacts_cell = match captured.executions.get(i) {
+                Some(files) if !files.is_empty() => format!("[#{}](executions/{}/)", i + 1, i + 1),
+                _ => "-".to_string(),
+            };
+
             output.push_str(&format!(
-                "| `{}` | {} | {} | {} | {} | {} |\n",
+                "| `{}` | {} | {} | {} | {} | {} | {} |\n",
                 self.escape_markdown_cell(&result.input_data, 60),
                 status,
                 escape,
                 self.escape_markdown_cell(&result.escape_details.summary(), 80),
                 error_cell,
-                action_cell
+                action_cell,
+                artifacts_cell
             ));
         }
 
         output
     }
 
+    fn format_captured_output(&self, captured: &CapturedOutput) -> String {
+        let execution_dirs = captured.executions.iter().filter(|files| !files.is_empty()).count();
+        if !captured.bridge_stderr && execution_dirs == 0 {
+            return "No bridge stderr or per-execution artifacts were captured.".to_string();
+        }
+
+        let mut output = String::new();
+        if captured.bridge_stderr {
+            output.push_str("- [`bridge_stderr.log`](bridge_stderr.log) - stderr emitted by the analyzer bridge process\n");
+        }
+        if execution_dirs > 0 {
+            output.push_str(&format!(
+                "- [`executions/`](executions/) - stdout, stderr and result details for {} execution(s) that produced output, crashed or escaped\n",
+                execution_dirs
+            ));
+        }
+        output
+    }
+
     fn format_error_diagnostics(&self, response: &AnalyzeResponse) -> String {
         let mut category_counts: BTreeMap<&'static str, usize> = BTreeMap::new();
         let mut sample_entries = String::new();
@@ -279,7 +1052,7 @@ This report shows the results of static object escape analysis for the target fu
         let error_results: Vec<&ExecutionResult> = response
             .results
             .iter()
-            .filter(|r| r.crashed || !r.error.trim().is_empty())
+            .filter(|r| r.crashed || r.error.is_some())
             .collect();
 
         if error_results.is_empty() {
@@ -319,82 +1092,72 @@ This report shows the results of static object escape analysis for the target fu
         output
     }
 
-    fn describe_error<'a>(&self, result: &'a ExecutionResult) -> ErrorDiagnosis<'a> {
-        let raw = if result.error.trim().is_empty() {
-            if result.crashed {
-                "Execution failed without an error message"
-            } else {
-                ""
-            }
-        } else {
-            result.error.trim()
+    fn describe_error(&self, result: &ExecutionResult) -> ErrorDiagnosis {
+        let (kind, message) = match &result.error {
+            Some(error) => (error.kind, error.message.as_str()),
+            None if result.crashed => (AnalysisErrorKind::RuntimeCrash, "Execution failed without an error message"),
+            None => (AnalysisErrorKind::Unknown, ""),
         };
 
-        let lower = raw.to_lowercase();
-
-        let (category, hint) = if lower.contains("timeout") || lower.contains("timed out") || lower.contains("exceeded") {
-            (
-                "Timeout",
-                "Increase timeout only after checking for blocked joins/awaits and non-terminating loops.",
-            )
-        } else if lower.contains("target resolution")
-            || lower.contains("not found")
-            || lower.contains("failed to load")
-            || lower.contains("invalid target")
-            || lower.contains("nosuchmethod")
-            || lower.contains("module not found")
-        {
-            (
-                "Target Resolution",
-                "Verify target path/signature and confirm the function exists in the selected language module.",
-            )
-        } else if lower.contains("protocol/input")
-            || lower.contains("json")
-            || lower.contains("parse")
-            || lower.contains("stdin")
-            || lower.contains("protocol")
-        {
-            (
-                "Protocol/Input",
-                "Validate request format and ensure bridge stdin/stdout JSON protocol remains unchanged.",
-            )
-        } else if lower.contains("environment")
-            || lower.contains("permission denied")
-            || lower.contains("not available")
-            || lower.contains("not found in path")
-            || lower.contains("command not found")
-            || lower.contains("missing tools")
-        {
-            (
-                "Environment",
-                "Check runtime/compiler dependencies and executable availability in PATH.",
-            )
-        } else if lower.contains("runtime crash")
-            || result.crashed
-            || lower.contains("panic")
-            || lower.contains("exception")
-            || lower.contains("traceback")
-            || lower.contains("segmentation")
-        {
-            (
-                "Runtime Crash",
-                "Inspect stack trace and target function side effects; rerun in dynamic mode with verbose logging.",
-            )
-        } else {
-            (
-                "Unknown",
-                "Review full bridge output and rerun with --verbose to capture additional diagnostics.",
-            )
+        let hint = match kind {
+            AnalysisErrorKind::BridgeHang => {
+                "The bridge stopped sending heartbeats and was killed; check the target for deadlocks that block the whole process."
+            }
+            AnalysisErrorKind::Timeout => {
+                "Increase timeout only after checking for blocked joins/awaits and non-terminating loops."
+            }
+            AnalysisErrorKind::BridgeTimeout => {
+                "The bridge ran past its overall deadline and was killed; check for escaped non-daemon threads or handles that keep the process alive."
+            }
+            AnalysisErrorKind::TargetNotFound => {
+                "Verify target path/signature and confirm the function exists in the selected language module."
+            }
+            AnalysisErrorKind::LoadFailed => {
+                "The target was found but failed to load; check imports, compile errors and missing dependencies."
+            }
+            AnalysisErrorKind::VersionMismatch => {
+                "Rebuild the language bridge so its analyzer version matches the orchestrator."
+            }
+            AnalysisErrorKind::UnsupportedFeature => {
+                "The bridge does not support this target or mode; run the other analysis mode or another analyzer."
+            }
+            AnalysisErrorKind::Protocol => {
+                "Validate request format and ensure bridge stdin/stdout JSON protocol remains unchanged."
+            }
+            AnalysisErrorKind::Environment => {
+                "Check runtime/compiler dependencies and executable availability in PATH."
+            }
+            AnalysisErrorKind::BridgeCrash => {
+                "The bridge process failed before reporting results; see bridge_stderr.log for its output."
+            }
+            AnalysisErrorKind::RuntimeCrash if message.starts_with("process_exit") => {
+                "The target exited the bridge process; the remaining inputs ran in a new process. Keep exit calls out of library code so pending joins and shutdowns can run."
+            }
+            AnalysisErrorKind::RuntimeCrash => {
+                "Inspect stack trace and target function side effects; rerun in dynamic mode with verbose logging."
+            }
+            AnalysisErrorKind::OracleFailed => {
+                "The output was wrong rather than missing; look for partial or empty results from work that finished after the function did."
+            }
+            AnalysisErrorKind::ResourceLimit => {
+                "The bridge was killed at a `--bridge-*` resource cap; look for a runaway loop, spawn loop or allocation in the target, or raise the cap."
+            }
+            AnalysisErrorKind::Interrupted => {
+                "The run was interrupted before this execution finished; analyze the target again for complete results."
+            }
+            AnalysisErrorKind::Unknown => {
+                "Review full bridge output and rerun with --verbose to capture additional diagnostics."
+            }
         };
 
         ErrorDiagnosis {
-            category,
-            short_message: self.first_line(raw),
+            category: kind.label(),
+            short_message: self.first_line(message).to_string(),
             hint,
         }
     }
 
-    fn first_line<'a>(&self, message: &'a str) -> String {
+    fn first_line(&self, message: &str) -> String {
         message
             .lines()
             .find(|line| !line.trim().is_empty())
@@ -406,8 +1169,7 @@ This report shows the results of static object escape analysis for the target fu
     fn escape_markdown_cell(&self, value: &str, max_chars: usize) -> String {
         let normalized = value
             .replace('|', "\\|")
-            .replace('\n', " ")
-            .replace('\r', " ")
+            .replace(['\n', '\r'], " ")
             .trim()
             .to_string();
 
@@ -423,6 +1185,9 @@ This report shows the results of static object escape analysis for the target fu
     }
 
     fn format_escape_details(&self, details: &crate::protocol::EscapeDetails) -> String {
+        if let Some(race) = &details.race {
+            return self.format_race_evidence(race);
+        }
         if details.is_empty() {
             return "No object escapes detected".to_string();
         }
@@ -452,47 +1217,439 @@ This report shows the results of static object escape analysis for the target fu
         output
     }
 
+    fn format_race_evidence(&self, race: &crate::protocol::RaceEvidence) -> String {
+        let mut output = format!("**Runs Compared:** {}\n\n**Distinct Outputs:**\n", race.runs);
+        for text in &race.outputs {
+            output.push_str(&format!("- `{}`\n", self.escape_markdown_cell(text, 120)));
+        }
+        if !race.side_effects.is_empty() {
+            output.push_str("\n**Distinct Side Effects (after the first run):**\n");
+            for summary in &race.side_effects {
+                output.push_str(&format!("- {}\n", summary));
+            }
+        }
+        output.push_str("\n**Shared State:**\n");
+        for evidence in &race.shared_state {
+            output.push_str(&format!("- {}\n", evidence));
+        }
+        output
+    }
+
     fn format_static_escapes(&self, escapes: &[crate::protocol::StaticEscape]) -> String {
         if escapes.is_empty() {
             return "✅ No escapes detected by static analysis".to_string();
         }
 
-        let mut output = String::from("| Type | Variable | Location | Reason | Confidence |\n");
-        output.push_str("|------|----------|----------|--------|------------|\n");
+        let mut output = String::from("| Type | Variable | Location | Reason | Confidence | Input |\n");
+        output.push_str("|------|----------|----------|--------|------------|-------|\n");
 
         for escape in escapes {
-            let escape_type = match escape.escape_type {
-                crate::protocol::EscapeType::ReturnEscape => "Return",
-                crate::protocol::EscapeType::ParameterEscape => "Parameter",
-                crate::protocol::EscapeType::GlobalEscape => "Global",
-                crate::protocol::EscapeType::ClosureEscape => "Closure",
-                crate::protocol::EscapeType::HeapEscape => "Heap",
-                crate::protocol::EscapeType::UnknownEscape => "Unknown",
-            };
-
-            let confidence = match escape.confidence {
-                crate::protocol::ConfidenceLevel::High => "🔴 High",
-                crate::protocol::ConfidenceLevel::Medium => "🟡 Medium",
-                crate::protocol::ConfidenceLevel::Low => "🟢 Low",
-            };
-
             output.push_str(&format!(
-                "| {} | `{}` | {}:{} | {} | {} |\n",
-                escape_type,
+                "| {} | `{}` | {}:{} | {} | {} | {} |\n",
+                escape_type_label(&escape.escape_type),
                 escape.variable_name,
                 escape.location.file,
                 escape.location.line,
                 self.escape_markdown_cell(&escape.reason, 60),
-                confidence
+                confidence_label(&escape.confidence),
+                if escape.input_tainted { "⚠️ tainted" } else { "" }
             ));
         }
 
+        let tainted: Vec<_> = escapes.iter().filter(|escape| escape.input_tainted).collect();
+        if !tainted.is_empty() {
+            output.push_str("\n**Input flows:**\n\n");
+            for escape in tainted {
+                output.push_str(&format!(
+                    "- `{}` (line {}): {}\n",
+                    escape.variable_name,
+                    escape.location.line,
+                    escape.taint_flow.join(" → ")
+                ));
+            }
+        }
+
+        let traced: Vec<_> = escapes.iter().filter(|escape| !escape.data_flow.is_empty()).collect();
+        if !traced.is_empty() {
+            output.push_str("\n**Data flow:**\n\n");
+            for escape in traced {
+                output.push_str(&format!(
+                    "- `{}` (line {}): {}\n",
+                    escape.variable_name,
+                    escape.location.line,
+                    escape.data_flow.join(" → ")
+                ));
+            }
+        }
+
+        let conditional: Vec<_> = escapes.iter().filter(|escape| escape.condition.is_some()).collect();
+        if !conditional.is_empty() {
+            output.push_str("\n**Conditional escapes:**\n\n");
+            for escape in conditional {
+                output.push_str(&format!(
+                    "- `{}` (line {}) only when `{}`\n",
+                    escape.variable_name,
+                    escape.location.line,
+                    escape.condition.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+
         output
     }
 }
 
-struct ErrorDiagnosis<'a> {
+/// Lines shown above and below each finding in `static_analysis.md`.
+const STATIC_SNIPPET_CONTEXT: usize = 2;
+
+/// `name`, or `stem-2.ext`, `stem-3.ext`, ... when an earlier file already took it.
+fn unique_artifact_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > 0 => (&name[..dot], &name[dot..]),
+        _ => (name, ""),
+    };
+    let mut candidate = name.to_string();
+    let mut suffix = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{}-{}{}", stem, suffix, extension);
+        suffix += 1;
+    }
+    taken.insert(candidate.clone());
+    candidate
+}
+
+fn escape_type_label(escape_type: &EscapeType) -> &'static str {
+    match escape_type {
+        EscapeType::ReturnEscape => "Return",
+        EscapeType::ParameterEscape => "Parameter",
+        EscapeType::GlobalEscape => "Global",
+        EscapeType::ClosureEscape => "Closure",
+        EscapeType::HeapEscape => "Heap",
+        EscapeType::SpawnEscape => "Spawn",
+        EscapeType::ExitEscape => "Exit",
+        EscapeType::UnknownEscape => "Unknown",
+    }
+}
+
+fn confidence_label(confidence: &ConfidenceLevel) -> &'static str {
+    match confidence {
+        ConfidenceLevel::High => "🔴 High",
+        ConfidenceLevel::Medium => "🟡 Medium",
+        ConfidenceLevel::Low => "🟢 Low",
+    }
+}
+
+/// Rows of mismatching executions shown in the spawn correlation table.
+const MAX_CORRELATION_ROWS: usize = 10;
+
+/// Static spawn estimate next to what each execution left running (Both mode).
+pub(crate) struct SpawnCorrelation<'a> {
+    pub expected: usize,
+    /// Executions whose bridge reports spawned threads/tasks, with that count.
+    pub observed: Vec<(&'a ExecutionResult, usize)>,
+}
+
+impl<'a> SpawnCorrelation<'a> {
+    pub fn mismatches(&self) -> impl Iterator<Item = &(&'a ExecutionResult, usize)> {
+        self.observed.iter().filter(|(_, observed)| *observed != self.expected)
+    }
+}
+
+/// `None` outside Both mode or when the static estimate is unbounded.
+pub(crate) fn spawn_correlation(response: &AnalyzeResponse) -> Option<SpawnCorrelation<'_>> {
+    if response.analysis_mode != AnalysisMode::Both {
+        return None;
+    }
+    let expected = response.static_analysis.as_ref()?.spawn_estimate.as_ref()?.per_call?;
+    let observed = response
+        .results
+        .iter()
+        .filter(|result| !result.not_executed && !result.crashed)
+        .filter_map(|result| result.escape_details.spawned_count().map(|count| (result, count)))
+        .collect();
+    Some(SpawnCorrelation { expected, observed })
+}
+
+fn sarif_rule_id(escape_type: &EscapeType) -> &'static str {
+    match escape_type {
+        EscapeType::ReturnEscape => "return-escape",
+        EscapeType::ParameterEscape => "parameter-escape",
+        EscapeType::GlobalEscape => "global-escape",
+        EscapeType::ClosureEscape => "closure-escape",
+        EscapeType::HeapEscape => "heap-escape",
+        EscapeType::SpawnEscape => "spawn-escape",
+        EscapeType::ExitEscape => "exit-escape",
+        EscapeType::UnknownEscape => "unknown-escape",
+    }
+}
+
+fn sarif_rule_description(escape_type: &EscapeType) -> &'static str {
+    match escape_type {
+        EscapeType::ReturnEscape => "Local object escapes through the return value",
+        EscapeType::ParameterEscape => "Local object escapes as an argument to another function",
+        EscapeType::GlobalEscape => "Local object is stored in global or module scope",
+        EscapeType::ClosureEscape => "Local object is captured by a closure, thread or task",
+        EscapeType::HeapEscape => "Local object is stored in a heap-allocated container",
+        EscapeType::SpawnEscape => "Threads or tasks are started repeatedly by the loops around a spawn",
+        EscapeType::ExitEscape => "The process exits from within the call, skipping cleanup of the work it started",
+        EscapeType::UnknownEscape => "Local object escapes through an unrecognized pattern",
+    }
+}
+
+fn sarif_rule(id: &str, description: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "shortDescription": { "text": description },
+    })
+}
+
+/// Descriptor of a catalog rule, with its remediation as the help text.
+fn sarif_catalog_rule(rule: &Rule) -> serde_json::Value {
+    serde_json::json!({
+        "id": rule.id,
+        "name": rule.name,
+        "shortDescription": { "text": rule.title },
+        "fullDescription": { "text": rule.description },
+        "help": { "text": rule.remediation },
+    })
+}
+
+fn sarif_static_result(rule_id: &str, escape: &StaticEscape) -> serde_json::Value {
+    let level = match escape.confidence {
+        ConfidenceLevel::High => "error",
+        ConfidenceLevel::Medium => "warning",
+        ConfidenceLevel::Low => "note",
+    };
+    let mut properties = serde_json::json!({
+        "variable": escape.variable_name,
+        "function": escape.location.function,
+        "confidence": format!("{:?}", escape.confidence),
+    });
+    if let Some(rule) = &escape.rule {
+        properties["rule"] = serde_json::json!(rule);
+    }
+    if let Some(condition) = &escape.condition {
+        properties["condition"] = serde_json::json!(condition);
+    }
+    if !escape.data_flow.is_empty() {
+        properties["dataFlow"] = serde_json::json!(escape.data_flow);
+    }
+    let mut result = serde_json::json!({
+        "ruleId": rule_id,
+        "level": level,
+        "message": { "text": format!("`{}`: {}", escape.variable_name, escape.reason) },
+        "locations": [sarif_location(&escape.location.file, escape.location.line, escape.location.column)],
+        "properties": properties,
+    });
+    if let Some(fix) = escape.suggested_fix.as_ref().filter(|fix| !fix.edits.is_empty()) {
+        result["fixes"] = serde_json::json!([sarif_fix(&escape.location.file, fix)]);
+    }
+    result
+}
+
+/// A suggested fix as a SARIF fix: each edit replaces whole lines, or inserts before a
+/// line through an empty deleted region.
+fn sarif_fix(file: &str, fix: &SuggestedFix) -> serde_json::Value {
+    let replacements: Vec<serde_json::Value> = fix
+        .edits
+        .iter()
+        .map(|edit| {
+            serde_json::json!({
+                "deletedRegion": {
+                    "startLine": edit.line,
+                    "startColumn": 1,
+                    "endLine": edit.line + edit.replaced_lines,
+                    "endColumn": 1,
+                },
+                "insertedContent": { "text": edit.text },
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "description": { "text": fix.description },
+        "artifactChanges": [{
+            "artifactLocation": { "uri": sarif_uri(file) },
+            "replacements": replacements,
+        }],
+    })
+}
+
+fn sarif_severity_level(severity: &str) -> &'static str {
+    match severity.to_lowercase().as_str() {
+        "critical" | "high" => "error",
+        "medium" => "warning",
+        _ => "note",
+    }
+}
+
+/// SARIF lines and columns are 1-based; paths are made relative to the working
+/// directory so code scanning can match them to repository files.
+fn sarif_location(file: &str, line: usize, column: usize) -> serde_json::Value {
+    serde_json::json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": sarif_uri(file) },
+            "region": { "startLine": line.max(1), "startColumn": column.max(1) },
+        }
+    })
+}
+
+fn sarif_uri(file: &str) -> String {
+    let path = Path::new(file);
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf());
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// Source file and definition line of the target, for findings without a location of
+/// their own. Falls back to the file named in the target and line 1.
+fn target_location(response: &AnalyzeResponse, target: &str) -> (String, usize) {
+    let function = callgraph::target_function(target);
+    if let Some(static_result) = &response.static_analysis {
+        let line = static_result
+            .call_graph
+            .as_ref()
+            .and_then(|graph| graph.nodes.iter().find(|node| node.name == function))
+            .and_then(|node| node.line)
+            .unwrap_or(1);
+        return (static_result.source_file.clone(), line);
+    }
+    let file = target.rsplit_once(':').map(|(file, _)| file).unwrap_or(target);
+    (file.to_string(), 1)
+}
+
+/// How long observed left-behind work lasted: stragglers that finished within the
+/// observation window against work still running when it ended. `None` when nothing
+/// was observed.
+pub(crate) fn escaped_work_summary(response: &AnalyzeResponse) -> Option<String> {
+    let work: Vec<EscapedWork> = response
+        .results
+        .iter()
+        .flat_map(|result| result.escape_details.escaped_work())
+        .collect();
+    if work.is_empty() {
+        return None;
+    }
+    let (running, finished): (Vec<&EscapedWork>, Vec<&EscapedWork>) = work.iter().partition(|work| work.still_running);
+    let mut parts = Vec::new();
+    if let Some(longest) = finished.iter().map(|work| work.outlived_ms).max() {
+        parts.push(format!(
+            "{} finished after the call returned (longest {}ms)",
+            finished.len(),
+            longest
+        ));
+    }
+    if !running.is_empty() {
+        parts.push(format!("{} still running when the observation window ended", running.len()));
+    }
+    Some(parts.join("; "))
+}
+
+/// Lowercase `[a-z0-9-]` form of a target for directory names: its file name and
+/// function (`lib.rs:process` becomes `lib-rs-process`), at most 48 characters.
+fn slug(target: &str) -> String {
+    let name = target.rsplit(['/', '\\']).next().unwrap_or(target);
+    let mut slug = String::new();
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.truncate(48);
+    slug.trim_end_matches('-').to_string()
+}
+
+#[cfg(unix)]
+fn replace_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    let staging = link.with_file_name(format!(".latest-{}", Uuid::new_v4()));
+    std::os::unix::fs::symlink(target, &staging)?;
+    std::fs::rename(&staging, link).inspect_err(|_| {
+        let _ = std::fs::remove_file(&staging);
+    })
+}
+
+#[cfg(not(unix))]
+fn replace_symlink(_target: &Path, _link: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "the latest link is only made on Unix"))
+}
+
+/// Versioned `report.json` contents: the full response with host metadata.
+pub(crate) async fn write_json_report(path: &Path, response: &AnalyzeResponse, target: &str) -> Result<()> {
+    let report = JsonReport::new(response, target);
+    tokio::fs::write(path, serde_json::to_string_pretty(&report)?).await?;
+    Ok(())
+}
+
+/// One line on how memory growth across repeats was judged, or `None` when no bridge
+/// measured it.
+pub(crate) fn memory_growth_summary(response: &AnalyzeResponse) -> Option<String> {
+    let memory = response.summary.memory.as_ref()?;
+    if memory.measured_inputs == 0 {
+        return Some("not judged; needs --repeat 2 or more".to_string());
+    }
+    Some(format!(
+        "{} probable leak(s), {} input(s) with unsustained growth (noise) of {} measured (threshold {})",
+        memory.probable_leaks,
+        memory.noise,
+        memory.measured_inputs,
+        format_bytes(memory.threshold_bytes as i64)
+    ))
+}
+
+/// `bytes` in B, KB or MB (powers of 1024).
+pub(crate) fn format_bytes(bytes: i64) -> String {
+    let magnitude = bytes.unsigned_abs() as f64;
+    if magnitude >= 1024.0 * 1024.0 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if magnitude >= 1024.0 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// One line on the descriptors executions left open, by kind, or `None` when no bridge
+/// reported any.
+pub(crate) fn leaked_descriptor_summary(response: &AnalyzeResponse) -> Option<String> {
+    let mut by_kind: BTreeMap<String, usize> = BTreeMap::new();
+    for descriptor in response.results.iter().flat_map(|result| result.escape_details.leaked_descriptors()) {
+        *by_kind.entry(descriptor.kind).or_default() += 1;
+    }
+    if by_kind.is_empty() {
+        return None;
+    }
+    let total: usize = by_kind.values().sum();
+    let kinds: Vec<String> = by_kind.iter().map(|(kind, count)| format!("{} {}", count, kind)).collect();
+    Some(format!("{} left open ({})", total, kinds.join(", ")))
+}
+
+/// One line on the timers executions left scheduled, by kind, or `None` when no bridge
+/// reported any.
+pub(crate) fn leaked_timer_summary(response: &AnalyzeResponse) -> Option<String> {
+    let mut by_kind: BTreeMap<String, usize> = BTreeMap::new();
+    for timer in response.results.iter().flat_map(|result| result.escape_details.leaked_timers()) {
+        *by_kind.entry(timer.kind).or_default() += 1;
+    }
+    if by_kind.is_empty() {
+        return None;
+    }
+    let total: usize = by_kind.values().sum();
+    let kinds: Vec<String> = by_kind.iter().map(|(kind, count)| format!("{} {}", count, kind)).collect();
+    Some(format!("{} still scheduled ({})", total, kinds.join(", ")))
+}
+
+#[derive(Default)]
+struct CapturedOutput {
+    bridge_stderr: bool,
+    /// Session-relative artifact paths, indexed like `AnalyzeResponse::results`.
+    executions: Vec<Vec<String>>,
+}
+
+struct ErrorDiagnosis {
     category: &'static str,
     short_message: String,
-    hint: &'a str,
+    hint: &'static str,
 }
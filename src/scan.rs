@@ -0,0 +1,393 @@
+//! Project scan
+//! `graphene-ha scan <dir>` runs static analysis over every source file of an arbitrary
+//! project, where `run-all` only knows the crate's own `tests/<language>` layout. Files
+//! are picked by extension, leaving out hidden paths, whatever `.gitignore` (and git's
+//! other exclude files) ignores, and files a `.grapheneignore` pattern covers entirely.
+//! Every function of a file is analyzed as its own target, as in the language server,
+//! and the findings of all files land in one report: `README.md` with counts per
+//! language and file and every finding, and `scan.json` for tooling. With
+//! `--since <ref>` only the files and functions changed since the ref are analyzed.
+
+use crate::changes::ChangedLines;
+use crate::metrics;
+use crate::orchestrator::{init_logging, init_quiet_logging, init_stderr_logging, normalize_language_filter, run_static_analysis, OutputFormat};
+use crate::protocol::{AnalysisMode, ConfidenceLevel, StaticEscape};
+use crate::static_analyzer::callgraph;
+use crate::static_analyzer::suppress::Suppressions;
+use crate::static_analyzer::StaticAnalyzerFactory;
+use anyhow::{Context, Result};
+use chrono::Local;
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Version of the `scan.json` layout, bumped like `report.json`'s.
+pub const SCAN_SCHEMA_VERSION: u32 = 1;
+
+/// Source extensions and the language analyzing them.
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("py", "python"),
+    ("js", "javascript"),
+    ("mjs", "javascript"),
+    ("java", "java"),
+    ("go", "go"),
+    ("rs", "rust"),
+];
+
+/// Contents of `scan.json`
+#[derive(Serialize)]
+struct ScanReport {
+    schema_version: u32,
+    generated_at: String,
+    root: String,
+    /// Git ref the scan was limited to changes since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    since: Option<String>,
+    summary: ScanSummary,
+    files: Vec<ScannedFile>,
+}
+
+#[derive(Default, Serialize)]
+struct ScanSummary {
+    files: usize,
+    functions: usize,
+    escapes: usize,
+    high_confidence: usize,
+    /// Functions whose analysis failed
+    failed: usize,
+    /// Files left out by `.grapheneignore`
+    ignored: usize,
+    by_language: BTreeMap<String, LanguageCounts>,
+}
+
+#[derive(Default, Serialize)]
+struct LanguageCounts {
+    files: usize,
+    functions: usize,
+    escapes: usize,
+}
+
+#[derive(Serialize)]
+struct ScannedFile {
+    /// Path relative to the scanned directory
+    path: String,
+    language: String,
+    functions: usize,
+    failed: usize,
+    escapes: Vec<StaticEscape>,
+}
+
+/// Static findings of every function in one file.
+pub(crate) struct FileEscapes {
+    pub functions: usize,
+    /// Functions whose analysis failed
+    pub failed: usize,
+    pub escapes: Vec<StaticEscape>,
+}
+
+/// Analyzes each of `functions` in `file` as its own target. Findings reached through
+/// a helper, which repeat on each function calling it, are kept once.
+pub(crate) async fn file_escapes(file: &str, language: &str, functions: Vec<String>) -> FileEscapes {
+    let mut found = FileEscapes {
+        functions: functions.len(),
+        failed: 0,
+        escapes: Vec::new(),
+    };
+    for function in functions {
+        let target = format!("{}:{}", file, function);
+        let started = Instant::now();
+        let response = match run_static_analysis(&target, Some(language), AnalysisMode::Static).await {
+            Ok(response) => {
+                metrics::record_analysis(&response, started.elapsed());
+                response
+            }
+            Err(e) => {
+                warn!("Static analysis of {} failed: {:#}", target, e);
+                found.failed += 1;
+                continue;
+            }
+        };
+        for escape in response.static_analysis.into_iter().flat_map(|result| result.escapes) {
+            let repeated = found.escapes.iter().any(|other| {
+                other.location.line == escape.location.line
+                    && other.variable_name == escape.variable_name
+                    && other.reason == escape.reason
+            });
+            if !repeated {
+                found.escapes.push(escape);
+            }
+        }
+    }
+    found
+}
+
+/// Scans `dir` and writes the aggregated report under `output_dir/scan`.
+pub async fn run_scan(
+    dir: PathBuf,
+    output_dir: PathBuf,
+    language: Option<String>,
+    verbose: bool,
+    output_format: OutputFormat,
+    no_report: bool,
+    since: Option<String>,
+) -> Result<()> {
+    match output_format {
+        OutputFormat::Text => init_logging(verbose),
+        OutputFormat::Json => init_stderr_logging(verbose),
+        OutputFormat::Quiet => init_quiet_logging(),
+    }
+    if !dir.is_dir() {
+        anyhow::bail!("{} is not a directory", dir.display());
+    }
+    let language = language.as_deref().map(normalize_language_filter);
+    let changes = since.as_deref().map(|reference| ChangedLines::since(reference, &dir)).transpose()?;
+
+    let mut summary = ScanSummary::default();
+    let mut available: BTreeMap<&str, bool> = BTreeMap::new();
+    let mut files = Vec::new();
+    for (path, file_language) in discover_sources(&dir, language.as_deref()) {
+        if changes.as_ref().is_some_and(|changes| !changes.touches_file(&path)) {
+            continue;
+        }
+        let file = path.to_string_lossy().into_owned();
+        if Suppressions::load(file_language, &file).ignores_file() {
+            info!("Skipping {} (.grapheneignore)", file);
+            summary.ignored += 1;
+            continue;
+        }
+        let usable = *available.entry(file_language).or_insert_with(|| {
+            let usable = StaticAnalyzerFactory::create(file_language).is_some_and(|analyzer| analyzer.is_available());
+            if !usable {
+                warn!("Skipping {} files: the static analyzer is not available", file_language);
+            }
+            usable
+        });
+        if !usable {
+            continue;
+        }
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                warn!("Failed to read {}: {}", file, e);
+                continue;
+            }
+        };
+        let mut functions = callgraph::function_names(file_language, &source);
+        if let Some(changes) = &changes {
+            functions = changes.changed_functions(&path, file_language, &source, functions);
+            if functions.is_empty() {
+                continue;
+            }
+        }
+        info!("Scanning {}", file);
+        let found = file_escapes(&file, file_language, functions).await;
+        files.push(ScannedFile {
+            path: path.strip_prefix(&dir).unwrap_or(&path).to_string_lossy().replace('\\', "/"),
+            language: file_language.to_string(),
+            functions: found.functions,
+            failed: found.failed,
+            escapes: found.escapes,
+        });
+    }
+
+    for file in &files {
+        let counts = summary.by_language.entry(file.language.clone()).or_default();
+        counts.files += 1;
+        counts.functions += file.functions;
+        counts.escapes += file.escapes.len();
+        summary.files += 1;
+        summary.functions += file.functions;
+        summary.failed += file.failed;
+        summary.escapes += file.escapes.len();
+        summary.high_confidence += file
+            .escapes
+            .iter()
+            .filter(|escape| escape.confidence == ConfidenceLevel::High)
+            .count();
+    }
+    let report = ScanReport {
+        schema_version: SCAN_SCHEMA_VERSION,
+        generated_at: Local::now().to_rfc3339(),
+        root: dir.to_string_lossy().into_owned(),
+        since: changes.as_ref().map(|changes| changes.reference().to_string()),
+        summary,
+        files,
+    };
+
+    let report_dir = if no_report { None } else { Some(write_report(&output_dir, &report)?) };
+    match output_format {
+        OutputFormat::Text => {
+            print_scan_summary(&report);
+            if let Some(report_dir) = report_dir {
+                println!("\n📁 Scan report generated in: {}", report_dir.display());
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Quiet => {}
+    }
+    Ok(())
+}
+
+/// Source files under `dir` in a language with a static analyzer, in path order.
+fn discover_sources(dir: &Path, language: Option<&str>) -> Vec<(PathBuf, &'static str)> {
+    let mut sources = Vec::new();
+    // `.gitignore` applies whether or not the directory is a git checkout.
+    let walker = WalkBuilder::new(dir)
+        .require_git(false)
+        .sort_by_file_path(|left, right| left.cmp(right))
+        .build();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!("Skipping unreadable path: {}", e);
+                continue;
+            }
+        };
+        if !entry.file_type().is_some_and(|file_type| file_type.is_file()) {
+            continue;
+        }
+        let extension = entry
+            .path()
+            .extension()
+            .and_then(|value| value.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let Some((_, file_language)) = EXTENSIONS.iter().find(|(known, _)| *known == extension) else {
+            continue;
+        };
+        if language.is_some_and(|language| language != *file_language) {
+            continue;
+        }
+        sources.push((entry.into_path(), *file_language));
+    }
+    sources
+}
+
+fn print_scan_summary(report: &ScanReport) {
+    let summary = &report.summary;
+    println!("\n╔════════════════════════════════════════════╗");
+    println!("║                Scan Summary                ║");
+    println!("╚════════════════════════════════════════════╝\n");
+    println!("Root: {}", report.root);
+    if let Some(since) = &report.since {
+        println!("Changes since: {}", since);
+    }
+    println!();
+    for (language, counts) in &summary.by_language {
+        println!(
+            "{:<12} {:>5} file(s), {:>6} function(s), {:>5} escape(s)",
+            language, counts.files, counts.functions, counts.escapes
+        );
+    }
+    let mut escaping: Vec<&ScannedFile> = report.files.iter().filter(|file| !file.escapes.is_empty()).collect();
+    escaping.sort_by_key(|file| std::cmp::Reverse(file.escapes.len()));
+    if !escaping.is_empty() {
+        println!("\nFiles with escapes:");
+        for file in escaping {
+            println!("  {:>4}  {}", file.escapes.len(), file.path);
+        }
+    }
+    println!(
+        "\nTotal: {} file(s), {} function(s), {} escape(s) ({} high confidence), {} failed function(s), {} file(s) ignored",
+        summary.files, summary.functions, summary.escapes, summary.high_confidence, summary.failed, summary.ignored
+    );
+}
+
+/// Writes `README.md` and `scan.json` to a new `scan_<timestamp>_<id>` directory.
+fn write_report(output_dir: &Path, report: &ScanReport) -> Result<PathBuf> {
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+    let uuid_str = Uuid::new_v4().to_string();
+    let random_id = uuid_str.split('-').next().unwrap_or("xxxx");
+    let dir = output_dir.join("scan").join(format!("scan_{}_{}", timestamp, random_id));
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    std::fs::write(dir.join("scan.json"), serde_json::to_string_pretty(report)?)?;
+    std::fs::write(dir.join("README.md"), markdown(report))?;
+    Ok(dir)
+}
+
+fn markdown(report: &ScanReport) -> String {
+    let summary = &report.summary;
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        r#"# Object Escape Scan Report
+
+**Root:** `{}`
+{}**Generated:** {}
+
+## Overview
+
+| Metric | Value |
+|--------|-------|
+| Files | {} |
+| Functions | {} |
+| Escapes | {} |
+| High Confidence | {} |
+| Failed Functions | {} |
+| Ignored Files | {} |
+
+## By Language
+
+| Language | Files | Functions | Escapes |
+|----------|-------|-----------|---------|
+"#,
+        report.root,
+        report
+            .since
+            .as_ref()
+            .map(|since| format!("**Changes Since:** `{}`\n", since))
+            .unwrap_or_default(),
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        summary.files,
+        summary.functions,
+        summary.escapes,
+        summary.high_confidence,
+        summary.failed,
+        summary.ignored
+    );
+    for (language, counts) in &summary.by_language {
+        let _ = writeln!(out, "| {} | {} | {} | {} |", language, counts.files, counts.functions, counts.escapes);
+    }
+
+    out.push_str("\n## Files\n\n| File | Language | Functions | Escapes |\n|------|----------|-----------|---------|\n");
+    for file in &report.files {
+        let _ = writeln!(
+            out,
+            "| `{}` | {} | {} | {} |",
+            file.path,
+            file.language,
+            file.functions,
+            file.escapes.len()
+        );
+    }
+
+    out.push_str("\n## Findings\n");
+    let mut any = false;
+    for file in report.files.iter().filter(|file| !file.escapes.is_empty()) {
+        any = true;
+        let _ = writeln!(out, "\n### `{}`\n", file.path);
+        for escape in &file.escapes {
+            let _ = writeln!(
+                out,
+                "- line {}: `{}` in `{}` ({:?}, {:?} confidence): {}",
+                escape.location.line,
+                escape.variable_name,
+                escape.location.function,
+                escape.escape_type,
+                escape.confidence,
+                escape.reason
+            );
+        }
+    }
+    if !any {
+        out.push_str("\nNo escapes found.\n");
+    }
+    out
+}
@@ -0,0 +1,300 @@
+//! Run-all scheduler
+//! `run-all` splits each language's targets into batches, one bridge process each, and
+//! runs them as jobs: highest `--priority` first, at most `--jobs` at once and at most
+//! `--language-jobs` of one language at once (a JVM per Java batch adds up quickly).
+//! With `--state <file>` every job's status and its targets' outcomes are written to
+//! the file as they finish, so a run that is interrupted and started again with the
+//! same file only analyzes what is left. The file is removed once every job is done.
+
+use anyhow::{Context, Result};
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::warn;
+
+/// Version of the state file layout.
+const STATE_VERSION: u32 = 1;
+
+/// How `run-all` turns discovered targets into jobs and runs them.
+#[derive(Debug, Clone)]
+pub struct SchedulerSettings {
+    /// Targets sent to one bridge process.
+    pub batch_size: usize,
+    /// Jobs running at once.
+    pub jobs: usize,
+    /// (language or text in the target path, priority); the highest match wins.
+    pub priorities: Vec<(String, i64)>,
+    /// Jobs of one language running at once, below `jobs`.
+    pub language_jobs: HashMap<String, usize>,
+    /// Reruns of targets whose bridge crashed or hung.
+    pub retries: usize,
+    /// Job state file for resuming an interrupted run.
+    pub state: Option<PathBuf>,
+}
+
+/// One batch of a language's targets, analyzed by one bridge process.
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub id: usize,
+    pub language: String,
+    pub targets: Vec<String>,
+    pub priority: i64,
+}
+
+impl SchedulerSettings {
+    /// Jobs for the targets of each `(language, targets)` suite that are not in `done`,
+    /// highest priority first. Targets are ordered by priority before batching, so a
+    /// batch holds targets of similar priority.
+    pub fn plan(&self, suites: &[(String, Vec<String>)], done: &HashSet<(String, String)>) -> Vec<Job> {
+        let mut jobs = Vec::new();
+        for (language, targets) in suites {
+            let mut targets: Vec<(i64, &String)> = targets
+                .iter()
+                .filter(|target| !done.contains(&(language.clone(), (*target).clone())))
+                .map(|target| (self.priority(language, target), target))
+                .collect();
+            // Stable, so discovery order holds among equal priorities.
+            targets.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+            for chunk in targets.chunks(self.batch_size.max(1)) {
+                jobs.push(Job {
+                    id: 0,
+                    language: language.clone(),
+                    targets: chunk.iter().map(|(_, target)| (*target).clone()).collect(),
+                    priority: chunk.iter().map(|(priority, _)| *priority).max().unwrap_or_default(),
+                });
+            }
+        }
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.priority));
+        for (id, job) in jobs.iter_mut().enumerate() {
+            job.id = id;
+        }
+        jobs
+    }
+
+    /// Runs `start` for every job, in order, whenever a slot for the job's language is
+    /// free; returns the results in completion order.
+    pub async fn run<T, F, Fut>(&self, jobs: Vec<Job>, mut start: F) -> Vec<T>
+    where
+        F: FnMut(Job) -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let mut pending: VecDeque<Job> = jobs.into();
+        let mut running = FuturesUnordered::new();
+        let mut per_language: HashMap<String, usize> = HashMap::new();
+        let mut results = Vec::new();
+        loop {
+            while running.len() < self.jobs.max(1) {
+                let Some(index) = pending
+                    .iter()
+                    .position(|job| per_language.get(&job.language).copied().unwrap_or(0) < self.language_limit(&job.language))
+                else {
+                    break;
+                };
+                let Some(job) = pending.remove(index) else {
+                    break;
+                };
+                *per_language.entry(job.language.clone()).or_default() += 1;
+                let language = job.language.clone();
+                let job = start(job);
+                running.push(async move { (language, job.await) });
+            }
+            let Some((language, result)) = running.next().await else {
+                break;
+            };
+            if let Some(count) = per_language.get_mut(&language) {
+                *count -= 1;
+            }
+            results.push(result);
+        }
+        results
+    }
+
+    fn priority(&self, language: &str, target: &str) -> i64 {
+        self.priorities
+            .iter()
+            .filter(|(pattern, _)| pattern.eq_ignore_ascii_case(language) || target.contains(pattern.as_str()))
+            .map(|(_, priority)| *priority)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn language_limit(&self, language: &str) -> usize {
+        self.language_jobs
+            .get(language)
+            .copied()
+            .unwrap_or(usize::MAX)
+            .max(1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Done,
+    /// The job gave up with an error; it runs again on resume.
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: DeserializeOwned"))]
+struct JobRecord<T> {
+    id: usize,
+    language: String,
+    targets: Vec<String>,
+    priority: i64,
+    status: JobStatus,
+    attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// Outcome of each target once the job is done.
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    outcomes: Vec<T>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: DeserializeOwned"))]
+struct JobState<T> {
+    version: u32,
+    jobs: Vec<JobRecord<T>>,
+}
+
+/// The `--state` file: every job of the run with its status, rewritten on each change.
+pub struct JobStore<T> {
+    path: PathBuf,
+    state: Mutex<JobState<T>>,
+}
+
+impl<T: Serialize + DeserializeOwned + Clone> JobStore<T> {
+    /// Loads the state of an interrupted run from `path`, or starts an empty one.
+    pub fn open(path: &Path) -> Result<Self> {
+        let state = if path.is_file() {
+            let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            let state: JobState<T> =
+                serde_json::from_str(&text).with_context(|| format!("Malformed job state file {}", path.display()))?;
+            if state.version != STATE_VERSION {
+                anyhow::bail!(
+                    "Job state file {} has version {}, expected {}",
+                    path.display(),
+                    state.version,
+                    STATE_VERSION
+                );
+            }
+            state
+        } else {
+            JobState { version: STATE_VERSION, jobs: Vec::new() }
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Outcomes of the jobs an earlier run finished.
+    pub fn finished(&self) -> Vec<T> {
+        self.state
+            .lock()
+            .map(|state| {
+                state
+                    .jobs
+                    .iter()
+                    .filter(|job| job.status == JobStatus::Done)
+                    .flat_map(|job| job.outcomes.iter().cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Replaces every unfinished job with the `jobs` planned for this run.
+    pub fn plan(&self, jobs: &[Job]) -> Result<()> {
+        self.update(|state| {
+            state.jobs.retain(|job| job.status == JobStatus::Done);
+            let first = state.jobs.iter().map(|job| job.id + 1).max().unwrap_or(0);
+            state.jobs.extend(jobs.iter().map(|job| JobRecord {
+                id: first + job.id,
+                language: job.language.clone(),
+                targets: job.targets.clone(),
+                priority: job.priority,
+                status: JobStatus::Pending,
+                attempts: 0,
+                error: None,
+                outcomes: Vec::new(),
+            }));
+        })
+    }
+
+    pub fn start(&self, job: &Job) {
+        self.record(job, |record| {
+            record.status = JobStatus::Running;
+            record.attempts += 1;
+        });
+    }
+
+    pub fn finish(&self, job: &Job, outcomes: &[T]) {
+        self.record(job, |record| {
+            record.status = JobStatus::Done;
+            record.error = None;
+            record.outcomes = outcomes.to_vec();
+        });
+    }
+
+    pub fn fail(&self, job: &Job, error: &anyhow::Error) {
+        self.record(job, |record| {
+            record.status = JobStatus::Failed;
+            record.error = Some(format!("{:#}", error));
+        });
+    }
+
+    /// Removes the file when every job is done; otherwise keeps it for the next run.
+    /// Returns whether it was removed.
+    pub fn close(self) -> Result<bool> {
+        let complete = self
+            .state
+            .lock()
+            .map(|state| state.jobs.iter().all(|job| job.status == JobStatus::Done))
+            .unwrap_or(false);
+        if complete {
+            std::fs::remove_file(&self.path).with_context(|| format!("Failed to remove {}", self.path.display()))?;
+        }
+        Ok(complete)
+    }
+
+    /// Applies `change` to the record of `job` (matched by its targets) and saves; a
+    /// failure to save is only logged, the run goes on.
+    fn record(&self, job: &Job, change: impl FnOnce(&mut JobRecord<T>)) {
+        let result = self.update(|state| {
+            if let Some(record) = state
+                .jobs
+                .iter_mut()
+                .find(|record| record.status != JobStatus::Done && record.language == job.language && record.targets == job.targets)
+            {
+                change(record);
+            }
+        });
+        if let Err(e) = result {
+            warn!("Failed to save job state: {:#}", e);
+        }
+    }
+
+    fn update(&self, change: impl FnOnce(&mut JobState<T>)) -> Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Job state lock poisoned"))?;
+        change(&mut state);
+        let text = serde_json::to_string_pretty(&*state)?;
+        // Written aside and renamed, so an interrupted write leaves the old state intact.
+        let temporary = self.path.with_extension("tmp");
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&temporary, text).with_context(|| format!("Failed to write {}", temporary.display()))?;
+        std::fs::rename(&temporary, &self.path).with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(())
+    }
+}
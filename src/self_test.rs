@@ -0,0 +1,220 @@
+//! Golden-fixture self-test
+//! Runs every available analyzer against bundled fixtures and compares the findings
+//! with the expectations recorded next to them.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use crate::analyzer::{workspace_root, AnalyzerRegistry};
+use crate::orchestrator::{init_logging, normalize_language_filter, run_static_analysis};
+use crate::protocol::{AnalysisMode, AnalyzeRequest, PROTOCOL_VERSION};
+use crate::static_analyzer::StaticAnalyzerFactory;
+
+/// Manifest file inside the fixtures directory.
+pub const MANIFEST_FILE: &str = "expected.json";
+
+const SELF_TEST_INPUT: &str = "golden";
+
+#[derive(Debug, Deserialize)]
+struct GoldenManifest {
+    fixtures: Vec<GoldenFixture>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoldenFixture {
+    language: String,
+    mode: AnalysisMode,
+    /// Target relative to the workspace root.
+    target: String,
+    escape: bool,
+    /// Static escape types (`GlobalEscape`, ...); ignored for dynamic fixtures.
+    #[serde(default)]
+    escape_types: Vec<String>,
+}
+
+/// Findings compared against a fixture's expectations.
+#[derive(Debug, PartialEq, Eq)]
+struct Findings {
+    escape: bool,
+    escape_types: BTreeSet<String>,
+}
+
+enum Outcome {
+    Passed,
+    Skipped(String),
+    Failed(Vec<String>),
+}
+
+pub async fn run_self_test(
+    fixtures_dir: PathBuf,
+    language_filter: Option<String>,
+    analysis_mode: AnalysisMode,
+) -> Result<()> {
+    init_logging(false);
+
+    let root = workspace_root()?;
+    let fixtures_dir = if fixtures_dir.is_absolute() {
+        fixtures_dir
+    } else {
+        root.join(fixtures_dir)
+    };
+    let manifest_path = fixtures_dir.join(MANIFEST_FILE);
+    let manifest: GoldenManifest = serde_json::from_str(
+        &fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Failed to read golden manifest: {}", manifest_path.display()))?,
+    )
+    .with_context(|| format!("Invalid golden manifest: {}", manifest_path.display()))?;
+
+    let registry = AnalyzerRegistry::initialize_all().await?;
+    let normalized_filter = language_filter
+        .as_deref()
+        .map(normalize_language_filter);
+
+    println!("\n╔════════════════════════════════════════════╗");
+    println!("║        Golden Fixture Self-Test            ║");
+    println!("╚════════════════════════════════════════════╝\n");
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for fixture in &manifest.fixtures {
+        let language = normalize_language_filter(&fixture.language);
+        if normalized_filter.as_deref().is_some_and(|filter| filter != language) {
+            continue;
+        }
+        if analysis_mode != AnalysisMode::Both && fixture.mode != analysis_mode {
+            continue;
+        }
+
+        let label = format!("{} {:?} {}", language, fixture.mode, fixture.target);
+        match run_fixture(&registry, &root, &language, fixture).await {
+            Outcome::Passed => {
+                *counts.entry("passed").or_default() += 1;
+                println!("✓ {}", label);
+            }
+            Outcome::Skipped(reason) => {
+                *counts.entry("skipped").or_default() += 1;
+                println!("– {} (skipped: {})", label, reason);
+            }
+            Outcome::Failed(diff) => {
+                *counts.entry("failed").or_default() += 1;
+                println!("✗ {}", label);
+                for line in diff {
+                    println!("    {}", line);
+                }
+            }
+        }
+    }
+
+    let count = |key: &str| counts.get(key).copied().unwrap_or(0);
+    println!(
+        "\n{} passed, {} failed, {} skipped\n",
+        count("passed"),
+        count("failed"),
+        count("skipped")
+    );
+
+    if count("failed") > 0 {
+        anyhow::bail!("{} golden fixture(s) did not match expected findings", count("failed"));
+    }
+    Ok(())
+}
+
+async fn run_fixture(
+    registry: &AnalyzerRegistry,
+    root: &Path,
+    language: &str,
+    fixture: &GoldenFixture,
+) -> Outcome {
+    let target = root.join(&fixture.target).to_string_lossy().to_string();
+    let actual = match fixture.mode {
+        AnalysisMode::Static => {
+            match StaticAnalyzerFactory::create(language) {
+                Some(analyzer) if analyzer.is_available() => {}
+                _ => return Outcome::Skipped(format!("{} static analyzer unavailable", language)),
+            }
+            match run_static_analysis(&target, Some(language), AnalysisMode::Static).await {
+                Ok(response) => {
+                    let escapes = response
+                        .static_analysis
+                        .map(|result| result.escapes)
+                        .unwrap_or_default();
+                    Findings {
+                        escape: !escapes.is_empty(),
+                        escape_types: escapes
+                            .iter()
+                            .map(|escape| format!("{:?}", escape.escape_type))
+                            .collect(),
+                    }
+                }
+                Err(e) => return Outcome::Failed(vec![format!("static analysis failed: {:#}", e)]),
+            }
+        }
+        AnalysisMode::Dynamic => {
+            let Some(analyzer) = registry.find_analyzer(&target, Some(language)) else {
+                return Outcome::Skipped(format!("{} analyzer unavailable", language));
+            };
+            let request = AnalyzeRequest {
+                protocol_version: PROTOCOL_VERSION,
+                session_id: Uuid::new_v4().to_string(),
+                target: target.clone(),
+                inputs: vec![SELF_TEST_INPUT.to_string()],
+                input_encodings: Vec::new(),
+                input_args: Vec::new(),
+                repeat: 1,
+                timeout_seconds: 30.0,
+                options: HashMap::new(),
+                analysis_mode: AnalysisMode::Dynamic,
+            };
+            match analyzer.analyze(request).await {
+                Ok(response) => {
+                    if let Some(result) = response.results.iter().find(|r| r.crashed || !r.success) {
+                        let error = result.error.as_ref().map(|e| e.to_string()).unwrap_or_default();
+                        return Outcome::Failed(vec![format!("target did not run cleanly: {}", error)]);
+                    }
+                    Findings {
+                        escape: !response.results.is_empty()
+                            && response.results.iter().all(|r| r.escape_detected),
+                        escape_types: BTreeSet::new(),
+                    }
+                }
+                Err(e) => return Outcome::Failed(vec![format!("dynamic analysis failed: {:#}", e)]),
+            }
+        }
+        AnalysisMode::Both => {
+            return Outcome::Failed(vec!["fixture mode must be Static or Dynamic".to_string()]);
+        }
+    };
+
+    let expected = Findings {
+        escape: fixture.escape,
+        escape_types: if fixture.mode == AnalysisMode::Static {
+            fixture.escape_types.iter().cloned().collect()
+        } else {
+            BTreeSet::new()
+        },
+    };
+
+    if actual == expected {
+        Outcome::Passed
+    } else {
+        Outcome::Failed(diff_findings(&expected, &actual))
+    }
+}
+
+fn diff_findings(expected: &Findings, actual: &Findings) -> Vec<String> {
+    let mut diff = Vec::new();
+    if expected.escape != actual.escape {
+        diff.push(format!("- escape: {}", expected.escape));
+        diff.push(format!("+ escape: {}", actual.escape));
+    }
+    if expected.escape_types != actual.escape_types {
+        let format_types = |types: &BTreeSet<String>| {
+            types.iter().cloned().collect::<Vec<_>>().join(", ")
+        };
+        diff.push(format!("- escape_types: [{}]", format_types(&expected.escape_types)));
+        diff.push(format!("+ escape_types: [{}]", format_types(&actual.escape_types)));
+    }
+    diff
+}
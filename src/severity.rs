@@ -0,0 +1,434 @@
+//! Severity scoring
+//! Derives each finding's severity instead of taking the bridges' flat "high": dynamic
+//! escapes start from the kind of work left behind (or from what that work did during
+//! `--observe`), drop a level when only some repeats escaped, and move a level with
+//! what static analysis found; static findings map from their confidence. Every table
+//! can be overridden from the `[severity]` table of `graphene.toml`, or for one run from
+//! a JSON rules file (`--severity-rules`), which takes the config's place.
+
+use crate::protocol::{AnalyzeResponse, EscapeDetails, EscapedWork, ExecutionResult, StaticAnalysisResult, Vulnerability};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Input recorded on findings that come from static analysis rather than an execution.
+const STATIC_INPUT: &str = "[static analysis]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[serde(alias = "Low")]
+    Low,
+    #[serde(alias = "Medium")]
+    Medium,
+    #[serde(alias = "High")]
+    High,
+    #[serde(alias = "Critical")]
+    Critical,
+}
+
+impl Severity {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
+    fn raised(self) -> Self {
+        match self {
+            Self::Low => Self::Medium,
+            Self::Medium => Self::High,
+            Self::High | Self::Critical => Self::Critical,
+        }
+    }
+
+    fn lowered(self) -> Self {
+        match self {
+            Self::Critical => Self::High,
+            Self::High => Self::Medium,
+            Self::Medium | Self::Low => Self::Low,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+            Self::Critical => "Critical",
+        })
+    }
+}
+
+/// Scoring rules. Fields left out of a rules file keep their defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SeverityRules {
+    /// Starting severity of a dynamic escape, by what it left behind
+    pub kinds: KindSeverities,
+    /// Fixed severity for a vulnerability type (`nondeterministic`, `object_escape`, ...),
+    /// which skips the rest of the scoring
+    pub types: BTreeMap<String, Severity>,
+    /// Escaping in fewer than this share of an input's executions lowers the severity
+    /// one level
+    pub inconsistent_below: f64,
+    /// Raise dynamic escapes one level when static analysis found a high-confidence
+    /// escape, and lower them one level when it found none
+    pub static_corroboration: bool,
+    /// Severity of a static finding, by its confidence
+    pub static_confidence: ConfidenceSeverities,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KindSeverities {
+    /// A subprocess still running, or unreaped, after the call
+    pub orphan_process: Severity,
+    /// A task or goroutine still running when the observation window ended, or a
+    /// repeating timer
+    pub infinite_task: Severity,
+    /// A lock poisoned by a panic while it was held
+    pub poisoned_mutex: Severity,
+    pub thread: Severity,
+    /// A daemon thread, which does not keep the process alive
+    pub daemon_thread: Severity,
+    /// Any other async task or goroutine
+    pub task: Severity,
+    /// An escaping object with no work left behind
+    pub object: Severity,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfidenceSeverities {
+    pub high: Severity,
+    pub medium: Severity,
+    pub low: Severity,
+}
+
+impl Default for SeverityRules {
+    fn default() -> Self {
+        Self {
+            kinds: KindSeverities::default(),
+            types: BTreeMap::new(),
+            inconsistent_below: 0.5,
+            static_corroboration: true,
+            static_confidence: ConfidenceSeverities::default(),
+        }
+    }
+}
+
+impl Default for KindSeverities {
+    fn default() -> Self {
+        Self {
+            orphan_process: Severity::Critical,
+            infinite_task: Severity::Critical,
+            poisoned_mutex: Severity::High,
+            thread: Severity::High,
+            daemon_thread: Severity::Low,
+            task: Severity::Medium,
+            object: Severity::Medium,
+        }
+    }
+}
+
+impl Default for ConfidenceSeverities {
+    fn default() -> Self {
+        Self {
+            high: Severity::High,
+            medium: Severity::Medium,
+            low: Severity::Low,
+        }
+    }
+}
+
+impl SeverityRules {
+    /// Rules from `--severity-rules` when given, else from the config file's `[severity]`
+    /// table, else the defaults.
+    pub fn configured(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::load(path),
+            None => Ok(crate::analyzer::external::load_severity_rules()?.unwrap_or_default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read severity rules {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Invalid severity rules in {}", path.display()))
+    }
+}
+
+/// Scores every vulnerability in `response`. `static_result` is the static analysis of
+/// the same target in `--analysis-mode both`, used to corroborate dynamic escapes.
+pub fn score(response: &mut AnalyzeResponse, static_result: Option<&StaticAnalysisResult>, rules: &SeverityRules) {
+    let AnalyzeResponse { vulnerabilities, results, .. } = response;
+    for vuln in vulnerabilities.iter_mut() {
+        if let Some(severity) = rules.types.get(&vuln.vulnerability_type) {
+            vuln.severity = severity.to_string();
+        } else if vuln.input == STATIC_INPUT {
+            let confidence = &rules.static_confidence;
+            let severity = match Severity::parse(&vuln.severity) {
+                Some(Severity::Critical | Severity::High) => confidence.high,
+                Some(Severity::Medium) => confidence.medium,
+                _ => confidence.low,
+            };
+            vuln.severity = severity.to_string();
+        } else if vuln.vulnerability_type == "object_escape" {
+            score_dynamic_escape(vuln, results, static_result, rules);
+        }
+    }
+}
+
+/// What an escaping execution left behind.
+#[derive(Debug, Clone, Copy)]
+enum EscapeKind {
+    OrphanProcess,
+    InfiniteTask,
+    PoisonedMutex,
+    Thread,
+    DaemonThread,
+    Task,
+    Object,
+}
+
+impl EscapeKind {
+    fn severity(self, kinds: &KindSeverities) -> Severity {
+        match self {
+            Self::OrphanProcess => kinds.orphan_process,
+            Self::InfiniteTask => kinds.infinite_task,
+            Self::PoisonedMutex => kinds.poisoned_mutex,
+            Self::Thread => kinds.thread,
+            Self::DaemonThread => kinds.daemon_thread,
+            Self::Task => kinds.task,
+            Self::Object => kinds.object,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::OrphanProcess => "orphaned process",
+            Self::InfiniteTask => "task that never finishes",
+            Self::PoisonedMutex => "poisoned mutex",
+            Self::Thread => "non-daemon thread",
+            Self::DaemonThread => "daemon thread",
+            Self::Task => "async task",
+            Self::Object => "escaping object",
+        }
+    }
+}
+
+fn score_dynamic_escape(
+    vuln: &mut Vulnerability,
+    results: &[ExecutionResult],
+    static_result: Option<&StaticAnalysisResult>,
+    rules: &SeverityRules,
+) {
+    let runs: Vec<&ExecutionResult> = results
+        .iter()
+        .filter(|r| r.input_data == vuln.input && !r.not_executed)
+        .collect();
+    let details = if vuln.escape_details.spawned_count().unwrap_or(0) > 0 {
+        &vuln.escape_details
+    } else {
+        runs.iter()
+            .filter(|r| r.escape_detected)
+            .map(|r| &r.escape_details)
+            .find(|details| details.spawned_count().unwrap_or(0) > 0)
+            .unwrap_or(&vuln.escape_details)
+    };
+
+    let mut evidence = Vec::new();
+    let mut work = vuln.escape_details.escaped_work();
+    if work.is_empty() {
+        work = runs
+            .iter()
+            .map(|r| r.escape_details.escaped_work())
+            .find(|work| !work.is_empty())
+            .unwrap_or_default();
+    }
+    let mut severity = match observed_grade(&work) {
+        // What the work did while watched outranks what kind of work it was.
+        Some((severity, observed)) => {
+            vuln.description = format!("{} (graded {} from escaped work: {})", vuln.description, severity, observed);
+            severity
+        }
+        None => {
+            let (kind, severity) = escape_kinds(details, &runs)
+                .into_iter()
+                .map(|kind| (kind, kind.severity(&rules.kinds)))
+                .max_by_key(|(_, severity)| *severity)
+                .unwrap_or((EscapeKind::Object, rules.kinds.object));
+            evidence.push(kind.describe().to_string());
+            severity
+        }
+    };
+
+    let escaped = runs.iter().filter(|r| r.escape_detected).count();
+    if runs.len() > 1 && (escaped as f64) < rules.inconsistent_below * runs.len() as f64 {
+        severity = severity.lowered();
+        evidence.push(format!("escaped in {} of {} runs", escaped, runs.len()));
+    }
+
+    if let Some(static_result) = static_result.filter(|_| rules.static_corroboration) {
+        if static_result.escapes.is_empty() {
+            severity = severity.lowered();
+            evidence.push("no static escape path".to_string());
+        } else if static_result
+            .escapes
+            .iter()
+            .any(|escape| escape.confidence == crate::protocol::ConfidenceLevel::High)
+        {
+            severity = severity.raised();
+            evidence.push("confirmed by static analysis".to_string());
+        }
+    }
+
+    if !evidence.is_empty() {
+        vuln.description = format!("{} (scored {}: {})", vuln.description, severity, evidence.join(", "));
+    }
+    vuln.severity = severity.to_string();
+}
+
+/// Kinds of work `details` lists, plus a poisoned mutex when an execution of the input
+/// failed on one.
+fn escape_kinds(details: &EscapeDetails, runs: &[&ExecutionResult]) -> Vec<EscapeKind> {
+    let flag = |value: &serde_json::Value, key: &str| value.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+    let mut kinds = Vec::new();
+    if details.processes.as_ref().is_some_and(|processes| !processes.is_empty()) {
+        kinds.push(EscapeKind::OrphanProcess);
+    }
+    for thread in details.threads.iter().flatten() {
+        kinds.push(if flag(thread, "is_daemon") {
+            EscapeKind::DaemonThread
+        } else {
+            EscapeKind::Thread
+        });
+    }
+    // A pool is one escape however many workers it left, blocking exit when any does.
+    for pool in details.thread_pools.iter().flatten() {
+        let threads = pool.get("threads").and_then(|v| v.as_array());
+        kinds.push(if threads.into_iter().flatten().all(|thread| flag(thread, "is_daemon")) {
+            EscapeKind::DaemonThread
+        } else {
+            EscapeKind::Thread
+        });
+    }
+    for task in details.async_tasks.iter().chain(&details.goroutines).flatten() {
+        let repeating = task
+            .get("task_type")
+            .and_then(|v| v.as_str())
+            .is_some_and(|kind| kind.contains("Interval"));
+        kinds.push(if repeating || flag(task, "still_running") {
+            EscapeKind::InfiniteTask
+        } else {
+            EscapeKind::Task
+        });
+    }
+    for timer in details.timers.iter().flatten() {
+        let repeating = timer.get("kind").and_then(|v| v.as_str()) == Some("interval");
+        kinds.push(if repeating {
+            EscapeKind::InfiniteTask
+        } else {
+            EscapeKind::Task
+        });
+    }
+    let poisoned = runs.iter().any(|r| {
+        let error = r.error.as_ref().map(|e| e.message.as_str()).unwrap_or_default();
+        [error, r.stderr.as_str(), r.output.as_str()]
+            .iter()
+            .any(|text| text.to_ascii_lowercase().contains("poison"))
+    });
+    if poisoned {
+        kinds.push(EscapeKind::PoisonedMutex);
+    }
+    kinds
+}
+
+/// Grade from what the escaped work did during the observation window (`--observe`):
+///
+/// | Escaped work | Severity |
+/// |--------------|----------|
+/// | still running, and burning CPU or holding a socket/lock/file | Critical |
+/// | still running, idle | High |
+/// | finished, but burned CPU or held a resource meanwhile | Medium |
+/// | finished, idle | Low |
+///
+/// `None` when nothing was observed.
+fn observed_grade(work: &[EscapedWork]) -> Option<(Severity, String)> {
+    if work.is_empty() {
+        return None;
+    }
+    let unbounded = work.iter().any(|w| w.still_running);
+    let busy = work.iter().any(burns_cpu);
+    let mut held: Vec<&str> = work.iter().flat_map(held_resources).collect();
+    held.sort_unstable();
+    held.dedup();
+
+    let severity = match (unbounded, busy || !held.is_empty()) {
+        (true, true) => Severity::Critical,
+        (true, false) => Severity::High,
+        (false, true) => Severity::Medium,
+        (false, false) => Severity::Low,
+    };
+    let mut evidence = vec![if unbounded {
+        "still running when the observation window ended".to_string()
+    } else {
+        format!(
+            "finished within {}ms of the call",
+            work.iter().map(|w| w.outlived_ms).max().unwrap_or(0)
+        )
+    }];
+    if busy {
+        evidence.push("burning CPU".to_string());
+    }
+    if !held.is_empty() {
+        evidence.push(format!("holding {}", held.join("/")));
+    }
+    Some((severity, evidence.join(", ")))
+}
+
+/// Used CPU for at least half of its observed lifetime, or was last seen on a CPU.
+fn burns_cpu(work: &EscapedWork) -> bool {
+    let measured = work
+        .cpu_ms
+        .is_some_and(|cpu| cpu > 0 && cpu * 2 >= work.outlived_ms);
+    let on_cpu = matches!(work.state.as_deref(), Some("running" | "runnable"));
+    measured || on_cpu
+}
+
+/// Resources the work was using: what the bridge reported, plus what the Node.js
+/// resource type or Go wait state implies.
+fn held_resources(work: &EscapedWork) -> Vec<&'static str> {
+    let mut held: Vec<&'static str> = work
+        .holds
+        .iter()
+        .filter_map(|kind| match kind.as_str() {
+            "socket" => Some("socket"),
+            "lock" => Some("lock"),
+            "file" => Some("file"),
+            _ => None,
+        })
+        .collect();
+    if let Some(resource) = work.resource.as_deref() {
+        if ["TCP", "UDP", "PIPE", "TLS", "HTTP"].iter().any(|kind| resource.contains(kind)) {
+            held.push("socket");
+        } else if resource.starts_with("FSREQ") || resource == "FILEHANDLE" {
+            held.push("file");
+        }
+    }
+    match work.state.as_deref() {
+        Some("IO wait") => held.push("socket"),
+        Some(state) if state.contains("Mutex") || state.starts_with("semacquire") => held.push("lock"),
+        _ => {}
+    }
+    held
+}
@@ -1,7 +1,7 @@
-/// Static escape analysis module
-/// Performs compile-time analysis to detect escaping variables and concurrency patterns
+//! Static escape analysis module
+//! Performs compile-time analysis to detect escaping variables and concurrency patterns
 
-use crate::protocol::StaticAnalysisResult;
+use crate::protocol::{CallGraph, StaticAnalysisResult, StaticEscape, SuggestedFix};
 use anyhow::Result;
 
 /// Trait for language-specific static analyzers
@@ -14,6 +14,16 @@ pub trait StaticEscapeAnalyzer {
     
     /// Check if analyzer is available (required tools/compilers installed)
     fn is_available(&self) -> bool;
+
+    /// Build the call graph reachable from the target function, annotated with spawn sites
+    fn call_graph(&self, target: &str, source_file: &str) -> Result<CallGraph> {
+        callgraph::build(self.language(), target, source_file)
+    }
+
+    /// Fix for a finding from the template of its rule, if the rule has one
+    fn suggest_fix(&self, _escape: &StaticEscape, _context: &fixes::FixContext) -> Option<SuggestedFix> {
+        None
+    }
 }
 
 /// Factory for creating static analyzers
@@ -32,6 +42,20 @@ impl StaticAnalyzerFactory {
     }
 }
 
+pub mod callgraph;
+pub mod taint;
+pub mod recursion;
+pub mod guard;
+pub mod data_flow;
+pub mod suppress;
+pub mod signature;
+pub mod spawn_count;
+pub mod waitgroup;
+pub mod exit_path;
+pub mod retaining;
+pub mod preprocess;
+pub mod rules;
+pub mod fixes;
 pub mod python;
 pub mod java;
 pub mod nodejs;
@@ -0,0 +1,663 @@
+//! Call graph extraction
+//! Builds an approximate call graph rooted at the target function from source text and
+//! marks the functions that contain thread/task spawn sites. Calls are resolved within
+//! the target's own file; anything defined elsewhere is kept as an external leaf.
+
+use crate::protocol::{
+    CallGraph, CallGraphEdge, CallGraphNode, ConfidenceLevel, SourceLocation, SpawnSite,
+    StaticEscape,
+};
+use crate::static_analyzer::preprocess;
+use crate::static_analyzer::taint::TaintReport;
+use crate::static_analyzer::StaticEscapeAnalyzer;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// Words in a finding's reason that tie it to a thread, task or worker.
+pub(super) const CONCURRENCY_WORDS: &[&str] = &[
+    "thread", "goroutine", "task", "spawn", "worker", "executor", "timer", "async",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Language {
+    Python,
+    JavaScript,
+    Go,
+    Java,
+    Rust,
+}
+
+impl Language {
+    pub(super) fn from_name(language: &str) -> Option<Self> {
+        match language.to_lowercase().as_str() {
+            "python" => Some(Self::Python),
+            "javascript" | "nodejs" => Some(Self::JavaScript),
+            "go" => Some(Self::Go),
+            "java" => Some(Self::Java),
+            "rust" => Some(Self::Rust),
+            _ => None,
+        }
+    }
+
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Self::Python => &[
+                "def", "if", "elif", "while", "for", "return", "with", "assert", "not", "and",
+                "or", "in", "lambda", "print", "yield", "await", "except", "class",
+            ],
+            Self::JavaScript => &[
+                "function", "if", "for", "while", "switch", "catch", "return", "typeof", "new",
+                "await", "super", "import", "require",
+            ],
+            Self::Go => &["func", "if", "for", "switch", "return", "go", "defer", "make", "len", "append"],
+            Self::Java => &[
+                "if", "for", "while", "switch", "catch", "return", "new", "synchronized", "super",
+                "this", "try",
+            ],
+            Self::Rust => &[
+                "fn", "if", "while", "for", "match", "return", "Some", "Ok", "Err", "loop", "move",
+            ],
+        }
+    }
+
+    /// Substrings that mark a line as starting a thread, task or worker.
+    fn spawn_patterns(self) -> &'static [&'static str] {
+        match self {
+            Self::Python => &[
+                "Thread(", "Process(", "Timer(", ".submit(", "create_task(", "ensure_future(",
+                "run_in_executor(", "start_new_thread(", "apply_async(",
+            ],
+            Self::JavaScript => &[
+                "new Worker(", "setTimeout(", "setInterval(", "setImmediate(", "process.nextTick(",
+                "queueMicrotask(", ".fork(",
+            ],
+            Self::Go => &["time.AfterFunc("],
+            Self::Java => &[
+                "new Thread(", ".submit(", ".execute(", "runAsync(", "supplyAsync(", ".schedule(",
+                "scheduleAtFixedRate(", "startVirtualThread(",
+            ],
+            Self::Rust => &["::spawn(", ".spawn(", "spawn_blocking(", "spawn_local("],
+        }
+    }
+
+    pub(super) fn is_spawn_site(self, code: &str) -> bool {
+        (self == Self::Go && code.trim_start().starts_with("go "))
+            || self.spawn_patterns().iter().any(|pattern| code.contains(pattern))
+    }
+}
+
+/// Function definition found in the source file. Body lines carry their 1-based line
+/// number and have comments and string contents removed.
+pub(super) struct FunctionDef {
+    pub(super) name: String,
+    pub(super) line: usize,
+    /// Text inside the parentheses of the definition.
+    pub(super) parameters: String,
+    pub(super) body: Vec<(usize, String)>,
+}
+
+/// A call on one line: the callee chain and its top-level arguments.
+pub(super) struct Call {
+    pub(super) chain: String,
+    /// `foo().bar(` and `"x".bar(`: a method on an expression result.
+    pub(super) on_expression: bool,
+    pub(super) arguments: Vec<String>,
+}
+
+/// Builds the call graph reachable from the target function.
+pub fn build(language: &str, target: &str, source_file: &str) -> Result<CallGraph> {
+    let language = Language::from_name(language)
+        .ok_or_else(|| anyhow::anyhow!("No call graph support for language: {}", language))?;
+    let source = std::fs::read_to_string(source_file)
+        .with_context(|| format!("Failed to read source file: {}", source_file))?;
+    let root = target_function(target);
+    let raw_lines: Vec<&str> = source.lines().collect();
+
+    let functions = extract_functions(&source, language);
+    let mut defined: HashMap<&str, &FunctionDef> = HashMap::new();
+    for function in &functions {
+        defined.entry(function.name.as_str()).or_insert(function);
+    }
+    if !defined.contains_key(root) {
+        anyhow::bail!("Function `{}` not found in {}", root, source_file);
+    }
+
+    let mut nodes: BTreeMap<String, CallGraphNode> = BTreeMap::new();
+    let mut edges: BTreeMap<(String, String), Vec<usize>> = BTreeMap::new();
+    let mut queue = VecDeque::from([root]);
+    let mut visited = HashSet::from([root]);
+
+    while let Some(name) = queue.pop_front() {
+        let function = defined[name];
+        let mut spawn_sites = Vec::new();
+
+        for (line, code) in &function.body {
+            if language.is_spawn_site(code) {
+                spawn_sites.push(SpawnSite {
+                    line: *line,
+                    code: raw_lines[line - 1].trim().to_string(),
+                });
+            }
+            for Call { chain: call, on_expression, .. } in extract_calls(code, language) {
+                let callee = match defined.get(last_segment(&call)) {
+                    Some(local) => {
+                        if visited.insert(local.name.as_str()) {
+                            queue.push_back(local.name.as_str());
+                        }
+                        local.name.clone()
+                    }
+                    None if on_expression => continue,
+                    None => {
+                        nodes.entry(call.clone()).or_insert_with(|| CallGraphNode {
+                            name: call.clone(),
+                            line: None,
+                            spawn_sites: vec![],
+                        });
+                        call
+                    }
+                };
+                let lines = edges.entry((function.name.clone(), callee)).or_default();
+                if !lines.contains(line) {
+                    lines.push(*line);
+                }
+            }
+        }
+
+        nodes.insert(
+            function.name.clone(),
+            CallGraphNode {
+                name: function.name.clone(),
+                line: Some(function.line),
+                spawn_sites,
+            },
+        );
+    }
+
+    Ok(CallGraph {
+        root: root.to_string(),
+        source_file: source_file.to_string(),
+        nodes: nodes.into_values().collect(),
+        edges: edges
+            .into_iter()
+            .map(|((caller, callee), lines)| CallGraphEdge { caller, callee, lines })
+            .collect(),
+    })
+}
+
+/// Findings that reach the target through the functions it calls. Each reachable
+/// function with a spawn site is analyzed on its own, and its concurrency escapes are
+/// reported again on the target with the call chain in `data_flow`. Derived findings
+/// are one confidence level below the finding they come from. With a taint report,
+/// helper findings that carry the target's input keep their taint flow.
+pub fn propagate_escapes(
+    analyzer: &dyn StaticEscapeAnalyzer,
+    target: &str,
+    source_file: &str,
+    graph: &CallGraph,
+    taint: Option<&TaintReport>,
+) -> Vec<StaticEscape> {
+    let mut derived = Vec::new();
+    for node in &graph.nodes {
+        if node.name == graph.root || node.line.is_none() || node.spawn_sites.is_empty() {
+            continue;
+        }
+        let Some(chain) = call_chain(graph, &node.name) else {
+            continue;
+        };
+        let Ok(result) = analyzer.analyze(&sibling_target(target, &node.name), source_file) else {
+            continue;
+        };
+
+        let names: Vec<&str> = chain
+            .iter()
+            .map(|(caller, _)| caller.as_str())
+            .chain([node.name.as_str()])
+            .collect();
+        for mut escape in result.escapes.into_iter().filter(|escape| is_spawn_escape(escape, node)) {
+            if let Some(taint) = taint {
+                taint.annotate(&mut escape, &node.name);
+            }
+            let mut data_flow: Vec<String> = chain
+                .iter()
+                .map(|(caller, line)| format!("{} calls at line {}", caller, line))
+                .collect();
+            data_flow.push(format!(
+                "{} line {}: {}",
+                node.name, escape.location.line, escape.reason
+            ));
+            derived.push(StaticEscape {
+                escape_type: escape.escape_type,
+                location: SourceLocation {
+                    file: source_file.to_string(),
+                    line: chain[0].1,
+                    column: 0,
+                    function: graph.root.clone(),
+                    code_snippet: escape.location.code_snippet,
+                },
+                variable_name: escape.variable_name,
+                reason: format!("Reached through {}: {}", names.join(" → "), escape.reason),
+                confidence: match escape.confidence {
+                    ConfidenceLevel::High => ConfidenceLevel::Medium,
+                    _ => ConfidenceLevel::Low,
+                },
+                data_flow,
+                input_tainted: escape.input_tainted,
+                taint_flow: escape.taint_flow,
+                rule: escape.rule,
+                rule_id: escape.rule_id,
+                condition: None,
+                suggested_fix: None,
+            });
+        }
+    }
+    derived
+}
+
+/// Calls leading from the root to `function` along the shortest path, as
+/// `(caller, call line)` hops.
+pub fn call_chain(graph: &CallGraph, function: &str) -> Option<Vec<(String, usize)>> {
+    let mut previous: HashMap<&str, (&str, usize)> = HashMap::new();
+    let mut queue = VecDeque::from([graph.root.as_str()]);
+    let mut seen = HashSet::from([graph.root.as_str()]);
+    while let Some(caller) = queue.pop_front() {
+        if caller == function {
+            break;
+        }
+        for edge in graph.edges.iter().filter(|edge| edge.caller == caller) {
+            if seen.insert(edge.callee.as_str()) {
+                previous.insert(edge.callee.as_str(), (caller, edge.lines[0]));
+                queue.push_back(edge.callee.as_str());
+            }
+        }
+    }
+
+    let mut chain = Vec::new();
+    let mut current = function;
+    while current != graph.root {
+        let (caller, line) = previous.get(current)?;
+        chain.push((caller.to_string(), *line));
+        current = caller;
+    }
+    chain.reverse();
+    (!chain.is_empty()).then_some(chain)
+}
+
+fn is_spawn_escape(escape: &StaticEscape, node: &CallGraphNode) -> bool {
+    let reason = escape.reason.to_lowercase();
+    node.spawn_sites.iter().any(|site| site.line == escape.location.line)
+        || CONCURRENCY_WORDS.iter().any(|word| reason.contains(word))
+}
+
+/// The target naming `function` in the same file or module as `target`.
+fn sibling_target(target: &str, function: &str) -> String {
+    if let Some((module, _)) = target.rsplit_once("::") {
+        format!("{}::{}", module, function)
+    } else if let Some((file, _)) = target.rsplit_once(':') {
+        format!("{}:{}", file, function)
+    } else {
+        function.to_string()
+    }
+}
+
+/// Graphviz rendering: the root in bold, spawning functions in red, external calls dashed.
+pub fn render_dot(graph: &CallGraph) -> String {
+    let quote = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut dot = String::from("digraph callgraph {\n    rankdir=LR;\n    node [shape=box, fontname=\"Helvetica\"];\n");
+
+    for node in &graph.nodes {
+        let mut label = node.name.clone();
+        if let Some(line) = node.line {
+            label.push_str(&format!("\\nL{}", line));
+        }
+        let mut attributes = Vec::new();
+        if !node.spawn_sites.is_empty() {
+            let lines: Vec<String> = node.spawn_sites.iter().map(|site| format!("L{}", site.line)).collect();
+            label.push_str(&format!("\\nspawns: {}", lines.join(", ")));
+            attributes.push("color=red, fontcolor=red".to_string());
+        }
+        if node.name == graph.root {
+            attributes.push("style=bold".to_string());
+        } else if node.line.is_none() {
+            attributes.push("style=dashed".to_string());
+        }
+        attributes.insert(0, format!("label=\"{}\"", label.replace('"', "\\\"")));
+        dot.push_str(&format!("    {} [{}];\n", quote(&node.name), attributes.join(", ")));
+    }
+
+    for edge in &graph.edges {
+        let lines: Vec<String> = edge.lines.iter().map(|line| format!("L{}", line)).collect();
+        dot.push_str(&format!(
+            "    {} -> {} [label=\"{}\"];\n",
+            quote(&edge.caller),
+            quote(&edge.callee),
+            lines.join(", ")
+        ));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Function part of a target: the segment after the last `:` (or `::`), without the
+/// class of a method target (`file.py:Class.method`).
+pub fn target_function(target: &str) -> &str {
+    let function = target.rsplit(':').next().unwrap_or(target).trim();
+    function.rsplit('.').next().unwrap_or(function)
+}
+
+/// Type a method target names before its function: `Class` in `file.py:Class.method`,
+/// `Struct` in `crate::module::Struct::method`. Rust types are told from modules by
+/// their leading capital.
+pub fn target_owner(target: &str) -> Option<&str> {
+    let function = target.rsplit(':').next().unwrap_or(target).trim();
+    if let Some((owner, _)) = function.rsplit_once('.') {
+        return Some(owner.rsplit('.').next().unwrap_or(owner)).filter(|owner| !owner.is_empty());
+    }
+    let mut segments = target.rsplit("::").skip(1);
+    segments
+        .next()
+        .filter(|_| target.contains("::"))
+        .map(str::trim)
+        .filter(|segment| segment.starts_with(|ch: char| ch.is_ascii_uppercase()))
+}
+
+/// Names of the functions defined in `source`, in order and without repeats; empty for
+/// languages without a call graph.
+pub fn function_names(language: &str, source: &str) -> Vec<String> {
+    let Some(language) = Language::from_name(language) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = Vec::new();
+    for function in extract_functions(source, language) {
+        if !names.contains(&function.name) {
+            names.push(function.name);
+        }
+    }
+    names
+}
+
+/// Functions defined in `source` with their first and last lines (1-based); empty for
+/// languages without a call graph.
+pub fn function_spans(language: &str, source: &str) -> Vec<(String, usize, usize)> {
+    let Some(language) = Language::from_name(language) else {
+        return Vec::new();
+    };
+    extract_functions(source, language)
+        .into_iter()
+        .map(|function| {
+            let end = function.body.last().map_or(function.line, |(line, _)| *line);
+            (function.name, function.line, end)
+        })
+        .collect()
+}
+
+pub(super) fn last_segment(call: &str) -> &str {
+    call.rsplit(['.', ':']).next().unwrap_or(call)
+}
+
+/// Functions of `source`, with their bodies as cleaned by `preprocess::strip`: text
+/// shown to users has to be read back from the original lines (`preprocess::restore_span`).
+pub(super) fn extract_functions(source: &str, language: Language) -> Vec<FunctionDef> {
+    let lines = preprocess::strip(source, language);
+    let mut functions = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let Some(name) = definition_name(line, language) else {
+            continue;
+        };
+        let body_end = if language == Language::Python {
+            indented_block_end(&lines, index)
+        } else {
+            braced_block_end(&lines, index)
+        };
+        let body = (index + 1..body_end)
+            .map(|body_index| (body_index + 1, lines[body_index].clone()))
+            .collect();
+        let parameters = definition_parameters(&lines[index..body_end.max(index + 1)], &name);
+        functions.push(FunctionDef {
+            name,
+            line: index + 1,
+            parameters,
+            body,
+        });
+    }
+    functions
+}
+
+/// Parameter list of a definition: the parenthesised text after the function name,
+/// which may continue over several lines.
+fn definition_parameters(lines: &[String], name: &str) -> String {
+    let header = lines.join("\n");
+    let Some(after_name) = header.find(name).map(|index| &header[index + name.len()..]) else {
+        return String::new();
+    };
+    let Some(open) = after_name.find('(') else {
+        return String::new();
+    };
+    let inner = &after_name[open + 1..];
+    let mut depth = 0i32;
+    for (index, ch) in inner.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' if depth == 0 => return inner[..index].to_string(),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    inner.to_string()
+}
+
+/// Name of the function defined on this line, if the line starts a definition.
+fn definition_name(line: &str, language: Language) -> Option<String> {
+    let trimmed = line.trim();
+    let name_before_paren = |text: &str| -> Option<String> {
+        let name = text.split(['(', '<']).next()?.trim();
+        is_identifier(name).then(|| name.to_string())
+    };
+
+    match language {
+        Language::Python => {
+            let rest = trimmed.strip_prefix("async ").unwrap_or(trimmed);
+            name_before_paren(rest.strip_prefix("def ")?)
+        }
+        Language::Rust => {
+            let index = trimmed.find("fn ")?;
+            if index > 0 && !trimmed[..index].ends_with(' ') {
+                return None;
+            }
+            name_before_paren(&trimmed[index + 3..])
+        }
+        Language::Go => {
+            let mut rest = trimmed.strip_prefix("func ")?.trim_start();
+            if rest.starts_with('(') {
+                // Method receiver.
+                rest = rest[rest.find(')')? + 1..].trim_start();
+            }
+            name_before_paren(rest)
+        }
+        Language::JavaScript => {
+            let rest = trimmed.strip_prefix("export ").unwrap_or(trimmed);
+            let rest = rest.strip_prefix("async ").unwrap_or(rest);
+            if let Some(after) = rest.strip_prefix("function") {
+                return name_before_paren(after.trim_start_matches('*').trim_start());
+            }
+            for keyword in ["const ", "let ", "var "] {
+                if let Some(after) = rest.strip_prefix(keyword) {
+                    let (name, value) = after.split_once('=')?;
+                    let value = value.trim_start();
+                    let value = value.strip_prefix("async ").unwrap_or(value);
+                    let is_function = value.starts_with("function") || value.contains("=>");
+                    let name = name.trim();
+                    return (is_function && is_identifier(name)).then(|| name.to_string());
+                }
+            }
+            // Class method: `name(args) {`
+            let name = name_before_paren(rest)?;
+            (rest.ends_with('{') && !language.keywords().contains(&name.as_str())).then_some(name)
+        }
+        Language::Java => {
+            let paren = trimmed.find('(')?;
+            let head = &trimmed[..paren];
+            if head.contains('=') || head.contains('.') || trimmed.ends_with(';') {
+                return None;
+            }
+            let tokens: Vec<&str> = head.split_whitespace().collect();
+            let name = *tokens.last()?;
+            if tokens.len() < 2
+                || !is_identifier(name)
+                || language.keywords().contains(&tokens[0])
+                || tokens.contains(&"new")
+            {
+                return None;
+            }
+            Some(name.to_string())
+        }
+    }
+}
+
+/// Index one past the last line of an indentation-delimited body.
+fn indented_block_end(lines: &[String], start: usize) -> usize {
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let base = indent(&lines[start]);
+    let mut end = start + 1;
+    for (index, line) in lines.iter().enumerate().skip(start + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if indent(line) <= base {
+            break;
+        }
+        end = index + 1;
+    }
+    end
+}
+
+/// Index one past the line holding the closing brace of a braced body. Declarations
+/// without a body (interfaces, abstract methods) end at their `;`.
+fn braced_block_end(lines: &[String], start: usize) -> usize {
+    let mut depth = 0i32;
+    let mut opened = false;
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        for ch in line.chars() {
+            match ch {
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' => depth -= 1,
+                ';' if !opened => return index + 1,
+                _ => {}
+            }
+        }
+        if opened && depth <= 0 {
+            return index + 1;
+        }
+    }
+    lines.len()
+}
+
+/// Calls on a line: the identifier chain (`a.b`, `a::b`) in front of each `(` and the
+/// arguments up to the matching `)` (or the end of the line).
+pub(super) fn extract_calls(code: &str, language: Language) -> Vec<Call> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut calls = Vec::new();
+    for (index, &ch) in chars.iter().enumerate() {
+        if ch != '(' {
+            continue;
+        }
+        let mut start = index;
+        while start > 0 {
+            let previous = chars[start - 1];
+            if previous.is_alphanumeric() || matches!(previous, '_' | '.' | ':' | '$') {
+                start -= 1;
+            } else {
+                break;
+            }
+        }
+        let chain: String = chars[start..index].iter().collect();
+        let on_expression = chain.starts_with('.');
+        let chain = chain.trim_matches(|c| c == '.' || c == ':');
+        let chain = chain
+            .strip_prefix("self.")
+            .or_else(|| chain.strip_prefix("this."))
+            .unwrap_or(chain);
+        let name = last_segment(chain);
+        if is_identifier(name) && !language.keywords().contains(&name) {
+            let mut depth = 0i32;
+            let mut end = chars.len();
+            for (offset, &next) in chars.iter().enumerate().skip(index + 1) {
+                match next {
+                    '(' | '[' | '{' => depth += 1,
+                    ')' if depth == 0 => {
+                        end = offset;
+                        break;
+                    }
+                    ')' | ']' | '}' => depth -= 1,
+                    _ => {}
+                }
+            }
+            let arguments: String = chars[index + 1..end].iter().collect();
+            calls.push(Call {
+                chain: chain.to_string(),
+                on_expression,
+                arguments: split_top_level(&arguments),
+            });
+        }
+    }
+    calls
+}
+
+/// Index of the body line that closes the statement starting at `start`, following
+/// parentheses and braces so multi-line closures are included.
+pub(super) fn statement_end(body: &[(usize, String)], start: usize) -> usize {
+    let mut depth = 0i32;
+    for (index, (_, code)) in body.iter().enumerate().skip(start) {
+        for ch in code.chars() {
+            match ch {
+                '(' | '{' => depth += 1,
+                ')' | '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        if depth <= 0 {
+            return index;
+        }
+    }
+    body.len() - 1
+}
+
+/// Splits on commas outside brackets and generic angle brackets; blank parts are dropped.
+pub(super) fn split_top_level(text: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    for ch in text.chars() {
+        match ch {
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' | ']' | '}' | '>' => depth -= 1,
+            ',' if depth <= 0 => {
+                parts.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(ch);
+    }
+    parts.push(current);
+    parts
+        .into_iter()
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Identifiers mentioned in a piece of code.
+pub(super) fn identifiers(code: &str) -> impl Iterator<Item = &str> {
+    code.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .filter(|token| is_identifier(token))
+}
+
+pub(super) fn is_identifier(value: &str) -> bool {
+    let mut chars = value.chars();
+    matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
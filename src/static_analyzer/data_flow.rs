@@ -0,0 +1,152 @@
+//! Data flow of escaping values
+//! Follows an escape's variable back through the assignments of its function to where
+//! the value was created, then records the moves, stores and drops on the way to the
+//! escape site (`worker` created at line 12 → `worker` stored into `workers` (line 15)
+//! → `workers` dropped (line 20)). Findings whose analyzer already tracked the flow, such as the
+//! Rust handle walker or call-chain findings, keep their own.
+
+use crate::protocol::StaticEscape;
+use crate::static_analyzer::callgraph::{
+    extract_calls, extract_functions, identifiers, is_identifier, last_segment, FunctionDef, Language,
+};
+use crate::static_analyzer::taint::{assignment, parameter_names};
+
+/// Methods that put their argument into the receiver.
+const STORE_METHODS: &[&str] = &[
+    "push", "push_back", "append", "add", "put", "set", "insert", "extend", "offer", "unshift", "Store",
+];
+
+/// Fills `data_flow` on every escape that has none and whose variable can be traced.
+pub fn annotate_flows(language: &str, source_file: &str, escapes: &mut [StaticEscape]) {
+    let Some(language) = Language::from_name(language) else {
+        return;
+    };
+    let Ok(source) = std::fs::read_to_string(source_file) else {
+        return;
+    };
+    let functions = extract_functions(&source, language);
+
+    for escape in escapes.iter_mut().filter(|escape| escape.data_flow.is_empty()) {
+        let line = escape.location.line;
+        // Innermost function whose body holds the line.
+        let Some(function) = functions
+            .iter()
+            .filter(|function| function.body.iter().any(|(body_line, _)| *body_line == line))
+            .max_by_key(|function| function.line)
+        else {
+            continue;
+        };
+        escape.data_flow = trace(function, &escape.variable_name, line, language);
+    }
+}
+
+/// Steps that carried `variable`'s value to the escape at `line`, or nothing when its
+/// origin is not in the function or the value never moved.
+fn trace(function: &FunctionDef, variable: &str, line: usize, language: Language) -> Vec<String> {
+    if !is_identifier(variable) {
+        return Vec::new();
+    }
+    let parameters = parameter_names(&function.parameters, language);
+    let known = |name: &str, before: usize| {
+        parameters.iter().any(|parameter| parameter == name) || last_assignment(function, name, before, language).is_some()
+    };
+
+    // Backward: `b = a` hands the value on, so follow `a` until the value is created.
+    let mut names = vec![variable.to_string()];
+    let mut steps: Vec<(usize, String)> = Vec::new();
+    let mut before = line + 1;
+    let origin = loop {
+        let current = names.last().cloned().unwrap_or_default();
+        match last_assignment(function, &current, before, language) {
+            Some((at, value)) => {
+                let source = value.trim().trim_end_matches(';').trim_start_matches(['&', '*']).trim();
+                if is_identifier(source) && !names.iter().any(|name| name == source) && known(source, at) {
+                    steps.push((at, format!("moved to `{}` (line {})", current, at)));
+                    names.push(source.to_string());
+                    before = at;
+                    continue;
+                }
+                steps.push((at, format!("`{}` created at line {}", current, at)));
+                break at;
+            }
+            None if parameters.contains(&current) => {
+                steps.push((function.line, format!("parameter `{}` of {}", current, function.name)));
+                break function.line;
+            }
+            None => return Vec::new(),
+        }
+    };
+
+    // Forward: moves, stores and drops up to the escape. An escape reported where the
+    // value is created (a handle never joined) follows it to the end of the function.
+    let end = if line > origin { line } else { usize::MAX };
+    let holds = |code: &str, names: &[String]| {
+        identifiers(code)
+            .find(|identifier| names.iter().any(|name| name == identifier))
+            .map(str::to_string)
+    };
+    for (at, code) in function.body.iter().filter(|(at, _)| *at > origin && *at < end) {
+        if let Some((targets, value)) = assignment(code, language) {
+            let value = value.trim().trim_end_matches(';').trim_start_matches(['&', '*']).trim();
+            if names.iter().any(|name| name == value) {
+                for target in targets {
+                    if !names.contains(&target) {
+                        steps.push((*at, format!("moved to `{}` (line {})", target, at)));
+                        names.push(target);
+                    }
+                }
+            }
+        }
+        for call in extract_calls(code, language) {
+            let method = last_segment(&call.chain);
+            if method == "drop" && call.arguments.len() == 1 {
+                if let Some(name) = holds(&call.arguments[0], &names) {
+                    steps.push((*at, format!("`{}` dropped (line {})", name, at)));
+                }
+                continue;
+            }
+            if !STORE_METHODS.contains(&method) {
+                continue;
+            }
+            // `workers = append(workers, worker)` stores into its first argument.
+            let (receiver, arguments) = match call.chain.split_once(['.', ':']) {
+                Some((receiver, _)) => (receiver, &call.arguments[..]),
+                None if method == "append" && !call.arguments.is_empty() => {
+                    (call.arguments[0].as_str(), &call.arguments[1..])
+                }
+                None => continue,
+            };
+            if !is_identifier(receiver) {
+                continue;
+            }
+            if names.iter().any(|name| name == receiver) {
+                if let Some(added) = arguments.iter().find(|argument| is_identifier(argument)) {
+                    steps.push((*at, format!("`{}` added to `{}` (line {})", added, receiver, at)));
+                }
+            } else if let Some(name) = arguments.iter().find_map(|argument| holds(argument, &names)) {
+                // The container now carries the value on.
+                steps.push((*at, format!("`{}` stored into `{}` (line {})", name, receiver, at)));
+                names.push(receiver.to_string());
+            }
+        }
+    }
+
+    if steps.iter().all(|(at, _)| *at == line) {
+        return Vec::new();
+    }
+    steps.sort_by_key(|(at, _)| *at);
+    let mut flow: Vec<String> = steps.into_iter().map(|(_, step)| step).collect();
+    if line > origin {
+        flow.push(format!("escapes at line {}", line));
+    }
+    flow
+}
+
+/// The last assignment to `name` before `before`, as (line, assigned expression).
+fn last_assignment(function: &FunctionDef, name: &str, before: usize, language: Language) -> Option<(usize, String)> {
+    function.body.iter().rev().filter(|(at, _)| *at < before).find_map(|(at, code)| {
+        assignment(code, language)
+            .filter(|(targets, _)| targets.iter().any(|target| target == name))
+            .map(|(_, value)| (*at, value))
+    })
+}
@@ -0,0 +1,164 @@
+//! Process exit detection
+//! Flags calls that end the whole process (`std::process::exit`, `os._exit`,
+//! `System.exit`, `process.exit()`, `os.Exit`) in the target or any function it reaches.
+//! Nothing after the exit runs: the joins, shutdowns and awaits that follow it in the
+//! exiting function and in every caller up to the target are skipped, and so are Go's
+//! deferred calls.
+
+use crate::protocol::{CallGraph, ConfidenceLevel, EscapeType, SourceLocation, StaticEscape};
+use crate::static_analyzer::callgraph::{call_chain, extract_functions, FunctionDef, Language};
+use crate::static_analyzer::rules::{self, Rule};
+use std::collections::HashMap;
+
+/// Catalog rule of these findings.
+const RULE: Rule = rules::EXIT_SKIPS_CLEANUP;
+
+/// Calls that wait for or release concurrent work.
+const CLEANUP_PATTERNS: &[&str] = &[
+    ".join(", ".shutdown(", ".shutdownNow(", ".awaitTermination(", ".Wait()", ".wait(",
+    ".close()", ".terminate(", ".await", "await ", "Promise.all", "drop(",
+];
+
+fn exit_patterns(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::Python => &["sys.exit(", "os._exit(", "os.abort(", "os.kill(os.getpid()"],
+        Language::JavaScript => &["process.exit(", "process.abort(", "process.kill(process.pid"],
+        Language::Go => &["os.Exit(", "log.Fatal", "syscall.Exit("],
+        Language::Java => &["System.exit(", "Runtime.getRuntime().halt("],
+        Language::Rust => &["process::exit(", "process::abort("],
+    }
+}
+
+/// Exit findings for the target and every function in its call graph.
+pub fn exit_paths(language: &str, source_file: &str, graph: &CallGraph) -> Vec<StaticEscape> {
+    let Some(language) = Language::from_name(language) else {
+        return Vec::new();
+    };
+    let Ok(source) = std::fs::read_to_string(source_file) else {
+        return Vec::new();
+    };
+    let raw_lines: Vec<&str> = source.lines().collect();
+    let functions = extract_functions(&source, language);
+    let mut defined: HashMap<&str, &FunctionDef> = HashMap::new();
+    for function in &functions {
+        defined.entry(function.name.as_str()).or_insert(function);
+    }
+
+    let mut findings = Vec::new();
+    for node in graph.nodes.iter().filter(|node| node.line.is_some()) {
+        let Some(function) = defined.get(node.name.as_str()) else {
+            continue;
+        };
+        let chain = if node.name == graph.root {
+            Vec::new()
+        } else {
+            match call_chain(graph, &node.name) {
+                Some(chain) => chain,
+                None => continue,
+            }
+        };
+
+        for (line, code) in &function.body {
+            let Some(exit) = exit_patterns(language).iter().find(|pattern| code.contains(*pattern)) else {
+                continue;
+            };
+            let exit = exit.trim_end_matches('(');
+
+            // The exiting function after the exit, then each caller after its call.
+            let mut frames: Vec<(&FunctionDef, usize)> = vec![(function, *line)];
+            for (caller, call_line) in chain.iter().rev() {
+                if let Some(caller) = defined.get(caller.as_str()) {
+                    frames.push((caller, *call_line));
+                }
+            }
+            let mut skipped = Vec::new();
+            let mut spawned = Vec::new();
+            for (frame, from) in &frames {
+                for (body_line, body_code) in &frame.body {
+                    if *body_line > *from && is_cleanup(body_code) {
+                        skipped.push(format!("`{}` at line {}", body_code.trim(), body_line));
+                    } else if *body_line < *from && language == Language::Go && body_code.trim().starts_with("defer ") {
+                        skipped.push(format!("deferred `{}` at line {}", body_code.trim(), body_line));
+                    }
+                    if *body_line < *from && language.is_spawn_site(body_code) {
+                        spawned.push(*body_line);
+                    }
+                }
+            }
+
+            let place = if chain.is_empty() {
+                format!("`{}` at line {} ends the process", exit, line)
+            } else {
+                let names: Vec<&str> = chain
+                    .iter()
+                    .map(|(caller, _)| caller.as_str())
+                    .chain([node.name.as_str()])
+                    .collect();
+                format!(
+                    "`{}` at line {} in `{}` (reached through {}) ends the process",
+                    exit,
+                    line,
+                    node.name,
+                    names.join(" → ")
+                )
+            };
+            let (reason, confidence) = if !skipped.is_empty() {
+                (format!("{}; never runs {}", place, skipped.join(", ")), ConfidenceLevel::High)
+            } else if !spawned.is_empty() {
+                let lines: Vec<String> = spawned.iter().map(|line| line.to_string()).collect();
+                (
+                    format!(
+                        "{}; work started at line(s) {} is cut off without cleanup",
+                        place,
+                        lines.join(", ")
+                    ),
+                    ConfidenceLevel::Medium,
+                )
+            } else {
+                (format!("{}; nothing after it runs", place), ConfidenceLevel::Low)
+            };
+
+            let mut data_flow: Vec<String> = chain
+                .iter()
+                .map(|(caller, call_line)| format!("{} calls at line {}", caller, call_line))
+                .collect();
+            data_flow.push(format!("{} line {}: {}", node.name, line, exit));
+            data_flow.extend(skipped.iter().map(|skipped| format!("skips {}", skipped)));
+
+            // Reported on the target, at the call leading to the exit.
+            let report_line = chain.first().map(|(_, call_line)| *call_line).unwrap_or(*line);
+            findings.push(StaticEscape {
+                escape_type: EscapeType::ExitEscape,
+                location: SourceLocation {
+                    file: source_file.to_string(),
+                    line: report_line,
+                    column: 0,
+                    function: graph.root.clone(),
+                    code_snippet: raw_lines.get(report_line - 1).map(|code| code.trim().to_string()),
+                },
+                variable_name: exit.to_string(),
+                reason,
+                confidence,
+                data_flow,
+                input_tainted: false,
+                taint_flow: vec![],
+                rule: Some(RULE.name.to_string()),
+                rule_id: Some(RULE.id.to_string()),
+                condition: None,
+                suggested_fix: None,
+            });
+        }
+    }
+    findings
+}
+
+/// Whether a line waits for or releases concurrent work. String joins (`", ".join(..)`)
+/// and path joins are not cleanup.
+fn is_cleanup(code: &str) -> bool {
+    CLEANUP_PATTERNS.iter().any(|pattern| {
+        code.match_indices(pattern).any(|(index, _)| {
+            let before = &code[..index];
+            *pattern != ".join(" || !(before.ends_with(['"', '\'']) || before.ends_with("path"))
+        })
+    })
+}
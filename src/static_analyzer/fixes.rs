@@ -0,0 +1,213 @@
+//! Suggested fixes
+//! Once rule ids are assigned, the analyzer of each finding may attach a fix from the
+//! template of its rule (`StaticEscapeAnalyzer::suggest_fix`): what to change, the
+//! code, and, when the change can be placed mechanically, the line edits making it.
+//! Findings reached through the call graph get none, since the code to change is in
+//! the helper.
+
+use crate::protocol::{FixEdit, StaticEscape, SuggestedFix};
+use crate::static_analyzer::callgraph::{self, Language};
+use crate::static_analyzer::StaticEscapeAnalyzer;
+
+/// The source around a finding, for placing its fix.
+pub struct FixContext<'a> {
+    lines: Vec<&'a str>,
+    /// First and last line of the function holding the finding; for braced languages
+    /// the last is the closing brace.
+    span: Option<(usize, usize)>,
+    language: Language,
+}
+
+impl<'a> FixContext<'a> {
+    /// Source line `line` (1-based), empty past the end.
+    pub fn line(&self, line: usize) -> &'a str {
+        line.checked_sub(1).and_then(|index| self.lines.get(index)).copied().unwrap_or("")
+    }
+
+    pub fn indent(&self, line: usize) -> &'a str {
+        let text = self.line(line);
+        &text[..text.len() - text.trim_start().len()]
+    }
+
+    /// Definition line of the function holding the finding.
+    pub fn header(&self) -> &'a str {
+        self.span.map_or("", |(start, _)| self.line(start))
+    }
+
+    /// Indentation of the block opened on `line`: that of its first statement, else one
+    /// `unit` deeper.
+    pub fn block_indent(&self, line: usize, unit: &str) -> String {
+        let next = self.indent(line + 1);
+        if next.len() > self.indent(line).len() {
+            next.to_string()
+        } else {
+            format!("{}{}", self.indent(line), unit)
+        }
+    }
+
+    /// Whether the function holding the finding is async, so it can await.
+    pub fn is_async(&self) -> bool {
+        self.header().split_whitespace().any(|word| word == "async")
+    }
+
+    /// Lines of the function's body, none when the function was not found.
+    pub fn body(&self) -> std::ops::Range<usize> {
+        self.span.map_or(0..0, |(start, end)| start + 1..end + 1)
+    }
+
+    /// Indentation of the function's own statements.
+    pub fn body_indent(&self) -> Option<&'a str> {
+        let (start, end) = self.span?;
+        (start + 1..=end).find(|line| !self.line(*line).trim().is_empty()).map(|line| self.indent(line))
+    }
+
+    /// Whether `line` is a statement of the function itself rather than of a nested
+    /// block, so what it declares is in scope until the function returns.
+    pub fn at_body_level(&self, line: usize) -> bool {
+        self.body_indent().is_some_and(|indent| indent == self.indent(line))
+    }
+
+    /// First line of the function declaring or assigning `name` (`let name =`,
+    /// `name := ...`, `var name T`, `Type name = ...`).
+    pub fn declaration(&self, name: &str) -> Option<usize> {
+        let (start, end) = self.span?;
+        (start..=end).find(|line| {
+            let code = self.line(*line);
+            code.match_indices(name).any(|(at, _)| {
+                let before = code[..at].chars().next_back();
+                let after = code[at + name.len()..].trim_start();
+                let declared = code[..at].trim_end().ends_with("var") && !after.starts_with('=');
+                !before.is_some_and(|ch| ch.is_alphanumeric() || ch == '_' || ch == '.')
+                    && (declared || after.starts_with(":=") || (after.starts_with('=') && !after.starts_with("==")))
+            })
+        })
+    }
+
+    /// Whether `name` is mentioned on `line` or a later line of the function.
+    pub fn mentioned_from(&self, name: &str, line: usize) -> bool {
+        self.span.is_some_and(|(_, end)| {
+            (line..=end).any(|line| callgraph::identifiers(self.line(line)).any(|word| word == name))
+        })
+    }
+
+    /// The statement on `line` when it starts and ends there, without its `;`.
+    pub fn single_statement(&self, line: usize) -> Option<&'a str> {
+        let code = self.line(line).trim();
+        let balanced = |open: char, close: char| code.matches(open).count() == code.matches(close).count();
+        let complete = balanced('(', ')') && balanced('{', '}') && balanced('[', ']');
+        let continues = code.ends_with([',', '.', '(', '{', '=']) || self.line(line + 1).trim().starts_with('.');
+        (!code.is_empty() && complete && !continues).then(|| code.trim_end_matches(';').trim_end())
+    }
+
+    /// Where cleanup for work started at `after` goes, with the indentation to use:
+    /// before the first later `return` at the same depth or shallower, else before the
+    /// function's tail expression or closing brace, or after its last line (Python).
+    pub fn exit(&self, after: usize) -> Option<(usize, &'a str)> {
+        let (_, end) = self.span?;
+        let body = self.body_indent()?;
+        let depth = self.indent(after).len();
+        for line in after + 1..=end {
+            let code = self.line(line).trim();
+            if (code == "return" || code.starts_with("return ") || code.starts_with("return;"))
+                && self.indent(line).len() <= depth
+            {
+                return Some((line, self.indent(line)));
+            }
+        }
+        if self.language == Language::Python {
+            return Some((end + 1, body));
+        }
+        let last = (after + 1..end).rev().find(|line| !self.line(*line).trim().is_empty());
+        match last {
+            Some(line) if self.language == Language::Rust && is_tail_expression(self.line(line)) && self.indent(line) == body => {
+                Some((line, body))
+            }
+            _ => Some((end, body)),
+        }
+    }
+}
+
+/// A tail expression: the last line of a Rust body that is not a statement.
+fn is_tail_expression(line: &str) -> bool {
+    let code = line.trim();
+    !code.is_empty() && !code.ends_with([';', '{', '}', ',']) && !code.starts_with("//")
+}
+
+/// `code` lines inserted before `line` at `indent`.
+pub fn insert(line: usize, indent: &str, code: &[String]) -> FixEdit {
+    FixEdit { line, replaced_lines: 0, text: indented(indent, code) }
+}
+
+/// Line `line` replaced by `code` lines at `indent`.
+pub fn replace(line: usize, indent: &str, code: &[String]) -> FixEdit {
+    FixEdit { line, replaced_lines: 1, text: indented(indent, code) }
+}
+
+/// Lines `first` to `last` replaced by `code` lines at `indent`.
+pub fn replace_block(first: usize, last: usize, indent: &str, code: &[String]) -> FixEdit {
+    FixEdit { line: first, replaced_lines: last + 1 - first, text: indented(indent, code) }
+}
+
+fn indented(indent: &str, code: &[String]) -> String {
+    code.iter()
+        .map(|line| if line.is_empty() { "\n".to_string() } else { format!("{}{}\n", indent, line) })
+        .collect()
+}
+
+/// A fix made by `edits`, shown as the code they add.
+pub fn placed(description: String, edits: Vec<FixEdit>) -> SuggestedFix {
+    let snippet = edits
+        .iter()
+        .map(|edit| dedent(&edit.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    SuggestedFix { description, snippet: snippet.trim_end().to_string(), edits }
+}
+
+/// `code` inserted before the function returns when the statement on `line` is at the
+/// function's own level, so what it declares is still in scope; only shown otherwise.
+pub fn before_exit(description: String, code: &[String], line: usize, context: &FixContext) -> SuggestedFix {
+    match context.exit(line).filter(|_| context.at_body_level(line)) {
+        Some((exit, indent)) => placed(description, vec![insert(exit, indent, code)]),
+        None => described(description, &code.join("\n")),
+    }
+}
+
+/// A fix only described, with example code.
+pub fn described(description: String, snippet: &str) -> SuggestedFix {
+    SuggestedFix { description, snippet: snippet.trim().to_string(), edits: vec![] }
+}
+
+fn dedent(text: &str) -> String {
+    let common = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    text.lines().map(|line| format!("{}\n", line.get(common..).unwrap_or("").trim_end())).collect()
+}
+
+/// Attaches the analyzer's suggested fix to each finding with a rule id.
+pub fn suggest(analyzer: &dyn StaticEscapeAnalyzer, source_file: &str, escapes: &mut [StaticEscape]) {
+    let Ok(source) = std::fs::read_to_string(source_file) else {
+        return;
+    };
+    let Some(language) = Language::from_name(analyzer.language()) else {
+        return;
+    };
+    let spans = callgraph::function_spans(analyzer.language(), &source);
+    for escape in escapes.iter_mut() {
+        if escape.rule_id.is_none() || escape.reason.starts_with("Reached through ") {
+            continue;
+        }
+        let line = escape.location.line;
+        let containing = || spans.iter().filter(|(_, start, end)| (*start..=*end).contains(&line));
+        let span = containing()
+            .find(|(name, _, _)| *name == escape.location.function)
+            .or_else(|| containing().min_by_key(|(_, start, end)| end - start))
+            .map(|(_, start, end)| (*start, *end));
+        let context = FixContext { lines: source.lines().collect(), span, language };
+        escape.suggested_fix = analyzer.suggest_fix(escape, &context);
+    }
+}
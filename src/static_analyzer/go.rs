@@ -1,9 +1,16 @@
-/// Go static escape analyzer using text-based pattern matching
+//! Go static escape analyzer using text-based pattern matching
+//! Patterns run on `preprocess::logical_lines`, so comments and string contents are
+//! never matched and a statement split over several lines is read as one.
 
 use crate::protocol::{
     ConfidenceLevel, EscapeType, SourceLocation, StaticAnalysisResult, StaticEscape,
-    StaticEscapeSummary,
+    StaticEscapeSummary, SuggestedFix,
 };
+use crate::static_analyzer::fixes::{self, FixContext};
+use crate::static_analyzer::waitgroup::{self, Awaited};
+use crate::static_analyzer::callgraph::Language;
+use crate::static_analyzer::preprocess;
+use crate::static_analyzer::rules::{self, Rule};
 use crate::static_analyzer::StaticEscapeAnalyzer;
 use anyhow::{Context, Result};
 use std::collections::{HashMap, HashSet};
@@ -34,14 +41,18 @@ impl StaticEscapeAnalyzer for GoStaticAnalyzer {
         let source = fs::read_to_string(source_file)
             .with_context(|| format!("Failed to read source file: {}", source_file))?;
         
+        let code = preprocess::logical_lines(&source, Language::Go).join("\n");
         let target_function = parse_target_function(target);
         let mut warnings = vec![];
         
-        let escapes = if let Some(function_name) = target_function.as_deref() {
-            analyze_function(&source, source_file, function_name, &mut warnings)
+        let mut escapes = if let Some(function_name) = target_function.as_deref() {
+            let mut escapes = analyze_function(&code, source_file, function_name, &mut warnings);
+            escapes.extend(waitgroup::check(&code, source_file, function_name));
+            escapes
         } else {
-            analyze_file(&source, source_file)
+            analyze_file(&code, source_file)
         };
+        preprocess::restore_snippets(&mut escapes, &source);
         
         let mut summary = StaticEscapeSummary::new();
         for escape in &escapes {
@@ -55,6 +66,8 @@ impl StaticEscapeAnalyzer for GoStaticAnalyzer {
             analysis_time_ms: start_time.elapsed().as_millis() as u64,
             warnings,
             summary,
+            call_graph: None,
+            spawn_estimate: None,
         })
     }
     
@@ -68,6 +81,10 @@ impl StaticEscapeAnalyzer for GoStaticAnalyzer {
             .output()
             .is_ok()
     }
+
+    fn suggest_fix(&self, escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+        suggested_fix(escape, context)
+    }
 }
 
 fn parse_target_function(target: &str) -> Option<String> {
@@ -98,6 +115,7 @@ fn analyze_function(
     let lines: Vec<&str> = source.lines().collect();
     let module_retainers = collect_package_retainers(&lines);
     let bridge_method_sinks = collect_go_bridge_method_sinks(&lines, &module_retainers);
+    let awaited = waitgroup::awaited_goroutines(source, function_name);
 
     let mut escapes = vec![];
     let mut dedupe: HashSet<String> = HashSet::new();
@@ -178,7 +196,7 @@ fn analyze_function(
                         push_unique_escape(
                             &mut escapes,
                             &mut dedupe,
-                            "global",
+                            &rules::GO_HELPER_RETAINED,
                             EscapeType::GlobalEscape,
                             source_file,
                             idx + 1,
@@ -215,7 +233,7 @@ fn analyze_function(
                             push_unique_escape(
                                 &mut escapes,
                                 &mut dedupe,
-                                "global",
+                                &rules::GO_HELPER_RETAINED,
                                 EscapeType::GlobalEscape,
                                 source_file,
                                 idx + 1,
@@ -260,7 +278,7 @@ fn analyze_function(
                         push_unique_escape(
                             &mut escapes,
                             &mut dedupe,
-                            if is_closure { "closure" } else { "global" },
+                            if is_closure { &rules::GO_RETAINED_CLOSURE } else { &rules::GO_RETAINED_CONTAINER },
                             escape_type,
                             source_file,
                             idx + 1,
@@ -292,7 +310,7 @@ fn analyze_function(
                         push_unique_escape(
                             &mut escapes,
                             &mut dedupe,
-                            "global",
+                            &rules::GO_RETAINED_CONTAINER,
                             EscapeType::GlobalEscape,
                             source_file,
                             idx + 1,
@@ -336,7 +354,7 @@ fn analyze_function(
                         push_unique_escape(
                             &mut escapes,
                             &mut dedupe,
-                            if is_closure { "closure" } else { "global" },
+                            if is_closure { &rules::GO_RETAINED_CLOSURE } else { &rules::GO_RETAINED_CONTAINER },
                             escape_type,
                             source_file,
                             idx + 1,
@@ -359,7 +377,7 @@ fn analyze_function(
                     push_unique_escape(
                         &mut escapes,
                         &mut dedupe,
-                        "return",
+                        &rules::GO_RETURNED_OBJECT,
                         EscapeType::ReturnEscape,
                         source_file,
                         idx + 1,
@@ -383,23 +401,38 @@ fn analyze_function(
                 received_channels.insert(chan_var);
             }
             
-            // Detect goroutine spawns
+            // Detect goroutine spawns; those a balanced WaitGroup waits for are not escapes.
             if trimmed.contains("go ") && !trimmed.starts_with("//") {
-                let reason = "Goroutine spawned - may not complete before function return".to_string();
-                push_unique_escape(
-                    &mut escapes,
-                    &mut dedupe,
-                    "goroutine",
-                    classify_go_async_escape(Some(trimmed), &reason, "goroutine"),
-                    source_file,
-                    idx + 1,
-                    0,
-                    function_name,
-                    "goroutine".to_string(),
-                    reason,
-                    ConfidenceLevel::High,
-                    Some(trimmed.to_string()),
-                );
+                let escape = match awaited.get(&(idx + 1)) {
+                    Some(Awaited::Always(_)) => None,
+                    Some(Awaited::Partially(wait)) => Some((
+                        format!(
+                            "Goroutine spawned - {} waits for it, but Add/Done may not balance on every path",
+                            wait
+                        ),
+                        ConfidenceLevel::Low,
+                    )),
+                    None => Some((
+                        "Goroutine spawned - may not complete before function return".to_string(),
+                        ConfidenceLevel::High,
+                    )),
+                };
+                if let Some((reason, confidence)) = escape {
+                    push_unique_escape(
+                        &mut escapes,
+                        &mut dedupe,
+                        &rules::GO_UNAWAITED_GOROUTINE,
+                        classify_go_async_escape(Some(trimmed), &reason, "goroutine"),
+                        source_file,
+                        idx + 1,
+                        0,
+                        function_name,
+                        "goroutine".to_string(),
+                        reason,
+                        confidence,
+                        Some(trimmed.to_string()),
+                    );
+                }
             }
             
             // Track braces
@@ -414,7 +447,7 @@ fn analyze_function(
                         push_unique_escape(
                             &mut escapes,
                             &mut dedupe,
-                            "channel",
+                            &rules::GO_UNRECEIVED_CHANNEL,
                             classify_go_async_escape(None, &reason, chan),
                             source_file,
                             idx + 1,
@@ -997,7 +1030,7 @@ fn extract_return_identifier(line: &str) -> Option<String> {
 fn push_unique_escape(
     escapes: &mut Vec<StaticEscape>,
     dedupe: &mut HashSet<String>,
-    key_type: &str,
+    rule: &Rule,
     escape_type: EscapeType,
     source_file: &str,
     line: usize,
@@ -1008,7 +1041,7 @@ fn push_unique_escape(
     confidence: ConfidenceLevel,
     code_snippet: Option<String>,
 ) {
-    let key = format!("{}|{}|{}|{}", key_type, line, variable_name, reason);
+    let key = format!("{}|{}|{}|{}", rule.id, line, variable_name, reason);
     if !dedupe.insert(key) {
         return;
     }
@@ -1026,6 +1059,12 @@ fn push_unique_escape(
         reason,
         confidence,
         data_flow: vec![],
+        input_tainted: false,
+        taint_flow: vec![],
+        rule: None,
+        rule_id: Some(rule.id.to_string()),
+        condition: None,
+        suggested_fix: None,
     });
 }
 
@@ -1124,13 +1163,19 @@ fn detect_goroutine(line: &str, source_file: &str, line_num: usize, function: &s
             reason,
             confidence: ConfidenceLevel::High,
             data_flow: vec![],
+            input_tainted: false,
+            taint_flow: vec![],
+            rule: None,
+            rule_id: Some(rules::GO_UNAWAITED_GOROUTINE.id.to_string()),
+            condition: None,
+            suggested_fix: None,
         })
     } else {
         None
     }
 }
 
-fn classify_go_async_escape(line: Option<&str>, reason: &str, variable_name: &str) -> EscapeType {
+pub(super) fn classify_go_async_escape(line: Option<&str>, reason: &str, variable_name: &str) -> EscapeType {
     let combined = format!("{} {} {}", reason, variable_name, line.unwrap_or_default()).to_lowercase();
 
     if combined.contains("return") || combined.contains("returned") {
@@ -1147,3 +1192,120 @@ fn classify_go_async_escape(line: Option<&str>, reason: &str, variable_name: &st
         EscapeType::HeapEscape
     }
 }
+
+const WAIT_GROUP_SNIPPET: &str = "
+var wg sync.WaitGroup
+wg.Add(1)
+go func() {
+\tdefer wg.Done()
+\t// ...
+}()
+wg.Wait()
+";
+
+const BOUNDED_GOROUTINES_SNIPPET: &str = "
+var wg sync.WaitGroup
+sem := make(chan struct{}, runtime.NumCPU())
+for _, item := range items {
+\twg.Add(1)
+\tsem <- struct{}{}
+\tgo func() {
+\t\tdefer func() { <-sem; wg.Done() }()
+\t\tprocess(item)
+\t}()
+}
+wg.Wait()
+";
+
+/// Fix for a finding from the template of its rule.
+fn suggested_fix(escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+    let line = escape.location.line;
+    let name = escape.variable_name.as_str();
+    match escape.rule_id.as_deref()? {
+        "GH-GO-001" => Some(wait_group_fix(escape, context)),
+        "GH-GO-002" if escape.reason.contains(" waits for it") => Some(fixes::described(
+            "Call `Add(1)` right before `go` and `defer Done()` first thing in the goroutine".to_string(),
+            WAIT_GROUP_SNIPPET,
+        )),
+        "GH-GO-002" => Some(goroutine_fix(line, context)),
+        "GH-GO-003" => {
+            let description = format!("Receive from `{}` before returning so the sending goroutine can finish", name);
+            let declared = context.declaration(name).unwrap_or(line);
+            Some(fixes::before_exit(description, &[format!("<-{}", name)], declared, context))
+        }
+        "GH-GEN-001" => Some(fixes::described(
+            "Bound the goroutines with a semaphore channel and wait for them with a `sync.WaitGroup`".to_string(),
+            BOUNDED_GOROUTINES_SNIPPET,
+        )),
+        _ => None,
+    }
+}
+
+/// A goroutine nothing waits for, counted on a new `sync.WaitGroup` waited on before
+/// the function returns.
+fn goroutine_fix(line: usize, context: &FixContext) -> SuggestedFix {
+    let description = "Wait for the goroutine with a `sync.WaitGroup` before returning".to_string();
+    let code = context.line(line).trim();
+    let indent = context.indent(line);
+    let placeable = code.starts_with("go ") && context.at_body_level(line) && context.declaration("wg").is_none();
+    let Some((exit, exit_indent)) = context.exit(line).filter(|_| placeable) else {
+        return fixes::described(description, WAIT_GROUP_SNIPPET);
+    };
+    let count = ["var wg sync.WaitGroup".to_string(), "wg.Add(1)".to_string()];
+    let wait = fixes::insert(exit, exit_indent, &["wg.Wait()".to_string()]);
+    if code.ends_with('{') {
+        let body = context.block_indent(line, "\t");
+        fixes::placed(
+            description,
+            vec![
+                fixes::insert(line, indent, &count),
+                fixes::insert(line + 1, &body, &["defer wg.Done()".to_string()]),
+                wait,
+            ],
+        )
+    } else if !code.contains('{') {
+        let call = code.trim_start_matches("go ").trim();
+        let mut wrapped = count.to_vec();
+        wrapped.extend(["go func() {".to_string(), "\tdefer wg.Done()".to_string(), format!("\t{}", call), "}()".to_string()]);
+        fixes::placed(description, vec![fixes::replace(line, indent, &wrapped), wait])
+    } else {
+        fixes::described(description, WAIT_GROUP_SNIPPET)
+    }
+}
+
+fn wait_group_fix(escape: &StaticEscape, context: &FixContext) -> SuggestedFix {
+    let line = escape.location.line;
+    let name = escape.variable_name.as_str();
+    if escape.reason.starts_with("errgroup") {
+        return fixes::described(
+            format!("Call `{}.Wait()` before returning and handle its error", name),
+            &format!("if err := {}.Wait(); err != nil {{\n\treturn err\n}}", name),
+        );
+    }
+    if escape.reason.contains("is never waited on") {
+        let description = format!("Call `{}.Wait()` before the function returns", name);
+        let statement = format!("{}.Wait()", name);
+        return match context.exit(line) {
+            Some((exit, indent)) => fixes::placed(description, vec![fixes::insert(exit, indent, &[statement])]),
+            None => fixes::described(description, &statement),
+        };
+    }
+    let done = format!("defer {}.Done()", name);
+    if escape.reason.contains("but never calls") && context.line(line).trim_end().ends_with('{') {
+        let body = context.block_indent(line, "\t");
+        return fixes::placed(
+            format!("Call `{}` first thing in the goroutine", done),
+            vec![fixes::insert(line + 1, &body, &[done])],
+        );
+    }
+    if escape.reason.starts_with("Goroutine ") {
+        return fixes::described(
+            format!("Call `{}` first thing in the goroutine so every path reaches it", done),
+            &done,
+        );
+    }
+    fixes::described(
+        format!("Call `{}.Add(1)` right before each `go` and `{}` first thing in the goroutine", name, done),
+        &WAIT_GROUP_SNIPPET.replace("wg.", &format!("{}.", name)).replace("var wg ", &format!("var {} ", name)),
+    )
+}
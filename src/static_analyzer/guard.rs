@@ -0,0 +1,311 @@
+//! Guarding conditions
+//! Finds the `if`/`else`, `match`/`switch` arm and `except`/`catch` branches that
+//! enclose an escape, so a finding can state which inputs exercise it
+//! (`condition: input.len() > 3`).
+
+use crate::protocol::StaticEscape;
+use crate::static_analyzer::callgraph::{extract_functions, FunctionDef, Language};
+
+/// Sets `condition` on every escape that sits inside a conditional branch of its function.
+pub fn annotate_conditions(language: &str, source_file: &str, escapes: &mut [StaticEscape]) {
+    let Some(language) = Language::from_name(language) else {
+        return;
+    };
+    let Ok(source) = std::fs::read_to_string(source_file) else {
+        return;
+    };
+    let raw_lines: Vec<&str> = source.lines().collect();
+    let functions = extract_functions(&source, language);
+
+    for escape in escapes.iter_mut().filter(|escape| escape.condition.is_none()) {
+        let line = escape.location.line;
+        // Innermost function whose body holds the line.
+        let Some(function) = functions
+            .iter()
+            .filter(|function| function.body.iter().any(|(body_line, _)| *body_line == line))
+            .max_by_key(|function| function.line)
+        else {
+            continue;
+        };
+        let guards = if language == Language::Python {
+            indented_guards(function, line, &raw_lines)
+        } else {
+            braced_guards(function, line, &raw_lines, language)
+        };
+        if !guards.is_empty() {
+            let separator = if language == Language::Python { " and " } else { " && " };
+            escape.condition = Some(guards.join(separator));
+        }
+    }
+}
+
+/// Guards around `line` in an indentation-structured (Python) function, outermost first.
+fn indented_guards(function: &FunctionDef, line: usize, raw_lines: &[&str]) -> Vec<String> {
+    let indent = |code: &str| code.len() - code.trim_start().len();
+    let Some(position) = function.body.iter().position(|(body_line, _)| *body_line == line) else {
+        return Vec::new();
+    };
+
+    let mut guards = Vec::new();
+    // `if x: spawn()` on the escape line itself.
+    let own = raw_text(raw_lines, line, Language::Python).trim();
+    if let Some((condition, _)) = own.strip_prefix("if ").and_then(|rest| rest.rsplit_once(':')) {
+        guards.push(condition.trim().to_string());
+    }
+
+    let mut current = indent(&function.body[position].1);
+    for index in (0..position).rev() {
+        let (header_line, code) = &function.body[index];
+        if code.trim().is_empty() || indent(code) >= current {
+            continue;
+        }
+        current = indent(code);
+        let header = code.trim();
+        let text = raw_text(raw_lines, *header_line, Language::Python);
+        let guard = if header.starts_with("if ") {
+            clean(text, "if ")
+        } else if header.starts_with("elif ") {
+            let mut previous = previous_branches(function, index, current, raw_lines);
+            previous.extend(clean(text, "elif "));
+            Some(previous.join(" and "))
+        } else if header.starts_with("else") {
+            Some(previous_branches(function, index, current, raw_lines).join(" and "))
+        } else if header.starts_with("case ") {
+            let pattern = clean(text, "case ");
+            let subject = (0..index).rev().find_map(|outer| {
+                let (outer_line, code) = &function.body[outer];
+                (indent(code) < current && code.trim().starts_with("match "))
+                    .then(|| clean(raw_text(raw_lines, *outer_line, Language::Python), "match "))
+                    .flatten()
+            });
+            pattern.map(|pattern| match subject {
+                Some(subject) => format!("{} matches {}", subject, pattern),
+                None => format!("case {}", pattern),
+            })
+        } else if header.starts_with("except") {
+            clean(text, "except")
+                .filter(|exception| !exception.is_empty())
+                .map(|exception| format!("raises {}", exception.split(" as ").next().unwrap_or(&exception)))
+                .or_else(|| Some("an exception is raised".to_string()))
+        } else {
+            None
+        };
+        if let Some(guard) = guard.filter(|guard| !guard.is_empty()) {
+            guards.push(guard);
+        }
+        if current == 0 {
+            break;
+        }
+    }
+    guards.reverse();
+    guards
+}
+
+/// Negated conditions of the `if`/`elif` branches before an `elif`/`else` at `index`.
+fn previous_branches(function: &FunctionDef, index: usize, indent: usize, raw_lines: &[&str]) -> Vec<String> {
+    let mut negated = Vec::new();
+    for (line, code) in function.body[..index].iter().rev() {
+        let code_indent = code.len() - code.trim_start().len();
+        if code.trim().is_empty() || code_indent > indent {
+            continue;
+        }
+        if code_indent < indent {
+            break;
+        }
+        let text = raw_text(raw_lines, *line, Language::Python);
+        if code.trim().starts_with("elif ") {
+            negated.extend(clean(text, "elif ").map(|condition| format!("not ({})", condition)));
+        } else if code.trim().starts_with("if ") {
+            negated.extend(clean(text, "if ").map(|condition| format!("not ({})", condition)));
+            break;
+        } else {
+            break;
+        }
+    }
+    negated.reverse();
+    negated
+}
+
+/// An open `{` block: its header and the guard it contributes, if any.
+struct Block {
+    header: String,
+    guard: Option<String>,
+}
+
+/// Guards around `line` in a brace-structured function, outermost first.
+fn braced_guards(function: &FunctionDef, line: usize, raw_lines: &[&str], language: Language) -> Vec<String> {
+    let mut stack: Vec<Block> = Vec::new();
+    // Negated conditions of the `if`/`else if` chain that just closed, for a following `else`.
+    let mut closed_chain: Vec<String> = Vec::new();
+    // Latest `case` label per open `switch`/`select` block, by stack depth.
+    let mut case_labels: Vec<(usize, String)> = Vec::new();
+
+    for (body_line, code) in &function.body {
+        let text = raw_text(raw_lines, *body_line, language);
+        // Close `}` at the start of the line first so `} else {` sees the finished chain.
+        let code = code.trim_start();
+        let closers = code.len() - code.trim_start_matches(|ch: char| ch == '}' || ch.is_whitespace()).len();
+        for _ in code[..closers].matches('}') {
+            close_block(&mut stack, &mut closed_chain, &mut case_labels);
+        }
+        let code = &code[closers..];
+        let trimmed = code.trim();
+        if trimmed.starts_with("case ") || trimmed.starts_with("default") {
+            let label = text.trim().trim_start_matches("case ").trim_end_matches(':').trim().to_string();
+            case_labels.retain(|(depth, _)| *depth != stack.len());
+            case_labels.push((stack.len(), label));
+        }
+        if *body_line == line {
+            let mut guards: Vec<String> = stack.iter().filter_map(|block| block.guard.clone()).collect();
+            for (depth, label) in &case_labels {
+                let subject = stack.get(depth.wrapping_sub(1)).map(|block| header_subject(&block.header));
+                guards.push(switch_guard(subject.as_deref(), label));
+            }
+            // Same-line branches: `if (x) spawn();` or a `Pattern => spawn()` match arm.
+            if let Some(condition) = branch_condition(trimmed, text) {
+                guards.push(condition);
+            } else if let Some((pattern, _)) = text.trim().split_once("=>") {
+                if let Some(subject) = stack.last().filter(|block| block.header.starts_with("match ")) {
+                    guards.push(format!("{} matches {}", header_subject(&subject.header), pattern.trim()));
+                }
+            }
+            guards.dedup();
+            return guards;
+        }
+
+        for ch in code.chars() {
+            match ch {
+                '{' => {
+                    let header = text.trim().trim_start_matches('}').trim();
+                    let header = header.split('{').next().unwrap_or(header).trim().to_string();
+                    let guard = block_guard(&header, &stack, &closed_chain);
+                    stack.push(Block { header, guard });
+                }
+                '}' => close_block(&mut stack, &mut closed_chain, &mut case_labels),
+                _ => {}
+            }
+        }
+    }
+    Vec::new()
+}
+
+/// Pops the innermost block, extending or resetting the closed `if` chain.
+fn close_block(stack: &mut Vec<Block>, closed_chain: &mut Vec<String>, case_labels: &mut Vec<(usize, String)>) {
+    if let Some(block) = stack.pop() {
+        let header = block.header.as_str();
+        if header.starts_with("if") {
+            *closed_chain = block.guard.map(|guard| vec![format!("!({})", guard)]).unwrap_or_default();
+        } else if header.starts_with("else if") {
+            if let Some(condition) = branch_condition(header, header) {
+                closed_chain.push(format!("!({})", condition));
+            }
+        } else {
+            closed_chain.clear();
+        }
+    }
+    case_labels.retain(|(depth, _)| *depth <= stack.len());
+}
+
+/// Guard contributed by a block header, if it is a branch.
+fn block_guard(header: &str, stack: &[Block], closed_chain: &[String]) -> Option<String> {
+    if header.starts_with("else if") {
+        let mut guard = closed_chain.to_vec();
+        guard.extend(branch_condition(header, header));
+        Some(guard.join(" && "))
+    } else if header.starts_with("else") {
+        let guard = closed_chain.join(" && ");
+        (!guard.is_empty()).then_some(guard)
+    } else if let Some(condition) = branch_condition(header, header) {
+        Some(condition)
+    } else if let Some(exception) = header.strip_prefix("catch") {
+        let exception = strip_parens(exception.trim());
+        let exception = exception.split_whitespace().next().unwrap_or(exception);
+        Some(format!("throws {}", exception))
+    } else if let Some((pattern, _)) = header.split_once("=>") {
+        let subject = stack.last().filter(|block| block.header.starts_with("match "))?;
+        Some(format!("{} matches {}", header_subject(&subject.header), pattern.trim()))
+    } else {
+        None
+    }
+}
+
+/// Condition of an `if` header, or of `else if` when it is the whole header.
+fn branch_condition(code: &str, text: &str) -> Option<String> {
+    let text = text.trim().trim_start_matches('}').trim();
+    let rest = if code.starts_with("else if") {
+        text.strip_prefix("else if")?
+    } else if code.starts_with("if ") || code.starts_with("if(") {
+        text.strip_prefix("if")?
+    } else {
+        return None;
+    };
+    let rest = rest.split('{').next().unwrap_or(rest).trim();
+    // `if (x) spawn();` without braces: keep the parenthesized condition only.
+    let condition = if rest.starts_with('(') {
+        let mut depth = 0;
+        let end = rest
+            .char_indices()
+            .find(|(_, ch)| {
+                match ch {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            })
+            .map(|(index, _)| index)
+            .unwrap_or(rest.len() - 1);
+        &rest[1..end]
+    } else if let Some((pattern, value)) = rest.strip_prefix("let ").and_then(|binding| binding.split_once(" = ")) {
+        // `if let Some(x) = lookup(input)`
+        return Some(format!("{} matches {}", value.trim(), pattern.trim()));
+    } else {
+        rest
+    };
+    let condition = condition.trim();
+    (!condition.is_empty()).then(|| condition.to_string())
+}
+
+/// Expression a `match`/`switch`/`select` block dispatches on.
+fn header_subject(header: &str) -> String {
+    let subject = header
+        .trim_start_matches("match")
+        .trim_start_matches("switch")
+        .trim_start_matches("select")
+        .trim();
+    strip_parens(subject).to_string()
+}
+
+fn switch_guard(subject: Option<&str>, label: &str) -> String {
+    match (subject.filter(|subject| !subject.is_empty()), label) {
+        (Some(subject), "default") => format!("{} matches no other case", subject),
+        (Some(subject), label) => format!("{} == {}", subject, label),
+        (None, label) => format!("case {}", label),
+    }
+}
+
+fn strip_parens(text: &str) -> &str {
+    text.strip_prefix('(')
+        .and_then(|inner| inner.strip_suffix(')'))
+        .unwrap_or(text)
+        .trim()
+}
+
+/// Text after `keyword` on a Python header line, without the trailing `:`.
+fn clean(text: &str, keyword: &str) -> Option<String> {
+    let rest = text.trim().strip_prefix(keyword.trim_end())?;
+    let rest = rest.trim();
+    let rest = rest.strip_suffix(':').unwrap_or(rest).trim();
+    Some(rest.to_string())
+}
+
+/// Source line as written, without a trailing comment.
+fn raw_text<'a>(raw_lines: &[&'a str], line: usize, language: Language) -> &'a str {
+    let text = raw_lines.get(line - 1).copied().unwrap_or_default();
+    let marker = if language == Language::Python { "#" } else { "//" };
+    let last_quote = text.rfind(['"', '\'', '`']).map(|index| index + 1).unwrap_or(0);
+    match text[last_quote..].find(marker) {
+        Some(index) => text[..last_quote + index].trim_end(),
+        None => text.trim_end(),
+    }
+}
@@ -1,9 +1,16 @@
-/// Java static escape analyzer using text-based pattern matching
+//! Java static escape analyzer using text-based pattern matching
+//! Patterns run on `preprocess::logical_lines`, so comments and string contents are
+//! never matched and a statement split over several lines is read as one.
 
 use crate::protocol::{
     ConfidenceLevel, EscapeType, SourceLocation, StaticAnalysisResult, StaticEscape,
-    StaticEscapeSummary,
+    StaticEscapeSummary, SuggestedFix,
 };
+use crate::static_analyzer::fixes::{self, FixContext};
+use crate::static_analyzer::retaining::RetainingApis;
+use crate::static_analyzer::rules::{self, Rule};
+use crate::static_analyzer::callgraph::Language;
+use crate::static_analyzer::preprocess;
 use crate::static_analyzer::StaticEscapeAnalyzer;
 use anyhow::{Context, Result};
 use std::collections::{HashMap, HashSet};
@@ -34,14 +41,17 @@ impl StaticEscapeAnalyzer for JavaStaticAnalyzer {
         let source = fs::read_to_string(source_file)
             .with_context(|| format!("Failed to read source file: {}", source_file))?;
         
+        let code = preprocess::logical_lines(&source, Language::Java).join("\n");
         let target_function = parse_target_function(target);
         let mut warnings = vec![];
         
-        let escapes = if let Some(function_name) = target_function.as_deref() {
-            analyze_method(&source, source_file, function_name, &mut warnings)
+        let mut escapes = if let Some(function_name) = target_function.as_deref() {
+            let retaining = RetainingApis::load("java")?;
+            analyze_method(&code, source_file, function_name, &retaining, &mut warnings)
         } else {
-            analyze_file(&source, source_file)
+            analyze_file(&code, source_file)
         };
+        preprocess::restore_snippets(&mut escapes, &source);
         
         let mut summary = StaticEscapeSummary::new();
         for escape in &escapes {
@@ -55,6 +65,8 @@ impl StaticEscapeAnalyzer for JavaStaticAnalyzer {
             analysis_time_ms: start_time.elapsed().as_millis() as u64,
             warnings,
             summary,
+            call_graph: None,
+            spawn_estimate: None,
         })
     }
     
@@ -68,6 +80,10 @@ impl StaticEscapeAnalyzer for JavaStaticAnalyzer {
             .output()
             .is_ok()
     }
+
+    fn suggest_fix(&self, escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+        suggested_fix(escape, context)
+    }
 }
 
 fn parse_target_function(target: &str) -> Option<String> {
@@ -93,6 +109,7 @@ fn analyze_method(
     source: &str,
     source_file: &str,
     method_name: &str,
+    retaining: &RetainingApis,
     warnings: &mut Vec<String>,
 ) -> Vec<StaticEscape> {
     let lines: Vec<&str> = source.lines().collect();
@@ -111,6 +128,15 @@ fn analyze_method(
     let mut local_vars: HashSet<String> = HashSet::new();
     let mut local_object_vars: HashSet<String> = HashSet::new();
     let mut object_dependencies: HashMap<String, HashSet<String>> = HashMap::new();
+
+    let mut parameters: HashSet<String> = HashSet::new();
+    // Runnable/Callable locals and the Thread locals built from them.
+    let mut task_vars: HashMap<String, SpawnedTask> = HashMap::new();
+    let mut thread_tasks: HashMap<String, SpawnedTask> = HashMap::new();
+    // CompletableFuture locals: name -> (line, task, snippet).
+    let mut pending_futures: HashMap<String, (usize, SpawnedTask, String)> = HashMap::new();
+    // Locals handed to retaining APIs, reported unless the receiver is shut down or joined.
+    let mut retained_args: Vec<RetainedArgument> = Vec::new();
     
     for (idx, line) in lines.iter().enumerate() {
         let trimmed = strip_comment(line).trim();
@@ -121,6 +147,7 @@ fn analyze_method(
                 if name == method_name {
                     found_method = true;
                     in_target = true;
+                    parameters = extract_method_parameters(trimmed);
                     brace_depth = 0;
                     if trimmed.contains('{') {
                         brace_depth = 1;
@@ -189,7 +216,7 @@ fn analyze_method(
                         push_unique_escape(
                             &mut escapes,
                             &mut dedupe,
-                            if is_closure { "closure" } else { "global" },
+                            if is_closure { &rules::JAVA_RETAINED_CLOSURE } else { &rules::JAVA_RETAINED_CONTAINER },
                             escape_type,
                             source_file,
                             idx + 1,
@@ -212,7 +239,7 @@ fn analyze_method(
                     push_unique_escape(
                         &mut escapes,
                         &mut dedupe,
-                        "return",
+                        &rules::JAVA_RETURNED_OBJECT,
                         EscapeType::ReturnEscape,
                         source_file,
                         idx + 1,
@@ -226,6 +253,113 @@ fn analyze_method(
                 }
             }
 
+            let mut known_locals: HashSet<String> = local_vars.union(&parameters).cloned().collect();
+
+            // `Runnable task = () -> ...` / `Callable<T> task = new Callable<>() { ... }`
+            if let Some((task_var, rhs)) = extract_java_assignment(trimmed) {
+                if starts_task_expression(&rhs) {
+                    known_locals.remove(&task_var);
+                    if let Some((statement, _)) = collect_statement(&lines, idx) {
+                        let task_expr = statement.split_once('=').map(|(_, rhs)| rhs).unwrap_or(&statement);
+                        if let Some(task) = spawned_task(task_expr.trim(), &known_locals, &task_vars) {
+                            task_vars.insert(task_var, task);
+                        }
+                    }
+                }
+            }
+
+            // Locals handed to calls that keep them: `bus.register(listener)`, `pool.submit(task)`
+            for call in retaining_calls(trimmed, retaining) {
+                let mut rest = call.args.clone();
+                while !rest.trim().is_empty() {
+                    let (argument, tail) = split_first_top_level_comma(&rest).unwrap_or((rest.clone(), String::new()));
+                    let argument = argument.trim();
+                    if known_locals.contains(argument) || task_vars.contains_key(argument) {
+                        retained_args.push(RetainedArgument {
+                            receiver: call.receiver.clone(),
+                            api: call.api.clone(),
+                            variable: argument.to_string(),
+                            line: idx + 1,
+                            column: trimmed.find(&call.api_text).unwrap_or(0),
+                            snippet: trimmed.to_string(),
+                        });
+                    }
+                    rest = tail;
+                }
+            }
+
+            // Lambdas and anonymous classes handed to a thread: `new Thread(() -> { ... }).start()`
+            let mut started_with_task = false;
+            if let Some((args, tail)) = spawn_call(&lines, idx, "new Thread(") {
+                let task_expr = split_first_top_level_comma(&args).map(|(task, _)| task).unwrap_or(args);
+                if let Some(task) = spawned_task(task_expr.trim(), &known_locals, &task_vars) {
+                    if tail.trim_start().starts_with(".start()") {
+                        started_with_task = true;
+                        let reason = format!(
+                            "Thread created and started inline without join; its {}",
+                            task.describe()
+                        );
+                        if push_unique_escape(
+                            &mut escapes,
+                            &mut dedupe,
+                            &rules::JAVA_INLINE_THREAD,
+                            classify_java_async_escape(Some(trimmed), &reason),
+                            source_file,
+                            idx + 1,
+                            0,
+                            method_name,
+                            "thread".to_string(),
+                            reason,
+                            ConfidenceLevel::High,
+                            Some(trimmed.to_string()),
+                        ) {
+                            attach_captures(&mut escapes, &task, &parameters);
+                        }
+                    } else if let Some(var_name) = extract_thread_variable(trimmed) {
+                        thread_tasks.insert(var_name, task);
+                    }
+                }
+            }
+
+            // `CompletableFuture.runAsync(() -> ...)` / `supplyAsync(...)` not awaited
+            for marker in ["CompletableFuture.runAsync(", "CompletableFuture.supplyAsync("] {
+                let Some((args, tail)) = spawn_call(&lines, idx, marker) else {
+                    continue;
+                };
+                let task_expr = split_first_top_level_comma(&args).map(|(task, _)| task).unwrap_or(args);
+                let Some(task) = spawned_task(task_expr.trim(), &known_locals, &task_vars) else {
+                    continue;
+                };
+                if tail.contains(".join()") || tail.contains(".get(") {
+                    continue;
+                }
+                if let Some((future_var, _)) = extract_java_assignment(trimmed) {
+                    pending_futures.insert(future_var, (idx + 1, task, trimmed.to_string()));
+                    continue;
+                }
+                let reason = format!(
+                    "Async task started with {} without join; its {}",
+                    marker.trim_end_matches('('),
+                    task.describe()
+                );
+                if push_unique_escape(
+                    &mut escapes,
+                    &mut dedupe,
+                    &rules::JAVA_UNJOINED_FUTURE,
+                    classify_java_async_escape(Some(trimmed), &reason),
+                    source_file,
+                    idx + 1,
+                    0,
+                    method_name,
+                    "future".to_string(),
+                    reason,
+                    ConfidenceLevel::High,
+                    Some(trimmed.to_string()),
+                ) {
+                    attach_captures(&mut escapes, &task, &parameters);
+                }
+            }
+
             // Track thread variable creation
             if trimmed.contains("new Thread") || trimmed.contains("new java.lang.Thread") {
                 if let Some(var_name) = extract_thread_variable(trimmed) {
@@ -233,12 +367,12 @@ fn analyze_method(
                 }
                 
                 // Check if thread is started on the same line
-                if trimmed.contains(".start()") && !trimmed.contains(".join()") {
+                if !started_with_task && trimmed.contains(".start()") && !trimmed.contains(".join()") {
                     let reason = "Thread created and started inline without join".to_string();
                     push_unique_escape(
                         &mut escapes,
                         &mut dedupe,
-                        "thread",
+                        &rules::JAVA_INLINE_THREAD,
                         classify_java_async_escape(Some(trimmed), &reason),
                         source_file,
                         idx + 1,
@@ -264,6 +398,13 @@ fn analyze_method(
             if let Some(var_name) = extract_join_call(trimmed) {
                 joined_vars.insert(var_name);
             }
+            if trimmed.contains(".get(") {
+                if let Some(var_name) = extract_variable_before_dot(trimmed) {
+                    if pending_futures.contains_key(&var_name) {
+                        joined_vars.insert(var_name);
+                    }
+                }
+            }
             
             // Track .shutdown() or .awaitTermination() on executors
             if trimmed.contains(".shutdown()") || trimmed.contains(".awaitTermination(") {
@@ -281,11 +422,15 @@ fn analyze_method(
                 for var in &thread_vars {
                     if !joined_vars.contains(var) {
                         if let Some(line_num) = find_variable_line(&lines, method_name, var, idx) {
-                            let reason = format!("Thread/Executor '{}' created but not joined/shutdown", var);
-                            push_unique_escape(
+                            let task = thread_tasks.get(var);
+                            let mut reason = format!("Thread/Executor '{}' created but not joined/shutdown", var);
+                            if let Some(task) = task {
+                                reason = format!("{}; its {}", reason, task.describe());
+                            }
+                            if push_unique_escape(
                                 &mut escapes,
                                 &mut dedupe,
-                                "executor",
+                                &rules::JAVA_UNJOINED_THREAD,
                                 classify_java_async_escape(None, &reason),
                                 source_file,
                                 line_num,
@@ -295,10 +440,64 @@ fn analyze_method(
                                 reason,
                                 ConfidenceLevel::High,
                                 None,
-                            );
+                            ) {
+                                if let Some(task) = task {
+                                    attach_captures(&mut escapes, task, &parameters);
+                                }
+                            }
                         }
                     }
                 }
+                for (var, (line_num, task, snippet)) in &pending_futures {
+                    if joined_vars.contains(var) {
+                        continue;
+                    }
+                    let reason = format!(
+                        "CompletableFuture '{}' started but never joined; its {}",
+                        var,
+                        task.describe()
+                    );
+                    if push_unique_escape(
+                        &mut escapes,
+                        &mut dedupe,
+                        &rules::JAVA_UNJOINED_FUTURE,
+                        classify_java_async_escape(Some(snippet), &reason),
+                        source_file,
+                        *line_num,
+                        0,
+                        method_name,
+                        var.clone(),
+                        reason,
+                        ConfidenceLevel::High,
+                        Some(snippet.clone()),
+                    ) {
+                        attach_captures(&mut escapes, task, &parameters);
+                    }
+                }
+                for argument in &retained_args {
+                    if argument.receiver.as_ref().is_some_and(|receiver| joined_vars.contains(receiver)) {
+                        continue;
+                    }
+                    let origin = if parameters.contains(&argument.variable) { "Parameter" } else { "Local" };
+                    let reason = format!(
+                        "{} '{}' is passed to '{}', which keeps it beyond the call",
+                        origin, argument.variable, argument.api
+                    );
+                    push_unique_escape(
+                        &mut escapes,
+                        &mut dedupe,
+                        &rules::JAVA_RETAINING_CALL,
+                        EscapeType::ParameterEscape,
+                        source_file,
+                        argument.line,
+                        argument.column,
+                        method_name,
+                        argument.variable.clone(),
+                        reason,
+                        ConfidenceLevel::Medium,
+                        Some(argument.snippet.clone()),
+                    );
+                }
                 break;
             }
         }
@@ -614,7 +813,7 @@ fn expand_dependencies(
 fn push_unique_escape(
     escapes: &mut Vec<StaticEscape>,
     dedupe: &mut HashSet<String>,
-    key_type: &str,
+    rule: &Rule,
     escape_type: EscapeType,
     source_file: &str,
     line: usize,
@@ -624,10 +823,10 @@ fn push_unique_escape(
     reason: String,
     confidence: ConfidenceLevel,
     code_snippet: Option<String>,
-) {
-    let key = format!("{}|{}|{}|{}", key_type, line, variable_name, reason);
+) -> bool {
+    let key = format!("{}|{}|{}|{}", rule.id, line, variable_name, reason);
     if !dedupe.insert(key) {
-        return;
+        return false;
     }
 
     escapes.push(StaticEscape {
@@ -643,7 +842,14 @@ fn push_unique_escape(
         reason,
         confidence,
         data_flow: vec![],
+        input_tainted: false,
+        taint_flow: vec![],
+        rule: None,
+        rule_id: Some(rule.id.to_string()),
+        condition: None,
+        suggested_fix: None,
     });
+    true
 }
 
 fn extract_method_name(line: &str) -> Option<String> {
@@ -665,6 +871,282 @@ fn extract_method_name(line: &str) -> Option<String> {
     None
 }
 
+/// Body handed to a thread or `CompletableFuture`: a lambda, method reference or
+/// anonymous `Runnable`/`Callable`, with the method locals and parameters it captures.
+#[derive(Clone)]
+struct SpawnedTask {
+    kind: String,
+    captures: Vec<String>,
+}
+
+impl SpawnedTask {
+    fn describe(&self) -> String {
+        if self.captures.is_empty() {
+            format!("{} captures no locals", self.kind)
+        } else {
+            let captures: Vec<String> = self.captures.iter().map(|name| format!("'{}'", name)).collect();
+            format!("{} captures {}", self.kind, captures.join(", "))
+        }
+    }
+}
+
+/// Records each captured name in `data_flow` of the escape just pushed.
+fn attach_captures(escapes: &mut [StaticEscape], task: &SpawnedTask, parameters: &HashSet<String>) {
+    if let Some(escape) = escapes.last_mut() {
+        escape.data_flow = task
+            .captures
+            .iter()
+            .map(|name| {
+                let origin = if parameters.contains(name) { "parameter" } else { "local" };
+                format!("{} captures {} '{}'", task.kind, origin, name)
+            })
+            .collect();
+    }
+}
+
+/// Whether `expr` is itself a task: an anonymous `Runnable`/`Callable`, a lambda
+/// (`x -> ...`, `(a, b) -> ...`) or a method reference (`worker::run`).
+fn starts_task_expression(expr: &str) -> bool {
+    let expr = expr.trim();
+    let is_name_list = |text: &str| {
+        text.chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | ',' | ' ' | '<' | '>' | '.'))
+    };
+    if expr.starts_with("new Runnable") || expr.starts_with("new Callable") {
+        true
+    } else if let Some((params, _)) = expr.split_once("->") {
+        let params = params.trim();
+        let params = params
+            .strip_prefix('(')
+            .and_then(|inner| inner.strip_suffix(')'))
+            .unwrap_or(params);
+        is_name_list(params)
+    } else if let Some((receiver, _)) = expr.split_once("::") {
+        !receiver.is_empty() && is_name_list(receiver)
+    } else {
+        false
+    }
+}
+
+/// Classifies a task expression and lists the outer locals it captures.
+fn spawned_task(
+    expr: &str,
+    known_locals: &HashSet<String>,
+    task_vars: &HashMap<String, SpawnedTask>,
+) -> Option<SpawnedTask> {
+    let expr = expr.trim();
+    if let Some(task) = task_vars.get(expr) {
+        return Some(task.clone());
+    }
+    if !starts_task_expression(expr) {
+        return None;
+    }
+
+    let (kind, body, own_names) = if expr.starts_with("new Runnable") || expr.starts_with("new Callable") {
+        let kind = if expr.starts_with("new Runnable") {
+            "anonymous Runnable"
+        } else {
+            "anonymous Callable"
+        };
+        (kind, expr, HashSet::new())
+    } else if let Some((params, body)) = expr.split_once("->") {
+        // `x -> ...` and `(a, b) -> ...` bind their own names.
+        ("lambda", body, extract_identifiers(params).into_iter().collect())
+    } else {
+        let (receiver, _) = expr.split_once("::")?;
+        ("method reference", receiver, HashSet::new())
+    };
+
+    // Names declared inside the body shadow outer locals.
+    let declared: HashSet<String> = body
+        .split([';', '{', '}'])
+        .filter_map(extract_java_assignment)
+        .map(|(name, _)| name)
+        .collect();
+
+    let mut captures: Vec<String> = Vec::new();
+    for id in extract_identifiers(body) {
+        if known_locals.contains(&id)
+            && !own_names.contains(&id)
+            && !declared.contains(&id)
+            && !captures.contains(&id)
+        {
+            captures.push(id);
+        }
+    }
+    Some(SpawnedTask {
+        kind: kind.to_string(),
+        captures,
+    })
+}
+
+/// A local handed to a retaining API.
+struct RetainedArgument {
+    /// Root name of the receiver, whose shutdown or join releases the argument
+    receiver: Option<String>,
+    api: String,
+    variable: String,
+    line: usize,
+    column: usize,
+    snippet: String,
+}
+
+/// A call on one line matching a retaining API.
+struct RetainingCall {
+    receiver: Option<String>,
+    /// The matched entry
+    api: String,
+    /// The called name as written, for the column
+    api_text: String,
+    args: String,
+}
+
+/// Calls on `line` that match a retaining API, with their arguments up to the closing
+/// parenthesis (or the end of the line).
+fn retaining_calls(line: &str, retaining: &RetainingApis) -> Vec<RetainingCall> {
+    let is_name_char = |ch: char| ch.is_ascii_alphanumeric() || ch == '_';
+    let mut calls = Vec::new();
+    let mut start = None;
+    for (pos, ch) in line.char_indices().chain([(line.len(), ' ')]) {
+        if is_name_char(ch) {
+            start = start.or(Some(pos));
+            continue;
+        }
+        let Some(name_start) = start.take() else {
+            continue;
+        };
+        let name = &line[name_start..pos];
+        let Some(open) = line[pos..].trim_start().strip_prefix('(').map(|rest| line.len() - rest.len()) else {
+            continue;
+        };
+        let chain_text = line[..name_start].trim_end();
+        let chain = match chain_text.strip_suffix('.') {
+            Some(receiver) => call_chain(receiver),
+            None => "",
+        };
+        let mut segments = extract_identifiers(chain);
+        let receiver = segments.first().cloned();
+        segments.push(name.to_string());
+        let Some(api) = retaining.matching(&segments) else {
+            continue;
+        };
+        let mut depth = 1i32;
+        let close = line[open..]
+            .char_indices()
+            .find(|(_, ch)| {
+                match ch {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            })
+            .map_or(line.len(), |(offset, _)| open + offset);
+        calls.push(RetainingCall {
+            receiver,
+            api,
+            api_text: name.to_string(),
+            args: line[open..close].to_string(),
+        });
+    }
+    calls
+}
+
+/// The receiver expression ending `text`: `Runtime.getRuntime()` in
+/// `x = Runtime.getRuntime()`.
+fn call_chain(text: &str) -> &str {
+    let mut depth = 0i32;
+    for (pos, ch) in text.char_indices().rev() {
+        match ch {
+            ')' => depth += 1,
+            '(' if depth > 0 => depth -= 1,
+            _ if depth > 0 => {}
+            ch if ch.is_ascii_alphanumeric() || ch == '_' || ch == '.' => {}
+            _ => return &text[pos + ch.len_utf8()..],
+        }
+    }
+    text
+}
+
+/// Arguments of the `marker(` call starting on line `idx`, following it over
+/// multi-line lambda and anonymous class bodies, plus the rest of its closing line.
+fn spawn_call(lines: &[&str], idx: usize, marker: &str) -> Option<(String, String)> {
+    let first = strip_comment(lines[idx]);
+    let start = first.find(marker)? + marker.len();
+
+    let mut args = String::new();
+    let mut depth = 1i32;
+    for (offset, line) in lines[idx..].iter().enumerate() {
+        let text = if offset == 0 { &first[start..] } else { strip_comment(line) };
+        for (pos, ch) in text.char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                return Some((args, text[pos + 1..].to_string()));
+            }
+            args.push(ch);
+        }
+        args.push(' ');
+    }
+    None
+}
+
+/// Statement starting on line `idx`, joined up to its `;` at brace depth zero,
+/// and the index of its last line.
+fn collect_statement(lines: &[&str], idx: usize) -> Option<(String, usize)> {
+    let mut statement = String::new();
+    let mut depth = 0i32;
+    for (offset, line) in lines[idx..].iter().enumerate() {
+        let text = strip_comment(line);
+        for ch in text.chars() {
+            match ch {
+                '(' | '{' => depth += 1,
+                ')' | '}' => depth -= 1,
+                ';' if depth <= 0 => return Some((statement, idx + offset)),
+                _ => {}
+            }
+            statement.push(ch);
+        }
+        statement.push(' ');
+    }
+    None
+}
+
+fn extract_method_parameters(header: &str) -> HashSet<String> {
+    let Some(open) = header.find('(') else {
+        return HashSet::new();
+    };
+    let close = header.rfind(')').filter(|close| *close > open).unwrap_or(header.len());
+    // Drop generic arguments so `Map<String, Integer> counts` stays one parameter.
+    let mut generic_depth = 0i32;
+    let mut rest: String = header[open + 1..close]
+        .chars()
+        .filter(|ch| {
+            match ch {
+                '<' => generic_depth += 1,
+                '>' => {
+                    generic_depth -= 1;
+                    return false;
+                }
+                _ => {}
+            }
+            generic_depth == 0
+        })
+        .collect();
+    let mut parameters = HashSet::new();
+    while !rest.trim().is_empty() {
+        let (parameter, tail) = split_first_top_level_comma(&rest).unwrap_or((rest.clone(), String::new()));
+        if let Some(name) = extract_last_identifier(&parameter) {
+            parameters.insert(name);
+        }
+        rest = tail;
+    }
+    parameters
+}
+
 fn extract_thread_variable(line: &str) -> Option<String> {
     // Pattern: Thread varname = new Thread(...)
     // or: Thread varname = ...
@@ -799,8 +1281,107 @@ fn detect_thread_creation(line: &str, source_file: &str, line_num: usize, functi
             reason,
             confidence: ConfidenceLevel::High,
             data_flow: vec![],
+            input_tainted: false,
+            taint_flow: vec![],
+            rule: None,
+            rule_id: Some(rules::JAVA_INLINE_THREAD.id.to_string()),
+            condition: None,
+            suggested_fix: None,
         })
     } else {
         None
     }
 }
+
+const BOUNDED_POOL_SNIPPET: &str = "
+ExecutorService pool = Executors.newFixedThreadPool(Runtime.getRuntime().availableProcessors());
+try {
+    for (Item item : items) {
+        pool.submit(() -> process(item));
+    }
+} finally {
+    pool.shutdown();
+}
+pool.awaitTermination(1, TimeUnit.MINUTES);
+";
+
+/// Fix for a finding from the template of its rule.
+fn suggested_fix(escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+    let line = escape.location.line;
+    let name = escape.variable_name.as_str();
+    match escape.rule_id.as_deref()? {
+        "GH-JV-001" => {
+            let description = "Keep the thread and join it before the method returns".to_string();
+            let started = context.single_statement(line).and_then(|code| code.strip_suffix(".start()"));
+            let thread = started.filter(|thread| {
+                thread.starts_with("new Thread(") || thread.starts_with("new java.lang.Thread(")
+            });
+            let placeable = context.at_body_level(line) && context.declaration("worker").is_none();
+            Some(match (thread, context.exit(line).filter(|_| placeable)) {
+                (Some(thread), Some((exit, indent))) => fixes::placed(
+                    description,
+                    vec![
+                        fixes::replace(
+                            line,
+                            context.indent(line),
+                            &[format!("Thread worker = {};", thread), "worker.start();".to_string()],
+                        ),
+                        fixes::insert(exit, indent, &interrupted("worker.join();")),
+                    ],
+                ),
+                _ => fixes::described(description, &interrupted("worker.join();").join("\n")),
+            })
+        }
+        "GH-JV-002" => {
+            let executor = context.line(line).contains("Executor");
+            let (description, wait) = if executor {
+                let mut wait = vec![format!("{}.shutdown();", name)];
+                wait.extend(interrupted(&format!(
+                    "{}.awaitTermination(1, java.util.concurrent.TimeUnit.MINUTES);",
+                    name
+                )));
+                (format!("Shut down `{}` and wait for its tasks before the method returns", name), wait)
+            } else {
+                (format!("Join `{}` before the method returns", name), interrupted(&format!("{}.join();", name)))
+            };
+            Some(fixes::before_exit(description, &wait, line, context))
+        }
+        "GH-JV-003" if escape.reason.starts_with("CompletableFuture") => Some(fixes::before_exit(
+            format!("Join `{}` before the method returns", name),
+            &[format!("{}.join();", name)],
+            line,
+            context,
+        )),
+        "GH-JV-003" => {
+            let description = "Keep the future and join it before the method returns".to_string();
+            let started = context.single_statement(line).filter(|code| code.starts_with("CompletableFuture."));
+            let placeable = context.at_body_level(line) && context.declaration("future").is_none();
+            Some(match (started, context.exit(line).filter(|_| placeable)) {
+                (Some(started), Some((exit, indent))) => fixes::placed(
+                    description,
+                    vec![
+                        fixes::replace(line, context.indent(line), &[format!("CompletableFuture<?> future = {};", started)]),
+                        fixes::insert(exit, indent, &["future.join();".to_string()]),
+                    ],
+                ),
+                _ => fixes::described(description, "CompletableFuture<?> future = CompletableFuture.runAsync(task);\n// ...\nfuture.join();"),
+            })
+        }
+        "GH-GEN-001" => Some(fixes::described(
+            "Submit the work to a fixed-size pool and wait for it to drain".to_string(),
+            BOUNDED_POOL_SNIPPET,
+        )),
+        _ => None,
+    }
+}
+
+/// `statement` in a `try` restoring the interrupt flag when the wait is interrupted.
+fn interrupted(statement: &str) -> Vec<String> {
+    vec![
+        "try {".to_string(),
+        format!("    {}", statement),
+        "} catch (InterruptedException e) {".to_string(),
+        "    Thread.currentThread().interrupt();".to_string(),
+        "}".to_string(),
+    ]
+}
@@ -1,9 +1,11 @@
-/// Node.js/JavaScript static escape analyzer
+//! Node.js/JavaScript static escape analyzer
 
 use crate::protocol::{
     StaticAnalysisResult, StaticEscape, StaticEscapeSummary, EscapeType,
-    SourceLocation, ConfidenceLevel,
+    SourceLocation, ConfidenceLevel, SuggestedFix,
 };
+use crate::static_analyzer::fixes::{self, FixContext};
+use crate::static_analyzer::rules;
 use crate::static_analyzer::StaticEscapeAnalyzer;
 use anyhow::{Result, Context};
 use std::process::Command;
@@ -25,7 +27,7 @@ impl StaticEscapeAnalyzer for NodeJsStaticAnalyzer {
         let (_, function) = parse_target(target)?;
         
         // Run Node.js analyzer script
-        let escapes = self.analyze_js(&source_file, &function)?;
+        let escapes = self.analyze_js(source_file, &function)?;
         
         // Build summary
         let mut summary = StaticEscapeSummary::new();
@@ -42,6 +44,8 @@ impl StaticEscapeAnalyzer for NodeJsStaticAnalyzer {
             analysis_time_ms,
             warnings: vec![],
             summary,
+            call_graph: None,
+            spawn_estimate: None,
         })
     }
     
@@ -55,6 +59,10 @@ impl StaticEscapeAnalyzer for NodeJsStaticAnalyzer {
             .output()
             .is_ok()
     }
+
+    fn suggest_fix(&self, escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+        suggested_fix(escape, context)
+    }
 }
 
 impl NodeJsStaticAnalyzer {
@@ -118,6 +126,12 @@ struct JsEscape {
     reason: String,
     confidence: String,
     code_snippet: Option<String>,
+    /// Lifecycle rule of the finding (`floating-promise`, `unterminated-worker`, ...)
+    #[serde(default)]
+    rule: Option<String>,
+    /// Catalog id of the rule the analyzer reports the finding under (`GH-JS-001`)
+    #[serde(default)]
+    rule_id: Option<String>,
 }
 
 impl From<JsEscape> for StaticEscape {
@@ -128,6 +142,9 @@ impl From<JsEscape> for StaticEscape {
             "global" => EscapeType::GlobalEscape,
             "closure" => EscapeType::ClosureEscape,
             "heap" => EscapeType::HeapEscape,
+            // Promises, workers, intervals and child processes under a lifecycle rule
+            // are runtime-managed work outliving the function, whatever the reason says.
+            "concurrency" if je.rule.is_some() => EscapeType::HeapEscape,
             "concurrency" => classify_js_concurrency_escape(
                 &je.reason,
                 &je.variable_name,
@@ -155,6 +172,12 @@ impl From<JsEscape> for StaticEscape {
             reason: je.reason,
             confidence,
             data_flow: vec![],
+            input_tainted: false,
+            taint_flow: vec![],
+            rule: je.rule,
+            rule_id: rules::checked("javascript", je.rule_id),
+            condition: None,
+            suggested_fix: None,
         }
     }
 }
@@ -200,3 +223,93 @@ fn parse_target(target: &str) -> Result<(String, String)> {
     }
     Ok((parts[0].to_string(), parts[1].to_string()))
 }
+
+const BOUNDED_PROMISES_SNIPPET: &str = "
+const limit = 8;
+for (let start = 0; start < items.length; start += limit) {
+    await Promise.all(items.slice(start, start + limit).map((item) => process(item)));
+}
+";
+
+/// Fix for a finding from the template of its rule.
+fn suggested_fix(escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+    let line = escape.location.line;
+    let name = escape.variable_name.as_str();
+    // Named handles are reported where the function ends; their fixes follow the declaration.
+    let declared = context.declaration(name).unwrap_or(line);
+    let named = |prefix: &str| escape.reason.starts_with(prefix);
+    match escape.rule_id.as_deref()? {
+        "GH-JS-001" => {
+            let call = context.single_statement(line).map(|code| code.trim_start_matches("void ").trim());
+            Some(match call {
+                Some(call) if context.is_async() => fixes::placed(
+                    "Await the call so its work finishes before the function returns".to_string(),
+                    vec![fixes::replace(line, context.indent(line), &[format!("await {};", call)])],
+                ),
+                _ => fixes::described(
+                    "Await the call from an async function, or handle its rejection".to_string(),
+                    &format!("{}.catch((error) => console.error(error));", call.unwrap_or("promise")),
+                ),
+            })
+        }
+        "GH-JS-002" if named("Worker '") => Some(exit_listener_fix(name, "exit", declared, context)),
+        "GH-JS-002" => Some(fixes::described(
+            "Keep the worker and wait for its 'exit' event, or terminate it when done".to_string(),
+            "const worker = new Worker(file);\nawait new Promise((resolve) => worker.once('exit', resolve));",
+        )),
+        "GH-JS-004" if named("Child process '") => Some(exit_listener_fix(name, "close", declared, context)),
+        "GH-JS-004" => Some(fixes::described(
+            "Keep the child process and wait for its 'close' event, or kill it when done".to_string(),
+            "const child = spawn(command, args);\nawait new Promise((resolve) => child.once('close', resolve));",
+        )),
+        "GH-JS-003" | "GH-JS-006" if named("Timer handle '") => {
+            let clear = if escape.rule_id.as_deref() == Some("GH-JS-003") { "clearInterval" } else { "clearTimeout" };
+            Some(fixes::before_exit(
+                format!("Call `{}({})` before the function returns", clear, name),
+                &[format!("{}({});", clear, name)],
+                declared,
+                context,
+            ))
+        }
+        "GH-JS-003" | "GH-JS-005" => {
+            let (handle, clear, arguments) = match name {
+                "setInterval" => ("interval", "clearInterval", "callback, delay"),
+                "setImmediate" => ("immediate", "clearImmediate", "callback"),
+                _ => ("timer", "clearTimeout", "callback, delay"),
+            };
+            Some(fixes::described(
+                format!("Keep the handle `{}` returns and clear it when the work is no longer needed", name),
+                &format!("const {} = {}({});\n// ...\n{}({});", handle, name, arguments, clear, handle),
+            ))
+        }
+        "GH-JS-008" if context.is_async() => Some(fixes::before_exit(
+            format!("Await `{}` before the function returns", name),
+            &[format!("await {};", name)],
+            declared,
+            context,
+        )),
+        "GH-JS-008" => Some(fixes::before_exit(
+            format!("Handle rejections of `{}`", name),
+            &[format!("{}.catch((error) => console.error(error));", name)],
+            declared,
+            context,
+        )),
+        "GH-GEN-001" => Some(fixes::described(
+            "Run the work in batches of bounded size and wait for each batch".to_string(),
+            BOUNDED_PROMISES_SNIPPET,
+        )),
+        _ => None,
+    }
+}
+
+/// Waits for the `event` that ends a worker or child process, from async functions.
+fn exit_listener_fix(name: &str, event: &str, declared: usize, context: &FixContext) -> SuggestedFix {
+    let wait = format!("await new Promise((resolve) => {}.once('{}', resolve));", name, event);
+    if context.is_async() {
+        return fixes::before_exit(format!("Wait for `{}` to emit '{}' before returning", name, event), &[wait], declared, context);
+    }
+    fixes::described(
+        format!("Handle the '{}' event of `{}`, or return it so the caller can wait for it", event, name),
+        &format!("{}.once('{}', (code) => {{\n    // ...\n}});", name, event),
+    )
+}
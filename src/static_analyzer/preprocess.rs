@@ -0,0 +1,224 @@
+//! Source preprocessing for the text-based analyzers
+//! Pattern matching runs on a cleaned copy of the source: comments are blanked, and
+//! string and character literals keep their quotes but lose their contents, so
+//! neither commented-out code nor text like `"go "` reads as code. Block comments,
+//! Go raw strings and Java text blocks may span lines. Statements continued over
+//! several lines (open argument lists, method chains, trailing operators) can then be
+//! joined onto the line they start on. Every line stays where it was, so line numbers,
+//! and columns on lines that were not joined, still point into the original file.
+
+use crate::protocol::StaticEscape;
+use crate::static_analyzer::callgraph::Language;
+
+/// Continuation lines joined into one statement at most, so an unbalanced parenthesis
+/// cannot swallow the rest of the file.
+const MAX_JOINED_LINES: usize = 24;
+
+/// Trailing tokens that continue a statement on the next line.
+const CONTINUATION_SUFFIXES: [&str; 10] = [",", ".", "&&", "||", "=", "+", "-", "*", "/", "|"];
+
+enum State {
+    Code,
+    LineComment,
+    /// Nesting depth; only Rust nests block comments
+    BlockComment(usize),
+    /// Closing delimiter, and whether backslash escapes apply
+    Literal(&'static str, bool),
+}
+
+/// The source with comments blanked and literal contents replaced by spaces, one entry
+/// per source line.
+pub(super) fn strip(source: &str, language: Language) -> Vec<String> {
+    let chars: Vec<char> = source.chars().collect();
+    let starts = |index: usize, token: &str| token.chars().enumerate().all(|(offset, ch)| chars.get(index + offset) == Some(&ch));
+    let mut out = String::with_capacity(source.len());
+    let mut state = State::Code;
+    let mut index = 0;
+    // Blanks one character, keeping line breaks.
+    let blank = |out: &mut String, ch: char| out.push(if ch == '\n' || ch == '\r' { ch } else { ' ' });
+
+    while index < chars.len() {
+        let ch = chars[index];
+        match state {
+            State::Code => {
+                let (line_comment, block_comments) = match language {
+                    Language::Python => ("#", false),
+                    _ => ("//", true),
+                };
+                if starts(index, line_comment) {
+                    state = State::LineComment;
+                    blank(&mut out, ch);
+                } else if block_comments && starts(index, "/*") {
+                    state = State::BlockComment(1);
+                    out.push_str("  ");
+                    index += 2;
+                    continue;
+                } else if let Some((open, close, escapes)) = literal_start(&chars, index, language) {
+                    out.extend(&chars[index..index + open]);
+                    index += open;
+                    state = State::Literal(close, escapes);
+                    continue;
+                } else {
+                    out.push(ch);
+                }
+            }
+            State::LineComment => {
+                if ch == '\n' {
+                    state = State::Code;
+                }
+                blank(&mut out, ch);
+            }
+            State::BlockComment(depth) => {
+                if starts(index, "*/") {
+                    state = if depth > 1 { State::BlockComment(depth - 1) } else { State::Code };
+                    out.push_str("  ");
+                    index += 2;
+                    continue;
+                }
+                if language == Language::Rust && starts(index, "/*") {
+                    state = State::BlockComment(depth + 1);
+                    out.push_str("  ");
+                    index += 2;
+                    continue;
+                }
+                blank(&mut out, ch);
+            }
+            State::Literal(close, escapes) => {
+                if escapes && ch == '\\' {
+                    blank(&mut out, ch);
+                    if let Some(&next) = chars.get(index + 1) {
+                        blank(&mut out, next);
+                    }
+                    index += 2;
+                    continue;
+                }
+                if starts(index, close) {
+                    out.push_str(close);
+                    index += close.chars().count();
+                    state = State::Code;
+                    continue;
+                }
+                // Single-line literals end at the line break even when unterminated;
+                // Rust strings may span lines.
+                let multi_line = close.len() > 1 || close == "`" || (language == Language::Rust && close == "\"");
+                if ch == '\n' && !multi_line {
+                    state = State::Code;
+                    out.push(ch);
+                } else {
+                    blank(&mut out, ch);
+                }
+            }
+        }
+        index += 1;
+    }
+    out.lines().map(str::to_string).collect()
+}
+
+/// A literal opening at `index`: the length of its opening delimiter, its closing
+/// delimiter, and whether backslash escapes apply inside it.
+fn literal_start(chars: &[char], index: usize, language: Language) -> Option<(usize, &'static str, bool)> {
+    let starts = |token: &str| token.chars().enumerate().all(|(offset, ch)| chars.get(index + offset) == Some(&ch));
+    let previous_is_word = index > 0 && (chars[index - 1].is_alphanumeric() || chars[index - 1] == '_');
+    match language {
+        Language::Python if starts("\"\"\"") => Some((3, "\"\"\"", true)),
+        Language::Python if starts("'''") => Some((3, "'''", true)),
+        Language::Java if starts("\"\"\"") => Some((3, "\"\"\"", true)),
+        Language::Go | Language::JavaScript if starts("`") => Some((1, "`", language == Language::JavaScript)),
+        Language::Rust if starts("r\"") && !previous_is_word => Some((2, "\"", false)),
+        Language::Rust if starts("r#\"") && !previous_is_word => Some((3, "\"#", false)),
+        _ if starts("\"") => Some((1, "\"", true)),
+        // A Rust quote not closing a character literal starts a lifetime.
+        Language::Rust if starts("'") => {
+            let is_char = chars.get(index + 1) == Some(&'\\') || chars.get(index + 2) == Some(&'\'');
+            is_char.then_some((1, "'", true))
+        }
+        _ if starts("'") => Some((1, "'", true)),
+        _ => None,
+    }
+}
+
+/// `strip`, then each statement continued over several lines joined onto its first
+/// line, leaving the lines it took empty. Continuations that open or close a block are
+/// left alone, so a body keeps its own lines.
+pub(super) fn logical_lines(source: &str, language: Language) -> Vec<String> {
+    let mut lines = strip(source, language);
+    let mut index = 0;
+    while index < lines.len() {
+        let end = continuation_end(&lines, index);
+        if end > index {
+            let joined = lines[index + 1..=end].iter().map(|line| line.trim()).collect::<Vec<_>>().join(" ");
+            let first = lines[index].trim_end().to_string();
+            lines[index] = format!("{} {}", first, joined);
+            for line in &mut lines[index + 1..=end] {
+                line.clear();
+            }
+        }
+        index = end + 1;
+    }
+    lines
+}
+
+/// Index of the last line of the statement starting at `start`, or `start` when it
+/// does not continue or cannot be joined.
+fn continuation_end(lines: &[String], start: usize) -> usize {
+    let mut depth = paren_depth(&lines[start]);
+    let mut end = start;
+    loop {
+        let current = lines[end].trim_end();
+        let next = lines.get(end + 1).map(|line| line.trim());
+        let continues = depth > 0 || continues_after(current) || next.is_some_and(|next| next.starts_with('.'));
+        let Some(next) = next.filter(|_| continues) else {
+            return end;
+        };
+        if next.contains(['{', '}']) || end + 1 - start >= MAX_JOINED_LINES {
+            return start;
+        }
+        end += 1;
+        depth += paren_depth(next);
+    }
+}
+
+fn continues_after(line: &str) -> bool {
+    !line.ends_with("++")
+        && !line.ends_with("--")
+        && CONTINUATION_SUFFIXES.iter().any(|suffix| line.ends_with(suffix))
+}
+
+/// Change in parenthesis and bracket nesting over a line.
+fn paren_depth(line: &str) -> i32 {
+    line.chars()
+        .map(|ch| match ch {
+            '(' | '[' => 1,
+            ')' | ']' => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// The part of `original` under the span `text` takes in `cleaned`, the cleaned copy of
+/// that line, so literal contents blanked by `strip` come back. `text` itself when it is
+/// not in `cleaned` or the line was joined with the ones after it.
+pub(super) fn restore_span(text: &str, cleaned: &str, original: &str) -> String {
+    let (cleaned, original) = (cleaned.trim_end_matches('\r'), original.trim_end_matches('\r'));
+    let Some(start) = cleaned.find(text) else {
+        return text.to_string();
+    };
+    if cleaned.chars().count() != original.chars().count() {
+        return text.to_string();
+    }
+    let start = cleaned[..start].chars().count();
+    original.chars().skip(start).take(text.chars().count()).collect()
+}
+
+/// Replaces the code snippets of `escapes`, taken from the cleaned lines, with the
+/// original source lines.
+pub(super) fn restore_snippets(escapes: &mut [StaticEscape], source: &str) {
+    let original: Vec<&str> = source.lines().collect();
+    for escape in escapes {
+        if escape.location.code_snippet.is_some() {
+            if let Some(line) = original.get(escape.location.line.wrapping_sub(1)) {
+                escape.location.code_snippet = Some(line.trim().to_string());
+            }
+        }
+    }
+}
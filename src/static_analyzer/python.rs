@@ -1,9 +1,11 @@
-/// Python static escape analyzer using AST analysis
+//! Python static escape analyzer using AST analysis
 
 use crate::protocol::{
     StaticAnalysisResult, StaticEscape, StaticEscapeSummary, EscapeType,
-    SourceLocation, ConfidenceLevel,
+    SourceLocation, ConfidenceLevel, SuggestedFix,
 };
+use crate::static_analyzer::fixes::{self, FixContext};
+use crate::static_analyzer::rules;
 use crate::static_analyzer::StaticEscapeAnalyzer;
 use anyhow::{Result, Context};
 use std::process::Command;
@@ -42,6 +44,8 @@ impl StaticEscapeAnalyzer for PythonStaticAnalyzer {
             analysis_time_ms,
             warnings: vec![],
             summary,
+            call_graph: None,
+            spawn_estimate: None,
         })
     }
     
@@ -52,6 +56,10 @@ impl StaticEscapeAnalyzer for PythonStaticAnalyzer {
     fn is_available(&self) -> bool {
         Self::find_python_executable().is_some()
     }
+
+    fn suggest_fix(&self, escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+        suggested_fix(escape, context)
+    }
 }
 
 impl PythonStaticAnalyzer {
@@ -165,6 +173,9 @@ struct PythonEscape {
     reason: String,
     confidence: String,
     code_snippet: Option<String>,
+    /// Catalog id of the rule the analyzer reports the finding under (`GH-PY-001`)
+    #[serde(default)]
+    rule_id: Option<String>,
 }
 
 impl From<PythonEscape> for StaticEscape {
@@ -202,6 +213,12 @@ impl From<PythonEscape> for StaticEscape {
             reason: pe.reason,
             confidence,
             data_flow: vec![],
+            input_tainted: false,
+            taint_flow: vec![],
+            rule: None,
+            rule_id: rules::checked("python", pe.rule_id),
+            condition: None,
+            suggested_fix: None,
         }
     }
 }
@@ -248,3 +265,40 @@ fn parse_target(target: &str) -> Result<(String, String)> {
     }
     Ok((parts[0].to_string(), parts[1].to_string()))
 }
+
+const BOUNDED_POOL_SNIPPET: &str = "
+with ThreadPoolExecutor(max_workers=8) as pool:
+    results = list(pool.map(process, items))
+";
+
+/// Fix for a finding from the template of its rule.
+fn suggested_fix(escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+    let name = escape.variable_name.as_str();
+    let line = escape.location.line;
+    match escape.rule_id.as_deref()? {
+        "GH-PY-001" => {
+            let created = context.line(line);
+            let (description, cleanup) = match escape.reason.split(" '").next().unwrap_or_default() {
+                kind if kind.ends_with(" list") => (
+                    format!("Join every worker in `{}` before the function returns", name),
+                    vec![format!("for worker in {}:", name), "    worker.join()".to_string()],
+                ),
+                _ if created.contains("Executor(") => (
+                    format!("Shut down `{}` and wait for its tasks before the function returns", name),
+                    vec![format!("{}.shutdown(wait=True)", name)],
+                ),
+                _ if created.contains("Pool(") => (
+                    format!("Close and join `{}` before the function returns", name),
+                    vec![format!("{}.close()", name), format!("{}.join()", name)],
+                ),
+                _ => (format!("Join `{}` before the function returns", name), vec![format!("{}.join()", name)]),
+            };
+            Some(fixes::before_exit(description, &cleanup, line, context))
+        }
+        "GH-GEN-001" => Some(fixes::described(
+            "Run the work on a pool of bounded size that is shut down when the block ends".to_string(),
+            BOUNDED_POOL_SNIPPET,
+        )),
+        _ => None,
+    }
+}
@@ -0,0 +1,239 @@
+//! Recursive spawn detection
+//! Flags functions that start a thread, task or worker which calls back into the
+//! spawning function (directly or through other local functions). Each level of the
+//! recursion spawns again, so the number of live threads grows with the recursion depth
+//! and is unbounded unless a parameter counts down to a constant base case.
+
+use crate::protocol::{CallGraph, ConfidenceLevel, EscapeType, SourceLocation, StaticEscape};
+use crate::static_analyzer::callgraph::{
+    extract_calls, extract_functions, identifiers, last_segment, statement_end, FunctionDef,
+    Language,
+};
+use crate::static_analyzer::rules::{self, Rule};
+use crate::static_analyzer::taint::parameter_names;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Catalog rule of these findings.
+const RULE: Rule = rules::RECURSIVE_SPAWN;
+
+/// Depth estimated from a counting-down parameter.
+struct DepthBound {
+    parameter: String,
+    initial: i64,
+    stop: i64,
+    step: i64,
+}
+
+impl DepthBound {
+    fn levels(&self) -> i64 {
+        ((self.initial - self.stop) / self.step).max(0)
+    }
+}
+
+/// Recursive spawn findings for every function in the call graph.
+pub fn recursive_spawns(language: &str, source_file: &str, graph: &CallGraph) -> Vec<StaticEscape> {
+    let Some(language) = Language::from_name(language) else {
+        return Vec::new();
+    };
+    let Ok(source) = std::fs::read_to_string(source_file) else {
+        return Vec::new();
+    };
+    let raw_lines: Vec<&str> = source.lines().collect();
+    let functions = extract_functions(&source, language);
+    let mut defined: HashMap<&str, &FunctionDef> = HashMap::new();
+    for function in &functions {
+        defined.entry(function.name.as_str()).or_insert(function);
+    }
+
+    let mut findings = Vec::new();
+    for node in graph.nodes.iter().filter(|node| node.line.is_some() && !node.spawn_sites.is_empty()) {
+        let Some(function) = defined.get(node.name.as_str()) else {
+            continue;
+        };
+
+        // Spawn statements whose spawned code leads back into this function.
+        let mut recursive = Vec::new();
+        for (index, (line, code)) in function.body.iter().enumerate() {
+            if !language.is_spawn_site(code) {
+                continue;
+            }
+            let end = statement_end(&function.body, index);
+            let statement: Vec<&str> = function.body[index..=end].iter().map(|(_, code)| code.as_str()).collect();
+            let statement = statement.join("\n");
+            let callee = identifiers(&statement)
+                .filter(|name| defined.contains_key(name))
+                .find(|name| *name == function.name || reaches(graph, name, &function.name));
+            if let Some(callee) = callee {
+                recursive.push((*line, callee.to_string(), statement));
+            }
+        }
+
+        let fan_out = recursive.len() as u32;
+        for (line, callee, statement) in &recursive {
+            let bound = depth_bound(function, statement, &functions, language);
+            let cycle = if *callee == function.name {
+                format!("`{}` starts a thread that calls `{}` again", function.name, function.name)
+            } else {
+                format!(
+                    "`{}` starts a thread running `{}`, which calls back into `{}`",
+                    function.name, callee, function.name
+                )
+            };
+            let (reason, confidence) = match &bound {
+                Some(bound) => {
+                    let levels = bound.levels();
+                    let threads: i64 = (1..=levels as u32).map(|level| i64::from(fan_out).pow(level)).sum();
+                    (
+                        format!(
+                            "Recursive spawn: {}; depth ≈ {} (`{}` from {} down to {}), about {} thread(s)",
+                            cycle, levels, bound.parameter, bound.initial, bound.stop, threads
+                        ),
+                        ConfidenceLevel::Medium,
+                    )
+                }
+                None => (
+                    format!("Recursive spawn: {} with no constant depth bound; spawning may be unbounded", cycle),
+                    ConfidenceLevel::High,
+                ),
+            };
+
+            findings.push(StaticEscape {
+                escape_type: EscapeType::ClosureEscape,
+                location: SourceLocation {
+                    file: source_file.to_string(),
+                    line: *line,
+                    column: 0,
+                    function: function.name.clone(),
+                    code_snippet: raw_lines.get(line - 1).map(|code| code.trim().to_string()),
+                },
+                variable_name: function.name.clone(),
+                reason,
+                confidence,
+                data_flow: vec![format!("{} spawns {} at line {}", function.name, callee, line)],
+                input_tainted: false,
+                taint_flow: vec![],
+                rule: Some(RULE.name.to_string()),
+                rule_id: Some(RULE.id.to_string()),
+                condition: None,
+                suggested_fix: None,
+            });
+        }
+    }
+    findings
+}
+
+/// Whether `to` is reachable from `from` along call graph edges.
+fn reaches(graph: &CallGraph, from: &str, to: &str) -> bool {
+    let mut queue = VecDeque::from([from]);
+    let mut visited = HashSet::from([from]);
+    while let Some(name) = queue.pop_front() {
+        for edge in graph.edges.iter().filter(|edge| edge.caller == name) {
+            if edge.callee == to {
+                return true;
+            }
+            if visited.insert(edge.callee.as_str()) {
+                queue.push_back(edge.callee.as_str());
+            }
+        }
+    }
+    false
+}
+
+/// A parameter passed as `p - N` into the recursive spawn, with a constant starting
+/// value (a default or a literal argument from another caller) and its base case.
+fn depth_bound(
+    function: &FunctionDef,
+    statement: &str,
+    functions: &[FunctionDef],
+    language: Language,
+) -> Option<DepthBound> {
+    let parameters = parameter_names(&function.parameters, language);
+    parameters.iter().enumerate().find_map(|(position, parameter)| {
+        let step = decrement(statement, parameter)?;
+        let initial = default_value(&function.parameters, parameter)
+            .or_else(|| literal_argument(function, position, functions, language))?;
+        let stop = base_case(function, parameter).unwrap_or(0);
+        Some(DepthBound {
+            parameter: parameter.clone(),
+            initial,
+            stop,
+            step,
+        })
+    })
+}
+
+/// `N` in `parameter - N` (or `parameter-N`) inside `code`.
+fn decrement(code: &str, parameter: &str) -> Option<i64> {
+    after_word(code, parameter).find_map(|rest| {
+        let rest = rest.trim_start().strip_prefix('-')?;
+        if rest.starts_with(['-', '=']) {
+            return None;
+        }
+        leading_integer(rest.trim_start()).filter(|step| *step > 0)
+    })
+}
+
+/// Default value of `parameter` in a definition's parameter list (`depth=3`, `depth = 3`,
+/// `depth: int = 3`).
+fn default_value(parameters: &str, parameter: &str) -> Option<i64> {
+    parameters.split(',').find_map(|part| {
+        let (head, value) = part.split_once('=')?;
+        let name = head.split(':').next()?.trim().trim_start_matches('*');
+        (name == parameter).then(|| leading_integer(value.trim())).flatten()
+    })
+}
+
+/// Integer literal passed at `position` by a caller other than `function` itself.
+fn literal_argument(
+    function: &FunctionDef,
+    position: usize,
+    functions: &[FunctionDef],
+    language: Language,
+) -> Option<i64> {
+    functions
+        .iter()
+        .filter(|caller| caller.name != function.name)
+        .flat_map(|caller| caller.body.iter())
+        .flat_map(|(_, code)| extract_calls(code, language))
+        .filter(|call| last_segment(&call.chain) == function.name)
+        .find_map(|call| call.arguments.get(position).and_then(|argument| leading_integer(argument.trim())))
+}
+
+/// Constant the parameter is compared against in a base case: `p <= K`, `p < K`
+/// (stops at `K - 1`) or `p == K`.
+fn base_case(function: &FunctionDef, parameter: &str) -> Option<i64> {
+    function.body.iter().find_map(|(_, code)| {
+        after_word(code, parameter).find_map(|rest| {
+            let rest = rest.trim_start();
+            if let Some(value) = rest.strip_prefix("<=").or_else(|| rest.strip_prefix("===")).or_else(|| rest.strip_prefix("==")) {
+                leading_integer(value.trim_start())
+            } else if let Some(value) = rest.strip_prefix('<') {
+                leading_integer(value.trim_start()).map(|value| value - 1)
+            } else {
+                None
+            }
+        })
+    })
+}
+
+/// Text following each whole-word occurrence of `word` in `code`.
+fn after_word<'a>(code: &'a str, word: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let is_word = |ch: char| ch.is_alphanumeric() || ch == '_';
+    code.match_indices(word).filter_map(move |(index, _)| {
+        let rest = &code[index + word.len()..];
+        let before = code[..index].chars().next_back();
+        let boundary = !before.is_some_and(|ch| is_word(ch) || ch == '.')
+            && !rest.chars().next().is_some_and(is_word);
+        boundary.then_some(rest)
+    })
+}
+
+/// Integer literal at the start of `text`, ignoring type suffixes such as `3u32`.
+fn leading_integer(text: &str) -> Option<i64> {
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, text),
+    };
+    let end = digits.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(digits.len());
+    digits[..end].parse::<i64>().ok().map(|value| sign * value)
+}
@@ -0,0 +1,93 @@
+//! Retaining APIs
+//! Calls that keep an argument beyond the call (callback and listener registration,
+//! global registries, executors) turn a local passed to them into a parameter escape.
+//! Each language has a built-in list, which the `[retaining_apis]` table of
+//! graphene.toml extends:
+//!
+//! ```toml
+//! [retaining_apis]
+//! rust = ["EventBus::subscribe", "register_hook"]
+//! java = ["EventBus.register"]
+//! ```
+//!
+//! An entry is a function or method name, optionally qualified (`Type::method`,
+//! `Type.method`). A bare name matches any call of that name; a qualified one also
+//! needs its qualifiers in the call's path or receiver chain, so `Runtime.addShutdownHook`
+//! matches `Runtime.getRuntime().addShutdownHook(hook)`.
+
+use crate::analyzer::external;
+use anyhow::Result;
+
+/// Generic names (`register`, `subscribe`, `execute`) are left out: too many unrelated
+/// Rust methods share them. Thread pool jobs have their own `detached-pool-job` rule.
+const RUST_BUILTINS: [&str; 6] = [
+    "panic::set_hook",
+    "signal_hook::register",
+    "register_callback",
+    "add_listener",
+    "add_callback",
+    "add_hook",
+];
+
+const JAVA_BUILTINS: [&str; 12] = [
+    "execute",
+    "submit",
+    "schedule",
+    "scheduleAtFixedRate",
+    "scheduleWithFixedDelay",
+    "invokeAll",
+    "Runtime.addShutdownHook",
+    "addListener",
+    "addObserver",
+    "addPropertyChangeListener",
+    "register",
+    "subscribe",
+];
+
+pub struct RetainingApis {
+    /// Each entry as written, with its path segments
+    entries: Vec<(String, Vec<String>)>,
+}
+
+impl RetainingApis {
+    /// The built-in list of `language` plus the entries the config file adds.
+    pub fn load(language: &str) -> Result<Self> {
+        let builtins: &[&str] = match language {
+            "rust" => &RUST_BUILTINS,
+            "java" => &JAVA_BUILTINS,
+            _ => &[],
+        };
+        let configured = external::load_retaining_apis(language)?;
+        let entries = builtins
+            .iter()
+            .map(|entry| entry.to_string())
+            .chain(configured)
+            .map(|entry| {
+                let segments = entry
+                    .split("::")
+                    .flat_map(|part| part.split('.'))
+                    .map(|segment| segment.trim().to_string())
+                    .filter(|segment| !segment.is_empty())
+                    .collect();
+                (entry, segments)
+            })
+            .collect();
+        Ok(Self { entries })
+    }
+
+    /// The entry matched by a call whose path or receiver chain is `segments`, ending
+    /// with the called name.
+    pub fn matching(&self, segments: &[String]) -> Option<String> {
+        let (name, chain) = segments.split_last()?;
+        self.entries
+            .iter()
+            .find(|(_, entry)| {
+                entry
+                    .split_last()
+                    .is_some_and(|(entry_name, qualifiers)| {
+                        entry_name == name && qualifiers.iter().all(|qualifier| chain.contains(qualifier))
+                    })
+            })
+            .map(|(entry, _)| entry.clone())
+    }
+}
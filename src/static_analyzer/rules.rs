@@ -0,0 +1,598 @@
+//! Rule catalog
+//! Every detection pattern of the static analyzers has a stable id (`GH-RS-001`),
+//! listed by `graphene-ha rules list` with a description and remediation. Analyzers
+//! set the id of the rule they report where they detect a finding, taking it from the
+//! constants below; the external Python and JavaScript analyzers report theirs in
+//! their output. Ids never change meaning; retired rules leave a gap.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Rule {
+    pub id: &'static str,
+    /// Short name; for dedicated rules the `rule` findings carry
+    pub name: &'static str,
+    /// Analyzer reporting it; `None` for rules every analyzer shares
+    pub language: Option<&'static str>,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub remediation: &'static str,
+}
+
+const fn rule(
+    id: &'static str,
+    name: &'static str,
+    language: Option<&'static str>,
+    title: &'static str,
+    description: &'static str,
+    remediation: &'static str,
+) -> Rule {
+    Rule { id, name, language, title, description, remediation }
+}
+
+pub const SPAWN_IN_LOOP: Rule = rule(
+    "GH-GEN-001",
+    "spawn-in-loop",
+    None,
+    "Spawn inside a loop",
+    "A thread, task or goroutine is started on every iteration of a loop, so the number of concurrent workers grows with the loop's bounds.",
+    "Bound the work with a pool, semaphore or fixed set of workers, or join each spawn before the next iteration.",
+);
+
+pub const RECURSIVE_SPAWN: Rule = rule(
+    "GH-GEN-002",
+    "recursive-spawn",
+    None,
+    "Recursive spawn",
+    "A function reachable from the target spawns work and calls itself again, so spawns multiply with the recursion depth.",
+    "Bound the recursion depth, or collect the work into a queue drained by a fixed number of workers.",
+);
+
+pub const EXIT_SKIPS_CLEANUP: Rule = rule(
+    "GH-GEN-003",
+    "exit-skips-cleanup",
+    None,
+    "Process exit skips cleanup",
+    "The target, or a function it reaches, ends the process while joins, shutdowns, awaits or deferred calls that follow are still pending.",
+    "Return an error or status to the caller instead of exiting, and exit only from the program's entry point after cleanup.",
+);
+
+pub const PY_UNJOINED_CONCURRENCY: Rule = rule(
+    "GH-PY-001",
+    "unjoined-concurrency",
+    Some("python"),
+    "Thread, process or pool not joined",
+    "A thread, process, executor or pool is created but not joined or closed on every path through the function.",
+    "Join or shut it down on every path, for example with a `with` block or a `try`/`finally`.",
+);
+
+pub const PY_DECORATOR_SPAWN: Rule = rule(
+    "GH-PY-002",
+    "decorator-spawn",
+    Some("python"),
+    "Call runs in the background through a decorator",
+    "The called function is wrapped by a project decorator that runs it in a thread, process or executor task.",
+    "Keep the handle the decorator returns and wait for it, or call the undecorated function.",
+);
+
+pub const PY_CONCURRENT_RETURN: Rule = rule(
+    "GH-PY-003",
+    "concurrent-return",
+    Some("python"),
+    "Value returned with unjoined concurrency",
+    "The function returns while concurrency it started is still running.",
+    "Join the work before returning, or return its handle so the caller can.",
+);
+
+pub const PY_CONCURRENT_ASSIGNMENT: Rule = rule(
+    "GH-PY-004",
+    "concurrent-assignment",
+    Some("python"),
+    "Value assigned from a function that leaves concurrency running",
+    "A variable is assigned the result of a function that starts concurrency it does not join.",
+    "Join the work inside the helper, or make the caller responsible for the handle it returns.",
+);
+
+pub const PY_RETURNED_OBJECT: Rule = rule(
+    "GH-PY-005",
+    "returned-object",
+    Some("python"),
+    "Local object returned",
+    "An object created in the function is returned to the caller.",
+    "Return a copy or a primitive when the caller must not share the object.",
+);
+
+pub const PY_GLOBAL_DECLARATION: Rule = rule(
+    "GH-PY-006",
+    "global-declaration",
+    Some("python"),
+    "Variable declared global",
+    "The function declares a variable `global`, so its assignments outlive the call.",
+    "Pass the value in and out explicitly instead of rebinding a module global.",
+);
+
+pub const PY_GLOBAL_CONTAINER: Rule = rule(
+    "GH-PY-007",
+    "global-container",
+    Some("python"),
+    "Object stored in a module container",
+    "A local object is stored in a module-level list, dict or other container.",
+    "Store a copy, or remove the entry before the function returns.",
+);
+
+pub const PY_OUTER_SCOPE_CAPTURE: Rule = rule(
+    "GH-PY-008",
+    "outer-scope-capture",
+    Some("python"),
+    "Variable captured from an outer scope",
+    "A nested function captures a variable of the enclosing function (`nonlocal`).",
+    "Pass the value as an argument instead of capturing it.",
+);
+
+pub const PY_PASSED_ARGUMENT: Rule = rule(
+    "GH-PY-009",
+    "passed-argument",
+    Some("python"),
+    "Object passed to another function",
+    "A tracked object is passed to a call that may keep it.",
+    "Pass a copy, or make sure the callee does not store its argument.",
+);
+
+pub const PY_LAMBDA_CAPTURE: Rule = rule(
+    "GH-PY-010",
+    "lambda-capture",
+    Some("python"),
+    "Object captured by a lambda",
+    "A lambda or closure captures a tracked object.",
+    "Bind the value the closure needs as a default argument copy, or avoid keeping the closure.",
+);
+
+pub const JS_FLOATING_PROMISE: Rule = rule(
+    "GH-JS-001",
+    "floating-promise",
+    Some("javascript"),
+    "Floating promise",
+    "An async function or `fetch` is called as a bare statement, so its work continues after the function returns and its errors go unhandled.",
+    "`await` the call, return its promise, or keep it and handle rejection.",
+);
+
+pub const JS_UNTERMINATED_WORKER: Rule = rule(
+    "GH-JS-002",
+    "unterminated-worker",
+    Some("javascript"),
+    "Worker never terminated",
+    "A `Worker` is started and never terminated, awaited through an `'exit'` listener or returned.",
+    "Call `terminate()` when done, or listen for `'exit'` and return the worker to the caller.",
+);
+
+pub const JS_UNCLEARED_INTERVAL: Rule = rule(
+    "GH-JS-003",
+    "uncleared-interval",
+    Some("javascript"),
+    "Interval never cleared",
+    "A `setInterval` handle is discarded or never passed to `clearInterval`, so the callback runs forever.",
+    "Keep the handle and call `clearInterval` on every path that ends the work.",
+);
+
+pub const JS_UNHANDLED_CHILD_PROCESS: Rule = rule(
+    "GH-JS-004",
+    "unhandled-child-process",
+    Some("javascript"),
+    "Child process without exit handling",
+    "A child process is started and never awaited, killed or given an `'exit'`/`'close'` listener.",
+    "Wait for `'exit'` or `'close'`, use the callback or promise form, or kill the process when done.",
+);
+
+pub const JS_DISCARDED_TIMER: Rule = rule(
+    "GH-JS-005",
+    "discarded-timer",
+    Some("javascript"),
+    "Timer handle discarded",
+    "`setTimeout`, `setInterval` or `setImmediate` is called without keeping its handle, so it cannot be cancelled.",
+    "Keep the handle and clear it when the work is no longer needed.",
+);
+
+pub const JS_UNCLEARED_TIMER: Rule = rule(
+    "GH-JS-006",
+    "uncleared-timer",
+    Some("javascript"),
+    "Timer never cleared",
+    "A timer handle is kept but never cleared before the function returns.",
+    "Call `clearTimeout`/`clearInterval` on the handle, or return it to the caller.",
+);
+
+pub const JS_DEFERRED_TICK: Rule = rule(
+    "GH-JS-007",
+    "deferred-tick",
+    Some("javascript"),
+    "Work deferred with process.nextTick",
+    "`process.nextTick` schedules a callback that runs after the function returns.",
+    "Run the work synchronously, or return a promise that resolves once it has run.",
+);
+
+pub const JS_UNHANDLED_PROMISE: Rule = rule(
+    "GH-JS-008",
+    "unhandled-promise",
+    Some("javascript"),
+    "Promise neither awaited nor handled",
+    "A promise is stored but never awaited, returned or given a rejection handler.",
+    "`await` it, return it, or attach `.catch()`.",
+);
+
+pub const JS_HELPER_RETAINED: Rule = rule(
+    "GH-JS-009",
+    "helper-retained",
+    Some("javascript"),
+    "Object retained through a helper",
+    "A local object is passed to a helper that stores it in a retained container.",
+    "Pass a copy to the helper, or change it not to keep its argument.",
+);
+
+pub const JS_RETAINED_CLOSURE: Rule = rule(
+    "GH-JS-010",
+    "retained-closure",
+    Some("javascript"),
+    "Object captured by a retained closure",
+    "A closure capturing a local object is stored in a retained container.",
+    "Capture only the primitive values the closure needs, or remove the closure when done.",
+);
+
+pub const JS_RETAINED_CONTAINER: Rule = rule(
+    "GH-JS-011",
+    "retained-container",
+    Some("javascript"),
+    "Object stored in a retained container",
+    "A local object is stored in a module-level container or binding that outlives the call.",
+    "Store a copy, or remove the entry before the function returns.",
+);
+
+pub const JS_RETURNED_OBJECT: Rule = rule(
+    "GH-JS-012",
+    "returned-object",
+    Some("javascript"),
+    "Local object returned",
+    "An object created in the function is returned to the caller.",
+    "Return a copy or a primitive when the caller must not share the object.",
+);
+
+pub const GO_WAITGROUP_IMBALANCE: Rule = rule(
+    "GH-GO-001",
+    "waitgroup-imbalance",
+    Some("go"),
+    "WaitGroup or errgroup imbalance",
+    "`Add` and `Done` calls of a `sync.WaitGroup` do not balance on every path, `Add` runs inside the goroutine, or the group is never waited on.",
+    "Call `Add` before starting each goroutine, `defer wg.Done()` first thing in it, and `Wait()` before returning.",
+);
+
+pub const GO_UNAWAITED_GOROUTINE: Rule = rule(
+    "GH-GO-002",
+    "unawaited-goroutine",
+    Some("go"),
+    "Goroutine not awaited",
+    "A goroutine is started and nothing the function waits on guarantees it finishes before the function returns.",
+    "Wait for it with a `sync.WaitGroup`, an `errgroup.Group` or a channel receive before returning.",
+);
+
+pub const GO_UNRECEIVED_CHANNEL: Rule = rule(
+    "GH-GO-003",
+    "unreceived-channel",
+    Some("go"),
+    "Channel never received on",
+    "A channel is created but never received from, so a goroutine sending on it can block forever.",
+    "Receive every value sent, buffer the channel for them, or close it and drain it before returning.",
+);
+
+pub const GO_HELPER_RETAINED: Rule = rule(
+    "GH-GO-004",
+    "helper-retained",
+    Some("go"),
+    "Object retained through a helper or interface",
+    "A local object is passed to a helper function or interface method that stores it in a retained container.",
+    "Pass a copy, or change the helper not to keep its argument.",
+);
+
+pub const GO_RETAINED_CLOSURE: Rule = rule(
+    "GH-GO-005",
+    "retained-closure",
+    Some("go"),
+    "Object captured by a retained closure",
+    "A closure capturing a local object is stored in a package-level container.",
+    "Capture only the values the closure needs by copy, or remove the closure when done.",
+);
+
+pub const GO_RETAINED_CONTAINER: Rule = rule(
+    "GH-GO-006",
+    "retained-container",
+    Some("go"),
+    "Object stored in a package container",
+    "A local object is appended, assigned or stored into a package-level slice, map or container.",
+    "Store a copy, or remove the entry before the function returns.",
+);
+
+pub const GO_RETURNED_OBJECT: Rule = rule(
+    "GH-GO-007",
+    "returned-object",
+    Some("go"),
+    "Local object returned",
+    "An object created in the function is returned to the caller.",
+    "Return a copy or a value type when the caller must not share the object.",
+);
+
+pub const JAVA_INLINE_THREAD: Rule = rule(
+    "GH-JV-001",
+    "inline-thread",
+    Some("java"),
+    "Thread started inline",
+    "A `new Thread(...)` is started without keeping a reference, so it can never be joined.",
+    "Keep the thread and `join()` it, or submit the task to an executor that is shut down and awaited.",
+);
+
+pub const JAVA_UNJOINED_THREAD: Rule = rule(
+    "GH-JV-002",
+    "unjoined-thread",
+    Some("java"),
+    "Thread or executor not joined",
+    "A thread is never joined, or an executor is never shut down and awaited.",
+    "`join()` the thread, or call `shutdown()` and `awaitTermination()` on the executor (try-with-resources on Java 19+).",
+);
+
+pub const JAVA_UNJOINED_FUTURE: Rule = rule(
+    "GH-JV-003",
+    "unjoined-future",
+    Some("java"),
+    "CompletableFuture not joined",
+    "A `CompletableFuture.runAsync`/`supplyAsync` task is started and never joined or returned.",
+    "`join()` or `get()` the future before returning, or return it to the caller.",
+);
+
+pub const JAVA_RETAINING_CALL: Rule = rule(
+    "GH-JV-004",
+    "retaining-call",
+    Some("java"),
+    "Object passed to a retaining API",
+    "A local or parameter is passed to an executor, listener registry or other API that keeps it beyond the call (`[retaining_apis]`).",
+    "Unregister or shut down what keeps it before returning, or pass a copy.",
+);
+
+pub const JAVA_RETAINED_CLOSURE: Rule = rule(
+    "GH-JV-005",
+    "retained-closure",
+    Some("java"),
+    "Object captured by a retained lambda",
+    "A lambda capturing a local object is stored in a static field.",
+    "Capture only the values the lambda needs, or remove it when done.",
+);
+
+pub const JAVA_RETAINED_CONTAINER: Rule = rule(
+    "GH-JV-006",
+    "retained-container",
+    Some("java"),
+    "Object stored in a static container",
+    "A local object is stored in a static collection, map or array.",
+    "Store a copy, or remove the entry before the method returns.",
+);
+
+pub const JAVA_RETURNED_OBJECT: Rule = rule(
+    "GH-JV-007",
+    "returned-object",
+    Some("java"),
+    "Local object returned",
+    "An object created in the method is returned to the caller.",
+    "Return a copy or an immutable view when the caller must not share the object.",
+);
+
+pub const RS_DROPPED_HANDLE: Rule = rule(
+    "GH-RS-001",
+    "dropped-handle",
+    Some("rust"),
+    "JoinHandle dropped without join",
+    "A thread or task handle is dropped, bound to `_`, discarded or `.detach()`ed, which detaches the thread or task (smol tasks are cancelled when dropped instead, and only escape when detached).",
+    "Keep the handle and `join()`/`.await` it, or use `thread::scope` or a `JoinSet`.",
+);
+
+pub const RS_UNJOINED_HANDLE: Rule = rule(
+    "GH-RS-002",
+    "unjoined-handle",
+    Some("rust"),
+    "JoinHandle never joined",
+    "A thread or task handle is kept but never joined, awaited or aborted before the function returns.",
+    "`join()` or `.await` the handle on every path, or `abort()` it.",
+);
+
+pub const RS_UNJOINED_HANDLE_COLLECTION: Rule = rule(
+    "GH-RS-003",
+    "unjoined-handle-collection",
+    Some("rust"),
+    "Collected JoinHandles never joined",
+    "Thread or task handles are pushed into a collection that is never drained and joined.",
+    "Join every handle in the collection before returning, or use a `JoinSet`.",
+);
+
+pub const RS_LEAKED_HANDLE: Rule = rule(
+    "GH-RS-004",
+    "leaked-handle",
+    Some("rust"),
+    "Handle, sender or guard leaked",
+    "`mem::forget`, `Box::leak` or `ManuallyDrop::new` skips the destructor of a thread handle, channel sender or lock guard, so the thread is never joined, the channel never closes or the lock is never released.",
+    "Let the value drop normally, or join, close or unlock it explicitly first.",
+);
+
+pub const RS_SPAWN_IN_DROP: Rule = rule(
+    "GH-RS-005",
+    "spawn-in-drop",
+    Some("rust"),
+    "Spawn from Drop",
+    "A value created in the function spawns work from its `Drop::drop` or a scope guard's cleanup closure, which runs whenever it is dropped, including on cancellation.",
+    "Do the work explicitly before dropping, and keep `Drop` free of spawns.",
+);
+
+pub const RS_DETACHED_CAPTURE: Rule = rule(
+    "GH-RS-006",
+    "detached-capture",
+    Some("rust"),
+    "Local captured by an outliving closure",
+    "A local is moved into a closure or async block run by a detached or never-joined thread or task, or stored in a static.",
+    "Join the thread or task before returning, or pass it an owned copy it may keep.",
+);
+
+pub const RS_RETAINED_ARGUMENT: Rule = rule(
+    "GH-RS-007",
+    "retained-argument",
+    Some("rust"),
+    "Local handed to a retaining API",
+    "A local is passed to an API that keeps it beyond the call (`[retaining_apis]`). A local spawned as a thread's or task's work is covered by the finding on its handle instead.",
+    "Unregister what keeps the value before returning, or pass a copy.",
+);
+
+pub const RS_UNSCOPED_SPAWN: Rule = rule(
+    "GH-RS-008",
+    "unscoped-spawn",
+    Some("rust"),
+    "Spawn may outlive its scope",
+    "A thread or task is spawned outside a scope that bounds its lifetime.",
+    "Use `thread::scope`, or keep and join the handle.",
+);
+
+pub const RS_GLOBAL_STORE: Rule = rule(
+    "GH-RS-009",
+    "global-store",
+    Some("rust"),
+    "Value stored in global state",
+    "A value is pushed, inserted or set into a static, directly or through a lock guard or thread-local borrow.",
+    "Store a copy, or remove the entry before the function returns.",
+);
+
+pub const RS_STATIC_MUT_WRITE: Rule = rule(
+    "GH-RS-010",
+    "static-mut-write",
+    Some("rust"),
+    "Write to a static mut",
+    "A `static mut` is assigned, so the value outlives the call and races with other threads.",
+    "Use a `Mutex`, atomic or `OnceLock` static, or pass the value explicitly.",
+);
+
+pub const RS_RETURNED_VALUE: Rule = rule(
+    "GH-RS-011",
+    "returned-value",
+    Some("rust"),
+    "Local value returned",
+    "A value created in the function is returned to the caller.",
+    "Nothing to fix when ownership is meant to move to the caller.",
+);
+
+pub const RS_HEAP_ALLOCATION: Rule = rule(
+    "GH-RS-012",
+    "heap-allocation",
+    Some("rust"),
+    "Heap allocation bound to a local",
+    "A heap-allocated structure (`Box`, `Rc`, `Arc`, `Vec`) is bound to a local that also escapes the call, so the allocation outlives it.",
+    "Fix the escape of the local; the allocation is freed once nothing outside the call holds it.",
+);
+
+pub const RS_DETACHED_POOL_JOB: Rule = rule(
+    "GH-RS-013",
+    "detached-pool-job",
+    Some("rust"),
+    "Pool job with nothing to wait for it",
+    "A job is handed to a thread pool without a handle: `rayon::spawn`, `spawn` on a rayon pool, `spawn_ok` on a `futures` pool, or `execute` on a `threadpool` pool that is not joined before the function returns.",
+    "Spawn in `rayon::scope`, use `spawn_with_handle` and await it, or `join()` the pool before returning.",
+);
+
+pub const RS_LONG_LIVED_POOL: Rule = rule(
+    "GH-RS-014",
+    "long-lived-pool",
+    Some("rust"),
+    "Thread pool outlives the call",
+    "A thread pool built in the function outlives it: rayon's global pool built with `build_global`, or a pool stored in a static, whose worker threads stay alive for the process.",
+    "Build a local pool and run the work in it with `install`, or build the shared pool once at startup.",
+);
+
+pub const RS_CHANNEL_WORKER: Rule = rule(
+    "GH-RS-015",
+    "channel-worker",
+    Some("rust"),
+    "Crossbeam channel worker never joined",
+    "A `crossbeam::channel` sender or receiver is moved into a thread or task that is detached or never joined, so the producer keeps sending, or the consumer keeps waiting for every sender to drop, after the function returns. Threads spawned in `crossbeam::scope` are joined when it ends and are not reported.",
+    "Spawn the producer or consumer in `crossbeam::scope` and drop the senders before the scope ends, or join the thread.",
+);
+
+/// The catalog, grouped by language.
+pub const RULES: &[Rule] = &[
+    SPAWN_IN_LOOP,
+    RECURSIVE_SPAWN,
+    EXIT_SKIPS_CLEANUP,
+    PY_UNJOINED_CONCURRENCY,
+    PY_DECORATOR_SPAWN,
+    PY_CONCURRENT_RETURN,
+    PY_CONCURRENT_ASSIGNMENT,
+    PY_RETURNED_OBJECT,
+    PY_GLOBAL_DECLARATION,
+    PY_GLOBAL_CONTAINER,
+    PY_OUTER_SCOPE_CAPTURE,
+    PY_PASSED_ARGUMENT,
+    PY_LAMBDA_CAPTURE,
+    JS_FLOATING_PROMISE,
+    JS_UNTERMINATED_WORKER,
+    JS_UNCLEARED_INTERVAL,
+    JS_UNHANDLED_CHILD_PROCESS,
+    JS_DISCARDED_TIMER,
+    JS_UNCLEARED_TIMER,
+    JS_DEFERRED_TICK,
+    JS_UNHANDLED_PROMISE,
+    JS_HELPER_RETAINED,
+    JS_RETAINED_CLOSURE,
+    JS_RETAINED_CONTAINER,
+    JS_RETURNED_OBJECT,
+    GO_WAITGROUP_IMBALANCE,
+    GO_UNAWAITED_GOROUTINE,
+    GO_UNRECEIVED_CHANNEL,
+    GO_HELPER_RETAINED,
+    GO_RETAINED_CLOSURE,
+    GO_RETAINED_CONTAINER,
+    GO_RETURNED_OBJECT,
+    JAVA_INLINE_THREAD,
+    JAVA_UNJOINED_THREAD,
+    JAVA_UNJOINED_FUTURE,
+    JAVA_RETAINING_CALL,
+    JAVA_RETAINED_CLOSURE,
+    JAVA_RETAINED_CONTAINER,
+    JAVA_RETURNED_OBJECT,
+    RS_DROPPED_HANDLE,
+    RS_UNJOINED_HANDLE,
+    RS_UNJOINED_HANDLE_COLLECTION,
+    RS_LEAKED_HANDLE,
+    RS_SPAWN_IN_DROP,
+    RS_DETACHED_CAPTURE,
+    RS_RETAINED_ARGUMENT,
+    RS_UNSCOPED_SPAWN,
+    RS_GLOBAL_STORE,
+    RS_STATIC_MUT_WRITE,
+    RS_RETURNED_VALUE,
+    RS_HEAP_ALLOCATION,
+    RS_DETACHED_POOL_JOB,
+    RS_LONG_LIVED_POOL,
+    RS_CHANNEL_WORKER,
+];
+
+/// Rules of `language`, plus those every analyzer shares; all of them for `None`.
+pub fn for_language(language: Option<&str>) -> Vec<&'static Rule> {
+    RULES
+        .iter()
+        .filter(|rule| language.is_none() || rule.language.is_none() || rule.language == language)
+        .collect()
+}
+
+pub fn find(id: &str) -> Option<&'static Rule> {
+    RULES.iter().find(|rule| rule.id == id)
+}
+
+/// `id` when it names a rule of `language`'s analyzer. The external analyzers report
+/// ids in their output, so one the catalog does not know is dropped with a warning.
+pub fn checked(language: &str, id: Option<String>) -> Option<String> {
+    let id = id?;
+    if for_language(Some(language)).iter().any(|rule| rule.id == id) {
+        return Some(id);
+    }
+    tracing::warn!("{} analyzer reported unknown rule id {}", language, id);
+    None
+}
@@ -1,14 +1,81 @@
-/// Rust static escape analyzer using lightweight source parsing
+//! Rust static escape analyzer built on the `syn` AST
+//! Walks the target function's body, including closures, async blocks and macro
+//! arguments that parse as Rust, and follows thread/task handles through moves,
+//! collections and drops to the join, await or hand-off that settles them. Locals
+//! captured by `move` closures that outlive the call, and writes to statics, are
+//! reported as closure and global escapes, and locals handed to calls that retain
+//! them (see `retaining`) as parameter escapes.
 
 use crate::protocol::{
     ConfidenceLevel, EscapeType, SourceLocation, StaticAnalysisResult, StaticEscape,
-    StaticEscapeSummary,
+    StaticEscapeSummary, SuggestedFix,
 };
+use crate::static_analyzer::callgraph;
+use crate::static_analyzer::fixes::{self, FixContext};
+use crate::static_analyzer::retaining::RetainingApis;
+use crate::static_analyzer::rules::{self, Rule};
 use crate::static_analyzer::StaticEscapeAnalyzer;
 use anyhow::{Context, Result};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::time::Instant;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    Block, Expr, ExprAssign, ExprCall, ExprClosure, ExprMethodCall, FnArg, ImplItemFn, Item, ItemFn,
+    ItemImpl, ItemStatic, Local, Macro, Pat, ReturnType, Signature, Stmt, Token, TraitItemFn,
+    Type,
+};
+
+/// Dedicated rule for `mem::forget`, `Box::leak` and `ManuallyDrop` applied to
+/// handles, senders and guards.
+const LEAK_RULE: &Rule = &rules::RS_LEAKED_HANDLE;
+
+/// Dedicated rule for threads and tasks started from `Drop::drop` or from cleanup
+/// closures registered with scope guards (`defer!`, `scopeguard::guard`).
+const DROP_SPAWN_RULE: &Rule = &rules::RS_SPAWN_IN_DROP;
+
+/// Dedicated rule for jobs handed to a thread pool with nothing to wait for them by:
+/// `rayon::spawn`, `spawn` on a rayon pool, `spawn_ok` on a `futures` pool, and
+/// `execute` on a `threadpool` pool that is never joined.
+const POOL_JOB_RULE: &Rule = &rules::RS_DETACHED_POOL_JOB;
+
+/// Dedicated rule for thread pools built in the function that outlive it: rayon's
+/// global pool, and pools stored in a static, whose workers stay alive for the process.
+const LONG_LIVED_POOL_RULE: &Rule = &rules::RS_LONG_LIVED_POOL;
+
+/// Dedicated rule for `crossbeam::channel` producers and consumers: a sender or receiver
+/// moved into a thread or task that is detached or never joined, rather than spawned
+/// in `crossbeam::scope`.
+const CHANNEL_WORKER_RULE: &Rule = &rules::RS_CHANNEL_WORKER;
+
+/// Functions and methods that start a thread or task. `spawn_ok` runs a future on a
+/// `futures` thread pool and `spawn_fifo` a job on a rayon pool; neither keeps a handle.
+const SPAWN_CALLS: [&str; 5] = ["spawn", "spawn_blocking", "spawn_local", "spawn_ok", "spawn_fifo"];
+
+/// Handle methods that wait for or cancel the thread or task.
+const JOIN_METHODS: [&str; 3] = ["join", "abort", "cancel"];
+
+/// Zero-argument methods that return a lock guard.
+const GUARD_METHODS: [&str; 5] = ["lock", "read", "write", "borrow_mut", "try_lock"];
+
+const CHANNEL_CONSTRUCTORS: [&str; 4] = ["channel", "sync_channel", "unbounded", "bounded"];
+
+const HEAP_TYPES: [&str; 7] = ["Box", "Vec", "String", "Arc", "Rc", "HashMap", "HashSet"];
+
+/// Methods that store a value into a container.
+const STORE_METHODS: [&str; 4] = ["push", "push_back", "insert", "extend"];
+
+/// `OnceCell`/`OnceLock`, `Cell` and `Option` methods that put a value in place.
+const SET_METHODS: [&str; 3] = ["set", "get_or_init", "replace"];
+
+/// Types that own what is spawned on them and join or abort it when dropped.
+const SPAWN_OWNERS: [&str; 3] = ["JoinSet", "Executor", "LocalExecutor"];
+
+/// Functions and methods running a closure whose parameter is a scope: what is spawned
+/// on it is joined before they return (`thread::scope`, `rayon::scope`, `pool.scope`).
+const SCOPE_CALLS: [&str; 4] = ["scope", "scope_fifo", "in_place_scope", "in_place_scope_fifo"];
 
 pub struct RustStaticAnalyzer;
 
@@ -23,18 +90,35 @@ impl StaticEscapeAnalyzer for RustStaticAnalyzer {
         let start_time = Instant::now();
         let source = fs::read_to_string(source_file)
             .with_context(|| format!("Failed to read source file: {}", source_file))?;
+        let file = syn::parse_file(&source)
+            .with_context(|| format!("Failed to parse Rust source file: {}", source_file))?;
+        let context = FileContext::new(&file, &source, source_file, RetainingApis::load("rust")?);
 
         let target_function = parse_target_function(target);
+        let owner = callgraph::target_owner(target);
         let mut warnings = vec![];
 
         let escapes = if let Some(function_name) = target_function.as_deref() {
-            analyze_function(&source, source_file, function_name, &mut warnings)
+            match find_function(&file.items, function_name, owner) {
+                Some((signature, body)) => {
+                    let mut escapes = analyze_function(&context, function_name, signature, body);
+                    escapes.extend(detect_drop_spawns(&context, function_name, signature, body));
+                    escapes
+                }
+                None => {
+                    warnings.push(match owner {
+                        Some(owner) => format!("Target method '{}::{}' not found in source file", owner, function_name),
+                        None => format!("Target function '{}' not found in source file", function_name),
+                    });
+                    vec![]
+                }
+            }
         } else {
-            analyze_file(&source, source_file)
+            analyze_file(&context, &file)
         };
 
         if target_function.is_some() && escapes.is_empty() {
-            warnings.push("No Rust escapes detected by heuristic analyzer".to_string());
+            warnings.push("No Rust escapes detected in the target function".to_string());
         }
 
         let mut summary = StaticEscapeSummary::new();
@@ -49,18 +133,22 @@ impl StaticEscapeAnalyzer for RustStaticAnalyzer {
             analysis_time_ms: start_time.elapsed().as_millis() as u64,
             warnings,
             summary,
+            call_graph: None,
+            spawn_estimate: None,
         })
     }
-    
+
     fn language(&self) -> &str {
         "rust"
     }
-    
+
+    /// Parsing is built in, so no toolchain is needed.
     fn is_available(&self) -> bool {
-        std::process::Command::new("rustc")
-            .arg("--version")
-            .output()
-            .is_ok()
+        true
+    }
+
+    fn suggest_fix(&self, escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+        suggested_fix(escape, context)
     }
 }
 
@@ -86,422 +174,2289 @@ fn parse_target_function(target: &str) -> Option<String> {
     None
 }
 
-fn analyze_file(source: &str, source_file: &str) -> Vec<StaticEscape> {
-    let mut escapes = vec![];
-    for (idx, line) in source.lines().enumerate() {
-        if let Some(escape) = detect_concurrency(line, source_file, idx + 1, "<module>") {
-            escapes.push(escape);
+/// What the analysis needs from the rest of the file.
+struct FileContext<'a> {
+    source_file: &'a str,
+    lines: Vec<&'a str>,
+    /// `static` items, whose contents outlive every call
+    statics: HashSet<String>,
+    /// Functions and methods declared to return a `JoinHandle`
+    spawner_fns: HashSet<String>,
+    /// Types whose `Drop::drop` spawns, with the line of the first spawn
+    drop_spawns: BTreeMap<String, usize>,
+    retaining: RetainingApis,
+}
+
+impl<'a> FileContext<'a> {
+    fn new(file: &syn::File, source: &'a str, source_file: &'a str, retaining: RetainingApis) -> Self {
+        let mut context = Self {
+            source_file,
+            lines: source.lines().collect(),
+            statics: HashSet::new(),
+            spawner_fns: HashSet::new(),
+            drop_spawns: BTreeMap::new(),
+            retaining,
+        };
+        context.visit_file(file);
+        context
+    }
+
+    /// Trimmed source text of a 1-based line.
+    fn line(&self, line: usize) -> String {
+        self.lines
+            .get(line.wrapping_sub(1))
+            .map(|code| code.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Whether a path root names global state: a `static` declared in the file or a
+    /// SCREAMING_CASE name imported from elsewhere.
+    fn is_global(&self, name: &str) -> bool {
+        self.statics.contains(name)
+            || (name.chars().any(|ch| ch.is_ascii_uppercase())
+                && name.chars().all(|ch| ch.is_ascii_uppercase() || ch.is_ascii_digit() || ch == '_'))
+    }
+
+    /// A finding of `rule` at `line`, to be filled in by the caller.
+    fn escape(&self, rule: &Rule, function_name: &str, line: usize, column: usize) -> StaticEscape {
+        StaticEscape {
+            escape_type: EscapeType::UnknownEscape,
+            location: SourceLocation {
+                file: self.source_file.to_string(),
+                line,
+                column,
+                function: function_name.to_string(),
+                code_snippet: Some(self.line(line)).filter(|code| !code.is_empty()),
+            },
+            variable_name: String::new(),
+            reason: String::new(),
+            confidence: ConfidenceLevel::High,
+            data_flow: vec![],
+            input_tainted: false,
+            taint_flow: vec![],
+            rule: None,
+            rule_id: Some(rule.id.to_string()),
+            condition: None,
+            suggested_fix: None,
         }
     }
-    escapes
 }
 
-fn analyze_function(
-    source: &str,
-    source_file: &str,
-    function_name: &str,
-    warnings: &mut Vec<String>,
-) -> Vec<StaticEscape> {
-    let lines: Vec<&str> = source.lines().collect();
-    let mut escapes = vec![];
-    let mut in_target = false;
-    let mut brace_depth: i32 = 0;
-    let mut locals: HashSet<String> = HashSet::new();
-    let mut found_function = false;
-    let mut i = 0;
-    let mut thread_handles: HashSet<String> = HashSet::new();  // Track thread/task handles
-    let mut joined_handles: HashSet<String> = HashSet::new();  // Track joined handles
-
-    while i < lines.len() {
-        let line = lines[i];
-
-        if !in_target {
-            if let Some(name) = extract_fn_name(line) {
-                if name == function_name {
-                    found_function = true;
-                    let mut signature = line.to_string();
-                    while !signature.contains('{') && i + 1 < lines.len() {
-                        i += 1;
-                        signature.push('\n');
-                        signature.push_str(lines[i]);
-                    }
-                    locals.extend(extract_params(&signature));
-                    brace_depth = count_braces(&signature);
-                    in_target = brace_depth > 0;
-                }
+impl<'ast> Visit<'ast> for FileContext<'_> {
+    fn visit_item_static(&mut self, item: &'ast ItemStatic) {
+        self.statics.insert(item.ident.to_string());
+    }
+
+    fn visit_signature(&mut self, signature: &'ast Signature) {
+        if let ReturnType::Type(_, ty) = &signature.output {
+            if type_mentions(ty, "JoinHandle") {
+                self.spawner_fns.insert(signature.ident.to_string());
             }
-        } else {
-            if let Some(local) = extract_let_binding(line) {
-                locals.insert(local.clone());
-                // Check if this is a thread/task handle
-                if is_thread_creation(line) {
-                    thread_handles.insert(local);
+        }
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast ItemImpl) {
+        let is_drop = item
+            .trait_
+            .as_ref()
+            .and_then(|(_, path, _)| path.segments.last())
+            .is_some_and(|segment| segment.ident == "Drop");
+        if let (true, Type::Path(self_ty)) = (is_drop, &*item.self_ty) {
+            if let Some(segment) = self_ty.path.segments.last() {
+                let mut finder = SpawnFinder::default();
+                for impl_item in &item.items {
+                    finder.visit_impl_item(impl_item);
+                }
+                if let Some(line) = finder.first {
+                    self.drop_spawns.entry(segment.ident.to_string()).or_insert(line);
                 }
             }
+        }
+        visit::visit_item_impl(self, item);
+    }
+}
 
-            // Check for .join() calls
-            if let Some(handle) = extract_join_call(line) {
-                joined_handles.insert(handle);
-            }
+/// Signature and body of the first function, method or default trait method with the
+/// name. With an `owner`, only methods in an `impl` of that type qualify.
+fn find_function<'a>(items: &'a [Item], name: &str, owner: Option<&str>) -> Option<(&'a Signature, &'a Block)> {
+    struct Finder<'a, 'n> {
+        name: &'n str,
+        owner: Option<&'n str>,
+        /// Type of the `impl` block being visited.
+        impl_type: Option<String>,
+        found: Option<(&'a Signature, &'a Block)>,
+    }
 
-            if let Some(escape) = detect_return_escape(line, source_file, i + 1, function_name, &locals) {
-                escapes.push(escape);
+    impl<'a> Visit<'a> for Finder<'a, '_> {
+        fn visit_item_fn(&mut self, item: &'a ItemFn) {
+            if self.found.is_none() && self.owner.is_none() && item.sig.ident == self.name {
+                self.found = Some((&item.sig, &item.block));
             }
+        }
 
-            if let Some(escape) = detect_heap_escape(line, source_file, i + 1, function_name) {
-                escapes.push(escape);
+        fn visit_item_impl(&mut self, item: &'a ItemImpl) {
+            let impl_type = match &*item.self_ty {
+                Type::Path(self_ty) => self_ty.path.segments.last().map(|segment| segment.ident.to_string()),
+                _ => None,
+            };
+            let outer = std::mem::replace(&mut self.impl_type, impl_type);
+            visit::visit_item_impl(self, item);
+            self.impl_type = outer;
+        }
+
+        fn visit_impl_item_fn(&mut self, item: &'a ImplItemFn) {
+            let owned = self.owner.is_none_or(|owner| self.impl_type.as_deref() == Some(owner));
+            if self.found.is_none() && owned && item.sig.ident == self.name {
+                self.found = Some((&item.sig, &item.block));
             }
+        }
 
-            if let Some(escape) = detect_retained_global_escape(line, source_file, i + 1, function_name) {
-                escapes.push(escape);
+        fn visit_trait_item_fn(&mut self, item: &'a TraitItemFn) {
+            if let (None, None, Some(block)) = (&self.found, self.owner, &item.default) {
+                if item.sig.ident == self.name {
+                    self.found = Some((&item.sig, block));
+                }
             }
+        }
+    }
+
+    let mut finder = Finder {
+        name,
+        owner,
+        impl_type: None,
+        found: None,
+    };
+    for item in items {
+        finder.visit_item(item);
+    }
+    finder.found
+}
+
+/// Every spawn site in the file, for targets that name no function.
+fn analyze_file(context: &FileContext, file: &syn::File) -> Vec<StaticEscape> {
+    struct FileSpawns<'c> {
+        context: &'c FileContext<'c>,
+        function: String,
+        owners: HashSet<String>,
+        escapes: Vec<StaticEscape>,
+    }
 
-            // Only report concurrency escape if handle is created (not just spawn calls)
-            // We'll check for unjoined handles at the end
+    impl FileSpawns<'_> {
+        fn enter(&mut self, name: String, body: impl FnOnce(&mut Self)) {
+            let outer = std::mem::replace(&mut self.function, name);
+            body(self);
+            self.function = outer;
+        }
+    }
+
+    impl<'ast> Visit<'ast> for FileSpawns<'_> {
+        fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+            self.enter(item.sig.ident.to_string(), |this| visit::visit_item_fn(this, item));
+        }
 
-            brace_depth += count_braces(line);
-            if brace_depth <= 0 {
-                break;
+        fn visit_impl_item_fn(&mut self, item: &'ast ImplItemFn) {
+            self.enter(item.sig.ident.to_string(), |this| visit::visit_impl_item_fn(this, item));
+        }
+
+        fn visit_local(&mut self, local: &'ast Local) {
+            if let (Some(name), Some(init)) = (single_binding(&local.pat), &local.init) {
+                if constructs_owner(&init.expr) {
+                    self.owners.insert(name);
+                }
             }
+            visit::visit_local(self, local);
         }
 
-        i += 1;
-    }
-    
-    // Check for unjoined thread handles
-    for handle in thread_handles {
-        if !joined_handles.contains(&handle) {
-            // Find the line where this handle was created
-            if let Some(line_num) = find_variable_line(source, function_name, &handle) {
-                let reason = format!("Thread/task handle '{}' created but not joined", handle);
-                escapes.push(StaticEscape {
-                    escape_type: classify_rust_async_escape(None, &reason, &handle),
-                    location: SourceLocation {
-                        file: source_file.to_string(),
-                        line: line_num,
-                        column: 0,
-                        function: function_name.to_string(),
-                        code_snippet: None,
-                    },
-                    variable_name: handle.clone(),
-                    reason,
-                    confidence: ConfidenceLevel::High,
-                    data_flow: vec![],
-                });
+        fn visit_expr(&mut self, expr: &'ast Expr) {
+            self.owners.extend(scope_param(expr));
+            if let Some(spawn) = spawn_site(expr, &self.context.spawner_fns, &self.owners) {
+                let start = expr.span().start();
+                let reason = format!("{} may leak work beyond scope", spawn.kind);
+                let mut escape = self.context.escape(&rules::RS_UNSCOPED_SPAWN, &self.function, start.line, start.column);
+                escape.escape_type = spawn_escape_type(&spawn, Some(&self.context.line(start.line)), &reason);
+                escape.variable_name = spawn.callee;
+                escape.reason = reason;
+                self.escapes.push(escape);
             }
+            visit::visit_expr(self, expr);
+        }
+
+        fn visit_macro(&mut self, mac: &'ast Macro) {
+            visit_macro_body(self, mac);
         }
     }
 
-    if !found_function {
-        warnings.push(format!(
-            "Target function '{}' not found in source file",
-            function_name
-        ));
+    let mut spawns = FileSpawns {
+        context,
+        function: "<module>".to_string(),
+        owners: HashSet::new(),
+        escapes: vec![],
+    };
+    spawns.visit_file(file);
+    spawns.escapes
+}
+
+fn analyze_function(
+    context: &FileContext,
+    function_name: &str,
+    signature: &Signature,
+    body: &Block,
+) -> Vec<StaticEscape> {
+    let mut visitor = FunctionVisitor {
+        context,
+        function_name,
+        locals: HashSet::new(),
+        local_lines: HashMap::new(),
+        handles: vec![],
+        names: HashMap::new(),
+        senders: HashSet::new(),
+        channel_ends: HashMap::new(),
+        guards: HashSet::new(),
+        spawners: context.spawner_fns.clone(),
+        owners: HashSet::new(),
+        pools: HashMap::new(),
+        binding: None,
+        heap_lines: HashSet::new(),
+        heap: vec![],
+        closures: HashMap::new(),
+        global_aliases: HashMap::new(),
+        escapes: vec![],
+    };
+    for input in &signature.inputs {
+        match input {
+            FnArg::Receiver(_) => {
+                visitor.locals.insert("self".to_string());
+            }
+            FnArg::Typed(arg) => pattern_idents(&arg.pat, &mut visitor.locals),
+        }
     }
+    visitor.visit_block(body);
+    visitor.finish()
+}
 
-    escapes
+/// What happened to a thread/task handle, or to a collection of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fate {
+    Pending,
+    /// Joined, awaited, aborted, or moved somewhere that decides its fate
+    Settled,
+    /// Passed to `drop` at this line
+    Dropped(usize),
+    /// Let go of with `.detach()` at this line, so it runs on unwatched
+    Detached(usize),
+}
+
+struct Handle {
+    name: String,
+    line: usize,
+    /// A `Vec` or other collection of handles rather than a single one
+    collection: bool,
+    /// A smol task, which is cancelled when its handle is dropped
+    cancels_on_drop: bool,
+    /// Pushed into this collection, which now decides its fate
+    moved_into: Option<usize>,
+    fate: Fate,
+    /// Steps from creation to the current fate, reported as the escape's `data_flow`
+    flow: Vec<String>,
+    /// Locals moved into the closures the threads or tasks run
+    captures: Vec<String>,
+    /// Locals passed by name to the spawn
+    passed: Vec<String>,
+}
+
+/// Which end of a crossbeam channel a local holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChannelEnd {
+    Sender,
+    Receiver,
+}
+
+/// The crate a thread pool comes from, which decides how its jobs are waited for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PoolKind {
+    /// `threadpool::ThreadPool`: jobs go in with `execute` and are waited for with `join`
+    Threadpool,
+    /// `rayon::ThreadPool`: `spawn` jobs are never waited for, `scope` and `install` ones are
+    Rayon,
+    /// `futures::executor::ThreadPool`: `spawn_ok` futures are never waited for
+    Futures,
+}
+
+/// A thread pool built in the function.
+struct Pool {
+    line: usize,
+    kind: PoolKind,
+    /// Lines of the jobs handed to it with `execute`
+    jobs: Vec<usize>,
+    /// Locals moved into those jobs
+    captures: Vec<String>,
+    joined: bool,
+    /// Moved, returned or stored, so something else decides when it is joined
+    moved: bool,
+}
+
+struct FunctionVisitor<'c> {
+    context: &'c FileContext<'c>,
+    function_name: &'c str,
+    /// Parameters and `let` bindings
+    locals: HashSet<String>,
+    local_lines: HashMap<String, usize>,
+    handles: Vec<Handle>,
+    /// Binding names, including aliases, to their handle
+    names: HashMap<String, usize>,
+    senders: HashSet<String>,
+    /// Ends of crossbeam channels created in the function, including clones
+    channel_ends: HashMap<String, ChannelEnd>,
+    guards: HashSet<String>,
+    /// Local closures and file functions that return a spawned handle
+    spawners: HashSet<String>,
+    /// Thread scopes and `JoinSet`s, which join or abort what is spawned on them
+    owners: HashSet<String>,
+    /// Thread pools built in the function
+    pools: HashMap<String, Pool>,
+    /// Binding of the `let` whose initializer is being visited
+    binding: Option<String>,
+    heap_lines: HashSet<usize>,
+    /// Heap allocations bound to locals, reported only if the local escapes some other way
+    heap: Vec<StaticEscape>,
+    /// `move` closures and `async move` blocks bound to a local, with the locals they capture
+    closures: HashMap<String, Vec<String>>,
+    /// Guards and thread-local borrows of a static, to the static:
+    /// `let mut registry = REGISTRY.lock()?`
+    global_aliases: HashMap<String, String>,
+    escapes: Vec<StaticEscape>,
 }
 
-fn extract_fn_name(line: &str) -> Option<String> {
-    let fn_idx = line.find("fn ")?;
-    let after_fn = &line[fn_idx + 3..];
-    let mut name = String::new();
-    for ch in after_fn.chars() {
-        if ch.is_alphanumeric() || ch == '_' {
-            name.push(ch);
+impl FunctionVisitor<'_> {
+    fn spawn_site(&self, expr: &Expr) -> Option<Spawn> {
+        let mut spawn = spawn_site(expr, &self.spawners, &self.owners)?;
+        // `pool.spawn(..)` on a rayon or futures pool built here keeps no handle.
+        if let Expr::MethodCall(call) = expr {
+            if let Some(name) = path_ident(&call.receiver).filter(|name| self.pools.contains_key(name)) {
+                spawn.kind = "Thread pool job".to_string();
+                spawn.callee = format!("{}.{}", name, call.method);
+                spawn.rule = Some(POOL_JOB_RULE);
+            }
+        }
+        Some(spawn)
+    }
+
+    fn handle_of(&self, name: &str) -> Option<usize> {
+        let mut index = *self.names.get(name)?;
+        while let Some(collection) = self.handles[index].moved_into {
+            index = collection;
+        }
+        Some(index)
+    }
+
+    fn expr_handle(&self, expr: &Expr) -> Option<usize> {
+        path_ident(strip_refs(expr)).and_then(|name| self.handle_of(&name))
+    }
+
+    /// The static a path root names, directly or through a guard or borrow of it.
+    fn global_of(&self, root: &str) -> Option<String> {
+        if self.context.is_global(root) {
+            Some(root.to_string())
         } else {
-            break;
+            self.global_aliases.get(root).cloned()
         }
     }
-    if name.is_empty() { None } else { Some(name) }
-}
 
-fn extract_params(signature: &str) -> HashSet<String> {
-    let mut params = HashSet::new();
-    let start = match signature.find('(') {
-        Some(idx) => idx,
-        None => return params,
-    };
-    let end = match signature[start + 1..].find(')') {
-        Some(idx) => start + 1 + idx,
-        None => return params,
-    };
-    let args = &signature[start + 1..end];
-    for arg in args.split(',') {
-        let mut arg = arg.trim();
-        if arg.is_empty() {
-            continue;
+    fn global_root(&self, expr: &Expr) -> Option<String> {
+        root_ident(expr).and_then(|root| self.global_of(&root))
+    }
+
+    /// The first local an expression uses outside `move` closures: what a store of it
+    /// keeps.
+    fn stored_local(&self, expr: &Expr) -> Option<String> {
+        Captures::of(expr).named.into_iter().find(|name| self.locals.contains(name))
+    }
+
+    /// Locals captured by the `move` closures and `async move` blocks in `expr`,
+    /// including those bound to a local earlier and passed by name.
+    fn captured_locals(&self, expr: &Expr) -> Vec<String> {
+        let captures = Captures::of(expr);
+        let named = captures
+            .named
+            .iter()
+            .filter_map(|name| self.closures.get(name))
+            .flatten()
+            .cloned();
+        let mut locals: Vec<String> = vec![];
+        for name in captures.captured.into_iter().chain(named) {
+            if self.locals.contains(&name) && !locals.contains(&name) {
+                locals.push(name);
+            }
         }
-        if arg.starts_with("self") || arg.starts_with("&self") || arg.starts_with("&mut self") {
-            params.insert("self".to_string());
-            continue;
+        locals
+    }
+
+    /// Locals passed by name (or by reference) among `args`. Closures and handles are
+    /// followed as such instead.
+    fn passed_locals<'e>(&self, args: impl IntoIterator<Item = &'e Expr>) -> Vec<String> {
+        let mut locals: Vec<String> = vec![];
+        for name in args.into_iter().filter_map(|arg| path_ident(strip_refs(arg))) {
+            let followed = self.closures.contains_key(&name) || self.handle_of(&name).is_some();
+            if self.locals.contains(&name) && !followed && !locals.contains(&name) {
+                locals.push(name);
+            }
         }
-        if let Some(idx) = arg.find(':') {
-            arg = &arg[..idx];
+        locals
+    }
+
+    /// One parameter escape per local in `passed`, handed to something that keeps it
+    /// beyond the call as `how` says.
+    fn report_passed(&mut self, passed: &[String], line: usize, column: usize, how: &str, flow: &[String]) {
+        for name in passed {
+            let mut escape = self.context.escape(&rules::RS_RETAINED_ARGUMENT, self.function_name, line, column);
+            escape.escape_type = EscapeType::ParameterEscape;
+            escape.reason = format!("Local '{}' is {}", name, how);
+            escape.variable_name = name.clone();
+            escape.data_flow = flow.to_vec();
+            self.escapes.push(escape);
         }
-        let arg = arg.trim().trim_start_matches('&').trim();
-        let arg = arg.strip_prefix("mut ").unwrap_or(arg).trim();
-        if let Some(name) = sanitize_ident(arg) {
-            params.insert(name);
+    }
+
+    /// A call of a retaining API with locals among its arguments. Spawns are followed
+    /// through their handles instead.
+    fn detect_retaining_call(&mut self, expr: &Expr) {
+        let segments = match expr {
+            Expr::Call(call) => call_path(call),
+            Expr::MethodCall(call) => {
+                let mut chain = receiver_chain(&call.receiver);
+                chain.push(call.method.to_string());
+                chain
+            }
+            _ => return,
+        };
+        let Some(api) = self.context.retaining.matching(&segments) else {
+            return;
+        };
+        if self.spawn_site(expr).is_some() {
+            return;
         }
+        let passed = self.passed_locals(call_args(expr));
+        let start = expr.span().start();
+        let how = format!("passed to `{}`, which keeps it beyond the call", api);
+        self.report_passed(&passed, start.line, start.column, &how, &[]);
     }
-    params
-}
 
-fn extract_let_binding(line: &str) -> Option<String> {
-    let let_idx = line.find("let ")?;
-    let mut remainder = &line[let_idx + 4..];
-    remainder = remainder.trim_start();
-    if remainder.starts_with('(') {
-        return None;
+    /// One closure escape per local in `captures`, whose closure outlives the call as
+    /// `outlives` says.
+    fn report_captures(&mut self, captures: &[String], line: usize, column: usize, outlives: &str, flow: &[String]) {
+        for name in captures {
+            // A channel end taken by a thread or task makes it a producer or consumer.
+            let end = self.channel_ends.get(name).filter(|_| outlives.starts_with("run by "));
+            let rule = if end.is_some() { CHANNEL_WORKER_RULE } else { &rules::RS_DETACHED_CAPTURE };
+            let mut escape = self.context.escape(rule, self.function_name, line, column);
+            escape.escape_type = EscapeType::ClosureEscape;
+            escape.reason = format!("Local '{}' is captured by a move closure {}", name, outlives);
+            if let Some(end) = end {
+                let (role, keeps) = match end {
+                    ChannelEnd::Sender => ("sender", "keeps sending after the call"),
+                    ChannelEnd::Receiver => ("receiver", "keeps receiving until every sender is dropped"),
+                };
+                escape.reason = format!(
+                    "Crossbeam channel {} '{}' is captured by a move closure {}: it is not spawned in a scope, so it {}",
+                    role, name, outlives, keeps
+                );
+                escape.rule = Some(CHANNEL_WORKER_RULE.name.to_string());
+            }
+            escape.variable_name = name.clone();
+            escape.data_flow = flow.to_vec();
+            self.escapes.push(escape);
+        }
     }
-    if let Some(rest) = remainder.strip_prefix("mut ") {
-        remainder = rest.trim_start();
+
+    fn add_handle(&mut self, name: &str, line: usize, collection: bool) -> usize {
+        self.handles.push(Handle {
+            name: name.to_string(),
+            line,
+            collection,
+            cancels_on_drop: false,
+            moved_into: None,
+            fate: Fate::Pending,
+            flow: vec![format!("`{}` created at line {}", name, line)],
+            captures: vec![],
+            passed: vec![],
+        });
+        let index = self.handles.len() - 1;
+        self.names.insert(name.to_string(), index);
+        index
+    }
+
+    fn settle(&mut self, index: usize) {
+        self.handles[index].fate = Fate::Settled;
+    }
+
+    fn report_detached(&mut self, expr: &Expr, spawn: &Spawn, how: &str) {
+        let start = expr.span().start();
+        let reason = format!("{} is detached: {}", spawn.kind, how);
+        let rule = spawn.rule.unwrap_or(&rules::RS_DROPPED_HANDLE);
+        let mut escape = self.context.escape(rule, self.function_name, start.line, start.column);
+        escape.escape_type = spawn_escape_type(spawn, Some(&self.context.line(start.line)), &reason);
+        escape.variable_name = spawn.callee.clone();
+        escape.reason = reason;
+        escape.rule = spawn.rule.map(|rule| rule.name.to_string());
+        self.escapes.push(escape);
+
+        let captures = self.captured_locals(expr);
+        let outlives = format!("run by a detached {}", spawn.kind.to_lowercase());
+        self.report_captures(&captures, start.line, start.column, &outlives, &[]);
+        let passed = self.passed_locals(call_args(expr));
+        let how = format!("handed to a detached {}", spawn.kind.to_lowercase());
+        self.report_passed(&passed, start.line, start.column, &how, &[]);
     }
-    sanitize_ident(remainder)
-}
 
-fn sanitize_ident(value: &str) -> Option<String> {
-    let mut ident = String::new();
-    for ch in value.chars() {
-        if ch.is_alphanumeric() || ch == '_' {
-            ident.push(ch);
+    fn report_heap(&mut self, call: &ExprCall) {
+        let start = call.span().start();
+        if !self.heap_lines.insert(start.line) {
+            return;
+        }
+        let mut escape = self.context.escape(&rules::RS_HEAP_ALLOCATION, self.function_name, start.line, start.column);
+        escape.escape_type = EscapeType::HeapEscape;
+        escape.variable_name = self.binding.clone().unwrap_or_else(|| "<unknown>".to_string());
+        escape.reason = "Heap-allocated structure assigned to local variable".to_string();
+        escape.confidence = ConfidenceLevel::Medium;
+        self.heap.push(escape);
+    }
+
+    /// `mem::forget(x)`, `Box::leak(Box::new(x))` or `ManuallyDrop::new(x)` on a thread
+    /// handle, channel sender or lock guard: the value's destructor never runs, so the
+    /// join, channel close or unlock it stands for never happens.
+    fn detect_leak_idiom(&mut self, call: &ExprCall, path: &[String]) {
+        let (pattern, action) = if ends_with(path, &["mem", "forget"]) {
+            ("mem::forget", "passed to mem::forget")
+        } else if ends_with(path, &["Box", "leak"]) {
+            ("Box::leak", "leaked with Box::leak")
+        } else if ends_with(path, &["ManuallyDrop", "new"]) {
+            ("ManuallyDrop::new", "wrapped in ManuallyDrop")
         } else {
-            break;
+            return;
+        };
+        let Some(mut argument) = call.args.first().map(strip_refs) else {
+            return;
+        };
+        if let Expr::Call(inner) = argument {
+            if ends_with(&call_path(inner), &["Box", "new"]) {
+                argument = inner.args.first().map(strip_refs).unwrap_or(argument);
+            }
         }
+        let name = path_ident(argument);
+        let is = |set: &HashSet<String>| name.as_ref().is_some_and(|name| set.contains(name));
+
+        let (kind, consequence) = if name.as_ref().is_some_and(|name| self.handle_of(name).is_some())
+            || self.spawn_site(strip(argument)).is_some()
+        {
+            ("Thread/task handle", "the thread is detached and can never be joined")
+        } else if is(&self.senders) {
+            ("Channel sender", "the channel never closes, so receivers waiting on it block forever")
+        } else if is(&self.guards) || creates_guard(argument) {
+            ("Lock guard", "the lock is never released, so later lockers deadlock")
+        } else {
+            return;
+        };
+
+        let variable_name = name.unwrap_or_else(|| "handle".to_string());
+        let start = call.span().start();
+        let mut escape = self.context.escape(LEAK_RULE, self.function_name, start.line, start.column);
+        escape.escape_type = if pattern == "Box::leak" {
+            EscapeType::GlobalEscape
+        } else {
+            EscapeType::HeapEscape
+        };
+        escape.reason = format!("{} '{}' {}: {}", kind, variable_name, action, consequence);
+        escape.variable_name = variable_name;
+        escape.rule = Some(LEAK_RULE.name.to_string());
+        self.escapes.push(escape);
     }
-    if ident.is_empty() { None } else { Some(ident) }
-}
 
-fn detect_return_escape(
-    line: &str,
-    source_file: &str,
-    line_number: usize,
-    function_name: &str,
-    locals: &HashSet<String>,
-) -> Option<StaticEscape> {
-    let return_idx = line.find("return ")?;
-    let remainder = line[return_idx + 7..].trim();
-    let name = sanitize_ident(remainder)?;
-    if !locals.contains(&name) {
-        return None;
+    /// `GLOBAL.lock().unwrap().push(value)`, `CELL.set(value)` and other stores into a
+    /// static, directly or through a guard or thread-local borrow of one. Closures
+    /// stored this way report the locals they capture.
+    fn detect_global_store(&mut self, call: &ExprMethodCall) {
+        let method = call.method.to_string();
+        let sets = SET_METHODS.contains(&method.as_str());
+        if !sets && !STORE_METHODS.contains(&method.as_str()) {
+            return;
+        }
+        let Some(global) = self.global_root(&call.receiver) else {
+            return;
+        };
+        let start = call.receiver.span().start();
+        if let Some(pool) = call.args.iter().find_map(|arg| self.stored_pool(arg)) {
+            let mut escape = self.context.escape(LONG_LIVED_POOL_RULE, self.function_name, start.line, start.column);
+            escape.escape_type = EscapeType::GlobalEscape;
+            escape.reason = match &pool {
+                Some(name) => format!("Thread pool '{}' is stored in global '{}', so its worker threads outlive the call", name, global),
+                None => format!("A thread pool built here is stored in global '{}', so its worker threads outlive the call", global),
+            };
+            escape.variable_name = pool.unwrap_or(global);
+            escape.rule = Some(LONG_LIVED_POOL_RULE.name.to_string());
+            self.escapes.push(escape);
+            return;
+        }
+        let captures: Vec<String> = call.args.iter().flat_map(|arg| self.captured_locals(arg)).collect();
+        if !captures.is_empty() {
+            let outlives = format!("stored in global '{}'", global);
+            self.report_captures(&captures, start.line, start.column, &outlives, &[]);
+            return;
+        }
+        let stored = call.args.last().and_then(|arg| self.stored_local(arg));
+        // Setting a cell from a constant or a fresh value stores nothing of the call's.
+        if sets && stored.is_none() {
+            return;
+        }
+        let variable_name = stored
+            .or_else(|| call.args.last().and_then(root_ident))
+            .unwrap_or_else(|| "<unknown>".to_string());
+        let mut escape = self.context.escape(&rules::RS_GLOBAL_STORE, self.function_name, start.line, start.column);
+        escape.escape_type = EscapeType::GlobalEscape;
+        escape.reason = format!("Value '{}' is persisted into retained global state", variable_name);
+        escape.variable_name = variable_name;
+        self.escapes.push(escape);
     }
-    Some(StaticEscape {
-        escape_type: EscapeType::ReturnEscape,
-        location: SourceLocation {
-            file: source_file.to_string(),
-            line: line_number,
-            column: return_idx,
-            function: function_name.to_string(),
-            code_snippet: Some(line.trim().to_string()),
-        },
-        variable_name: name.clone(),
-        reason: format!("Variable '{}' returned from function", name),
-        confidence: ConfidenceLevel::High,
-        data_flow: vec![],
-    })
-}
 
-fn detect_heap_escape(
-    line: &str,
-    source_file: &str,
-    line_number: usize,
-    function_name: &str,
-) -> Option<StaticEscape> {
-    let heap_patterns = [
-        "Box::new",
-        "Vec::new",
-        "String::new",
-        "Arc::new",
-        "Rc::new",
-        "HashMap::new",
-        "HashSet::new",
-    ];
-    if !heap_patterns.iter().any(|p| line.contains(p)) {
-        return None;
+    /// A thread pool `arg` stores: `Some(name)` for a pool built earlier into a local
+    /// (now moved), `None` for one built in place, directly or by an initializer closure.
+    fn stored_pool(&mut self, arg: &Expr) -> Option<Option<String>> {
+        if let Some(name) = path_ident(strip(arg)).filter(|name| self.pools.contains_key(name)) {
+            if let Some(pool) = self.pools.get_mut(&name) {
+                pool.moved = true;
+            }
+            return Some(Some(name));
+        }
+        let built = match strip(arg) {
+            Expr::Closure(closure) => tail_expr(&closure.body).is_some_and(|tail| constructs_pool(tail).is_some()),
+            arg => constructs_pool(arg).is_some(),
+        };
+        built.then_some(None)
     }
-    let var = extract_let_binding(line).unwrap_or_else(|| "<unknown>".to_string());
-    let column = line.find(&var).unwrap_or(0);
-    Some(StaticEscape {
-        escape_type: EscapeType::HeapEscape,
-        location: SourceLocation {
-            file: source_file.to_string(),
-            line: line_number,
-            column,
-            function: function_name.to_string(),
-            code_snippet: Some(line.trim().to_string()),
-        },
-        variable_name: var,
-        reason: "Heap-allocated structure assigned to local variable".to_string(),
-        confidence: ConfidenceLevel::Medium,
-        data_flow: vec![],
-    })
-}
 
-fn detect_retained_global_escape(
-    line: &str,
-    source_file: &str,
-    line_number: usize,
-    function_name: &str,
-) -> Option<StaticEscape> {
-    if !line.contains("RETAINED_") || !line.contains(".push(") {
-        return None;
+    /// `pool.execute(..)` or `pool.join()` on a thread pool built in the function.
+    fn pool_call(&mut self, name: &str, call: &ExprMethodCall) {
+        let line = call.span().start().line;
+        let captures: Vec<String> = call.args.iter().flat_map(|arg| self.captured_locals(arg)).collect();
+        let Some(pool) = self.pools.get_mut(name) else {
+            return;
+        };
+        match call.method.to_string().as_str() {
+            "execute" => {
+                pool.jobs.push(line);
+                pool.captures.extend(captures);
+            }
+            "join" => pool.joined = true,
+            _ => {}
+        }
     }
 
-    let variable_name = extract_push_argument(line).unwrap_or_else(|| "<unknown>".to_string());
-    let column = line.find("RETAINED_").unwrap_or(0);
+    /// `ThreadPoolBuilder::new().build_global()`: rayon's global pool, whose workers
+    /// live as long as the process.
+    fn detect_global_pool(&mut self, call: &ExprMethodCall) {
+        if call.method != "build_global" {
+            return;
+        }
+        let start = call.method.span().start();
+        let mut escape = self.context.escape(LONG_LIVED_POOL_RULE, self.function_name, start.line, start.column);
+        escape.escape_type = EscapeType::GlobalEscape;
+        escape.reason = "Rayon's global thread pool is built here, so its worker threads outlive the call".to_string();
+        escape.variable_name = "build_global".to_string();
+        escape.rule = Some(LONG_LIVED_POOL_RULE.name.to_string());
+        self.escapes.push(escape);
+    }
 
-    Some(StaticEscape {
-        escape_type: EscapeType::GlobalEscape,
-        location: SourceLocation {
-            file: source_file.to_string(),
-            line: line_number,
-            column,
-            function: function_name.to_string(),
-            code_snippet: Some(line.trim().to_string()),
-        },
-        variable_name: variable_name.clone(),
-        reason: format!(
-            "Value '{}' is persisted into retained global state",
-            variable_name
-        ),
-        confidence: ConfidenceLevel::High,
-        data_flow: vec![],
-    })
-}
+    /// `handles.push(handle)` or `handles.push(thread::spawn(..))`: the collection now
+    /// holds the handle. Returns whether the call was one.
+    fn collect_handle(&mut self, call: &'_ ExprMethodCall) -> bool {
+        if !["push", "push_back"].contains(&call.method.to_string().as_str()) {
+            return false;
+        }
+        let (Some(target), Some(argument)) = (path_ident(strip_refs(&call.receiver)), call.args.last()) else {
+            return false;
+        };
+        let pushed = self.expr_handle(argument);
+        if pushed.is_none() && self.spawn_site(strip(argument)).is_none() {
+            return false;
+        }
+        let collection = match self.handle_of(&target) {
+            Some(index) if self.handles[index].collection => index,
+            Some(_) => return false,
+            None => {
+                let line = self
+                    .local_lines
+                    .get(&target)
+                    .copied()
+                    .unwrap_or_else(|| call.span().start().line);
+                self.add_handle(&target, line, true)
+            }
+        };
+        let line = call.span().start().line;
+        match pushed {
+            Some(index) if index != collection => {
+                self.handles[index].moved_into = Some(collection);
+                let mut steps = std::mem::take(&mut self.handles[index].flow);
+                steps.push(format!("`{}` pushed into `{}` (line {})", self.handles[index].name, target, line));
+                self.handles[collection].flow.extend(steps);
+                let captures = std::mem::take(&mut self.handles[index].captures);
+                self.handles[collection].captures.extend(captures);
+                let passed = std::mem::take(&mut self.handles[index].passed);
+                self.handles[collection].passed.extend(passed);
+            }
+            Some(_) => {}
+            None => {
+                let step = format!("handle spawned and pushed into `{}` (line {})", target, line);
+                self.handles[collection].flow.push(step);
+                let captures = self.captured_locals(argument);
+                self.handles[collection].captures.extend(captures);
+                let passed = self.passed_locals(call_args(strip(argument)));
+                self.handles[collection].passed.extend(passed);
+                self.visit_expr(argument);
+            }
+        }
+        true
+    }
 
-fn extract_push_argument(line: &str) -> Option<String> {
-    let push_idx = line.find(".push(")? + 6;
-    let rest = &line[push_idx..];
-    let end_idx = rest.find(')')?;
-    sanitize_ident(rest[..end_idx].trim())
-}
+    fn finish(mut self) -> Vec<StaticEscape> {
+        let handles = std::mem::take(&mut self.handles);
+        for handle in handles.iter().filter(|handle| handle.moved_into.is_none()) {
+            let (rule, reason) = match handle.fate {
+                Fate::Settled => continue,
+                // Dropping the handle cancels the task, whether at a `drop` or at the end of scope.
+                Fate::Pending | Fate::Dropped(_) if handle.cancels_on_drop => continue,
+                Fate::Pending if handle.collection => (
+                    &rules::RS_UNJOINED_HANDLE_COLLECTION,
+                    format!("Thread/task handles collected in '{}' are never joined", handle.name),
+                ),
+                Fate::Pending => (
+                    &rules::RS_UNJOINED_HANDLE,
+                    format!("Thread/task handle '{}' created but not joined", handle.name),
+                ),
+                Fate::Dropped(line) => (
+                    &rules::RS_DROPPED_HANDLE,
+                    format!(
+                        "Thread/task handle '{}' dropped at line {} without being joined, detaching it",
+                        handle.name, line
+                    ),
+                ),
+                Fate::Detached(line) => (
+                    &rules::RS_DROPPED_HANDLE,
+                    format!("Thread/task handle '{}' detached at line {} without being awaited", handle.name, line),
+                ),
+            };
+            let mut escape = self.context.escape(rule, self.function_name, handle.line, 0);
+            escape.escape_type = classify_rust_async_escape(None, &reason, &handle.name);
+            escape.variable_name = handle.name.clone();
+            escape.reason = reason;
+            escape.data_flow = handle.flow.clone();
+            // A local handed to the spawn by name is the work the handle runs, so this
+            // finding covers it rather than a retained-argument one of its own.
+            let mut passed = handle.passed.clone();
+            passed.sort();
+            passed.dedup();
+            escape.data_flow.extend(passed.iter().map(|name| format!("`{}` spawned as the thread/task's work", name)));
+            if handle.fate == Fate::Pending {
+                escape.data_flow.push(format!("never joined before `{}` returns", self.function_name));
+            }
+            let flow = escape.data_flow.clone();
+            self.escapes.push(escape);
 
-fn detect_concurrency(
-    line: &str,
-    source_file: &str,
-    line_number: usize,
-    function_name: &str,
-) -> Option<StaticEscape> {
-    let patterns = [
-        ("std::thread::spawn", "Thread spawn"),
-        ("thread::spawn", "Thread spawn"),
-        ("tokio::spawn", "Async task spawn"),
-        ("tokio::task::spawn", "Async task spawn"),
-        ("std::thread::Builder", "Thread builder"),
-    ];
-    for (pattern, reason) in patterns {
-        if line.contains(pattern) {
-            let column = line.find(pattern).unwrap_or(0);
-            let reason_text = format!("{} may leak work beyond scope", reason);
-            return Some(StaticEscape {
-                escape_type: classify_rust_async_escape(Some(line), &reason_text, pattern),
-                location: SourceLocation {
-                    file: source_file.to_string(),
-                    line: line_number,
-                    column,
-                    function: function_name.to_string(),
-                    code_snippet: Some(line.trim().to_string()),
-                },
-                variable_name: pattern.to_string(),
-                reason: reason_text,
-                confidence: ConfidenceLevel::High,
-                data_flow: vec![],
-            });
+            let holder = match handle.fate {
+                Fate::Pending if handle.collection => {
+                    format!("the threads/tasks collected in '{}', which are never joined", handle.name)
+                }
+                Fate::Dropped(line) => {
+                    format!("thread/task '{}', which is detached when dropped at line {}", handle.name, line)
+                }
+                Fate::Detached(line) => format!("thread/task '{}', which is detached at line {}", handle.name, line),
+                _ => format!("thread/task '{}', which is never joined", handle.name),
+            };
+            let mut captures = handle.captures.clone();
+            captures.sort();
+            captures.dedup();
+            self.report_captures(&captures, handle.line, 0, &format!("run by {}", holder), &flow);
+        }
+
+        let mut pools: Vec<(String, Pool)> = std::mem::take(&mut self.pools).into_iter().collect();
+        pools.sort_by_key(|(_, pool)| pool.line);
+        for (name, pool) in pools {
+            let waited = pool.kind != PoolKind::Threadpool || pool.joined || pool.moved;
+            let Some(&first) = pool.jobs.first().filter(|_| !waited) else {
+                continue;
+            };
+            let flow = vec![
+                format!("`{}` built at line {}", name, pool.line),
+                format!("jobs handed to it with `execute` (line {})", first),
+                format!("never joined before `{}` returns", self.function_name),
+            ];
+            let reason = format!(
+                "Jobs handed to thread pool '{}' with `execute` are never waited for: it is not joined before `{}` returns",
+                name, self.function_name
+            );
+            let mut escape = self.context.escape(POOL_JOB_RULE, self.function_name, first, 0);
+            escape.escape_type = classify_rust_async_escape(None, &reason, &name);
+            escape.variable_name = name.clone();
+            escape.reason = reason;
+            escape.data_flow = flow.clone();
+            escape.rule = Some(POOL_JOB_RULE.name.to_string());
+            self.escapes.push(escape);
+            let mut captures = pool.captures;
+            captures.sort();
+            captures.dedup();
+            let holder = format!("run by a job of thread pool '{}', which is never joined", name);
+            self.report_captures(&captures, first, 0, &holder, &flow);
         }
+
+        // A local `Vec` or `Box` dropped at the end of the call is not worth reporting.
+        let heap = std::mem::take(&mut self.heap);
+        let escaped: HashSet<&str> = self.escapes.iter().map(|escape| escape.variable_name.as_str()).collect();
+        let shared: Vec<StaticEscape> = heap
+            .into_iter()
+            .filter(|escape| escaped.contains(escape.variable_name.as_str()))
+            .collect();
+        self.escapes.extend(shared);
+        self.escapes
     }
-    None
 }
 
-fn count_braces(line: &str) -> i32 {
-    let mut count = 0i32;
-    for ch in line.chars() {
-        match ch {
-            '{' => count += 1,
-            '}' => count -= 1,
-            _ => {}
-        }
+impl<'ast> Visit<'ast> for FunctionVisitor<'_> {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        self.detect_retaining_call(expr);
+        self.owners.extend(scope_param(expr));
+        visit::visit_expr(self, expr);
     }
-    count
-}
-
-fn is_thread_creation(line: &str) -> bool {
-    let thread_patterns = [
-        "thread::spawn",
-        "std::thread::spawn",
-        "tokio::spawn",
-        "tokio::task::spawn",
-        "thread::Builder",
-    ];
-    thread_patterns.iter().any(|p| line.contains(p))
-}
-
-fn extract_join_call(line: &str) -> Option<String> {
-    // Look for patterns like: handle.join(), handle.await
-    if line.contains(".join()") || line.contains(".await") {
-        // Try to extract the variable name before the dot
-        if let Some(dot_idx) = line.find('.') {
-            let before_dot = &line[..dot_idx];
-            // Get the last identifier before the dot
-            let mut var_name = String::new();
-            for ch in before_dot.chars().rev() {
-                if ch.is_alphanumeric() || ch == '_' {
-                    var_name.insert(0, ch);
-                } else if !var_name.is_empty() {
-                    break;
+
+    fn visit_local(&mut self, local: &'ast Local) {
+        let line = local.let_token.span.start().line;
+        let mut bound = HashSet::new();
+        pattern_idents(&local.pat, &mut bound);
+        for name in bound {
+            self.local_lines.insert(name.clone(), line);
+            self.locals.insert(name);
+        }
+        let Some(init) = &local.init else {
+            return;
+        };
+        let binding = single_binding(&local.pat);
+        let expr = strip(&init.expr);
+
+        if let Some(spawn) = self.spawn_site(expr) {
+            match &binding {
+                _ if spawn.rule.is_some() => {
+                    self.report_detached(expr, &spawn, &format!("`{}` keeps no handle to it", spawn.callee))
+                }
+                Some(name) => {
+                    let index = self.add_handle(name, line, false);
+                    self.handles[index].cancels_on_drop = spawn.cancels_on_drop;
+                    self.handles[index].captures = self.captured_locals(expr);
+                    self.handles[index].passed = self.passed_locals(call_args(expr));
                 }
+                None if spawn.cancels_on_drop => {}
+                None if matches!(peel_type(&local.pat), Pat::Wild(_)) => {
+                    self.report_detached(expr, &spawn, "bound to `_`, which drops the handle immediately")
+                }
+                None => {}
+            }
+        } else if let Some(index) = self.expr_handle(expr) {
+            // `let worker = handle;` moves the handle; either name can settle it.
+            if let Some(name) = binding {
+                let step = format!("moved to `{}` (line {})", name, line);
+                self.handles[index].flow.push(step);
+                self.names.insert(name, index);
             }
-            if !var_name.is_empty() {
-                return Some(var_name);
+            return;
+        } else if let Some(name) = &binding {
+            if collects_spawns(expr, &self.spawners, &self.owners) {
+                let index = self.add_handle(name, line, true);
+                self.handles[index].captures = self.captured_locals(expr);
+            } else if creates_guard(expr) {
+                self.guards.insert(name.clone());
+                if let Some(global) = self.global_root(expr) {
+                    self.global_aliases.insert(name.clone(), global);
+                }
+            } else if constructs_owner(expr) {
+                self.owners.insert(name.clone());
+            } else if let Some(kind) = constructs_pool(expr) {
+                let pool = Pool { line, kind, jobs: vec![], captures: vec![], joined: false, moved: false };
+                self.pools.insert(name.clone(), pool);
+            } else if let Expr::Closure(closure) = &*init.expr {
+                if closure_spawns(closure, &self.spawners, &self.owners) {
+                    self.spawners.insert(name.clone());
+                }
+            } else if let Expr::MethodCall(call) = expr {
+                let clones_sender = call.method == "clone"
+                    && path_ident(&call.receiver).is_some_and(|sender| self.senders.contains(&sender));
+                if clones_sender {
+                    self.senders.insert(name.clone());
+                }
+                let cloned_end = (call.method == "clone")
+                    .then(|| path_ident(&call.receiver).and_then(|end| self.channel_ends.get(&end).copied()))
+                    .flatten();
+                if let Some(end) = cloned_end {
+                    self.channel_ends.insert(name.clone(), end);
+                }
             }
         }
+        if let Some(sender) = channel_sender(&local.pat, expr) {
+            self.senders.insert(sender);
+        }
+        self.channel_ends.extend(crossbeam_channel(&local.pat, expr));
+        let moves = match &*init.expr {
+            Expr::Closure(closure) => closure.capture.is_some(),
+            Expr::Async(block) => block.capture.is_some(),
+            _ => false,
+        };
+        if let (true, Some(name)) = (moves, &binding) {
+            self.closures.insert(name.clone(), self.captured_locals(&init.expr));
+        }
+
+        let outer = std::mem::replace(&mut self.binding, binding);
+        self.visit_expr(&init.expr);
+        self.binding = outer;
+        if let Some((_, diverge)) = &init.diverge {
+            self.visit_expr(diverge);
+        }
     }
-    None
-}
 
-fn find_variable_line(source: &str, function_name: &str, var_name: &str) -> Option<usize> {
-    let lines: Vec<&str> = source.lines().collect();
-    let mut in_target = false;
-    let mut brace_depth = 0i32;
-    
-    for (idx, line) in lines.iter().enumerate() {
-        if !in_target {
-            if let Some(name) = extract_fn_name(line) {
-                if name == function_name {
-                    in_target = true;
-                    brace_depth = count_braces(line);
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        if let Stmt::Expr(expr, Some(_)) = stmt {
+            let expr = strip(expr);
+            if let Some(spawn) = self.spawn_site(expr) {
+                if spawn.rule.is_some() {
+                    self.report_detached(expr, &spawn, &format!("`{}` keeps no handle to it", spawn.callee));
+                } else if !spawn.cancels_on_drop {
+                    self.report_detached(expr, &spawn, "its handle is discarded at the end of the statement");
                 }
+            } else if let Some(spawn) = detach_call(expr).and_then(|spawned| self.spawn_site(spawned)) {
+                self.report_detached(expr, &spawn, "`.detach()` lets it run past the call");
             }
-        } else {
-            if line.contains(&format!("let {}", var_name)) || line.contains(&format!("let mut {}", var_name)) {
-                return Some(idx + 1);
+        }
+        visit::visit_stmt(self, stmt);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        let path = call_path(call);
+        if matches!(path.last().map(String::as_str), Some("drop")) && call.args.len() == 1 {
+            let argument = strip(&call.args[0]);
+            if let Some(index) = self.expr_handle(argument) {
+                if self.handles[index].fate == Fate::Pending {
+                    let line = call.span().start().line;
+                    self.handles[index].fate = Fate::Dropped(line);
+                    let name = path_ident(strip_refs(argument)).unwrap_or_default();
+                    let step = format!("`{}` dropped (line {})", name, line);
+                    self.handles[index].flow.push(step);
+                }
+                return;
             }
-            brace_depth += count_braces(line);
-            if brace_depth <= 0 {
-                break;
+            if let Some(spawn) = self.spawn_site(argument).filter(|spawn| !spawn.cancels_on_drop) {
+                self.report_detached(argument, &spawn, "its handle is passed straight to drop");
             }
         }
+        self.detect_leak_idiom(call, &path);
+        if path.len() >= 2
+            && path[path.len() - 1] == "new"
+            && HEAP_TYPES.contains(&path[path.len() - 2].as_str())
+        {
+            self.report_heap(call);
+        }
+        visit::visit_expr_call(self, call);
     }
-    None
-}
 
-fn classify_rust_async_escape(line: Option<&str>, reason: &str, variable_name: &str) -> EscapeType {
-    let combined = format!("{} {} {}", reason, variable_name, line.unwrap_or_default()).to_lowercase();
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        // `LOCAL.with(|registry| ..)`: the closure parameter borrows the thread-local.
+        if let Some(global) = self.global_root(&call.receiver).filter(|_| call.method == "with") {
+            for argument in &call.args {
+                if let Expr::Closure(closure) = argument {
+                    let mut params = HashSet::new();
+                    for input in &closure.inputs {
+                        pattern_idents(input, &mut params);
+                    }
+                    self.global_aliases.extend(params.into_iter().map(|param| (param, global.clone())));
+                }
+            }
+        }
+        self.detect_global_store(call);
+        self.detect_global_pool(call);
+        if self.collect_handle(call) {
+            return;
+        }
+        // Calls on a pool borrow it; `execute` hands it a job and `join` waits for them.
+        if let Some(name) = path_ident(strip_refs(&call.receiver)).filter(|name| self.pools.contains_key(name)) {
+            self.pool_call(&name, call);
+            for argument in &call.args {
+                self.visit_expr(argument);
+            }
+            return;
+        }
 
-    if combined.contains("return") || combined.contains("returned") {
-        EscapeType::ReturnEscape
-    } else if combined.contains("global") || combined.contains("static ") {
-        EscapeType::GlobalEscape
-    } else if combined.contains("move ||")
-        || combined.contains("async move")
-        || combined.contains("closure")
-        || combined.contains(" capture")
-    {
-        EscapeType::ClosureEscape
-    } else if combined.contains("spawn(") || combined.contains("parameter") || combined.contains("argument") {
-        EscapeType::ParameterEscape
-    } else {
-        // Unjoined handles and runtime task state are treated as heap-backed object escapes.
-        EscapeType::HeapEscape
+        // Other calls on a handle borrow it unless they join, cancel or detach it.
+        if let Some(index) = self.expr_handle(&call.receiver) {
+            if JOIN_METHODS.contains(&call.method.to_string().as_str()) {
+                self.settle(index);
+            } else if call.method == "detach" && self.handles[index].fate == Fate::Pending {
+                let line = call.span().start().line;
+                self.handles[index].fate = Fate::Detached(line);
+                let name = path_ident(strip_refs(&call.receiver)).unwrap_or_default();
+                self.handles[index].flow.push(format!("`{}` detached (line {})", name, line));
+            }
+            for argument in &call.args {
+                self.visit_expr(argument);
+            }
+            return;
+        }
+        // `handles.into_iter().map(|handle| handle.join())` and similar chains.
+        if let Some(index) = root_ident(&call.receiver).and_then(|root| self.handle_of(&root)) {
+            if self.handles[index].collection && contains_join(|finder| finder.visit_expr_method_call(call)) {
+                self.settle(index);
+            }
+        }
+        visit::visit_expr_method_call(self, call);
     }
+
+    fn visit_expr_await(&mut self, expr: &'ast syn::ExprAwait) {
+        match self.expr_handle(&expr.base) {
+            Some(index) => self.settle(index),
+            None => visit::visit_expr_await(self, expr),
+        }
+    }
+
+    fn visit_expr_reference(&mut self, expr: &'ast syn::ExprReference) {
+        // Borrowing a handle or pool does not move it.
+        let pool = path_ident(strip_refs(&expr.expr)).is_some_and(|name| self.pools.contains_key(&name));
+        if self.expr_handle(&expr.expr).is_none() && !pool {
+            visit::visit_expr_reference(self, expr);
+        }
+    }
+
+    fn visit_expr_path(&mut self, expr: &'ast syn::ExprPath) {
+        // Any other use of a handle by value hands it to a callee, container or caller.
+        let name = expr.path.get_ident().filter(|_| expr.qself.is_none()).map(ToString::to_string);
+        if let Some(pool) = name.as_ref().and_then(|name| self.pools.get_mut(name)) {
+            pool.moved = true;
+        }
+        if let Some(index) = name.and_then(|name| self.handle_of(&name)) {
+            self.settle(index);
+        }
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast syn::ExprForLoop) {
+        let Some(index) = root_ident(&expr.expr).and_then(|root| self.handle_of(&root)) else {
+            visit::visit_expr_for_loop(self, expr);
+            return;
+        };
+        if contains_join(|finder| finder.visit_block(&expr.body)) {
+            self.settle(index);
+        }
+        self.visit_block(&expr.body);
+    }
+
+    /// `unsafe { GLOBAL = value }` on a `static mut`, or `*guard = value` on a guard of
+    /// a static.
+    fn visit_expr_assign(&mut self, expr: &'ast ExprAssign) {
+        if let Some(global) = self.global_root(&expr.left) {
+            let start = expr.span().start();
+            let captures = self.captured_locals(&expr.right);
+            if captures.is_empty() {
+                let value = self.stored_local(&expr.right);
+                let mut escape = self.context.escape(&rules::RS_STATIC_MUT_WRITE, self.function_name, start.line, start.column);
+                escape.escape_type = EscapeType::GlobalEscape;
+                match value {
+                    Some(value) => {
+                        escape.reason = format!("Value '{}' is written to global '{}'", value, global);
+                        escape.variable_name = value;
+                    }
+                    None => {
+                        escape.reason = format!("Global '{}' is overwritten", global);
+                        escape.variable_name = global;
+                        escape.confidence = ConfidenceLevel::Medium;
+                    }
+                }
+                self.escapes.push(escape);
+            } else {
+                let outlives = format!("stored in global '{}'", global);
+                self.report_captures(&captures, start.line, start.column, &outlives, &[]);
+            }
+        }
+        visit::visit_expr_assign(self, expr);
+    }
+
+    fn visit_expr_return(&mut self, expr: &'ast syn::ExprReturn) {
+        if let Some(name) = expr.expr.as_deref().and_then(path_ident) {
+            if self.locals.contains(&name) {
+                let start = expr.span().start();
+                let mut escape = self.context.escape(&rules::RS_RETURNED_VALUE, self.function_name, start.line, start.column);
+                escape.escape_type = EscapeType::ReturnEscape;
+                escape.reason = format!("Variable '{}' returned from function", name);
+                escape.variable_name = name;
+                self.escapes.push(escape);
+            }
+        }
+        visit::visit_expr_return(self, expr);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        visit_macro_body(self, mac);
+    }
+}
+
+/// A call that starts a thread or task.
+struct Spawn {
+    /// `Thread spawn`, `Async task spawn`, ...
+    kind: String,
+    /// The function, method or spawner called
+    callee: String,
+    /// Whether it is handed a `move` closure or `async move` block
+    moves: bool,
+    /// `smol::spawn(..)`: the task is cancelled when its handle is dropped, and only
+    /// outlives it once detached
+    cancels_on_drop: bool,
+    /// Dedicated rule of a spawn that returns no handle, such as a thread pool job
+    rule: Option<&'static Rule>,
+}
+
+/// `thread::spawn(..)`, `tokio::spawn(..)`, `async_std::task::spawn(..)`,
+/// `smol::spawn(..)`, `Builder::new().spawn(..)`, `rt.spawn(..)`, `pool.spawn_ok(..)`,
+/// or a call to a spawner, unless it spawns on a thread scope, `JoinSet` or executor
+/// in `owners`.
+fn spawn_site(expr: &Expr, spawners: &HashSet<String>, owners: &HashSet<String>) -> Option<Spawn> {
+    let moves = |args: &Punctuated<Expr, Token![,]>| {
+        args.iter().any(|arg| match arg {
+            Expr::Closure(closure) => closure.capture.is_some(),
+            Expr::Async(block) => block.capture.is_some(),
+            _ => false,
+        })
+    };
+    match expr {
+        Expr::Call(call) => {
+            let path = call_path(call);
+            let last = path.last()?;
+            if SPAWN_CALLS.contains(&last.as_str()) {
+                let rayon = path.first().is_some_and(|root| root == "rayon");
+                let kind = if rayon {
+                    "Thread pool job"
+                } else if path.iter().any(|segment| segment == "thread") {
+                    "Thread spawn"
+                } else if last == "spawn_blocking" {
+                    "Blocking task spawn"
+                } else {
+                    "Async task spawn"
+                };
+                Some(Spawn {
+                    kind: kind.to_string(),
+                    callee: path.join("::"),
+                    moves: moves(&call.args),
+                    cancels_on_drop: path.first().is_some_and(|root| root == "smol"),
+                    rule: rayon.then_some(POOL_JOB_RULE),
+                })
+            } else if path.len() == 1 && spawners.contains(last) {
+                Some(Spawn {
+                    kind: format!("Spawn through `{}`", last),
+                    callee: last.clone(),
+                    moves: false,
+                    cancels_on_drop: false,
+                    rule: None,
+                })
+            } else {
+                None
+            }
+        }
+        Expr::MethodCall(call) => {
+            let method = call.method.to_string();
+            if SPAWN_CALLS.contains(&method.as_str()) && !call.args.is_empty() {
+                if root_ident(&call.receiver).is_some_and(|root| owners.contains(&root)) {
+                    return None;
+                }
+                let pooled = method == "spawn_ok";
+                let kind = if pooled {
+                    "Thread pool job"
+                } else if chain_calls(&call.receiver, "Builder") {
+                    if chain_calls(&call.receiver, "task") {
+                        "Task builder"
+                    } else {
+                        "Thread builder"
+                    }
+                } else {
+                    "Task spawn"
+                };
+                Some(Spawn {
+                    kind: kind.to_string(),
+                    callee: method,
+                    moves: moves(&call.args),
+                    cancels_on_drop: false,
+                    rule: pooled.then_some(POOL_JOB_RULE),
+                })
+            } else if spawners.contains(&method) && path_ident(&call.receiver).as_deref() == Some("self") {
+                Some(Spawn {
+                    kind: format!("Spawn through `{}`", method),
+                    callee: method,
+                    moves: false,
+                    cancels_on_drop: false,
+                    rule: None,
+                })
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The spawn a `.detach()` call lets go of: `smol::spawn(..).detach()`.
+fn detach_call(expr: &Expr) -> Option<&Expr> {
+    match expr {
+        Expr::MethodCall(call) if call.method == "detach" && call.args.is_empty() => Some(strip(&call.receiver)),
+        _ => None,
+    }
+}
+
+fn spawn_escape_type(spawn: &Spawn, code: Option<&str>, reason: &str) -> EscapeType {
+    if spawn.moves {
+        EscapeType::ClosureEscape
+    } else {
+        classify_rust_async_escape(code, reason, &spawn.callee)
+    }
+}
+
+/// Names used inside the `move` closures and `async move` blocks of an expression
+/// and not bound there, which the closures capture, and the bare names used outside
+/// them.
+#[derive(Default)]
+struct Captures {
+    captured: Vec<String>,
+    named: Vec<String>,
+    /// Number of enclosing `move` closures
+    depth: usize,
+    bound: HashSet<String>,
+}
+
+impl Captures {
+    fn of(expr: &Expr) -> Self {
+        let mut captures = Self::default();
+        captures.visit_expr(expr);
+        let bound = std::mem::take(&mut captures.bound);
+        captures.captured.retain(|name| !bound.contains(name));
+        captures
+    }
+
+    fn enter(&mut self, moves: bool, body: impl FnOnce(&mut Self)) {
+        self.depth += usize::from(moves);
+        body(self);
+        self.depth -= usize::from(moves);
+    }
+}
+
+impl<'ast> Visit<'ast> for Captures {
+    fn visit_expr_closure(&mut self, closure: &'ast ExprClosure) {
+        self.enter(closure.capture.is_some(), |this| visit::visit_expr_closure(this, closure));
+    }
+
+    fn visit_expr_async(&mut self, block: &'ast syn::ExprAsync) {
+        self.enter(block.capture.is_some(), |this| visit::visit_expr_async(this, block));
+    }
+
+    fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+        if self.depth > 0 {
+            self.bound.insert(pat.ident.to_string());
+        }
+        visit::visit_pat_ident(self, pat);
+    }
+
+    fn visit_expr_path(&mut self, expr: &'ast syn::ExprPath) {
+        let Some(name) = expr.path.get_ident().filter(|_| expr.qself.is_none()).map(ToString::to_string) else {
+            return;
+        };
+        let names = if self.depth > 0 { &mut self.captured } else { &mut self.named };
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        visit_macro_body(self, mac);
+    }
+}
+
+/// A closure whose result is a spawned handle: `|x| thread::spawn(move || ..)`.
+fn closure_spawns(closure: &ExprClosure, spawners: &HashSet<String>, owners: &HashSet<String>) -> bool {
+    if let ReturnType::Type(_, ty) = &closure.output {
+        if type_mentions(ty, "JoinHandle") {
+            return true;
+        }
+    }
+    tail_expr(&closure.body).is_some_and(|tail| spawn_site(strip(tail), spawners, owners).is_some())
+}
+
+/// `(0..n).map(|i| thread::spawn(..)).collect()`: a collection of handles.
+fn collects_spawns(expr: &Expr, spawners: &HashSet<String>, owners: &HashSet<String>) -> bool {
+    let Expr::MethodCall(call) = expr else {
+        return false;
+    };
+    if call.method != "collect" {
+        return false;
+    }
+    let mut link = &*call.receiver;
+    while let Expr::MethodCall(inner) = link {
+        let maps_to_spawn = inner.args.iter().any(|arg| match arg {
+            Expr::Closure(closure) => closure_spawns(closure, spawners, owners),
+            Expr::Path(_) => path_ident(arg).is_some_and(|name| spawners.contains(&name)),
+            _ => false,
+        });
+        if maps_to_spawn {
+            return true;
+        }
+        link = &inner.receiver;
+    }
+    false
+}
+
+/// The value expression of a closure body or block.
+fn tail_expr(expr: &Expr) -> Option<&Expr> {
+    match expr {
+        Expr::Block(block) => match block.block.stmts.last()? {
+            Stmt::Expr(tail, None) => tail_expr(tail),
+            _ => None,
+        },
+        Expr::Paren(paren) => tail_expr(&paren.expr),
+        expr => Some(expr),
+    }
+}
+
+/// `thread::scope(|s| ..)`, `pool.scope(|s| ..)` and similar: the scope parameter,
+/// whose spawns are joined before the call returns.
+fn scope_param(expr: &Expr) -> Option<String> {
+    let (name, args) = match expr {
+        Expr::Call(call) => (call_path(call).last()?.clone(), &call.args),
+        Expr::MethodCall(call) => (call.method.to_string(), &call.args),
+        _ => return None,
+    };
+    if !SCOPE_CALLS.contains(&name.as_str()) {
+        return None;
+    }
+    args.iter().find_map(|arg| match arg {
+        Expr::Closure(closure) => closure.inputs.first().and_then(|input| {
+            let mut names = HashSet::new();
+            pattern_idents(input, &mut names);
+            names.into_iter().next()
+        }),
+        _ => None,
+    })
+}
+
+/// The kind of thread pool `expr` builds: `ThreadPool::new(4)`, `ThreadPool::new()`
+/// (`futures`, which takes no size), `ThreadPoolBuilder::new().build()` (rayon),
+/// `threadpool::Builder::new().build()` or `ThreadPool::builder().create()` (`futures`).
+fn constructs_pool(expr: &Expr) -> Option<PoolKind> {
+    match strip(expr) {
+        Expr::Call(call) => {
+            let path = call_path(call);
+            let constructor = ["new", "default", "with_name"].iter().any(|last| ends_with(&path, &["ThreadPool", last]));
+            constructor.then(|| {
+                if ends_with(&path, &["ThreadPool", "new"]) && call.args.is_empty() {
+                    PoolKind::Futures
+                } else {
+                    PoolKind::Threadpool
+                }
+            })
+        }
+        Expr::MethodCall(call) if call.method == "build" => {
+            if chain_calls(&call.receiver, "ThreadPoolBuilder") {
+                Some(PoolKind::Rayon)
+            } else {
+                chain_calls(&call.receiver, "threadpool").then_some(PoolKind::Threadpool)
+            }
+        }
+        Expr::MethodCall(call) if call.method == "create" && chain_calls(&call.receiver, "ThreadPool") => {
+            Some(PoolKind::Futures)
+        }
+        _ => None,
+    }
+}
+
+fn constructs_owner(expr: &Expr) -> bool {
+    match strip(expr) {
+        Expr::Call(call) => call_path(call).iter().any(|segment| SPAWN_OWNERS.contains(&segment.as_str())),
+        _ => false,
+    }
+}
+
+/// `m.lock().unwrap()`, `cell.borrow_mut()` and other guard-returning chains.
+fn creates_guard(expr: &Expr) -> bool {
+    let mut link = strip(expr);
+    loop {
+        match link {
+            Expr::MethodCall(call) => {
+                if call.args.is_empty() && GUARD_METHODS.contains(&call.method.to_string().as_str()) {
+                    return true;
+                }
+                link = &call.receiver;
+            }
+            Expr::Try(inner) => link = &inner.expr,
+            _ => return false,
+        }
+    }
+}
+
+/// Sender half of `let (tx, rx) = mpsc::channel();` and similar constructors.
+fn channel_sender(pat: &Pat, init: &Expr) -> Option<String> {
+    let Expr::Call(call) = init else {
+        return None;
+    };
+    if !call_path(call).last().is_some_and(|last| CHANNEL_CONSTRUCTORS.contains(&last.as_str())) {
+        return None;
+    }
+    match peel_type(pat) {
+        Pat::Tuple(tuple) => single_binding(tuple.elems.first()?),
+        _ => None,
+    }
+}
+
+/// Both ends of `let (tx, rx) = crossbeam::channel::unbounded();`, or of `bounded(n)`
+/// imported from `crossbeam` or `crossbeam_channel`; std and tokio have neither.
+fn crossbeam_channel(pat: &Pat, init: &Expr) -> Vec<(String, ChannelEnd)> {
+    let Expr::Call(call) = init else {
+        return vec![];
+    };
+    let path = call_path(call);
+    let crossbeam = path.iter().any(|segment| segment.starts_with("crossbeam"))
+        || path.len() == 1
+        || path.first().is_some_and(|root| root == "channel");
+    let constructor = path.last().is_some_and(|last| last == "unbounded" || last == "bounded");
+    let Pat::Tuple(tuple) = peel_type(pat) else {
+        return vec![];
+    };
+    if !crossbeam || !constructor {
+        return vec![];
+    }
+    let ends = tuple.elems.iter().map(single_binding);
+    ends.zip([ChannelEnd::Sender, ChannelEnd::Receiver])
+        .filter_map(|(name, end)| Some((name?, end)))
+        .collect()
+}
+
+/// Whether a method chain includes a call to a path containing `segment`, as in
+/// `thread::Builder::new().name(..)`.
+fn chain_calls(expr: &Expr, segment: &str) -> bool {
+    let mut link = expr;
+    loop {
+        match link {
+            Expr::MethodCall(call) => link = &call.receiver,
+            Expr::Try(inner) => link = &inner.expr,
+            Expr::Paren(inner) => link = &inner.expr,
+            Expr::Call(call) => return call_path(call).iter().any(|part| part == segment),
+            _ => return false,
+        }
+    }
+}
+
+/// Arguments of a function or method call.
+fn call_args(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::Call(call) => call.args.iter().collect(),
+        Expr::MethodCall(call) => call.args.iter().collect(),
+        _ => vec![],
+    }
+}
+
+/// Names along a receiver chain from its root: `["bus", "lock", "unwrap"]` for
+/// `bus.lock().unwrap()`.
+fn receiver_chain(expr: &Expr) -> Vec<String> {
+    match expr {
+        Expr::MethodCall(call) => {
+            let mut chain = receiver_chain(&call.receiver);
+            chain.push(call.method.to_string());
+            chain
+        }
+        Expr::Field(field) => {
+            let mut chain = receiver_chain(&field.base);
+            if let syn::Member::Named(name) = &field.member {
+                chain.push(name.to_string());
+            }
+            chain
+        }
+        Expr::Call(call) => call_path(call),
+        Expr::Path(path) => path.path.segments.iter().map(|segment| segment.ident.to_string()).collect(),
+        Expr::Reference(inner) => receiver_chain(&inner.expr),
+        Expr::Paren(inner) => receiver_chain(&inner.expr),
+        Expr::Try(inner) => receiver_chain(&inner.expr),
+        Expr::Await(inner) => receiver_chain(&inner.base),
+        Expr::Unary(inner) => receiver_chain(&inner.expr),
+        _ => vec![],
+    }
+}
+
+/// Peels `.unwrap()`, `.expect(..)`, `?` and parentheses off a spawn or guard chain.
+fn strip(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::MethodCall(call) if call.method == "unwrap" || call.method == "expect" => strip(&call.receiver),
+        Expr::Try(inner) => strip(&inner.expr),
+        Expr::Paren(inner) => strip(&inner.expr),
+        expr => expr,
+    }
+}
+
+fn strip_refs(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Reference(inner) => strip_refs(&inner.expr),
+        Expr::Paren(inner) => strip_refs(&inner.expr),
+        expr => expr,
+    }
+}
+
+/// A bare local name.
+fn path_ident(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(path) if path.qself.is_none() => path.path.get_ident().map(ToString::to_string),
+        _ => None,
+    }
+}
+
+/// Name at the start of a method, field or index chain: `GLOBAL` in `GLOBAL.lock().push(x)`.
+fn root_ident(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::MethodCall(call) => root_ident(&call.receiver),
+        Expr::Field(field) => root_ident(&field.base),
+        Expr::Index(index) => root_ident(&index.expr),
+        Expr::Reference(inner) => root_ident(&inner.expr),
+        Expr::Paren(inner) => root_ident(&inner.expr),
+        Expr::Try(inner) => root_ident(&inner.expr),
+        Expr::Await(inner) => root_ident(&inner.base),
+        Expr::Unary(inner) => root_ident(&inner.expr),
+        Expr::Path(_) => path_ident(expr),
+        _ => None,
+    }
+}
+
+fn call_path(call: &ExprCall) -> Vec<String> {
+    match &*call.func {
+        Expr::Path(path) => path.path.segments.iter().map(|segment| segment.ident.to_string()).collect(),
+        _ => vec![],
+    }
+}
+
+fn ends_with(path: &[String], suffix: &[&str]) -> bool {
+    path.len() >= suffix.len() && path[path.len() - suffix.len()..].iter().zip(suffix).all(|(a, b)| a == b)
+}
+
+fn peel_type(pat: &Pat) -> &Pat {
+    match pat {
+        Pat::Type(typed) => peel_type(&typed.pat),
+        pat => pat,
+    }
+}
+
+/// The name bound by `let name` or `let mut name: T`.
+fn single_binding(pat: &Pat) -> Option<String> {
+    match peel_type(pat) {
+        Pat::Ident(ident) if ident.subpat.is_none() => Some(ident.ident.to_string()),
+        _ => None,
+    }
+}
+
+fn pattern_idents(pat: &Pat, names: &mut HashSet<String>) {
+    struct Idents<'n>(&'n mut HashSet<String>);
+
+    impl<'ast> Visit<'ast> for Idents<'_> {
+        fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+            self.0.insert(pat.ident.to_string());
+            visit::visit_pat_ident(self, pat);
+        }
+    }
+
+    Idents(names).visit_pat(pat);
+}
+
+fn type_mentions(ty: &Type, name: &str) -> bool {
+    struct Mentions<'n> {
+        name: &'n str,
+        found: bool,
+    }
+
+    impl<'ast> Visit<'ast> for Mentions<'_> {
+        fn visit_path_segment(&mut self, segment: &'ast syn::PathSegment) {
+            self.found |= segment.ident == self.name;
+            visit::visit_path_segment(self, segment);
+        }
+    }
+
+    let mut mentions = Mentions { name, found: false };
+    mentions.visit_type(ty);
+    mentions.found
+}
+
+/// Macro arguments that parse as expressions (`vec![..]`, `assert!(..)`, `join!(..)`)
+/// or as statements (`defer! { .. }`), visited like the surrounding code.
+fn visit_macro_body<V>(visitor: &mut V, mac: &Macro)
+where
+    V: for<'ast> Visit<'ast>,
+{
+    if let Ok(exprs) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+        for expr in &exprs {
+            visitor.visit_expr(expr);
+        }
+    } else if let Ok(stmts) = mac.parse_body_with(Block::parse_within) {
+        for stmt in &stmts {
+            visitor.visit_stmt(stmt);
+        }
+    }
+}
+
+/// Line of the first spawn in whatever it visits.
+#[derive(Default)]
+struct SpawnFinder {
+    first: Option<usize>,
+}
+
+impl<'ast> Visit<'ast> for SpawnFinder {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if self.first.is_none() && spawn_site(expr, &HashSet::new(), &HashSet::new()).is_some() {
+            self.first = Some(expr.span().start().line);
+        }
+        visit::visit_expr(self, expr);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        visit_macro_body(self, mac);
+    }
+}
+
+/// Whether what `visit` walks joins or awaits something.
+fn contains_join(visit: impl FnOnce(&mut JoinFinder)) -> bool {
+    let mut finder = JoinFinder { found: false };
+    visit(&mut finder);
+    finder.found
+}
+
+struct JoinFinder {
+    found: bool,
+}
+
+impl<'ast> Visit<'ast> for JoinFinder {
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        self.found |= JOIN_METHODS.contains(&call.method.to_string().as_str());
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_await(&mut self, expr: &'ast syn::ExprAwait) {
+        self.found = true;
+        visit::visit_expr_await(self, expr);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        self.found |= call_path(call)
+            .last()
+            .is_some_and(|last| last == "join_all" || last == "try_join_all");
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        visit_macro_body(self, mac);
+    }
+}
+
+/// Values of types whose `Drop::drop` spawns, created in the target, and scope-guard
+/// cleanup closures in the target that spawn. Both start work at drop time, which
+/// depends on scope exit, panics and moves rather than on anything the caller awaits.
+fn detect_drop_spawns(
+    context: &FileContext,
+    function_name: &str,
+    signature: &Signature,
+    body: &Block,
+) -> Vec<StaticEscape> {
+    struct DropSpawns<'c> {
+        context: &'c FileContext<'c>,
+        /// First line constructing each drop-spawning type
+        constructions: BTreeMap<String, usize>,
+        /// Guard, line it is registered on, and line of the spawn in its closure
+        guards: Vec<(String, usize, usize)>,
+    }
+
+    impl DropSpawns<'_> {
+        fn constructs(&mut self, path: &syn::Path, line: usize) {
+            for segment in &path.segments {
+                let name = segment.ident.to_string();
+                if self.context.drop_spawns.contains_key(&name) {
+                    self.constructions.entry(name).or_insert(line);
+                }
+            }
+        }
+    }
+
+    impl<'ast> Visit<'ast> for DropSpawns<'_> {
+        fn visit_expr_struct(&mut self, expr: &'ast syn::ExprStruct) {
+            self.constructs(&expr.path, expr.span().start().line);
+            visit::visit_expr_struct(self, expr);
+        }
+
+        fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+            let line = call.span().start().line;
+            if let Expr::Path(path) = &*call.func {
+                self.constructs(&path.path, line);
+            }
+            let path = call_path(call);
+            if matches!(path.last().map(String::as_str), Some("guard" | "defer")) {
+                let mut finder = SpawnFinder::default();
+                for arg in &call.args {
+                    finder.visit_expr(arg);
+                }
+                if let Some(spawn_line) = finder.first {
+                    self.guards.push((path.join("::"), line, spawn_line));
+                }
+            }
+            visit::visit_expr_call(self, call);
+        }
+
+        fn visit_local(&mut self, local: &'ast Local) {
+            if let Pat::Type(typed) = &local.pat {
+                let line = local.let_token.span.start().line;
+                let names: Vec<String> = self.context.drop_spawns.keys().cloned().collect();
+                for name in names {
+                    if type_mentions(&typed.ty, &name) {
+                        self.constructions.entry(name).or_insert(line);
+                    }
+                }
+            }
+            visit::visit_local(self, local);
+        }
+
+        fn visit_macro(&mut self, mac: &'ast Macro) {
+            if mac.path.segments.last().is_some_and(|segment| segment.ident == "defer") {
+                let mut finder = SpawnFinder::default();
+                visit_macro_body(&mut finder, mac);
+                if let Some(spawn_line) = finder.first {
+                    self.guards.push(("defer!".to_string(), mac.span().start().line, spawn_line));
+                }
+            }
+            visit_macro_body(self, mac);
+        }
+    }
+
+    let mut visitor = DropSpawns { context, constructions: BTreeMap::new(), guards: vec![] };
+    visitor.visit_block(body);
+    let is_async = signature.asyncness.is_some();
+
+    let mut escapes = vec![];
+    for (type_name, line) in visitor.constructions {
+        let spawn_line = context.drop_spawns[&type_name];
+        let spawn_code = context.line(spawn_line);
+        let reason = format!(
+            "'{}' created here starts work from its Drop::drop (line {}: `{}`) whenever it is dropped{}",
+            type_name,
+            spawn_line,
+            spawn_code,
+            if is_async { ", including mid-await when the future is cancelled" } else { "" }
+        );
+        let confidence = if is_async || spawn_code.contains("tokio::") {
+            ConfidenceLevel::High
+        } else {
+            ConfidenceLevel::Medium
+        };
+        escapes.push(drop_spawn_escape(context, function_name, line, &type_name, reason, confidence, &spawn_code));
+    }
+    for (guard, line, spawn_line) in visitor.guards {
+        let reason = format!(
+            "Cleanup closure registered with `{}` spawns work at line {} when the guard is dropped",
+            guard, spawn_line
+        );
+        let spawn_code = context.line(spawn_line);
+        escapes.push(drop_spawn_escape(context, function_name, line, &guard, reason, ConfidenceLevel::High, &spawn_code));
+    }
+    escapes.sort_by_key(|escape| escape.location.line);
+    escapes
+}
+
+fn drop_spawn_escape(
+    context: &FileContext,
+    function_name: &str,
+    line: usize,
+    variable_name: &str,
+    reason: String,
+    confidence: ConfidenceLevel,
+    spawn_code: &str,
+) -> StaticEscape {
+    let mut escape = context.escape(DROP_SPAWN_RULE, function_name, line, 0);
+    escape.escape_type = classify_rust_async_escape(Some(spawn_code), &reason, variable_name);
+    escape.variable_name = variable_name.to_string();
+    escape.reason = reason;
+    escape.confidence = confidence;
+    escape.data_flow = vec![format!("drop spawns: {}", spawn_code)];
+    escape.rule = Some(DROP_SPAWN_RULE.name.to_string());
+    escape
+}
+
+fn classify_rust_async_escape(line: Option<&str>, reason: &str, variable_name: &str) -> EscapeType {
+    let combined = format!("{} {} {}", reason, variable_name, line.unwrap_or_default()).to_lowercase();
+
+    if combined.contains("return") || combined.contains("returned") {
+        EscapeType::ReturnEscape
+    } else if combined.contains("global") || combined.contains("static ") {
+        EscapeType::GlobalEscape
+    } else if combined.contains("move ||")
+        || combined.contains("async move")
+        || combined.contains("closure")
+        || combined.contains(" capture")
+    {
+        EscapeType::ClosureEscape
+    } else if combined.contains("spawn(") || combined.contains("parameter") || combined.contains("argument") {
+        EscapeType::ParameterEscape
+    } else {
+        // Unjoined handles and runtime task state are treated as heap-backed object escapes.
+        EscapeType::HeapEscape
+    }
+}
+
+/// Threads spawned in a scope borrow the caller's locals and are joined when it ends.
+const SCOPE_SNIPPET: &str = "
+thread::scope(|scope| {
+    scope.spawn(|| {
+        // borrow locals here instead of moving them
+    });
+}); // every scoped thread is joined here
+";
+
+/// Tasks owned by a `JoinSet` are aborted when it is dropped.
+const JOIN_SET_SNIPPET: &str = "
+let mut tasks = tokio::task::JoinSet::new();
+tasks.spawn(async move {
+    // ...
+});
+while let Some(result) = tasks.join_next().await {
+    result.expect(\"task panicked\");
+}
+";
+
+const BOUNDED_THREADS_SNIPPET: &str = "
+let workers = thread::available_parallelism().map_or(4, |count| count.get());
+thread::scope(|scope| {
+    for chunk in items.chunks(items.len().div_ceil(workers).max(1)) {
+        scope.spawn(move || chunk.iter().for_each(process));
+    }
+});
+";
+
+const BOUNDED_TASKS_SNIPPET: &str = "
+let permits = Arc::new(tokio::sync::Semaphore::new(8));
+let mut tasks = tokio::task::JoinSet::new();
+for item in items {
+    let permit = permits.clone().acquire_owned().await.expect(\"semaphore closed\");
+    tasks.spawn(async move {
+        let _permit = permit;
+        process(item).await
+    });
+}
+while let Some(result) = tasks.join_next().await {
+    result.expect(\"task panicked\");
+}
+";
+
+/// Rayon jobs spawned in a scope borrow the caller's locals and finish before it ends.
+const RAYON_SCOPE_SNIPPET: &str = "
+rayon::scope(|scope| {
+    scope.spawn(|_| {
+        // borrow locals here instead of moving them
+    });
+}); // every job spawned in the scope has finished here
+";
+
+/// A consumer joined by the scope; dropping the sender ends its loop.
+const CROSSBEAM_CONSUMER_SNIPPET: &str = "
+let (sender, receiver) = crossbeam::channel::unbounded();
+crossbeam::scope(|scope| {
+    scope.spawn(move |_| {
+        for item in receiver {
+            // ...
+        }
+    });
+    // send here, then drop the sender so the consumer's loop ends
+    drop(sender);
+})
+.expect(\"a scoped thread panicked\"); // the consumer is joined here
+";
+
+/// A producer joined by the scope while the caller receives.
+const CROSSBEAM_PRODUCER_SNIPPET: &str = "
+let (sender, receiver) = crossbeam::channel::bounded(16);
+crossbeam::scope(|scope| {
+    scope.spawn(move |_| {
+        // sender.send(item) for each item; the channel closes when it is dropped
+    });
+    for item in receiver.iter() {
+        // ...
+    }
+})
+.expect(\"a scoped thread panicked\"); // the producer is joined here
+";
+
+/// Fix for a finding from the template of its rule.
+fn suggested_fix(escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+    let line = escape.location.line;
+    let name = escape.variable_name.as_str();
+    match escape.rule_id.as_deref()? {
+        "GH-RS-001" => dropped_handle_fix(escape, context),
+        "GH-RS-002" => {
+            let task = declared_as_task(name, context);
+            Some(wait_fix(name, task, line, context))
+        }
+        "GH-RS-003" => {
+            let task = context.line(line).contains("async") || declared_as_task(name, context);
+            let exit = context.exit(line).filter(|_| context.at_body_level(line) && (!task || context.is_async()));
+            // Code after the loop that still reads the vector needs it drained, not moved.
+            let still_used = exit.is_some_and(|(exit, _)| context.mentioned_from(name, exit));
+            let mutable = context.declaration(name).is_some_and(|line| context.line(line).contains("let mut "));
+            let handles = if still_used { format!("{}.drain(..)", name) } else { name.to_string() };
+            let wait = vec![
+                format!("for handle in {} {{", handles),
+                format!("    {}", wait_statement("handle", task)),
+                "}".to_string(),
+            ];
+            let description = format!("Join every handle in `{}` before the function returns", name);
+            match exit.filter(|_| !still_used || mutable) {
+                Some((exit, indent)) => Some(fixes::placed(description, vec![fixes::insert(exit, indent, &wait)])),
+                None => Some(fixes::described(description, &wait.join("\n"))),
+            }
+        }
+        "GH-RS-004" => leaked_handle_fix(escape, context),
+        "GH-RS-006" | "GH-RS-007" => {
+            if let Some(holder) = escape.reason.split("thread/task '").nth(1).and_then(|rest| rest.split('\'').next()) {
+                let task = declared_as_task(holder, context);
+                return Some(fixes::described(
+                    format!("Join `{}` before returning so `{}` does not outlive the call", holder, name),
+                    &handle_wait(holder, task, context),
+                ));
+            }
+            if escape.reason.ends_with("detached thread pool job") || escape.reason.contains("thread pool '") {
+                return Some(pool_job_fix(&escape.reason, line, context));
+            }
+            let detached = escape.reason.contains("detached");
+            detached.then(|| runtime_fix(context.line(line), escape.reason.to_lowercase().contains("task")))
+        }
+        "GH-RS-013" => Some(pool_job_fix(&escape.reason, line, context)),
+        "GH-RS-015" => {
+            let holder = escape.reason.split("thread/task '").nth(1).and_then(|rest| rest.split('\'').next());
+            if let Some(holder) = holder {
+                let task = declared_as_task(holder, context);
+                return Some(fixes::described(
+                    format!("Join `{}` before returning, after dropping the senders it waits on", holder),
+                    &handle_wait(holder, task, context),
+                ));
+            }
+            let (role, snippet) = if escape.reason.starts_with("Crossbeam channel sender") {
+                ("producer", CROSSBEAM_PRODUCER_SNIPPET)
+            } else {
+                ("consumer", CROSSBEAM_CONSUMER_SNIPPET)
+            };
+            Some(fixes::described(format!("Spawn the {} in `crossbeam::scope` so it is joined before returning", role), snippet))
+        }
+        "GH-RS-014" if name == "build_global" => Some(fixes::described(
+            "Build a pool of your own and run the work in it with `install`, or build the global pool once from `main`"
+                .to_string(),
+            "let pool = rayon::ThreadPoolBuilder::new().num_threads(4).build().expect(\"build pool\");\npool.install(|| {\n    // ...\n}); // the pool's workers exit when it is dropped",
+        )),
+        "GH-RS-014" => Some(fixes::described(
+            "Keep the pool local and join it before it drops, or build the shared pool once at startup".to_string(),
+            "let pool = threadpool::ThreadPool::new(4);\n// ...\npool.join(); // the workers exit once the pool is dropped",
+        )),
+        "GH-RS-008" if !escape.reason.starts_with("Spawn through") => {
+            Some(scoped_fix(escape.reason.to_lowercase().contains("task")))
+        }
+        "GH-GEN-001" if context.line(line).contains("async") => Some(fixes::described(
+            "Bound the number of tasks with a semaphore and join them through a `JoinSet`".to_string(),
+            BOUNDED_TASKS_SNIPPET,
+        )),
+        "GH-GEN-001" => scoped_loop_fix(line, context).or_else(|| {
+            Some(fixes::described(
+                "Split the work over a fixed number of scoped threads".to_string(),
+                BOUNDED_THREADS_SNIPPET,
+            ))
+        }),
+        _ => None,
+    }
+}
+
+/// Joins a thread or awaits a task before the function returns.
+fn wait_fix(name: &str, task: bool, line: usize, context: &FixContext) -> SuggestedFix {
+    let statement = handle_wait(name, task, context);
+    if task && !context.is_async() {
+        if let Some(block_on) = declared_runtime(name, context) {
+            return fixes::described(
+                format!("Await `{}` from async code, or block on it before returning", name),
+                &format!("{}\n// or, from synchronous code:\n{}({});", statement, block_on, name),
+            );
+        }
+        return fixes::described(
+            format!("Await `{}` from async code, or abort it before returning", name),
+            &format!("{}\n// or, from synchronous code:\n{}.abort();", statement, name),
+        );
+    }
+    let verb = if task { "Await" } else { "Join" };
+    fixes::before_exit(format!("{} `{}` before the function returns", verb, name), &[statement], line, context)
+}
+
+/// A handle dropped or never bound: keep it and wait for it instead.
+fn dropped_handle_fix(escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+    let name = escape.variable_name.as_str();
+    // The line the handle was let go of, the statement that did it, and how.
+    let released = [
+        (" dropped at line ", format!("drop({});", name), "dropping"),
+        (" detached at line ", format!("{}.detach();", name), "detaching"),
+    ]
+    .into_iter()
+    .find_map(|(marker, call, releasing)| Some((escape.reason.split(marker).nth(1)?, call, releasing)));
+    if let Some((released, call, releasing)) = released {
+        let released: usize = released.split_whitespace().next()?.parse().ok()?;
+        let task = declared_as_task(name, context);
+        if task && !context.is_async() {
+            return Some(wait_fix(name, task, escape.location.line, context));
+        }
+        let mut statements = context.declaration(name).map_or_else(Vec::new, |spawn| sender_drops(spawn, released, context));
+        statements.push(handle_wait(name, task, context));
+        let indent = context.indent(released);
+        let releases = context.line(released).trim() == call;
+        let edit = if releases {
+            fixes::replace(released, indent, &statements)
+        } else {
+            fixes::insert(released, indent, &statements)
+        };
+        let verb = if task { "Await" } else { "Join" };
+        return Some(fixes::placed(format!("{} `{}` instead of {} it", verb, name, releasing), vec![edit]));
+    }
+    if escape.reason.ends_with("`.detach()` lets it run past the call") {
+        return Some(fixes::described(
+            "Keep the task instead of detaching it and await it before returning; dropping it cancels the task"
+                .to_string(),
+            &format!("let task = {}(future);\n// ...\ntask.await;", name),
+        ));
+    }
+
+    let kind = escape.reason.split(" is detached: ").next()?;
+    let task = kind.to_lowercase().contains("task");
+    if kind.starts_with("Spawn through") || kind.ends_with(" builder") || escape.reason == kind {
+        return Some(runtime_fix(context.line(escape.location.line), task));
+    }
+    let line = escape.location.line;
+    let spawn = detached_spawn(context.line(line));
+    let placeable = context.at_body_level(line) && (!task || context.is_async());
+    match (spawn, context.exit(line).filter(|_| placeable)) {
+        (Some(spawn), Some((exit, indent))) => {
+            let verb = if task { "await" } else { "join" };
+            let handle = handle_name(line, context);
+            let mut statements = sender_drops(line, exit, context);
+            statements.push(wait_statement(&handle, task));
+            Some(fixes::placed(
+                format!("Keep the handle and {} it before the function returns", verb),
+                vec![
+                    fixes::replace(line, context.indent(line), &[format!("let {} = {}", handle, spawn)]),
+                    fixes::insert(exit, indent, &statements),
+                ],
+            ))
+        }
+        _ => Some(runtime_fix(context.line(line), task)),
+    }
+}
+
+/// `drop(sender);` for each channel the thread spawned on `line` reads until it closes
+/// (`for item in receiver`, `while let Ok(item) = receiver.recv()`) whose sender the
+/// function still holds at `exit`, so joining the thread there does not wait forever.
+fn sender_drops(line: usize, exit: usize, context: &FixContext) -> Vec<String> {
+    let depth = context.indent(line).len();
+    let closure: Vec<&str> = (line + 1..exit)
+        .map(|line| context.line(line))
+        .take_while(|code| code.trim().is_empty() || code.len() - code.trim_start().len() > depth)
+        .collect();
+    let receivers = closure.iter().filter_map(|code| {
+        let code = code.trim();
+        let source = match code.strip_prefix("for ") {
+            Some(rest) => rest.split_once(" in ")?.1,
+            None => code.strip_prefix("while let ")?.split_once(" = ")?.1,
+        };
+        let source = source.trim_end_matches('{').trim_end().trim_start_matches('&');
+        let source = source.trim_end_matches(".recv()").trim_end_matches(".iter()").trim_end_matches(".into_iter()");
+        callgraph::is_identifier(source).then_some(source)
+    });
+    let mut drops = Vec::new();
+    for receiver in receivers {
+        let Some(sender) = bound_sender(receiver, line, context) else {
+            continue;
+        };
+        let mentions = |code: &str| callgraph::identifiers(code).any(|word| word == sender);
+        let dropped = context.body().any(|line| context.line(line).contains(&format!("drop({})", sender)));
+        // A sender moved into the thread closes with it; one dropped already needs nothing.
+        if !dropped && !closure.iter().any(|code| mentions(code)) {
+            drops.push(format!("drop({});", sender));
+        }
+    }
+    drops
+}
+
+/// The sender bound with `receiver` by a `let (sender, receiver) = ...` before `line`.
+fn bound_sender<'a>(receiver: &str, line: usize, context: &FixContext<'a>) -> Option<&'a str> {
+    context.body().take_while(|body_line| *body_line < line).find_map(|body_line| {
+        let pattern = context.line(body_line).trim().strip_prefix("let (")?.split_once(')')?.0;
+        let (sender, bound) = pattern.split_once(',')?;
+        let sender = sender.trim().trim_start_matches("mut ").trim();
+        (bound.trim().trim_start_matches("mut ").trim() == receiver && callgraph::is_identifier(sender)).then_some(sender)
+    })
+}
+
+/// The spawn call starting `line`, as a statement or bound to `_`.
+fn detached_spawn(line: &str) -> Option<&str> {
+    let code = line.trim();
+    let spawn = match code.strip_prefix("let _") {
+        Some(rest) => rest.split_once(" = ")?.1,
+        None => code,
+    };
+    let callee = spawn.split('(').next()?;
+    SPAWN_CALLS.iter().any(|call| callee.ends_with(call)).then_some(spawn)
+}
+
+/// `handle` for the first detached spawn of a function, `handle2` and on for later
+/// ones, so fixes of several spawns can be applied together.
+fn handle_name(line: usize, context: &FixContext) -> String {
+    let earlier = context
+        .body()
+        .filter(|other| *other < line && context.at_body_level(*other) && detached_spawn(context.line(*other)).is_some())
+        .count();
+    (earlier + 1..)
+        .map(|index| if index == 1 { "handle".to_string() } else { format!("handle{}", index) })
+        .find(|name| context.declaration(name).is_none())
+        .unwrap_or_default()
+}
+
+/// A loop at the function's own level that starts threads: the loop run inside
+/// `thread::scope`, which joins every thread it started before returning.
+fn scoped_loop_fix(line: usize, context: &FixContext) -> Option<SuggestedFix> {
+    let spawn = detached_spawn(context.line(line)).filter(|spawn| spawn.contains("thread::spawn("))?;
+    let depth = context.indent(line).len();
+    let header = context.body().rev().find(|other| *other < line && context.indent(*other).len() < depth)?;
+    let opens_loop = ["for ", "while ", "loop "].iter().any(|keyword| context.line(header).trim().starts_with(keyword));
+    if !opens_loop || !context.at_body_level(header) {
+        return None;
+    }
+    let indent = context.indent(header);
+    let end = context
+        .body()
+        .find(|other| *other > line && context.indent(*other) == indent && context.line(*other).trim().starts_with('}'))?;
+    let mut code = vec!["std::thread::scope(|scope| {".to_string()];
+    for other in header..=end {
+        let text = context.line(other);
+        let text = if other == line {
+            text.replacen(spawn, &spawn.replacen(spawn.split('(').next()?, "scope.spawn", 1), 1)
+        } else {
+            text.to_string()
+        };
+        let nested = text.strip_prefix(indent).unwrap_or(&text);
+        code.push(if nested.trim().is_empty() { String::new() } else { format!("    {}", nested) });
+    }
+    code.push("});".to_string());
+    Some(fixes::placed(
+        "Run the loop in `thread::scope` so every thread it starts is joined before returning".to_string(),
+        vec![fixes::replace_block(header, end, indent, &code)],
+    ))
+}
+
+/// `mem::forget` replaced by what the destructor would have done.
+fn leaked_handle_fix(escape: &StaticEscape, context: &FixContext) -> Option<SuggestedFix> {
+    let name = escape.variable_name.as_str();
+    let line = escape.location.line;
+    let (description, statement) = if escape.reason.starts_with("Thread/task handle") {
+        let task = declared_as_task(name, context);
+        if task && !context.is_async() {
+            return Some(wait_fix(name, task, line, context));
+        }
+        let verb = if task { "Await" } else { "Join" };
+        (format!("{} `{}` instead of leaking it", verb, name), handle_wait(name, task, context))
+    } else if escape.reason.starts_with("Channel sender") {
+        (format!("Drop `{}` so the channel closes", name), format!("drop({});", name))
+    } else {
+        (format!("Drop `{}` so the lock is released", name), format!("drop({});", name))
+    };
+    let forgets = context
+        .single_statement(line)
+        .is_some_and(|code| code.ends_with(&format!("mem::forget({})", name)));
+    Some(if forgets {
+        fixes::placed(description, vec![fixes::replace(line, context.indent(line), &[statement])])
+    } else {
+        fixes::described(description, &statement)
+    })
+}
+
+fn scoped_fix(task: bool) -> SuggestedFix {
+    if task {
+        fixes::described("Spawn the task on a `JoinSet` and join it before returning".to_string(), JOIN_SET_SNIPPET)
+    } else {
+        fixes::described("Spawn the thread in `thread::scope` so it is joined before returning".to_string(), SCOPE_SNIPPET)
+    }
+}
+
+/// Waits for handle `name`. async-std and smol handles give the task's output when
+/// awaited, not a `Result`.
+fn handle_wait(name: &str, task: bool, context: &FixContext) -> String {
+    if task && declared_runtime(name, context).is_some() {
+        format!("{}.await;", name)
+    } else {
+        wait_statement(name, task)
+    }
+}
+
+/// `block_on` of the async-std or smol runtime handle `name` was spawned on.
+fn declared_runtime(name: &str, context: &FixContext) -> Option<&'static str> {
+    let code = context.line(context.declaration(name)?);
+    [("async_std::", "async_std::task::block_on"), ("smol::", "smol::block_on")]
+        .into_iter()
+        .find_map(|(runtime, block_on)| code.contains(runtime).then_some(block_on))
+}
+
+/// `scoped_fix`, except for async-std and smol tasks spawned on `code`, which have no
+/// `JoinSet`: their handle is kept and awaited instead.
+fn runtime_fix(code: &str, task: bool) -> SuggestedFix {
+    let spawn = [("async_std::", "async_std::task::spawn"), ("smol::", "smol::spawn")]
+        .into_iter()
+        .find(|(runtime, _)| code.contains(runtime));
+    match spawn {
+        Some((_, spawn)) if task => fixes::described(
+            "Keep the task's handle and await it before returning".to_string(),
+            &format!("let task = {}(future);\n// ...\ntask.await;", spawn),
+        ),
+        _ => scoped_fix(task),
+    }
+}
+
+/// `pool.spawn_ok(..)` replaced by a spawn that hands back a handle to await.
+fn pool_handle_fix() -> SuggestedFix {
+    fixes::described(
+        "Spawn with `spawn_with_handle` (`futures::task::SpawnExt`) and await the handle before returning".to_string(),
+        "let handle = pool.spawn_with_handle(future).expect(\"spawn task\");\n// ...\nhandle.await;",
+    )
+}
+
+/// A job handed to a thread pool without a handle: `spawn_ok` with a handle instead,
+/// `execute` followed by `join`, or a rayon job spawned in a scope.
+fn pool_job_fix(reason: &str, line: usize, context: &FixContext) -> SuggestedFix {
+    if let Some(pool) = reason.split("thread pool '").nth(1).and_then(|rest| rest.split('\'').next()) {
+        let wait = vec![format!("{}.join();", pool)];
+        return fixes::before_exit(format!("Join `{}` before the function returns", pool), &wait, line, context);
+    }
+    if context.line(line).contains("spawn_ok") {
+        return pool_handle_fix();
+    }
+    fixes::described(
+        "Spawn the job in `rayon::scope` (or the pool's `scope`) so it has finished before returning".to_string(),
+        RAYON_SCOPE_SNIPPET,
+    )
+}
+
+fn wait_statement(name: &str, task: bool) -> String {
+    if task {
+        format!("{}.await.expect(\"task panicked\");", name)
+    } else {
+        format!("{}.join().expect(\"thread panicked\");", name)
+    }
+}
+
+/// Whether handle `name` comes from an async or blocking task spawn rather than a thread.
+fn declared_as_task(name: &str, context: &FixContext) -> bool {
+    context.declaration(name).is_some_and(|line| {
+        let code = context.line(line);
+        ["async", "task::spawn", "tokio::spawn", "smol::spawn", "spawn_blocking", "spawn_local"]
+            .iter()
+            .any(|marker| code.contains(marker))
+    })
 }
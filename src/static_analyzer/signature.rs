@@ -0,0 +1,207 @@
+//! Parameter kinds
+//! Reads the parameters of a target function and classifies each by its declared type
+//! or, in untyped code, by its default value and name (`count = 0` is an integer,
+//! `options = {}` a map). Input generation uses the kinds to produce values the
+//! parameter can actually take.
+
+use crate::static_analyzer::callgraph::{extract_functions, is_identifier, split_top_level, target_function, Language};
+
+/// Shape of the values a parameter takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    Integer,
+    Float,
+    Boolean,
+    Text,
+    /// Dicts, maps, structs and classes, generated as JSON objects
+    Map,
+    /// Lists, slices, arrays and sets, generated as JSON arrays
+    List,
+    Unknown,
+}
+
+impl ParamKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Boolean => "boolean",
+            Self::Text => "string",
+            Self::Map => "object",
+            Self::List => "list",
+            Self::Unknown => "untyped",
+        }
+    }
+}
+
+/// One parameter of the target.
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub name: String,
+    pub kind: ParamKind,
+    /// Has a default value or is variadic, so a call may leave it out.
+    pub optional: bool,
+}
+
+/// Names that hold a count or size in untyped code.
+const INTEGER_NAMES: &[&str] = &[
+    "n", "count", "size", "num", "number", "index", "idx", "limit", "depth", "retries", "workers",
+    "threads", "iterations", "length", "port", "timeout_ms", "delay_ms",
+];
+
+/// Names that hold options or a record in untyped code.
+const MAP_NAMES: &[&str] = &["options", "opts", "config", "settings", "params", "kwargs", "headers", "record"];
+
+/// Parameters of `target`'s function in `source_file`, or `None` when the file cannot be
+/// read or does not define the function.
+pub fn target_parameters(language: &str, source_file: &str, target: &str) -> Option<Vec<Parameter>> {
+    let language = Language::from_name(language)?;
+    let source = std::fs::read_to_string(source_file).ok()?;
+    let function = target_function(target);
+    let function = function.rsplit('.').next().unwrap_or(function);
+    let definition = extract_functions(&source, language)
+        .into_iter()
+        .find(|definition| definition.name == function)?;
+    Some(parse_parameters(&definition.parameters, language))
+}
+
+fn parse_parameters(parameters: &str, language: Language) -> Vec<Parameter> {
+    let mut parsed: Vec<(String, Option<String>, Option<String>, bool)> = split_top_level(parameters)
+        .iter()
+        .filter_map(|part| {
+            let part = part.trim();
+            let variadic = part.starts_with('*') || part.contains("...");
+            let (name, declared, default) = split_parameter(part, language)?;
+            Some((name, declared, default, variadic))
+        })
+        .filter(|(name, ..)| !matches!(name.as_str(), "self" | "cls" | "this"))
+        .collect();
+    // Go shares one type between names (`a, b string`): it belongs to every name before it.
+    if language == Language::Go {
+        for index in (0..parsed.len().saturating_sub(1)).rev() {
+            if parsed[index].1.is_none() {
+                parsed[index].1 = parsed[index + 1].1.clone();
+            }
+        }
+    }
+    parsed
+        .into_iter()
+        .map(|(name, declared, default, variadic)| {
+            let optional = variadic || default.is_some();
+            let kind = declared
+                .as_deref()
+                .map(kind_of_type)
+                .filter(|kind| *kind != ParamKind::Unknown)
+                .or_else(|| default.as_deref().map(kind_of_default))
+                .filter(|kind| *kind != ParamKind::Unknown)
+                .unwrap_or_else(|| kind_of_name(&name));
+            Parameter { name, kind, optional }
+        })
+        .collect()
+}
+
+/// (name, declared type, default value) of one parameter.
+fn split_parameter(part: &str, language: Language) -> Option<(String, Option<String>, Option<String>)> {
+    let (head, default) = match part.split_once('=') {
+        Some((head, default)) if language != Language::Go && language != Language::Java => {
+            (head.trim(), Some(default.trim().to_string()))
+        }
+        _ => (part, None),
+    };
+    let (name, declared) = match language {
+        Language::Java => {
+            let words: Vec<&str> = head
+                .split_whitespace()
+                .filter(|word| !word.starts_with('@') && *word != "final")
+                .collect();
+            let (name, declared) = words.split_last()?;
+            (name.to_string(), Some(declared.join(" ")).filter(|declared| !declared.is_empty()))
+        }
+        Language::Go => {
+            let head = head.trim_start_matches("...");
+            match head.split_once(char::is_whitespace) {
+                Some((name, declared)) => (name.to_string(), Some(declared.trim().to_string())),
+                None => (head.to_string(), None),
+            }
+        }
+        _ => {
+            let head = head.trim_start_matches('*').trim_start_matches('&').trim_start_matches("mut ");
+            match head.split_once(':') {
+                Some((name, declared)) => (name.trim().to_string(), Some(declared.trim().to_string())),
+                None => (head.trim().to_string(), None),
+            }
+        }
+    };
+    let name = name.trim_start_matches("mut ").trim().to_string();
+    is_identifier(&name).then_some((name, declared, default))
+}
+
+/// Kind of a declared type in any of the supported languages.
+fn kind_of_type(declared: &str) -> ParamKind {
+    let declared = unwrap_optional(declared.trim().trim_start_matches(['&', '*']).trim_start_matches("mut ").trim());
+    let variadic = declared.starts_with("...") || declared.ends_with("...");
+    if variadic || declared.ends_with("[]") || declared.starts_with('[') {
+        return ParamKind::List;
+    }
+    let base = declared.split(['<', '[']).next().unwrap_or(declared).trim();
+    let base = base.rsplit(['.', ':']).next().unwrap_or(base);
+    match base.to_lowercase().as_str() {
+        "int" | "integer" | "long" | "short" | "byte" | "bigint" | "number" | "i8" | "i16" | "i32" | "i64"
+        | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "int8" | "int16" | "int32"
+        | "int64" | "uint" | "uint8" | "uint16" | "uint32" | "uint64" | "biginteger" | "atomicinteger" => {
+            ParamKind::Integer
+        }
+        "float" | "double" | "f32" | "f64" | "float32" | "float64" | "decimal" | "bigdecimal" => ParamKind::Float,
+        "bool" | "boolean" => ParamKind::Boolean,
+        "str" | "string" | "char" | "character" | "bytes" | "bytearray" | "charsequence" | "rune" | "osstr"
+        | "path" | "pathbuf" => ParamKind::Text,
+        "dict" | "map" | "hashmap" | "btreemap" | "mapping" | "object" | "record" | "jsonobject" | "value"
+        | "typeddict" | "defaultdict" | "ordereddict" | "concurrenthashmap" | "treemap" | "linkedhashmap" => {
+            ParamKind::Map
+        }
+        "list" | "vec" | "vecdeque" | "set" | "hashset" | "btreeset" | "tuple" | "sequence" | "iterable"
+        | "array" | "arraylist" | "linkedlist" | "collection" | "frozenset" | "jsonarray" => ParamKind::List,
+        "any" | "interface{}" | "" => ParamKind::Unknown,
+        // Other capitalized names are structs and classes, passed as JSON objects.
+        _ if base.chars().next().is_some_and(char::is_uppercase) => ParamKind::Map,
+        _ => ParamKind::Unknown,
+    }
+}
+
+/// `Optional[T]`, `Option<T>` and `T | None` are generated as `T`.
+fn unwrap_optional(declared: &str) -> &str {
+    for wrapper in ["Optional[", "Option<", "typing.Optional["] {
+        if let Some(inner) = declared.strip_prefix(wrapper) {
+            return inner[..inner.len().saturating_sub(1)].trim();
+        }
+    }
+    declared
+        .split('|')
+        .map(str::trim)
+        .find(|part| !matches!(*part, "None" | "null" | "undefined"))
+        .unwrap_or(declared)
+}
+
+fn kind_of_default(default: &str) -> ParamKind {
+    let default = default.trim();
+    match default {
+        "True" | "False" | "true" | "false" => ParamKind::Boolean,
+        _ if default.starts_with('{') || default.starts_with("dict(") => ParamKind::Map,
+        _ if default.starts_with('[') || default.starts_with("list(") || default.starts_with('(') => ParamKind::List,
+        _ if default.starts_with(['"', '\'', '`']) => ParamKind::Text,
+        _ if default.parse::<i64>().is_ok() => ParamKind::Integer,
+        _ if default.parse::<f64>().is_ok() => ParamKind::Float,
+        _ => ParamKind::Unknown,
+    }
+}
+
+fn kind_of_name(name: &str) -> ParamKind {
+    let name = name.to_lowercase();
+    if INTEGER_NAMES.contains(&name.as_str()) || name.starts_with("num_") || name.ends_with("_count") {
+        ParamKind::Integer
+    } else if MAP_NAMES.contains(&name.as_str()) {
+        ParamKind::Map
+    } else {
+        ParamKind::Unknown
+    }
+}
@@ -0,0 +1,514 @@
+//! Spawn count estimation
+//! Multiplies each spawn site reachable from the target by the constant bounds of the
+//! loops around it (`for i in 0..5`, `range(3)`, `for (int i = 0; i < N; i++)`) and by
+//! how often its function is called, giving the number of threads/tasks one call of the
+//! target is expected to start. Names bound to a single integer literal in the file are
+//! folded in as constants. Sites in loops are also classified by how their spawn count
+//! can grow and reported under the `spawn-in-loop` rule, with confidence rising from
+//! constant bounds to input-dependent and infinite loops. Sites the function waits for
+//! (scoped spawns, joined handles) still count towards the estimate but are not reported.
+
+use crate::protocol::{
+    CallGraph, ConfidenceLevel, EscapeType, SourceLocation, SpawnCount, SpawnEstimate, SpawnGrowth,
+    StaticEscape,
+};
+use crate::static_analyzer::callgraph::{
+    extract_functions, identifiers, split_top_level, FunctionDef, Language,
+};
+use crate::static_analyzer::preprocess;
+use crate::static_analyzer::rules::{self, Rule};
+use crate::static_analyzer::taint::{assignment, parameter_names, TaintReport};
+use std::collections::HashMap;
+
+/// Catalog rule of spawns inside loops.
+const RULE: Rule = rules::SPAWN_IN_LOOP;
+
+/// Spawn count and growth of a site or call, combined over the loops around it.
+type Repetition = (Option<usize>, SpawnGrowth);
+
+/// Estimates spawns per call of the graph's root. `None` for unsupported languages.
+/// Loops over variables the taint report marks as input count as input-dependent.
+pub fn estimate(
+    language: &str,
+    source_file: &str,
+    graph: &CallGraph,
+    taint: Option<&TaintReport>,
+) -> Option<SpawnEstimate> {
+    let language = Language::from_name(language)?;
+    let source = std::fs::read_to_string(source_file).ok()?;
+    let functions = extract_functions(&source, language);
+    let mut defined: HashMap<&str, &FunctionDef> = HashMap::new();
+    for function in &functions {
+        defined.entry(function.name.as_str()).or_insert(function);
+    }
+    let constants = constants(&source, language);
+    let bounds = Bounds { language, constants: &constants, taint };
+    let raw_lines: Vec<&str> = source.lines().collect();
+
+    let mut multiplicity = HashMap::new();
+    let mut sites = Vec::new();
+    for node in graph.nodes.iter().filter(|node| node.line.is_some()) {
+        let Some(function) = defined.get(node.name.as_str()) else {
+            continue;
+        };
+        let (calls, call_growth) =
+            call_multiplicity(&node.name, graph, &defined, &bounds, &mut multiplicity, &mut Vec::new());
+        for site in &node.spawn_sites {
+            let loops = enclosing_loops(function, site.line, language);
+            let (per_loop, growth) = bounds.repetition(&loops, function);
+            sites.push(SpawnCount {
+                function: node.name.clone(),
+                line: site.line,
+                per_call: calls.zip(per_loop).map(|(calls, per_loop)| calls.saturating_mul(per_loop)),
+                loops: written_loops(&loops, function, site.line, &raw_lines),
+                growth: growth.max(call_growth),
+            });
+        }
+    }
+
+    Some(SpawnEstimate {
+        per_call: sites
+            .iter()
+            .map(|site| site.per_call)
+            .try_fold(0usize, |total, count| Some(total.saturating_add(count?))),
+        sites,
+    })
+}
+
+/// Times `function` runs per call of the root: the sum over its call sites of the
+/// caller's own count times the loops around the call, with the worst growth among
+/// them. Unknown inside recursion, which the `recursive-spawn` rule covers.
+fn call_multiplicity(
+    function: &str,
+    graph: &CallGraph,
+    defined: &HashMap<&str, &FunctionDef>,
+    bounds: &Bounds,
+    memo: &mut HashMap<String, Repetition>,
+    stack: &mut Vec<String>,
+) -> Repetition {
+    if function == graph.root {
+        return (Some(1), SpawnGrowth::Single);
+    }
+    if let Some(known) = memo.get(function) {
+        return *known;
+    }
+    if stack.iter().any(|name| name == function) {
+        return (None, SpawnGrowth::Single);
+    }
+    stack.push(function.to_string());
+    let (mut total, mut growth) = (Some(0usize), SpawnGrowth::Single);
+    for edge in graph.edges.iter().filter(|edge| edge.callee == function) {
+        let (caller_calls, caller_growth) = call_multiplicity(&edge.caller, graph, defined, bounds, memo, stack);
+        let Some(caller) = defined.get(edge.caller.as_str()) else {
+            continue;
+        };
+        for line in &edge.lines {
+            let loops = enclosing_loops(caller, *line, bounds.language);
+            let (per_loop, loop_growth) = bounds.repetition(&loops, caller);
+            total = total
+                .zip(caller_calls)
+                .zip(per_loop)
+                .map(|((total, calls), per_loop)| total.saturating_add(calls.saturating_mul(per_loop)));
+            growth = growth.max(caller_growth).max(loop_growth);
+        }
+    }
+    stack.pop();
+    memo.insert(function.to_string(), (total, growth));
+    (total, growth)
+}
+
+/// `spawn-in-loop` findings for the sites of an estimate that sit in loops and are not
+/// waited for before their function returns.
+pub fn loop_spawns(language: &str, estimate: &SpawnEstimate, source_file: &str) -> Vec<StaticEscape> {
+    let source = std::fs::read_to_string(source_file).unwrap_or_default();
+    let raw_lines: Vec<&str> = source.lines().collect();
+    let language = Language::from_name(language);
+    let functions = language.map(|language| extract_functions(&source, language)).unwrap_or_default();
+    let waited = |site: &SpawnCount| {
+        language.is_some_and(|language| {
+            functions
+                .iter()
+                .filter(|function| function.name == site.function)
+                .any(|function| waited_for(function, site.line, language))
+        })
+    };
+    estimate
+        .sites
+        .iter()
+        .filter(|site| site.growth != SpawnGrowth::Single && !waited(site))
+        .map(|site| {
+            let loops = if site.loops.is_empty() {
+                "a loop in a caller".to_string()
+            } else {
+                format!("`{}`", site.loops.join("` › `"))
+            };
+            let (reason, confidence) = match site.growth {
+                SpawnGrowth::Unbounded => (
+                    format!("Spawn inside an unbounded loop ({}): threads/tasks accumulate without limit", loops),
+                    ConfidenceLevel::High,
+                ),
+                SpawnGrowth::InputDependent => (
+                    format!("Spawn inside a loop bounded by the input ({}): callers control how many threads/tasks start", loops),
+                    ConfidenceLevel::High,
+                ),
+                SpawnGrowth::Dynamic => (
+                    format!("Spawn inside a loop with a run-time bound ({}): thread/task count grows with the data", loops),
+                    ConfidenceLevel::Medium,
+                ),
+                _ => (
+                    match site.per_call {
+                        Some(count) => format!("Spawn inside a bounded loop ({}): {} thread(s)/task(s) per call", loops, count),
+                        None => format!("Spawn inside a bounded loop ({})", loops),
+                    },
+                    ConfidenceLevel::Low,
+                ),
+            };
+            StaticEscape {
+                escape_type: EscapeType::SpawnEscape,
+                location: SourceLocation {
+                    file: source_file.to_string(),
+                    line: site.line,
+                    column: 0,
+                    function: site.function.clone(),
+                    code_snippet: raw_lines.get(site.line - 1).map(|code| code.trim().to_string()),
+                },
+                variable_name: site.function.clone(),
+                reason,
+                confidence,
+                data_flow: vec![],
+                input_tainted: false,
+                taint_flow: vec![],
+                rule: Some(RULE.name.to_string()),
+                rule_id: Some(RULE.id.to_string()),
+                condition: None,
+                suggested_fix: None,
+            }
+        })
+        .collect()
+}
+
+/// Headers of the loops enclosing `line` in `function`, outermost first. A range
+/// iterator on the line itself (`(0..4).map(|_| thread::spawn(..))`) counts as a loop.
+fn enclosing_loops(function: &FunctionDef, line: usize, language: Language) -> Vec<String> {
+    let Some(position) = function.body.iter().position(|(body_line, _)| *body_line == line) else {
+        return Vec::new();
+    };
+    let mut loops: Vec<String> = enclosing_blocks(function, position, language)
+        .into_iter()
+        .filter(|header| is_loop_header(header, language))
+        .collect();
+
+    let own = function.body[position].1.trim();
+    if let Some(range) = range_iterator(own).filter(|_| language == Language::Rust) {
+        loops.push(range.to_string());
+    } else if is_loop_header(own, language) && own.contains('{') {
+        loops.push(own.split('{').next().unwrap_or(own).trim().to_string());
+    }
+    loops
+}
+
+/// Loop headers as written in the source. Each header was cut from one cleaned line of
+/// `function` at or above `line`, and is read back from the same span of that line.
+fn written_loops(loops: &[String], function: &FunctionDef, line: usize, raw_lines: &[&str]) -> Vec<String> {
+    loops
+        .iter()
+        .map(|header| {
+            function
+                .body
+                .iter()
+                .rev()
+                .filter(|(body_line, _)| *body_line <= line)
+                .find(|(_, code)| code.contains(header.as_str()))
+                .and_then(|(body_line, code)| {
+                    let original = raw_lines.get(body_line - 1)?;
+                    Some(preprocess::restore_span(header, code, original))
+                })
+                .unwrap_or_else(|| header.clone())
+        })
+        .collect()
+}
+
+/// Headers of the blocks enclosing body line `position`, outermost first: the text before
+/// each open `{`, or the less indented lines above it in Python (without the `:`).
+fn enclosing_blocks(function: &FunctionDef, position: usize, language: Language) -> Vec<String> {
+    let mut headers = Vec::new();
+    if language == Language::Python {
+        let indent = |code: &str| code.len() - code.trim_start().len();
+        let mut current = indent(&function.body[position].1);
+        for (_, code) in function.body[..position].iter().rev() {
+            if code.trim().is_empty() || indent(code) >= current {
+                continue;
+            }
+            current = indent(code);
+            headers.push(code.trim().trim_end_matches(':').to_string());
+        }
+        headers.reverse();
+    } else {
+        for (_, code) in &function.body[..position] {
+            for (index, ch) in code.char_indices() {
+                match ch {
+                    '{' => {
+                        let header = code[..index].trim().trim_start_matches('}').trim();
+                        headers.push(header.to_string());
+                    }
+                    '}' => {
+                        headers.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    headers
+}
+
+/// Whether the spawn on `line` of `function` finishes before the function returns: it
+/// runs inside a joining scope, or its handle (or the collection it is pushed to) is
+/// joined further down. Go goroutines count as waited for when a `.Wait()` follows.
+fn waited_for(function: &FunctionDef, line: usize, language: Language) -> bool {
+    let Some(position) = function.body.iter().position(|(body_line, _)| *body_line == line) else {
+        return false;
+    };
+    if enclosing_blocks(function, position, language)
+        .iter()
+        .any(|header| is_joining_scope(header, language))
+    {
+        return true;
+    }
+
+    let code = function.body[position].1.trim();
+    let later = &function.body[position + 1..];
+    if language == Language::Go {
+        return later.iter().any(|(_, code)| code.contains(".Wait()"));
+    }
+    if let Some((targets, _)) = assignment(code, language) {
+        return targets
+            .iter()
+            .any(|handle| later.iter().any(|(_, code)| code.contains(&format!("{}.join(", handle))));
+    }
+    // `handles.push(thread::spawn(..))`: joined when a later line walks `handles` and a
+    // `.join(` follows on that line or after it.
+    let Some(collection) = pushed_to(code) else {
+        return false;
+    };
+    let walked = later.iter().position(|(_, code)| {
+        identifiers(code).any(|name| name == collection) && !code.contains(&format!("{}.len(", collection))
+    });
+    walked.is_some_and(|walked| {
+        later[walked..]
+            .iter()
+            .any(|(_, code)| code.contains(".join(") || code.contains("Promise.all"))
+    })
+}
+
+/// Block headers whose scope waits for what is spawned in it: `thread::scope(|s| {`,
+/// `crossbeam::scope(..)`, Java's `StructuredTaskScope`, Python executors and task groups
+/// in a `with` statement.
+fn is_joining_scope(header: &str, language: Language) -> bool {
+    match language {
+        Language::Python => {
+            header.starts_with("with ")
+                && ["Executor(", "TaskGroup(", "Pool("].iter().any(|call| header.contains(call))
+        }
+        _ => header.contains("scope(") || header.contains("StructuredTaskScope"),
+    }
+}
+
+/// The collection a spawn's handle is added to: `handles` in `handles.push(..)`.
+fn pushed_to(code: &str) -> Option<&str> {
+    [".push(", ".append(", ".add("]
+        .iter()
+        .filter_map(|call| code.find(call))
+        .min()
+        .and_then(|index| identifiers(&code[..index]).last())
+}
+
+/// `a..b` in `(a..b).map(..)` / `(a..b).for_each(..)`.
+fn range_iterator(code: &str) -> Option<&str> {
+    let dots = code.find("..")?;
+    let open = code[..dots].rfind('(')?;
+    let close = dots + code[dots..].find(')')?;
+    code[close + 1..].starts_with('.').then(|| &code[open + 1..close])
+}
+
+fn is_loop_header(header: &str, language: Language) -> bool {
+    let header = header.trim();
+    header == "for"
+        || header.starts_with("for ")
+        || header.starts_with("for(")
+        || header.starts_with("while ")
+        || header.starts_with("while(")
+        || (language == Language::Rust && (header == "loop" || header.starts_with("loop ")))
+        || (language != Language::Python && (header == "do" || header.starts_with("do ")))
+}
+
+struct Bounds<'a> {
+    language: Language,
+    constants: &'a HashMap<String, i64>,
+    taint: Option<&'a TaintReport>,
+}
+
+impl Bounds<'_> {
+    /// Combined count and growth of the loops around a line of `function`.
+    fn repetition(&self, loops: &[String], function: &FunctionDef) -> Repetition {
+        let parameters = parameter_names(&function.parameters, self.language);
+        let is_input = |name: &str| {
+            parameters.iter().any(|parameter| parameter == name)
+                || self.taint.is_some_and(|taint| taint.is_tainted(&function.name, name))
+        };
+        loops.iter().fold((Some(1), SpawnGrowth::Single), |(count, growth), header| {
+            let (iterations, loop_growth) = self.classify(header, &is_input);
+            (
+                count.zip(iterations).map(|(count, iterations)| count.saturating_mul(iterations)),
+                growth.max(loop_growth),
+            )
+        })
+    }
+
+    fn classify(&self, header: &str, is_input: &dyn Fn(&str) -> bool) -> Repetition {
+        if let Some(iterations) = self.iterations(header) {
+            return (Some(iterations), SpawnGrowth::Bounded);
+        }
+        let compact: String = header
+            .chars()
+            .filter(|ch| !ch.is_whitespace() && !matches!(ch, '(' | ')' | ':'))
+            .collect();
+        let growth = if matches!(compact.as_str(), "loop" | "for" | "for;;" | "whiletrue" | "whileTrue" | "while1") {
+            SpawnGrowth::Unbounded
+        } else if identifiers(header).any(is_input) {
+            SpawnGrowth::InputDependent
+        } else {
+            SpawnGrowth::Dynamic
+        };
+        (None, growth)
+    }
+
+    /// Iterations of a loop header with constant bounds; `None` for anything else.
+    fn iterations(&self, header: &str) -> Option<usize> {
+        let header = header.trim().trim_end_matches(':').trim();
+        let body = header
+            .strip_prefix("for")
+            .map(str::trim)
+            .unwrap_or(header);
+        let count = if let Some(arguments) = body
+            .split_once(" in range(")
+            .and_then(|(_, rest)| rest.rsplit_once(')'))
+            .map(|(arguments, _)| arguments)
+        {
+            // Python `range(stop)`, `range(start, stop)`, `range(start, stop, step)`.
+            let values = split_top_level(arguments)
+                .iter()
+                .map(|argument| self.value(argument))
+                .collect::<Option<Vec<_>>>()?;
+            match values.as_slice() {
+                [stop] => *stop,
+                [start, stop] => stop.saturating_sub(*start),
+                [start, stop, step] if *step > 0 => stop.saturating_sub(*start).saturating_add(step - 1) / step,
+                _ => return None,
+            }
+        } else if let Some(count) = self.range_expression(body) {
+            count
+        } else if let Some(count) = body.strip_prefix("range ").or_else(|| body.strip_prefix(":= range ")) {
+            // Go 1.22 `for range 5` / `for i := range 5`.
+            self.value(count)?
+        } else if let Some((_, count)) = body.split_once(":= range ") {
+            self.value(count)?
+        } else {
+            self.counting_loop(body.trim_start_matches('(').trim_end_matches(')'))?
+        };
+        usize::try_from(count.max(0)).ok()
+    }
+
+    /// Rust `a..b` / `a..=b`, optionally after `pattern in`.
+    fn range_expression(&self, body: &str) -> Option<i64> {
+        if self.language != Language::Rust {
+            return None;
+        }
+        let range = body.rsplit_once(" in ").map(|(_, range)| range).unwrap_or(body).trim();
+        let range = range.trim_start_matches('(').trim_end_matches(')');
+        let (start, end) = range.split_once("..")?;
+        match end.strip_prefix('=') {
+            Some(end) => Some(self.value(end)?.saturating_sub(self.value(start)?).saturating_add(1)),
+            None => Some(self.value(end)?.saturating_sub(self.value(start)?)),
+        }
+    }
+
+    /// C-style `init; cond; update` (Java, JavaScript, Go).
+    fn counting_loop(&self, body: &str) -> Option<i64> {
+        let parts: Vec<&str> = body.split(';').map(str::trim).collect();
+        let [init, condition, update] = parts.as_slice() else {
+            return None;
+        };
+        let (variable, start) = init.split_once('=')?;
+        let variable = variable.trim_end_matches(':').split_whitespace().last()?;
+        let start = self.value(start)?;
+
+        let (left, operator, right) = ["<=", "<", "!="]
+            .iter()
+            .find_map(|operator| condition.split_once(operator).map(|(left, right)| (left, *operator, right)))?;
+        if left.trim() != variable {
+            return None;
+        }
+        let end = self.value(right)?;
+
+        let step = if *update == format!("{}++", variable) || *update == format!("++{}", variable) {
+            1
+        } else {
+            let (target, step) = update.split_once("+=")?;
+            if target.trim() != variable {
+                return None;
+            }
+            self.value(step)?
+        };
+        if step <= 0 {
+            return None;
+        }
+        let span = end.saturating_sub(start);
+        let span = if operator == "<=" { span.saturating_add(1) } else { span };
+        Some(span.saturating_add(step - 1).div_euclid(step))
+    }
+
+    /// Integer literal or constant name.
+    fn value(&self, text: &str) -> Option<i64> {
+        let text = text.trim();
+        integer(text).or_else(|| self.constants.get(text).copied())
+    }
+}
+
+/// Names assigned exactly one integer value anywhere in the file (`const N: usize = 5;`,
+/// `MAX_WORKERS = 4`, `final int COUNT = 3;`, `n := 2`).
+fn constants(source: &str, language: Language) -> HashMap<String, i64> {
+    let mut values: HashMap<String, Option<i64>> = HashMap::new();
+    for line in source.lines() {
+        let code = line.trim();
+        if code.starts_with("for") || ["+=", "-=", "*=", "/=", "++", "--"].iter().any(|op| code.contains(op)) {
+            continue;
+        }
+        let Some((targets, value)) = assignment(code, language) else {
+            continue;
+        };
+        let value = integer(value.trim().trim_end_matches(';'));
+        for target in targets {
+            let entry = values.entry(target).or_insert(value);
+            if *entry != value {
+                *entry = None;
+            }
+        }
+    }
+    values.into_iter().filter_map(|(name, value)| Some((name, value?))).collect()
+}
+
+/// Integer literal, allowing `_` separators and type suffixes (`1_000`, `5usize`, `3L`).
+fn integer(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let digits: String = text
+        .chars()
+        .take_while(|ch| ch.is_ascii_digit() || *ch == '_')
+        .filter(|ch| *ch != '_')
+        .collect();
+    let suffix = &text[text.find(|ch: char| !ch.is_ascii_digit() && ch != '_').unwrap_or(text.len())..];
+    let valid_suffix = suffix.is_empty()
+        || suffix.chars().all(|ch| ch.is_ascii_alphanumeric())
+            && suffix.starts_with(['u', 'i', 'L', 'l']);
+    (!digits.is_empty() && valid_suffix).then(|| digits.parse().ok()).flatten()
+}
@@ -0,0 +1,292 @@
+//! Suppressions
+//! Findings can be silenced with a `graphene:ignore [category ...]` comment on the
+//! flagged line, the line above it, or the enclosing function's definition, and with a
+//! `.grapheneignore` file of `path[:function] [category ...]` patterns. Categories are
+//! escape types (`global`, `closure`, ...), rule names (`recursive-spawn`) or catalog
+//! ids (`GH-GEN-002`), `concurrency` or `descriptor`; none means every finding. Suppressed findings are dropped from the
+//! results and counted in the summary instead.
+
+use crate::protocol::{AnalyzeResponse, EscapeType, StaticAnalysisResult, StaticEscape, StaticEscapeSummary};
+use crate::static_analyzer::callgraph::{extract_functions, target_function, Language, CONCURRENCY_WORDS};
+use std::path::{Path, PathBuf};
+
+/// Marker that starts an inline suppression comment.
+const MARKER: &str = "graphene:ignore";
+
+/// Name of the ignore file, looked up from the source file's directory upwards.
+pub const IGNORE_FILE: &str = ".grapheneignore";
+
+/// What a suppression covers; an empty list covers everything.
+#[derive(Debug, Clone, Default)]
+struct Categories(Vec<String>);
+
+impl Categories {
+    fn parse<'a>(words: impl Iterator<Item = &'a str>) -> Self {
+        Self(
+            words
+                .flat_map(|word| word.split(','))
+                .map(|word| word.trim().to_lowercase())
+                .map(|word| word.strip_suffix("-escape").map(str::to_string).unwrap_or(word))
+                .filter(|word| !word.is_empty())
+                .collect(),
+        )
+    }
+
+    fn all(&self) -> bool {
+        self.0.is_empty() || self.0.iter().any(|category| category == "all")
+    }
+
+    fn has(&self, category: &str) -> bool {
+        self.all() || self.0.iter().any(|own| own == category)
+    }
+
+    fn covers_static(&self, escape: &StaticEscape) -> bool {
+        let escape_type = match escape.escape_type {
+            EscapeType::ReturnEscape => "return",
+            EscapeType::ParameterEscape => "parameter",
+            EscapeType::GlobalEscape => "global",
+            EscapeType::ClosureEscape => "closure",
+            EscapeType::HeapEscape => "heap",
+            EscapeType::SpawnEscape => "spawn",
+            EscapeType::ExitEscape => "exit",
+            EscapeType::UnknownEscape => "unknown",
+        };
+        let reason = escape.reason.to_lowercase();
+        let concurrent = matches!(escape.escape_type, EscapeType::ClosureEscape | EscapeType::SpawnEscape)
+            || CONCURRENCY_WORDS.iter().any(|word| reason.contains(word));
+        self.has(escape_type)
+            || escape.rule.as_deref().is_some_and(|rule| self.has(rule))
+            || escape.rule_id.as_deref().is_some_and(|id| self.has(&id.to_lowercase()))
+            || (concurrent && self.has("concurrency"))
+    }
+}
+
+/// One `.grapheneignore` line.
+#[derive(Debug, Clone)]
+struct IgnorePattern {
+    path: String,
+    function: String,
+    categories: Categories,
+}
+
+/// Suppressions that apply to one source file.
+pub struct Suppressions {
+    lines: Vec<String>,
+    /// Function names and their definition lines
+    definitions: Vec<(String, usize)>,
+    /// Path of the source file relative to the ignore file's directory
+    relative_path: String,
+    patterns: Vec<IgnorePattern>,
+}
+
+impl Suppressions {
+    /// Inline comments of `source_file` and the nearest `.grapheneignore` above it.
+    /// Unreadable files contribute nothing.
+    pub fn load(language: &str, source_file: &str) -> Self {
+        let source = std::fs::read_to_string(source_file).unwrap_or_default();
+        let path = std::fs::canonicalize(source_file).unwrap_or_else(|_| PathBuf::from(source_file));
+        let (relative_path, patterns) = match find_ignore_file(&path) {
+            Some(ignore_file) => {
+                let base = ignore_file.parent().unwrap_or(Path::new(""));
+                let relative = path.strip_prefix(base).unwrap_or(&path);
+                let text = std::fs::read_to_string(&ignore_file).unwrap_or_default();
+                (relative.to_string_lossy().replace('\\', "/"), parse_ignore_file(&text))
+            }
+            None => (source_file.replace('\\', "/"), Vec::new()),
+        };
+        let definitions = Language::from_name(language)
+            .map(|language| extract_functions(&source, language))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|def| (def.name, def.line))
+            .collect();
+        Self {
+            lines: source.lines().map(str::to_string).collect(),
+            definitions,
+            relative_path,
+            patterns,
+        }
+    }
+
+    /// Moves suppressed static escapes out of `result`, rebuilding its summary.
+    pub fn apply_static(&self, result: &mut StaticAnalysisResult) {
+        let escapes = std::mem::take(&mut result.escapes);
+        let (suppressed, kept): (Vec<StaticEscape>, Vec<StaticEscape>) = escapes
+            .into_iter()
+            .partition(|escape| self.suppresses_static(escape));
+        if suppressed.is_empty() {
+            result.escapes = kept;
+            return;
+        }
+        let mut summary = StaticEscapeSummary::new();
+        for escape in &kept {
+            summary.add_escape(escape);
+        }
+        summary.suppressed = result.summary.suppressed + suppressed.len();
+        result.summary = summary;
+        result.escapes = kept;
+    }
+
+    /// Clears the escapes of executions of `target` that a suppression covers, drops
+    /// their escape vulnerabilities and updates the summary counts.
+    pub fn apply_dynamic(&self, response: &mut AnalyzeResponse, target: &str) {
+        let function = target_function(target);
+        let categories = self.function_categories(function);
+        if categories.is_empty() {
+            return;
+        }
+        let covers = |details: &crate::protocol::EscapeDetails| {
+            categories.iter().any(|categories| {
+                if categories.all() {
+                    return true;
+                }
+                let spawned = details.spawned_count().unwrap_or(0) > 0;
+                let leaked = !details.leaked_descriptors().is_empty();
+                let by_type = details
+                    .escape_paths
+                    .iter()
+                    .any(|path| categories.has(&path.escape_type.to_lowercase().replace("escape", "")));
+                (spawned && categories.has("concurrency")) || (leaked && categories.has("descriptor")) || by_type
+            })
+        };
+
+        let mut suppressed = 0;
+        let mut genuine = 0;
+        for result in response.results.iter_mut().filter(|r| r.escape_detected) {
+            if covers(&result.escape_details) {
+                result.escape_detected = false;
+                suppressed += 1;
+                if result.error.as_ref().is_none_or(|e| !e.kind.is_timeout()) {
+                    genuine += 1;
+                }
+            }
+        }
+        if suppressed == 0 {
+            return;
+        }
+        let results = &response.results;
+        response.vulnerabilities.retain(|vuln| {
+            vuln.vulnerability_type != "object_escape"
+                || results.iter().any(|r| r.input_data == vuln.input && r.escape_detected)
+        });
+        let summary = &mut response.summary;
+        summary.escapes = summary.escapes.saturating_sub(suppressed);
+        summary.genuine_escapes = summary.genuine_escapes.saturating_sub(genuine);
+        summary.suppressed += suppressed;
+    }
+
+    fn suppresses_static(&self, escape: &StaticEscape) -> bool {
+        let line = escape.location.line;
+        let mut inline = vec![line, line.saturating_sub(1)];
+        inline.extend(self.definition_lines(&escape.location.function));
+        let by_comment = inline
+            .into_iter()
+            .filter_map(|line| self.annotation(line))
+            .any(|categories| categories.covers_static(escape));
+        by_comment
+            || self
+                .matching_patterns(&escape.location.function)
+                .any(|categories| categories.covers_static(escape))
+    }
+
+    /// Categories suppressed for the whole of `function`, by comment or ignore file.
+    fn function_categories(&self, function: &str) -> Vec<Categories> {
+        let mut categories: Vec<Categories> = self
+            .definition_lines(function)
+            .into_iter()
+            .filter_map(|line| self.annotation(line))
+            .collect();
+        categories.extend(self.matching_patterns(function).cloned());
+        categories
+    }
+
+    /// Whether an ignore-file pattern without a function or categories covers the whole
+    /// file, so `scan` can skip it.
+    pub fn ignores_file(&self) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.function == "*" && pattern.categories.all() && self.path_matches(pattern))
+    }
+
+    fn matching_patterns<'a>(&'a self, function: &'a str) -> impl Iterator<Item = &'a Categories> + 'a {
+        self.patterns
+            .iter()
+            .filter(move |pattern| self.path_matches(pattern) && glob_match(&pattern.function, function))
+            .map(|pattern| &pattern.categories)
+    }
+
+    fn path_matches(&self, pattern: &IgnorePattern) -> bool {
+        let file_name = self.relative_path.rsplit('/').next().unwrap_or_default();
+        let path = if pattern.path.contains('/') { self.relative_path.as_str() } else { file_name };
+        glob_match(&pattern.path, path)
+    }
+
+    /// The definition line of `function` and the line above it.
+    fn definition_lines(&self, function: &str) -> Vec<usize> {
+        let name = function.rsplit(['.', ':']).next().unwrap_or(function);
+        self.definitions
+            .iter()
+            .filter(|(defined, _)| defined == name)
+            .flat_map(|(_, line)| [*line, line.saturating_sub(1)])
+            .collect()
+    }
+
+    /// Categories of a `graphene:ignore` comment on a 1-based line.
+    fn annotation(&self, line: usize) -> Option<Categories> {
+        let text = self.lines.get(line.checked_sub(1)?)?;
+        let (_, rest) = text.split_once(MARKER)?;
+        // Stop at the end of a block comment.
+        let rest = rest.split("*/").next().unwrap_or(rest).split("-->").next().unwrap_or(rest);
+        Some(Categories::parse(rest.split_whitespace()))
+    }
+}
+
+fn find_ignore_file(source: &Path) -> Option<PathBuf> {
+    let start = source.parent()?;
+    start
+        .ancestors()
+        .map(|dir| dir.join(IGNORE_FILE))
+        .find(|candidate| candidate.is_file())
+        .or_else(|| Some(PathBuf::from(IGNORE_FILE)).filter(|candidate| candidate.is_file()))
+}
+
+/// `path[:function] [category ...]` per line; `#` starts a comment.
+fn parse_ignore_file(text: &str) -> Vec<IgnorePattern> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let spec = words.next()?;
+            let (path, function) = spec.split_once(':').unwrap_or((spec, "*"));
+            Some(IgnorePattern {
+                path: path.trim_start_matches("./").to_string(),
+                function: function.to_string(),
+                categories: Categories::parse(words),
+            })
+        })
+        .collect()
+}
+
+/// Glob match where `*` stays within a path segment, `**` crosses segments and `?`
+/// matches one character.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    fn matches(pattern: &[char], text: &[char]) -> bool {
+        match pattern.first() {
+            None => text.is_empty(),
+            Some('*') if pattern.get(1) == Some(&'*') => {
+                let rest = pattern[2..].strip_prefix(&['/']).unwrap_or(&pattern[2..]);
+                (0..=text.len()).any(|skip| matches(rest, &text[skip..]))
+            }
+            Some('*') => {
+                let segment = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+                (0..=segment).any(|skip| matches(&pattern[1..], &text[skip..]))
+            }
+            Some('?') => !text.is_empty() && text[0] != '/' && matches(&pattern[1..], &text[1..]),
+            Some(&c) => text.first() == Some(&c) && matches(&pattern[1..], &text[1..]),
+        }
+    }
+    matches(&pattern, &text)
+}
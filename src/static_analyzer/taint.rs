@@ -0,0 +1,241 @@
+//! Input taint tracking
+//! Follows the target function's parameters through local assignments, mutating calls
+//! and calls into functions of the same file, and records the spawn statements that
+//! capture them. Escapes that carry caller data are riskier (use after scope, data
+//! races) than ones that only involve objects the spawned code made itself.
+
+use crate::protocol::StaticEscape;
+use crate::static_analyzer::callgraph::{
+    extract_calls, extract_functions, identifiers, is_identifier, last_segment, split_top_level,
+    statement_end, target_function, FunctionDef, Language,
+};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+/// Upper bound on function re-visits while parameter taint settles.
+const MAX_VISITS: usize = 256;
+
+/// Tainted variables per function, each with the steps that carried input into it.
+pub struct TaintReport {
+    functions: HashMap<String, FunctionTaint>,
+}
+
+#[derive(Default)]
+struct FunctionTaint {
+    variables: HashMap<String, Vec<String>>,
+    /// Spawn statements as (first line, last line, captured tainted variable).
+    spawn_captures: Vec<(usize, usize, String)>,
+}
+
+impl TaintReport {
+    /// Whether `variable` in `function` carries the target's input.
+    pub fn is_tainted(&self, function: &str, variable: &str) -> bool {
+        self.functions
+            .get(function)
+            .is_some_and(|taint| taint.variables.contains_key(variable))
+    }
+
+    /// Marks `escape`, found in `function`, as input-tainted when its variable carries
+    /// input or it sits in a spawn statement that captures such a variable.
+    pub fn annotate(&self, escape: &mut StaticEscape, function: &str) {
+        let Some(taint) = self.functions.get(function) else {
+            return;
+        };
+        let line = escape.location.line;
+        let flow = if let Some(path) = taint.variables.get(&escape.variable_name) {
+            let mut flow = path.clone();
+            flow.push(format!("escapes at line {}", line));
+            flow
+        } else if let Some((start, _, variable)) = taint
+            .spawn_captures
+            .iter()
+            .find(|(start, end, _)| (*start..=*end).contains(&line))
+        {
+            let mut flow = taint.variables[variable].clone();
+            flow.push(format!("`{}` captured by the spawn at line {}", variable, start));
+            flow
+        } else {
+            return;
+        };
+        escape.input_tainted = true;
+        escape.taint_flow = flow;
+    }
+}
+
+/// Traces the target's parameters through its source file. `None` when the language
+/// is unsupported or the target function cannot be found.
+pub fn trace(language: &str, target: &str, source_file: &str) -> Option<TaintReport> {
+    let language = Language::from_name(language)?;
+    let source = std::fs::read_to_string(source_file).ok()?;
+    let functions = extract_functions(&source, language);
+    let mut defined: HashMap<&str, &FunctionDef> = HashMap::new();
+    for function in &functions {
+        defined.entry(function.name.as_str()).or_insert(function);
+    }
+    let root = *defined.get(target_function(target))?;
+
+    let mut seeds: HashMap<String, HashMap<String, Vec<String>>> = HashMap::new();
+    seeds.insert(
+        root.name.clone(),
+        parameter_names(&root.parameters, language)
+            .into_iter()
+            .map(|name| {
+                let step = format!("parameter `{}` of {}", name, root.name);
+                (name, vec![step])
+            })
+            .collect(),
+    );
+
+    let mut report = TaintReport { functions: HashMap::new() };
+    let mut queue = VecDeque::from([root.name.clone()]);
+    let mut visits = 0;
+    while let Some(name) = queue.pop_front() {
+        visits += 1;
+        if visits > MAX_VISITS {
+            break;
+        }
+        let function = defined[name.as_str()];
+        let (taint, calls) = trace_function(function, seeds[&name].clone(), language);
+
+        // Tainted arguments taint the matching parameter of local callees.
+        for (callee, position, path) in calls {
+            let Some(callee_def) = defined.get(callee.as_str()) else {
+                continue;
+            };
+            let Some(parameter) = parameter_names(&callee_def.parameters, language).into_iter().nth(position) else {
+                continue;
+            };
+            let callee_seeds = seeds.entry(callee_def.name.clone()).or_default();
+            if let Entry::Vacant(entry) = callee_seeds.entry(parameter) {
+                let mut path = path;
+                path.push(format!("passed to `{}` as `{}`", callee_def.name, entry.key()));
+                entry.insert(path);
+                queue.push_back(callee_def.name.clone());
+            }
+        }
+        report.functions.insert(name, taint);
+    }
+    Some(report)
+}
+
+/// One pass over a function body. Returns its taint and the local calls that receive
+/// tainted arguments, as (callee, argument position, path so far).
+fn trace_function(
+    function: &FunctionDef,
+    seeds: HashMap<String, Vec<String>>,
+    language: Language,
+) -> (FunctionTaint, Vec<(String, usize, Vec<String>)>) {
+    let mut taint = FunctionTaint {
+        variables: seeds,
+        spawn_captures: Vec::new(),
+    };
+    let mut tainted_calls = Vec::new();
+    let first_tainted = |code: &str, variables: &HashMap<String, Vec<String>>| {
+        identifiers(code)
+            .find(|identifier| variables.contains_key(*identifier))
+            .map(str::to_string)
+    };
+
+    for (index, (line, code)) in function.body.iter().enumerate() {
+        if let Some((targets, value)) = assignment(code, language) {
+            if let Some(source) = first_tainted(&value, &taint.variables) {
+                for target in targets {
+                    if !taint.variables.contains_key(&target) {
+                        let mut path = taint.variables[&source].clone();
+                        path.push(format!("`{}` ← `{}` (line {})", target, source, line));
+                        taint.variables.insert(target, path);
+                    }
+                }
+            }
+        }
+
+        for call in extract_calls(code, language) {
+            // `payload.put(key, input)` makes `payload` carry the input too.
+            if let Some((receiver, _)) = call.chain.split_once(['.', ':']) {
+                let tainted_argument = call
+                    .arguments
+                    .iter()
+                    .find_map(|argument| first_tainted(argument, &taint.variables));
+                if let Some(source) = tainted_argument {
+                    if is_identifier(receiver) && !taint.variables.contains_key(receiver) {
+                        let mut path = taint.variables[&source].clone();
+                        path.push(format!("`{}` stored into `{}` (line {})", source, receiver, line));
+                        taint.variables.insert(receiver.to_string(), path);
+                    }
+                }
+            }
+            for (position, argument) in call.arguments.iter().enumerate() {
+                if let Some(source) = first_tainted(argument, &taint.variables) {
+                    tainted_calls.push((
+                        last_segment(&call.chain).to_string(),
+                        position,
+                        taint.variables[&source].clone(),
+                    ));
+                }
+            }
+        }
+
+        if language.is_spawn_site(code) {
+            let end = statement_end(&function.body, index);
+            let statement: Vec<&str> = function.body[index..=end].iter().map(|(_, code)| code.as_str()).collect();
+            if let Some(variable) = first_tainted(&statement.join("\n"), &taint.variables) {
+                taint.spawn_captures.push((*line, function.body[end].0, variable));
+            }
+        }
+    }
+    (taint, tainted_calls)
+}
+
+/// Variables assigned on a line and the assigned expression, for plain assignments
+/// and declarations (`x = e`, `let x = e`, `x := e`, `Type x = e`, `a, b = e`).
+pub(super) fn assignment(code: &str, language: Language) -> Option<(Vec<String>, String)> {
+    let chars: Vec<char> = code.chars().collect();
+    let position = (0..chars.len()).find(|&index| {
+        chars[index] == '='
+            && chars.get(index + 1).is_none_or(|next| !matches!(next, '=' | '>'))
+            && (index == 0 || !matches!(chars[index - 1], '=' | '!' | '<' | '>'))
+    })?;
+    let target: String = chars[..position].iter().collect();
+    let value: String = chars[position + 1..].iter().collect();
+    let target = target.trim().trim_end_matches([':', '+', '-', '*', '/', '|', '&']);
+    if target.contains('(') {
+        return None;
+    }
+
+    let targets: Vec<String> = if language == Language::Java {
+        // `Map<String, String> payload`: the declared name is the last token.
+        target.split_whitespace().last().into_iter().map(str::to_string).collect()
+    } else {
+        split_top_level(target)
+            .iter()
+            .filter_map(|part| {
+                let part = part.split(':').next().unwrap_or(part);
+                part.split_whitespace()
+                    .rfind(|word| !matches!(*word, "let" | "mut" | "const" | "var" | "final"))
+                    .map(str::to_string)
+            })
+            .collect()
+    };
+    let targets: Vec<String> = targets.into_iter().filter(|name| is_identifier(name)).collect();
+    (!targets.is_empty()).then_some((targets, value))
+}
+
+/// Parameter names in declaration order; receivers (`self`, `cls`, `&self`) are skipped.
+pub(super) fn parameter_names(parameters: &str, language: Language) -> Vec<String> {
+    // Go shares one type between names (`a, b string`), so its name is the first token.
+    split_top_level(parameters)
+        .iter()
+        .filter_map(|part| {
+            let part = part.trim_start_matches("...").trim_start_matches('*');
+            let name = match language {
+                Language::Java => part.split_whitespace().last()?,
+                Language::Go => part.split_whitespace().next()?,
+                _ => {
+                    let head = part.split([':', '=']).next()?.trim();
+                    head.trim_start_matches('&').trim_start_matches("mut ").trim()
+                }
+            };
+            (is_identifier(name) && !matches!(name, "self" | "cls" | "mut")).then(|| name.to_string())
+        })
+        .collect()
+}
@@ -0,0 +1,558 @@
+//! Go WaitGroup balance checking
+//! Matches `wg.Add(n)` in the target function against the goroutines that call
+//! `wg.Done()`. Too few `Done` calls (a goroutine that never calls it, or skips it on an
+//! early `return`) leave `wg.Wait()` blocked forever; too many make the counter go
+//! negative or let `Wait` return while goroutines are still running. Goroutines in a
+//! balanced group the target waits on are provably awaited, as are those started with
+//! an errgroup's `Go` when the target calls its `Wait`.
+
+use crate::protocol::{ConfidenceLevel, SourceLocation, StaticEscape};
+use crate::static_analyzer::callgraph::{
+    extract_calls, extract_functions, identifiers, is_identifier, last_segment, split_top_level,
+    statement_end, FunctionDef, Language,
+};
+use crate::static_analyzer::go::classify_go_async_escape;
+use crate::static_analyzer::rules::{self, Rule};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Catalog rule of these findings.
+const RULE: Rule = rules::GO_WAITGROUP_IMBALANCE;
+
+/// How a goroutine body reaches `wg.Done()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DoneStatus {
+    /// Deferred, or called after every `return`.
+    Always,
+    /// Called at the end of the body, but the `return` on this line skips it.
+    SkippedByReturn(usize),
+    /// Only called inside a branch starting on this line.
+    Conditional(usize),
+    Missing,
+    /// The goroutine runs a function defined elsewhere.
+    Unknown,
+}
+
+/// A `go` statement that takes part in a WaitGroup.
+struct Goroutine {
+    line: usize,
+    code: String,
+    /// Innermost enclosing `for` loop, by header line.
+    group: Option<usize>,
+    done: DoneStatus,
+    /// Calls `wg.Add` itself, so it is left out of the count comparison.
+    adds_itself: bool,
+}
+
+#[derive(Default)]
+struct WaitGroupUse {
+    /// `wg.Add(n)` outside goroutines: line, innermost `for` loop and the count if literal.
+    adds: Vec<(usize, Option<usize>, Option<usize>)>,
+    /// `wg.Add` inside a goroutine body: its line and the `go` statement's line.
+    adds_in_goroutine: Vec<(usize, usize)>,
+    goroutines: Vec<Goroutine>,
+    waited: bool,
+}
+
+/// An `errgroup.Group` declared in the target.
+#[derive(Default)]
+struct ErrGroupUse {
+    declared: usize,
+    /// Lines of `g.Go(...)` calls
+    calls: Vec<usize>,
+    waited: bool,
+}
+
+/// How a goroutine started by the target is waited for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Awaited {
+    /// On every path: a `Wait()` on a group whose `Add` and `Done` calls balance.
+    Always(String),
+    /// A `Wait()` covers it, but the group's counts or `Done` calls may not balance.
+    Partially(String),
+}
+
+/// WaitGroup imbalance findings for the target function, and errgroups it never waits on.
+pub fn check(source: &str, source_file: &str, function_name: &str) -> Vec<StaticEscape> {
+    let mut findings = Vec::new();
+    let Some((parameter_groups, uses, errgroups)) = collect_uses(source, function_name) else {
+        return findings;
+    };
+    for (name, wait_group) in &uses {
+        findings.extend(waitgroup_findings(name, wait_group, !parameter_groups.contains(name), source_file, function_name));
+    }
+    for (name, group) in &errgroups {
+        if group.waited || group.calls.is_empty() {
+            continue;
+        }
+        let reason = format!(
+            "errgroup '{}' starts {} goroutine(s) with {}.Go but is never waited on; they outlive the function",
+            name,
+            group.calls.len(),
+            name
+        );
+        findings.push(finding(source_file, function_name, group.declared, name, reason, ConfidenceLevel::High, None));
+    }
+    findings
+}
+
+/// How each `go` statement in the target is waited for, by line. Goroutines that take
+/// part in no group that the target waits on are left out.
+pub fn awaited_goroutines(source: &str, function_name: &str) -> HashMap<usize, Awaited> {
+    let mut awaited = HashMap::new();
+    let Some((parameter_groups, uses, _)) = collect_uses(source, function_name) else {
+        return awaited;
+    };
+    for (name, wait_group) in uses.iter().filter(|(_, wait_group)| wait_group.waited) {
+        let wait = format!("{}.Wait()", name);
+        let balanced = waitgroup_findings(name, wait_group, !parameter_groups.contains(name), "", function_name).is_empty()
+            && wait_group.adds_in_goroutine.is_empty();
+        for goroutine in &wait_group.goroutines {
+            let status = if balanced && goroutine.done == DoneStatus::Always {
+                Awaited::Always(wait.clone())
+            } else {
+                Awaited::Partially(wait.clone())
+            };
+            // A goroutine fully covered by any one group is awaited.
+            match awaited.get(&goroutine.line) {
+                Some(Awaited::Always(_)) => {}
+                _ => {
+                    awaited.insert(goroutine.line, status);
+                }
+            }
+        }
+    }
+    awaited
+}
+
+type Uses = (Vec<String>, BTreeMap<String, WaitGroupUse>, BTreeMap<String, ErrGroupUse>);
+
+/// The target's WaitGroup parameters and how it uses each WaitGroup and errgroup.
+fn collect_uses(source: &str, function_name: &str) -> Option<Uses> {
+    let functions = extract_functions(source, Language::Go);
+    let target = functions.iter().find(|function| function.name == function_name)?;
+    let defined: HashMap<&str, &FunctionDef> =
+        functions.iter().map(|function| (function.name.as_str(), function)).collect();
+
+    let mut errgroups: BTreeMap<String, ErrGroupUse> = BTreeMap::new();
+    for (line, code) in &target.body {
+        if let Some(name) = declared_errgroup(code) {
+            errgroups.insert(name, ErrGroupUse { declared: *line, ..Default::default() });
+        }
+        for (name, group) in errgroups.iter_mut() {
+            if call_argument(code, name, "Go").is_some() {
+                group.calls.push(*line);
+            }
+            if code.contains(&format!("{}.Wait()", name)) {
+                group.waited = true;
+            }
+        }
+    }
+
+    let parameter_groups = waitgroup_parameters(&target.parameters);
+    let mut names: HashSet<String> = parameter_groups.iter().cloned().collect();
+    for line in source.lines().filter(|line| line.starts_with("var ")) {
+        names.extend(declared_waitgroup(line));
+    }
+    for (_, code) in &target.body {
+        names.extend(declared_waitgroup(code));
+    }
+    if names.is_empty() {
+        return Some((parameter_groups, BTreeMap::new(), errgroups));
+    }
+
+    let mut uses: BTreeMap<String, WaitGroupUse> = BTreeMap::new();
+    // Open blocks: header line when the block is a `for` loop.
+    let mut blocks: Vec<Option<usize>> = Vec::new();
+    let mut index = 0;
+    while index < target.body.len() {
+        let (line, code) = &target.body[index];
+        let trimmed = code.trim();
+        let group = blocks.iter().rev().find_map(|block| *block);
+
+        if let Some(spawned) = trimmed.strip_prefix("go ") {
+            let end = statement_end(&target.body, index);
+            let statement = &target.body[index..=end];
+            for name in &names {
+                let Some(done) = goroutine_done(name, spawned, statement, &defined) else {
+                    continue;
+                };
+                let add = format!("{}.Add(", name);
+                let adds: Vec<(usize, usize)> = statement
+                    .iter()
+                    .filter(|(_, code)| code.contains(&add))
+                    .map(|(add_line, _)| (*add_line, *line))
+                    .collect();
+                let entry = uses.entry(name.clone()).or_default();
+                entry.goroutines.push(Goroutine {
+                    line: *line,
+                    code: trimmed.to_string(),
+                    group,
+                    done,
+                    adds_itself: !adds.is_empty(),
+                });
+                entry.adds_in_goroutine.extend(adds);
+            }
+            index = end + 1;
+            continue;
+        }
+
+        for name in &names {
+            if let Some(count) = call_argument(trimmed, name, "Add") {
+                uses.entry(name.clone()).or_default().adds.push((*line, group, count.parse().ok()));
+            }
+            if trimmed.contains(&format!("{}.Wait()", name)) {
+                uses.entry(name.clone()).or_default().waited = true;
+            }
+        }
+
+        let mut opened_header = false;
+        for ch in code.chars() {
+            match ch {
+                '{' => {
+                    let is_loop = !opened_header && (trimmed.starts_with("for ") || trimmed == "for {");
+                    blocks.push(is_loop.then_some(*line));
+                    opened_header = true;
+                }
+                '}' => {
+                    blocks.pop();
+                }
+                _ => {}
+            }
+        }
+        index += 1;
+    }
+
+    Some((parameter_groups, uses, errgroups))
+}
+
+/// Findings for one WaitGroup; `local` when the target declares it rather than
+/// receiving it.
+fn waitgroup_findings(
+    name: &str,
+    wait_group: &WaitGroupUse,
+    local: bool,
+    source_file: &str,
+    function_name: &str,
+) -> Vec<StaticEscape> {
+    let mut findings = Vec::new();
+    report_goroutines(name, wait_group, source_file, function_name, &mut findings);
+    report_counts(name, wait_group, source_file, function_name, &mut findings);
+
+    for (line, go_line) in &wait_group.adds_in_goroutine {
+        let reason = format!(
+            "{}.Add is called inside the goroutine started at line {}; {}.Wait() can unblock before it runs",
+            name, go_line, name
+        );
+        findings.push(finding(source_file, function_name, *line, name, reason, ConfidenceLevel::Medium, None));
+    }
+
+    let counted = wait_group.goroutines.iter().any(|goroutine| goroutine.done != DoneStatus::Missing);
+    if local && !wait_group.waited && counted {
+        let line = wait_group
+            .adds
+            .first()
+            .map(|(line, _, _)| *line)
+            .unwrap_or(wait_group.goroutines[0].line);
+        let reason = format!(
+            "WaitGroup '{}' is never waited on; its goroutines outlive the function",
+            name
+        );
+        findings.push(finding(source_file, function_name, line, name, reason, ConfidenceLevel::Medium, None));
+    }
+    findings
+}
+
+/// Goroutines that skip `Done` on some path or never call it.
+fn report_goroutines(
+    name: &str,
+    wait_group: &WaitGroupUse,
+    source_file: &str,
+    function_name: &str,
+    findings: &mut Vec<StaticEscape>,
+) {
+    for goroutine in &wait_group.goroutines {
+        let (reason, confidence) = match goroutine.done {
+            DoneStatus::SkippedByReturn(line) => (
+                format!(
+                    "Goroutine skips {}.Done() when it exits early at line {}; {}.Wait() blocks forever on that path",
+                    name, line, name
+                ),
+                ConfidenceLevel::High,
+            ),
+            DoneStatus::Conditional(line) => (
+                format!(
+                    "Goroutine only calls {}.Done() inside the branch at line {}; {}.Wait() blocks forever on other paths",
+                    name, line, name
+                ),
+                ConfidenceLevel::Medium,
+            ),
+            DoneStatus::Missing => (
+                format!(
+                    "Goroutine uses WaitGroup '{}' but never calls {}.Done(); {}.Wait() blocks forever",
+                    name, name, name
+                ),
+                ConfidenceLevel::High,
+            ),
+            DoneStatus::Always | DoneStatus::Unknown => continue,
+        };
+        findings.push(finding(
+            source_file,
+            function_name,
+            goroutine.line,
+            name,
+            reason,
+            confidence,
+            Some(goroutine.code.clone()),
+        ));
+    }
+}
+
+/// Literal `Add` counts against the goroutines calling `Done`, per loop body. Skipped
+/// when the counts are not literal or the `Add` and the goroutines sit in different loops
+/// (`wg.Add(len(items))` before `for _, item := range items`).
+fn report_counts(
+    name: &str,
+    wait_group: &WaitGroupUse,
+    source_file: &str,
+    function_name: &str,
+    findings: &mut Vec<StaticEscape>,
+) {
+    if wait_group.adds.is_empty()
+        || wait_group.adds.iter().any(|(_, _, count)| count.is_none())
+        || wait_group.goroutines.iter().any(|goroutine| goroutine.done == DoneStatus::Unknown)
+    {
+        return;
+    }
+    let mut added: BTreeMap<Option<usize>, (usize, usize)> = BTreeMap::new();
+    for (line, group, count) in &wait_group.adds {
+        let entry = added.entry(*group).or_insert((*line, 0));
+        entry.1 += count.unwrap_or_default();
+    }
+    let mut done: BTreeMap<Option<usize>, usize> = BTreeMap::new();
+    for goroutine in wait_group.goroutines.iter().filter(|goroutine| !goroutine.adds_itself) {
+        let calls = usize::from(goroutine.done != DoneStatus::Missing);
+        *done.entry(goroutine.group).or_default() += calls;
+    }
+    let done_groups: HashSet<Option<usize>> =
+        done.iter().filter(|(_, calls)| **calls > 0).map(|(group, _)| *group).collect();
+    if !done_groups.iter().all(|group| added.contains_key(group)) {
+        return;
+    }
+
+    for (group, (line, count)) in &added {
+        let calls = done.get(group).copied().unwrap_or_default();
+        let per = if group.is_some() { " per loop iteration" } else { "" };
+        let (reason, confidence) = if count > &calls {
+            let outcome = if wait_group.waited {
+                format!("{}.Wait() blocks forever", name)
+            } else {
+                "the counter never reaches zero".to_string()
+            };
+            (
+                format!(
+                    "{}.Add counts {} goroutine(s){} but only {} call {}.Done(); {}",
+                    name, count, per, calls, name, outcome
+                ),
+                if wait_group.waited { ConfidenceLevel::High } else { ConfidenceLevel::Medium },
+            )
+        } else if count < &calls {
+            (
+                format!(
+                    "{} goroutine(s){} call {}.Done() but {}.Add only counts {}; the counter goes negative or {}.Wait() unblocks while goroutines still run",
+                    calls, per, name, name, count, name
+                ),
+                ConfidenceLevel::High,
+            )
+        } else {
+            continue;
+        };
+        findings.push(finding(source_file, function_name, *line, name, reason, confidence, None));
+    }
+}
+
+/// How the goroutine started by `go <spawned>` reaches `name.Done()`, or `None` when
+/// it does not take part in the WaitGroup.
+fn goroutine_done(
+    name: &str,
+    spawned: &str,
+    statement: &[(usize, String)],
+    defined: &HashMap<&str, &FunctionDef>,
+) -> Option<DoneStatus> {
+    if spawned.trim_start().starts_with("func") {
+        let mentions = statement.iter().any(|(_, code)| mentions(code, name));
+        if !mentions {
+            return None;
+        }
+        // `go func(wg *sync.WaitGroup) { ... }(&wg)` renames the group.
+        let header = &statement[0].1;
+        let parameters = header
+            .split_once("func")
+            .and_then(|(_, rest)| rest.trim_start().strip_prefix('('))
+            .and_then(|rest| rest.split_once(')'))
+            .map(|(parameters, _)| parameters)
+            .unwrap_or_default();
+        let inner = waitgroup_parameters(parameters).into_iter().next().unwrap_or_else(|| name.to_string());
+        if statement.len() == 1 {
+            return Some(if header.contains(&format!("{}.Done()", inner)) {
+                DoneStatus::Always
+            } else {
+                DoneStatus::Missing
+            });
+        }
+        return Some(done_status(&inner, &statement[1..]));
+    }
+
+    let call = extract_calls(spawned, Language::Go).into_iter().next()?;
+    let position = call
+        .arguments
+        .iter()
+        .position(|argument| argument.trim().trim_start_matches('&') == name);
+    let Some(function) = defined.get(last_segment(&call.chain)) else {
+        return position.map(|_| DoneStatus::Unknown);
+    };
+    // The group arrives as a parameter, or the function uses a package-level group.
+    let inner = match position {
+        Some(position) => split_top_level(&function.parameters.replace("<-", "  "))
+            .get(position)
+            .and_then(|parameter| parameter.split_whitespace().next().map(str::to_string))?,
+        None if function.body.iter().any(|(_, code)| mentions(code, name)) => name.to_string(),
+        None => return None,
+    };
+    Some(done_status(&inner, &function.body))
+}
+
+/// `Done` coverage of a goroutine body whose first line sits one block deep.
+fn done_status(name: &str, body: &[(usize, String)]) -> DoneStatus {
+    let done = format!("{}.Done()", name);
+    let mut depth = 1i32;
+    let mut first_return: Option<usize> = None;
+    let mut branch: Option<usize> = None;
+    // Header line of each open block, to report the branch holding a nested `Done`.
+    let mut headers: Vec<usize> = Vec::new();
+    let mut deferred_until: Option<usize> = None;
+
+    for (index, (line, code)) in body.iter().enumerate() {
+        let trimmed = code.trim();
+        if trimmed.starts_with("defer ") {
+            if trimmed.contains(&done) {
+                return DoneStatus::Always;
+            }
+            // `defer func() { wg.Done() }()` over several lines.
+            deferred_until = Some(statement_end(body, index));
+        }
+        if trimmed.contains(&done) {
+            if deferred_until.is_some_and(|end| index <= end) {
+                return DoneStatus::Always;
+            }
+            if depth <= 1 {
+                return match first_return {
+                    Some(line) => DoneStatus::SkippedByReturn(line),
+                    None => DoneStatus::Always,
+                };
+            }
+            branch.get_or_insert(headers.last().copied().unwrap_or(*line));
+        }
+        if (trimmed == "return" || trimmed.starts_with("return ")) && first_return.is_none() {
+            first_return = Some(*line);
+        }
+        for ch in code.chars() {
+            match ch {
+                '{' => {
+                    depth += 1;
+                    headers.push(*line);
+                }
+                '}' => {
+                    depth -= 1;
+                    headers.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    branch.map(DoneStatus::Conditional).unwrap_or(DoneStatus::Missing)
+}
+
+/// Names of the `sync.WaitGroup` parameters in a parameter list.
+fn waitgroup_parameters(parameters: &str) -> Vec<String> {
+    split_top_level(&parameters.replace("<-", "  "))
+        .iter()
+        .filter(|parameter| parameter.contains("sync.WaitGroup"))
+        .filter_map(|parameter| parameter.split_whitespace().next().map(str::to_string))
+        .collect()
+}
+
+/// Group declared on this line: `var wg sync.WaitGroup`, `wg := &sync.WaitGroup{}`,
+/// `wg := new(sync.WaitGroup)`.
+fn declared_waitgroup(code: &str) -> Option<String> {
+    let code = code.trim();
+    if !code.contains("sync.WaitGroup") {
+        return None;
+    }
+    let name = if let Some(rest) = code.strip_prefix("var ") {
+        rest.split_whitespace().next()?
+    } else {
+        code.split_once(":=")?.0.trim()
+    };
+    is_identifier(name).then(|| name.to_string())
+}
+
+/// Group declared on this line: `g, ctx := errgroup.WithContext(ctx)`,
+/// `var g errgroup.Group`, `g := new(errgroup.Group)`, `g := &errgroup.Group{}`.
+fn declared_errgroup(code: &str) -> Option<String> {
+    let code = code.trim();
+    if !code.contains("errgroup.") {
+        return None;
+    }
+    let name = if let Some(rest) = code.strip_prefix("var ") {
+        rest.split_whitespace().next()?
+    } else {
+        code.split_once(":=")?.0.split(',').next()?.trim()
+    };
+    is_identifier(name).then(|| name.to_string())
+}
+
+/// Argument of `name.method(arg)` on the line.
+fn call_argument<'a>(code: &'a str, name: &str, method: &str) -> Option<&'a str> {
+    let call = format!("{}.{}(", name, method);
+    let start = code.find(&call)?;
+    if code[..start].ends_with(|ch: char| ch.is_alphanumeric() || ch == '_' || ch == '.') {
+        return None;
+    }
+    let rest = &code[start + call.len()..];
+    Some(rest.split(')').next().unwrap_or(rest).trim())
+}
+
+fn mentions(code: &str, name: &str) -> bool {
+    identifiers(code).any(|identifier| identifier == name)
+}
+
+fn finding(
+    source_file: &str,
+    function_name: &str,
+    line: usize,
+    name: &str,
+    reason: String,
+    confidence: ConfidenceLevel,
+    code_snippet: Option<String>,
+) -> StaticEscape {
+    StaticEscape {
+        escape_type: classify_go_async_escape(code_snippet.as_deref(), &reason, name),
+        location: SourceLocation {
+            file: source_file.to_string(),
+            line,
+            column: 0,
+            function: function_name.to_string(),
+            code_snippet,
+        },
+        variable_name: name.to_string(),
+        reason,
+        confidence,
+        data_flow: vec![],
+        input_tainted: false,
+        taint_flow: vec![],
+        rule: Some(RULE.name.to_string()),
+        rule_id: Some(RULE.id.to_string()),
+        condition: None,
+        suggested_fix: None,
+    }
+}
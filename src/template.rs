@@ -0,0 +1,112 @@
+//! Report templates
+//! `--report-template <dir>` renders the user's own reports next to the built-in ones.
+//! Files ending in `.hbs` are Handlebars templates, rendered by the `handlebars` crate
+//! with the contents of report.json and written without the extension; other files
+//! (stylesheets, logos) are copied as they are. Templates are parsed when loaded, so a
+//! typo, or a file that would overwrite a built-in report, fails the command before
+//! any analysis runs.
+
+use crate::report::BUILT_IN_REPORTS;
+use anyhow::{bail, Context, Result};
+use handlebars::Handlebars;
+use serde_json::Value;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Extension marking a file of the template directory as a template.
+const TEMPLATE_EXTENSION: &str = "hbs";
+
+/// The files of a template directory, templates parsed.
+#[derive(Debug, Clone)]
+pub struct ReportTemplates {
+    dir: PathBuf,
+    registry: Handlebars<'static>,
+    /// Output path of each file, relative to the session directory
+    files: Vec<(PathBuf, TemplateFile)>,
+}
+
+#[derive(Debug, Clone)]
+enum TemplateFile {
+    /// Registered under the template's path in the directory
+    Template(String),
+    Asset,
+}
+
+impl ReportTemplates {
+    /// Reads and parses every file under `dir`.
+    pub fn load(dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            bail!("Report template directory not found: {}", dir.display());
+        }
+        let mut registry = Handlebars::new();
+        let mut files = Vec::new();
+        for entry in ignore::WalkBuilder::new(dir).standard_filters(false).hidden(true).build() {
+            let entry = entry?;
+            if !entry.file_type().is_some_and(|kind| kind.is_file()) {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(dir)?.to_path_buf();
+            let file = if entry.path().extension().is_some_and(|extension| extension == TEMPLATE_EXTENSION) {
+                let source = fs::read_to_string(entry.path())
+                    .with_context(|| format!("Failed to read report template {}", entry.path().display()))?;
+                let name = relative.to_string_lossy().replace('\\', "/");
+                registry
+                    .register_template_string(&name, source)
+                    .with_context(|| format!("Invalid report template {}", entry.path().display()))?;
+                (relative.with_extension(""), TemplateFile::Template(name))
+            } else {
+                (relative, TemplateFile::Asset)
+            };
+            if let Some(report) = built_in_report(&file.0) {
+                bail!(
+                    "Report template {} would overwrite the built-in {}; rename it",
+                    entry.path().display(),
+                    report
+                );
+            }
+            files.push(file);
+        }
+        if !files.iter().any(|(_, file)| matches!(file, TemplateFile::Template(_))) {
+            bail!("No .{} templates in {}", TEMPLATE_EXTENSION, dir.display());
+        }
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(Self { dir: dir.to_path_buf(), registry, files })
+    }
+
+    /// Renders the templates with `context` into `out_dir` and copies the other files,
+    /// returning the paths written.
+    pub fn render_into(&self, out_dir: &Path, context: &Value) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::with_capacity(self.files.len());
+        for (relative, file) in &self.files {
+            let path = out_dir.join(relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            match file {
+                TemplateFile::Template(name) => {
+                    let rendered = self
+                        .registry
+                        .render(name, context)
+                        .with_context(|| format!("Failed to render report template {}", name))?;
+                    fs::write(&path, rendered)?;
+                }
+                TemplateFile::Asset => {
+                    fs::copy(self.dir.join(relative), &path)?;
+                }
+            }
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+/// The built-in report an output path of the template directory would overwrite: the
+/// same file, or one under the same directory (`executions/`). Compared ignoring case,
+/// as file systems on Windows and macOS do.
+fn built_in_report(relative: &Path) -> Option<&'static str> {
+    let Some(Component::Normal(first)) = relative.components().next() else {
+        return None;
+    };
+    let first = first.to_string_lossy();
+    BUILT_IN_REPORTS.into_iter().find(|report| report.eq_ignore_ascii_case(&first))
+}
@@ -0,0 +1,135 @@
+//! Verification mode
+//! `run-all --verify` compares each target's outcome with the one its test case
+//! expects, turning the tests tree into a benchmark of the analyzers. The expectation
+//! comes from the function or file name (`escape_*` escapes; `no_escape_*`,
+//! `properly_*` and `safe_*` do not; camelCase names alike), else from the first
+//! `ESCAPE:` or `SAFE:` comment in the target function or the comments right above it.
+
+use std::collections::BTreeMap;
+
+/// Mismatches listed by name before the rest are only counted.
+const LISTED_MISMATCHES: usize = 20;
+
+/// Expected and actual outcome of one target
+pub struct Verdict {
+    pub language: String,
+    pub target: String,
+    /// Whether the test case expects an escape; `None` for unannotated targets.
+    pub expected: Option<bool>,
+    /// Whether the analysis found one; `None` when it produced no results.
+    pub detected: Option<bool>,
+}
+
+/// Expected outcome from the naming convention: the function name, then the file name.
+pub fn expected_from_name(target: &str) -> Option<bool> {
+    let function = crate::static_analyzer::callgraph::target_function(target);
+    let file = (!target.contains("::"))
+        .then(|| target.rsplit_once(':').map(|(file, _)| file))
+        .flatten()
+        .and_then(|file| file.rsplit(['/', '\\']).next())
+        .map(|name| name.split('.').next().unwrap_or(name));
+    [Some(function), file].into_iter().flatten().find_map(name_expectation)
+}
+
+fn name_expectation(name: &str) -> Option<bool> {
+    let name = name.replace('_', "").to_lowercase();
+    if ["noescape", "properly", "safe"].iter().any(|prefix| name.starts_with(prefix)) {
+        Some(false)
+    } else if name.starts_with("escape") {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Confusion counts of a set of verdicts
+#[derive(Default)]
+pub struct Tally {
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    pub true_negatives: usize,
+    /// Annotated targets the analysis produced no results for
+    pub no_results: usize,
+    pub unannotated: usize,
+}
+
+impl Tally {
+    pub fn add(&mut self, verdict: &Verdict) {
+        match (verdict.expected, verdict.detected) {
+            (None, _) => self.unannotated += 1,
+            (Some(_), None) => self.no_results += 1,
+            (Some(true), Some(true)) => self.true_positives += 1,
+            (Some(false), Some(true)) => self.false_positives += 1,
+            (Some(true), Some(false)) => self.false_negatives += 1,
+            (Some(false), Some(false)) => self.true_negatives += 1,
+        }
+    }
+
+    /// Share of reported escapes that were expected; `None` without reported escapes.
+    pub fn precision(&self) -> Option<f64> {
+        ratio(self.true_positives, self.true_positives + self.false_positives)
+    }
+
+    /// Share of expected escapes that were reported; `None` without expected escapes.
+    pub fn recall(&self) -> Option<f64> {
+        ratio(self.true_positives, self.true_positives + self.false_negatives)
+    }
+}
+
+fn ratio(part: usize, whole: usize) -> Option<f64> {
+    (whole > 0).then(|| part as f64 / whole as f64)
+}
+
+/// A ratio as a percentage, `n/a` when undefined.
+pub fn percent(value: Option<f64>) -> String {
+    value.map_or_else(|| "n/a".to_string(), |value| format!("{:.1}%", value * 100.0))
+}
+
+/// Prints the confusion counts, precision and recall per language and overall, and
+/// the targets whose outcome differed from the expected one.
+pub fn print_verification(verdicts: &[Verdict]) {
+    let mut by_language: BTreeMap<&str, Tally> = BTreeMap::new();
+    let mut total = Tally::default();
+    for verdict in verdicts {
+        by_language.entry(&verdict.language).or_default().add(verdict);
+        total.add(verdict);
+    }
+
+    println!("\n╔════════════════════════════════════════════╗");
+    println!("║               Verification                 ║");
+    println!("╚════════════════════════════════════════════╝\n");
+    println!(
+        "{:<12} {:>5} {:>5} {:>5} {:>5} {:>10} {:>10} {:>10} {:>11}",
+        "language", "TP", "FP", "FN", "TN", "precision", "recall", "no results", "unannotated"
+    );
+    for (language, tally) in by_language.iter().map(|(language, tally)| (*language, tally)).chain([("total", &total)]) {
+        println!(
+            "{:<12} {:>5} {:>5} {:>5} {:>5} {:>10} {:>10} {:>10} {:>11}",
+            language,
+            tally.true_positives,
+            tally.false_positives,
+            tally.false_negatives,
+            tally.true_negatives,
+            percent(tally.precision()),
+            percent(tally.recall()),
+            tally.no_results,
+            tally.unannotated
+        );
+    }
+
+    let mismatches: Vec<&Verdict> = verdicts
+        .iter()
+        .filter(|verdict| matches!((verdict.expected, verdict.detected), (Some(expected), Some(detected)) if expected != detected))
+        .collect();
+    if !mismatches.is_empty() {
+        println!("\nMismatches:");
+        for verdict in mismatches.iter().take(LISTED_MISMATCHES) {
+            let kind = if verdict.expected == Some(true) { "missed escape" } else { "false escape" };
+            println!("  {} {}: {}", verdict.language, verdict.target, kind);
+        }
+        if mismatches.len() > LISTED_MISMATCHES {
+            println!("  … and {} more", mismatches.len() - LISTED_MISMATCHES);
+        }
+    }
+}
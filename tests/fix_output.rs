@@ -0,0 +1,47 @@
+//! `graphene-ha fix` run on copies of the Rust fixtures: the fixed code must still
+//! build and must not leave a joined thread waiting on a channel nobody closes.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Copies fixture `name` into a fresh directory and fixes `function` in it.
+fn fixed(name: &str, function: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("graphene-fix-{}-{}", function, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join(name);
+    fs::copy(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/rust").join(name), &file).unwrap();
+    let status = Command::new(env!("CARGO_BIN_EXE_graphene-ha"))
+        .arg("fix")
+        .arg("--target")
+        .arg(format!("{}:{}", file.display(), function))
+        .current_dir(&dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "fix failed for {}:{}", name, function);
+    file
+}
+
+#[test]
+fn joined_handles_vector_still_builds_when_read_afterwards() {
+    let file = fixed("advanced_escapes.rs", "handles_pushed_not_joined");
+    let source = fs::read_to_string(&file).unwrap();
+    assert!(source.contains("for handle in workers.drain(..) {"), "{}", source);
+
+    let output = Command::new(std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string()))
+        .args(["--edition", "2021", "--crate-type", "lib", "--emit", "metadata", "--out-dir"])
+        .arg(file.parent().unwrap())
+        .arg(&file)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "fixed code does not build:\n{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn joined_consumer_has_its_sender_dropped_first() {
+    let file = fixed("crossbeam_escapes.rs", "detached_consumer");
+    let source = fs::read_to_string(&file).unwrap();
+    let drop = source.find("drop(sender);").expect("sender is not dropped");
+    let join = source.find("handle.join()").expect("consumer is not joined");
+    assert!(drop < join, "{}", source);
+}
@@ -0,0 +1,25 @@
+package escape_tests
+
+var retainedCase301 = []map[string]string{}
+
+func Case301CommentedSpawnSafe(input string) string {
+	raw := input
+	if raw == "" {
+		raw = "sample"
+	}
+	payload := map[string]string{
+		"task": "commented_spawn_safe",
+		"entity": "preprocess",
+		"stage": "comments",
+		"input": raw,
+	}
+	/*
+		go func() {
+			retainedCase301 = append(retainedCase301, payload)
+		}()
+	*/
+	// go deliver(payload)
+	note := "go func() { retainedCase301 = append(retainedCase301, payload) }()"
+	// SAFE: the goroutines only appear in comments and a string literal.
+	return note[:2] + payload["input"]
+}
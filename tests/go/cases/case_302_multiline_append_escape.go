@@ -0,0 +1,22 @@
+package escape_tests
+
+var retainedCase302 = []map[string]string{}
+
+func Case302MultilineAppendEscape(input string) string {
+	raw := input
+	if raw == "" {
+		raw = "sample"
+	}
+	payload := map[string]string{
+		"task": "multiline_append_escape",
+		"entity": "preprocess",
+		"stage": "continuation",
+		"input": raw,
+	}
+	// ESCAPE: the append into the retained slice is split over several lines.
+	retainedCase302 = append(
+		retainedCase302,
+		payload,
+	)
+	return "ok"
+}
@@ -0,0 +1,17 @@
+// Golden fixtures for `graphene-ha self-test`. Expected findings live in tests/golden/expected.json.
+package golden
+
+var registry = []map[string]string{}
+
+func GoldenGlobalEscape(input string) string {
+	record := map[string]string{"input": input}
+	// ESCAPE: record is appended to package-level registry.
+	registry = append(registry, record)
+	return "ok"
+}
+
+func GoldenLocalOnly(input string) string {
+	// SAFE: record never leaves the function.
+	record := map[string]string{"input": input}
+	return record["input"]
+}
@@ -0,0 +1,26 @@
+package golden;
+
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+
+/** Golden fixtures for graphene-ha self-test. Expected findings live in tests/golden/expected.json. */
+public class GoldenFixtures {
+    private static final List<Map<String, String>> REGISTRY = new ArrayList<>();
+
+    public static String goldenGlobalEscape(String input) {
+        Map<String, String> record = new HashMap<>();
+        record.put("input", input);
+        // ESCAPE: record is added to the static REGISTRY.
+        REGISTRY.add(record);
+        return "ok";
+    }
+
+    public static String goldenLocalOnly(String input) {
+        // SAFE: record never leaves the method.
+        Map<String, String> record = new HashMap<>();
+        record.put("input", input);
+        return String.valueOf(record.size());
+    }
+}
@@ -0,0 +1,17 @@
+// Golden fixtures for `graphene-ha self-test`. Expected findings live in tests/golden/expected.json.
+const REGISTRY = [];
+
+function goldenGlobalEscape(input) {
+    const record = { input };
+    // ESCAPE: record is pushed into module-level REGISTRY.
+    REGISTRY.push(record);
+    return 'ok';
+}
+
+function goldenLocalOnly(input) {
+    // SAFE: record never leaves the function.
+    const record = { input };
+    return String(Object.keys(record).length);
+}
+
+module.exports = { goldenGlobalEscape, goldenLocalOnly };
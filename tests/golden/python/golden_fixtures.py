@@ -0,0 +1,16 @@
+"""Golden fixtures for `graphene-ha self-test`. Expected findings live in tests/golden/expected.json."""
+
+REGISTRY = {}
+
+
+def golden_global_escape(input_data):
+    record = {'input': input_data}
+    # ESCAPE: record is stored in module-level REGISTRY.
+    REGISTRY['golden'] = record
+    return 'ok'
+
+
+def golden_local_only(input_data):
+    # SAFE: record never leaves the function.
+    record = {'input': input_data}
+    return str(len(record))
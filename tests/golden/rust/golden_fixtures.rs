@@ -0,0 +1,18 @@
+//! Golden fixtures for `graphene-ha self-test`. Expected findings live in tests/golden/expected.json.
+
+use std::sync::Mutex;
+
+static RETAINED_GOLDEN: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn golden_global_escape(input: String) -> String {
+    let record = format!("input={}", input);
+    // ESCAPE: record is pushed into the static RETAINED_GOLDEN.
+    RETAINED_GOLDEN.lock().unwrap().push(record);
+    "ok".to_string()
+}
+
+pub fn golden_local_only(input: String) -> String {
+    // SAFE: record never leaves the function.
+    let record = format!("input={}", input);
+    record.len().to_string()
+}
@@ -0,0 +1,28 @@
+package com.escape.tests.cases;
+
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+
+/** Preprocessing case 304: spawns and stores only in comments and string literals. */
+public class Case304CommentedSpawnSafe {
+    private static final List<Map<String, String>> RETAINED_AUDIT = new ArrayList<>();
+
+    public static String execute(String input) {
+        String raw = (input == null || input.isEmpty()) ? "sample" : input;
+        Map<String, String> payload = new HashMap<>();
+        payload.put("task", "commented_spawn_safe");
+        payload.put("entity", "preprocess");
+        payload.put("stage", "comments");
+        payload.put("input", raw);
+
+        /*
+         * new Thread(() -> RETAINED_AUDIT.add(payload)).start();
+         */
+        // RETAINED_AUDIT.add(payload);
+        String note = "RETAINED_AUDIT.add(payload); new Thread(worker).start();";
+        // SAFE: the thread and the store only appear in comments and a string literal.
+        return note.length() + ":" + payload.get("input");
+    }
+}
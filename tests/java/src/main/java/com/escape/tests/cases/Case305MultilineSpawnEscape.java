@@ -0,0 +1,27 @@
+package com.escape.tests.cases;
+
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+
+/** Preprocessing case 305: a store split over several lines. */
+public class Case305MultilineSpawnEscape {
+    private static final List<Map<String, String>> RETAINED_AUDIT = new ArrayList<>();
+
+    public static String execute(String input) {
+        String raw = (input == null || input.isEmpty()) ? "sample" : input;
+        Map<String, String> payload = new HashMap<>();
+        payload.put("task", "multiline_spawn_escape");
+        payload.put("entity", "preprocess");
+        payload.put("stage", "continuation");
+        payload.put("input", raw);
+
+        // ESCAPE: the thread storing payload is created over several lines.
+        new Thread(() -> RETAINED_AUDIT
+                .add(
+                        payload))
+                .start();
+        return "ok";
+    }
+}
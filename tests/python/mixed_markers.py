@@ -0,0 +1,21 @@
+"""
+Safe and escaping cases in one module: each function's own ESCAPE:/SAFE: comment
+labels it, whichever comes first in the file.
+"""
+
+RETAINED_CACHE = {}
+
+
+def summarize_locally(input_data):
+    raw = input_data or 'sample'
+    payload = {'input': raw, 'length': len(raw)}
+    # SAFE: payload remains local; only primitive summary string is returned.
+    return f"{payload['input']}:{payload['length']}"
+
+
+def cache_for_reuse(input_data):
+    raw = input_data or 'sample'
+    payload = {'input': raw, 'length': len(raw)}
+    # ESCAPE: payload is promoted to module-level RETAINED_CACHE for cross-request reuse.
+    RETAINED_CACHE[raw] = payload
+    return 'ok'
@@ -0,0 +1,88 @@
+//! Spawn patterns that need the AST to follow: spawns that span several lines, go
+//! through builders or factory closures, and handles that move before being joined.
+
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+static RETAINED_WORKERS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn retain(payload: String) {
+    thread::sleep(Duration::from_millis(50));
+    RETAINED_WORKERS.lock().expect("workers lock").push(payload);
+}
+
+fn start_worker(payload: String) -> JoinHandle<()> {
+    thread::spawn(move || retain(payload))
+}
+
+pub fn spawn_via_factory(input: String) -> String {
+    let factory = |payload: String| {
+        thread::spawn(move || {
+            retain(payload);
+        })
+    };
+    // ESCAPE: the handle returned by the factory closure is never joined.
+    let _worker = factory(input);
+    "ok".to_string()
+}
+
+pub fn spawn_via_helper(input: String) -> String {
+    // ESCAPE: the helper's handle is discarded.
+    start_worker(input);
+    "ok".to_string()
+}
+
+pub fn builder_spawn_multiline(input: String) -> String {
+    // ESCAPE: the builder's handle is dropped without a join.
+    let worker = thread::Builder::new()
+        .name("graphene-worker".to_string())
+        .spawn(move || {
+            retain(input);
+        })
+        .expect("spawn worker");
+    drop(worker);
+    "ok".to_string()
+}
+
+pub fn handles_pushed_not_joined(input: String) -> String {
+    let mut workers = Vec::new();
+    for index in 0..3 {
+        let payload = format!("{}-{}", input, index);
+        // ESCAPE: the collected handles are never joined.
+        workers.push(thread::spawn(move || retain(payload)));
+    }
+    format!("{} workers", workers.len())
+}
+
+pub fn handle_moved_then_joined(input: String) -> String {
+    // SAFE: the handle is joined under the name it was moved to.
+    let handle = thread::spawn(move || input.len());
+    let worker = handle;
+    worker.join().expect("worker panicked").to_string()
+}
+
+pub fn collected_and_joined(input: String) -> String {
+    // SAFE: every handle is joined before returning.
+    let workers: Vec<JoinHandle<usize>> = (0..3)
+        .map(|index| {
+            let payload = format!("{}-{}", input, index);
+            thread::spawn(move || payload.len())
+        })
+        .collect();
+    let total: usize = workers.into_iter().map(|worker| worker.join().expect("worker panicked")).sum();
+    total.to_string()
+}
+
+pub fn scoped_workers(input: String) -> String {
+    // SAFE: scoped threads are joined when the scope ends.
+    let lengths = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for part in input.split(',') {
+            let lengths = &lengths;
+            scope.spawn(move || lengths.lock().expect("lengths lock").push(part.len()));
+        }
+    });
+    let total: usize = lengths.into_inner().expect("lengths lock").iter().sum();
+    total.to_string()
+}
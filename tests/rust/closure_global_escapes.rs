@@ -0,0 +1,76 @@
+//! Closure, global and parameter escapes: locals captured by `move` closures that
+//! outlive the call, values written into statics directly, through a guard or a
+//! thread-local, and locals handed to a thread that is never joined.
+
+use std::cell::RefCell;
+use std::sync::{Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+
+type Callback = Box<dyn Fn() -> usize + Send>;
+
+static CALLBACKS: Mutex<Vec<Callback>> = Mutex::new(Vec::new());
+static CONFIG: OnceLock<String> = OnceLock::new();
+static mut LAST_INPUT: Option<String> = None;
+
+thread_local! {
+    static SEEN: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn detached_capture(input: String) -> String {
+    let payload = format!("{}-detached", input);
+    // ESCAPE: `payload` moves into a thread that is never joined.
+    thread::spawn(move || payload.len());
+    "ok".to_string()
+}
+
+pub fn callback_registered(input: String) -> String {
+    let prefix = input.clone();
+    // ESCAPE: the callback capturing `prefix` is kept in a static registry.
+    CALLBACKS.lock().expect("callbacks lock").push(Box::new(move || prefix.len()));
+    input
+}
+
+pub fn config_initialized(input: String) -> String {
+    // ESCAPE: the input becomes the process-wide configuration.
+    CONFIG.get_or_init(|| input.clone()).to_string()
+}
+
+pub fn static_mut_written(input: String) -> String {
+    let copy = input.clone();
+    // ESCAPE: the copy outlives the call in a `static mut`.
+    unsafe {
+        LAST_INPUT = Some(copy);
+    }
+    input
+}
+
+pub fn guard_written(input: String) -> String {
+    let mut callbacks = CALLBACKS.lock().expect("callbacks lock");
+    let label = input.clone();
+    // ESCAPE: pushed through a guard of the static registry.
+    callbacks.push(Box::new(move || label.len()));
+    input
+}
+
+pub fn thread_local_recorded(input: String) -> String {
+    // ESCAPE: the thread-local keeps the input after the call returns.
+    SEEN.with(|seen| seen.borrow_mut().push(input.clone()));
+    input
+}
+
+fn start_counter(payload: String) -> JoinHandle<usize> {
+    thread::spawn(move || payload.len())
+}
+
+pub fn handed_to_helper(input: String) -> String {
+    let payload = format!("{}-counted", input);
+    // ESCAPE: `payload` goes to a thread that is never joined.
+    let _counter = start_counter(payload);
+    input
+}
+
+pub fn joined_capture(input: String) -> String {
+    // SAFE: the thread owning the capture is joined before returning.
+    let worker = thread::spawn(move || input.len());
+    worker.join().expect("worker panicked").to_string()
+}
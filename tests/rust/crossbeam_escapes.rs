@@ -0,0 +1,51 @@
+//! Producers and consumers on `crossbeam::channel`: threads holding a sender or
+//! receiver that are never joined outlive the call, while those spawned in
+//! `crossbeam::scope` are joined when it ends, like std `thread::scope`.
+
+use crossbeam::channel::{bounded, unbounded};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+static CONSUMED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn detached_consumer(input: String) -> String {
+    let (sender, receiver) = unbounded::<String>();
+    // ESCAPE: the consumer drains the channel after the call returns.
+    thread::spawn(move || {
+        for item in receiver {
+            thread::sleep(Duration::from_millis(50));
+            CONSUMED.lock().expect("consumed lock").push(item);
+        }
+    });
+    sender.send(input).expect("send input");
+    "ok".to_string()
+}
+
+pub fn detached_producer(input: String) -> String {
+    let (sender, receiver) = bounded(1);
+    // ESCAPE: the producer is still sending when the call returns.
+    thread::spawn(move || {
+        for word in input.split_whitespace() {
+            thread::sleep(Duration::from_millis(50));
+            if sender.send(word.to_string()).is_err() {
+                break;
+            }
+        }
+    });
+    receiver.recv().unwrap_or_default()
+}
+
+pub fn scoped_pipeline(input: String) -> String {
+    let (sender, receiver) = unbounded();
+    // SAFE: the producer is joined when the scope ends, closing the channel.
+    crossbeam::scope(|scope| {
+        scope.spawn(move |_| {
+            for word in input.split_whitespace() {
+                sender.send(word.len()).expect("send length");
+            }
+        });
+    })
+    .expect("a scoped thread panicked");
+    receiver.iter().sum::<usize>().to_string()
+}
@@ -0,0 +1,53 @@
+//! Jobs on rayon and `threadpool` pools: `rayon::spawn` keeps nothing to wait for,
+//! while jobs spawned in `rayon::scope` finish before it returns; `execute` jobs are
+//! waited for only when the pool is joined. Rayon's global pool, once built, keeps its
+//! workers for the rest of the process.
+
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+static POOL_RESULTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn rayon_fire_and_forget(input: String) -> String {
+    // ESCAPE: the job owning `input` runs on rayon's pool with nothing to wait for it.
+    rayon::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        POOL_RESULTS.lock().expect("results lock").push(input);
+    });
+    "ok".to_string()
+}
+
+pub fn rayon_scoped(input: String) -> String {
+    let mut length = 0;
+    // SAFE: jobs spawned in the scope have finished when it returns.
+    rayon::scope(|scope| {
+        scope.spawn(|_| length = input.len());
+    });
+    length.to_string()
+}
+
+pub fn threadpool_unjoined(input: String) -> String {
+    let pool = threadpool::ThreadPool::new(2);
+    // ESCAPE: the pool is never joined, so the job outlives the call.
+    pool.execute(move || {
+        thread::sleep(Duration::from_millis(50));
+        POOL_RESULTS.lock().expect("results lock").push(input);
+    });
+    "ok".to_string()
+}
+
+pub fn threadpool_joined(input: String) -> String {
+    let pool = threadpool::ThreadPool::new(2);
+    let (sender, receiver) = mpsc::channel();
+    pool.execute(move || sender.send(input.len()).expect("send length"));
+    // SAFE: joining the pool waits for its jobs.
+    pool.join();
+    receiver.recv().expect("job result").to_string()
+}
+
+pub fn global_pool(input: String) -> String {
+    // ESCAPE: the global pool's workers live as long as the process.
+    let built = rayon::ThreadPoolBuilder::new().num_threads(2).build_global().is_ok();
+    format!("{}:{}", input.len(), built)
+}
@@ -0,0 +1,29 @@
+//! Tasks on runtimes other than tokio: futures handed to a `futures` thread pool with
+//! `spawn_ok`, which keeps no handle, and with `spawn_with_handle`, whose handle is
+//! awaited. async-std and smol spawns go through the same rules, with smol tasks
+//! escaping only once `.detach()`ed.
+
+use futures::executor::{block_on, ThreadPool};
+use futures::task::SpawnExt;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+static POOL_RESULTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn pool_fire_and_forget(input: String) -> String {
+    let pool = ThreadPool::new().expect("thread pool");
+    // ESCAPE: the task owning `input` runs on the pool with no handle to wait for.
+    pool.spawn_ok(async move {
+        thread::sleep(Duration::from_millis(50));
+        POOL_RESULTS.lock().expect("results lock").push(input);
+    });
+    "ok".to_string()
+}
+
+pub fn pool_awaited(input: String) -> String {
+    let pool = ThreadPool::new().expect("thread pool");
+    // SAFE: the task's handle is awaited before returning.
+    let handle = pool.spawn_with_handle(async move { input.len() }).expect("spawn task");
+    block_on(handle).to_string()
+}